@@ -0,0 +1,218 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compares the sequential ingestion path (one file at a time, a new set of appenders
+//! for every chunk) with the parallel one (files parsed concurrently, a single
+//! shared appender), both at the library level and end to end with `logs parse`.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+use std::thread;
+use tempfile::TempDir;
+
+use rabbitmq_lqt_lib::entry_metadata::annotate_entry;
+use rabbitmq_lqt_lib::parser::{LogEntryStream, ParsedLogEntry};
+use rabbitmq_lqt_lib::rel_db::{
+    DatabaseConnection, NodeLogEntry, create_database_for_bulk_import, finalize_bulk_import,
+    post_insertion_operations,
+};
+
+const PIPELINE_CHUNK_SIZE: usize = 25_000;
+const ENTRIES_PER_FILE: usize = 100_000;
+
+fn write_node_logs(dir: &Path, nodes: usize) -> Vec<PathBuf> {
+    (0..nodes)
+        .map(|n| {
+            let path = dir.join(format!("rabbit@node{}.log", n));
+            let mut writer = BufWriter::new(File::create(&path).unwrap());
+            for i in 0..ENTRIES_PER_FILE {
+                let secs = i / 100;
+                writeln!(
+                    writer,
+                    "2025-10-27 {:02}:{:02}:{:02}.{:06}+00:00 [info] <0.{}.0> accepting AMQP connection 10.0.{}.2:{} -> 10.0.0.1:5672",
+                    18 + secs / 3600,
+                    (secs / 60) % 60,
+                    secs % 60,
+                    (i % 100) * 10_000,
+                    i % 500,
+                    n,
+                    i % 65536
+                )
+                .unwrap();
+            }
+            writer.flush().unwrap();
+            path
+        })
+        .collect()
+}
+
+fn node_name(path: &Path) -> String {
+    path.file_stem().unwrap().to_string_lossy().to_string()
+}
+
+fn parse_and_annotate_chunks(path: &Path, mut f: impl FnMut(Vec<ParsedLogEntry>)) {
+    let reader = BufReader::new(File::open(path).unwrap());
+    let mut chunk = Vec::with_capacity(PIPELINE_CHUNK_SIZE);
+    for entry in LogEntryStream::new(reader) {
+        chunk.push(entry.unwrap());
+        if chunk.len() == PIPELINE_CHUNK_SIZE {
+            chunk.par_iter_mut().for_each(annotate_entry);
+            f(std::mem::take(&mut chunk));
+        }
+    }
+    if !chunk.is_empty() {
+        chunk.par_iter_mut().for_each(annotate_entry);
+        f(chunk);
+    }
+}
+
+/// Files are parsed one after another, chunks are inserted by a single thread
+/// with [`NodeLogEntry::insert_parsed_entries`]
+fn ingest_sequentially(db: &DatabaseConnection, log_paths: &[PathBuf]) {
+    let (tx, rx) = mpsc::sync_channel::<(String, Vec<ParsedLogEntry>)>(4);
+    let db_clone = db.clone();
+    let insert_thread = thread::spawn(move || {
+        let mut next_id = 1;
+        while let Ok((node, mut entries)) = rx.recv() {
+            for entry in entries.iter_mut() {
+                entry.explicit_id = Some(next_id);
+                next_id += 1;
+            }
+            NodeLogEntry::insert_parsed_entries(&db_clone, &entries, &node).unwrap();
+        }
+    });
+
+    for path in log_paths {
+        let node = node_name(path);
+        parse_and_annotate_chunks(path, |chunk| tx.send((node.clone(), chunk)).unwrap());
+    }
+
+    drop(tx);
+    insert_thread.join().unwrap();
+}
+
+/// Files are parsed concurrently, chunks are inserted in file order
+/// through a single [`EntryAppender`]
+///
+/// [`EntryAppender`]: rabbitmq_lqt_lib::rel_db::EntryAppender
+fn ingest_in_parallel(db: &DatabaseConnection, log_paths: &[PathBuf]) {
+    thread::scope(|scope| {
+        let receivers: Vec<_> = log_paths
+            .iter()
+            .map(|path| {
+                let (tx, rx) = mpsc::sync_channel::<Vec<ParsedLogEntry>>(4);
+                scope.spawn(move || {
+                    parse_and_annotate_chunks(path, |chunk| tx.send(chunk).unwrap())
+                });
+                (node_name(path), rx)
+            })
+            .collect();
+
+        NodeLogEntry::with_appender(db, |appender| {
+            let mut next_id = 1;
+            for (node, rx) in receivers {
+                while let Ok(entries) = rx.recv() {
+                    for entry in &entries {
+                        appender.append(entry, next_id, &node)?;
+                        next_id += 1;
+                    }
+                    appender.flush()?;
+                }
+            }
+            Ok::<_, duckdb::Error>(())
+        })
+        .unwrap();
+    });
+}
+
+fn bench_ingestion_paths(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ingestion");
+    group.sample_size(10);
+
+    let log_dir = TempDir::new().unwrap();
+    for &nodes in &[1, 3, 5] {
+        let log_paths = write_node_logs(log_dir.path(), nodes);
+
+        group.bench_with_input(
+            BenchmarkId::new("sequential", format!("{}x100K", nodes)),
+            &log_paths,
+            |b, paths| {
+                b.iter(|| {
+                    let db_dir = TempDir::new().unwrap();
+                    let db =
+                        create_database_for_bulk_import(&db_dir.path().join("bench.db")).unwrap();
+                    ingest_sequentially(&db, paths);
+                    post_insertion_operations(&db).unwrap();
+                    finalize_bulk_import(&db).unwrap();
+                });
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("parallel", format!("{}x100K", nodes)),
+            &log_paths,
+            |b, paths| {
+                b.iter(|| {
+                    let db_dir = TempDir::new().unwrap();
+                    let db =
+                        create_database_for_bulk_import(&db_dir.path().join("bench.db")).unwrap();
+                    ingest_in_parallel(&db, paths);
+                    post_insertion_operations(&db).unwrap();
+                    finalize_bulk_import(&db).unwrap();
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_logs_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("logs_parse");
+    group.sample_size(10);
+
+    let log_dir = TempDir::new().unwrap();
+    let log_paths = write_node_logs(log_dir.path(), 5);
+    let default_parallelism = thread::available_parallelism().unwrap().to_string();
+
+    for parallelism in ["1", default_parallelism.as_str()] {
+        group.bench_with_input(
+            BenchmarkId::new("parallelism", parallelism),
+            &log_paths,
+            |b, paths| {
+                b.iter(|| {
+                    let db_dir = TempDir::new().unwrap();
+                    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rabbitmq-lqt"));
+                    cmd.args(["logs", "parse", "--silent", "--parallelism", parallelism])
+                        .arg("--output-db-file-path")
+                        .arg(db_dir.path().join("bench.db"));
+                    for path in paths {
+                        cmd.arg("--input-log-file-path").arg(path);
+                    }
+                    assert!(cmd.status().unwrap().success());
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_ingestion_paths, bench_logs_parse);
+criterion_main!(benches);
@@ -12,14 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
 use std::cmp::min;
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Error as IoError, ErrorKind, Read, Result as IoResult};
+use std::io::{
+    BufRead, BufReader, Error as IoError, ErrorKind, Read, Result as IoResult, Seek, SeekFrom,
+};
 use std::path::{Path, PathBuf};
 
 use flate2::read::GzDecoder;
 use tar::Archive;
 use tempfile::TempDir;
+use zip::ZipArchive;
 
 use rabbitmq_lqt_lib::Error as LibError;
 
@@ -32,8 +36,10 @@ pub enum ArchiveType {
     PlainLog,
     GzipLog,
     XzLog,
+    Tar,
     TarGz,
     TarXz,
+    Zip,
 }
 
 impl ArchiveType {
@@ -44,7 +50,11 @@ impl ArchiveType {
             .unwrap_or("")
             .to_lowercase();
 
-        if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+        if file_name.ends_with(".zip") {
+            ArchiveType::Zip
+        } else if file_name.ends_with(".tar") {
+            ArchiveType::Tar
+        } else if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
             ArchiveType::TarGz
         } else if file_name.ends_with(".tar.xz") || file_name.ends_with(".txz") {
             ArchiveType::TarXz
@@ -58,7 +68,15 @@ impl ArchiveType {
     }
 
     pub fn is_tar_archive(&self) -> bool {
-        matches!(self, ArchiveType::TarGz | ArchiveType::TarXz)
+        matches!(
+            self,
+            ArchiveType::Tar | ArchiveType::TarGz | ArchiveType::TarXz
+        )
+    }
+
+    /// Whether this is an archive of multiple files rather than a single, possibly compressed, log
+    pub fn is_multi_file_archive(&self) -> bool {
+        self.is_tar_archive() || *self == ArchiveType::Zip
     }
 }
 
@@ -151,11 +169,11 @@ pub fn open_log_reader(path: &Path) -> Result<LogReader> {
             })?;
             Ok(LogReader::Xz(BufReader::new(xz_reader)))
         }
-        ArchiveType::TarGz | ArchiveType::TarXz => {
+        ArchiveType::Tar | ArchiveType::TarGz | ArchiveType::TarXz | ArchiveType::Zip => {
             Err(CommandRunError::Library(LibError::Io(IoError::new(
                 ErrorKind::InvalidInput,
                 format!(
-                    "Cannot open tar archive as a single file: {}. Use extract_tar_archive instead.",
+                    "Cannot open archive as a single file: {}. Use extract_tar_archive or extract_bundle instead.",
                     path.display()
                 ),
             ))))
@@ -163,6 +181,31 @@ pub fn open_log_reader(path: &Path) -> Result<LogReader> {
     }
 }
 
+/// Like [`open_log_reader`] but starts reading a plain log at `offset`,
+/// e.g. where an earlier ingestion of a growing file stopped.
+/// Compressed logs cannot be resumed and are always read from the start.
+pub fn open_log_reader_at(path: &Path, offset: u64) -> Result<LogReader> {
+    let mut reader = open_log_reader(path)?;
+    if offset > 0 {
+        let LogReader::Plain(ref mut plain) = reader else {
+            return Err(CommandRunError::Library(LibError::Io(IoError::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Cannot resume reading a compressed file: {}",
+                    path.display()
+                ),
+            ))));
+        };
+        plain.seek(SeekFrom::Start(offset)).map_err(|e| {
+            CommandRunError::Library(LibError::Io(IoError::new(
+                e.kind(),
+                format!("Failed to seek in '{}': {}", path.display(), e),
+            )))
+        })?;
+    }
+    Ok(reader)
+}
+
 pub struct ExtractedArchive {
     // Field exists to keep the temp directory alive until dropped
     pub _temp_dir: TempDir,
@@ -170,38 +213,43 @@ pub struct ExtractedArchive {
 }
 
 pub fn extract_tar_archive(path: &Path) -> Result<ExtractedArchive> {
-    let archive_type = ArchiveType::from_path(path);
-    let file = File::open(path).map_err(|e| {
+    if !ArchiveType::from_path(path).is_tar_archive() {
+        return Err(CommandRunError::Library(LibError::Io(IoError::new(
+            ErrorKind::InvalidInput,
+            format!("Not a tar archive: {}", path.display()),
+        ))));
+    }
+
+    let temp_dir = TempDir::new().map_err(|e| {
         CommandRunError::Library(LibError::Io(IoError::new(
             e.kind(),
-            format!("Failed to open archive '{}': {}", path.display(), e),
+            format!("Failed to create temp directory: {}", e),
         )))
     })?;
+    unpack_archive(path, temp_dir.path())?;
 
-    let temp_dir = TempDir::new().map_err(|e| {
+    let log_files = find_log_files_in_dir(temp_dir.path())?;
+
+    Ok(ExtractedArchive {
+        _temp_dir: temp_dir,
+        log_files,
+    })
+}
+
+/// Unpacks a tar or zip archive into `destination`. Entries with paths that
+/// would end up outside of `destination` are skipped.
+pub fn unpack_archive(path: &Path, destination: &Path) -> Result<()> {
+    let archive_type = ArchiveType::from_path(path);
+    let file = File::open(path).map_err(|e| {
         CommandRunError::Library(LibError::Io(IoError::new(
             e.kind(),
-            format!("Failed to create temp directory: {}", e),
+            format!("Failed to open archive '{}': {}", path.display(), e),
         )))
     })?;
 
     match archive_type {
-        ArchiveType::TarGz => {
-            let decoder = GzDecoder::new(file);
-            let mut archive = Archive::new(decoder);
-            archive.set_preserve_permissions(false);
-            archive.set_preserve_mtime(false);
-            archive.unpack(temp_dir.path()).map_err(|e| {
-                CommandRunError::Library(LibError::Io(IoError::new(
-                    e.kind(),
-                    format!(
-                        "Failed to extract tar.gz archive '{}': {}",
-                        path.display(),
-                        e
-                    ),
-                )))
-            })?;
-        }
+        ArchiveType::Tar => unpack_tar(file, path, destination, "tar"),
+        ArchiveType::TarGz => unpack_tar(GzDecoder::new(file), path, destination, "tar.gz"),
         ArchiveType::TarXz => {
             let mut compressed = Vec::new();
             let mut file = file;
@@ -224,33 +272,45 @@ pub fn extract_tar_archive(path: &Path) -> Result<ExtractedArchive> {
                 )))
             })?;
 
-            let mut archive = Archive::new(decompressed.as_slice());
-            archive.set_preserve_permissions(false);
-            archive.set_preserve_mtime(false);
-            archive.unpack(temp_dir.path()).map_err(|e| {
+            unpack_tar(decompressed.as_slice(), path, destination, "tar.xz")
+        }
+        ArchiveType::Zip => {
+            let mut archive = ZipArchive::new(file).map_err(|e| {
                 CommandRunError::Library(LibError::Io(IoError::new(
-                    e.kind(),
-                    format!(
-                        "Failed to extract tar.xz archive '{}': {}",
-                        path.display(),
-                        e
-                    ),
+                    ErrorKind::InvalidData,
+                    format!("Failed to read zip archive '{}': {}", path.display(), e),
                 )))
             })?;
+            archive.extract(destination).map_err(|e| {
+                CommandRunError::Library(LibError::Io(IoError::new(
+                    ErrorKind::InvalidData,
+                    format!("Failed to extract zip archive '{}': {}", path.display(), e),
+                )))
+            })
         }
-        _ => {
-            return Err(CommandRunError::Library(LibError::Io(IoError::new(
+        ArchiveType::PlainLog | ArchiveType::GzipLog | ArchiveType::XzLog => {
+            Err(CommandRunError::Library(LibError::Io(IoError::new(
                 ErrorKind::InvalidInput,
-                format!("Not a tar archive: {}", path.display()),
-            ))));
+                format!("Not an archive: {}", path.display()),
+            ))))
         }
     }
+}
 
-    let log_files = find_log_files_in_dir(temp_dir.path())?;
-
-    Ok(ExtractedArchive {
-        _temp_dir: temp_dir,
-        log_files,
+fn unpack_tar<R: Read>(reader: R, path: &Path, destination: &Path, format: &str) -> Result<()> {
+    let mut archive = Archive::new(reader);
+    archive.set_preserve_permissions(false);
+    archive.set_preserve_mtime(false);
+    archive.unpack(destination).map_err(|e| {
+        CommandRunError::Library(LibError::Io(IoError::new(
+            e.kind(),
+            format!(
+                "Failed to extract {} archive '{}': {}",
+                format,
+                path.display(),
+                e
+            ),
+        )))
     })
 }
 
@@ -297,16 +357,38 @@ fn collect_log_files_recursive(
     Ok(())
 }
 
-fn is_log_file(path: &Path) -> bool {
+pub fn is_log_file(path: &Path) -> bool {
     let file_name = path
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("")
         .to_lowercase();
+    let file_name = strip_rotation_suffix(&file_name);
 
     file_name.ends_with(".log") || file_name.ends_with(".log.gz") || file_name.ends_with(".log.xz")
 }
 
+/// Strips the number logrotate and RabbitMQ append to rotated log files,
+/// e.g. `rabbit@host1.log.1` or `rabbit@host1.log.2.gz`, keeping the compression suffix.
+pub fn strip_rotation_suffix(file_name: &str) -> Cow<'_, str> {
+    let (name, compression) = match file_name.rsplit_once('.') {
+        Some((name, ext)) if ext.eq_ignore_ascii_case("gz") || ext.eq_ignore_ascii_case("xz") => {
+            (name, &file_name[name.len()..])
+        }
+        _ => (file_name, ""),
+    };
+    match name.rsplit_once('.') {
+        Some((log_name, number))
+            if !number.is_empty()
+                && number.bytes().all(|b| b.is_ascii_digit())
+                && log_name.to_lowercase().ends_with(".log") =>
+        {
+            Cow::Owned(format!("{}{}", log_name, compression))
+        }
+        _ => Cow::Borrowed(file_name),
+    }
+}
+
 pub fn strip_compression_suffix(file_name: &str) -> &str {
     let suffixes = [
         ".log.tar.gz",
@@ -338,6 +420,7 @@ pub fn is_supported_log_file(path: &Path) -> bool {
         .and_then(|n| n.to_str())
         .unwrap_or("")
         .to_lowercase();
+    let file_name = strip_rotation_suffix(&file_name);
 
     file_name.ends_with(".log")
         || file_name.ends_with(".log.gz")
@@ -0,0 +1,317 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ingestion of support bundles: zip or tar archives, or directories, collected
+//! from one or more nodes, possibly with archives nested in them.
+//!
+//! Bundles rarely name log files after their node the way `logs parse` expects,
+//! e.g. a bundle can have a `rabbit@host1/log/rabbit.log` file. So the node of a log
+//! file is inferred from, in order:
+//!
+//!  1. the name of the file, if it has a node name in it
+//!  2. the nearest directory that has a node name in its name
+//!  3. the node banner RabbitMQ logs on boot
+//!  4. the name of the file, just like `logs parse` does
+//!
+//! Files such as `rabbitmq.conf` and `enabled_plugins` are kept as node artifacts.
+//! They are attributed to the node in their path or, if there is none, to the only node
+//! whose logs are in the same directory tree.
+
+use crate::archive::{ArchiveType, is_log_file, open_log_reader, unpack_archive};
+use crate::core::Result;
+use crate::errors::CommandRunError;
+use crate::ingestion::{LogFile, extract_node_name};
+use rabbitmq_lqt_lib::Error as LibError;
+use rabbitmq_lqt_lib::rel_db::node_artifacts::{ArtifactKind, Model as Artifact};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::{BufRead, Error as IoError, ErrorKind};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// How deep archives can be nested in a bundle
+const MAX_ARCHIVE_NESTING: usize = 3;
+
+const MAX_DIRECTORY_DEPTH: usize = 10;
+
+/// How many lines of a log file are searched for the node banner
+const NODE_BANNER_SEARCH_LINES: usize = 500;
+
+/// Node artifacts that cannot be attributed to a node
+pub const UNKNOWN_NODE: &str = "unknown";
+
+pub struct ExtractedBundle {
+    // Field exists to keep the temp directory alive until dropped
+    pub _temp_dir: TempDir,
+    pub log_files: Vec<LogFile>,
+    pub artifacts: Vec<Artifact>,
+}
+
+/// Extracts a support bundle and attributes its log files and artifacts to nodes.
+/// `path` can be a zip or tar archive or a directory.
+pub fn extract_bundle(path: &Path) -> Result<ExtractedBundle> {
+    let temp_dir = TempDir::new().map_err(|e| {
+        CommandRunError::Library(LibError::Io(IoError::new(
+            e.kind(),
+            format!("Failed to create temp directory: {}", e),
+        )))
+    })?;
+
+    let mut walker = BundleWalker {
+        temp_dir: temp_dir.path(),
+        unpacked_archives: 0,
+        files: Vec::new(),
+    };
+    let root_name = PathBuf::from(archive_stem(path));
+    if path.is_dir() {
+        walker.collect_files(path, &root_name, 0, 0)?;
+    } else if ArchiveType::from_path(path).is_multi_file_archive() {
+        walker.unpack_and_collect_files(path, &root_name, 0)?;
+    } else {
+        return Err(CommandRunError::Library(LibError::Io(IoError::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Not a support bundle: {}\n\
+                \n\
+                A bundle must be a directory or a .zip, .tar, .tar.gz or .tar.xz archive.",
+                path.display()
+            ),
+        ))));
+    }
+    let mut files = walker.files;
+    files.sort_unstable_by(|a, b| a.logical_path.cmp(&b.logical_path));
+
+    let mut log_files = Vec::new();
+    let mut log_paths = Vec::new();
+    let mut artifact_files = Vec::new();
+    for file in files {
+        if is_log_file(&file.path) {
+            let node_name = infer_node_name(&file)?;
+            log_paths.push((file.logical_path, node_name.clone()));
+            log_files.push(LogFile {
+                path: file.path,
+                node_name,
+            });
+        } else if let Some(kind) = ArtifactKind::from_path(&file.path) {
+            artifact_files.push((file, kind));
+        }
+    }
+
+    let nodes_by_directory = nodes_by_directory(&log_paths);
+    let mut artifacts = Vec::with_capacity(artifact_files.len());
+    for (file, kind) in artifact_files {
+        let content = fs::read_to_string(&file.path).map_err(|e| {
+            CommandRunError::Library(LibError::Io(IoError::new(
+                e.kind(),
+                format!("Failed to read '{}': {}", file.path.display(), e),
+            )))
+        })?;
+        artifacts.push(Artifact {
+            node: artifact_node_name(&file.logical_path, &nodes_by_directory),
+            kind,
+            file_path: file.logical_path.to_string_lossy().to_string(),
+            content,
+        });
+    }
+
+    Ok(ExtractedBundle {
+        _temp_dir: temp_dir,
+        log_files,
+        artifacts,
+    })
+}
+
+struct BundleFile {
+    /// Where the file is on disk, possibly in a temporary directory
+    path: PathBuf,
+    /// Where the file is in the bundle, with archives treated as directories,
+    /// e.g. `bundle/rabbit@host1/log/rabbit.log` for a `rabbit.log` file
+    /// in a `rabbit@host1.tar.gz` archive of a `bundle.zip` archive
+    logical_path: PathBuf,
+}
+
+struct BundleWalker<'a> {
+    temp_dir: &'a Path,
+    unpacked_archives: usize,
+    files: Vec<BundleFile>,
+}
+
+impl BundleWalker<'_> {
+    fn collect_files(
+        &mut self,
+        dir: &Path,
+        logical_dir: &Path,
+        depth: usize,
+        nesting: usize,
+    ) -> Result<()> {
+        if depth > MAX_DIRECTORY_DEPTH {
+            return Ok(());
+        }
+
+        let entries = fs::read_dir(dir).map_err(|e| {
+            CommandRunError::Library(LibError::Io(IoError::new(
+                e.kind(),
+                format!("Failed to read directory '{}': {}", dir.display(), e),
+            )))
+        })?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| {
+                CommandRunError::Library(LibError::Io(IoError::new(
+                    e.kind(),
+                    format!("Failed to read directory entry: {}", e),
+                )))
+            })?;
+
+            let path = entry.path();
+            let logical_path = logical_dir.join(entry.file_name());
+            if path.is_dir() {
+                self.collect_files(&path, &logical_path, depth + 1, nesting)?;
+            } else if path.is_file() && ArchiveType::from_path(&path).is_multi_file_archive() {
+                if nesting >= MAX_ARCHIVE_NESTING {
+                    log::warn!(
+                        "Skipping {}: archives are nested too deeply",
+                        logical_path.display()
+                    );
+                    continue;
+                }
+                let logical_dir = logical_dir.join(archive_stem(&path));
+                self.unpack_and_collect_files(&path, &logical_dir, nesting + 1)?;
+            } else if path.is_file() {
+                self.files.push(BundleFile { path, logical_path });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Every archive is unpacked into a directory of its own, so that the files
+    /// of an archive never mix with the files of the archive it is nested in
+    fn unpack_and_collect_files(
+        &mut self,
+        archive_path: &Path,
+        logical_dir: &Path,
+        nesting: usize,
+    ) -> Result<()> {
+        self.unpacked_archives += 1;
+        let destination = self
+            .temp_dir
+            .join(self.unpacked_archives.to_string())
+            .join(logical_dir);
+        unpack_bundle_archive(archive_path, &destination)?;
+        self.collect_files(&destination, logical_dir, 0, nesting)
+    }
+}
+
+fn unpack_bundle_archive(path: &Path, destination: &Path) -> Result<()> {
+    fs::create_dir_all(destination).map_err(|e| {
+        CommandRunError::Library(LibError::Io(IoError::new(
+            e.kind(),
+            format!(
+                "Failed to create directory '{}': {}",
+                destination.display(),
+                e
+            ),
+        )))
+    })?;
+    unpack_archive(path, destination)
+}
+
+/// The name of an archive without its extensions, e.g. `host1` for `host1.tar.gz`
+fn archive_stem(path: &Path) -> String {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("archive");
+    let lower = file_name.to_lowercase();
+    for suffix in [".tar.gz", ".tar.xz", ".tgz", ".txz", ".tar", ".zip"] {
+        if lower.ends_with(suffix) {
+            return file_name[..file_name.len() - suffix.len()].to_string();
+        }
+    }
+    file_name.to_string()
+}
+
+fn infer_node_name(file: &BundleFile) -> Result<String> {
+    let from_file_name = extract_node_name(&file.path)?;
+    if is_node_name(&from_file_name) {
+        return Ok(from_file_name);
+    }
+    if let Some(node_name) = node_name_from_directories(&file.logical_path) {
+        return Ok(node_name);
+    }
+    if let Some(node_name) = node_name_from_content(&file.path) {
+        return Ok(node_name);
+    }
+    Ok(from_file_name)
+}
+
+fn is_node_name(s: &str) -> bool {
+    match s.split_once('@') {
+        Some((name, host)) => !name.is_empty() && !host.is_empty() && !host.contains('@'),
+        None => false,
+    }
+}
+
+fn node_name_from_directories(path: &Path) -> Option<String> {
+    path.parent()?
+        .ancestors()
+        .filter_map(|dir| dir.file_name().and_then(|n| n.to_str()))
+        .map(|name| archive_stem(Path::new(name)))
+        .find(|name| is_node_name(name))
+}
+
+/// Looks for the `node : rabbit@hostname` line of the banner RabbitMQ logs on boot
+fn node_name_from_content(log_path: &Path) -> Option<String> {
+    let reader = open_log_reader(log_path).ok()?;
+    reader
+        .lines()
+        .take(NODE_BANNER_SEARCH_LINES)
+        .map_while(|line| line.ok())
+        .find_map(|line| {
+            let (_, banner_value) = line.split_once(" node ")?;
+            let value = banner_value
+                .trim_start()
+                .strip_prefix(':')?
+                .split(|c: char| c.is_whitespace() || c.is_control())
+                .find(|s| !s.is_empty())?;
+            is_node_name(value).then(|| value.to_string())
+        })
+}
+
+/// Maps every directory to the nodes whose logs are in its tree
+fn nodes_by_directory(log_paths: &[(PathBuf, String)]) -> HashMap<&Path, BTreeSet<&str>> {
+    let mut nodes: HashMap<&Path, BTreeSet<&str>> = HashMap::new();
+    for (logical_path, node_name) in log_paths {
+        for dir in logical_path.ancestors().skip(1) {
+            nodes.entry(dir).or_default().insert(node_name);
+        }
+    }
+    nodes
+}
+
+fn artifact_node_name(path: &Path, nodes_by_directory: &HashMap<&Path, BTreeSet<&str>>) -> String {
+    if let Some(node_name) = node_name_from_directories(path) {
+        return node_name;
+    }
+    // The nearest directory with logs in it decides: if these logs are of
+    // several nodes, so are the logs of every directory above it
+    path.ancestors()
+        .skip(1)
+        .find_map(|dir| nodes_by_directory.get(dir))
+        .filter(|nodes| nodes.len() == 1)
+        .and_then(|nodes| nodes.first())
+        .map(|node| node.to_string())
+        .unwrap_or_else(|| UNKNOWN_NODE.to_string())
+}
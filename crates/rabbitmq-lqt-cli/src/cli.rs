@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use clap::{Arg, ArgAction, Command, value_parser};
+use rabbitmq_lqt_lib::ContextWindow;
 
 pub use bel7_cli::CompletionShell;
 
@@ -81,6 +82,7 @@ fn web_subcommands() -> Vec<Command> {
                 .value_name("PATH")
                 .help("Path to the database file"),
         )
+        .args(time_anchor_args())
         .arg(
             Arg::new("host")
                 .long("host")
@@ -95,7 +97,14 @@ fn web_subcommands() -> Vec<Command> {
                 .default_value("15692")
                 .value_name("PORT")
                 .help("Port to listen on"),
-        );
+        )
+        .arg(
+            Arg::new("watch_log_dir_path")
+                .long("watch-log-dir-path")
+                .value_name("DIR")
+                .help("Keep ingesting the log files in this directory into the database, creating it if needed, and show new entries as they arrive"),
+        )
+        .args(watch_args());
 
     vec![serve_cmd]
 }
@@ -115,6 +124,11 @@ fn filter_args() -> Vec<Arg> {
             .value_name("SEVERITY")
             .value_parser(["debug", "info", "notice", "warning", "error", "critical"])
             .help("Filter by severity level"),
+        Arg::new("min_severity")
+            .long("min-severity")
+            .value_name("SEVERITY")
+            .value_parser(["debug", "info", "notice", "warning", "error", "critical"])
+            .help("Only include entries of this severity level or a more severe one, e.g. 'warning' also includes errors"),
         Arg::new("erlang_pid")
             .long("erlang-pid")
             .value_name("PID")
@@ -149,6 +163,90 @@ fn filter_args() -> Vec<Arg> {
     ]
 }
 
+fn rules_file_arg() -> Arg {
+    Arg::new("rules_file")
+        .long("rules-file")
+        .value_name("PATH")
+        .help("Path to a TOML file with user-defined annotation rules, applied after the built-in annotators")
+}
+
+fn parallelism_arg() -> Arg {
+    Arg::new("parallelism")
+        .long("parallelism")
+        .short('j')
+        .value_name("N")
+        .value_parser(value_parser!(u16).range(1..))
+        .help("How many log files to parse concurrently [default: the number of CPU cores]")
+}
+
+/// Options of continuous ingestion, shared by `logs watch` and `web serve`
+fn watch_args() -> Vec<Arg> {
+    vec![
+        Arg::new("poll_interval")
+            .long("poll-interval")
+            .value_name("MS")
+            .default_value("1000")
+            .value_parser(value_parser!(u64).range(1..))
+            .help("How often to check the log files for new entries, in milliseconds"),
+        rules_file_arg(),
+        parallelism_arg(),
+    ]
+}
+
+fn time_anchor_args() -> Vec<Arg> {
+    vec![
+        Arg::new("time_anchor")
+            .long("time-anchor")
+            .value_name("ANCHOR")
+            .value_parser(["log-end", "now"])
+            .default_value("log-end")
+            .help("What relative times such as '2 hours ago' or '@1h' are measured from: the most recent entry in the log set (log-end) or the current time (now)"),
+        Arg::new("reference_time")
+            .long("reference-time")
+            .value_name("DATETIME")
+            .help("Measure relative times from this point in time instead. Overrides --time-anchor. Accepts: dates (2025-10-27), datetime (2025-10-27 18:23:00), RFC 3339"),
+    ]
+}
+
+fn mapping_passphrase_arg() -> Arg {
+    Arg::new("mapping_passphrase")
+        .long("mapping-passphrase")
+        .value_name("PASSPHRASE")
+        .env("RABBITMQ_LQT_MAPPING_PASSPHRASE")
+        .hide_env_values(true)
+        .help("Passphrase used to encrypt or decrypt obfuscation mapping files")
+}
+
+fn policy_file_arg() -> Arg {
+    Arg::new("policy_file")
+        .long("policy-file")
+        .value_name("PATH")
+        .help("Path to a TOML obfuscation policy file: disabled categories, values to leave as is and custom patterns")
+}
+
+fn obfuscation_mapping_args() -> Vec<Arg> {
+    vec![
+        Arg::new("import_mapping_file")
+            .long("import-mapping-file")
+            .value_name("PATH")
+            .help("Path to an obfuscation mapping file saved by an earlier run. Its aliases are reused, so they stay stable across runs"),
+        Arg::new("export_mapping_file")
+            .long("export-mapping-file")
+            .value_name("PATH")
+            .help("Save the obfuscation mapping to this file, encrypted if a passphrase is provided. Use 'logs deobfuscate' to translate aliases back"),
+        mapping_passphrase_arg(),
+    ]
+}
+
+fn input_archive_arg() -> Arg {
+    Arg::new("input_archive")
+        .long("input-archive")
+        .value_name("PATH")
+        .action(ArgAction::Append)
+        .num_args(1..)
+        .help("Path to a support bundle: a .zip, .tar, .tar.gz or .tar.xz archive, possibly with nested archives, or a directory. Node names are inferred from paths or log contents, and files such as rabbitmq.conf and enabled_plugins are stored as node artifacts. Can be provided multiple times")
+}
+
 fn logs_subcommands() -> Vec<Command> {
     let parse_cmd = Command::new("parse")
         .about("Parses and annotates RabbitMQ log files")
@@ -169,9 +267,10 @@ fn logs_subcommands() -> Vec<Command> {
                 .num_args(1..)
                 .help("Path(s) to a directory containing RabbitMQ log files (*.log)"),
         )
+        .arg(input_archive_arg())
         .group(
             clap::ArgGroup::new("input_log_files")
-                .args(["input_log_file_path", "input_log_dir_path"])
+                .args(["input_log_file_path", "input_log_dir_path", "input_archive"])
                 .required(true)
                 .multiple(true),
         )
@@ -183,6 +282,14 @@ fn logs_subcommands() -> Vec<Command> {
                 .value_name("PATH")
                 .help("Path to the output database file"),
         )
+        .arg(rules_file_arg())
+        .arg(parallelism_arg())
+        .arg(
+            Arg::new("build_search_index")
+                .long("build-search-index")
+                .action(ArgAction::SetTrue)
+                .help("Build a full-text index of messages for QL `search` queries. Kept up to date by merge, sync and watch"),
+        )
         .arg(
             Arg::new("silent")
                 .long("silent")
@@ -191,7 +298,7 @@ fn logs_subcommands() -> Vec<Command> {
         );
 
     let merge_cmd = Command::new("merge")
-        .about("Merges additional log files into an existing database")
+        .about("Merges additional log files into an existing database. Files imported earlier are only read from where their last import stopped")
         .arg(
             Arg::new("input_log_file_path")
                 .long("input-log-file-path")
@@ -209,9 +316,10 @@ fn logs_subcommands() -> Vec<Command> {
                 .num_args(1..)
                 .help("Path(s) to a directory containing RabbitMQ log files (*.log)"),
         )
+        .arg(input_archive_arg())
         .group(
             clap::ArgGroup::new("input_log_files")
-                .args(["input_log_file_path", "input_log_dir_path"])
+                .args(["input_log_file_path", "input_log_dir_path", "input_archive"])
                 .required(true)
                 .multiple(true),
         )
@@ -223,6 +331,27 @@ fn logs_subcommands() -> Vec<Command> {
                 .value_name("PATH")
                 .help("Path to the existing database file to merge into"),
         )
+        .arg(rules_file_arg())
+        .arg(parallelism_arg())
+        .arg(
+            Arg::new("silent")
+                .long("silent")
+                .action(ArgAction::SetTrue)
+                .help("Suppress output messages"),
+        );
+
+    let sync_cmd = Command::new("sync")
+        .about("Ingests what was appended to the log files in an existing database since they were imported")
+        .arg(
+            Arg::new("db_file_path")
+                .long("db-file-path")
+                .short('i')
+                .required(true)
+                .value_name("PATH")
+                .help("Path to the existing database file to sync"),
+        )
+        .arg(rules_file_arg())
+        .arg(parallelism_arg())
         .arg(
             Arg::new("silent")
                 .long("silent")
@@ -230,24 +359,74 @@ fn logs_subcommands() -> Vec<Command> {
                 .help("Suppress output messages"),
         );
 
+    let watch_cmd = Command::new("watch")
+        .about("Continuously ingests the node logs in a directory, including files that show up later or rotate, into a database")
+        .arg(
+            Arg::new("input_log_dir_path")
+                .long("input-log-dir-path")
+                .short('d')
+                .required(true)
+                .value_name("DIR")
+                .help("Path to the directory with the RabbitMQ log files to watch"),
+        )
+        .arg(
+            Arg::new("db_file_path")
+                .long("db-file-path")
+                .short('i')
+                .required(true)
+                .value_name("PATH")
+                .help("Path to the database file, created if it does not exist"),
+        )
+        .args(watch_args());
+
     let obfuscate_cmd = Command::new("obfuscate")
         .about("Obfuscates sensitive information in RabbitMQ log files")
+        .long_about(
+            "Obfuscates sensitive information in RabbitMQ log files.\n\n\
+             A single log file is obfuscated into --output-log-file-path. A directory or a tar archive\n\
+             of log files is obfuscated into a .tar.gz bundle at --output-bundle-path, using the same\n\
+             aliases for hostnames, queues, users and so on in every file.",
+        )
         .arg(
             Arg::new("input_log_file_path")
                 .long("input-log-file-path")
                 .short('i')
-                .required(true)
                 .value_name("PATH")
-                .help("Path to the input RabbitMQ log file"),
+                .help("Path to the input RabbitMQ log file or a tar archive of log files"),
+        )
+        .arg(
+            Arg::new("input_log_dir_path")
+                .long("input-log-dir-path")
+                .short('d')
+                .value_name("DIRECTORY")
+                .requires("output_bundle_path")
+                .help("Path to a directory containing RabbitMQ log files (*.log)"),
+        )
+        .group(
+            clap::ArgGroup::new("input_log_files")
+                .args(["input_log_file_path", "input_log_dir_path"])
+                .required(true),
         )
         .arg(
             Arg::new("output_log_file_path")
                 .long("output-log-file-path")
                 .short('o')
-                .required(true)
                 .value_name("PATH")
                 .help("Path to the output obfuscated log file"),
         )
+        .arg(
+            Arg::new("output_bundle_path")
+                .long("output-bundle-path")
+                .value_name("PATH")
+                .help("Path to the output .tar.gz bundle of obfuscated log files"),
+        )
+        .group(
+            clap::ArgGroup::new("output")
+                .args(["output_log_file_path", "output_bundle_path"])
+                .required(true),
+        )
+        .arg(policy_file_arg())
+        .args(obfuscation_mapping_args())
         .arg(
             Arg::new("silent")
                 .long("silent")
@@ -255,6 +434,71 @@ fn logs_subcommands() -> Vec<Command> {
                 .help("Suppress output messages"),
         );
 
+    let obfuscate_db_cmd = Command::new("obfuscate-db")
+        .about("Obfuscates sensitive information in a database created by 'logs parse'")
+        .long_about(
+            "Obfuscates sensitive information in a database created by 'logs parse'.\n\n\
+             Entries, their metadata and the file metadata are copied into a new database, with\n\
+             the same aliases for hostnames, queues, users and so on used across all of them.\n\
+             The input database is not modified.",
+        )
+        .arg(
+            Arg::new("input_db_file_path")
+                .long("input-db-file-path")
+                .short('i')
+                .required(true)
+                .value_name("PATH")
+                .help("Path to the database file to obfuscate"),
+        )
+        .arg(
+            Arg::new("output_db_file_path")
+                .long("output-db-file-path")
+                .short('o')
+                .required(true)
+                .value_name("PATH")
+                .help("Path to the output obfuscated database file"),
+        )
+        .arg(policy_file_arg())
+        .args(obfuscation_mapping_args())
+        .arg(
+            Arg::new("silent")
+                .long("silent")
+                .action(ArgAction::SetTrue)
+                .help("Suppress output messages"),
+        );
+
+    let deobfuscate_cmd = Command::new("deobfuscate")
+        .about("Translates obfuscation aliases back into the original values")
+        .long_about(
+            "Translates obfuscation aliases back into the original values.\n\n\
+             Replaces aliases such as 'host3' or 'queue17' in text, e.g. a message from a vendor\n\
+             or the output of 'logs ql' on an obfuscated database, using a mapping file saved\n\
+             with --export-mapping-file. Reads standard input unless --input-file-path is given.",
+        )
+        .arg(
+            Arg::new("mapping_file")
+                .long("mapping-file")
+                .short('m')
+                .required(true)
+                .value_name("PATH")
+                .help("Path to the obfuscation mapping file"),
+        )
+        .arg(mapping_passphrase_arg())
+        .arg(
+            Arg::new("input_file_path")
+                .long("input-file-path")
+                .short('i')
+                .value_name("PATH")
+                .help("Path to the text to deobfuscate [default: standard input]"),
+        )
+        .arg(
+            Arg::new("output_file_path")
+                .long("output-file-path")
+                .short('o')
+                .value_name("PATH")
+                .help("Path to write the deobfuscated text to [default: standard output]"),
+        );
+
     let query_cmd = Command::new("query")
         .about("Query log entries from a database")
         .arg(
@@ -266,12 +510,25 @@ fn logs_subcommands() -> Vec<Command> {
                 .help("Path to the database file"),
         )
         .args(filter_args())
+        .args(time_anchor_args())
         .arg(
             Arg::new("node")
                 .long("node")
                 .value_name("NODE")
                 .help("Filter by node name (e.g., rabbit@sunnyside)"),
         )
+        .arg(
+            Arg::new("search")
+                .long("search")
+                .value_name("TEXT")
+                .help("Full-text search of messages, e.g. 'timeout \"connection closed\" handsh*'"),
+        )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_name("COLUMNS")
+                .help("Comma-separated columns to sort by, each optionally followed by :asc or :desc, e.g. 'node,severity:desc'. Severities sort by rank"),
+        )
         .arg(
             Arg::new("limit")
                 .long("limit")
@@ -321,6 +578,299 @@ fn logs_subcommands() -> Vec<Command> {
                 .value_name("QUERY")
                 .help("RQL query string (e.g., ':errors | limit 100', '#tls and -#timeouts')"),
         )
+        .args(time_anchor_args())
+        .arg(
+            Arg::new("without_colors")
+                .long("without-colors")
+                .action(ArgAction::SetTrue)
+                .help("Disable colored output"),
+        );
+
+    let context_cmd = Command::new("context")
+        .about("Show the entries logged before and after an entry")
+        .arg(
+            Arg::new("input_db_file_path")
+                .long("input-db-file-path")
+                .short('i')
+                .required(true)
+                .value_name("PATH")
+                .help("Path to the database file"),
+        )
+        .arg(
+            Arg::new("id")
+                .long("id")
+                .required(true)
+                .value_name("ID")
+                .help("ID of the entry to show the context of")
+                .value_parser(value_parser!(i64)),
+        )
+        .arg(
+            Arg::new("before")
+                .long("before")
+                .short('B')
+                .default_value("20")
+                .value_name("N")
+                .help(format!(
+                    "Number of entries to show before the entry, at most {}",
+                    ContextWindow::MAX_ENTRIES
+                ))
+                .value_parser(value_parser!(u64).range(..=ContextWindow::MAX_ENTRIES)),
+        )
+        .arg(
+            Arg::new("after")
+                .long("after")
+                .short('A')
+                .default_value("20")
+                .value_name("N")
+                .help(format!(
+                    "Number of entries to show after the entry, at most {}",
+                    ContextWindow::MAX_ENTRIES
+                ))
+                .value_parser(value_parser!(u64).range(..=ContextWindow::MAX_ENTRIES)),
+        )
+        .arg(
+            Arg::new("same_node")
+                .long("same-node")
+                .action(ArgAction::SetTrue)
+                .help("Only show entries of the same node"),
+        )
+        .arg(
+            Arg::new("same_pid")
+                .long("same-pid")
+                .action(ArgAction::SetTrue)
+                .help("Only show entries of the same Erlang process (on the same node)"),
+        )
+        .arg(
+            Arg::new("without_colors")
+                .long("without-colors")
+                .action(ArgAction::SetTrue)
+                .help("Disable colored output"),
+        );
+
+    let entities_cmd = Command::new("entities")
+        .about("List the most mentioned queues, streams, exchanges, virtual hosts, users and connections")
+        .arg(
+            Arg::new("input_db_file_path")
+                .long("input-db-file-path")
+                .short('i')
+                .required(true)
+                .value_name("PATH")
+                .help("Path to the database file"),
+        )
+        .arg(
+            Arg::new("kind")
+                .long("kind")
+                .short('k')
+                .value_name("KIND")
+                .help("Only list entities of this kind")
+                .value_parser(["queue", "stream", "exchange", "vhost", "user", "connection"]),
+        )
+        .arg(
+            Arg::new("limit")
+                .long("limit")
+                .short('n')
+                .default_value("20")
+                .value_name("N")
+                .help("Maximum number of entities to list")
+                .value_parser(value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("without_colors")
+                .long("without-colors")
+                .action(ArgAction::SetTrue)
+                .help("Disable colored output"),
+        );
+
+    let patterns_cmd = Command::new("patterns")
+        .about("List the most frequent message patterns: messages with PIDs, names, addresses and numbers replaced by placeholders")
+        .arg(
+            Arg::new("input_db_file_path")
+                .long("input-db-file-path")
+                .short('i')
+                .required(true)
+                .value_name("PATH")
+                .help("Path to the database file"),
+        )
+        .arg(
+            Arg::new("node")
+                .long("node")
+                .value_name("NODE")
+                .help("Only count entries of this node (e.g., rabbit@sunnyside)"),
+        )
+        .arg(
+            Arg::new("limit")
+                .long("limit")
+                .short('n')
+                .default_value("20")
+                .value_name("N")
+                .help("Maximum number of patterns to list")
+                .value_parser(value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("without_colors")
+                .long("without-colors")
+                .action(ArgAction::SetTrue)
+                .help("Disable colored output"),
+        );
+
+    let timeline_cmd = Command::new("timeline")
+        .about("Show a chronological, per-node view of all nodes with bursts collapsed and cluster-level transitions highlighted")
+        .arg(
+            Arg::new("input_db_file_path")
+                .long("input-db-file-path")
+                .short('i')
+                .required(true)
+                .value_name("PATH")
+                .help("Path to the database file"),
+        )
+        .arg(
+            Arg::new("since_time")
+                .long("since-time")
+                .value_name("DATETIME")
+                .help("Lower bound of datetime range. Accepts: dates (2025-10-27), datetime (2025-10-27 18:23:00), RFC 3339, or human formats ('yesterday', '2 days ago', 'last Monday')"),
+        )
+        .arg(
+            Arg::new("to_time")
+                .long("to-time")
+                .value_name("DATETIME")
+                .help("Upper bound of datetime range (defaults to now). Accepts: dates (2025-10-27), datetime (2025-10-27 18:23:00), RFC 3339, or human formats ('yesterday', '2 days ago', 'now')"),
+        )
+        .args(time_anchor_args())
+        .arg(
+            Arg::new("node")
+                .long("node")
+                .value_name("NODE")
+                .help("Only include entries of this node (e.g., rabbit@sunnyside)"),
+        )
+        .arg(
+            Arg::new("limit")
+                .long("limit")
+                .value_name("N")
+                .help("Limit the number of entries considered")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("burst_window")
+                .long("burst-window")
+                .value_name("SECONDS")
+                .default_value("5")
+                .help("Collapse near-identical messages a node logs within this many seconds of each other. 0 disables collapsing")
+                .value_parser(value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("transitions_only")
+                .long("transitions-only")
+                .action(ArgAction::SetTrue)
+                .help("Only show node boots, shutdowns, leader changes, partitions, alarms and maintenance mode changes"),
+        )
+        .arg(
+            Arg::new("without_colors")
+                .long("without-colors")
+                .action(ArgAction::SetTrue)
+                .help("Disable colored output"),
+        );
+
+    let diff_cmd = Command::new("diff")
+        .about("Compare two databases, or two time windows of one, by message patterns, severity, subsystem and label counts, and file metadata")
+        .arg(
+            Arg::new("baseline_db_file_path")
+                .long("baseline-db-file-path")
+                .short('b')
+                .required(true)
+                .value_name("PATH")
+                .help("Path to the database file of the baseline, e.g. a healthy period"),
+        )
+        .arg(
+            Arg::new("comparison_db_file_path")
+                .long("comparison-db-file-path")
+                .short('c')
+                .value_name("PATH")
+                .required_unless_present_any([
+                    "baseline_since_time",
+                    "baseline_to_time",
+                    "comparison_since_time",
+                    "comparison_to_time",
+                ])
+                .help("Path to the database file to compare to the baseline. Defaults to the baseline database, in which case time windows must be provided"),
+        )
+        .arg(
+            Arg::new("baseline_since_time")
+                .long("baseline-since-time")
+                .value_name("DATETIME")
+                .help("Lower bound of the baseline datetime range. Accepts: dates (2025-10-27), datetime (2025-10-27 18:23:00), RFC 3339, or human formats ('yesterday', '2 days ago', 'last Monday')"),
+        )
+        .arg(
+            Arg::new("baseline_to_time")
+                .long("baseline-to-time")
+                .value_name("DATETIME")
+                .help("Upper bound of the baseline datetime range"),
+        )
+        .arg(
+            Arg::new("comparison_since_time")
+                .long("comparison-since-time")
+                .value_name("DATETIME")
+                .help("Lower bound of the comparison datetime range"),
+        )
+        .arg(
+            Arg::new("comparison_to_time")
+                .long("comparison-to-time")
+                .value_name("DATETIME")
+                .help("Upper bound of the comparison datetime range"),
+        )
+        .args(time_anchor_args())
+        .arg(
+            Arg::new("limit")
+                .long("limit")
+                .short('n')
+                .value_name("N")
+                .default_value("20")
+                .help("Maximum number of rows to show per section")
+                .value_parser(value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("without_colors")
+                .long("without-colors")
+                .action(ArgAction::SetTrue)
+                .help("Disable colored output"),
+        );
+
+    let report_cmd = Command::new("report")
+        .about("Run a catalog of rule-based checks over a database and list the findings, most severe first")
+        .arg(
+            Arg::new("input_db_file_path")
+                .long("input-db-file-path")
+                .short('i')
+                .required(true)
+                .value_name("PATH")
+                .help("Path to the database file"),
+        )
+        .arg(
+            Arg::new("since_time")
+                .long("since-time")
+                .value_name("DATETIME")
+                .help("Lower bound of datetime range. Accepts: dates (2025-10-27), datetime (2025-10-27 18:23:00), RFC 3339, or human formats ('yesterday', '2 days ago', 'last Monday')"),
+        )
+        .arg(
+            Arg::new("to_time")
+                .long("to-time")
+                .value_name("DATETIME")
+                .help("Upper bound of datetime range (defaults to now). Accepts: dates (2025-10-27), datetime (2025-10-27 18:23:00), RFC 3339, or human formats ('yesterday', '2 days ago', 'now')"),
+        )
+        .args(time_anchor_args())
+        .arg(
+            Arg::new("node")
+                .long("node")
+                .value_name("NODE")
+                .help("Only check entries and files of this node (e.g., rabbit@sunnyside)"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "markdown", "json"])
+                .default_value("table")
+                .help("Output format"),
+        )
         .arg(
             Arg::new("without_colors")
                 .long("without-colors")
@@ -354,6 +904,7 @@ fn logs_subcommands() -> Vec<Command> {
                 .action(ArgAction::SetTrue)
                 .help("Follow the log file for new entries (like tail -f)"),
         )
+        .arg(rules_file_arg())
         .arg(
             Arg::new("without_colors")
                 .long("without-colors")
@@ -365,10 +916,20 @@ fn logs_subcommands() -> Vec<Command> {
     vec![
         parse_cmd,
         merge_cmd,
+        sync_cmd,
+        watch_cmd,
         obfuscate_cmd,
+        obfuscate_db_cmd,
+        deobfuscate_cmd,
         query_cmd,
         overview_cmd,
         ql_cmd,
+        context_cmd,
+        entities_cmd,
+        patterns_cmd,
+        timeline_cmd,
+        diff_cmd,
+        report_cmd,
         tail_cmd,
     ]
 }
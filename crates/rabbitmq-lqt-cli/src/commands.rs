@@ -12,41 +12,51 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use crate::archive::{
-    ArchiveType, extract_tar_archive, is_supported_log_file, open_log_reader,
-    strip_compression_suffix,
+    ArchiveType, ExtractedArchive, extract_tar_archive, is_supported_log_file, open_log_reader,
 };
+use crate::bundle::{ExtractedBundle, extract_bundle};
 use crate::core::Result;
 use crate::errors::CommandRunError;
+use crate::ingestion::{
+    IngestionOptions, LogFile, default_parallelism, extract_node_name, ingest_log_files,
+};
 use crate::output;
+use crate::watch::{
+    WatchOptions, open_or_create_database, shutdown_signal, validate_watched_directory,
+    watch_log_directory,
+};
 use bel7_cli::{BRAILLE_TICK_CHARS, ExitCode, ExitCodeProvider, SpinnerReporter};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use clap::ArgMatches;
-use duckdb::Error as DuckDbError;
+use flate2::Compression;
+use flate2::write::GzEncoder;
 use rabbitmq_lqt_lib::Error as LibError;
-use rabbitmq_lqt_lib::entry_metadata::annotate_entry;
-use rabbitmq_lqt_lib::file_set_metadata::extract_file_metadata;
-use rabbitmq_lqt_lib::parser::{IncrementalParser, ParsedLogEntry};
-use rabbitmq_lqt_lib::rel_db::FileMetadata;
+use rabbitmq_lqt_lib::entry_metadata::annotate_entry_with_rules;
+use rabbitmq_lqt_lib::parser::{IncrementalParser, LogFormat};
+use rabbitmq_lqt_lib::rel_db::{FileIngestionState, FileMetadata};
 use rabbitmq_lqt_lib::{
-    DatabaseConnection, EntryFilter, NodeLogEntry, QueryContext, create_database_for_bulk_import,
-    finalize_bulk_import, open_database, parse_log_file, post_insertion_operations,
+    ContextScope, ContextWindow, DatabaseConnection, DiffSide, EntityKind, EntryFilter, LogDiff,
+    MessagePatterns, MessageSearchIndex, NodeArtifacts, NodeLogEntry, NodeLogEntryEntities,
+    QueryContext, RuleSet, SearchQuery, Severity, SortKey, TimeAnchor, TimeReference, Timeline,
+    TimelineOptions, create_database_for_bulk_import, finalize_bulk_import, obfuscate_database,
+    open_database, parse_log_file, post_insertion_operations,
 };
-use rabbitmq_lqt_obfuscation::{LogObfuscator, ObfuscationStats};
-use rabbitmq_lqt_ql::to_query_context;
-use rayon::prelude::*;
+use rabbitmq_lqt_obfuscation::{
+    Deobfuscator, LogObfuscator, ObfuscationMapping, ObfuscationPolicy, ObfuscationStats,
+};
+use rabbitmq_lqt_ql::{Report, ReportOptions, to_query_context_with_reference};
 use std::collections::HashSet;
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader, BufWriter, Error as IoError, ErrorKind, Seek, SeekFrom, Write};
+use std::io::{
+    self, BufRead, BufReader, BufWriter, Error as IoError, ErrorKind, Seek, SeekFrom, Write,
+};
 use std::path::{Path, PathBuf};
-use std::result::Result as StdResult;
-use std::sync::mpsc;
-use std::thread;
+use std::str::FromStr;
 use std::time::{Duration, Instant};
 use tabled::{Table, Tabled, settings::Style};
+use tar::{Builder as TarBuilder, Header as TarHeader};
 use tokio::time;
 
-const PIPELINE_CHUNK_SIZE: usize = 25_000;
-
 const ERR_MSG_FILE_NOT_FOUND_HELP: &str = "Make sure:\n\
   • The file path(s) are correct\n\
   • You have read permissions for the file(s)\n\
@@ -102,6 +112,31 @@ pub fn handle_merge_command(args: &ArgMatches) -> ExitCode {
     }
 }
 
+pub fn handle_sync_command(args: &ArgMatches) -> ExitCode {
+    match sync_logs(args) {
+        Ok(_) => {
+            if !args.get_flag("silent") {
+                println!("Done");
+            }
+            ExitCode::Ok
+        }
+        Err(e) => {
+            log::error!("Failed to sync logs: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+pub async fn handle_watch_command(args: &ArgMatches) -> ExitCode {
+    match watch_logs(args).await {
+        Ok(_) => ExitCode::Ok,
+        Err(e) => {
+            log::error!("Failed to watch logs: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
 pub fn handle_obfuscate_command(args: &ArgMatches) -> ExitCode {
     match obfuscate_log(args) {
         Ok(_) => {
@@ -117,6 +152,31 @@ pub fn handle_obfuscate_command(args: &ArgMatches) -> ExitCode {
     }
 }
 
+pub fn handle_obfuscate_db_command(args: &ArgMatches) -> ExitCode {
+    match obfuscate_db(args) {
+        Ok(_) => {
+            if !args.get_flag("silent") {
+                println!("Done");
+            }
+            ExitCode::Ok
+        }
+        Err(e) => {
+            log::error!("Failed to obfuscate database: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+pub fn handle_deobfuscate_command(args: &ArgMatches) -> ExitCode {
+    match deobfuscate(args) {
+        Ok(_) => ExitCode::Ok,
+        Err(e) => {
+            log::error!("Failed to deobfuscate: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
 pub fn handle_ql_command(args: &ArgMatches) -> ExitCode {
     match ql_query(args) {
         Ok(_) => ExitCode::Ok,
@@ -127,6 +187,66 @@ pub fn handle_ql_command(args: &ArgMatches) -> ExitCode {
     }
 }
 
+pub fn handle_context_command(args: &ArgMatches) -> ExitCode {
+    match show_context(args) {
+        Ok(_) => ExitCode::Ok,
+        Err(e) => {
+            log::error!("Failed to show entry context: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+pub fn handle_entities_command(args: &ArgMatches) -> ExitCode {
+    match list_entities(args) {
+        Ok(_) => ExitCode::Ok,
+        Err(e) => {
+            log::error!("Failed to list entities: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+pub fn handle_patterns_command(args: &ArgMatches) -> ExitCode {
+    match list_patterns(args) {
+        Ok(_) => ExitCode::Ok,
+        Err(e) => {
+            log::error!("Failed to list patterns: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+pub fn handle_timeline_command(args: &ArgMatches) -> ExitCode {
+    match show_timeline(args) {
+        Ok(_) => ExitCode::Ok,
+        Err(e) => {
+            log::error!("Failed to build timeline: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+pub fn handle_diff_command(args: &ArgMatches) -> ExitCode {
+    match show_diff(args) {
+        Ok(_) => ExitCode::Ok,
+        Err(e) => {
+            log::error!("Failed to compare log sets: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+pub fn handle_report_command(args: &ArgMatches) -> ExitCode {
+    match show_report(args) {
+        Ok(_) => ExitCode::Ok,
+        Err(e) => {
+            log::error!("Failed to build report: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
 pub async fn handle_tail_command(args: &ArgMatches) -> ExitCode {
     match tail_logs(args).await {
         Ok(_) => ExitCode::Ok,
@@ -158,18 +278,23 @@ async fn tail_logs(args: &ArgMatches) -> Result<()> {
     let follow = args.get_flag("follow");
     let without_colors = args.get_flag("without_colors");
     let filter = build_entry_filter(args)?;
+    let rules = load_rules(args)?;
 
     let node_name = extract_node_name(&log_path)?;
 
     let reader = BufReader::new(File::open(&log_path)?);
     let parse_result = parse_log_file(reader)?;
+    // An empty file tells nothing about its format, the follow loop will detect it instead
+    let format = (!parse_result.entries.is_empty()).then_some(parse_result.format);
     let mut entries = parse_result.entries;
     let total_entries = entries.len();
 
     if filter.is_empty() {
         let start_index = total_entries.saturating_sub(n);
         let tail_entries = &mut entries[start_index..];
-        tail_entries.iter_mut().for_each(annotate_entry);
+        tail_entries
+            .iter_mut()
+            .for_each(|e| annotate_entry_with_rules(e, rules.as_ref()));
 
         if !tail_entries.is_empty() {
             let models: Vec<_> = tail_entries
@@ -182,7 +307,9 @@ async fn tail_logs(args: &ArgMatches) -> Result<()> {
             output::display_log_entries(models, without_colors)?;
         }
     } else {
-        entries.iter_mut().for_each(annotate_entry);
+        entries
+            .iter_mut()
+            .for_each(|e| annotate_entry_with_rules(e, rules.as_ref()));
         let filtered: Vec<_> = filter.filter(&entries);
         let filtered_len = filtered.len();
         let start_index = filtered_len.saturating_sub(n);
@@ -205,10 +332,12 @@ async fn tail_logs(args: &ArgMatches) -> Result<()> {
         tail_follow(
             &log_path,
             &node_name,
+            format,
             total_entries,
             file_len,
             without_colors,
             &filter,
+            rules.as_ref(),
         )
         .await?;
     }
@@ -216,15 +345,22 @@ async fn tail_logs(args: &ArgMatches) -> Result<()> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn tail_follow(
     log_path: &Path,
     node_name: &str,
+    format: Option<LogFormat>,
     start_sequence_id: usize,
     initial_file_size: u64,
     without_colors: bool,
     filter: &EntryFilter,
+    rules: Option<&RuleSet>,
 ) -> Result<()> {
-    let mut parser = IncrementalParser::new(start_sequence_id);
+    let new_parser = |start_sequence_id| match format {
+        Some(format) => IncrementalParser::with_format(start_sequence_id, format),
+        None => IncrementalParser::new(start_sequence_id),
+    };
+    let mut parser = new_parser(start_sequence_id);
     let mut last_size = initial_file_size;
     let mut next_display_id = start_sequence_id as i64 + 1;
     let mut interval = time::interval(Duration::from_millis(300));
@@ -241,7 +377,7 @@ async fn tail_follow(
                 };
 
                 if current_size < last_size {
-                    parser = IncrementalParser::new(0);
+                    parser = new_parser(0);
                     last_size = 0;
                     next_display_id = 1;
                 }
@@ -264,7 +400,7 @@ async fn tail_follow(
                     if !new_entries.is_empty() {
                         new_entries
                             .iter_mut()
-                            .for_each(annotate_entry);
+                            .for_each(|e| annotate_entry_with_rules(e, rules));
                         let matching: Vec<_> = if filter.is_empty() {
                             new_entries.iter().collect()
                         } else {
@@ -288,7 +424,7 @@ async fn tail_follow(
     }
 
     if let Some(mut entry) = parser.flush() {
-        annotate_entry(&mut entry);
+        annotate_entry_with_rules(&mut entry, rules);
         if filter.is_empty() || filter.matches(&entry) {
             let model = output::parsed_entry_to_model(&entry, node_name, next_display_id);
             output::display_log_entries(vec![model], without_colors)?;
@@ -445,18 +581,6 @@ fn collect_log_files_from_directory(dir_path: &str) -> Result<Vec<PathBuf>> {
     Ok(log_files)
 }
 
-enum InsertionTask {
-    EntriesChunk {
-        node_name: String,
-        entries: Vec<ParsedLogEntry>,
-    },
-    FileCompletionMarker {
-        node_name: String,
-        file_path: PathBuf,
-        total_lines: usize,
-    },
-}
-
 fn collect_log_paths_from_args(args: &ArgMatches) -> Result<Vec<PathBuf>> {
     let mut log_paths: Vec<PathBuf> = Vec::new();
 
@@ -474,87 +598,6 @@ fn collect_log_paths_from_args(args: &ArgMatches) -> Result<Vec<PathBuf>> {
     Ok(deduplicate_paths(log_paths))
 }
 
-fn process_log_file(log_path: &Path, tx: &mpsc::Sender<InsertionTask>, silent: bool) -> Result<()> {
-    let mut spinner = if !silent {
-        let mut s = SpinnerReporter::new().with_tick_chars(BRAILLE_TICK_CHARS);
-        s.start(&format!("Parsing {}", log_path.display()));
-        Some(s)
-    } else {
-        None
-    };
-
-    let node_name = extract_node_name(log_path)?;
-
-    if let Some(ref s) = spinner {
-        s.set_message(&format!("Reading {}", log_path.display()));
-    }
-
-    let reader = open_log_reader(log_path)?;
-
-    if let Some(ref s) = spinner {
-        s.set_message(&format!("Parsing {}", log_path.display()));
-    }
-
-    let parse_result = parse_log_file(reader)?;
-    let total_lines = parse_result.total_lines;
-    let entries = parse_result.entries;
-
-    if let Some(ref s) = spinner {
-        s.set_message(&format!("Processing {} entries", entries.len()));
-    }
-
-    let chunks: Vec<_> = entries
-        .chunks(PIPELINE_CHUNK_SIZE)
-        .map(|c| c.to_vec())
-        .collect();
-
-    for mut chunk in chunks {
-        chunk.par_iter_mut().for_each(annotate_entry);
-        chunk.sort_unstable_by_key(|e| e.sequence_id);
-
-        tx.send(InsertionTask::EntriesChunk {
-            node_name: node_name.clone(),
-            entries: chunk,
-        })
-        .map_err(|e| {
-            CommandRunError::Library(LibError::Io(IoError::other(format!(
-                "Failed to send entries for insertion: {}",
-                e
-            ))))
-        })?;
-    }
-
-    tx.send(InsertionTask::FileCompletionMarker {
-        node_name: node_name.clone(),
-        file_path: log_path.to_path_buf(),
-        total_lines,
-    })
-    .map_err(|e| {
-        CommandRunError::Library(LibError::Io(IoError::other(format!(
-            "Failed to send a file completion marker: {}",
-            e
-        ))))
-    })?;
-
-    if let Some(ref mut s) = spinner {
-        s.finish(&format!("✓ Parsed {}", log_path.display()));
-    }
-
-    Ok(())
-}
-
-fn wait_for_insertion_thread(
-    insert_thread: thread::JoinHandle<StdResult<(), DuckDbError>>,
-) -> Result<()> {
-    insert_thread
-        .join()
-        .map_err(|_| {
-            CommandRunError::Library(LibError::Io(IoError::other("Insertion thread panicked")))
-        })?
-        .map_err(|e| CommandRunError::Library(LibError::Database(e)))?;
-    Ok(())
-}
-
 fn parse_logs(args: &ArgMatches) -> Result<()> {
     let start_time = Instant::now();
 
@@ -565,35 +608,35 @@ fn parse_logs(args: &ArgMatches) -> Result<()> {
         .expect("output_db_file_path is a required argument")
         .into();
 
+    let archive_paths = collect_archive_paths_from_args(args);
     validate_file_paths(&log_paths)?;
+    validate_file_paths(&archive_paths)?;
     validate_database_path(&db_path)?;
+    let rules = load_rules(args)?;
 
     let db = create_database_for_bulk_import(&db_path)?;
     let silent = args.get_flag("silent");
 
-    let (tx, rx) = mpsc::channel::<InsertionTask>();
-    let db_clone = db.clone();
-    let insert_thread = thread::spawn(move || insert_entries_thread(db_clone, rx));
-
-    // Keep extracted archives alive until processing is complete
-    let mut extracted_archives = Vec::new();
-
-    for log_path in &log_paths {
-        let archive_type = ArchiveType::from_path(log_path);
-        if archive_type.is_tar_archive() {
-            let extracted = extract_tar_archive(log_path)?;
-            for contained_file in &extracted.log_files {
-                process_log_file(contained_file, &tx, silent)?;
-            }
-            extracted_archives.push(extracted);
-        } else {
-            process_log_file(log_path, &tx, silent)?;
-        }
-    }
-
-    drop(tx);
-    wait_for_insertion_thread(insert_thread)?;
+    // Keep extracted archives and bundles alive until processing is complete
+    let (log_paths, extracted_archives) = expand_tar_archives(log_paths)?;
+    let bundles = extract_bundles(&archive_paths)?;
+    let log_files = log_files_to_ingest(log_paths, &bundles)?;
+    ingest_log_files(
+        &db,
+        &log_files,
+        &IngestionOptions {
+            rules: rules.as_ref(),
+            parallelism: parallelism(args),
+            start_id: 1,
+            incremental: false,
+            defer_incomplete_lines: false,
+            silent,
+        },
+    )?;
+    insert_node_artifacts(&db, &bundles)?;
     drop(extracted_archives);
+    drop(bundles);
+    NodeLogEntry::renumber_in_timestamp_order(&db)?;
 
     let mut index_spinner = if !silent {
         let mut s = SpinnerReporter::new().with_tick_chars(BRAILLE_TICK_CHARS);
@@ -610,6 +653,22 @@ fn parse_logs(args: &ArgMatches) -> Result<()> {
         s.finish("✓ Indexes created");
     }
 
+    if args.get_flag("build_search_index") {
+        let mut search_index_spinner = if !silent {
+            let mut s = SpinnerReporter::new().with_tick_chars(BRAILLE_TICK_CHARS);
+            s.start("Building the message search index...");
+            Some(s)
+        } else {
+            None
+        };
+
+        MessageSearchIndex::build(&db)?;
+
+        if let Some(ref mut s) = search_index_spinner {
+            s.finish("✓ Search index built");
+        }
+    }
+
     let total = NodeLogEntry::count_all(&db)?;
     let elapsed = start_time.elapsed();
     let elapsed_secs = elapsed.as_secs_f64();
@@ -623,6 +682,89 @@ fn parse_logs(args: &ArgMatches) -> Result<()> {
     Ok(())
 }
 
+/// Replaces tar archives with the log files they contain. The returned archives
+/// must be kept alive until the extracted files are processed.
+fn expand_tar_archives(log_paths: Vec<PathBuf>) -> Result<(Vec<PathBuf>, Vec<ExtractedArchive>)> {
+    let mut expanded = Vec::with_capacity(log_paths.len());
+    let mut extracted_archives = Vec::new();
+
+    for log_path in log_paths {
+        if ArchiveType::from_path(&log_path).is_tar_archive() {
+            let extracted = extract_tar_archive(&log_path)?;
+            expanded.extend(extracted.log_files.iter().cloned());
+            extracted_archives.push(extracted);
+        } else {
+            expanded.push(log_path);
+        }
+    }
+
+    Ok((expanded, extracted_archives))
+}
+
+fn collect_archive_paths_from_args(args: &ArgMatches) -> Vec<PathBuf> {
+    let archive_paths = args
+        .get_many::<String>("input_archive")
+        .map(|paths| paths.map(PathBuf::from).collect())
+        .unwrap_or_default();
+    deduplicate_paths(archive_paths)
+}
+
+/// Extracts the given support bundles. The returned bundles must be kept alive
+/// until their log files are processed.
+fn extract_bundles(archive_paths: &[PathBuf]) -> Result<Vec<ExtractedBundle>> {
+    let mut bundles = Vec::with_capacity(archive_paths.len());
+    for path in archive_paths {
+        let bundle = extract_bundle(path)?;
+        if bundle.log_files.is_empty() {
+            log::warn!("No log files found in {}", path.display());
+        }
+        log::info!(
+            "Found {} log files and {} node artifacts in {}",
+            bundle.log_files.len(),
+            bundle.artifacts.len(),
+            path.display()
+        );
+        bundles.push(bundle);
+    }
+    Ok(bundles)
+}
+
+fn log_files_to_ingest(
+    log_paths: Vec<PathBuf>,
+    bundles: &[ExtractedBundle],
+) -> Result<Vec<LogFile>> {
+    let mut log_files = log_paths
+        .into_iter()
+        .map(LogFile::from_path)
+        .collect::<Result<Vec<_>>>()?;
+    for bundle in bundles {
+        log_files.extend(bundle.log_files.iter().cloned());
+    }
+    Ok(log_files)
+}
+
+fn insert_node_artifacts(db: &DatabaseConnection, bundles: &[ExtractedBundle]) -> Result<()> {
+    for artifact in bundles.iter().flat_map(|b| &b.artifacts) {
+        NodeArtifacts::insert(db, artifact)?;
+    }
+    Ok(())
+}
+
+fn parallelism(args: &ArgMatches) -> usize {
+    args.get_one::<u16>("parallelism")
+        .map(|&n| n as usize)
+        .unwrap_or_else(default_parallelism)
+}
+
+fn load_rules(args: &ArgMatches) -> Result<Option<RuleSet>> {
+    let Some(path) = args.get_one::<String>("rules_file") else {
+        return Ok(None);
+    };
+    let rules = RuleSet::from_file(Path::new(path))?;
+    log::info!("Loaded {} annotation rules from {}", rules.len(), path);
+    Ok(Some(rules))
+}
+
 fn validate_existing_database_path(db_path: &Path) -> Result<()> {
     if !db_path.exists() {
         return Err(CommandRunError::Library(LibError::Io(IoError::new(
@@ -665,37 +807,22 @@ fn merge_logs(args: &ArgMatches) -> Result<()> {
         .expect("db_file_path is a required argument")
         .into();
 
+    let archive_paths = collect_archive_paths_from_args(args);
     validate_file_paths(&log_paths)?;
+    validate_file_paths(&archive_paths)?;
     validate_existing_database_path(&db_path)?;
+    let rules = load_rules(args)?;
 
     let db = open_database(&db_path)?;
     let count_before = NodeLogEntry::count_all(&db)?;
-    let start_id = NodeLogEntry::max_entry_id(&db)? + 1;
-    let silent = args.get_flag("silent");
-
-    let (tx, rx) = mpsc::channel::<InsertionTask>();
-    let db_clone = db.clone();
-    let insert_thread =
-        thread::spawn(move || merge_entries_thread_with_start_id(db_clone, rx, start_id));
-
-    let mut extracted_archives = Vec::new();
-
-    for log_path in &log_paths {
-        let archive_type = ArchiveType::from_path(log_path);
-        if archive_type.is_tar_archive() {
-            let extracted = extract_tar_archive(log_path)?;
-            for contained_file in &extracted.log_files {
-                process_log_file(contained_file, &tx, silent)?;
-            }
-            extracted_archives.push(extracted);
-        } else {
-            process_log_file(log_path, &tx, silent)?;
-        }
-    }
 
-    drop(tx);
-    wait_for_insertion_thread(insert_thread)?;
+    let (log_paths, extracted_archives) = expand_tar_archives(log_paths)?;
+    let bundles = extract_bundles(&archive_paths)?;
+    let log_files = log_files_to_ingest(log_paths, &bundles)?;
+    insert_node_artifacts(&db, &bundles)?;
+    ingest_into_existing_database(&db, &log_files, rules.as_ref(), args)?;
     drop(extracted_archives);
+    drop(bundles);
 
     let count_after = NodeLogEntry::count_all(&db)?;
     let entries_added = count_after - count_before;
@@ -712,91 +839,114 @@ fn merge_logs(args: &ArgMatches) -> Result<()> {
     Ok(())
 }
 
-fn insert_entries_thread(
-    db: DatabaseConnection,
-    rx: mpsc::Receiver<InsertionTask>,
-) -> StdResult<(), DuckDbError> {
-    insert_entries_thread_with_start_id(db, rx, 1)
-}
-
-fn insert_entries_thread_with_start_id(
-    db: DatabaseConnection,
-    rx: mpsc::Receiver<InsertionTask>,
-    start_id: i64,
-) -> StdResult<(), DuckDbError> {
-    insert_entries_thread_impl(db, rx, start_id, false)
-}
-
-fn merge_entries_thread_with_start_id(
-    db: DatabaseConnection,
-    rx: mpsc::Receiver<InsertionTask>,
-    start_id: i64,
-) -> StdResult<(), DuckDbError> {
-    insert_entries_thread_impl(db, rx, start_id, true)
-}
-
-fn insert_entries_thread_impl(
-    db: DatabaseConnection,
-    rx: mpsc::Receiver<InsertionTask>,
-    start_id: i64,
-    use_upsert: bool,
-) -> StdResult<(), DuckDbError> {
-    let mut next_id = start_id;
-    let mut current_file_entries: Vec<ParsedLogEntry> = Vec::new();
-
-    while let Ok(job) = rx.recv() {
-        match job {
-            InsertionTask::EntriesChunk {
-                node_name,
-                mut entries,
-            } => {
-                for (i, entry) in entries.iter_mut().enumerate() {
-                    entry.explicit_id = Some(next_id + i as i64);
-                }
-                next_id += entries.len() as i64;
+/// Ingests what was appended to every log file imported into the database earlier
+fn sync_logs(args: &ArgMatches) -> Result<()> {
+    let start_time = Instant::now();
 
-                NodeLogEntry::insert_parsed_entries(&db, &entries, &node_name)?;
+    let db_path: PathBuf = args
+        .get_one::<String>("db_file_path")
+        .expect("db_file_path is a required argument")
+        .into();
 
-                current_file_entries.extend(entries);
-            }
-            InsertionTask::FileCompletionMarker {
-                node_name,
-                file_path,
-                total_lines,
-            } => {
-                let file_path_str = file_path.to_string_lossy().to_string();
-                let file_metadata = extract_file_metadata(
-                    &current_file_entries,
-                    file_path_str,
-                    &node_name,
-                    total_lines as i64,
-                );
-                if use_upsert {
-                    FileMetadata::upsert_metadata(&db, file_metadata)?;
-                } else {
-                    FileMetadata::insert_metadata(&db, file_metadata)?;
-                }
-                current_file_entries.clear();
-            }
+    validate_existing_database_path(&db_path)?;
+    let rules = load_rules(args)?;
+
+    let db = open_database(&db_path)?;
+    let count_before = NodeLogEntry::count_all(&db)?;
+
+    let mut log_files = Vec::new();
+    for state in FileIngestionState::find_all(&db)? {
+        let path = PathBuf::from(&state.file_path);
+        if path.is_file() {
+            log_files.push(LogFile {
+                path,
+                node_name: state.node,
+            });
+        } else {
+            log::warn!("Skipping {}: the file no longer exists", path.display());
         }
     }
+    ingest_into_existing_database(&db, &log_files, rules.as_ref(), args)?;
+
+    let count_after = NodeLogEntry::count_all(&db)?;
+    let entries_added = count_after - count_before;
+    let elapsed = start_time.elapsed();
+    let elapsed_secs = elapsed.as_secs_f64();
+
+    log::info!(
+        "Synced {} new log entries from {} files (total: {}) in {:.2}s",
+        entries_added,
+        log_files.len(),
+        count_after,
+        elapsed_secs
+    );
+
     Ok(())
 }
 
-fn extract_node_name(log_path: &Path) -> Result<String> {
-    let file_name = log_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| {
-            CommandRunError::Library(LibError::Io(IoError::new(
-                ErrorKind::InvalidInput,
-                format!("Invalid log file name: {}", log_path.display()),
-            )))
-        })?;
+/// Keeps ingesting new log entries from a directory until interrupted
+async fn watch_logs(args: &ArgMatches) -> Result<()> {
+    let dir_path: PathBuf = args
+        .get_one::<String>("input_log_dir_path")
+        .expect("input_log_dir_path is a required argument")
+        .into();
+    let db_path: PathBuf = args
+        .get_one::<String>("db_file_path")
+        .expect("db_file_path is a required argument")
+        .into();
+
+    validate_watched_directory(&dir_path)?;
+    let options = watch_options(args)?;
+    let db = open_or_create_database(&db_path)?;
+    post_insertion_operations(&db)?;
+
+    watch_log_directory(db.clone(), dir_path, options, shutdown_signal()).await?;
 
-    let node_name = strip_compression_suffix(file_name).to_string();
+    log::info!(
+        "Stopped watching, the database contains {} log entries",
+        NodeLogEntry::count_all(&db)?
+    );
+
+    Ok(())
+}
+
+pub fn watch_options(args: &ArgMatches) -> Result<WatchOptions> {
+    let poll_interval = args
+        .get_one::<u64>("poll_interval")
+        .copied()
+        .expect("poll_interval has a default value");
+    Ok(WatchOptions {
+        poll_interval: Duration::from_millis(poll_interval),
+        rules: load_rules(args)?,
+        parallelism: parallelism(args),
+    })
+}
 
-    Ok(node_name)
+/// Ingests log files into an existing database. Files that were imported earlier
+/// are only read from where their last ingestion stopped.
+///
+/// New entries are appended in timestamp order, stored entries keep their ids.
+fn ingest_into_existing_database(
+    db: &DatabaseConnection,
+    log_files: &[LogFile],
+    rules: Option<&RuleSet>,
+    args: &ArgMatches,
+) -> Result<()> {
+    let start_id = NodeLogEntry::max_entry_id(db)? + 1;
+    ingest_log_files(
+        db,
+        log_files,
+        &IngestionOptions {
+            rules,
+            parallelism: parallelism(args),
+            start_id,
+            incremental: true,
+            defer_incomplete_lines: false,
+            silent: args.get_flag("silent"),
+        },
+    )?;
+    NodeLogEntry::renumber_appended_in_timestamp_order(db, start_id)?;
+    Ok(())
 }
 
 fn overview(args: &ArgMatches) -> Result<()> {
@@ -812,6 +962,7 @@ fn overview(args: &ArgMatches) -> Result<()> {
 
     let without_colors = args.get_flag("without_colors");
     output::display_file_metadata(metadata_entries, without_colors)?;
+    output::display_node_artifacts(&NodeArtifacts::find_all(&db)?);
 
     Ok(())
 }
@@ -824,11 +975,14 @@ fn query_logs(args: &ArgMatches) -> Result<()> {
 
     validate_existing_database_path(&db_path)?;
 
+    let db = open_database(&db_path)?;
+    let reference = time_reference(args, &db)?;
+
     let mut ctx = QueryContext::default();
 
     if let Some(since) = args
         .get_one::<String>("since_time")
-        .map(|s| parse_datetime_flexible(s))
+        .map(|s| parse_datetime_relative_to(s, &reference))
         .transpose()?
     {
         ctx = ctx.since(since);
@@ -836,7 +990,7 @@ fn query_logs(args: &ArgMatches) -> Result<()> {
 
     if let Some(to) = args
         .get_one::<String>("to_time")
-        .map(|s| parse_datetime_flexible(s))
+        .map(|s| parse_datetime_relative_to(s, &reference))
         .transpose()?
     {
         ctx = ctx.to(to);
@@ -846,6 +1000,13 @@ fn query_logs(args: &ArgMatches) -> Result<()> {
         ctx = ctx.severity(sev);
     }
 
+    if let Some(sev) = args.get_one::<String>("min_severity") {
+        let severity = sev.parse::<Severity>().map_err(|e| {
+            CommandRunError::Library(LibError::Io(IoError::new(ErrorKind::InvalidInput, e)))
+        })?;
+        ctx = ctx.min_severity(severity);
+    }
+
     if let Some(pid) = args.get_one::<String>("erlang_pid") {
         ctx = ctx.erlang_pid(pid);
     }
@@ -873,6 +1034,24 @@ fn query_logs(args: &ArgMatches) -> Result<()> {
         ctx = ctx.matching_all_labels(true);
     }
 
+    if let Some(text) = args.get_one::<String>("search") {
+        let search = SearchQuery::parse(text);
+        if search.is_empty() {
+            return Err(CommandRunError::Library(LibError::Io(IoError::new(
+                ErrorKind::InvalidInput,
+                format!("Search '{}' has no words to look for", text),
+            ))));
+        }
+        ctx = ctx.search(search);
+    }
+
+    if let Some(sort) = args.get_one::<String>("sort") {
+        let keys = SortKey::parse_list(sort).map_err(|e| {
+            CommandRunError::Library(LibError::Io(IoError::new(ErrorKind::InvalidInput, e)))
+        })?;
+        ctx = ctx.order_by(keys);
+    }
+
     if let Some(l) = args.get_one::<usize>("limit").copied() {
         ctx = ctx.limit(l as u64);
     }
@@ -889,7 +1068,6 @@ fn query_logs(args: &ArgMatches) -> Result<()> {
         ctx = ctx.add_label("unlabelled");
     }
 
-    let db = open_database(&db_path)?;
     let entries = NodeLogEntry::query(&db, &ctx)?;
     log::info!("Found {} matching entries", entries.len());
 
@@ -903,11 +1081,38 @@ fn parse_datetime_flexible(s: &str) -> Result<DateTime<Utc>> {
     rabbitmq_lqt_lib::datetime::parse_datetime_flexible(s).map_err(CommandRunError::DateTimeParse)
 }
 
-fn build_entry_filter(args: &ArgMatches) -> Result<EntryFilter> {
-    let mut filter = EntryFilter::default();
+fn parse_datetime_relative_to(s: &str, reference: &TimeReference) -> Result<DateTime<Utc>> {
+    rabbitmq_lqt_lib::datetime::parse_datetime_relative_to(s, reference.reference_time())
+        .map_err(CommandRunError::DateTimeParse)
+}
 
-    if let Some(sev) = args.get_one::<String>("severity") {
-        filter = filter.severity(sev);
+/// Builds the reference relative times are measured from, using
+/// `--reference-time` if provided and `--time-anchor` otherwise.
+fn time_reference(args: &ArgMatches, db: &DatabaseConnection) -> Result<TimeReference> {
+    let anchor = match args.get_one::<String>("reference_time") {
+        Some(s) => TimeAnchor::At(parse_datetime_flexible(s)?),
+        None => args
+            .get_one::<String>("time_anchor")
+            .map(|s| s.parse::<TimeAnchor>())
+            .transpose()
+            .map_err(CommandRunError::DateTimeParse)?
+            .unwrap_or(TimeAnchor::LogEnd),
+    };
+    Ok(TimeReference::from_database(db, anchor)?)
+}
+
+fn build_entry_filter(args: &ArgMatches) -> Result<EntryFilter> {
+    let mut filter = EntryFilter::default();
+
+    // The entry filter matches the given severity level and more severe ones,
+    // so when both options are given, the more severe level applies
+    if let Some(sev) = ["severity", "min_severity"]
+        .into_iter()
+        .filter_map(|id| args.get_one::<String>(id))
+        .filter_map(|s| s.parse::<Severity>().ok())
+        .max()
+    {
+        filter = filter.severity(sev.as_str());
     }
 
     if let Some(sub) = args.get_one::<String>("subsystem") {
@@ -976,11 +1181,54 @@ fn ql_query(args: &ArgMatches) -> Result<()> {
         .get_one::<String>("query")
         .expect("query is a required argument");
 
-    let ctx = to_query_context(query_str)?;
+    let db = open_database(&db_path)?;
+    let reference = time_reference(args, &db)?;
+    let ctx = to_query_context_with_reference(query_str, &reference)?;
+    let without_colors = args.get_flag("without_colors");
+
+    if ctx.has_aggregation() {
+        let result = NodeLogEntry::aggregate(&db, &ctx)?;
+        log::info!("Found {} matching rows", result.len());
+        output::display_tabular_result(result, without_colors)?;
+    } else {
+        let entries = NodeLogEntry::query(&db, &ctx)?;
+        log::info!("Found {} matching entries", entries.len());
+        output::display_log_entries(entries, without_colors)?;
+    }
+
+    Ok(())
+}
+
+fn show_context(args: &ArgMatches) -> Result<()> {
+    let db_path: PathBuf = args
+        .get_one::<String>("input_db_file_path")
+        .expect("input_db_file_path is a required argument")
+        .into();
+
+    validate_existing_database_path(&db_path)?;
+
+    let id = *args
+        .get_one::<i64>("id")
+        .expect("id is a required argument");
+    let before = *args.get_one::<u64>("before").expect("before has a default");
+    let after = *args.get_one::<u64>("after").expect("after has a default");
+    let scope = if args.get_flag("same_pid") {
+        ContextScope::Process
+    } else if args.get_flag("same_node") {
+        ContextScope::Node
+    } else {
+        ContextScope::All
+    };
+    let window = ContextWindow::new(before, after).scoped(scope);
 
     let db = open_database(&db_path)?;
-    let entries = NodeLogEntry::query(&db, &ctx)?;
-    log::info!("Found {} matching entries", entries.len());
+    let entries = NodeLogEntry::context(&db, id, &window)?.ok_or_else(|| {
+        CommandRunError::Library(LibError::Io(IoError::new(
+            ErrorKind::NotFound,
+            format!("No log entry with ID {}", id),
+        )))
+    })?;
+    log::info!("Found {} entries around entry {}", entries.len(), id);
 
     let without_colors = args.get_flag("without_colors");
     output::display_log_entries(entries, without_colors)?;
@@ -988,17 +1236,122 @@ fn ql_query(args: &ArgMatches) -> Result<()> {
     Ok(())
 }
 
+fn list_entities(args: &ArgMatches) -> Result<()> {
+    let db_path: PathBuf = args
+        .get_one::<String>("input_db_file_path")
+        .expect("input_db_file_path is a required argument")
+        .into();
+
+    validate_existing_database_path(&db_path)?;
+
+    let kind = args
+        .get_one::<String>("kind")
+        .map(|s| EntityKind::from_str(s).expect("kind is validated by clap"));
+    let limit = *args.get_one::<u64>("limit").expect("limit has a default");
+
+    let db = open_database(&db_path)?;
+    let mentions = NodeLogEntryEntities::most_mentioned(&db, kind, limit)?;
+    log::info!("Found {} entities", mentions.len());
+
+    let without_colors = args.get_flag("without_colors");
+    output::display_entity_mentions(mentions, without_colors)?;
+
+    Ok(())
+}
+
+fn list_patterns(args: &ArgMatches) -> Result<()> {
+    let db_path: PathBuf = args
+        .get_one::<String>("input_db_file_path")
+        .expect("input_db_file_path is a required argument")
+        .into();
+
+    validate_existing_database_path(&db_path)?;
+
+    let node = args.get_one::<String>("node").map(String::as_str);
+    let limit = *args.get_one::<u64>("limit").expect("limit has a default");
+
+    let db = open_database(&db_path)?;
+    let patterns = MessagePatterns::most_frequent(&db, node, limit)?;
+    log::info!("Found {} patterns", patterns.len());
+
+    let without_colors = args.get_flag("without_colors");
+    output::display_pattern_frequencies(patterns, without_colors)?;
+
+    Ok(())
+}
+
+fn show_timeline(args: &ArgMatches) -> Result<()> {
+    let db_path: PathBuf = args
+        .get_one::<String>("input_db_file_path")
+        .expect("input_db_file_path is a required argument")
+        .into();
+
+    validate_existing_database_path(&db_path)?;
+
+    let db = open_database(&db_path)?;
+    let reference = time_reference(args, &db)?;
+
+    let mut ctx = QueryContext::default();
+
+    if let Some(since) = args
+        .get_one::<String>("since_time")
+        .map(|s| parse_datetime_relative_to(s, &reference))
+        .transpose()?
+    {
+        ctx = ctx.since(since);
+    }
+
+    if let Some(to) = args
+        .get_one::<String>("to_time")
+        .map(|s| parse_datetime_relative_to(s, &reference))
+        .transpose()?
+    {
+        ctx = ctx.to(to);
+    }
+
+    if let Some(n) = args.get_one::<String>("node") {
+        ctx = ctx.node(n);
+    }
+
+    if let Some(l) = args.get_one::<usize>("limit").copied() {
+        ctx = ctx.limit(l as u64);
+    }
+
+    let burst_window = *args
+        .get_one::<u64>("burst_window")
+        .expect("burst_window has a default");
+    let options = TimelineOptions::default()
+        .burst_window(TimeDelta::seconds(burst_window as i64))
+        .transitions_only(args.get_flag("transitions_only"));
+
+    let timeline = Timeline::query(&db, &ctx, &options)?;
+    log::info!(
+        "Timeline has {} events across {} nodes",
+        timeline.events.len(),
+        timeline.nodes.len()
+    );
+
+    let without_colors = args.get_flag("without_colors");
+    output::display_timeline(&timeline, without_colors)?;
+
+    Ok(())
+}
+
 fn obfuscate_log(args: &ArgMatches) -> Result<()> {
+    if let Some(bundle_path) = args.get_one::<String>("output_bundle_path") {
+        return obfuscate_log_bundle(args, Path::new(bundle_path));
+    }
+
     let start_time = Instant::now();
 
     let input_path: PathBuf = args
         .get_one::<String>("input_log_file_path")
-        .expect("input_log_file_path is a required argument")
+        .expect("input_log_file_path is required without --output-bundle-path")
         .into();
 
     let output_path: PathBuf = args
         .get_one::<String>("output_log_file_path")
-        .expect("output_log_file_path is a required argument")
+        .expect("output_log_file_path is required without --output-bundle-path")
         .into();
 
     if !input_path.exists() {
@@ -1012,6 +1365,17 @@ fn obfuscate_log(args: &ArgMatches) -> Result<()> {
         ))));
     }
 
+    if ArchiveType::from_path(&input_path).is_tar_archive() {
+        return Err(CommandRunError::Library(LibError::Io(IoError::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Input is a tar archive: {}\n\n\
+                Use --output-bundle-path to obfuscate all log files in it into a bundle.",
+                input_path.display()
+            ),
+        ))));
+    }
+
     ensure_parent_directory_exists(&output_path)?;
 
     let silent = args.get_flag("silent");
@@ -1024,31 +1388,189 @@ fn obfuscate_log(args: &ArgMatches) -> Result<()> {
         None
     };
 
-    let input_file = File::open(&input_path).map_err(|e| {
+    let reader = open_log_reader(&input_path)?;
+
+    let output_file = File::create(&output_path).map_err(|e| {
         CommandRunError::Library(LibError::Io(IoError::new(
             e.kind(),
             format!(
-                "Failed to open input log file '{}': {}",
-                input_path.display(),
+                "Failed to create output file '{}': {}",
+                output_path.display(),
                 e
             ),
         )))
     })?;
-    let reader = BufReader::new(input_file);
+    let mut writer = BufWriter::new(output_file);
 
-    let output_file = File::create(&output_path).map_err(|e| {
+    let mut obfuscator = configured_obfuscator(args)?;
+    add_node_names(&mut obfuscator, std::slice::from_ref(&input_path));
+    let line_count = obfuscate_lines(&mut obfuscator, reader, &mut writer)?;
+
+    writer.flush().map_err(|e| {
+        CommandRunError::Library(LibError::Io(IoError::new(
+            e.kind(),
+            format!("Failed to flush output file: {}", e),
+        )))
+    })?;
+
+    if let Some(ref mut s) = spinner {
+        s.finish_and_clear();
+    }
+
+    export_obfuscation_mapping(args, &obfuscator)?;
+
+    let stats = obfuscator.stats();
+    let elapsed = start_time.elapsed();
+
+    if !silent {
+        let table = build_obfuscation_stats_table(
+            &input_path,
+            &output_path,
+            "Lines processed",
+            line_count,
+            elapsed,
+            stats,
+        );
+        println!("{}", table);
+    }
+
+    Ok(())
+}
+
+/// Obfuscates every log file of a directory or a tar archive into a `.tar.gz` bundle.
+/// A single obfuscator is used for all files, so an alias stands for the same
+/// hostname, queue or user in every file of the bundle.
+fn obfuscate_log_bundle(args: &ArgMatches, bundle_path: &Path) -> Result<()> {
+    let start_time = Instant::now();
+
+    let (input_path, log_paths) = match args.get_one::<String>("input_log_dir_path") {
+        Some(dir_path) => (
+            PathBuf::from(dir_path),
+            collect_log_files_from_directory(dir_path)?,
+        ),
+        None => {
+            let input_path: PathBuf = args
+                .get_one::<String>("input_log_file_path")
+                .expect("either input_log_file_path or input_log_dir_path is required")
+                .into();
+            validate_file_paths(std::slice::from_ref(&input_path))?;
+            (input_path.clone(), vec![input_path])
+        }
+    };
+
+    ensure_parent_directory_exists(bundle_path)?;
+
+    let silent = args.get_flag("silent");
+
+    let mut spinner = if !silent {
+        let mut s = SpinnerReporter::new().with_tick_chars(BRAILLE_TICK_CHARS);
+        s.start(&format!("Obfuscating {}", input_path.display()));
+        Some(s)
+    } else {
+        None
+    };
+
+    // Keep extracted archives alive until processing is complete
+    let (log_paths, extracted_archives) = expand_tar_archives(log_paths)?;
+
+    let bundle_file = File::create(bundle_path).map_err(|e| {
         CommandRunError::Library(LibError::Io(IoError::new(
             e.kind(),
             format!(
-                "Failed to create output file '{}': {}",
-                output_path.display(),
+                "Failed to create output bundle '{}': {}",
+                bundle_path.display(),
                 e
             ),
         )))
     })?;
-    let mut writer = BufWriter::new(output_file);
+    let encoder = GzEncoder::new(BufWriter::new(bundle_file), Compression::default());
+    let mut bundle = TarBuilder::new(encoder);
+
+    let mut obfuscator = configured_obfuscator(args)?;
+    add_node_names(&mut obfuscator, &log_paths);
+    let mut bundled_file_names = HashSet::new();
+    let mut line_count = 0usize;
+
+    for log_path in &log_paths {
+        let reader = open_log_reader(log_path)?;
+
+        // Obfuscated contents are staged in a temporary file because
+        // tar entries must be preceded by their size
+        let mut staged = tempfile::tempfile()?;
+        let mut writer = BufWriter::new(&mut staged);
+        line_count += obfuscate_lines(&mut obfuscator, reader, &mut writer)?;
+        writer.flush()?;
+        drop(writer);
+        staged.seek(SeekFrom::Start(0))?;
+
+        let file_name = bundled_file_name(&mut obfuscator, log_path, &mut bundled_file_names);
+        let mut header = TarHeader::new_gnu();
+        header.set_size(staged.metadata()?.len());
+        header.set_mode(0o644);
+        header.set_cksum();
+        bundle
+            .append_data(&mut header, &file_name, &mut staged)
+            .map_err(|e| {
+                CommandRunError::Library(LibError::Io(IoError::new(
+                    e.kind(),
+                    format!("Failed to add '{}' to the output bundle: {}", file_name, e),
+                )))
+            })?;
+    }
+
+    let encoder = bundle.into_inner()?;
+    encoder.finish()?.flush().map_err(|e| {
+        CommandRunError::Library(LibError::Io(IoError::new(
+            e.kind(),
+            format!("Failed to flush output bundle: {}", e),
+        )))
+    })?;
+    drop(extracted_archives);
 
-    let mut obfuscator = LogObfuscator::new();
+    if let Some(ref mut s) = spinner {
+        s.finish_and_clear();
+    }
+
+    export_obfuscation_mapping(args, &obfuscator)?;
+
+    let stats = obfuscator.stats();
+    let elapsed = start_time.elapsed();
+
+    log::info!(
+        "Obfuscated {} log files into {}",
+        log_paths.len(),
+        bundle_path.display()
+    );
+
+    if !silent {
+        let table = build_obfuscation_stats_table(
+            &input_path,
+            bundle_path,
+            "Lines processed",
+            line_count,
+            elapsed,
+            stats,
+        );
+        println!("{}", table);
+    }
+
+    Ok(())
+}
+
+/// Node names with fully qualified hostnames look like email addresses,
+/// so the obfuscator is told which nodes the log files belong to
+fn add_node_names(obfuscator: &mut LogObfuscator, log_paths: &[PathBuf]) {
+    for node_name in log_paths.iter().filter_map(|p| extract_node_name(p).ok()) {
+        obfuscator.add_node_name(&node_name);
+    }
+}
+
+/// Obfuscates every line read from `reader` into `writer`, returning the number of lines.
+fn obfuscate_lines(
+    obfuscator: &mut LogObfuscator,
+    reader: impl BufRead,
+    writer: &mut impl Write,
+) -> Result<usize> {
     let mut line_count = 0usize;
 
     for line_result in reader.lines() {
@@ -1070,29 +1592,208 @@ fn obfuscate_log(args: &ArgMatches) -> Result<()> {
         line_count += 1;
     }
 
-    writer.flush().map_err(|e| {
-        CommandRunError::Library(LibError::Io(IoError::new(
-            e.kind(),
-            format!("Failed to flush output file: {}", e),
-        )))
-    })?;
+    Ok(line_count)
+}
+
+/// The name of an obfuscated log file in a bundle. File names usually contain
+/// the node name, so they are obfuscated with the same mapping as the contents.
+/// Compression suffixes are dropped because bundled files are stored uncompressed.
+fn bundled_file_name(
+    obfuscator: &mut LogObfuscator,
+    log_path: &Path,
+    bundled_file_names: &mut HashSet<String>,
+) -> String {
+    let node_name = extract_node_name(log_path).unwrap_or_else(|_| "rabbit".to_string());
+    let stem = obfuscator.obfuscate_line(&node_name);
+
+    let mut candidate = format!("{}.log", stem);
+    let mut n = 1;
+    while !bundled_file_names.insert(candidate.clone()) {
+        n += 1;
+        candidate = format!("{}-{}.log", stem, n);
+    }
+    candidate
+}
+
+fn obfuscate_db(args: &ArgMatches) -> Result<()> {
+    let start_time = Instant::now();
+
+    let input_path: PathBuf = args
+        .get_one::<String>("input_db_file_path")
+        .expect("input_db_file_path is a required argument")
+        .into();
+
+    let output_path: PathBuf = args
+        .get_one::<String>("output_db_file_path")
+        .expect("output_db_file_path is a required argument")
+        .into();
+
+    validate_existing_database_path(&input_path)?;
+    if output_path.exists() && output_path.canonicalize()? == input_path.canonicalize()? {
+        return Err(CommandRunError::Library(LibError::Io(IoError::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "The output database must differ from the input one: {}",
+                output_path.display()
+            ),
+        ))));
+    }
+    validate_database_path(&output_path)?;
+
+    let silent = args.get_flag("silent");
+
+    let mut spinner = if !silent {
+        let mut s = SpinnerReporter::new().with_tick_chars(BRAILLE_TICK_CHARS);
+        s.start(&format!("Obfuscating {}", input_path.display()));
+        Some(s)
+    } else {
+        None
+    };
+
+    let source = open_database(&input_path)?;
+    let target = create_database_for_bulk_import(&output_path)?;
+
+    let mut obfuscator = configured_obfuscator(args)?;
+    let entry_count = obfuscate_database(&source, &target, &mut obfuscator)?;
+    finalize_bulk_import(&target)?;
 
     if let Some(ref mut s) = spinner {
         s.finish_and_clear();
     }
 
+    export_obfuscation_mapping(args, &obfuscator)?;
+
     let stats = obfuscator.stats();
     let elapsed = start_time.elapsed();
 
+    log::info!(
+        "Obfuscated {} log entries in {:.2}s",
+        entry_count,
+        elapsed.as_secs_f64()
+    );
+
     if !silent {
-        let table =
-            build_obfuscation_stats_table(&input_path, &output_path, line_count, elapsed, stats);
+        let table = build_obfuscation_stats_table(
+            &input_path,
+            &output_path,
+            "Entries processed",
+            entry_count as usize,
+            elapsed,
+            stats,
+        );
         println!("{}", table);
     }
 
     Ok(())
 }
 
+/// Creates an obfuscator with the policy of `--policy-file` that reuses
+/// the aliases of `--import-mapping-file`, if provided
+fn configured_obfuscator(args: &ArgMatches) -> Result<LogObfuscator> {
+    let policy = match args.get_one::<String>("policy_file") {
+        Some(path) => ObfuscationPolicy::from_file(Path::new(path))?,
+        None => ObfuscationPolicy::default(),
+    };
+    let mut obfuscator = LogObfuscator::with_policy(policy);
+    if let Some(mapping_path) = args.get_one::<String>("import_mapping_file") {
+        let mapping = read_obfuscation_mapping(Path::new(mapping_path), args)?;
+        log::info!(
+            "Imported {} obfuscation aliases from {}",
+            mapping.len(),
+            mapping_path
+        );
+        obfuscator.import_mapping(mapping);
+    }
+    Ok(obfuscator)
+}
+
+/// Saves the aliases assigned by `obfuscator` to `--export-mapping-file`, if provided
+fn export_obfuscation_mapping(args: &ArgMatches, obfuscator: &LogObfuscator) -> Result<()> {
+    let Some(mapping_path) = args.get_one::<String>("export_mapping_file") else {
+        return Ok(());
+    };
+    let mapping_path = Path::new(mapping_path);
+    ensure_parent_directory_exists(mapping_path)?;
+
+    let passphrase = args.get_one::<String>("mapping_passphrase");
+    if passphrase.is_none() {
+        log::warn!(
+            "The obfuscation mapping at {} is not encrypted and contains the original values, use --mapping-passphrase to encrypt it",
+            mapping_path.display()
+        );
+    }
+    obfuscator
+        .export_mapping()
+        .write_to_file(mapping_path, passphrase.map(String::as_str))?;
+    Ok(())
+}
+
+fn read_obfuscation_mapping(mapping_path: &Path, args: &ArgMatches) -> Result<ObfuscationMapping> {
+    if !mapping_path.is_file() {
+        return Err(CommandRunError::Library(LibError::Io(IoError::new(
+            ErrorKind::NotFound,
+            format!(
+                "Obfuscation mapping file not found: {}\n\n{}",
+                mapping_path.display(),
+                ERR_MSG_FILE_NOT_FOUND_HELP
+            ),
+        ))));
+    }
+
+    let passphrase = args.get_one::<String>("mapping_passphrase");
+    Ok(ObfuscationMapping::read_from_file(
+        mapping_path,
+        passphrase.map(String::as_str),
+    )?)
+}
+
+fn deobfuscate(args: &ArgMatches) -> Result<()> {
+    let mapping_path: PathBuf = args
+        .get_one::<String>("mapping_file")
+        .expect("mapping_file is a required argument")
+        .into();
+    let deobfuscator = Deobfuscator::new(&read_obfuscation_mapping(&mapping_path, args)?);
+
+    let reader: Box<dyn BufRead> = match args.get_one::<String>("input_file_path") {
+        Some(input_path) => {
+            let file = File::open(input_path).map_err(|e| {
+                CommandRunError::Library(LibError::Io(IoError::new(
+                    e.kind(),
+                    format!("Failed to open input file '{}': {}", input_path, e),
+                )))
+            })?;
+            Box::new(BufReader::new(file))
+        }
+        None => Box::new(io::stdin().lock()),
+    };
+
+    let mut writer: Box<dyn Write> = match args.get_one::<String>("output_file_path") {
+        Some(output_path) => {
+            let output_path = Path::new(output_path);
+            ensure_parent_directory_exists(output_path)?;
+            let file = File::create(output_path).map_err(|e| {
+                CommandRunError::Library(LibError::Io(IoError::new(
+                    e.kind(),
+                    format!(
+                        "Failed to create output file '{}': {}",
+                        output_path.display(),
+                        e
+                    ),
+                )))
+            })?;
+            Box::new(BufWriter::new(file))
+        }
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    for line in reader.lines() {
+        writeln!(writer, "{}", deobfuscator.deobfuscate_line(&line?))?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
 #[derive(Tabled)]
 struct ObfuscationStatsRow<'a> {
     #[tabled(rename = "Metric")]
@@ -1104,22 +1805,23 @@ struct ObfuscationStatsRow<'a> {
 fn build_obfuscation_stats_table(
     input_path: &Path,
     output_path: &Path,
-    line_count: usize,
+    processed_metric: &str,
+    processed_count: usize,
     elapsed: Duration,
     stats: &ObfuscationStats,
 ) -> Table {
     let data = vec![
         ObfuscationStatsRow {
-            metric: "Input file",
+            metric: "Input",
             value: input_path.display().to_string(),
         },
         ObfuscationStatsRow {
-            metric: "Output file",
+            metric: "Output",
             value: output_path.display().to_string(),
         },
         ObfuscationStatsRow {
-            metric: "Lines processed",
-            value: line_count.to_string(),
+            metric: processed_metric,
+            value: processed_count.to_string(),
         },
         ObfuscationStatsRow {
             metric: "Time elapsed",
@@ -1165,9 +1867,163 @@ fn build_obfuscation_stats_table(
             metric: "Unique IPv6 addresses obfuscated",
             value: stats.ipv6_addresses_obfuscated.to_string(),
         },
+        ObfuscationStatsRow {
+            metric: "Unique email addresses obfuscated",
+            value: stats.email_addresses_obfuscated.to_string(),
+        },
+        ObfuscationStatsRow {
+            metric: "Unique connection names obfuscated",
+            value: stats.connection_names_obfuscated.to_string(),
+        },
+        ObfuscationStatsRow {
+            metric: "Unique Windows paths obfuscated",
+            value: stats.windows_paths_obfuscated.to_string(),
+        },
+        ObfuscationStatsRow {
+            metric: "Unique MAC addresses obfuscated",
+            value: stats.mac_addresses_obfuscated.to_string(),
+        },
+        ObfuscationStatsRow {
+            metric: "Unique custom pattern values obfuscated",
+            value: stats.custom_values_obfuscated.to_string(),
+        },
+        ObfuscationStatsRow {
+            metric: "URI credentials redacted",
+            value: stats.uri_credentials_redacted.to_string(),
+        },
+        ObfuscationStatsRow {
+            metric: "Tokens redacted",
+            value: stats.tokens_redacted.to_string(),
+        },
     ];
 
     let mut table = Table::new(data);
     table.with(Style::rounded());
     table
 }
+
+fn show_diff(args: &ArgMatches) -> Result<()> {
+    let baseline_path: PathBuf = args
+        .get_one::<String>("baseline_db_file_path")
+        .expect("baseline_db_file_path is a required argument")
+        .into();
+    validate_existing_database_path(&baseline_path)?;
+    let baseline_db = open_database(&baseline_path)?;
+
+    let comparison_db = match args.get_one::<String>("comparison_db_file_path") {
+        Some(path) => {
+            let comparison_path = PathBuf::from(path);
+            validate_existing_database_path(&comparison_path)?;
+            Some(open_database(&comparison_path)?)
+        }
+        None => None,
+    };
+    let comparison_db = comparison_db.as_ref().unwrap_or(&baseline_db);
+
+    let baseline_ctx = diff_side_context(
+        args,
+        &baseline_db,
+        "baseline_since_time",
+        "baseline_to_time",
+    )?;
+    let comparison_ctx = diff_side_context(
+        args,
+        comparison_db,
+        "comparison_since_time",
+        "comparison_to_time",
+    )?;
+
+    let diff = LogDiff::compute(
+        &DiffSide::new(&baseline_db, baseline_ctx),
+        &DiffSide::new(comparison_db, comparison_ctx),
+    )?;
+    log::info!(
+        "Found {} new patterns, {} vanished patterns, {} count changes and {} metadata changes",
+        diff.new_patterns.len(),
+        diff.vanished_patterns.len(),
+        diff.count_deltas.len(),
+        diff.fact_changes.len()
+    );
+
+    let limit = *args.get_one::<usize>("limit").expect("limit has a default");
+    let without_colors = args.get_flag("without_colors");
+    output::display_log_diff(&diff, limit, without_colors)?;
+
+    Ok(())
+}
+
+/// Builds the time window of one side of a diff. Relative times are
+/// measured using the database of that side.
+fn diff_side_context(
+    args: &ArgMatches,
+    db: &DatabaseConnection,
+    since_arg: &str,
+    to_arg: &str,
+) -> Result<QueryContext> {
+    let reference = time_reference(args, db)?;
+    let mut ctx = QueryContext::default();
+
+    if let Some(since) = args
+        .get_one::<String>(since_arg)
+        .map(|s| parse_datetime_relative_to(s, &reference))
+        .transpose()?
+    {
+        ctx = ctx.since(since);
+    }
+
+    if let Some(to) = args
+        .get_one::<String>(to_arg)
+        .map(|s| parse_datetime_relative_to(s, &reference))
+        .transpose()?
+    {
+        ctx = ctx.to(to);
+    }
+
+    Ok(ctx)
+}
+
+fn show_report(args: &ArgMatches) -> Result<()> {
+    let db_path: PathBuf = args
+        .get_one::<String>("input_db_file_path")
+        .expect("input_db_file_path is a required argument")
+        .into();
+
+    validate_existing_database_path(&db_path)?;
+
+    let db = open_database(&db_path)?;
+    let reference = time_reference(args, &db)?;
+
+    let mut options = ReportOptions::default();
+
+    if let Some(since) = args
+        .get_one::<String>("since_time")
+        .map(|s| parse_datetime_relative_to(s, &reference))
+        .transpose()?
+    {
+        options = options.since(since);
+    }
+
+    if let Some(to) = args
+        .get_one::<String>("to_time")
+        .map(|s| parse_datetime_relative_to(s, &reference))
+        .transpose()?
+    {
+        options = options.to(to);
+    }
+
+    if let Some(n) = args.get_one::<String>("node") {
+        options = options.node(n);
+    }
+
+    let report = Report::run(&db, &options)?;
+    log::info!("Found {} findings", report.findings.len());
+
+    let without_colors = args.get_flag("without_colors");
+    match args.get_one::<String>("format").map(String::as_str) {
+        Some("markdown") => output::display_report_markdown(&report),
+        Some("json") => output::display_report_json(&report)?,
+        _ => output::display_report(&report, without_colors)?,
+    }
+
+    Ok(())
+}
@@ -14,6 +14,7 @@
 use bel7_cli::{ExitCode, ExitCodeProvider};
 use duckdb::Error as DuckDbError;
 use rabbitmq_lqt_lib::Error as LibError;
+use rabbitmq_lqt_obfuscation::Error as ObfuscationError;
 use rabbitmq_lqt_ql::errors::Error as QlError;
 use std::io::{Error as IoError, ErrorKind};
 use thiserror::Error;
@@ -28,7 +29,10 @@ pub enum CommandRunError {
     DateTimeParse(String),
 
     #[error("Query language error: {0}")]
-    QueryLanguage(#[from] QlError),
+    QueryLanguage(QlError),
+
+    #[error(transparent)]
+    Obfuscation(#[from] ObfuscationError),
 }
 
 impl ExitCodeProvider for CommandRunError {
@@ -43,11 +47,14 @@ impl ExitCodeProvider for CommandRunError {
                 LibError::Database(_) | LibError::ConnectionPool(_) => ExitCode::Software,
                 LibError::ParseEntry { .. }
                 | LibError::ParseTimestamp(_)
-                | LibError::ReadLine { .. } => ExitCode::DataErr,
+                | LibError::ReadLine { .. }
+                | LibError::InvalidRules(_) => ExitCode::DataErr,
                 _ => ExitCode::Software,
             },
             CommandRunError::DateTimeParse(_) => ExitCode::DataErr,
             CommandRunError::QueryLanguage(_) => ExitCode::DataErr,
+            CommandRunError::Obfuscation(ObfuscationError::Io(_)) => ExitCode::IoErr,
+            CommandRunError::Obfuscation(_) => ExitCode::DataErr,
         }
     }
 }
@@ -58,6 +65,15 @@ impl From<IoError> for CommandRunError {
     }
 }
 
+impl From<QlError> for CommandRunError {
+    fn from(err: QlError) -> Self {
+        match err {
+            QlError::Library(lib_err) => CommandRunError::Library(lib_err),
+            err => CommandRunError::QueryLanguage(err),
+        }
+    }
+}
+
 impl From<DuckDbError> for CommandRunError {
     fn from(err: DuckDbError) -> Self {
         CommandRunError::Library(LibError::Database(err))
@@ -0,0 +1,749 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The ingestion pipeline used by `logs parse` and `logs merge`.
+//!
+//! Log files are parsed and annotated concurrently on a bounded pool of worker threads.
+//! Every file has its own bounded queue of entry chunks. The calling thread consumes
+//! these queues in the order the files were given, so entry ids are assigned exactly
+//! as if the files were processed one after another, and inserts them through
+//! a single [`EntryAppender`].
+//!
+//! Where the ingestion of every file stopped is recorded in [`FileIngestionState`],
+//! so that `logs merge` and `logs sync` only read what was appended to a file since.
+//!
+//! [`EntryAppender`]: rabbitmq_lqt_lib::rel_db::EntryAppender
+//! [`FileIngestionState`]: rabbitmq_lqt_lib::rel_db::FileIngestionState
+use crate::archive::{
+    ArchiveType, LogReader, open_log_reader_at, strip_compression_suffix, strip_rotation_suffix,
+};
+use crate::core::Result;
+use crate::errors::CommandRunError;
+use crate::output;
+use bel7_cli::{BRAILLE_TICK_CHARS, SpinnerReporter};
+use chrono::{DateTime, Utc};
+use rabbitmq_lqt_lib::Error as LibError;
+use rabbitmq_lqt_lib::entry_metadata::annotate_entry_with_rules;
+use rabbitmq_lqt_lib::file_set_metadata::FileMetadataContext;
+use rabbitmq_lqt_lib::parser::{LogEntryStream, ParsedLogEntry};
+use rabbitmq_lqt_lib::rel_db::file_ingestion_state::Model as FileIngestionStateModel;
+use rabbitmq_lqt_lib::rel_db::node_log_entry::entry_fingerprint;
+use rabbitmq_lqt_lib::rel_db::{EntryAppender, FileIngestionState, FileMetadata};
+use rabbitmq_lqt_lib::{DatabaseConnection, NodeLogEntry, RuleSet};
+use rayon::prelude::*;
+use ring::digest::{SHA256, digest};
+use std::collections::HashSet;
+use std::fs::{File, Metadata};
+use std::io::{Error as IoError, ErrorKind, Read};
+use std::mem;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+use std::vec;
+
+const PIPELINE_CHUNK_SIZE: usize = 25_000;
+
+/// How many parsed and annotated chunks of a file can wait for insertion. Together with
+/// [`PIPELINE_CHUNK_SIZE`] and the number of workers, this bounds the memory used
+/// by `logs parse` and `logs merge` regardless of the size of the input files.
+const PIPELINE_QUEUE_CAPACITY: usize = 4;
+
+/// How many bytes at the start of a file are hashed to tell whether
+/// it is still the file that was ingested earlier
+const HEAD_LENGTH: u64 = 4096;
+
+pub struct IngestionOptions<'a> {
+    pub rules: Option<&'a RuleSet>,
+    /// How many files are parsed concurrently
+    pub parallelism: usize,
+    /// The id of the first inserted entry
+    pub start_id: i64,
+    /// Whether files that were imported earlier are only read from where their last
+    /// ingestion stopped, with their metadata updated instead of inserted,
+    /// as `logs merge` and `logs sync` do
+    pub incremental: bool,
+    /// Whether a last line without a line ending is left for a later ingestion,
+    /// as `logs watch` does with files that are still being written to
+    pub defer_incomplete_lines: bool,
+    pub silent: bool,
+}
+
+/// The number of CPU cores, used when `--parallelism` is not specified.
+pub fn default_parallelism() -> usize {
+    thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1)
+}
+
+enum InsertionTask {
+    EntriesChunk {
+        entries: Vec<ParsedLogEntry>,
+        bytes_read: u64,
+    },
+    FileCompletionMarker {
+        total_lines: usize,
+        tail_lines: usize,
+        bytes_read: u64,
+        parsing_time: Duration,
+    },
+}
+
+/// A log file to ingest and the node its entries are attributed to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFile {
+    pub path: PathBuf,
+    pub node_name: String,
+}
+
+impl LogFile {
+    /// Attributes the file to the node its name is derived from, see [`extract_node_name`]
+    pub fn from_path(path: PathBuf) -> Result<Self> {
+        let node_name = extract_node_name(&path)?;
+        Ok(Self { path, node_name })
+    }
+}
+
+/// What a file on disk looks like right before it is ingested
+struct FileSnapshot {
+    inode: Option<u64>,
+    size: u64,
+    head_length: u64,
+    head_hash: String,
+}
+
+impl FileSnapshot {
+    fn take(path: &Path) -> Result<Self> {
+        let metadata = path.metadata().map_err(|e| {
+            CommandRunError::Library(LibError::Io(IoError::new(
+                e.kind(),
+                format!("Failed to read metadata of '{}': {}", path.display(), e),
+            )))
+        })?;
+        let size = metadata.len();
+        let head_length = size.min(HEAD_LENGTH);
+        Ok(Self {
+            inode: inode(&metadata),
+            size,
+            head_length,
+            head_hash: head_hash(path, head_length)?,
+        })
+    }
+}
+
+#[cfg(unix)]
+fn inode(metadata: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn inode(_metadata: &Metadata) -> Option<u64> {
+    None
+}
+
+/// The SHA-256 of the first `length` bytes of a file, hex-encoded
+fn head_hash(path: &Path, length: u64) -> Result<String> {
+    let mut head = Vec::with_capacity(length as usize);
+    File::open(path)
+        .and_then(|file| file.take(length).read_to_end(&mut head))
+        .map_err(|e| {
+            CommandRunError::Library(LibError::Io(IoError::new(
+                e.kind(),
+                format!("Failed to read '{}': {}", path.display(), e),
+            )))
+        })?;
+    Ok(digest(&SHA256, &head)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// How a file is read, depending on what was ingested from it earlier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadPlan {
+    /// The file was not ingested before, or was by a version that did not record
+    /// where its ingestion stopped
+    Full,
+    /// Only the last entry of the file's last ingestion and what was appended
+    /// to the file since then is read
+    Resume { offset: u64, tail_lines: u64 },
+    /// The file was rotated, replaced or truncated since its last ingestion,
+    /// so it is read again from the start
+    Restart,
+    /// Nothing was appended to the file since its last ingestion
+    Unchanged,
+}
+
+impl ReadPlan {
+    fn start_offset(&self) -> u64 {
+        match self {
+            ReadPlan::Resume { offset, .. } => *offset,
+            _ => 0,
+        }
+    }
+}
+
+fn plan_read(db: &DatabaseConnection, path: &Path, snapshot: &FileSnapshot) -> Result<ReadPlan> {
+    let Some(state) = FileIngestionState::find(db, &path.to_string_lossy())? else {
+        return Ok(ReadPlan::Full);
+    };
+
+    if state.inode != snapshot.inode || head_hash(path, state.head_length)? != state.head_hash {
+        log::info!(
+            "{} was rotated or replaced since it was last imported, reading it from the start",
+            path.display()
+        );
+        return Ok(ReadPlan::Restart);
+    }
+
+    match ArchiveType::from_path(path) {
+        ArchiveType::PlainLog if snapshot.size < state.file_size => {
+            log::info!(
+                "{} was truncated since it was last imported, reading it from the start",
+                path.display()
+            );
+            Ok(ReadPlan::Restart)
+        }
+        ArchiveType::PlainLog if snapshot.size == state.file_size => Ok(ReadPlan::Unchanged),
+        ArchiveType::PlainLog => Ok(ReadPlan::Resume {
+            offset: state.ingested_bytes,
+            tail_lines: state.tail_lines,
+        }),
+        // Compressed files cannot be resumed, they are only expected to change when replaced
+        _ if snapshot.size == state.file_size => Ok(ReadPlan::Unchanged),
+        _ => {
+            log::info!(
+                "{} changed since it was last imported, reading it from the start",
+                path.display()
+            );
+            Ok(ReadPlan::Restart)
+        }
+    }
+}
+
+struct PlannedFile<'a> {
+    file: &'a LogFile,
+    snapshot: FileSnapshot,
+    plan: ReadPlan,
+}
+
+struct QueuedFile<'a> {
+    path: &'a Path,
+    start_offset: u64,
+    tx: mpsc::SyncSender<InsertionTask>,
+}
+
+struct InsertedFile<'a> {
+    path: &'a Path,
+    node_name: &'a str,
+    first_timestamp: Option<DateTime<Utc>>,
+    snapshot: FileSnapshot,
+    plan: ReadPlan,
+    rx: mpsc::Receiver<InsertionTask>,
+}
+
+/// Parses, annotates and stores the given log files.
+///
+/// Files are grouped by node and stored in the order of their first entries, so rotated files
+/// such as `rabbit@host1.log.2.gz`, `rabbit@host1.log.1` and `rabbit@host1.log` are stored
+/// chronologically whatever order they were given in. Entries that are already stored for
+/// the node, e.g. because two files overlap or one is a copy of another, are skipped.
+///
+/// Ids are assigned in the order entries are stored, starting with [`IngestionOptions::start_id`].
+/// Use [`NodeLogEntry::renumber_in_timestamp_order`] to make them follow timestamp order
+/// across all nodes, or [`NodeLogEntry::renumber_appended_in_timestamp_order`] to do that
+/// only for the entries stored by an incremental ingestion.
+///
+/// With [`IngestionOptions::incremental`], files that were ingested before are only read
+/// from where their last ingestion stopped, and skipped if nothing was appended to them.
+pub fn ingest_log_files(
+    db: &DatabaseConnection,
+    log_files: &[LogFile],
+    options: &IngestionOptions<'_>,
+) -> Result<()> {
+    let mut planned = Vec::with_capacity(log_files.len());
+    for file in log_files {
+        let snapshot = FileSnapshot::take(&file.path)?;
+        let plan = if options.incremental {
+            plan_read(db, &file.path, &snapshot)?
+        } else {
+            ReadPlan::Full
+        };
+        if plan == ReadPlan::Unchanged {
+            // `logs watch` finds most files unchanged every time it polls
+            let level = if options.silent {
+                log::Level::Debug
+            } else {
+                log::Level::Info
+            };
+            log::log!(
+                level,
+                "Skipping {}: nothing was appended to it since it was last imported",
+                file.path.display()
+            );
+            continue;
+        }
+        planned.push(PlannedFile {
+            file,
+            snapshot,
+            plan,
+        });
+    }
+
+    let first_timestamps = planned
+        .par_iter()
+        .map(|p| {
+            first_entry_timestamp(
+                &p.file.path,
+                p.plan.start_offset(),
+                options.defer_incomplete_lines,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut ordered: Vec<_> = planned.into_iter().zip(first_timestamps).collect();
+    ordered.sort_by(|(a, a_first), (b, b_first)| {
+        (&a.file.node_name, a_first, &a.file.path).cmp(&(&b.file.node_name, b_first, &b.file.path))
+    });
+
+    let workers = options.parallelism.clamp(1, ordered.len().max(1));
+    let mut queued = Vec::with_capacity(ordered.len());
+    let mut inserted = Vec::with_capacity(ordered.len());
+    for (planned_file, first_timestamp) in ordered {
+        let (tx, rx) = mpsc::sync_channel(PIPELINE_QUEUE_CAPACITY);
+        let file = planned_file.file;
+        queued.push(QueuedFile {
+            path: &file.path,
+            start_offset: planned_file.plan.start_offset(),
+            tx,
+        });
+        inserted.push(InsertedFile {
+            path: &file.path,
+            node_name: &file.node_name,
+            first_timestamp,
+            snapshot: planned_file.snapshot,
+            plan: planned_file.plan,
+            rx,
+        });
+    }
+    let queue = &Mutex::new(queued.into_iter());
+    let cancelled = &AtomicBool::new(false);
+    let rules = options.rules;
+    let defer_incomplete_lines = options.defer_incomplete_lines;
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(move || {
+                    parse_queued_files(queue, cancelled, rules, defer_incomplete_lines)
+                })
+            })
+            .collect();
+
+        let inserted_result = insert_files(db, inserted, options);
+        if inserted_result.is_err() {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+
+        // A parsing error is the reason the insertion of a file ended early,
+        // so it takes precedence over the error reported by the inserter
+        let mut parsing_result = Ok(());
+        for handle in handles {
+            let worker_result = handle.join().unwrap_or_else(|_| {
+                Err(CommandRunError::Library(LibError::Io(IoError::other(
+                    "A log parsing thread panicked",
+                ))))
+            });
+            if parsing_result.is_ok() {
+                parsing_result = worker_result;
+            }
+        }
+        parsing_result.and(inserted_result)
+    })
+}
+
+/// Opens a log file for parsing from `start_offset` on. Only plain logs can be
+/// still written to, so only their incomplete lines are deferred, and only their last
+/// entries are held back to be read again by the next incremental ingestion.
+fn open_log_stream(
+    log_path: &Path,
+    start_offset: u64,
+    defer_incomplete_lines: bool,
+) -> Result<LogEntryStream<LogReader>> {
+    let stream = LogEntryStream::new(open_log_reader_at(log_path, start_offset)?);
+    if ArchiveType::from_path(log_path) != ArchiveType::PlainLog {
+        Ok(stream)
+    } else if defer_incomplete_lines {
+        Ok(stream.deferring_incomplete_lines())
+    } else {
+        Ok(stream.holding_back_last_entry())
+    }
+}
+
+fn first_entry_timestamp(
+    log_path: &Path,
+    start_offset: u64,
+    defer_incomplete_lines: bool,
+) -> Result<Option<DateTime<Utc>>> {
+    let mut stream = open_log_stream(log_path, start_offset, defer_incomplete_lines)?;
+    let first = match stream.next().transpose()? {
+        Some(entry) => Some(entry),
+        None => stream.take_held_back(),
+    };
+    Ok(first.map(|entry| entry.timestamp))
+}
+
+fn parse_queued_files(
+    queue: &Mutex<vec::IntoIter<QueuedFile<'_>>>,
+    cancelled: &AtomicBool,
+    rules: Option<&RuleSet>,
+    defer_incomplete_lines: bool,
+) -> Result<()> {
+    let mut result = Ok(());
+    while !cancelled.load(Ordering::Relaxed) {
+        let Some(file) = queue.lock().expect("file queue lock is poisoned").next() else {
+            break;
+        };
+        if let Err(e) = parse_log_file(
+            file.path,
+            file.start_offset,
+            defer_incomplete_lines,
+            &file.tx,
+            rules,
+        ) {
+            cancelled.store(true, Ordering::Relaxed);
+            result = Err(e);
+            break;
+        }
+    }
+    // Drops the senders of the files no worker is going to parse,
+    // so that the inserter does not wait for them
+    queue
+        .lock()
+        .expect("file queue lock is poisoned")
+        .by_ref()
+        .for_each(drop);
+    result
+}
+
+/// Parses a log file from `start_offset` on and hands it over to the inserter in chunks.
+/// Returns early if the inserter has stopped.
+fn parse_log_file(
+    log_path: &Path,
+    start_offset: u64,
+    defer_incomplete_lines: bool,
+    tx: &mpsc::SyncSender<InsertionTask>,
+    rules: Option<&RuleSet>,
+) -> Result<()> {
+    let started_at = Instant::now();
+    let mut stream = open_log_stream(log_path, start_offset, defer_incomplete_lines)?;
+    let mut chunk = Vec::with_capacity(PIPELINE_CHUNK_SIZE);
+
+    while let Some(entry) = stream.next().transpose()? {
+        chunk.push(entry);
+        if chunk.len() == PIPELINE_CHUNK_SIZE {
+            let full_chunk = mem::replace(&mut chunk, Vec::with_capacity(PIPELINE_CHUNK_SIZE));
+            if !send_entries_chunk(tx, full_chunk, stream.bytes_read(), rules) {
+                return Ok(());
+            }
+        }
+    }
+    // Stored even though more of its lines can follow: the next incremental ingestion
+    // reads it again and replaces it if it grew
+    chunk.extend(stream.take_held_back());
+    if !chunk.is_empty() && !send_entries_chunk(tx, chunk, stream.bytes_read(), rules) {
+        return Ok(());
+    }
+
+    log::debug!(
+        "Detected {} log format in {}",
+        stream.format().unwrap_or_default(),
+        log_path.display()
+    );
+
+    // A failed send means that the inserter has stopped, it reports why
+    let _ = tx.send(InsertionTask::FileCompletionMarker {
+        total_lines: stream.total_lines(),
+        tail_lines: stream.held_back_lines(),
+        bytes_read: stream.bytes_read(),
+        parsing_time: started_at.elapsed(),
+    });
+    Ok(())
+}
+
+/// Annotates a chunk of entries in parallel and hands it over to the inserter.
+/// Blocks while the inserter is [`PIPELINE_QUEUE_CAPACITY`] chunks of this file behind.
+/// Returns false if the inserter has stopped.
+fn send_entries_chunk(
+    tx: &mpsc::SyncSender<InsertionTask>,
+    mut entries: Vec<ParsedLogEntry>,
+    bytes_read: u64,
+    rules: Option<&RuleSet>,
+) -> bool {
+    entries
+        .par_iter_mut()
+        .for_each(|e| annotate_entry_with_rules(e, rules));
+
+    tx.send(InsertionTask::EntriesChunk {
+        entries,
+        bytes_read,
+    })
+    .is_ok()
+}
+
+fn insert_files(
+    db: &DatabaseConnection,
+    files: Vec<InsertedFile<'_>>,
+    options: &IngestionOptions<'_>,
+) -> Result<()> {
+    NodeLogEntry::with_appender(db, |appender| {
+        let mut next_id = options.start_id;
+        for file in files {
+            next_id = insert_file(db, appender, file, next_id, options)?;
+        }
+        Ok(())
+    })
+}
+
+/// Inserts the entries of a file as they arrive, followed by its metadata.
+/// Returns the id of the next entry.
+fn insert_file(
+    db: &DatabaseConnection,
+    appender: &mut EntryAppender<'_>,
+    file: InsertedFile<'_>,
+    mut next_id: i64,
+    options: &IngestionOptions<'_>,
+) -> Result<i64> {
+    let mut spinner = if !options.silent {
+        let mut s = SpinnerReporter::new().with_tick_chars(BRAILLE_TICK_CHARS);
+        s.start(&format!("Parsing {}", file.path.display()));
+        Some(s)
+    } else {
+        None
+    };
+
+    // Compressed files are decompressed on the fly, so only the size of plain logs
+    // can be compared to the number of bytes parsed
+    let start_offset = file.plan.start_offset();
+    let file_size = match ArchiveType::from_path(file.path) {
+        ArchiveType::PlainLog => Some(file.snapshot.size.saturating_sub(start_offset)),
+        _ => None,
+    };
+    let started_at = Instant::now();
+    let file_path = file.path.to_string_lossy().to_string();
+    // The metadata and ingestion state of a file that is read again from the start
+    // describe contents it no longer has
+    let mut context = match file.plan {
+        ReadPlan::Restart => {
+            FileIngestionState::delete(db, &file_path)?;
+            FileMetadataContext::default()
+        }
+        _ if options.incremental => FileMetadata::find(db, &file_path)?
+            .map(FileMetadataContext::from_model)
+            .unwrap_or_default(),
+        _ => FileMetadataContext::default(),
+    };
+    let previously_stored_entries = context.total_entries;
+
+    let stored = match file.first_timestamp {
+        Some(since) => appender.fingerprints_since(file.node_name, since)?,
+        None => HashSet::new(),
+    };
+    let mut duplicates = 0;
+    // A resumed file starts with the last entry stored by its previous ingestion
+    let mut rereading_tail =
+        matches!(file.plan, ReadPlan::Resume { tail_lines, .. } if tail_lines > 0);
+
+    loop {
+        let task = file.rx.recv().map_err(|_| {
+            CommandRunError::Library(LibError::Io(IoError::new(
+                ErrorKind::Interrupted,
+                format!("Parsing of {} was interrupted", file.path.display()),
+            )))
+        })?;
+
+        match task {
+            InsertionTask::EntriesChunk {
+                mut entries,
+                bytes_read,
+            } => {
+                if mem::take(&mut rereading_tail)
+                    && let Some(tail) = entries.first()
+                    && (stored.contains(&entry_fingerprint(tail))
+                        || appender
+                            .replace_shorter_version(tail, file.node_name)?
+                            .is_some())
+                {
+                    entries.remove(0);
+                }
+                let entries = if stored.is_empty() {
+                    entries
+                } else {
+                    let total = entries.len();
+                    let unique: Vec<_> = entries
+                        .into_iter()
+                        .filter(|entry| !stored.contains(&entry_fingerprint(entry)))
+                        .collect();
+                    duplicates += total - unique.len();
+                    unique
+                };
+                for entry in &entries {
+                    appender.append(entry, next_id, file.node_name)?;
+                    next_id += 1;
+                }
+                appender.flush()?;
+                context.add_entries(&entries);
+
+                if let Some(ref s) = spinner {
+                    s.set_message(&parsing_progress_message(
+                        file.path,
+                        bytes_read,
+                        file_size,
+                        (context.total_entries - previously_stored_entries) as usize,
+                        started_at.elapsed(),
+                    ));
+                }
+            }
+            InsertionTask::FileCompletionMarker {
+                total_lines,
+                tail_lines,
+                bytes_read,
+                parsing_time,
+            } => {
+                let total_entries = (context.total_entries - previously_stored_entries) as usize;
+                context.nodes.insert(file.node_name.to_string());
+                match file.plan {
+                    // Metadata stored without a recorded ingestion state covers the whole file
+                    // read from the start, so its lines are not counted twice
+                    ReadPlan::Full | ReadPlan::Restart => context.total_lines = total_lines as i64,
+                    // So are the lines of the last entry, which were counted when it was first read
+                    ReadPlan::Resume {
+                        tail_lines: read_again,
+                        ..
+                    } => context.total_lines += total_lines as i64 - read_again as i64,
+                    ReadPlan::Unchanged => {}
+                }
+                let file_metadata = context.to_model(file_path.clone());
+                if options.incremental {
+                    FileMetadata::upsert_metadata(db, file_metadata)?;
+                } else {
+                    FileMetadata::insert_metadata(db, file_metadata)?;
+                }
+                FileIngestionState::upsert(
+                    db,
+                    &FileIngestionStateModel {
+                        file_path,
+                        node: file.node_name.to_string(),
+                        inode: file.snapshot.inode,
+                        file_size: file.snapshot.size,
+                        ingested_bytes: start_offset + bytes_read,
+                        tail_lines: tail_lines as u64,
+                        head_length: file.snapshot.head_length,
+                        head_hash: file.snapshot.head_hash,
+                    },
+                )?;
+
+                if duplicates > 0 {
+                    log::info!(
+                        "Skipped {} entries of {} that were already stored for {}",
+                        duplicates,
+                        file.path.display(),
+                        file.node_name
+                    );
+                }
+                if let Some(ref mut s) = spinner {
+                    s.finish(&format!(
+                        "✓ Parsed {}{}: {} entries{}, {} in {:.2}s ({})",
+                        file.path.display(),
+                        resumption_note(start_offset),
+                        total_entries,
+                        duplicates_note(duplicates),
+                        output::format_byte_size(bytes_read),
+                        parsing_time.as_secs_f64(),
+                        throughput(bytes_read, total_entries, parsing_time)
+                    ));
+                }
+                return Ok(next_id);
+            }
+        }
+    }
+}
+
+fn resumption_note(start_offset: u64) -> String {
+    if start_offset == 0 {
+        String::new()
+    } else {
+        format!(" from {} on", output::format_byte_size(start_offset))
+    }
+}
+
+fn duplicates_note(duplicates: usize) -> String {
+    if duplicates == 0 {
+        String::new()
+    } else {
+        format!(" ({} duplicates skipped)", duplicates)
+    }
+}
+
+fn parsing_progress_message(
+    log_path: &Path,
+    bytes_read: u64,
+    file_size: Option<u64>,
+    entries: usize,
+    elapsed: Duration,
+) -> String {
+    let bytes = match file_size {
+        Some(size) => format!(
+            "{} of {}",
+            output::format_byte_size(bytes_read),
+            output::format_byte_size(size)
+        ),
+        None => format!("{} decompressed", output::format_byte_size(bytes_read)),
+    };
+    format!(
+        "Parsing {}: {}, {} entries ({})",
+        log_path.display(),
+        bytes,
+        entries,
+        throughput(bytes_read, entries, elapsed)
+    )
+}
+
+fn throughput(bytes: u64, entries: usize, elapsed: Duration) -> String {
+    let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+    format!(
+        "{}/s, {:.0} entries/s",
+        output::format_byte_size((bytes as f64 / secs) as u64),
+        entries as f64 / secs
+    )
+}
+
+pub fn extract_node_name(log_path: &Path) -> Result<String> {
+    let file_name = log_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| {
+            CommandRunError::Library(LibError::Io(IoError::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid log file name: {}", log_path.display()),
+            )))
+        })?;
+
+    let node_name = strip_compression_suffix(&strip_rotation_suffix(file_name)).to_string();
+
+    Ok(node_name)
+}
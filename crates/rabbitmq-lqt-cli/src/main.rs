@@ -17,19 +17,22 @@
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 mod archive;
+mod bundle;
 mod cli;
 mod commands;
 mod completions;
 mod core;
 mod errors;
+mod ingestion;
 mod output;
+mod watch;
 
-use bel7_cli::ExitCode;
+use bel7_cli::{ExitCode, ExitCodeProvider};
 use std::io::stderr;
 use tokio::runtime::Runtime;
 
 #[cfg(feature = "web-ui")]
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 fn main() -> ExitCode {
     if let Err(e) = init_logging() {
@@ -56,10 +59,20 @@ async fn dispatch_command(cli: &clap::ArgMatches) -> ExitCode {
         Some(("logs", logs_args)) => match logs_args.subcommand() {
             Some(("parse", args)) => commands::handle_parse_command(args),
             Some(("merge", args)) => commands::handle_merge_command(args),
+            Some(("sync", args)) => commands::handle_sync_command(args),
+            Some(("watch", args)) => commands::handle_watch_command(args).await,
             Some(("obfuscate", args)) => commands::handle_obfuscate_command(args),
+            Some(("obfuscate-db", args)) => commands::handle_obfuscate_db_command(args),
+            Some(("deobfuscate", args)) => commands::handle_deobfuscate_command(args),
             Some(("query", args)) => commands::handle_query_command(args),
             Some(("overview", args)) => commands::handle_overview_command(args),
             Some(("ql", args)) => commands::handle_ql_command(args),
+            Some(("context", args)) => commands::handle_context_command(args),
+            Some(("entities", args)) => commands::handle_entities_command(args),
+            Some(("patterns", args)) => commands::handle_patterns_command(args),
+            Some(("timeline", args)) => commands::handle_timeline_command(args),
+            Some(("diff", args)) => commands::handle_diff_command(args),
+            Some(("report", args)) => commands::handle_report_command(args),
             Some(("tail", args)) => commands::handle_tail_command(args).await,
             _ => {
                 eprintln!(
@@ -125,8 +138,28 @@ async fn handle_web_serve_command(args: &clap::ArgMatches) -> ExitCode {
         .expect("port has a default value")
         .parse()
         .expect("port must be a valid number");
+    let time_anchor = match rabbitmq_lqt_ui::server::time_anchor_from_args(args) {
+        Ok(anchor) => anchor,
+        Err(e) => {
+            log::error!("{}", e);
+            return ExitCode::Usage;
+        }
+    };
 
-    match rabbitmq_lqt_ui::run_server(&db_path, host, port).await {
+    let result = match args.get_one::<String>("watch_log_dir_path") {
+        Some(dir_path) => match start_watching(args, &db_path, dir_path.into()) {
+            Ok(db) => {
+                rabbitmq_lqt_ui::serve_database(db, &db_path, host, port, time_anchor, true).await
+            }
+            Err(e) => {
+                log::error!("Failed to watch logs: {}", e);
+                return e.exit_code();
+            }
+        },
+        None => rabbitmq_lqt_ui::run_server(&db_path, host, port, time_anchor).await,
+    };
+
+    match result {
         Ok(()) => ExitCode::Ok,
         Err(e) => {
             log::error!("Web UI startup error: {}", e);
@@ -135,6 +168,31 @@ async fn handle_web_serve_command(args: &clap::ArgMatches) -> ExitCode {
     }
 }
 
+/// Ingests new log entries in the background while the web UI serves them.
+/// DuckDB allows only one process to write to a database file, so `logs watch`
+/// cannot run alongside `web serve` against the same file.
+#[cfg(feature = "web-ui")]
+fn start_watching(
+    args: &clap::ArgMatches,
+    db_path: &Path,
+    dir_path: PathBuf,
+) -> core::Result<rabbitmq_lqt_lib::DatabaseConnection> {
+    watch::validate_watched_directory(&dir_path)?;
+    let options = commands::watch_options(args)?;
+    let db = watch::open_or_create_database(db_path)?;
+    rabbitmq_lqt_lib::post_insertion_operations(&db)?;
+
+    let watched_db = db.clone();
+    tokio::spawn(async move {
+        let watched =
+            watch::watch_log_directory(watched_db, dir_path, options, std::future::pending());
+        if let Err(e) = watched.await {
+            log::error!("Stopped watching logs: {}", e);
+        }
+    });
+    Ok(db)
+}
+
 fn init_logging() -> Result<(), log::SetLoggerError> {
     fern::Dispatch::new()
         .format(|out, message, record| {
@@ -13,17 +13,29 @@
 // limitations under the License.
 use crate::errors::CommandRunError;
 use bel7_cli::{responsive_width, should_colorize};
+use chrono::{DateTime, Utc};
 use owo_colors::OwoColorize;
-use rabbitmq_lqt_lib::constants::{doc_url_from_id, resolution_or_discussion_url_from_id};
 use rabbitmq_lqt_lib::entry_metadata::subsystems::Subsystem;
 use rabbitmq_lqt_lib::parser::ParsedLogEntry;
+use rabbitmq_lqt_lib::rel_db::aggregation::format_cell;
 use rabbitmq_lqt_lib::rel_db::file_metadata;
+use rabbitmq_lqt_lib::rel_db::node_artifacts::{
+    self, ArtifactKind, REDACTED_VALUE, is_secret_setting, parse_enabled_plugins,
+    parse_rabbitmq_conf,
+};
 use rabbitmq_lqt_lib::rel_db::node_log_entry::Model;
+use rabbitmq_lqt_lib::{
+    CountDelta, EntityMentions, FactChange, LogDiff, PatternChange, PatternFrequency,
+    TabularResult, Timeline, TimelineEvent,
+};
+use rabbitmq_lqt_ql::{Finding, FindingSeverity, Report};
 use std::borrow::Cow;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::io::Error as IoError;
 use tabled::{
     Table, Tabled,
+    builder::Builder,
     settings::{Modify, Style, Width, object::Columns},
 };
 
@@ -72,6 +84,12 @@ const _: () = assert!(
 );
 
 const MIN_MESSAGE_WIDTH: usize = 60;
+/// Minimum width of a node column in `logs timeline` output
+const MIN_TIMELINE_COLUMN_WIDTH: usize = 30;
+/// Width of the name column in `logs entities` output, the only column that is wrapped
+const ENTITY_NAME_COLUMN_WIDTH: usize = 40;
+/// Width of the summary, nodes, evidence and links columns in `logs report` output
+const FINDING_TEXT_COLUMN_WIDTH: usize = 40;
 const TERMINAL_WIDTH_UTILIZATION: f64 = 0.85;
 
 fn colorize_node_name(node: &str, use_colors: bool) -> Cow<'_, str> {
@@ -154,6 +172,11 @@ impl DisplayLogEntry {
         } else {
             labels_str
         };
+        let doc_url = model.doc_url().unwrap_or("-").to_string();
+        let resolution_or_discussion_url = model
+            .resolution_or_discussion_url()
+            .unwrap_or("-")
+            .to_string();
 
         Self {
             id: model.id,
@@ -168,16 +191,8 @@ impl DisplayLogEntry {
                 .map(|s| s.to_string())
                 .unwrap_or_else(|| "-".into()),
             labels: labels_display,
-            doc_url: model
-                .doc_url_id
-                .and_then(doc_url_from_id)
-                .unwrap_or("-")
-                .to_string(),
-            resolution_or_discussion_url: model
-                .resolution_or_discussion_url_id
-                .and_then(resolution_or_discussion_url_from_id)
-                .unwrap_or("-")
-                .to_string(),
+            doc_url,
+            resolution_or_discussion_url,
         }
     }
 }
@@ -191,9 +206,11 @@ pub fn parsed_entry_to_model(entry: &ParsedLogEntry, node: &str, id: i64) -> Mod
         erlang_pid: entry.process_id.clone(),
         subsystem_id: entry.subsystem_id,
         message: entry.message.clone(),
-        labels: entry.labels.to_bits_i64(),
+        labels: entry.labels,
         resolution_or_discussion_url_id: entry.resolution_or_discussion_url_id,
         doc_url_id: entry.doc_url_id,
+        metadata: entry.metadata.clone(),
+        custom_annotations: entry.custom_annotations.clone(),
     }
 }
 
@@ -232,6 +249,520 @@ pub fn display_log_entries(
     Ok(())
 }
 
+/// Display the result of a `project`, `distinct` or `count` stage as a table
+pub fn display_tabular_result(
+    result: TabularResult,
+    without_colors: bool,
+) -> Result<(), CommandRunError> {
+    if result.is_empty() {
+        println!("No matching log entries found.");
+        return Ok(());
+    }
+
+    let use_colors = should_use_colors(without_colors);
+
+    let mut builder = Builder::default();
+    builder.push_record(result.columns.iter().cloned());
+    for row in &result.rows {
+        let cells = row.iter().zip(&result.columns).map(|(value, column)| {
+            let text = format_cell(value);
+            match column.as_str() {
+                "node" => colorize_node_name(&text, use_colors).into_owned(),
+                "severity" => colorize_severity(&text, use_colors).into_owned(),
+                _ => text,
+            }
+        });
+        builder.push_record(cells);
+    }
+
+    let mut table = builder.build();
+    table.with(Style::modern());
+
+    let target_width = responsive_width(TERMINAL_WIDTH_UTILIZATION);
+    table.with(Width::wrap(target_width));
+
+    println!("{}", table);
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Tabled)]
+struct DisplayEntityMentions {
+    #[tabled(rename = "Kind")]
+    kind: String,
+
+    #[tabled(rename = "Virtual Host")]
+    vhost: String,
+
+    #[tabled(rename = "Name")]
+    name: String,
+
+    #[tabled(rename = "Entries")]
+    entry_count: i64,
+
+    #[tabled(rename = "Nodes")]
+    node_count: i64,
+
+    #[tabled(rename = "First Seen")]
+    first_seen: String,
+
+    #[tabled(rename = "Last Seen")]
+    last_seen: String,
+}
+
+impl DisplayEntityMentions {
+    fn from_mentions(mentions: EntityMentions) -> Self {
+        Self {
+            kind: mentions.kind.to_string(),
+            vhost: mentions.vhost.unwrap_or_else(|| "-".into()),
+            name: mentions.name,
+            entry_count: mentions.entry_count,
+            node_count: mentions.node_count,
+            first_seen: format_optional_timestamp(mentions.first_seen),
+            last_seen: format_optional_timestamp(mentions.last_seen),
+        }
+    }
+}
+
+fn format_optional_timestamp(ts: Option<DateTime<Utc>>) -> String {
+    ts.map(|ts| ts.to_rfc3339()).unwrap_or_else(|| "-".into())
+}
+
+/// Formats a number of bytes using binary units, e.g. `1.5 GiB`
+pub fn format_byte_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Display the most mentioned entities as a table
+pub fn display_entity_mentions(
+    mentions: Vec<EntityMentions>,
+    _without_colors: bool,
+) -> Result<(), CommandRunError> {
+    if mentions.is_empty() {
+        println!("No entities found.");
+        return Ok(());
+    }
+
+    let display_mentions: Vec<DisplayEntityMentions> = mentions
+        .into_iter()
+        .map(DisplayEntityMentions::from_mentions)
+        .collect();
+
+    let mut table = Table::new(&display_mentions);
+    table
+        .with(Style::modern())
+        .with(Modify::new(Columns::new(2..3)).with(Width::wrap(ENTITY_NAME_COLUMN_WIDTH)));
+
+    println!("{}", table);
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Tabled)]
+struct DisplayPatternFrequency {
+    #[tabled(rename = "Pattern")]
+    template: String,
+
+    #[tabled(rename = "Entries")]
+    entry_count: i64,
+
+    #[tabled(rename = "Nodes")]
+    node_count: i64,
+
+    #[tabled(rename = "First Seen")]
+    first_seen: String,
+
+    #[tabled(rename = "Last Seen")]
+    last_seen: String,
+}
+
+impl DisplayPatternFrequency {
+    fn from_frequency(frequency: PatternFrequency) -> Self {
+        Self {
+            template: frequency.template,
+            entry_count: frequency.entry_count,
+            node_count: frequency.node_count,
+            first_seen: format_optional_timestamp(frequency.first_seen),
+            last_seen: format_optional_timestamp(frequency.last_seen),
+        }
+    }
+}
+
+/// Display the most frequent message patterns as a table
+pub fn display_pattern_frequencies(
+    patterns: Vec<PatternFrequency>,
+    _without_colors: bool,
+) -> Result<(), CommandRunError> {
+    if patterns.is_empty() {
+        println!("No patterns found.");
+        return Ok(());
+    }
+
+    let display_patterns: Vec<DisplayPatternFrequency> = patterns
+        .into_iter()
+        .map(DisplayPatternFrequency::from_frequency)
+        .collect();
+
+    let mut table = Table::new(&display_patterns);
+    table.with(Style::modern());
+
+    let target_width = responsive_width(TERMINAL_WIDTH_UTILIZATION);
+    table.with(Width::wrap(target_width));
+
+    println!("{}", table);
+
+    Ok(())
+}
+
+/// Formats a timeline event as a cell of its node's column
+fn format_timeline_event(event: &TimelineEvent, use_colors: bool) -> String {
+    let mut text = match event.transition {
+        Some(transition) => format!("[{}] {}", transition, event.summary()),
+        None => format!("{}: {}", event.severity, event.summary()),
+    };
+    if event.is_burst() {
+        let span = (event.last_timestamp - event.timestamp).num_seconds();
+        text = format!("{} (x{} over {}s)", text, event.count, span);
+    }
+
+    if !use_colors {
+        return text;
+    }
+    match (event.transition, event.severity.as_str()) {
+        (Some(_), "error" | "critical") => text.red().bold().to_string(),
+        (Some(_), _) => text.bold().to_string(),
+        (None, "error" | "critical") => text.red().to_string(),
+        (None, "warning") => text.bright_yellow().to_string(),
+        _ => text,
+    }
+}
+
+/// Display a timeline as a table with one column per node
+pub fn display_timeline(timeline: &Timeline, without_colors: bool) -> Result<(), CommandRunError> {
+    if timeline.is_empty() {
+        println!("No matching log entries found.");
+        return Ok(());
+    }
+
+    let use_colors = should_use_colors(without_colors);
+
+    let mut builder = Builder::default();
+    builder.push_record(
+        std::iter::once("Time".to_string()).chain(
+            timeline
+                .nodes
+                .iter()
+                .map(|node| colorize_node_name(node, use_colors).into_owned()),
+        ),
+    );
+    for event in &timeline.events {
+        let column = timeline
+            .nodes
+            .iter()
+            .position(|node| node == &event.node)
+            .expect("timeline nodes include the nodes of all events");
+        let mut row = vec![String::new(); timeline.nodes.len() + 1];
+        row[0] = event.timestamp.to_rfc3339();
+        row[column + 1] = format_timeline_event(event, use_colors);
+        builder.push_record(row);
+    }
+
+    let mut table = builder.build();
+    table.with(Style::modern());
+
+    let target_width = responsive_width(TERMINAL_WIDTH_UTILIZATION);
+    let node_column_width = target_width
+        .saturating_sub(TIMESTAMP_COLUMN_WIDTH)
+        .checked_div(timeline.nodes.len())
+        .unwrap_or(target_width)
+        .max(MIN_TIMELINE_COLUMN_WIDTH);
+    table.with(Modify::new(Columns::new(1..)).with(Width::wrap(node_column_width)));
+
+    println!("{}", table);
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Tabled)]
+struct DisplayPatternChange {
+    #[tabled(rename = "Pattern")]
+    template: String,
+
+    #[tabled(rename = "Entries")]
+    entry_count: i64,
+
+    #[tabled(rename = "Nodes")]
+    nodes: String,
+}
+
+impl DisplayPatternChange {
+    fn from_change(change: &PatternChange) -> Self {
+        Self {
+            template: change.template.clone(),
+            entry_count: change.entry_count,
+            nodes: change.nodes.join(", "),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Tabled)]
+struct DisplayCountDelta {
+    #[tabled(rename = "Node")]
+    node: String,
+
+    #[tabled(rename = "Kind")]
+    dimension: String,
+
+    #[tabled(rename = "Value")]
+    value: String,
+
+    #[tabled(rename = "Baseline")]
+    baseline: i64,
+
+    #[tabled(rename = "Comparison")]
+    comparison: i64,
+
+    #[tabled(rename = "Change")]
+    change: String,
+}
+
+impl DisplayCountDelta {
+    fn from_delta(delta: &CountDelta) -> Self {
+        Self {
+            node: delta.node.clone(),
+            dimension: delta.dimension.to_string(),
+            value: delta.value.clone(),
+            baseline: delta.baseline,
+            comparison: delta.comparison,
+            change: format!("{:+}", delta.delta()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Tabled)]
+struct DisplayFactChange {
+    #[tabled(rename = "Node")]
+    node: String,
+
+    #[tabled(rename = "Fact")]
+    fact: String,
+
+    #[tabled(rename = "Added")]
+    added: String,
+
+    #[tabled(rename = "Removed")]
+    removed: String,
+}
+
+impl DisplayFactChange {
+    fn from_change(change: &FactChange) -> Self {
+        Self {
+            node: change.node.clone(),
+            fact: change.fact.to_string(),
+            added: change.added.join(", "),
+            removed: change.removed.join(", "),
+        }
+    }
+}
+
+/// Prints a titled table of up to `limit` rows, noting how many rows were left out
+fn print_diff_section<T: Tabled>(title: &str, rows: Vec<T>, limit: usize) {
+    if rows.is_empty() {
+        return;
+    }
+
+    let total = rows.len();
+    let shown: Vec<T> = rows.into_iter().take(limit).collect();
+
+    println!();
+    println!("{} ({}):", title, total);
+    let mut table = Table::new(&shown);
+    table.with(Style::modern());
+    table.with(Width::wrap(responsive_width(TERMINAL_WIDTH_UTILIZATION)));
+    println!("{}", table);
+
+    if total > shown.len() {
+        println!("... and {} more", total - shown.len());
+    }
+}
+
+/// Display the differences between two log sets, one table per kind of change
+pub fn display_log_diff(
+    diff: &LogDiff,
+    limit: usize,
+    _without_colors: bool,
+) -> Result<(), CommandRunError> {
+    println!(
+        "Baseline entries: {}, comparison entries: {}",
+        diff.baseline_entries, diff.comparison_entries
+    );
+
+    if diff.is_empty() {
+        println!("No differences found.");
+        return Ok(());
+    }
+
+    print_diff_section(
+        "New patterns",
+        diff.new_patterns
+            .iter()
+            .map(DisplayPatternChange::from_change)
+            .collect(),
+        limit,
+    );
+    print_diff_section(
+        "Vanished patterns",
+        diff.vanished_patterns
+            .iter()
+            .map(DisplayPatternChange::from_change)
+            .collect(),
+        limit,
+    );
+    print_diff_section(
+        "Count changes",
+        diff.count_deltas
+            .iter()
+            .map(DisplayCountDelta::from_delta)
+            .collect(),
+        limit,
+    );
+    print_diff_section(
+        "Metadata changes",
+        diff.fact_changes
+            .iter()
+            .map(DisplayFactChange::from_change)
+            .collect(),
+        limit,
+    );
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Tabled)]
+struct DisplayFinding {
+    #[tabled(rename = "Severity")]
+    severity: String,
+
+    #[tabled(rename = "Check")]
+    check: String,
+
+    #[tabled(rename = "Summary")]
+    summary: String,
+
+    #[tabled(rename = "Nodes")]
+    nodes: String,
+
+    #[tabled(rename = "Evidence")]
+    evidence: String,
+
+    #[tabled(rename = "Links")]
+    urls: String,
+}
+
+impl DisplayFinding {
+    fn from_finding(finding: &Finding, use_colors: bool) -> Self {
+        Self {
+            severity: colorize_finding_severity(finding.severity, use_colors).into_owned(),
+            check: finding.check.to_string(),
+            summary: finding.summary.clone(),
+            nodes: finding.nodes.join("\n"),
+            evidence: format_evidence(&finding.evidence),
+            urls: finding.urls.join("\n"),
+        }
+    }
+}
+
+fn colorize_finding_severity(severity: FindingSeverity, use_colors: bool) -> Cow<'static, str> {
+    if !use_colors {
+        return Cow::Borrowed(severity.as_str());
+    }
+    match severity {
+        FindingSeverity::Critical => Cow::Owned(severity.as_str().red().bold().to_string()),
+        FindingSeverity::Warning => Cow::Owned(severity.as_str().yellow().to_string()),
+        FindingSeverity::Info => Cow::Borrowed(severity.as_str()),
+    }
+}
+
+fn format_evidence(ids: &[i64]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Display report findings as a table
+pub fn display_report(report: &Report, without_colors: bool) -> Result<(), CommandRunError> {
+    if report.is_empty() {
+        println!("No findings.");
+        return Ok(());
+    }
+
+    let use_colors = should_use_colors(without_colors);
+    let display_findings: Vec<DisplayFinding> = report
+        .findings
+        .iter()
+        .map(|f| DisplayFinding::from_finding(f, use_colors))
+        .collect();
+
+    let mut table = Table::new(&display_findings);
+    table
+        .with(Style::modern())
+        .with(Modify::new(Columns::new(2..6)).with(Width::wrap(FINDING_TEXT_COLUMN_WIDTH)));
+
+    println!("{}", table);
+
+    Ok(())
+}
+
+/// Display report findings as a Markdown document, e.g. to paste into an issue
+pub fn display_report_markdown(report: &Report) {
+    println!("# Findings");
+    println!();
+
+    if report.is_empty() {
+        println!("No findings.");
+        return;
+    }
+
+    for (i, finding) in report.findings.iter().enumerate() {
+        println!("## {}. {} ({})", i + 1, finding.check, finding.severity);
+        println!();
+        println!("{}", finding.summary);
+        println!();
+        if !finding.nodes.is_empty() {
+            println!(" * Nodes: {}", finding.nodes.join(", "));
+        }
+        if !finding.evidence.is_empty() {
+            println!(
+                " * Evidence entry IDs: {}",
+                format_evidence(&finding.evidence)
+            );
+        }
+        for url in &finding.urls {
+            println!(" * See: <{}>", url);
+        }
+        println!();
+    }
+}
+
+/// Display report findings as JSON
+pub fn display_report_json(report: &Report) -> Result<(), CommandRunError> {
+    let json = serde_json::to_string_pretty(report).map_err(IoError::other)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
 pub fn display_file_metadata(
     metadata_entries: Vec<file_metadata::Model>,
     _without_colors: bool,
@@ -308,3 +839,39 @@ pub fn display_file_metadata(
 
     Ok(())
 }
+
+/// Displays the configuration files and plugin lists ingested from support bundles.
+/// Values of secret settings are not displayed.
+pub fn display_node_artifacts(artifacts: &[node_artifacts::Model]) {
+    for artifact in artifacts {
+        println!();
+        println!("Node Artifact: {}", artifact.file_path);
+        println!("  Node: {}", artifact.node);
+        println!("  Kind: {}", artifact.kind);
+
+        match artifact.kind {
+            ArtifactKind::RabbitmqConf => {
+                let settings = parse_rabbitmq_conf(&artifact.content);
+                println!("  Settings ({}):", settings.len());
+                for (key, value) in &settings {
+                    let value = if is_secret_setting(key) {
+                        REDACTED_VALUE
+                    } else {
+                        value.as_str()
+                    };
+                    println!("    * {} = {}", key, value);
+                }
+            }
+            ArtifactKind::EnabledPlugins => {
+                let plugins = parse_enabled_plugins(&artifact.content);
+                println!("  Enabled Plugins ({}):", plugins.len());
+                for plugin in &plugins {
+                    println!("    * {}", plugin);
+                }
+            }
+            ArtifactKind::AdvancedConfig => {
+                println!("  Lines: {}", artifact.content.lines().count());
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Continuous ingestion of the node logs in a directory, used by `logs watch`
+//! and `web serve --watch-log-dir-path`.
+//!
+//! Every poll lists the directory again, so files that show up later are picked up.
+//! Files are read incrementally (see [`crate::ingestion`]): rotated files are read
+//! from the start and entries that were already stored are skipped.
+
+use crate::archive::{ArchiveType, is_supported_log_file};
+use crate::core::Result;
+use crate::errors::CommandRunError;
+use crate::ingestion::{IngestionOptions, LogFile, ingest_log_files};
+use rabbitmq_lqt_lib::Error as LibError;
+use rabbitmq_lqt_lib::{DatabaseConnection, NodeLogEntry, RuleSet, create_database, open_database};
+use std::fs;
+use std::future::Future;
+use std::io::{Error as IoError, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{self, MissedTickBehavior};
+
+pub struct WatchOptions {
+    pub poll_interval: Duration,
+    pub rules: Option<RuleSet>,
+    pub parallelism: usize,
+}
+
+/// Ingests new log entries from the files in `dir` every poll interval
+/// until `shutdown` completes.
+///
+/// The entries of each poll get IDs above those of earlier polls, in timestamp order
+/// among themselves, so IDs only follow timestamps within a poll.
+/// Failed polls are logged and retried on the next poll.
+pub async fn watch_log_directory(
+    db: DatabaseConnection,
+    dir: PathBuf,
+    options: WatchOptions,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    let options = Arc::new(options);
+    let mut interval = time::interval(options.poll_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    tokio::pin!(shutdown);
+
+    log::info!("Watching {} for new log entries", dir.display());
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => {
+                break;
+            }
+            _ = interval.tick() => {
+                let (db, dir, options) = (db.clone(), dir.clone(), options.clone());
+                let polled = tokio::task::spawn_blocking(move || {
+                    ingest_new_entries(&db, &dir, &options).map_err(|e| {
+                        format!("Failed to ingest log files from {}: {}", dir.display(), e)
+                    })
+                })
+                .await
+                .map_err(|e| {
+                    CommandRunError::Library(LibError::Io(IoError::other(format!(
+                        "Task join error: {}",
+                        e
+                    ))))
+                })?;
+                if let Err(message) = polled {
+                    log::warn!("{}", message);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Completes on Ctrl+C or, on Unix, when the process is asked to terminate
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+/// Opens the database to watch into, creating it if it does not exist yet
+pub fn open_or_create_database(db_path: &Path) -> Result<DatabaseConnection> {
+    if db_path.exists() {
+        if !db_path.is_file() {
+            return Err(CommandRunError::Library(LibError::Io(IoError::new(
+                ErrorKind::InvalidInput,
+                format!("Database path is not a file: {}", db_path.display()),
+            ))));
+        }
+        Ok(open_database(db_path)?)
+    } else {
+        log::info!("Creating database {}", db_path.display());
+        Ok(create_database(db_path)?)
+    }
+}
+
+/// Unlike other commands, an empty directory is fine: its files can show up later.
+pub fn validate_watched_directory(dir: &Path) -> Result<()> {
+    if !dir.is_dir() {
+        return Err(CommandRunError::Library(LibError::Io(IoError::new(
+            ErrorKind::NotFound,
+            format!(
+                "Directory not found: {}\n\
+                \n\
+                Make sure:\n\
+                • The directory path is correct\n\
+                • You have read permissions for the directory",
+                dir.display()
+            ),
+        ))));
+    }
+    Ok(())
+}
+
+fn ingest_new_entries(db: &DatabaseConnection, dir: &Path, options: &WatchOptions) -> Result<()> {
+    let log_files = list_log_files(dir)?;
+    let count_before = NodeLogEntry::count_all(db)?;
+    let start_id = NodeLogEntry::max_entry_id(db)? + 1;
+    ingest_log_files(
+        db,
+        &log_files,
+        &IngestionOptions {
+            rules: options.rules.as_ref(),
+            parallelism: options.parallelism,
+            start_id,
+            incremental: true,
+            defer_incomplete_lines: true,
+            silent: true,
+        },
+    )?;
+    // Also indexes the new entries for search
+    NodeLogEntry::renumber_appended_in_timestamp_order(db, start_id)?;
+
+    let count_after = NodeLogEntry::count_all(db)?;
+    if count_after > count_before {
+        log::info!(
+            "Stored {} new log entries (total: {})",
+            count_after - count_before,
+            count_after
+        );
+    }
+    Ok(())
+}
+
+/// Lists the log files in `dir`. Tar archives are skipped: they are only
+/// expected in support bundles, not in the log directory of a running node.
+fn list_log_files(dir: &Path) -> Result<Vec<LogFile>> {
+    let entries = fs::read_dir(dir).map_err(|e| {
+        CommandRunError::Library(LibError::Io(IoError::new(
+            e.kind(),
+            format!("Failed to read directory '{}': {}", dir.display(), e),
+        )))
+    })?;
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_file()
+            && is_supported_log_file(&path)
+            && !ArchiveType::from_path(&path).is_tar_archive()
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort_unstable();
+
+    paths.into_iter().map(LogFile::from_path).collect()
+}
@@ -0,0 +1,297 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use tar::{Builder as TarBuilder, Header as TarHeader};
+use tempfile::{NamedTempFile, TempDir};
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+use crate::test_helpers::*;
+
+const RABBITMQ_CONF: &str = "\
+# Managed by the deployment tool
+listeners.tcp.default = 5672
+default_user = admin
+default_pass = s3kr3t
+";
+
+const ENABLED_PLUGINS: &str = "[rabbitmq_management,rabbitmq_shovel].\n";
+
+/// Entries logged at `hour`, starting with a node banner if `node` is given
+fn log_lines(hour: u32, node: Option<&str>, messages: &[&str]) -> String {
+    let mut lines = String::new();
+    if let Some(node) = node {
+        lines.push_str(&format!(
+            "2025-10-27 {:02}:23:00.000000+00:00 [info] <0.208.0>  node           : {}\n",
+            hour, node
+        ));
+    }
+    for (i, message) in messages.iter().enumerate() {
+        lines.push_str(&format!(
+            "2025-10-27 {:02}:23:{:02}.000000+00:00 [info] <0.1.0> {}\n",
+            hour,
+            i + 1,
+            message
+        ));
+    }
+    lines
+}
+
+fn write_zip(path: &Path, files: &[(&str, &[u8])]) -> Result<(), Box<dyn Error>> {
+    let mut zip = ZipWriter::new(File::create(path)?);
+    for (name, content) in files {
+        zip.start_file(*name, SimpleFileOptions::default())?;
+        zip.write_all(content)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+fn tar_gz(files: &[(&str, &[u8])]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut builder = TarBuilder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (name, content) in files {
+        let mut header = TarHeader::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, *content)?;
+    }
+    Ok(builder.into_inner()?.finish()?)
+}
+
+fn parse_bundle(bundle_path: &Path, db_path: &str) -> assert_cmd::assert::Assert {
+    run_succeeds([
+        "logs",
+        "parse",
+        "--input-archive",
+        bundle_path.to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+    ])
+}
+
+#[test]
+fn parse_zip_bundle_infers_node_names_from_directories() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let bundle_path = temp_dir.path().join("bundle.zip");
+    let log = log_lines(18, None, &["first", "second"]);
+    write_zip(
+        &bundle_path,
+        &[
+            ("bundle/rabbit@sunnyside/log/rabbit.log", log.as_bytes()),
+            (
+                "bundle/rabbit@sunnyside/etc/rabbitmq.conf",
+                RABBITMQ_CONF.as_bytes(),
+            ),
+            (
+                "bundle/rabbit@sunnyside/etc/enabled_plugins",
+                ENABLED_PLUGINS.as_bytes(),
+            ),
+            ("bundle/README.txt", b"not a log file"),
+        ],
+    )?;
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_bundle(&bundle_path, db_path).stderr(output_includes("2 log entries"));
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("* rabbit@sunnyside"))
+        .stdout(output_includes(
+            "Node Artifact: bundle/bundle/rabbit@sunnyside/etc/rabbitmq.conf",
+        ))
+        .stdout(output_includes("Node: rabbit@sunnyside"))
+        .stdout(output_includes("Kind: rabbitmq_conf"))
+        .stdout(output_includes("* listeners.tcp.default = 5672"))
+        .stdout(output_includes("* default_pass = ******"))
+        .stdout(output_includes("s3kr3t").not())
+        .stdout(output_includes("Enabled Plugins (2):"))
+        .stdout(output_includes("* rabbitmq_shovel"));
+
+    Ok(())
+}
+
+#[test]
+fn parse_bundle_with_nested_archives_and_rotated_logs() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let current_log = log_lines(18, Some("rabbit@eastside"), &["current"]);
+    let rotated_log = log_lines(17, Some("rabbit@eastside"), &["rotated", "rotated again"]);
+    let node_archive = tar_gz(&[
+        ("logs/rabbit.log", current_log.as_bytes()),
+        ("logs/rabbit.log.1", rotated_log.as_bytes()),
+        ("config/rabbitmq.conf", RABBITMQ_CONF.as_bytes()),
+    ])?;
+    let other_log = log_lines(18, None, &["westside"]);
+    let bundle_path = temp_dir.path().join("bundle.zip");
+    write_zip(
+        &bundle_path,
+        &[
+            ("nodes/eastside.tar.gz", &node_archive),
+            ("nodes/rabbit@westside.log", other_log.as_bytes()),
+        ],
+    )?;
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_bundle(&bundle_path, db_path).stderr(output_includes("6 log entries"));
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("* rabbit@eastside"))
+        .stdout(output_includes("* rabbit@westside"))
+        .stdout(output_includes(
+            "Node Artifact: bundle/nodes/eastside/config/rabbitmq.conf",
+        ))
+        .stdout(output_includes("Node: rabbit@eastside"));
+
+    Ok(())
+}
+
+#[test]
+fn parse_bundle_directory() -> Result<(), Box<dyn Error>> {
+    let bundle_dir = TempDir::new()?;
+    let node_dir = bundle_dir.path().join("rabbit@northside");
+    fs::create_dir_all(&node_dir)?;
+    fs::write(
+        node_dir.join("rabbit.log.2.gz"),
+        gzip(log_lines(18, None, &["compressed and rotated"]).as_bytes())?,
+    )?;
+    fs::write(node_dir.join("enabled_plugins"), ENABLED_PLUGINS)?;
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_bundle(bundle_dir.path(), db_path).stderr(output_includes("1 log entries"));
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("* rabbit@northside"))
+        .stdout(output_includes("Kind: enabled_plugins"))
+        .stdout(output_includes("* rabbitmq_management"));
+
+    Ok(())
+}
+
+#[test]
+fn parse_combines_bundles_and_log_files() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let bundle_path = temp_dir.path().join("bundle.zip");
+    let log = log_lines(18, Some("rabbit@southside"), &["from a bundle"]);
+    write_zip(&bundle_path, &[("logs/rabbit.log", log.as_bytes())])?;
+    let log_path = fixture_log_path();
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "parse",
+        "--input-log-file-path",
+        log_path.to_str().unwrap(),
+        "--input-archive",
+        bundle_path.to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+    ]);
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("* rabbit@fixture1"))
+        .stdout(output_includes("* rabbit@southside"));
+
+    Ok(())
+}
+
+#[test]
+fn merge_bundle_into_existing_database() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let db_path = temp_dir.path().join("logs.db");
+    let db_path = db_path.to_str().unwrap();
+    parse_log_to_db(fixture_log_path().to_str().unwrap(), db_path)?;
+
+    let bundle_path = temp_dir.path().join("bundle.tar.gz");
+    let log = log_lines(18, None, &["merged"]);
+    fs::write(
+        &bundle_path,
+        tar_gz(&[
+            ("rabbit@mergeside/rabbit.log", log.as_bytes()),
+            ("rabbit@mergeside/rabbitmq.conf", RABBITMQ_CONF.as_bytes()),
+        ])?,
+    )?;
+
+    run_succeeds([
+        "logs",
+        "merge",
+        "--input-archive",
+        bundle_path.to_str().unwrap(),
+        "--db-file-path",
+        db_path,
+    ])
+    .stderr(output_includes("Merged 1 new log entries"));
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("* rabbit@mergeside"))
+        .stdout(output_includes("Kind: rabbitmq_conf"));
+
+    Ok(())
+}
+
+#[test]
+fn parse_rotated_log_file_strips_rotation_suffix() -> Result<(), Box<dyn Error>> {
+    let log_dir = TempDir::new()?;
+    let log_path = log_dir.path().join("rabbit@rotated.log.3");
+    fs::write(&log_path, log_lines(18, None, &["rotated"]))?;
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "parse",
+        "--input-log-dir-path",
+        log_dir.path().to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+    ]);
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("* rabbit@rotated\n"));
+
+    Ok(())
+}
+
+#[test]
+fn parse_rejects_a_log_file_as_a_bundle() -> Result<(), Box<dyn Error>> {
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_fails([
+        "logs",
+        "parse",
+        "--input-archive",
+        fixture_log_path().to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+    ])
+    .stderr(output_includes("Not a support bundle"));
+
+    Ok(())
+}
+
+fn gzip(content: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(content)?;
+    Ok(encoder.finish()?)
+}
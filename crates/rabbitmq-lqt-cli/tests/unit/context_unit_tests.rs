@@ -0,0 +1,109 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use predicates::prelude::*;
+use std::error::Error;
+use tempfile::NamedTempFile;
+
+use crate::test_helpers::*;
+
+fn setup_test_db() -> Result<NamedTempFile, Box<dyn Error>> {
+    let log_path = fixture_log_path();
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    Ok(db_file)
+}
+
+#[test]
+fn context_shows_surrounding_entries() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    // Entry 15 was logged by <0.230.0>, entries 16 and 17 by <0.208.0>
+    run_succeeds([
+        "logs",
+        "context",
+        "--input-db-file-path",
+        db_path,
+        "--id",
+        "16",
+        "--before",
+        "1",
+        "--after",
+        "1",
+        "--without-colors",
+    ])
+    .stderr(output_includes("Found 3 entries around entry 16"))
+    .stdout(output_includes("<0.230.0>"));
+
+    run_succeeds([
+        "logs",
+        "context",
+        "--input-db-file-path",
+        db_path,
+        "--id",
+        "16",
+        "-B",
+        "1",
+        "-A",
+        "1",
+        "--same-pid",
+        "--without-colors",
+    ])
+    .stderr(output_includes("Found 3 entries around entry 16"))
+    .stdout(output_includes("<0.230.0>").not());
+
+    Ok(())
+}
+
+#[test]
+fn context_of_unknown_entry() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_fails([
+        "logs",
+        "context",
+        "--input-db-file-path",
+        db_path,
+        "--id",
+        "99999",
+    ])
+    .stderr(output_includes("No log entry with ID 99999"));
+
+    Ok(())
+}
+
+#[test]
+fn context_rejects_large_windows() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_fails([
+        "logs",
+        "context",
+        "--input-db-file-path",
+        db_path,
+        "--id",
+        "16",
+        "--after",
+        "1001",
+    ])
+    .stderr(output_includes("1001 is not in 0..=1000"));
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use predicates::prelude::*;
+use std::error::Error;
+use tempfile::NamedTempFile;
+
+use crate::test_helpers::*;
+
+fn setup_single_node_test_db() -> Result<NamedTempFile, Box<dyn Error>> {
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_log_to_db(fixture_log_path().to_str().unwrap(), db_path)?;
+
+    Ok(db_file)
+}
+
+fn setup_two_node_test_db() -> Result<NamedTempFile, Box<dyn Error>> {
+    let db_file = setup_single_node_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "merge",
+        "--input-log-file-path",
+        fixture_log_path_cottontail().to_str().unwrap(),
+        "--db-file-path",
+        db_path,
+        "--silent",
+    ]);
+
+    Ok(db_file)
+}
+
+#[test]
+fn diff_of_two_databases() -> Result<(), Box<dyn Error>> {
+    let baseline_file = setup_single_node_test_db()?;
+    let comparison_file = setup_two_node_test_db()?;
+
+    run_succeeds([
+        "logs",
+        "diff",
+        "--baseline-db-file-path",
+        baseline_file.path().to_str().unwrap(),
+        "--comparison-db-file-path",
+        comparison_file.path().to_str().unwrap(),
+        "--without-colors",
+    ])
+    .stdout(output_includes("Baseline entries:"))
+    .stdout(output_includes("New patterns"))
+    .stdout(output_includes("Count changes"))
+    .stdout(output_includes("rabbit@fixture3"))
+    .stdout(output_includes("Vanished patterns").not());
+
+    Ok(())
+}
+
+#[test]
+fn diff_of_identical_databases() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_single_node_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "diff",
+        "--baseline-db-file-path",
+        db_path,
+        "--comparison-db-file-path",
+        db_path,
+    ])
+    .stdout(output_includes("No differences found."));
+
+    Ok(())
+}
+
+#[test]
+fn diff_of_two_time_windows() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_single_node_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "diff",
+        "--baseline-db-file-path",
+        db_path,
+        "--baseline-to-time",
+        "2025-10-27T11:23:28-07:00",
+        "--comparison-since-time",
+        "2025-10-27T11:23:28-07:00",
+        "--limit",
+        "1",
+        "--without-colors",
+    ])
+    .stdout(output_includes("Count changes"))
+    .stdout(output_includes("more"));
+
+    Ok(())
+}
+
+#[test]
+fn diff_requires_a_comparison() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_single_node_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_fails(["logs", "diff", "--baseline-db-file-path", db_path])
+        .stderr(output_includes("--comparison-db-file-path"));
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use predicates::prelude::*;
+use std::error::Error;
+use tempfile::NamedTempFile;
+
+use crate::test_helpers::*;
+
+fn setup_json_test_db() -> Result<NamedTempFile, Box<dyn Error>> {
+    let log_path = fixture_log_path_json();
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    Ok(db_file)
+}
+
+#[test]
+fn entities_lists_most_mentioned_resources() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds(["logs", "entities", "--input-db-file-path", db_path])
+        .stdout(output_includes("orders"))
+        .stdout(output_includes("vhost1"))
+        .stdout(output_includes("user2"))
+        .stdout(output_includes("connection"));
+
+    Ok(())
+}
+
+#[test]
+fn entities_filtered_by_kind() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "entities",
+        "--input-db-file-path",
+        db_path,
+        "--kind",
+        "queue",
+    ])
+    .stderr(output_includes("Found 1 entities"))
+    .stdout(output_includes("orders"))
+    .stdout(output_includes("user2").not());
+
+    Ok(())
+}
+
+#[test]
+fn entities_with_limit() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "entities",
+        "--input-db-file-path",
+        db_path,
+        "--kind",
+        "user",
+        "--limit",
+        "1",
+    ])
+    .stderr(output_includes("Found 1 entities"))
+    .stdout(output_includes("user2"));
+
+    Ok(())
+}
+
+#[test]
+fn entities_rejects_unknown_kind() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_fails([
+        "logs",
+        "entities",
+        "--input-db-file-path",
+        db_path,
+        "--kind",
+        "binding",
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn ql_query_by_queue() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        r#"queue == "orders""#,
+    ])
+    .stderr(output_includes("Found 2 matching entries"));
+
+    Ok(())
+}
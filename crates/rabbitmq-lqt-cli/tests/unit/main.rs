@@ -1,11 +1,21 @@
 mod test_helpers;
 
+mod bundle_unit_tests;
+mod context_unit_tests;
+mod diff_unit_tests;
+mod entities_unit_tests;
 mod help_unit_tests;
 mod merge_unit_tests;
 mod obfuscate_unit_tests;
 mod parse_unit_tests;
+mod patterns_unit_tests;
 mod progress_bar_unit_tests;
 mod ql_unit_tests;
 mod query_unit_tests;
+mod report_unit_tests;
+mod rules_unit_tests;
 mod shell_completions_tests;
+mod sync_unit_tests;
 mod tail_unit_tests;
+mod timeline_unit_tests;
+mod watch_unit_tests;
@@ -251,7 +251,7 @@ fn merge_incremental_growth_with_four_fixtures() -> Result<(), Box<dyn Error>> {
 }
 
 #[test]
-fn merge_same_file_twice_adds_entries_and_upserts_metadata() -> Result<(), Box<dyn Error>> {
+fn merge_same_file_twice_skips_duplicates_and_upserts_metadata() -> Result<(), Box<dyn Error>> {
     let log_path = fixture_log_path();
     let db_file = NamedTempFile::new()?;
     let db_path = db_file.path().to_str().unwrap();
@@ -274,8 +274,8 @@ fn merge_same_file_twice_adds_entries_and_upserts_metadata() -> Result<(), Box<d
         "--db-file-path",
         db_path,
     ])
-    .stderr(output_includes("Merged 465 new log entries"))
-    .stderr(output_includes("total: 930"));
+    .stderr(output_includes("Merged 0 new log entries"))
+    .stderr(output_includes("total: 465"));
 
     Ok(())
 }
@@ -314,6 +314,37 @@ fn merge_multiple_files_at_once() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn merge_multiple_files_in_parallel() -> Result<(), Box<dyn Error>> {
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_log_to_db(fixture_log_path().to_str().unwrap(), db_path)?;
+
+    run_succeeds([
+        "logs",
+        "merge",
+        "-j",
+        "3",
+        "--input-log-file-path",
+        fixture_log_path_hare().to_str().unwrap(),
+        "--input-log-file-path",
+        fixture_log_path_cottontail().to_str().unwrap(),
+        "--input-log-file-path",
+        fixture_log_path_flopsy().to_str().unwrap(),
+        "--db-file-path",
+        db_path,
+    ])
+    .stderr(output_includes("Merged"));
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("* rabbit@fixture2"))
+        .stdout(output_includes("* rabbit@fixture3"))
+        .stdout(output_includes("* rabbit@fixture4"));
+
+    Ok(())
+}
+
 #[test]
 fn merge_directory_into_existing_database() -> Result<(), Box<dyn Error>> {
     let dir = tempfile::tempdir()?;
@@ -357,3 +388,26 @@ fn merge_directory_into_existing_database() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn merge_json_log_file_into_existing_database() -> Result<(), Box<dyn Error>> {
+    let log_path = fixture_log_path();
+    let json_log_path = fixture_log_path_json();
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    run_succeeds([
+        "logs",
+        "merge",
+        "--input-log-file-path",
+        json_log_path.to_str().unwrap(),
+        "--db-file-path",
+        db_path,
+    ])
+    .stderr(output_includes("Merged 8 new log entries"))
+    .stderr(output_includes("total: 473"));
+
+    Ok(())
+}
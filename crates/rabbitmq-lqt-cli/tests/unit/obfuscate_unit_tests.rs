@@ -12,8 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use assert_cmd::cargo::cargo_bin_cmd;
+use flate2::read::GzDecoder;
+use predicates::prelude::*;
 use std::error::Error;
-use std::fs;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+use tar::Archive;
 use tempfile::NamedTempFile;
 
 use crate::test_helpers::*;
@@ -132,3 +138,470 @@ fn obfuscate_with_silent_flag() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+fn read_bundle(path: &Path) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let mut archive = Archive::new(GzDecoder::new(File::open(path)?));
+    let mut files = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let mut content = String::new();
+        entry.read_to_string(&mut content)?;
+        files.push((name, content));
+    }
+    Ok(files)
+}
+
+#[test]
+fn obfuscate_directory_into_bundle() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::copy(fixture_log_path(), dir.path().join("rabbit@fixture1.log"))?;
+    fs::copy(
+        fixture_log_path_hare(),
+        dir.path().join("rabbit@fixture2.log"),
+    )?;
+    let bundle_path = dir.path().join("obfuscated").join("bundle.tar.gz");
+
+    run_succeeds([
+        "logs",
+        "obfuscate",
+        "--input-log-dir-path",
+        dir.path().to_str().unwrap(),
+        "--output-bundle-path",
+        bundle_path.to_str().unwrap(),
+    ]);
+
+    let files = read_bundle(&bundle_path)?;
+    assert_eq!(files.len(), 2);
+    for (name, content) in &files {
+        assert!(!name.contains("fixture"), "File names should be obfuscated");
+        assert!(
+            !content.contains("fixture1"),
+            "Hostnames should be obfuscated"
+        );
+        assert!(
+            !content.contains("fixture2"),
+            "Hostnames should be obfuscated"
+        );
+    }
+    // Both files are obfuscated with the same mapping, so the hostnames get distinct aliases
+    let names: Vec<_> = files.iter().map(|(name, _)| name.as_str()).collect();
+    assert!(names.contains(&"rabbit@host1.log"));
+    assert!(names.contains(&"rabbit@host2.log"));
+
+    Ok(())
+}
+
+#[test]
+fn obfuscate_tar_archive_into_bundle() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    let bundle_path = dir.path().join("bundle.tar.gz");
+
+    run_succeeds([
+        "logs",
+        "obfuscate",
+        "--input-log-file-path",
+        fixture_tar_gz_path().to_str().unwrap(),
+        "--output-bundle-path",
+        bundle_path.to_str().unwrap(),
+        "--silent",
+    ]);
+
+    let files = read_bundle(&bundle_path)?;
+    assert!(!files.is_empty());
+    for (name, content) in &files {
+        assert!(!name.contains("fixture"), "File names should be obfuscated");
+        assert!(
+            !content.contains("@fixture"),
+            "Hostnames should be obfuscated"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn obfuscate_tar_archive_requires_bundle_path() -> Result<(), Box<dyn Error>> {
+    let output_file = NamedTempFile::new()?;
+    let output_path = output_file.path().to_str().unwrap();
+
+    run_fails([
+        "logs",
+        "obfuscate",
+        "--input-log-file-path",
+        fixture_tar_gz_path().to_str().unwrap(),
+        "--output-log-file-path",
+        output_path,
+    ])
+    .stderr(output_includes("--output-bundle-path"));
+
+    Ok(())
+}
+
+#[test]
+fn obfuscate_directory_requires_bundle_path() -> Result<(), Box<dyn Error>> {
+    let output_file = NamedTempFile::new()?;
+    let output_path = output_file.path().to_str().unwrap();
+
+    run_fails([
+        "logs",
+        "obfuscate",
+        "--input-log-dir-path",
+        fixture_directory_path().to_str().unwrap(),
+        "--output-log-file-path",
+        output_path,
+    ])
+    .stderr(output_includes("--output-bundle-path"));
+
+    Ok(())
+}
+
+#[test]
+fn obfuscate_database() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    let input_db_path = dir.path().join("input.rlqt");
+    let output_db_path = dir.path().join("obfuscated.rlqt");
+    parse_log_to_db(
+        fixture_log_path().to_str().unwrap(),
+        input_db_path.to_str().unwrap(),
+    )?;
+
+    run_succeeds([
+        "logs",
+        "obfuscate-db",
+        "--input-db-file-path",
+        input_db_path.to_str().unwrap(),
+        "--output-db-file-path",
+        output_db_path.to_str().unwrap(),
+    ])
+    .stdout(output_includes("Entries processed"));
+
+    run_succeeds([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        output_db_path.to_str().unwrap(),
+        "--limit",
+        "50",
+        "--without-colors",
+    ])
+    .stdout(output_includes("rabbit@host1"))
+    .stdout(output_includes("fixture1").not());
+
+    run_succeeds([
+        "logs",
+        "overview",
+        "--input-db-file-path",
+        output_db_path.to_str().unwrap(),
+    ])
+    .stdout(output_includes("fixture1").not());
+
+    // The input database is left intact
+    run_succeeds([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        input_db_path.to_str().unwrap(),
+        "--limit",
+        "50",
+        "--without-colors",
+    ])
+    .stdout(output_includes("rabbit@fixture1"));
+
+    Ok(())
+}
+
+#[test]
+fn obfuscate_database_with_fully_qualified_node_names() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    let log_path = dir.path().join("mq1@broker.prod.example.com.log");
+    let input_db_path = dir.path().join("input.rlqt");
+    let output_db_path = dir.path().join("obfuscated.rlqt");
+    fs::write(
+        &log_path,
+        "2025-10-27 18:23:01.000000+00:00 [info] <0.1.0> Node mq1@broker.prod.example.com is up\n\
+         2025-10-27 18:23:02.000000+00:00 [info] <0.1.0> Notified ops@megacorp.com\n",
+    )?;
+    parse_log_to_db(log_path.to_str().unwrap(), input_db_path.to_str().unwrap())?;
+
+    run_succeeds([
+        "logs",
+        "obfuscate-db",
+        "--input-db-file-path",
+        input_db_path.to_str().unwrap(),
+        "--output-db-file-path",
+        output_db_path.to_str().unwrap(),
+    ]);
+
+    // The node column and the messages use the same alias
+    run_succeeds([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        output_db_path.to_str().unwrap(),
+        "--node",
+        "mq1@host1",
+        "--without-colors",
+    ])
+    .stdout(output_includes("Node mq1@host1 is up"))
+    .stdout(output_includes("Notified email1@example.com"))
+    .stdout(output_includes("broker.prod").not())
+    .stderr(output_includes("Found 2 matching entries"));
+
+    Ok(())
+}
+
+#[test]
+fn obfuscate_database_into_itself_fails() -> Result<(), Box<dyn Error>> {
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+    parse_log_to_db(fixture_log_path().to_str().unwrap(), db_path)?;
+
+    run_fails([
+        "logs",
+        "obfuscate-db",
+        "--input-db-file-path",
+        db_path,
+        "--output-db-file-path",
+        db_path,
+    ])
+    .stderr(output_includes("must differ"));
+
+    Ok(())
+}
+
+fn write_log_with_sensitive_values(path: &Path, hostname: &str) -> Result<(), Box<dyn Error>> {
+    fs::write(
+        path,
+        format!(
+            "2025-10-27 11:23:27.566558-07:00 [info] <0.208.0> Node rabbit@{} started\n\
+             2025-10-27 11:23:28.566558-07:00 [info] <0.300.0> queue 'orders' in vhost 'production': leader elected\n",
+            hostname
+        ),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn obfuscate_with_exported_mapping_then_deobfuscate() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    let input_path = temp_dir.path().join("rabbit@sunnyside.log");
+    let output_path = temp_dir.path().join("obfuscated.log");
+    let mapping_path = temp_dir.path().join("mapping.json");
+    let deobfuscated_path = temp_dir.path().join("deobfuscated.log");
+    write_log_with_sensitive_values(&input_path, "sunnyside")?;
+
+    run_succeeds([
+        "logs",
+        "obfuscate",
+        "--input-log-file-path",
+        input_path.to_str().unwrap(),
+        "--output-log-file-path",
+        output_path.to_str().unwrap(),
+        "--export-mapping-file",
+        mapping_path.to_str().unwrap(),
+        "--silent",
+    ]);
+
+    let mapping = fs::read_to_string(&mapping_path)?;
+    assert!(mapping.contains(r#""sunnyside": "host1""#));
+    assert!(mapping.contains(r#""orders": "queue1""#));
+
+    run_succeeds([
+        "logs",
+        "deobfuscate",
+        "--mapping-file",
+        mapping_path.to_str().unwrap(),
+        "--input-file-path",
+        output_path.to_str().unwrap(),
+        "--output-file-path",
+        deobfuscated_path.to_str().unwrap(),
+    ]);
+
+    let deobfuscated = fs::read_to_string(&deobfuscated_path)?;
+    assert_eq!(deobfuscated, fs::read_to_string(&input_path)?);
+
+    cargo_bin_cmd!("rabbitmq-lqt")
+        .args([
+            "logs",
+            "deobfuscate",
+            "--mapping-file",
+            mapping_path.to_str().unwrap(),
+        ])
+        .write_stdin("Please look at queue1 on rabbit@host1\n")
+        .assert()
+        .success()
+        .stdout("Please look at orders on rabbit@sunnyside\n");
+
+    Ok(())
+}
+
+#[test]
+fn obfuscate_with_imported_mapping_keeps_aliases() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    let first_input_path = temp_dir.path().join("rabbit@sunnyside.log");
+    let second_input_path = temp_dir.path().join("rabbit@rainyday.log");
+    let second_output_path = temp_dir.path().join("second.log");
+    let first_mapping_path = temp_dir.path().join("first.json");
+    let second_mapping_path = temp_dir.path().join("second.json");
+    write_log_with_sensitive_values(&first_input_path, "sunnyside")?;
+    write_log_with_sensitive_values(&second_input_path, "rainyday")?;
+
+    run_succeeds([
+        "logs",
+        "obfuscate",
+        "--input-log-file-path",
+        first_input_path.to_str().unwrap(),
+        "--output-log-file-path",
+        temp_dir.path().join("first.log").to_str().unwrap(),
+        "--export-mapping-file",
+        first_mapping_path.to_str().unwrap(),
+        "--silent",
+    ]);
+
+    run_succeeds([
+        "logs",
+        "obfuscate",
+        "--input-log-file-path",
+        second_input_path.to_str().unwrap(),
+        "--output-log-file-path",
+        second_output_path.to_str().unwrap(),
+        "--import-mapping-file",
+        first_mapping_path.to_str().unwrap(),
+        "--export-mapping-file",
+        second_mapping_path.to_str().unwrap(),
+        "--silent",
+    ]);
+
+    // The queue keeps its alias, the new hostname gets the next one
+    let second = fs::read_to_string(&second_output_path)?;
+    assert!(second.contains("rabbit@host2"));
+    assert!(second.contains("queue 'queue1'"));
+
+    let mapping = fs::read_to_string(&second_mapping_path)?;
+    assert!(mapping.contains(r#""sunnyside": "host1""#));
+    assert!(mapping.contains(r#""rainyday": "host2""#));
+
+    Ok(())
+}
+
+#[test]
+fn obfuscate_with_encrypted_mapping() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    let input_path = temp_dir.path().join("rabbit@sunnyside.log");
+    let mapping_path = temp_dir.path().join("mapping.json");
+    write_log_with_sensitive_values(&input_path, "sunnyside")?;
+
+    run_succeeds([
+        "logs",
+        "obfuscate",
+        "--input-log-file-path",
+        input_path.to_str().unwrap(),
+        "--output-log-file-path",
+        temp_dir.path().join("obfuscated.log").to_str().unwrap(),
+        "--export-mapping-file",
+        mapping_path.to_str().unwrap(),
+        "--mapping-passphrase",
+        "correct horse battery staple",
+        "--silent",
+    ]);
+
+    let mapping = fs::read_to_string(&mapping_path)?;
+    assert!(!mapping.contains("sunnyside"));
+
+    cargo_bin_cmd!("rabbitmq-lqt")
+        .args([
+            "logs",
+            "deobfuscate",
+            "--mapping-file",
+            mapping_path.to_str().unwrap(),
+        ])
+        .env_remove("RABBITMQ_LQT_MAPPING_PASSPHRASE")
+        .write_stdin("rabbit@host1\n")
+        .assert()
+        .failure()
+        .stderr(output_includes("passphrase is required"));
+
+    cargo_bin_cmd!("rabbitmq-lqt")
+        .args([
+            "logs",
+            "deobfuscate",
+            "--mapping-file",
+            mapping_path.to_str().unwrap(),
+        ])
+        .env(
+            "RABBITMQ_LQT_MAPPING_PASSPHRASE",
+            "correct horse battery staple",
+        )
+        .write_stdin("rabbit@host1\n")
+        .assert()
+        .success()
+        .stdout("rabbit@sunnyside\n");
+
+    Ok(())
+}
+
+#[test]
+fn obfuscate_with_policy_file() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    let input_path = temp_dir.path().join("rabbit@sunnyside.log");
+    let output_path = temp_dir.path().join("obfuscated.log");
+    let policy_path = temp_dir.path().join("policy.toml");
+    fs::write(
+        &input_path,
+        "2025-10-27 11:23:27.566558-07:00 [info] <0.300.0> user 'guest' authenticated and granted access to vhost '/'\n\
+         2025-10-27 11:23:28.566558-07:00 [info] <0.301.0> publish failed for customer_id=4242 in queue 'orders'\n",
+    )?;
+    fs::write(
+        &policy_path,
+        r#"
+disabled_categories = ["queues"]
+allowed_values = ["/", "guest"]
+
+[[custom_patterns]]
+name = "customer-ids"
+regex = 'customer_id=(\d+)'
+prefix = "customer"
+"#,
+    )?;
+
+    run_succeeds([
+        "logs",
+        "obfuscate",
+        "--input-log-file-path",
+        input_path.to_str().unwrap(),
+        "--output-log-file-path",
+        output_path.to_str().unwrap(),
+        "--policy-file",
+        policy_path.to_str().unwrap(),
+    ])
+    .stdout(output_includes("Unique custom pattern values obfuscated"));
+
+    let output = fs::read_to_string(&output_path)?;
+    assert!(output.contains("user 'guest' authenticated and granted access to vhost '/'"));
+    assert!(output.contains("customer_id=customer1 in queue 'orders'"));
+
+    Ok(())
+}
+
+#[test]
+fn obfuscate_with_invalid_policy_file() -> Result<(), Box<dyn Error>> {
+    let log_path = fixture_log_path();
+    let output_file = NamedTempFile::new()?;
+    let policy_file = NamedTempFile::new()?;
+    fs::write(policy_file.path(), "disabled_categories = [\"rabbits\"]\n")?;
+
+    run_fails([
+        "logs",
+        "obfuscate",
+        "--input-log-file-path",
+        log_path.to_str().unwrap(),
+        "--output-log-file-path",
+        output_file.path().to_str().unwrap(),
+        "--policy-file",
+        policy_file.path().to_str().unwrap(),
+    ])
+    .stderr(output_includes("Invalid obfuscation policy"));
+
+    Ok(())
+}
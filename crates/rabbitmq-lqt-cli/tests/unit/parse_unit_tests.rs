@@ -16,7 +16,9 @@ use predicates::prelude::*;
 use std::error::Error;
 use std::fs;
 use std::fs::metadata;
-use tempfile::NamedTempFile;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use tempfile::{NamedTempFile, TempDir};
 
 use crate::test_helpers::*;
 
@@ -519,3 +521,306 @@ fn parse_tar_xz_archive() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn parse_json_log_file() -> Result<(), Box<dyn Error>> {
+    let log_path = fixture_log_path_json();
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "parse",
+        "--input-log-file-path",
+        log_path.to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+    ])
+    .stderr(output_includes("8 log entries"));
+
+    run_succeeds([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        db_path,
+        "--severity",
+        "error",
+    ])
+    .stderr(output_includes("Found 1 matching entries"));
+
+    Ok(())
+}
+
+#[test]
+fn parse_plaintext_and_json_log_files_together() -> Result<(), Box<dyn Error>> {
+    let log_path = fixture_log_path();
+    let json_log_path = fixture_log_path_json();
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "parse",
+        "--input-log-file-path",
+        log_path.to_str().unwrap(),
+        "--input-log-file-path",
+        json_log_path.to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+    ])
+    .stderr(output_includes("473 log entries"));
+
+    Ok(())
+}
+
+/// Writes a log with more entries than fit into a single pipeline chunk,
+/// with a multi-line entry every 1000 entries
+fn write_large_log(path: &Path, entries: usize) -> Result<usize, Box<dyn Error>> {
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    let mut lines = 0;
+    for i in 0..entries {
+        let secs = i / 1000;
+        writeln!(
+            writer,
+            "2025-10-27 18:{:02}:{:02}.{:06}+00:00 [info] <0.{}.0> accepting AMQP connection 10.0.0.2:{}",
+            secs / 60,
+            secs % 60,
+            (i % 1000) * 1000,
+            i % 500,
+            i % 65536
+        )?;
+        lines += 1;
+        if i % 1000 == 0 {
+            writeln!(writer, "  continuation of entry {}", i)?;
+            lines += 1;
+        }
+    }
+    writer.flush()?;
+    Ok(lines)
+}
+
+#[test]
+fn parse_streams_files_larger_than_a_pipeline_chunk() -> Result<(), Box<dyn Error>> {
+    let log_dir = TempDir::new()?;
+    let log_path = log_dir.path().join("rabbit@streaming.log");
+    let total_lines = write_large_log(&log_path, 60_001)?;
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "parse",
+        "--input-log-file-path",
+        log_path.to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+    ])
+    .stderr(output_includes("stored 60001 log entries"));
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes(&format!("Total Lines: {}", total_lines)))
+        .stdout(output_includes("Total Entries: 60001"))
+        .stdout(output_includes("Oldest Entry: 2025-10-27 18:00:00 UTC"))
+        .stdout(output_includes(
+            "Most Recent Entry: 2025-10-27 18:01:00 UTC",
+        ))
+        .stdout(output_includes("* rabbit@streaming"));
+
+    Ok(())
+}
+
+fn parse_fixtures_with_parallelism(db_path: &str, parallelism: &str) {
+    let log_paths = [
+        fixture_log_path(),
+        fixture_log_path_hare(),
+        fixture_log_path_cottontail(),
+        fixture_log_path_flopsy(),
+    ];
+    let mut args = vec![
+        "logs".to_string(),
+        "parse".to_string(),
+        "--parallelism".to_string(),
+        parallelism.to_string(),
+        "--output-db-file-path".to_string(),
+        db_path.to_string(),
+    ];
+    for log_path in &log_paths {
+        args.push("--input-log-file-path".to_string());
+        args.push(log_path.to_str().unwrap().to_string());
+    }
+
+    run_succeeds(args).stderr(output_includes("log entries"));
+}
+
+fn stdout_of<const N: usize>(args: [&str; N]) -> Result<String, Box<dyn Error>> {
+    let output = run_succeeds(args).get_output().stdout.clone();
+    Ok(String::from_utf8(output)?)
+}
+
+#[test]
+fn parse_assigns_the_same_ids_regardless_of_parallelism() -> Result<(), Box<dyn Error>> {
+    let sequential_db = NamedTempFile::new()?;
+    let sequential_db_path = sequential_db.path().to_str().unwrap();
+    let parallel_db = NamedTempFile::new()?;
+    let parallel_db_path = parallel_db.path().to_str().unwrap();
+
+    parse_fixtures_with_parallelism(sequential_db_path, "1");
+    parse_fixtures_with_parallelism(parallel_db_path, "4");
+
+    for db_path in [sequential_db_path, parallel_db_path] {
+        run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+            .stdout(output_includes("* rabbit@fixture1"))
+            .stdout(output_includes("* rabbit@fixture4"));
+    }
+
+    let query = |db_path: &str| {
+        stdout_of([
+            "logs",
+            "query",
+            "--input-db-file-path",
+            db_path,
+            "--limit",
+            "100000",
+            "--without-colors",
+        ])
+    };
+    assert_eq!(query(sequential_db_path)?, query(parallel_db_path)?);
+
+    let overview = |db_path: &str| stdout_of(["logs", "overview", "--input-db-file-path", db_path]);
+    assert_eq!(overview(sequential_db_path)?, overview(parallel_db_path)?);
+
+    Ok(())
+}
+
+#[test]
+fn parse_with_parallelism_reports_errors_of_any_file() -> Result<(), Box<dyn Error>> {
+    let log_dir = TempDir::new()?;
+    let broken_log_path = log_dir.path().join("rabbit@broken.log");
+    fs::write(
+        &broken_log_path,
+        b"2025-10-27 18:23:00.000000+00:00 [info] <0.1.0> starting\n\xff\xfe\n",
+    )?;
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_fails([
+        "logs",
+        "parse",
+        "-j",
+        "2",
+        "--input-log-file-path",
+        fixture_log_path().to_str().unwrap(),
+        "--input-log-file-path",
+        broken_log_path.to_str().unwrap(),
+        "--input-log-file-path",
+        fixture_log_path_hare().to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+    ])
+    .stderr(output_includes("Failed to read log file at line 2"));
+
+    Ok(())
+}
+
+#[test]
+fn parse_rejects_zero_parallelism() -> Result<(), Box<dyn Error>> {
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_fails([
+        "logs",
+        "parse",
+        "--parallelism",
+        "0",
+        "--input-log-file-path",
+        fixture_log_path().to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+    ])
+    .stderr(output_includes("--parallelism"));
+
+    Ok(())
+}
+
+fn write_log_lines(path: &Path, lines: &[(&str, &str)]) -> Result<(), Box<dyn Error>> {
+    let content: String = lines
+        .iter()
+        .map(|(time, message)| {
+            format!(
+                "2025-10-27 {}.000000+00:00 [info] <0.1.0> {}\n",
+                time, message
+            )
+        })
+        .collect();
+    fs::write(path, content)?;
+    Ok(())
+}
+
+#[test]
+fn parse_orders_rotated_files_and_skips_overlapping_entries() -> Result<(), Box<dyn Error>> {
+    let log_dir = TempDir::new()?;
+    write_log_lines(
+        &log_dir.path().join("rabbit@n1.log"),
+        &[("18:00:04", "newest message")],
+    )?;
+    write_log_lines(
+        &log_dir.path().join("rabbit@n1.log.1"),
+        &[
+            ("18:00:02", "older message"),
+            ("18:00:03", "middle message"),
+        ],
+    )?;
+    write_log_lines(
+        &log_dir.path().join("rabbit@n1.log.2"),
+        &[
+            ("18:00:01", "oldest message"),
+            ("18:00:02", "older message"),
+        ],
+    )?;
+    write_log_lines(
+        &log_dir.path().join("rabbit@n2.log"),
+        &[("18:00:02", "message of another node")],
+    )?;
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "parse",
+        "--input-log-dir-path",
+        log_dir.path().to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+    ])
+    .stderr(output_includes("stored 5 log entries"));
+
+    let stdout = stdout_of([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "* | project id, message",
+        "--without-colors",
+    ])?;
+    let messages_by_id: Vec<_> = stdout
+        .lines()
+        .filter_map(|line| {
+            let cells: Vec<_> = line.split('│').map(str::trim).collect();
+            let id = cells.get(1)?.parse::<i64>().ok()?;
+            Some((id, cells.get(2)?.to_string()))
+        })
+        .collect();
+    assert_eq!(
+        messages_by_id,
+        vec![
+            (1, "oldest message".to_string()),
+            (2, "older message".to_string()),
+            (3, "message of another node".to_string()),
+            (4, "middle message".to_string()),
+            (5, "newest message".to_string()),
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use predicates::prelude::*;
+use std::error::Error;
+use tempfile::NamedTempFile;
+
+use crate::test_helpers::*;
+
+fn setup_json_test_db() -> Result<NamedTempFile, Box<dyn Error>> {
+    let log_path = fixture_log_path_json();
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    Ok(db_file)
+}
+
+#[test]
+fn patterns_lists_most_frequent_templates() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "patterns",
+        "--input-db-file-path",
+        db_path,
+        "--without-colors",
+    ])
+    .stderr(output_includes("Found 8 patterns"))
+    .stdout(output_includes("accepting"))
+    .stdout(output_includes("10.0.0.2").not());
+
+    Ok(())
+}
+
+#[test]
+fn patterns_with_limit() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "patterns",
+        "--input-db-file-path",
+        db_path,
+        "--limit",
+        "2",
+    ])
+    .stderr(output_includes("Found 2 patterns"));
+
+    Ok(())
+}
+
+#[test]
+fn ql_count_by_pattern() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "* | count by pattern",
+        "--without-colors",
+    ])
+    .stderr(output_includes("matching rows"))
+    .stdout(output_includes("pattern"))
+    .stdout(output_includes("<ip>"));
+
+    Ok(())
+}
+
+#[test]
+fn ql_filters_by_pattern() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        r#"pattern =~ "^accepting AMQP connection""#,
+        "--without-colors",
+    ])
+    .stderr(output_includes("Found 1 matching"));
+
+    Ok(())
+}
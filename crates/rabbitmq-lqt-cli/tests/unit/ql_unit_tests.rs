@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use predicates::prelude::*;
 use std::error::Error;
 use tempfile::NamedTempFile;
 
@@ -82,6 +83,107 @@ fn ql_query_with_limit() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn ql_query_with_sort_on_several_fields() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "* | sort severity desc, id desc | head 1 | project id, severity",
+        "--without-colors",
+    ])
+    .stdout(output_includes("444"))
+    .stdout(output_includes("notice"));
+
+    run_fails([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "* | sort labels",
+    ])
+    .stderr(output_includes("sorting by labels"));
+
+    Ok(())
+}
+
+#[test]
+fn ql_query_with_context_stage() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "severity = notice | context 1 by pid",
+        "--without-colors",
+    ])
+    .stderr(output_includes("Found 36 matching entries"));
+
+    run_fails([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "severity = notice | context 1 | count",
+    ])
+    .stderr(output_includes("context with count"));
+
+    Ok(())
+}
+
+#[test]
+fn ql_query_with_severity_threshold() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "severity > info | count by severity",
+        "--without-colors",
+    ])
+    .stdout(output_includes("notice"))
+    .stdout(output_includes("14"))
+    .stdout(output_includes("debug").not());
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "severity < info | count",
+        "--without-colors",
+    ])
+    .stdout(output_includes("406"));
+
+    run_fails([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "severity >= fatal",
+    ])
+    .stderr(output_includes("Invalid severity level 'fatal'"));
+
+    Ok(())
+}
+
 #[test]
 fn ql_query_with_severity_and_limit() -> Result<(), Box<dyn Error>> {
     let db_file = setup_test_db()?;
@@ -253,6 +355,65 @@ fn ql_query_with_time_range() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn ql_query_with_time_range_anchored_to_now() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--time-anchor",
+        "now",
+        "--query",
+        "@1h",
+    ])
+    .stderr(output_includes("Found 0 matching entries"));
+
+    Ok(())
+}
+
+#[test]
+fn ql_query_with_reference_time_before_log_set() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--reference-time",
+        "2000-01-01 00:00:00",
+        "--query",
+        "@1h",
+    ])
+    .stderr(output_includes("Found 0 matching entries"));
+
+    Ok(())
+}
+
+#[test]
+fn ql_query_with_invalid_reference_time() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_fails([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--reference-time",
+        "not-a-date-at-all",
+        "--query",
+        "@1h",
+    ]);
+
+    Ok(())
+}
+
 #[test]
 fn ql_query_with_boolean_logic() -> Result<(), Box<dyn Error>> {
     let db_file = setup_test_db()?;
@@ -341,3 +502,152 @@ fn ql_query_with_hashtag_label_combination() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn ql_query_count_by_severity() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "* | count by severity",
+        "--without-colors",
+    ])
+    .stderr(output_includes("matching rows"))
+    .stdout(output_includes("severity"))
+    .stdout(output_includes("count"))
+    .stdout(output_includes("info"));
+
+    Ok(())
+}
+
+#[test]
+fn ql_query_count_without_field() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "* | count",
+        "--without-colors",
+    ])
+    .stderr(output_includes("Found 1 matching rows"))
+    .stdout(output_includes("count"));
+
+    Ok(())
+}
+
+#[test]
+fn ql_query_distinct_nodes() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "* | distinct node",
+        "--without-colors",
+    ])
+    .stderr(output_includes("Found 1 matching rows"))
+    .stdout(output_includes("rabbit@fixture1"));
+
+    Ok(())
+}
+
+#[test]
+fn ql_query_project_fields() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "* | project id, severity | limit 3",
+        "--without-colors",
+    ])
+    .stderr(output_includes("Found 3 matching rows"))
+    .stdout(output_includes("id"))
+    .stdout(output_includes("severity"));
+
+    Ok(())
+}
+
+fn setup_json_test_db() -> Result<NamedTempFile, Box<dyn Error>> {
+    let log_path = fixture_log_path_json();
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    Ok(db_file)
+}
+
+#[test]
+fn ql_query_vhost_selector() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        r#"{vhost="vhost1"}"#,
+    ])
+    .stderr(output_includes("Found 4 matching entries"));
+
+    Ok(())
+}
+
+#[test]
+fn ql_query_mfa_and_source_line() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        r#"mfa =~ "^rabbit_prelaunch" and line > 100"#,
+    ])
+    .stderr(output_includes("Found 1 matching entries"));
+
+    Ok(())
+}
+
+#[test]
+fn ql_query_count_by_user() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        "user != \"\" | count by user",
+        "--without-colors",
+    ])
+    .stderr(output_includes("Found 2 matching rows"))
+    .stdout(output_includes("user3"));
+
+    Ok(())
+}
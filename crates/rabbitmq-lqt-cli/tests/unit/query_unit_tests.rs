@@ -323,3 +323,118 @@ fn query_with_both_url_filters() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn query_with_sort() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        db_path,
+        "--sort",
+        "severity:desc,id:desc",
+        "--limit",
+        "2",
+        "--without-colors",
+    ])
+    .stdout(output_includes("starting migration of 20 tables"))
+    .stdout(output_includes("`ram_node_type` enabled"))
+    .stderr(output_includes("Found 2 matching entries"));
+
+    run_fails([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        db_path,
+        "--sort",
+        "labels",
+    ])
+    .stderr(output_includes("Cannot sort by 'labels'"));
+
+    Ok(())
+}
+
+#[test]
+fn query_with_min_severity() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        db_path,
+        "--min-severity",
+        "info",
+        "--without-colors",
+    ])
+    .stderr(output_includes("Found 59 matching entries"));
+
+    run_succeeds([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        db_path,
+        "--min-severity",
+        "notice",
+        "--severity",
+        "info",
+    ])
+    .stderr(output_includes("Found 0 matching entries"));
+
+    run_fails([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        db_path,
+        "--min-severity",
+        "fatal",
+    ])
+    .stderr(output_includes("invalid value 'fatal'"));
+
+    Ok(())
+}
+
+#[test]
+fn query_with_search() -> Result<(), Box<dyn Error>> {
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "parse",
+        "--input-log-file-path",
+        fixture_log_path_cottontail().to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+        "--build-search-index",
+        "--silent",
+    ]);
+
+    run_succeeds([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        db_path,
+        "--search",
+        "\"accepting amqp conn*\"",
+        "--without-colors",
+    ])
+    .stdout(output_includes("accepting AMQP connection"))
+    .stderr(output_includes("Found"));
+
+    run_fails([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        db_path,
+        "--search",
+        "...",
+    ])
+    .stderr(output_includes("has no words to look for"));
+
+    Ok(())
+}
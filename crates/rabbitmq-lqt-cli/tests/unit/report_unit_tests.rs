@@ -0,0 +1,95 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::error::Error;
+use tempfile::NamedTempFile;
+
+use crate::test_helpers::*;
+
+fn setup_test_db(log_path: std::path::PathBuf) -> Result<NamedTempFile, Box<dyn Error>> {
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    Ok(db_file)
+}
+
+#[test]
+fn report_lists_findings() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db(fixture_log_path_cottontail())?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "report",
+        "--input-db-file-path",
+        db_path,
+        "--without-colors",
+    ])
+    .stderr(output_includes("findings"))
+    .stdout(output_includes("repeated_elections"))
+    .stdout(output_includes("deprecated_features"));
+
+    Ok(())
+}
+
+#[test]
+fn report_in_markdown() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db(fixture_log_path_cottontail())?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "report",
+        "--input-db-file-path",
+        db_path,
+        "--format",
+        "markdown",
+    ])
+    .stdout(output_includes("# Findings"))
+    .stdout(output_includes("repeated_elections (warning)"))
+    .stdout(output_includes("Evidence entry IDs:"));
+
+    Ok(())
+}
+
+#[test]
+fn report_in_json() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db(fixture_log_path_cottontail())?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "report",
+        "--input-db-file-path",
+        db_path,
+        "--format",
+        "json",
+    ])
+    .stdout(output_includes(r#""check": "repeated_elections""#))
+    .stdout(output_includes(r#""evidence": ["#));
+
+    Ok(())
+}
+
+#[test]
+fn report_without_findings() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_test_db(fixture_log_path_json())?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds(["logs", "report", "--input-db-file-path", db_path])
+        .stdout(output_includes("No findings."));
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+use tempfile::NamedTempFile;
+
+use crate::test_helpers::*;
+
+fn setup_annotated_json_test_db() -> Result<NamedTempFile, Box<dyn Error>> {
+    let log_path = fixture_log_path_json();
+    let rules_path = fixture_rules_path();
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "parse",
+        "--input-log-file-path",
+        log_path.to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+        "--rules-file",
+        rules_path.to_str().unwrap(),
+        "--silent",
+    ]);
+
+    Ok(db_file)
+}
+
+#[test]
+fn parse_with_rules_file_assigns_custom_labels() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_annotated_json_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        r#"labels any ["custom:orders"]"#,
+        "--without-colors",
+    ])
+    .stdout(output_includes("custom:needs-triage"))
+    .stdout(output_includes("https://wiki.example.com/orders-queue"))
+    .stdout(output_includes("custom:client-disconnects").not());
+
+    Ok(())
+}
+
+#[test]
+fn parse_without_rules_file_assigns_no_custom_labels() -> Result<(), Box<dyn Error>> {
+    let log_path = fixture_log_path_json();
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        r#"labels any ["custom:orders"]"#,
+        "--without-colors",
+    ])
+    .stdout(output_includes("No matching log entries found"));
+
+    Ok(())
+}
+
+#[test]
+fn merge_with_rules_file_assigns_custom_labels() -> Result<(), Box<dyn Error>> {
+    let rules_path = fixture_rules_path();
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_log_to_db(fixture_log_path().to_str().unwrap(), db_path)?;
+
+    run_succeeds([
+        "logs",
+        "merge",
+        "--input-log-file-path",
+        fixture_log_path_json().to_str().unwrap(),
+        "--db-file-path",
+        db_path,
+        "--rules-file",
+        rules_path.to_str().unwrap(),
+    ]);
+
+    run_succeeds([
+        "logs",
+        "ql",
+        "--input-db-file-path",
+        db_path,
+        "--query",
+        r#"labels any ["custom:client-disconnects"]"#,
+        "--without-colors",
+    ])
+    .stdout(output_includes("custom:client-disconnects"));
+
+    Ok(())
+}
+
+#[test]
+fn tail_with_rules_file_shows_custom_labels() -> Result<(), Box<dyn Error>> {
+    let log_path = fixture_log_path_json();
+    let rules_path = fixture_rules_path();
+
+    run_succeeds([
+        "logs",
+        "tail",
+        "--input-log-file-path",
+        log_path.to_str().unwrap(),
+        "--rules-file",
+        rules_path.to_str().unwrap(),
+        "--without-colors",
+    ])
+    .stdout(output_includes("custom:client-disconnects"))
+    .stdout(output_includes("custom:orders"));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_rules_file_fails() -> Result<(), Box<dyn Error>> {
+    let log_path = fixture_log_path_json();
+    let rules_file = NamedTempFile::new()?;
+    fs::write(
+        rules_file.path(),
+        "[[rules]]\nname = \"billing\"\nwhen = { contains = \"billing\" }\nthen = { labels = [\"billing\"] }\n",
+    )?;
+    let db_file = NamedTempFile::new()?;
+
+    run_fails([
+        "logs",
+        "parse",
+        "--input-log-file-path",
+        log_path.to_str().unwrap(),
+        "--output-db-file-path",
+        db_file.path().to_str().unwrap(),
+        "--rules-file",
+        rules_file.path().to_str().unwrap(),
+    ])
+    .stderr(output_includes("Invalid annotation rules"))
+    .stderr(output_includes("unknown label 'billing'"));
+
+    Ok(())
+}
+
+#[test]
+fn nonexistent_rules_file_fails() -> Result<(), Box<dyn Error>> {
+    let log_path = fixture_log_path_json();
+    let db_file = NamedTempFile::new()?;
+
+    run_fails([
+        "logs",
+        "parse",
+        "--input-log-file-path",
+        log_path.to_str().unwrap(),
+        "--output-db-file-path",
+        db_file.path().to_str().unwrap(),
+        "--rules-file",
+        "/nonexistent/annotation_rules.toml",
+    ]);
+
+    Ok(())
+}
@@ -0,0 +1,277 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use tempfile::TempDir;
+
+use crate::test_helpers::*;
+
+fn log_lines(seconds: &[u32]) -> String {
+    seconds
+        .iter()
+        .map(|second| {
+            format!(
+                "2025-10-27 18:23:{:02}.000000+00:00 [info] <0.1.0> message {}\n",
+                second, second
+            )
+        })
+        .collect()
+}
+
+fn append_log_lines(path: &Path, seconds: &[u32]) -> Result<(), Box<dyn Error>> {
+    let mut file = OpenOptions::new().append(true).open(path)?;
+    file.write_all(log_lines(seconds).as_bytes())?;
+    Ok(())
+}
+
+fn merge(log_path: &Path, db_path: &str) -> assert_cmd::assert::Assert {
+    run_succeeds([
+        "logs",
+        "merge",
+        "--input-log-file-path",
+        log_path.to_str().unwrap(),
+        "--db-file-path",
+        db_path,
+    ])
+}
+
+#[test]
+fn merge_only_ingests_appended_entries() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let log_path = temp_dir.path().join("rabbit@growing.log");
+    let db_path = temp_dir.path().join("logs.db");
+    let db_path = db_path.to_str().unwrap();
+    fs::write(&log_path, log_lines(&[1, 2, 3]))?;
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    append_log_lines(&log_path, &[4, 5])?;
+    merge(&log_path, db_path)
+        .stderr(output_includes("Merged 2 new log entries"))
+        .stderr(output_includes("total: 5"));
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("Total Lines: 5"))
+        .stdout(output_includes("Total Entries: 5"))
+        .stdout(output_includes("Oldest Entry: 2025-10-27 18:23:01 UTC"))
+        .stdout(output_includes(
+            "Most Recent Entry: 2025-10-27 18:23:05 UTC",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn merge_skips_unchanged_files() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let log_path = temp_dir.path().join("rabbit@steady.log");
+    let db_path = temp_dir.path().join("logs.db");
+    let db_path = db_path.to_str().unwrap();
+    fs::write(&log_path, log_lines(&[1, 2]))?;
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    merge(&log_path, db_path)
+        .stderr(output_includes("nothing was appended to it"))
+        .stderr(output_includes("Merged 0 new log entries"));
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("Total Lines: 2"))
+        .stdout(output_includes("Total Entries: 2"));
+
+    Ok(())
+}
+
+#[test]
+fn merge_reads_a_truncated_file_from_the_start() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let log_path = temp_dir.path().join("rabbit@truncated.log");
+    let db_path = temp_dir.path().join("logs.db");
+    let db_path = db_path.to_str().unwrap();
+    fs::write(&log_path, log_lines(&[1, 2, 3]))?;
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    fs::write(&log_path, log_lines(&[7]))?;
+    merge(&log_path, db_path)
+        .stderr(output_includes("reading it from the start"))
+        .stderr(output_includes("Merged 1 new log entries"));
+
+    // The metadata of the file only describes its current contents
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("Total Lines: 1"))
+        .stdout(output_includes("Total Entries: 1"))
+        .stdout(output_includes("Oldest Entry: 2025-10-27 18:23:07 UTC"))
+        .stdout(output_includes(
+            "Most Recent Entry: 2025-10-27 18:23:07 UTC",
+        ));
+
+    append_log_lines(&log_path, &[8])?;
+    merge(&log_path, db_path).stderr(output_includes("Merged 1 new log entries"));
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("Total Lines: 2"))
+        .stdout(output_includes("Total Entries: 2"));
+
+    Ok(())
+}
+
+#[test]
+fn merge_keeps_the_ids_of_stored_entries() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let stored_path = temp_dir.path().join("rabbit@stored.log");
+    let merged_path = temp_dir.path().join("rabbit@merged.log");
+    let db_path = temp_dir.path().join("logs.db");
+    let db_path = db_path.to_str().unwrap();
+    fs::write(&stored_path, log_lines(&[1, 3]))?;
+    fs::write(&merged_path, log_lines(&[4, 2]))?;
+    parse_log_to_db(stored_path.to_str().unwrap(), db_path)?;
+
+    merge(&merged_path, db_path).stderr(output_includes("Merged 2 new log entries"));
+
+    // Merged entries are appended in timestamp order
+    for (id, message) in [
+        (1, "message 1"),
+        (2, "message 3"),
+        (3, "message 2"),
+        (4, "message 4"),
+    ] {
+        run_succeeds([
+            "logs",
+            "context",
+            "--input-db-file-path",
+            db_path,
+            "--id",
+            &id.to_string(),
+            "--before",
+            "0",
+            "--after",
+            "0",
+            "--without-colors",
+        ])
+        .stdout(output_includes(message));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn merge_and_sync_keep_the_lines_of_an_entry_resumed_in_the_middle() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let log_path = temp_dir.path().join("rabbit@crashing.log");
+    let db_path = temp_dir.path().join("logs.db");
+    let db_path = db_path.to_str().unwrap();
+    fs::write(
+        &log_path,
+        log_lines(&[1])
+            + "2025-10-27 18:23:02.000000+00:00 [error] <0.1.0> crash report:\n  reason: boom\n",
+    )?;
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    let mut file = OpenOptions::new().append(true).open(&log_path)?;
+    file.write_all(b"  stacktrace: [rabbit_channel, terminate]\n")?;
+    run_succeeds(["logs", "sync", "--db-file-path", db_path])
+        .stderr(output_includes("Synced 0 new log entries"));
+
+    file.write_all(b"  offender: <0.1.0>\n")?;
+    append_log_lines(&log_path, &[3])?;
+    merge(&log_path, db_path)
+        .stderr(output_includes("Merged 1 new log entries"))
+        .stderr(output_includes("total: 3"));
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("Total Lines: 6"))
+        .stdout(output_includes("Total Entries: 3"));
+    run_succeeds([
+        "logs",
+        "context",
+        "--input-db-file-path",
+        db_path,
+        "--id",
+        "2",
+        "--before",
+        "0",
+        "--after",
+        "0",
+        "--without-colors",
+    ])
+    .stdout(output_includes("reason: boom"))
+    .stdout(output_includes("stacktrace: [rabbit_channel, terminate]"))
+    .stdout(output_includes("offender: <0.1.0>"));
+
+    Ok(())
+}
+
+#[test]
+fn sync_ingests_appended_entries_of_every_imported_file() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let log_path1 = temp_dir.path().join("rabbit@sync1.log");
+    let log_path2 = temp_dir.path().join("rabbit@sync2.log");
+    let db_path = temp_dir.path().join("logs.db");
+    let db_path = db_path.to_str().unwrap();
+    fs::write(&log_path1, log_lines(&[1, 2]))?;
+    fs::write(&log_path2, log_lines(&[3]))?;
+    run_succeeds([
+        "logs",
+        "parse",
+        "--input-log-file-path",
+        log_path1.to_str().unwrap(),
+        log_path2.to_str().unwrap(),
+        "--output-db-file-path",
+        db_path,
+    ]);
+
+    append_log_lines(&log_path1, &[4])?;
+    append_log_lines(&log_path2, &[5, 6])?;
+    run_succeeds(["logs", "sync", "--db-file-path", db_path])
+        .stderr(output_includes("Synced 3 new log entries from 2 files"))
+        .stderr(output_includes("total: 6"));
+
+    run_succeeds(["logs", "sync", "--db-file-path", db_path])
+        .stderr(output_includes("Synced 0 new log entries"));
+
+    run_succeeds(["logs", "overview", "--input-db-file-path", db_path])
+        .stdout(output_includes("* rabbit@sync1"))
+        .stdout(output_includes("* rabbit@sync2"))
+        .stdout(output_includes("Total Entries: 3"));
+
+    Ok(())
+}
+
+#[test]
+fn sync_skips_files_that_no_longer_exist() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let log_path = temp_dir.path().join("rabbit@gone.log");
+    let db_path = temp_dir.path().join("logs.db");
+    let db_path = db_path.to_str().unwrap();
+    fs::write(&log_path, log_lines(&[1]))?;
+    parse_log_to_db(log_path.to_str().unwrap(), db_path)?;
+
+    fs::remove_file(&log_path)?;
+    run_succeeds(["logs", "sync", "--db-file-path", db_path])
+        .stderr(output_includes("the file no longer exists"))
+        .stderr(output_includes("Synced 0 new log entries from 0 files"));
+
+    Ok(())
+}
+
+#[test]
+fn sync_into_nonexistent_database_fails() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let db_path = temp_dir.path().join("missing.db");
+
+    run_fails(["logs", "sync", "--db-file-path", db_path.to_str().unwrap()])
+        .stderr(output_includes("Database file not found"));
+
+    Ok(())
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use predicates::prelude::*;
 use std::error::Error;
 use std::fs::OpenOptions;
 use std::io::Write;
@@ -38,6 +39,31 @@ fn tail_default_shows_last_10_entries() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn tail_with_severity_and_min_severity_uses_the_more_severe_level() -> Result<(), Box<dyn Error>> {
+    let log_path = fixture_log_path();
+
+    for (severity, min_severity) in [("notice", "debug"), ("debug", "notice")] {
+        run_succeeds([
+            "logs",
+            "tail",
+            "--input-log-file-path",
+            log_path.to_str().unwrap(),
+            "-n",
+            "1000",
+            "--severity",
+            severity,
+            "--min-severity",
+            min_severity,
+            "--without-colors",
+        ])
+        .stdout(output_includes("│ notice "))
+        .stdout(output_includes("│ info ").not());
+    }
+
+    Ok(())
+}
+
 #[test]
 fn tail_with_custom_line_count() -> Result<(), Box<dyn Error>> {
     let log_path = fixture_log_path();
@@ -263,3 +289,22 @@ fn tail_follow_with_appended_entries() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn tail_json_log_file() -> Result<(), Box<dyn Error>> {
+    let log_path = fixture_log_path_json();
+
+    run_succeeds([
+        "logs",
+        "tail",
+        "--input-log-file-path",
+        log_path.to_str().unwrap(),
+        "-n",
+        "1",
+        "--without-colors",
+    ])
+    .stdout(output_includes("ID"))
+    .stdout(output_includes("declared"));
+
+    Ok(())
+}
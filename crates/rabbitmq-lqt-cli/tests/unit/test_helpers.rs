@@ -56,6 +56,14 @@ pub fn fixture_log_path_flopsy() -> PathBuf {
         .join("rabbit@fixture4.log")
 }
 
+pub fn fixture_log_path_json() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("json")
+        .join("rabbit@fixture5.log")
+}
+
 pub fn fixture_log_path_gzip() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .join("tests")
@@ -88,6 +96,14 @@ pub fn fixture_tar_xz_path() -> PathBuf {
         .join("fixtures34.log.tar.xz")
 }
 
+pub fn fixture_rules_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("rules")
+        .join("annotation_rules.toml")
+}
+
 pub fn parse_log_to_db(log_path: &str, db_path: &str) -> Result<(), Box<dyn Error>> {
     cargo_bin_cmd!("rabbitmq-lqt")
         .args([
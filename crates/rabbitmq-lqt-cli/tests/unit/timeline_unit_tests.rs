@@ -0,0 +1,112 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use predicates::prelude::*;
+use std::error::Error;
+use tempfile::NamedTempFile;
+
+use crate::test_helpers::*;
+
+fn setup_two_node_test_db() -> Result<NamedTempFile, Box<dyn Error>> {
+    let db_file = NamedTempFile::new()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    parse_log_to_db(fixture_log_path().to_str().unwrap(), db_path)?;
+    run_succeeds([
+        "logs",
+        "merge",
+        "--input-log-file-path",
+        fixture_log_path_hare().to_str().unwrap(),
+        "--db-file-path",
+        db_path,
+        "--silent",
+    ]);
+
+    Ok(db_file)
+}
+
+#[test]
+fn timeline_merges_all_nodes() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_two_node_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "timeline",
+        "--input-db-file-path",
+        db_path,
+        "--without-colors",
+    ])
+    .stderr(output_includes("across 2 nodes"))
+    .stdout(output_includes("rabbit@fixture1"))
+    .stdout(output_includes("rabbit@fixture2"));
+
+    Ok(())
+}
+
+#[test]
+fn timeline_transitions_only() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_two_node_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "timeline",
+        "--input-db-file-path",
+        db_path,
+        "--transitions-only",
+        "--without-colors",
+    ])
+    .stdout(output_includes("[leader_change]"))
+    .stdout(output_includes("debug:").not());
+
+    Ok(())
+}
+
+#[test]
+fn timeline_of_a_single_node() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_two_node_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_succeeds([
+        "logs",
+        "timeline",
+        "--input-db-file-path",
+        db_path,
+        "--node",
+        "rabbit@fixture2",
+        "--without-colors",
+    ])
+    .stderr(output_includes("across 1 nodes"))
+    .stdout(output_includes("rabbit@fixture1").not());
+
+    Ok(())
+}
+
+#[test]
+fn timeline_rejects_invalid_burst_window() -> Result<(), Box<dyn Error>> {
+    let db_file = setup_two_node_test_db()?;
+    let db_path = db_file.path().to_str().unwrap();
+
+    run_fails([
+        "logs",
+        "timeline",
+        "--input-db-file-path",
+        db_path,
+        "--burst-window",
+        "soon",
+    ]);
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Child, Command, Output, Stdio};
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+use crate::test_helpers::*;
+
+/// Comfortably longer than the poll interval the tests watch with
+const SETTLE_TIME: Duration = Duration::from_millis(800);
+
+fn log_line(second: u32, message: &str) -> String {
+    format!(
+        "2025-10-27 18:23:{:02}.000000+00:00 [info] <0.1.0> {}\n",
+        second, message
+    )
+}
+
+fn append(path: &Path, content: &str) -> Result<(), Box<dyn Error>> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+fn start_watching(log_dir: &Path, db_path: &Path) -> Result<Child, Box<dyn Error>> {
+    let child = Command::new(assert_cmd::cargo::cargo_bin!("rabbitmq-lqt"))
+        .args([
+            "logs",
+            "watch",
+            "--input-log-dir-path",
+            log_dir.to_str().unwrap(),
+            "--db-file-path",
+            db_path.to_str().unwrap(),
+            "--poll-interval",
+            "100",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    thread::sleep(SETTLE_TIME);
+    Ok(child)
+}
+
+fn stop_watching(child: Child) -> Result<Output, Box<dyn Error>> {
+    Command::new("kill")
+        .args(["-s", "INT", &child.id().to_string()])
+        .status()?;
+    let output = child.wait_with_output()?;
+    assert!(output.status.success(), "{:?}", output);
+    Ok(output)
+}
+
+#[test]
+fn watch_ingests_appended_entries_and_new_files() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let log_dir = temp_dir.path().join("logs");
+    fs::create_dir(&log_dir)?;
+    let db_path = temp_dir.path().join("watched.db");
+    let log_path = log_dir.join("rabbit@watched1.log");
+    append(&log_path, &log_line(1, "first"))?;
+
+    let child = start_watching(&log_dir, &db_path)?;
+
+    // The last line is still being written
+    append(&log_path, &log_line(2, "second"))?;
+    append(
+        &log_path,
+        "2025-10-27 18:23:03.000000+00:00 [info] <0.1.0> thi",
+    )?;
+    thread::sleep(SETTLE_TIME);
+    append(&log_path, "rd\n")?;
+    append(&log_dir.join("rabbit@watched2.log"), &log_line(4, "fourth"))?;
+    thread::sleep(SETTLE_TIME);
+
+    let output = stop_watching(child)?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Stored 1 new log entries (total: 1)"),
+        "{}",
+        stderr
+    );
+
+    run_succeeds([
+        "logs",
+        "overview",
+        "--input-db-file-path",
+        db_path.to_str().unwrap(),
+    ])
+    .stdout(output_includes("* rabbit@watched1"))
+    .stdout(output_includes("* rabbit@watched2"));
+    run_succeeds([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        db_path.to_str().unwrap(),
+        "--without-colors",
+    ])
+    .stdout(output_includes("third"))
+    .stderr(output_includes("Found 4 matching entries"));
+
+    Ok(())
+}
+
+#[test]
+fn watch_keeps_multiline_entries_written_in_two_parts() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let log_dir = temp_dir.path().join("logs");
+    fs::create_dir(&log_dir)?;
+    let db_path = temp_dir.path().join("watched.db");
+    let log_path = log_dir.join("rabbit@multiline.log");
+    append(&log_path, &log_line(1, "first"))?;
+
+    let child = start_watching(&log_dir, &db_path)?;
+
+    // A poll lands in the middle of a crash report
+    append(
+        &log_path,
+        &(log_line(2, "crash report:")
+            + "  reason: channel_termination_timeout
+"),
+    )?;
+    thread::sleep(SETTLE_TIME);
+    append(
+        &log_path,
+        &("  stacktrace: [rabbit_channel, terminate]
+"
+        .to_string()
+            + &log_line(3, "after")),
+    )?;
+    thread::sleep(SETTLE_TIME);
+
+    let output = stop_watching(child)?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("Orphaned continuation line"), "{}", stderr);
+
+    run_succeeds([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        db_path.to_str().unwrap(),
+        "--without-colors",
+    ])
+    .stdout(output_includes("reason: channel_termination_timeout"))
+    .stdout(output_includes("stacktrace: [rabbit_channel, terminate]"))
+    .stderr(output_includes("Found 3 matching entries"));
+    run_succeeds([
+        "logs",
+        "overview",
+        "--input-db-file-path",
+        db_path.to_str().unwrap(),
+    ])
+    .stdout(output_includes("Total Lines: 5"));
+
+    Ok(())
+}
+
+#[test]
+fn watch_reads_rotated_files_without_duplicates() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let log_dir = temp_dir.path().join("logs");
+    fs::create_dir(&log_dir)?;
+    let db_path = temp_dir.path().join("watched.db");
+    let log_path = log_dir.join("rabbit@rotated.log");
+    append(&log_path, &(log_line(1, "first") + &log_line(2, "second")))?;
+
+    let child = start_watching(&log_dir, &db_path)?;
+
+    fs::rename(&log_path, log_dir.join("rabbit@rotated.log.1"))?;
+    append(&log_path, &log_line(3, "third"))?;
+    thread::sleep(SETTLE_TIME);
+
+    let output = stop_watching(child)?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("reading it from the start"), "{}", stderr);
+    assert!(
+        stderr.contains("Skipped 2 entries of"),
+        "The rotated file should have been deduplicated: {}",
+        stderr
+    );
+
+    run_succeeds([
+        "logs",
+        "query",
+        "--input-db-file-path",
+        db_path.to_str().unwrap(),
+        "--without-colors",
+    ])
+    .stdout(output_includes("third"))
+    .stderr(output_includes("Found 3 matching entries"));
+
+    Ok(())
+}
+
+#[test]
+fn watch_nonexistent_directory_fails() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let db_path = temp_dir.path().join("watched.db");
+
+    run_fails([
+        "logs",
+        "watch",
+        "--input-log-dir-path",
+        temp_dir.path().join("missing").to_str().unwrap(),
+        "--db-file-path",
+        db_path.to_str().unwrap(),
+    ])
+    .stderr(output_includes("Directory not found"));
+
+    assert!(!db_path.exists());
+
+    Ok(())
+}
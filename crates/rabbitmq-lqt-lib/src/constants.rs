@@ -19,6 +19,13 @@ pub const ISSUE_14181: i16 = 1;
 pub const ISSUE_14213: i16 = 2;
 pub const DISCUSSION_14094: i16 = 3;
 pub const PULL_REQUEST_14409: i16 = 4;
+/// All ids that [`resolution_or_discussion_url_from_id`] knows a URL for.
+pub const RESOLUTION_OR_DISCUSSION_URL_IDS: [i16; 4] = [
+    ISSUE_14181,
+    ISSUE_14213,
+    DISCUSSION_14094,
+    PULL_REQUEST_14409,
+];
 
 pub fn doc_url_from_id(id: i16) -> Option<&'static str> {
     match id {
@@ -12,9 +12,115 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rel_db::{DatabaseConnection, FileMetadata, NodeLogEntry};
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use duckdb::Error as DuckDbError;
+use std::fmt;
+use std::str::FromStr;
+
+/// What relative time expressions, such as QL's `@1h` or "2 hours ago", are measured from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeAnchor {
+    /// The current wall clock time.
+    Now,
+    /// The most recent entry in the log set.
+    LogEnd,
+    /// An explicitly provided reference time.
+    At(DateTime<Utc>),
+}
+
+impl fmt::Display for TimeAnchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeAnchor::Now => write!(f, "now"),
+            TimeAnchor::LogEnd => write!(f, "log-end"),
+            TimeAnchor::At(ts) => write!(f, "{}", ts.to_rfc3339()),
+        }
+    }
+}
+
+impl FromStr for TimeAnchor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "now" => Ok(TimeAnchor::Now),
+            "log-end" | "log_end" | "end" => Ok(TimeAnchor::LogEnd),
+            other => parse_datetime_flexible(other).map(TimeAnchor::At),
+        }
+    }
+}
+
+/// Resolves [`TimeAnchor`]s to points in time for a given log set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeReference {
+    pub default_anchor: TimeAnchor,
+    pub log_end: Option<DateTime<Utc>>,
+}
+
+impl Default for TimeReference {
+    fn default() -> Self {
+        Self::now()
+    }
+}
+
+impl TimeReference {
+    /// A reference that anchors everything to the current wall clock time.
+    pub fn now() -> Self {
+        Self {
+            default_anchor: TimeAnchor::Now,
+            log_end: None,
+        }
+    }
+
+    pub fn new(default_anchor: TimeAnchor, log_end: Option<DateTime<Utc>>) -> Self {
+        Self {
+            default_anchor,
+            log_end,
+        }
+    }
+
+    /// Looks up the end of the log set in the database.
+    ///
+    /// Uses `max(most_recent_entry_at)` from `file_metadata` and falls back
+    /// to the most recent entry timestamp when no file metadata is available.
+    pub fn from_database(
+        db: &DatabaseConnection,
+        default_anchor: TimeAnchor,
+    ) -> Result<Self, DuckDbError> {
+        let log_end = match FileMetadata::most_recent_entry_at(db)? {
+            Some(ts) => Some(ts),
+            None => NodeLogEntry::most_recent_timestamp(db)?,
+        };
+        Ok(Self::new(default_anchor, log_end))
+    }
+
+    /// Returns the point in time the default anchor refers to.
+    pub fn reference_time(&self) -> DateTime<Utc> {
+        self.resolve(self.default_anchor)
+    }
+
+    /// Returns the point in time the given anchor refers to.
+    /// [`TimeAnchor::LogEnd`] falls back to the current time for empty log sets.
+    pub fn resolve(&self, anchor: TimeAnchor) -> DateTime<Utc> {
+        match anchor {
+            TimeAnchor::Now => Utc::now(),
+            TimeAnchor::LogEnd => self.log_end.unwrap_or_else(Utc::now),
+            TimeAnchor::At(ts) => ts,
+        }
+    }
+}
 
 pub fn parse_datetime_flexible(s: &str) -> Result<DateTime<Utc>, String> {
+    parse_datetime_relative_to(s, Utc::now())
+}
+
+/// Like [`parse_datetime_flexible`] but natural language expressions such as
+/// "2 hours ago" are interpreted relative to `reference` instead of the current time.
+pub fn parse_datetime_relative_to(
+    s: &str,
+    reference: DateTime<Utc>,
+) -> Result<DateTime<Utc>, String> {
     let len = s.len();
 
     match len {
@@ -36,7 +142,7 @@ pub fn parse_datetime_flexible(s: &str) -> Result<DateTime<Utc>, String> {
         return Ok(dt.with_timezone(&Utc));
     }
 
-    chrono_english::parse_date_string(s, Utc::now(), chrono_english::Dialect::Us).map_err(|e| {
+    chrono_english::parse_date_string(s, reference, chrono_english::Dialect::Us).map_err(|e| {
         format!(
             "Could not parse '{}' as a date/time. Supported formats:\n  - Date: YYYY-MM-DD (e.g., 2025-10-27)\n  - DateTime: YYYY-MM-DD HH:MM:SS (e.g., 2025-10-27 18:23:00)\n  - RFC 3339 (e.g., 2025-10-27T18:23:00Z)\n  - Natural language: 'yesterday', '2 days ago', 'last Monday', '1 week ago'\nError: {}",
             s, e
@@ -0,0 +1,383 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Comparison of two log sets, see [`LogDiff`].
+//!
+//! A log set is a database file or a time window of one. Typical uses are
+//! comparing a healthy period to a broken one, or last week's bundle to this week's.
+
+use crate::rel_db::DatabaseConnection;
+use crate::rel_db::aggregation::LogColumn;
+use crate::rel_db::file_metadata::{FileMetadata, Model as FileMetadataModel};
+use crate::rel_db::node_log_entry::{NodeLogEntry, QueryContext};
+use duckdb::Error as DuckDbError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use strum::{Display, EnumString};
+
+/// Entry counts that are compared per node.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum CountDimension {
+    Severity,
+    Subsystem,
+    Label,
+}
+
+impl CountDimension {
+    pub const ALL: [CountDimension; 3] = [
+        CountDimension::Severity,
+        CountDimension::Subsystem,
+        CountDimension::Label,
+    ];
+
+    pub fn column(&self) -> LogColumn {
+        match self {
+            CountDimension::Severity => LogColumn::Severity,
+            CountDimension::Subsystem => LogColumn::Subsystem,
+            CountDimension::Label => LogColumn::Labels,
+        }
+    }
+}
+
+/// A `file_metadata` fact that is compared per node.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Fact {
+    RabbitmqVersion,
+    ErlangVersion,
+    TlsLibrary,
+    EnabledPlugin,
+}
+
+impl Fact {
+    fn values_of(&self, file: &FileMetadataModel) -> Vec<String> {
+        match self {
+            Fact::RabbitmqVersion => file.rabbitmq_versions.clone(),
+            Fact::ErlangVersion => file.erlang_versions.clone(),
+            Fact::TlsLibrary => file.tls_library.iter().cloned().collect(),
+            Fact::EnabledPlugin => file.enabled_plugins.clone(),
+        }
+    }
+}
+
+/// One of the two log sets of a diff: a database, optionally narrowed down
+/// to a time window with [`QueryContext::since`] and [`QueryContext::to`].
+pub struct DiffSide<'a> {
+    pub db: &'a DatabaseConnection,
+    pub ctx: QueryContext,
+}
+
+impl<'a> DiffSide<'a> {
+    pub fn new(db: &'a DatabaseConnection, ctx: QueryContext) -> Self {
+        Self { db, ctx }
+    }
+}
+
+/// The entry counts and file metadata of a log set that a diff compares.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogSetSummary {
+    /// Entry counts per node, dimension and value
+    pub counts: BTreeMap<(String, CountDimension, String), i64>,
+    /// Entry counts per pattern template and node
+    pub patterns: BTreeMap<String, BTreeMap<String, i64>>,
+    pub files: Vec<FileMetadataModel>,
+}
+
+impl LogSetSummary {
+    /// Summarizes a log set. Only the metadata of files whose entries overlap
+    /// the time window of the side is included.
+    pub fn query(side: &DiffSide<'_>) -> Result<Self, DuckDbError> {
+        let mut summary = Self::default();
+
+        for dimension in CountDimension::ALL {
+            for (node, value, count) in
+                NodeLogEntry::get_node_counts_by(side.db, &side.ctx, dimension.column())?
+            {
+                if let Some(value) = value {
+                    summary.add_count(&node, dimension, &value, count);
+                }
+            }
+        }
+
+        for (node, template, count) in
+            NodeLogEntry::get_node_counts_by(side.db, &side.ctx, LogColumn::Pattern)?
+        {
+            if let Some(template) = template {
+                summary.add_pattern(&node, &template, count);
+            }
+        }
+
+        let since = side.ctx.get_since_time();
+        let to = side.ctx.get_to_time();
+        summary.files = FileMetadata::find_all(side.db)?
+            .into_iter()
+            .filter(|file| {
+                let starts_before_end = match (file.oldest_entry_at, to) {
+                    (Some(oldest), Some(to)) => oldest <= to,
+                    _ => true,
+                };
+                let ends_after_start = match (file.most_recent_entry_at, since) {
+                    (Some(most_recent), Some(since)) => most_recent >= since,
+                    _ => true,
+                };
+                starts_before_end && ends_after_start
+            })
+            .collect();
+
+        Ok(summary)
+    }
+
+    pub fn add_count(&mut self, node: &str, dimension: CountDimension, value: &str, count: i64) {
+        *self
+            .counts
+            .entry((node.to_string(), dimension, value.to_string()))
+            .or_default() += count;
+    }
+
+    pub fn add_pattern(&mut self, node: &str, template: &str, count: i64) {
+        *self
+            .patterns
+            .entry(template.to_string())
+            .or_default()
+            .entry(node.to_string())
+            .or_default() += count;
+    }
+
+    /// The number of entries, every entry has exactly one severity.
+    pub fn entry_count(&self) -> i64 {
+        self.counts
+            .iter()
+            .filter(|((_, dimension, _), _)| *dimension == CountDimension::Severity)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    fn nodes(&self) -> BTreeSet<String> {
+        self.counts
+            .keys()
+            .map(|(node, _, _)| node.clone())
+            .collect()
+    }
+
+    fn facts(&self) -> BTreeMap<(String, Fact), BTreeSet<String>> {
+        let mut facts: BTreeMap<(String, Fact), BTreeSet<String>> = BTreeMap::new();
+        for file in &self.files {
+            for node in &file.nodes {
+                for fact in [
+                    Fact::RabbitmqVersion,
+                    Fact::ErlangVersion,
+                    Fact::TlsLibrary,
+                    Fact::EnabledPlugin,
+                ] {
+                    facts
+                        .entry((node.clone(), fact))
+                        .or_default()
+                        .extend(fact.values_of(file));
+                }
+            }
+        }
+        facts
+    }
+}
+
+/// A change in the number of entries of a node with a given severity, subsystem or label.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CountDelta {
+    pub node: String,
+    pub dimension: CountDimension,
+    pub value: String,
+    pub baseline: i64,
+    pub comparison: i64,
+}
+
+impl CountDelta {
+    pub fn delta(&self) -> i64 {
+        self.comparison - self.baseline
+    }
+}
+
+/// A message pattern that only occurs in one of the log sets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternChange {
+    pub template: String,
+    pub entry_count: i64,
+    pub nodes: Vec<String>,
+}
+
+/// Values of a `file_metadata` fact that a node gained or lost, e.g. a plugin
+/// that was enabled or an upgraded RabbitMQ version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FactChange {
+    pub node: String,
+    pub fact: Fact,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// The differences between a baseline log set and a comparison log set.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogDiff {
+    pub baseline_entries: i64,
+    pub comparison_entries: i64,
+    /// Patterns that only occur in the comparison log set, most frequent first
+    pub new_patterns: Vec<PatternChange>,
+    /// Patterns that only occur in the baseline log set, most frequent first
+    pub vanished_patterns: Vec<PatternChange>,
+    /// Changed counts, grouped by node and dimension, largest changes first
+    pub count_deltas: Vec<CountDelta>,
+    /// Changed facts of nodes that are present in both log sets
+    pub fact_changes: Vec<FactChange>,
+}
+
+impl LogDiff {
+    pub fn compute(
+        baseline: &DiffSide<'_>,
+        comparison: &DiffSide<'_>,
+    ) -> Result<Self, DuckDbError> {
+        let baseline = LogSetSummary::query(baseline)?;
+        let comparison = LogSetSummary::query(comparison)?;
+        Ok(Self::build(&baseline, &comparison))
+    }
+
+    pub fn build(baseline: &LogSetSummary, comparison: &LogSetSummary) -> Self {
+        Self {
+            baseline_entries: baseline.entry_count(),
+            comparison_entries: comparison.entry_count(),
+            new_patterns: patterns_only_in(comparison, baseline),
+            vanished_patterns: patterns_only_in(baseline, comparison),
+            count_deltas: count_deltas(baseline, comparison),
+            fact_changes: fact_changes(baseline, comparison),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.new_patterns.is_empty()
+            && self.vanished_patterns.is_empty()
+            && self.count_deltas.is_empty()
+            && self.fact_changes.is_empty()
+    }
+}
+
+fn patterns_only_in(this: &LogSetSummary, other: &LogSetSummary) -> Vec<PatternChange> {
+    let mut changes: Vec<PatternChange> = this
+        .patterns
+        .iter()
+        .filter(|(template, _)| !other.patterns.contains_key(*template))
+        .map(|(template, per_node)| PatternChange {
+            template: template.clone(),
+            entry_count: per_node.values().sum(),
+            nodes: per_node.keys().cloned().collect(),
+        })
+        .collect();
+    changes.sort_by(|a, b| {
+        b.entry_count
+            .cmp(&a.entry_count)
+            .then_with(|| a.template.cmp(&b.template))
+    });
+    changes
+}
+
+fn count_deltas(baseline: &LogSetSummary, comparison: &LogSetSummary) -> Vec<CountDelta> {
+    let keys: BTreeSet<&(String, CountDimension, String)> = baseline
+        .counts
+        .keys()
+        .chain(comparison.counts.keys())
+        .collect();
+
+    let mut deltas: Vec<CountDelta> = keys
+        .into_iter()
+        .map(|key| {
+            let (node, dimension, value) = key;
+            CountDelta {
+                node: node.clone(),
+                dimension: *dimension,
+                value: value.clone(),
+                baseline: baseline.counts.get(key).copied().unwrap_or(0),
+                comparison: comparison.counts.get(key).copied().unwrap_or(0),
+            }
+        })
+        .filter(|delta| delta.delta() != 0)
+        .collect();
+    deltas.sort_by(|a, b| {
+        a.node
+            .cmp(&b.node)
+            .then(a.dimension.cmp(&b.dimension))
+            .then(b.delta().abs().cmp(&a.delta().abs()))
+            .then_with(|| a.value.cmp(&b.value))
+    });
+    deltas
+}
+
+fn fact_changes(baseline: &LogSetSummary, comparison: &LogSetSummary) -> Vec<FactChange> {
+    let shared_nodes: BTreeSet<String> = baseline
+        .nodes()
+        .intersection(&comparison.nodes())
+        .cloned()
+        .collect();
+    let baseline_facts = baseline.facts();
+    let comparison_facts = comparison.facts();
+
+    let keys: BTreeSet<&(String, Fact)> = baseline_facts
+        .keys()
+        .chain(comparison_facts.keys())
+        .filter(|(node, _)| shared_nodes.contains(node))
+        .collect();
+
+    let empty = BTreeSet::new();
+    keys.into_iter()
+        .filter_map(|key| {
+            let before = baseline_facts.get(key).unwrap_or(&empty);
+            let after = comparison_facts.get(key).unwrap_or(&empty);
+            let added: Vec<String> = after.difference(before).cloned().collect();
+            let removed: Vec<String> = before.difference(after).cloned().collect();
+            if added.is_empty() && removed.is_empty() {
+                return None;
+            }
+            Some(FactChange {
+                node: key.0.clone(),
+                fact: key.1,
+                added,
+                removed,
+            })
+        })
+        .collect()
+}
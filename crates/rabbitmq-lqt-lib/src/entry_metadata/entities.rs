@@ -0,0 +1,164 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Extraction of the resources (queues, streams, exchanges, virtual hosts, users
+//! and connections) an entry mentions, e.g. `queue 'orders' in vhost 'vhost1'`
+//! or a Ra server id such as `{'orders','rabbit@host1'}`.
+
+use crate::parser::StructuredMetadata;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use strum::{Display, EnumString};
+
+static RESOURCE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(queue|stream|exchange):? '([^']+)'(?:\s+in\s+(?:vhost|virtual host)\s+'([^']*)')?",
+    )
+    .expect("RESOURCE_PATTERN is a valid regex")
+});
+
+static ERLANG_QUEUE_TUPLE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{'([^']+)','[^'@]+@[^']+'\}")
+        .expect("ERLANG_QUEUE_TUPLE_PATTERN is a valid regex")
+});
+
+/// The kind of resource an entity refers to.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum EntityKind {
+    Queue,
+    Stream,
+    Exchange,
+    Vhost,
+    User,
+    Connection,
+}
+
+impl EntityKind {
+    pub const ALL: [EntityKind; 6] = [
+        EntityKind::Queue,
+        EntityKind::Stream,
+        EntityKind::Exchange,
+        EntityKind::Vhost,
+        EntityKind::User,
+        EntityKind::Connection,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityKind::Queue => "queue",
+            EntityKind::Stream => "stream",
+            EntityKind::Exchange => "exchange",
+            EntityKind::Vhost => "vhost",
+            EntityKind::User => "user",
+            EntityKind::Connection => "connection",
+        }
+    }
+}
+
+/// A resource mentioned by an entry. Queues, streams and exchanges carry
+/// the virtual host they belong to, if the entry names it. Virtual hosts,
+/// users and connections are not scoped to a virtual host.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Entity {
+    pub kind: EntityKind,
+    pub vhost: Option<String>,
+    pub name: String,
+}
+
+impl Entity {
+    pub fn new(kind: EntityKind, vhost: Option<String>, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            vhost,
+            name: name.into(),
+        }
+    }
+}
+
+/// Extracts the entities mentioned by an entry from its message and
+/// its (already annotated) structured metadata. Each entity is returned once.
+pub fn extract_entities(message: &str, metadata: &StructuredMetadata) -> Vec<Entity> {
+    let mut entities: Vec<Entity> = Vec::new();
+
+    if message.contains('\'') {
+        for caps in RESOURCE_PATTERN.captures_iter(message) {
+            let kind = match caps[1].to_ascii_lowercase().as_str() {
+                "queue" => EntityKind::Queue,
+                "stream" => EntityKind::Stream,
+                _ => EntityKind::Exchange,
+            };
+            let vhost = caps
+                .get(3)
+                .map(|m| m.as_str().to_string())
+                .or_else(|| metadata.vhost.clone());
+            push_unique(&mut entities, Entity::new(kind, vhost, &caps[2]));
+        }
+
+        // Ra server ids of quorum queues. Skipped if the queue is already
+        // known from a `queue '...' in vhost '...'` mention.
+        if message.contains("{'") {
+            for caps in ERLANG_QUEUE_TUPLE_PATTERN.captures_iter(message) {
+                let name = &caps[1];
+                let already_known = entities
+                    .iter()
+                    .any(|e| e.kind == EntityKind::Queue && e.name == name);
+                if !already_known {
+                    let entity = Entity::new(EntityKind::Queue, metadata.vhost.clone(), name);
+                    push_unique(&mut entities, entity);
+                }
+            }
+        }
+    }
+
+    if let Some(ref vhost) = metadata.vhost
+        && !vhost.is_empty()
+    {
+        push_unique(&mut entities, Entity::new(EntityKind::Vhost, None, vhost));
+    }
+    if let Some(ref user) = metadata.user
+        && !user.is_empty()
+    {
+        push_unique(&mut entities, Entity::new(EntityKind::User, None, user));
+    }
+    if let Some(ref connection) = metadata.connection {
+        push_unique(
+            &mut entities,
+            Entity::new(EntityKind::Connection, None, connection),
+        );
+    }
+
+    entities
+}
+
+fn push_unique(entities: &mut Vec<Entity>, entity: Entity) {
+    if !entities.contains(&entity) {
+        entities.push(entity);
+    }
+}
@@ -15,7 +15,7 @@
 //! Label annotators for RabbitMQ log entries.
 //!
 //! This module contains annotators that examine log entries and apply semantic labels
-//! (represented as bitflags in `LogEntryLabels`). Label annotators are read-only -
+//! (represented as a bitset in `LogEntryLabels`). Label annotators are read-only -
 //! they check if an entry matches certain patterns and return which labels should be applied,
 //! but they don't modify the entry itself.
 //!
@@ -11,11 +11,11 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use bitflags::bitflags;
 use serde::de::{MapAccess, Visitor};
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{self, Formatter};
+use std::ops::{BitAnd, BitOr, BitOrAssign};
 
 /// Label name constants - single source of truth for label identifiers
 pub const LABEL_UNLABELLED: &str = "unlabelled";
@@ -73,7 +73,10 @@ pub const LABEL_OAUTH2: &str = "oauth2";
 pub const LABEL_SQL: &str = "sql";
 pub const LABEL_MNESIA: &str = "mnesia";
 
-/// Array of all label names in the order they were defined
+/// Array of all label names in the order they were defined.
+///
+/// The position of a name in this array is its label id. Label ids are persisted
+/// in `node_log_entry_labels`, so new labels must only ever be appended.
 pub const LABEL_NAMES: &[&str] = &[
     LABEL_UNLABELLED,
     LABEL_ERL_PROCESS_CRASH,
@@ -131,146 +134,220 @@ pub const LABEL_NAMES: &[&str] = &[
     LABEL_MNESIA,
 ];
 
-// Given that a parsed dataset can have 100s of thousands or millions of labels,
-// they are stored using a bitflags representation for efficiency.
-bitflags! {
-    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
-    pub struct LogEntryLabels: u64 {
-        const UNLABELLED          = 1 << 0;
-        const ERL_PROCESS_CRASH   = 1 << 1;
-        const UNDEFINED_FN        = 1 << 2;
-        const PROCESS_STOPS       = 1 << 3;
-        const RAFT                = 1 << 4;
-        const ELECTIONS           = 1 << 5;
-        const QUEUES              = 1 << 6;
-        const AUTO_DELETE         = 1 << 7;
-        const EXCLUSIVE           = 1 << 8;
-        const EXCEPTIONS          = 1 << 9;
-        const DELETE              = 1 << 10;
-        const QUEUE_FEDERATION    = 1 << 11;
-        const VIRTUAL_HOSTS       = 1 << 12;
-        const CONNECTIONS         = 1 << 13;
-        const ACCESS_CONTROL      = 1 << 14;
-        const SHOVELS             = 1 << 15;
-        const CQ_STORES           = 1 << 16;
-        const DISCONNECTS         = 1 << 17;
-        const FEDERATION          = 1 << 18;
-        const DELETION_PROTECTION = 1 << 19;
-        const MULTILINE           = 1 << 20;
-        const STREAMS             = 1 << 21;
-        const LIMITS              = 1 << 22;
-        const WORKER_POOL         = 1 << 23;
-        const PEER_DISCOVERY_CLASSIC = 1 << 24;
-        const PLUGINS             = 1 << 25;
-        const EXCHANGES           = 1 << 26;
-        const STARTUP_BANNER      = 1 << 27;
-        const CHANNELS            = 1 << 28;
-        const SHUTDOWN            = 1 << 29;
-        const DEFINITIONS         = 1 << 30;
-        const FEATURE_FLAGS       = 1 << 31;
-        const STOMP               = 1 << 32;
-        const WEBSOCKETS          = 1 << 33;
-        const MQTT                = 1 << 34;
-        const CLUSTERING          = 1 << 35;
-        const METRICS             = 1 << 36;
-        const TLS                 = 1 << 37;
-        const QUORUM_QUEUES       = 1 << 38;
-        const NETWORKING          = 1 << 39;
-        const CLASSIC_QUEUES      = 1 << 40;
-        const POLICIES            = 1 << 41;
-        const TIMEOUTS            = 1 << 42;
-        const CONSUMERS           = 1 << 43;
-        const DEPRECATED_FEATURES = 1 << 44;
-        const MAINTENANCE_MODE    = 1 << 45;
-        const KHEPRI              = 1 << 46;
-        const RUNTIME_PARAMETERS  = 1 << 47;
-        const HTTP                = 1 << 48;
-        const SESSIONS            = 1 << 49;
-        const AMQP10              = 1 << 50;
-        const OAUTH2              = 1 << 51;
-        const SQL                 = 1 << 52;
-        const MNESIA              = 1 << 53;
-    }
-}
+/// Number of 64-bit words needed to store one bit per known label.
+pub const LABEL_WORDS: usize = LABEL_NAMES.len().div_ceil(64);
+
+/// A set of labels, one bit per label id.
+///
+/// Given that a parsed dataset can have 100s of thousands or millions of entries,
+/// labels are kept as a compact bitset in memory. The set grows with [`LABEL_NAMES`],
+/// so adding a label never requires a storage format change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LogEntryLabels([u64; LABEL_WORDS]);
 
 impl LogEntryLabels {
+    pub const UNLABELLED: Self = Self::from_id(0);
+    pub const ERL_PROCESS_CRASH: Self = Self::from_id(1);
+    pub const UNDEFINED_FN: Self = Self::from_id(2);
+    pub const PROCESS_STOPS: Self = Self::from_id(3);
+    pub const RAFT: Self = Self::from_id(4);
+    pub const ELECTIONS: Self = Self::from_id(5);
+    pub const QUEUES: Self = Self::from_id(6);
+    pub const AUTO_DELETE: Self = Self::from_id(7);
+    pub const EXCLUSIVE: Self = Self::from_id(8);
+    pub const EXCEPTIONS: Self = Self::from_id(9);
+    pub const DELETE: Self = Self::from_id(10);
+    pub const QUEUE_FEDERATION: Self = Self::from_id(11);
+    pub const VIRTUAL_HOSTS: Self = Self::from_id(12);
+    pub const CONNECTIONS: Self = Self::from_id(13);
+    pub const ACCESS_CONTROL: Self = Self::from_id(14);
+    pub const SHOVELS: Self = Self::from_id(15);
+    pub const CQ_STORES: Self = Self::from_id(16);
+    pub const DISCONNECTS: Self = Self::from_id(17);
+    pub const FEDERATION: Self = Self::from_id(18);
+    pub const DELETION_PROTECTION: Self = Self::from_id(19);
+    pub const MULTILINE: Self = Self::from_id(20);
+    pub const STREAMS: Self = Self::from_id(21);
+    pub const LIMITS: Self = Self::from_id(22);
+    pub const WORKER_POOL: Self = Self::from_id(23);
+    pub const PEER_DISCOVERY_CLASSIC: Self = Self::from_id(24);
+    pub const PLUGINS: Self = Self::from_id(25);
+    pub const EXCHANGES: Self = Self::from_id(26);
+    pub const STARTUP_BANNER: Self = Self::from_id(27);
+    pub const CHANNELS: Self = Self::from_id(28);
+    pub const SHUTDOWN: Self = Self::from_id(29);
+    pub const DEFINITIONS: Self = Self::from_id(30);
+    pub const FEATURE_FLAGS: Self = Self::from_id(31);
+    pub const STOMP: Self = Self::from_id(32);
+    pub const WEBSOCKETS: Self = Self::from_id(33);
+    pub const MQTT: Self = Self::from_id(34);
+    pub const CLUSTERING: Self = Self::from_id(35);
+    pub const METRICS: Self = Self::from_id(36);
+    pub const TLS: Self = Self::from_id(37);
+    pub const QUORUM_QUEUES: Self = Self::from_id(38);
+    pub const NETWORKING: Self = Self::from_id(39);
+    pub const CLASSIC_QUEUES: Self = Self::from_id(40);
+    pub const POLICIES: Self = Self::from_id(41);
+    pub const TIMEOUTS: Self = Self::from_id(42);
+    pub const CONSUMERS: Self = Self::from_id(43);
+    pub const DEPRECATED_FEATURES: Self = Self::from_id(44);
+    pub const MAINTENANCE_MODE: Self = Self::from_id(45);
+    pub const KHEPRI: Self = Self::from_id(46);
+    pub const RUNTIME_PARAMETERS: Self = Self::from_id(47);
+    pub const HTTP: Self = Self::from_id(48);
+    pub const SESSIONS: Self = Self::from_id(49);
+    pub const AMQP10: Self = Self::from_id(50);
+    pub const OAUTH2: Self = Self::from_id(51);
+    pub const SQL: Self = Self::from_id(52);
+    pub const MNESIA: Self = Self::from_id(53);
+
+    /// An empty label set
+    #[inline]
+    pub const fn empty() -> Self {
+        Self([0; LABEL_WORDS])
+    }
+
+    /// A label set that contains every known label
+    pub const fn all() -> Self {
+        let mut labels = Self::empty();
+        let mut id = 0;
+        while id < LABEL_NAMES.len() {
+            labels.0[id / 64] |= 1 << (id % 64);
+            id += 1;
+        }
+        labels
+    }
+
+    /// A label set that contains a single label id. Ids are indices into [`LABEL_NAMES`].
+    #[inline]
+    pub const fn from_id(id: usize) -> Self {
+        let mut labels = Self::empty();
+        labels.0[id / 64] = 1 << (id % 64);
+        labels
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+
+    /// Returns true if all labels in `other` are also in this set
+    #[inline]
+    pub fn contains(&self, other: Self) -> bool {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .all(|(ours, theirs)| ours & theirs == *theirs)
+    }
+
+    /// Returns true if this set and `other` have at least one label in common
+    #[inline]
+    pub fn intersects(&self, other: Self) -> bool {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .any(|(ours, theirs)| ours & theirs != 0)
+    }
+
+    #[inline]
+    pub fn insert(&mut self, other: Self) {
+        *self |= other;
+    }
+
     #[inline]
     pub fn merge(&mut self, other: Self) {
         *self |= other;
     }
 
-    /// Convert to a bitflag value for storage
+    /// Ids of the labels in this set, in ascending order
+    pub fn ids(&self) -> impl Iterator<Item = u16> + '_ {
+        (0..LABEL_NAMES.len())
+            .filter(|id| self.0[id / 64] & (1 << (id % 64)) != 0)
+            .map(|id| id as u16)
+    }
+
+    /// Names of the labels in this set, in the order they were defined
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.ids().map(|id| LABEL_NAMES[id as usize])
+    }
+
+    /// Number of labels in this set
     #[inline]
-    pub fn to_bits_i64(self) -> i64 {
-        self.bits() as i64
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
     }
 
-    /// Instantiate from a stored bitflag value
+    /// Convert label name to its id
     #[inline]
-    pub fn from_bits_i64(bits: i64) -> Self {
-        Self::from_bits_truncate(bits as u64)
+    pub fn id_for_label(label: &str) -> Option<u16> {
+        LABEL_NAMES
+            .iter()
+            .position(|name| *name == label)
+            .map(|id| id as u16)
     }
 
-    /// Convert label name to its bit position
+    /// Convert label name to a single label set
     #[inline]
-    pub fn bit_for_label(label: &str) -> Option<u64> {
-        match label {
-            LABEL_UNLABELLED => Some(1 << 0),
-            LABEL_ERL_PROCESS_CRASH => Some(1 << 1),
-            LABEL_UNDEFINED_FN => Some(1 << 2),
-            LABEL_PROCESS_STOPS => Some(1 << 3),
-            LABEL_RAFT => Some(1 << 4),
-            LABEL_ELECTIONS => Some(1 << 5),
-            LABEL_QUEUES => Some(1 << 6),
-            LABEL_AUTO_DELETE => Some(1 << 7),
-            LABEL_EXCLUSIVE => Some(1 << 8),
-            LABEL_EXCEPTIONS => Some(1 << 9),
-            LABEL_DELETE => Some(1 << 10),
-            LABEL_QUEUE_FEDERATION => Some(1 << 11),
-            LABEL_VIRTUAL_HOSTS => Some(1 << 12),
-            LABEL_CONNECTIONS => Some(1 << 13),
-            LABEL_ACCESS_CONTROL => Some(1 << 14),
-            LABEL_SHOVELS => Some(1 << 15),
-            LABEL_CQ_STORES => Some(1 << 16),
-            LABEL_DISCONNECTS => Some(1 << 17),
-            LABEL_FEDERATION => Some(1 << 18),
-            LABEL_DELETION_PROTECTION => Some(1 << 19),
-            LABEL_MULTILINE => Some(1 << 20),
-            LABEL_STREAMS => Some(1 << 21),
-            LABEL_LIMITS => Some(1 << 22),
-            LABEL_WORKER_POOL => Some(1 << 23),
-            LABEL_PEER_DISCOVERY_CLASSIC => Some(1 << 24),
-            LABEL_PLUGINS => Some(1 << 25),
-            LABEL_EXCHANGES => Some(1 << 26),
-            LABEL_STARTUP_BANNER => Some(1 << 27),
-            LABEL_CHANNELS => Some(1 << 28),
-            LABEL_SHUTDOWN => Some(1 << 29),
-            LABEL_DEFINITIONS => Some(1 << 30),
-            LABEL_FEATURE_FLAGS => Some(1 << 31),
-            LABEL_STOMP => Some(1 << 32),
-            LABEL_WEBSOCKETS => Some(1 << 33),
-            LABEL_MQTT => Some(1 << 34),
-            LABEL_CLUSTERING => Some(1 << 35),
-            LABEL_METRICS => Some(1 << 36),
-            LABEL_TLS => Some(1 << 37),
-            LABEL_QUORUM_QUEUES => Some(1 << 38),
-            LABEL_NETWORKING => Some(1 << 39),
-            LABEL_CLASSIC_QUEUES => Some(1 << 40),
-            LABEL_POLICIES => Some(1 << 41),
-            LABEL_TIMEOUTS => Some(1 << 42),
-            LABEL_CONSUMERS => Some(1 << 43),
-            LABEL_DEPRECATED_FEATURES => Some(1 << 44),
-            LABEL_MAINTENANCE_MODE => Some(1 << 45),
-            LABEL_KHEPRI => Some(1 << 46),
-            LABEL_RUNTIME_PARAMETERS => Some(1 << 47),
-            LABEL_HTTP => Some(1 << 48),
-            LABEL_SESSIONS => Some(1 << 49),
-            LABEL_AMQP10 => Some(1 << 50),
-            LABEL_OAUTH2 => Some(1 << 51),
-            LABEL_SQL => Some(1 << 52),
-            LABEL_MNESIA => Some(1 << 53),
-            _ => None,
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::id_for_label(label).map(|id| Self::from_id(id as usize))
+    }
+
+    /// Instantiate from stored label ids, ignoring ids this version does not know about
+    pub fn from_ids<I: IntoIterator<Item = u16>>(ids: I) -> Self {
+        let mut labels = Self::empty();
+        for id in ids {
+            if (id as usize) < LABEL_NAMES.len() {
+                labels |= Self::from_id(id as usize);
+            }
         }
+        labels
+    }
+
+    /// Instantiate from a comma-separated list of label ids,
+    /// the format labels are read in from `node_log_entry_labels`
+    pub fn from_id_list(list: &str) -> Self {
+        Self::from_ids(
+            list.split(',')
+                .filter_map(|id| id.trim().parse::<u16>().ok()),
+        )
+    }
+}
+
+impl Default for LogEntryLabels {
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl BitOr for LogEntryLabels {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: Self) -> Self {
+        self |= rhs;
+        self
+    }
+}
+
+impl BitOrAssign for LogEntryLabels {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        for (ours, theirs) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *ours |= theirs;
+        }
+    }
+}
+
+impl BitAnd for LogEntryLabels {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(mut self, rhs: Self) -> Self {
+        for (ours, theirs) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *ours &= theirs;
+        }
+        self
     }
 }
 
@@ -279,171 +356,10 @@ impl Serialize for LogEntryLabels {
     where
         S: Serializer,
     {
-        let mut map = serializer.serialize_map(None)?;
-
-        if self.contains(Self::UNLABELLED) {
-            map.serialize_entry(LABEL_UNLABELLED, &true)?;
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for name in self.names() {
+            map.serialize_entry(name, &true)?;
         }
-        if self.contains(Self::ERL_PROCESS_CRASH) {
-            map.serialize_entry(LABEL_ERL_PROCESS_CRASH, &true)?;
-        }
-        if self.contains(Self::UNDEFINED_FN) {
-            map.serialize_entry(LABEL_UNDEFINED_FN, &true)?;
-        }
-        if self.contains(Self::PROCESS_STOPS) {
-            map.serialize_entry(LABEL_PROCESS_STOPS, &true)?;
-        }
-        if self.contains(Self::RAFT) {
-            map.serialize_entry(LABEL_RAFT, &true)?;
-        }
-        if self.contains(Self::ELECTIONS) {
-            map.serialize_entry(LABEL_ELECTIONS, &true)?;
-        }
-        if self.contains(Self::QUEUES) {
-            map.serialize_entry(LABEL_QUEUES, &true)?;
-        }
-        if self.contains(Self::AUTO_DELETE) {
-            map.serialize_entry(LABEL_AUTO_DELETE, &true)?;
-        }
-        if self.contains(Self::EXCLUSIVE) {
-            map.serialize_entry(LABEL_EXCLUSIVE, &true)?;
-        }
-        if self.contains(Self::EXCEPTIONS) {
-            map.serialize_entry(LABEL_EXCEPTIONS, &true)?;
-        }
-        if self.contains(Self::DELETE) {
-            map.serialize_entry(LABEL_DELETE, &true)?;
-        }
-        if self.contains(Self::QUEUE_FEDERATION) {
-            map.serialize_entry(LABEL_QUEUE_FEDERATION, &true)?;
-        }
-        if self.contains(Self::VIRTUAL_HOSTS) {
-            map.serialize_entry(LABEL_VIRTUAL_HOSTS, &true)?;
-        }
-        if self.contains(Self::CONNECTIONS) {
-            map.serialize_entry(LABEL_CONNECTIONS, &true)?;
-        }
-        if self.contains(Self::ACCESS_CONTROL) {
-            map.serialize_entry(LABEL_ACCESS_CONTROL, &true)?;
-        }
-        if self.contains(Self::SHOVELS) {
-            map.serialize_entry(LABEL_SHOVELS, &true)?;
-        }
-        if self.contains(Self::CQ_STORES) {
-            map.serialize_entry(LABEL_CQ_STORES, &true)?;
-        }
-        if self.contains(Self::DISCONNECTS) {
-            map.serialize_entry(LABEL_DISCONNECTS, &true)?;
-        }
-        if self.contains(Self::FEDERATION) {
-            map.serialize_entry(LABEL_FEDERATION, &true)?;
-        }
-        if self.contains(Self::DELETION_PROTECTION) {
-            map.serialize_entry(LABEL_DELETION_PROTECTION, &true)?;
-        }
-        if self.contains(Self::MULTILINE) {
-            map.serialize_entry(LABEL_MULTILINE, &true)?;
-        }
-        if self.contains(Self::STREAMS) {
-            map.serialize_entry(LABEL_STREAMS, &true)?;
-        }
-        if self.contains(Self::LIMITS) {
-            map.serialize_entry(LABEL_LIMITS, &true)?;
-        }
-        if self.contains(Self::WORKER_POOL) {
-            map.serialize_entry(LABEL_WORKER_POOL, &true)?;
-        }
-        if self.contains(Self::PEER_DISCOVERY_CLASSIC) {
-            map.serialize_entry(LABEL_PEER_DISCOVERY_CLASSIC, &true)?;
-        }
-        if self.contains(Self::PLUGINS) {
-            map.serialize_entry(LABEL_PLUGINS, &true)?;
-        }
-        if self.contains(Self::EXCHANGES) {
-            map.serialize_entry(LABEL_EXCHANGES, &true)?;
-        }
-        if self.contains(Self::STARTUP_BANNER) {
-            map.serialize_entry(LABEL_STARTUP_BANNER, &true)?;
-        }
-        if self.contains(Self::CHANNELS) {
-            map.serialize_entry(LABEL_CHANNELS, &true)?;
-        }
-        if self.contains(Self::SHUTDOWN) {
-            map.serialize_entry(LABEL_SHUTDOWN, &true)?;
-        }
-        if self.contains(Self::DEFINITIONS) {
-            map.serialize_entry(LABEL_DEFINITIONS, &true)?;
-        }
-        if self.contains(Self::FEATURE_FLAGS) {
-            map.serialize_entry(LABEL_FEATURE_FLAGS, &true)?;
-        }
-        if self.contains(Self::STOMP) {
-            map.serialize_entry(LABEL_STOMP, &true)?;
-        }
-        if self.contains(Self::WEBSOCKETS) {
-            map.serialize_entry(LABEL_WEBSOCKETS, &true)?;
-        }
-        if self.contains(Self::MQTT) {
-            map.serialize_entry(LABEL_MQTT, &true)?;
-        }
-        if self.contains(Self::CLUSTERING) {
-            map.serialize_entry(LABEL_CLUSTERING, &true)?;
-        }
-        if self.contains(Self::METRICS) {
-            map.serialize_entry(LABEL_METRICS, &true)?;
-        }
-        if self.contains(Self::TLS) {
-            map.serialize_entry(LABEL_TLS, &true)?;
-        }
-        if self.contains(Self::QUORUM_QUEUES) {
-            map.serialize_entry(LABEL_QUORUM_QUEUES, &true)?;
-        }
-        if self.contains(Self::NETWORKING) {
-            map.serialize_entry(LABEL_NETWORKING, &true)?;
-        }
-        if self.contains(Self::CLASSIC_QUEUES) {
-            map.serialize_entry(LABEL_CLASSIC_QUEUES, &true)?;
-        }
-        if self.contains(Self::POLICIES) {
-            map.serialize_entry(LABEL_POLICIES, &true)?;
-        }
-        if self.contains(Self::TIMEOUTS) {
-            map.serialize_entry(LABEL_TIMEOUTS, &true)?;
-        }
-        if self.contains(Self::CONSUMERS) {
-            map.serialize_entry(LABEL_CONSUMERS, &true)?;
-        }
-        if self.contains(Self::DEPRECATED_FEATURES) {
-            map.serialize_entry(LABEL_DEPRECATED_FEATURES, &true)?;
-        }
-        if self.contains(Self::MAINTENANCE_MODE) {
-            map.serialize_entry(LABEL_MAINTENANCE_MODE, &true)?;
-        }
-        if self.contains(Self::KHEPRI) {
-            map.serialize_entry(LABEL_KHEPRI, &true)?;
-        }
-        if self.contains(Self::RUNTIME_PARAMETERS) {
-            map.serialize_entry(LABEL_RUNTIME_PARAMETERS, &true)?;
-        }
-        if self.contains(Self::HTTP) {
-            map.serialize_entry(LABEL_HTTP, &true)?;
-        }
-        if self.contains(Self::SESSIONS) {
-            map.serialize_entry(LABEL_SESSIONS, &true)?;
-        }
-        if self.contains(Self::AMQP10) {
-            map.serialize_entry(LABEL_AMQP10, &true)?;
-        }
-        if self.contains(Self::OAUTH2) {
-            map.serialize_entry(LABEL_OAUTH2, &true)?;
-        }
-        if self.contains(Self::SQL) {
-            map.serialize_entry(LABEL_SQL, &true)?;
-        }
-        if self.contains(Self::MNESIA) {
-            map.serialize_entry(LABEL_MNESIA, &true)?;
-        }
-
         map.end()
     }
 }
@@ -470,72 +386,8 @@ impl<'de> Deserialize<'de> for LogEntryLabels {
 
                 while let Some(key) = map.next_key::<String>()? {
                     let value: bool = map.next_value()?;
-                    if value {
-                        match key.as_str() {
-                            LABEL_UNLABELLED => labels |= LogEntryLabels::UNLABELLED,
-                            LABEL_ERL_PROCESS_CRASH => labels |= LogEntryLabels::ERL_PROCESS_CRASH,
-                            LABEL_UNDEFINED_FN => labels |= LogEntryLabels::UNDEFINED_FN,
-                            LABEL_PROCESS_STOPS => labels |= LogEntryLabels::PROCESS_STOPS,
-                            LABEL_RAFT => labels |= LogEntryLabels::RAFT,
-                            LABEL_ELECTIONS => labels |= LogEntryLabels::ELECTIONS,
-                            LABEL_QUEUES => labels |= LogEntryLabels::QUEUES,
-                            LABEL_AUTO_DELETE => labels |= LogEntryLabels::AUTO_DELETE,
-                            LABEL_EXCLUSIVE => labels |= LogEntryLabels::EXCLUSIVE,
-                            LABEL_EXCEPTIONS => labels |= LogEntryLabels::EXCEPTIONS,
-                            LABEL_DELETE => labels |= LogEntryLabels::DELETE,
-                            LABEL_QUEUE_FEDERATION => labels |= LogEntryLabels::QUEUE_FEDERATION,
-                            LABEL_VIRTUAL_HOSTS => labels |= LogEntryLabels::VIRTUAL_HOSTS,
-                            LABEL_CONNECTIONS => labels |= LogEntryLabels::CONNECTIONS,
-                            LABEL_ACCESS_CONTROL => labels |= LogEntryLabels::ACCESS_CONTROL,
-                            LABEL_SHOVELS => labels |= LogEntryLabels::SHOVELS,
-                            LABEL_CQ_STORES => labels |= LogEntryLabels::CQ_STORES,
-                            LABEL_DISCONNECTS => labels |= LogEntryLabels::DISCONNECTS,
-                            LABEL_FEDERATION => labels |= LogEntryLabels::FEDERATION,
-                            LABEL_DELETION_PROTECTION => {
-                                labels |= LogEntryLabels::DELETION_PROTECTION
-                            }
-                            LABEL_MULTILINE => labels |= LogEntryLabels::MULTILINE,
-                            LABEL_STREAMS => labels |= LogEntryLabels::STREAMS,
-                            LABEL_LIMITS => labels |= LogEntryLabels::LIMITS,
-                            LABEL_WORKER_POOL => labels |= LogEntryLabels::WORKER_POOL,
-                            LABEL_PEER_DISCOVERY_CLASSIC => {
-                                labels |= LogEntryLabels::PEER_DISCOVERY_CLASSIC
-                            }
-                            LABEL_PLUGINS => labels |= LogEntryLabels::PLUGINS,
-                            LABEL_EXCHANGES => labels |= LogEntryLabels::EXCHANGES,
-                            LABEL_STARTUP_BANNER => labels |= LogEntryLabels::STARTUP_BANNER,
-                            LABEL_CHANNELS => labels |= LogEntryLabels::CHANNELS,
-                            LABEL_SHUTDOWN => labels |= LogEntryLabels::SHUTDOWN,
-                            LABEL_DEFINITIONS => labels |= LogEntryLabels::DEFINITIONS,
-                            LABEL_FEATURE_FLAGS => labels |= LogEntryLabels::FEATURE_FLAGS,
-                            LABEL_STOMP => labels |= LogEntryLabels::STOMP,
-                            LABEL_WEBSOCKETS => labels |= LogEntryLabels::WEBSOCKETS,
-                            LABEL_MQTT => labels |= LogEntryLabels::MQTT,
-                            LABEL_CLUSTERING => labels |= LogEntryLabels::CLUSTERING,
-                            LABEL_METRICS => labels |= LogEntryLabels::METRICS,
-                            LABEL_TLS => labels |= LogEntryLabels::TLS,
-                            LABEL_QUORUM_QUEUES => labels |= LogEntryLabels::QUORUM_QUEUES,
-                            LABEL_NETWORKING => labels |= LogEntryLabels::NETWORKING,
-                            LABEL_CLASSIC_QUEUES => labels |= LogEntryLabels::CLASSIC_QUEUES,
-                            LABEL_POLICIES => labels |= LogEntryLabels::POLICIES,
-                            LABEL_TIMEOUTS => labels |= LogEntryLabels::TIMEOUTS,
-                            LABEL_CONSUMERS => labels |= LogEntryLabels::CONSUMERS,
-                            LABEL_DEPRECATED_FEATURES => {
-                                labels |= LogEntryLabels::DEPRECATED_FEATURES
-                            }
-                            LABEL_MAINTENANCE_MODE => labels |= LogEntryLabels::MAINTENANCE_MODE,
-                            LABEL_KHEPRI => labels |= LogEntryLabels::KHEPRI,
-                            LABEL_RUNTIME_PARAMETERS => {
-                                labels |= LogEntryLabels::RUNTIME_PARAMETERS
-                            }
-                            LABEL_HTTP => labels |= LogEntryLabels::HTTP,
-                            LABEL_SESSIONS => labels |= LogEntryLabels::SESSIONS,
-                            LABEL_AMQP10 => labels |= LogEntryLabels::AMQP10,
-                            LABEL_OAUTH2 => labels |= LogEntryLabels::OAUTH2,
-                            LABEL_SQL => labels |= LogEntryLabels::SQL,
-                            LABEL_MNESIA => labels |= LogEntryLabels::MNESIA,
-                            _ => {}
-                        }
+                    if value && let Some(label) = LogEntryLabels::from_label(&key) {
+                        labels |= label;
                     }
                 }
 
@@ -0,0 +1,63 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Extraction of structured metadata from plain text log messages.
+//!
+//! The JSON formatter logs the virtual host, user and connection as separate
+//! fields. Plain text entries only mention them in the message, e.g.
+//! `connection 10.0.0.2:8959 -> 10.0.0.1:5672: user 'user2' authenticated and granted access to vhost 'vhost1'`.
+
+use crate::parser::ParsedLogEntry;
+use regex::Regex;
+use std::sync::LazyLock;
+
+static VHOST_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:vhost|virtual host):? '([^']*)'").expect("VHOST_PATTERN is a valid regex")
+});
+
+static USER_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\buser:? '([^']*)'").expect("USER_PATTERN is a valid regex"));
+
+static CONNECTION_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"((?:\[[^\]]+\]|[^\s:(\[]+):\d+ -> (?:\[[^\]]+\]|[^\s:]+):\d+)")
+        .expect("CONNECTION_PATTERN is a valid regex")
+});
+
+/// Fills in the virtual host, user and connection of an entry from its message.
+/// Fields that are already set, e.g. by the JSON parser, are left as they are.
+#[inline]
+pub fn annotate_structured_metadata(entry: &mut ParsedLogEntry) {
+    let msg_lower = &entry.message_lowercased;
+
+    if entry.metadata.vhost.is_none()
+        && (msg_lower.contains("vhost") || msg_lower.contains("virtual host"))
+    {
+        entry.metadata.vhost = capture(&VHOST_PATTERN, &entry.message);
+    }
+
+    if entry.metadata.user.is_none() && msg_lower.contains("user") {
+        entry.metadata.user = capture(&USER_PATTERN, &entry.message);
+    }
+
+    if entry.metadata.connection.is_none() && entry.message.contains(" -> ") {
+        entry.metadata.connection = capture(&CONNECTION_PATTERN, &entry.message);
+    }
+}
+
+fn capture(pattern: &Regex, message: &str) -> Option<String> {
+    pattern
+        .captures(message)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+}
@@ -13,15 +13,20 @@
 // limitations under the License.
 pub mod annotator;
 pub mod doc_url_annotators;
+pub mod entities;
 pub mod label_annotators;
 pub mod labels;
+pub mod metadata_annotators;
+pub mod patterns;
 pub mod resolution_url_annotators;
+pub mod rules;
 pub mod shared;
 pub mod subsystem_annotators;
 pub mod subsystems;
 
 pub use annotator::Annotator;
 pub use doc_url_annotators::annotate_doc_urls;
+pub use entities::{Entity, EntityKind, extract_entities};
 pub use label_annotators::{
     AccessControlAnnotator, AutoDeleteAnnotator, ConnectionsAnnotator, DeleteAnnotator,
     ElectionsAnnotator, ErlProcessCrashAnnotator, ExceptionsAnnotator, ExclusiveAnnotator,
@@ -30,17 +35,21 @@ pub use label_annotators::{
     VirtualHostsAnnotator, annotate_labels,
 };
 pub use labels::{LABEL_NAMES, LogEntryLabels};
+pub use metadata_annotators::annotate_structured_metadata;
+pub use patterns::{MessagePattern, message_template};
 pub use resolution_url_annotators::annotate_resolution_or_discussion_urls;
+pub use rules::{CUSTOM_LABEL_PREFIX, CustomAnnotations, Rule, RuleSet};
 pub use subsystem_annotators::{SubsystemAnnotator, annotate_subsystems};
 pub use subsystems::Subsystem;
 
 use crate::parser::ParsedLogEntry;
 
 /// Annotate a log entry with all metadata in a single pass.
-/// This function consolidates subsystem, label, doc URL, and resolution URL annotation
-/// for better cache locality and performance.
+/// This function consolidates structured metadata, subsystem, label, doc URL,
+/// and resolution URL annotation for better cache locality and performance.
 #[inline]
 pub fn annotate_entry(entry: &mut ParsedLogEntry) {
+    annotate_structured_metadata(entry);
     annotate_subsystems(entry);
     entry.labels = annotate_labels(entry);
     if entry.labels.is_empty() {
@@ -49,3 +58,12 @@ pub fn annotate_entry(entry: &mut ParsedLogEntry) {
     annotate_doc_urls(entry);
     annotate_resolution_or_discussion_urls(entry);
 }
+
+/// Annotate a log entry with [`annotate_entry`], then apply the user-defined rules, if any.
+#[inline]
+pub fn annotate_entry_with_rules(entry: &mut ParsedLogEntry, rules: Option<&RuleSet>) {
+    annotate_entry(entry);
+    if let Some(rules) = rules {
+        rules.apply(entry);
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Message patterns (templates): messages with their variable parts, such as
+//! Erlang PIDs, references, IP addresses, node names, quoted resource names
+//! and numbers, replaced by placeholders.
+//!
+//! Entries that are the same message logged for different connections, queues
+//! or processes share a pattern, which makes it possible to rank the kinds
+//! of messages in a log set by frequency.
+
+use rabbitmq_lqt_obfuscation::patterns;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Templates longer than this are truncated
+pub const MAX_TEMPLATE_LENGTH: usize = 512;
+
+/// A template and its id, see [`MessagePattern::of`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MessagePattern {
+    pub id: i64,
+    pub template: String,
+}
+
+impl MessagePattern {
+    /// Computes the pattern of a message. Ids are stable across runs and
+    /// database files, so entries of databases produced separately can be compared.
+    pub fn of(message: &str) -> Self {
+        let template = message_template(message);
+        Self {
+            id: pattern_id(&template),
+            template,
+        }
+    }
+}
+
+struct Normalizer {
+    re: Regex,
+    replacement: &'static str,
+}
+
+impl Normalizer {
+    fn new(pattern: &str, replacement: &'static str) -> Self {
+        Self {
+            re: Regex::new(pattern).expect("normalizer patterns are valid regexes"),
+            replacement,
+        }
+    }
+}
+
+/// Applied in order: more specific patterns go first so that,
+/// for example, the digits of a PID are not replaced one by one.
+static NORMALIZERS: LazyLock<Vec<Normalizer>> = LazyLock::new(|| {
+    vec![
+        Normalizer::new(patterns::UNIX_PATH, "<path>"),
+        Normalizer::new(r"#Ref<[\d.]+>", "<ref>"),
+        Normalizer::new(r"#Port<[\d.]+>", "<port>"),
+        Normalizer::new(r"<\d+\.\d+\.\d+>", "<pid>"),
+        Normalizer::new(patterns::IPV6, "<ip>"),
+        Normalizer::new(&format!(r"{}(?::\d+)?", patterns::IPV4), "<ip>"),
+        Normalizer::new(patterns::NODE_NAME, "<node>"),
+        Normalizer::new(r#"<<"[^"]*">>"#, r#"<<"<*>">>"#),
+        Normalizer::new(r"'[^'\n]*'", "'<*>'"),
+        Normalizer::new(r#""[^"\n]*""#, r#""<*>""#),
+        Normalizer::new(
+            r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+            "<uuid>",
+        ),
+        Normalizer::new(r"\b(?:0x)?[0-9a-fA-F]{16,}\b", "<hex>"),
+        Normalizer::new(r"\b\d+(?:\.\d+)*\b", "<n>"),
+    ]
+});
+
+/// Computes the template of a message. Only the first line is used: the rest
+/// of multi-line messages, e.g. crash reports or stack traces, is mostly process state.
+pub fn message_template(message: &str) -> String {
+    let first_line = message.lines().next().unwrap_or_default().trim();
+
+    let mut template = first_line.to_string();
+    for normalizer in NORMALIZERS.iter() {
+        if normalizer.re.is_match(&template) {
+            template = normalizer
+                .re
+                .replace_all(&template, normalizer.replacement)
+                .into_owned();
+        }
+    }
+
+    if template.len() > MAX_TEMPLATE_LENGTH {
+        let mut end = MAX_TEMPLATE_LENGTH;
+        while !template.is_char_boundary(end) {
+            end -= 1;
+        }
+        template.truncate(end);
+    }
+    template
+}
+
+/// A 64-bit FNV-1a hash of the template. Unlike the standard library hashers,
+/// it is guaranteed to be the same across Rust versions and platforms.
+pub fn pattern_id(template: &str) -> i64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = template.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+    hash as i64
+}
@@ -0,0 +1,334 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! User-defined annotators loaded from a TOML rules file.
+//!
+//! A rules file is a list of `[[rules]]` tables. Each rule has a `name`,
+//! a `when` table with conditions and a `then` table with annotations:
+//!
+//! ```toml
+//! [[rules]]
+//! name = "billing-plugin-errors"
+//!
+//! [rules.when]
+//! starts_with = "[billing]"
+//! severity = "error"
+//!
+//! [rules.then]
+//! labels = ["plugins"]
+//! custom_labels = ["billing"]
+//! subsystem = "plugins"
+//! doc_url = "https://wiki.example.com/billing-plugin"
+//! ```
+//!
+//! An entry matches a rule when it satisfies every condition of the rule.
+//! Rules are applied in the order they are defined, after the built-in annotators,
+//! so a rule can use the subsystem assigned by the built-in annotators or by an earlier rule.
+//!
+//! `labels` refers to the built-in labels, `custom_labels` to labels that are
+//! only known to the rules file. Custom labels are displayed and queried
+//! with the [`CUSTOM_LABEL_PREFIX`], e.g. `custom:billing`.
+
+use crate::entry_metadata::labels::LogEntryLabels;
+use crate::entry_metadata::subsystems::Subsystem;
+use crate::errors::Error;
+use crate::parser::ParsedLogEntry;
+use crate::severity::Severity;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The prefix custom label names are displayed and queried with.
+pub const CUSTOM_LABEL_PREFIX: &str = "custom:";
+
+/// Annotations assigned by user-defined rules that do not fit
+/// the built-in label, subsystem and URL ids.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CustomAnnotations {
+    /// Custom label names, without the [`CUSTOM_LABEL_PREFIX`]
+    pub labels: Vec<String>,
+    pub doc_url: Option<String>,
+    pub resolution_or_discussion_url: Option<String>,
+}
+
+impl CustomAnnotations {
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+            && self.doc_url.is_none()
+            && self.resolution_or_discussion_url.is_none()
+    }
+
+    /// Custom label names with the [`CUSTOM_LABEL_PREFIX`], e.g. `custom:billing`
+    pub fn label_names(&self) -> impl Iterator<Item = String> + '_ {
+        self.labels
+            .iter()
+            .map(|label| format!("{}{}", CUSTOM_LABEL_PREFIX, label))
+    }
+
+    fn add_label(&mut self, label: &str) {
+        if !self.labels.iter().any(|l| l == label) {
+            self.labels.push(label.to_string());
+        }
+    }
+}
+
+/// Returns the custom label name if `label` has the [`CUSTOM_LABEL_PREFIX`].
+pub fn custom_label_name(label: &str) -> Option<&str> {
+    label
+        .strip_prefix(CUSTOM_LABEL_PREFIX)
+        .filter(|name| is_valid_custom_label(name))
+}
+
+/// Custom label names are limited to lowercase ASCII letters, digits,
+/// dashes and underscores, so that they can be used in QL queries and
+/// comma-separated lists as is.
+pub fn is_valid_custom_label(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    rules: Vec<RuleDefinition>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleDefinition {
+    name: String,
+    when: ConditionsDefinition,
+    then: ActionsDefinition,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConditionsDefinition {
+    contains: Option<String>,
+    starts_with: Option<String>,
+    regex: Option<String>,
+    severity: Option<String>,
+    subsystem: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ActionsDefinition {
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    custom_labels: Vec<String>,
+    subsystem: Option<String>,
+    doc_url: Option<String>,
+    resolution_url: Option<String>,
+}
+
+/// A user-defined annotator.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    contains: Option<String>,
+    starts_with: Option<String>,
+    regex: Option<Regex>,
+    severity: Option<Severity>,
+    subsystem: Option<Subsystem>,
+    labels: LogEntryLabels,
+    custom_labels: Vec<String>,
+    assigned_subsystem: Option<Subsystem>,
+    doc_url: Option<String>,
+    resolution_url: Option<String>,
+}
+
+impl Rule {
+    fn compile(definition: RuleDefinition) -> Result<Self, String> {
+        let name = definition.name;
+        let when = definition.when;
+        let then = definition.then;
+
+        if when.contains.is_none()
+            && when.starts_with.is_none()
+            && when.regex.is_none()
+            && when.severity.is_none()
+            && when.subsystem.is_none()
+        {
+            return Err(format!("rule '{}' has no conditions", name));
+        }
+        if then.labels.is_empty()
+            && then.custom_labels.is_empty()
+            && then.subsystem.is_none()
+            && then.doc_url.is_none()
+            && then.resolution_url.is_none()
+        {
+            return Err(format!("rule '{}' assigns no annotations", name));
+        }
+
+        let regex = when
+            .regex
+            .map(|pattern| {
+                Regex::new(&pattern)
+                    .map_err(|e| format!("rule '{}' has an invalid regex: {}", name, e))
+            })
+            .transpose()?;
+        let severity = when
+            .severity
+            .map(|s| Severity::from_str(&s).map_err(|e| format!("rule '{}': {}", name, e)))
+            .transpose()?;
+        let subsystem = when
+            .subsystem
+            .map(|s| parse_subsystem(&name, &s))
+            .transpose()?;
+        let assigned_subsystem = then
+            .subsystem
+            .map(|s| parse_subsystem(&name, &s))
+            .transpose()?;
+
+        let mut labels = LogEntryLabels::empty();
+        for label in &then.labels {
+            let flag = LogEntryLabels::from_label(label).ok_or_else(|| {
+                format!(
+                    "rule '{}' assigns an unknown label '{}', use custom_labels for labels of your own",
+                    name, label
+                )
+            })?;
+            labels |= flag;
+        }
+        for label in &then.custom_labels {
+            if !is_valid_custom_label(label) {
+                return Err(format!(
+                    "rule '{}' assigns an invalid custom label '{}': only lowercase letters, digits, '-' and '_' are allowed",
+                    name, label
+                ));
+            }
+        }
+
+        Ok(Self {
+            contains: when.contains.map(|s| s.to_lowercase()),
+            starts_with: when.starts_with,
+            regex,
+            severity,
+            subsystem,
+            labels,
+            custom_labels: then.custom_labels,
+            assigned_subsystem,
+            doc_url: then.doc_url,
+            resolution_url: then.resolution_url,
+            name,
+        })
+    }
+
+    /// Returns true if the entry satisfies every condition of this rule.
+    /// `contains` is case-insensitive, `starts_with` and `regex` are case-sensitive.
+    pub fn does_match(&self, entry: &ParsedLogEntry) -> bool {
+        self.severity.is_none_or(|s| entry.severity == s)
+            && self
+                .subsystem
+                .is_none_or(|s| entry.subsystem_id == Some(s.to_id()))
+            && self
+                .starts_with
+                .as_deref()
+                .is_none_or(|prefix| entry.message.starts_with(prefix))
+            && self
+                .contains
+                .as_deref()
+                .is_none_or(|needle| entry.message_lowercased.contains(needle))
+            && self
+                .regex
+                .as_ref()
+                .is_none_or(|re| re.is_match(&entry.message))
+    }
+
+    fn annotate(&self, entry: &mut ParsedLogEntry) {
+        if !self.labels.is_empty() {
+            if entry.labels == LogEntryLabels::UNLABELLED {
+                entry.labels = LogEntryLabels::empty();
+            }
+            entry.labels |= self.labels;
+        }
+        for label in &self.custom_labels {
+            entry.custom_annotations.add_label(label);
+        }
+        if let Some(subsystem) = self.assigned_subsystem {
+            entry.subsystem_id = Some(subsystem.to_id());
+        }
+        if let Some(ref url) = self.doc_url {
+            entry.custom_annotations.doc_url = Some(url.clone());
+        }
+        if let Some(ref url) = self.resolution_url {
+            entry.custom_annotations.resolution_or_discussion_url = Some(url.clone());
+        }
+    }
+}
+
+fn parse_subsystem(rule: &str, name: &str) -> Result<Subsystem, String> {
+    Subsystem::from_str(name)
+        .map_err(|_| format!("rule '{}' refers to an unknown subsystem '{}'", rule, name))
+}
+
+/// An ordered set of user-defined annotators.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let contents = fs::read_to_string(path)?;
+        Self::from_toml_str(&contents).map_err(|e| match e {
+            Error::InvalidRules(reason) => {
+                Error::InvalidRules(format!("{}: {}", path.display(), reason))
+            }
+            other => other,
+        })
+    }
+
+    pub fn from_toml_str(s: &str) -> Result<Self, Error> {
+        let file: RulesFile = toml::from_str(s)
+            .map_err(|e| Error::InvalidRules(e.to_string().trim_end().to_string()))?;
+        let rules = file
+            .rules
+            .into_iter()
+            .map(Rule::compile)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Error::InvalidRules)?;
+        Ok(Self { rules })
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Applies every matching rule to an entry that was annotated by [`annotate_entry`].
+    ///
+    /// [`annotate_entry`]: crate::entry_metadata::annotate_entry
+    pub fn apply(&self, entry: &mut ParsedLogEntry) {
+        for rule in &self.rules {
+            if rule.does_match(entry) {
+                rule.annotate(entry);
+            }
+        }
+    }
+}
@@ -33,6 +33,9 @@ pub enum Error {
     #[error("Failed to parse timestamp: {0}")]
     ParseTimestamp(String),
 
+    #[error("Invalid annotation rules: {0}")]
+    InvalidRules(String),
+
     #[error("Failed to read log file at line {line}: {source}")]
     ReadLine {
         line: usize,
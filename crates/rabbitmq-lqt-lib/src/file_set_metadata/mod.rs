@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::entry_metadata::labels::{LABEL_NAMES, LogEntryLabels};
+use crate::entry_metadata::labels::LogEntryLabels;
 use crate::entry_metadata::subsystems::Subsystem;
 use crate::parser::ParsedLogEntry;
 use crate::rel_db::file_metadata;
@@ -40,6 +40,24 @@ pub struct FileMetadataContext {
 }
 
 impl FileMetadataContext {
+    /// Resumes accumulation from the stored metadata of a file,
+    /// e.g. when only the entries appended to it since are ingested
+    pub fn from_model(model: file_metadata::Model) -> Self {
+        Self {
+            rabbitmq_versions: model.rabbitmq_versions,
+            erlang_versions: model.erlang_versions,
+            tls_library: model.tls_library,
+            oldest_entry_at: model.oldest_entry_at,
+            most_recent_entry_at: model.most_recent_entry_at,
+            total_lines: model.total_lines,
+            total_entries: model.total_entries,
+            nodes: model.nodes.into_iter().collect(),
+            subsystems: model.subsystems.into_iter().collect(),
+            labels: model.labels.into_iter().collect(),
+            enabled_plugins: model.enabled_plugins.into_iter().collect(),
+        }
+    }
+
     pub fn to_model(&self, file_path: String) -> file_metadata::Model {
         file_metadata::Model {
             file_path,
@@ -57,6 +75,59 @@ impl FileMetadataContext {
         }
     }
 
+    /// Accumulates the metadata of annotated entries. Entries must be added in the order
+    /// they appear in the file, possibly in several chunks, so that an entire file
+    /// does not have to be kept in memory.
+    pub fn add_entries(&mut self, entries: &[ParsedLogEntry]) {
+        let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+            return;
+        };
+        self.total_entries += entries.len() as i64;
+        // A rotated or truncated file can be followed by entries older than the stored ones
+        self.oldest_entry_at = Some(
+            self.oldest_entry_at
+                .map_or(first.timestamp, |oldest| oldest.min(first.timestamp)),
+        );
+        self.most_recent_entry_at = self.most_recent_entry_at.max(Some(last.timestamp));
+
+        for entry in entries {
+            self.add_message_metadata(&entry.message);
+        }
+        self.aggregate_from_entries(entries);
+    }
+
+    fn add_message_metadata(&mut self, message: &str) {
+        if let Some((rmq_ver, erl_ver)) = parse_startup_banner_line(message) {
+            if let Some(v) = rmq_ver {
+                self.rabbitmq_versions.push(v);
+            }
+            if let Some(v) = erl_ver {
+                self.erlang_versions.push(v);
+            }
+        }
+
+        if let Some(tls_lib) = parse_tls_library_line(message) {
+            self.tls_library = Some(tls_lib);
+        }
+
+        if let Some(expected_count) = parse_plugin_list_start(message) {
+            let mut actual_count = 0;
+            for line in message.lines().skip(1) {
+                if let Some(plugin_name) = parse_plugin_name(line) {
+                    self.enabled_plugins.insert(plugin_name);
+                    actual_count += 1;
+                }
+            }
+            if actual_count != expected_count {
+                log::warn!(
+                    "Plugin count mismatch: expected {}, found {}",
+                    expected_count,
+                    actual_count
+                );
+            }
+        }
+    }
+
     pub fn aggregate_from_entries(&mut self, entries: &[ParsedLogEntry]) {
         let mut combined_labels = LogEntryLabels::empty();
 
@@ -70,13 +141,8 @@ impl FileMetadataContext {
             combined_labels |= entry.labels;
         }
 
-        for &name in LABEL_NAMES {
-            if let Some(bit) = LogEntryLabels::bit_for_label(name) {
-                let flag = LogEntryLabels::from_bits_truncate(bit);
-                if combined_labels.contains(flag) {
-                    self.labels.insert(name.to_string());
-                }
-            }
+        for name in combined_labels.names() {
+            self.labels.insert(name.to_string());
         }
     }
 }
@@ -169,47 +235,7 @@ pub fn extract_file_metadata(
 ) -> file_metadata::Model {
     let mut ctx = FileMetadataContext::default();
     ctx.nodes.insert(node.to_string());
-
     ctx.total_lines = total_lines;
-    ctx.total_entries = entries.len() as i64;
-
-    if !entries.is_empty() {
-        ctx.oldest_entry_at = Some(entries[0].timestamp);
-        ctx.most_recent_entry_at = entries.last().map(|e| e.timestamp);
-    }
-
-    for entry in entries {
-        if let Some((rmq_ver, erl_ver)) = parse_startup_banner_line(&entry.message) {
-            if let Some(v) = rmq_ver {
-                ctx.rabbitmq_versions.push(v);
-            }
-            if let Some(v) = erl_ver {
-                ctx.erlang_versions.push(v);
-            }
-        }
-
-        if let Some(tls_lib) = parse_tls_library_line(&entry.message) {
-            ctx.tls_library = Some(tls_lib);
-        }
-
-        if let Some(expected_count) = parse_plugin_list_start(&entry.message) {
-            let mut actual_count = 0;
-            for line in entry.message.lines().skip(1) {
-                if let Some(plugin_name) = parse_plugin_name(line) {
-                    ctx.enabled_plugins.insert(plugin_name);
-                    actual_count += 1;
-                }
-            }
-            if actual_count != expected_count {
-                log::warn!(
-                    "Plugin count mismatch: expected {}, found {}",
-                    expected_count,
-                    actual_count
-                );
-            }
-        }
-    }
-
-    ctx.aggregate_from_entries(entries);
+    ctx.add_entries(entries);
     ctx.to_model(file_path)
 }
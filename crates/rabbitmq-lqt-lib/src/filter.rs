@@ -22,7 +22,7 @@ use std::str::FromStr;
 pub struct EntryFilter {
     severity: Option<Severity>,
     subsystem_id: Option<i16>,
-    label_mask: u64,
+    labels: LogEntryLabels,
     matching_all_labels: bool,
     erlang_pid: Option<String>,
     since_time: Option<DateTime<Utc>>,
@@ -50,8 +50,8 @@ impl EntryFilter {
 
     #[must_use]
     pub fn add_label(mut self, label: &str) -> Self {
-        if let Some(bit) = LogEntryLabels::bit_for_label(label) {
-            self.label_mask |= bit;
+        if let Some(flag) = LogEntryLabels::from_label(label) {
+            self.labels |= flag;
         }
         self
     }
@@ -96,7 +96,7 @@ impl EntryFilter {
     pub fn is_empty(&self) -> bool {
         self.severity.is_none()
             && self.subsystem_id.is_none()
-            && self.label_mask == 0
+            && self.labels.is_empty()
             && self.erlang_pid.is_none()
             && self.since_time.is_none()
             && self.to_time.is_none()
@@ -118,13 +118,12 @@ impl EntryFilter {
             return false;
         }
 
-        if self.label_mask != 0 {
-            let entry_bits = entry.labels.bits();
+        if !self.labels.is_empty() {
             if self.matching_all_labels {
-                if entry_bits & self.label_mask != self.label_mask {
+                if !entry.labels.contains(self.labels) {
                     return false;
                 }
-            } else if entry_bits & self.label_mask == 0 {
+            } else if !entry.labels.intersects(self.labels) {
                 return false;
             }
         }
@@ -147,12 +146,20 @@ impl EntryFilter {
             return false;
         }
 
-        if self.has_resolution_or_discussion_url && entry.resolution_or_discussion_url_id.is_none()
+        if self.has_resolution_or_discussion_url
+            && entry.resolution_or_discussion_url_id.is_none()
+            && entry
+                .custom_annotations
+                .resolution_or_discussion_url
+                .is_none()
         {
             return false;
         }
 
-        if self.has_doc_url && entry.doc_url_id.is_none() {
+        if self.has_doc_url
+            && entry.doc_url_id.is_none()
+            && entry.custom_annotations.doc_url.is_none()
+        {
             return false;
         }
 
@@ -13,29 +13,47 @@
 // limitations under the License.
 pub mod constants;
 pub mod datetime;
+pub mod diff;
 pub mod entry_metadata;
 pub mod errors;
 pub mod file_set_metadata;
 pub mod filter;
 pub mod parser;
 pub mod rel_db;
+pub mod search;
 pub mod severity;
+pub mod timeline;
 
 use std::result::Result as StdResult;
 
 pub use constants::{doc_url_from_id, resolution_or_discussion_url_from_id};
+pub use datetime::{TimeAnchor, TimeReference};
+pub use diff::{
+    CountDelta, CountDimension, DiffSide, Fact, FactChange, LogDiff, LogSetSummary, PatternChange,
+};
+pub use entry_metadata::entities::{Entity, EntityKind};
 pub use entry_metadata::label_annotators::annotate_labels;
 pub use entry_metadata::labels::LogEntryLabels;
+pub use entry_metadata::patterns::{MessagePattern, message_template};
+pub use entry_metadata::rules::{CustomAnnotations, RuleSet};
 pub use entry_metadata::subsystem_annotators::annotate_subsystems;
 pub use entry_metadata::subsystems::Subsystem;
 pub use errors::Error;
 pub use filter::EntryFilter;
-pub use parser::{IncrementalParser, ParseResult, ParsedLogEntry, parse_log_file};
+pub use parser::{
+    IncrementalParser, LogEntryStream, LogFormat, ParseResult, ParsedLogEntry, StructuredMetadata,
+    parse_log_file, parse_log_file_with_format,
+};
 pub use rel_db::{
-    DatabaseConnection, NodeLogEntry, QueryContext, QueryPreset, create_database,
-    create_database_for_bulk_import, finalize_bulk_import, open_database,
-    post_insertion_operations,
+    Aggregation, ArtifactKind, ConfigSetting, ContextScope, ContextWindow, DatabaseConnection,
+    EntityMentions, EntryAppender, LogColumn, MessagePatterns, MessageSearchIndex, NodeArtifacts,
+    NodeLogEntry, NodeLogEntryCustomAnnotations, NodeLogEntryEntities, NodeLogEntryLabels,
+    PatternFrequency, QueryContext, QueryPreset, SortKey, SqlCondition, SqlParam, TabularResult,
+    create_database, create_database_for_bulk_import, finalize_bulk_import, migrate_database,
+    obfuscate_database, open_database, post_insertion_operations,
 };
+pub use search::{SearchClause, SearchQuery};
 pub use severity::Severity;
+pub use timeline::{Timeline, TimelineEvent, TimelineOptions, Transition};
 
 pub type Result<T, E = Error> = StdResult<T, E>;
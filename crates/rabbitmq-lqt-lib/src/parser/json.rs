@@ -0,0 +1,189 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing of entries produced by RabbitMQ's JSON log formatter (`log.file.formatter = json`).
+//!
+//! Example:
+//! `{"time":"2025-10-27 18:23:00.123456+00:00","level":"info","msg":"Ready to start client connection listeners","pid":"<0.230.0>","domain":"rabbitmq","mfa":["rabbit_networking","boot",0],"file":"rabbit_networking.erl","line":145}`
+
+use super::{ParsedLogEntry, StructuredMetadata, parse_timestamp};
+use crate::Severity;
+use crate::entry_metadata::labels::LogEntryLabels;
+use crate::entry_metadata::rules::CustomAnnotations;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+/// Used when an entry does not carry a `pid`, same as for SASL reports.
+const UNKNOWN_PID: &str = "<0.0.0>";
+
+/// Epoch timestamps above this value are in microseconds (`time_format = epoch_usecs`),
+/// below it, in seconds (`time_format = epoch_secs`).
+const EPOCH_MICROS_THRESHOLD: i64 = 100_000_000_000;
+
+#[derive(Deserialize)]
+struct JsonLogLine {
+    #[serde(alias = "timestamp", alias = "ts")]
+    time: JsonValue,
+    #[serde(alias = "severity", alias = "lvl")]
+    level: String,
+    #[serde(alias = "message")]
+    msg: JsonValue,
+    #[serde(default)]
+    pid: Option<String>,
+    #[serde(default)]
+    domain: Option<JsonValue>,
+    #[serde(default)]
+    mfa: Option<JsonValue>,
+    #[serde(default)]
+    file: Option<String>,
+    #[serde(default)]
+    line: Option<JsonValue>,
+    #[serde(default)]
+    connection: Option<String>,
+    #[serde(default)]
+    vhost: Option<String>,
+    #[serde(default)]
+    user: Option<String>,
+}
+
+/// Parses a single JSON log line. Returns `None` for lines that are not
+/// JSON objects or lack the `time`, `level` or `msg` fields.
+pub fn parse_json_log_entry(line: &str) -> Option<ParsedLogEntry> {
+    let trimmed = line.trim();
+    if !trimmed.starts_with('{') {
+        return None;
+    }
+
+    let parsed: JsonLogLine = serde_json::from_str(trimmed).ok()?;
+    let timestamp = parse_json_time(&parsed.time)?;
+    let severity = parse_json_level(&parsed.level)?;
+
+    let message = match parsed.msg {
+        JsonValue::String(s) => s.trim_end().to_string(),
+        other => other.to_string(),
+    };
+    let message_lowercased = message.to_lowercase();
+
+    let metadata = StructuredMetadata {
+        domain: parsed.domain.as_ref().and_then(format_domain),
+        mfa: parsed.mfa.as_ref().and_then(format_mfa),
+        file: parsed.file,
+        line: parsed.line.as_ref().and_then(parse_line_number),
+        connection: parsed.connection,
+        vhost: parsed.vhost,
+        user: parsed.user,
+    };
+
+    Some(ParsedLogEntry {
+        sequence_id: 0,
+        explicit_id: None,
+        timestamp,
+        severity,
+        process_id: parsed.pid.unwrap_or_else(|| UNKNOWN_PID.to_string()),
+        message,
+        message_lowercased,
+        subsystem_id: None,
+        labels: LogEntryLabels::default(),
+        resolution_or_discussion_url_id: None,
+        doc_url_id: None,
+        metadata,
+        custom_annotations: CustomAnnotations::default(),
+    })
+}
+
+/// Supports the `rfc3339_space` (default), `rfc3339_T`, `epoch_usecs` and `epoch_secs` time formats.
+fn parse_json_time(value: &JsonValue) -> Option<DateTime<Utc>> {
+    match value {
+        JsonValue::String(s) => {
+            if let Ok((_, ts)) = parse_timestamp(s) {
+                return Some(ts);
+            }
+            DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc))
+        }
+        JsonValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                if i.abs() >= EPOCH_MICROS_THRESHOLD {
+                    DateTime::from_timestamp_micros(i)
+                } else {
+                    DateTime::from_timestamp(i, 0)
+                }
+            } else {
+                let secs = n.as_f64()?;
+                DateTime::from_timestamp_micros((secs * 1_000_000.0) as i64)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Erlang's logger has two levels above `critical` that RabbitMQ's plain text
+/// formatter never emits: they are treated as `critical`.
+fn parse_json_level(level: &str) -> Option<Severity> {
+    match level.to_ascii_lowercase().as_str() {
+        "debug" => Some(Severity::Debug),
+        "info" => Some(Severity::Info),
+        "notice" => Some(Severity::Notice),
+        "warning" | "warn" => Some(Severity::Warning),
+        "error" | "err" => Some(Severity::Error),
+        "critical" | "crit" | "alert" | "emergency" | "emerg" => Some(Severity::Critical),
+        _ => None,
+    }
+}
+
+/// Domains are logged either as a dot-separated string or as a list of atoms.
+fn format_domain(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(s) if !s.is_empty() => Some(s.clone()),
+        JsonValue::Array(parts) if !parts.is_empty() => Some(
+            parts
+                .iter()
+                .map(json_scalar_to_string)
+                .collect::<Vec<_>>()
+                .join("."),
+        ),
+        _ => None,
+    }
+}
+
+/// MFAs are logged either as a `[module, function, arity]` list or as a string.
+fn format_mfa(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(s) if !s.is_empty() => Some(s.clone()),
+        JsonValue::Array(parts) if parts.len() == 3 => Some(format!(
+            "{}:{}/{}",
+            json_scalar_to_string(&parts[0]),
+            json_scalar_to_string(&parts[1]),
+            json_scalar_to_string(&parts[2])
+        )),
+        _ => None,
+    }
+}
+
+fn parse_line_number(value: &JsonValue) -> Option<u32> {
+    match value {
+        JsonValue::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+        JsonValue::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn json_scalar_to_string(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
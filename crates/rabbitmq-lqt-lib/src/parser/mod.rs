@@ -11,7 +11,10 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+mod json;
+
 use crate::entry_metadata::labels::LogEntryLabels;
+use crate::entry_metadata::rules::CustomAnnotations;
 use crate::{Result, Severity};
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use nom::{
@@ -24,12 +27,17 @@ use nom::{
     sequence::delimited,
 };
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::fmt;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
+use std::str::FromStr;
 use std::sync::LazyLock;
 
+pub use json::parse_json_log_entry;
+
 static ANSI_ESCAPE_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*m").expect("Invalid ANSI regex"));
 
@@ -44,6 +52,82 @@ fn strip_ansi_codes(input: &str) -> Cow<'_, str> {
 /// according to benchmarks and profiling.
 const INITIAL_ENTRIES_CAPACITY: usize = 16384;
 
+/// The layout of a log file, see `log.file.formatter` in `rabbitmq.conf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LogFormat {
+    /// `2025-10-27 18:23:00.123456+00:00 [info] <0.208.0> Message`, possibly
+    /// interleaved with OTP SASL reports
+    #[default]
+    Plaintext,
+    /// One JSON object per line
+    Json,
+}
+
+impl LogFormat {
+    /// Detects the format from a line of a log file, typically the first non-blank one.
+    pub fn detect(line: &str) -> Self {
+        let stripped_line = strip_ansi_codes(line);
+        if stripped_line.trim_start().starts_with('{') {
+            Self::Json
+        } else {
+            Self::Plaintext
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Plaintext => "plaintext",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "plaintext" | "plain" | "text" => Ok(Self::Plaintext),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Invalid log format: '{}'", s)),
+        }
+    }
+}
+
+/// Logger metadata that accompanies an entry. The JSON formatter
+/// emits these as top-level fields. For plain text entries, the virtual host,
+/// user and connection are extracted from the message during annotation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StructuredMetadata {
+    /// Logger domain, e.g. `rabbitmq.prelaunch`
+    pub domain: Option<String>,
+    /// The function that logged the entry, as `module:function/arity`
+    pub mfa: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub connection: Option<String>,
+    pub vhost: Option<String>,
+    pub user: Option<String>,
+}
+
+impl StructuredMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.domain.is_none()
+            && self.mfa.is_none()
+            && self.file.is_none()
+            && self.line.is_none()
+            && self.connection.is_none()
+            && self.vhost.is_none()
+            && self.user.is_none()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ParsedLogEntry {
     pub sequence_id: usize,
@@ -57,6 +141,11 @@ pub struct ParsedLogEntry {
     pub labels: LogEntryLabels,
     pub resolution_or_discussion_url_id: Option<i16>,
     pub doc_url_id: Option<i16>,
+    pub metadata: StructuredMetadata,
+    /// Annotations assigned by user-defined rules, see [`RuleSet`]
+    ///
+    /// [`RuleSet`]: crate::entry_metadata::rules::RuleSet
+    pub custom_annotations: CustomAnnotations,
 }
 
 impl ParsedLogEntry {
@@ -89,97 +178,247 @@ impl ParsedLogEntry {
 pub struct ParseResult {
     pub entries: Vec<ParsedLogEntry>,
     pub total_lines: usize,
+    pub format: LogFormat,
 }
 
-fn process_new_entry(
-    entries: &mut Vec<ParsedLogEntry>,
-    current_entry: &mut Option<ParsedLogEntry>,
-    new_entry: ParsedLogEntry,
-) {
-    let is_continuation = current_entry
-        .as_ref()
-        .is_some_and(|prev| new_entry.is_continuation_of(prev));
+/// Parses a log file, detecting its [`LogFormat`] from the first non-blank line.
+pub fn parse_log_file<R: BufRead>(reader: R) -> Result<ParseResult> {
+    collect_entries(LogEntryStream::new(reader))
+}
 
-    match (is_continuation, current_entry.as_mut()) {
-        (true, Some(prev_entry)) => {
-            prev_entry.append_continuation(&new_entry.message);
-        }
-        (false, _) => {
-            if let Some(prev_entry) = current_entry.take() {
-                entries.push(prev_entry);
-            }
-            *current_entry = Some(new_entry);
-        }
-        (true, None) => {}
-    }
+/// Like [`parse_log_file`] but skips format detection.
+pub fn parse_log_file_with_format<R: BufRead>(reader: R, format: LogFormat) -> Result<ParseResult> {
+    collect_entries(LogEntryStream::with_format(reader, format))
 }
 
-fn process_continuation_line(
-    current_entry: &mut Option<ParsedLogEntry>,
-    line: &str,
-    line_number: usize,
-) {
-    if let Some(entry) = current_entry {
-        entry.append_continuation(line.trim_end());
-    } else {
-        log::warn!("Orphaned continuation line {}: {}", line_number + 1, line);
+fn collect_entries<R: BufRead>(mut stream: LogEntryStream<R>) -> Result<ParseResult> {
+    let mut entries = Vec::with_capacity(INITIAL_ENTRIES_CAPACITY);
+    for entry in stream.by_ref() {
+        entries.push(entry?);
     }
+
+    Ok(ParseResult {
+        entries,
+        total_lines: stream.total_lines(),
+        format: stream.format().unwrap_or_default(),
+    })
 }
 
-pub fn parse_log_file<R: BufRead>(reader: R) -> Result<ParseResult> {
-    let mut entries = Vec::with_capacity(INITIAL_ENTRIES_CAPACITY);
-    let mut current_entry: Option<ParsedLogEntry> = None;
-    let mut total_lines = 0;
-
-    for (line_number, line_result) in reader.lines().enumerate() {
-        total_lines = line_number + 1;
-        let line = line_result.map_err(|e| crate::Error::ReadLine {
-            line: total_lines,
-            source: e,
-        })?;
-
-        let stripped_line = strip_ansi_codes(&line);
-        match parse_log_entry(&stripped_line) {
-            Ok((_, entry)) => process_new_entry(&mut entries, &mut current_entry, entry),
-            Err(_) => process_continuation_line(&mut current_entry, &stripped_line, line_number),
+/// Parses a log file one entry at a time, so that memory usage does not
+/// depend on the size of the file. [`parse_log_file`] collects the entries
+/// of such a stream into a `Vec`.
+pub struct LogEntryStream<R> {
+    reader: R,
+    parser: IncrementalParser,
+    line: String,
+    bytes_read: u64,
+    defer_incomplete_lines: bool,
+    hold_back_last_entry: bool,
+    /// Where the entry being parsed starts
+    pending_entry_offset: u64,
+    held_back: Option<ParsedLogEntry>,
+    held_back_lines: usize,
+    /// Set once the end of the input was reached or an incomplete line was deferred,
+    /// since what follows the latter is unknown
+    ended: bool,
+}
+
+impl<R: BufRead> LogEntryStream<R> {
+    /// Creates a stream that detects the [`LogFormat`] from the first non-blank line.
+    pub fn new(reader: R) -> Self {
+        Self::with_parser(reader, IncrementalParser::new(0))
+    }
+
+    pub fn with_format(reader: R, format: LogFormat) -> Self {
+        Self::with_parser(reader, IncrementalParser::with_format(0, format))
+    }
+
+    fn with_parser(reader: R, parser: IncrementalParser) -> Self {
+        Self {
+            reader,
+            parser,
+            line: String::new(),
+            bytes_read: 0,
+            defer_incomplete_lines: false,
+            hold_back_last_entry: false,
+            pending_entry_offset: 0,
+            held_back: None,
+            held_back_lines: 0,
+            ended: false,
         }
     }
 
-    if let Some(entry) = current_entry {
-        entries.push(entry);
+    /// Ends the stream before a last line without a line ending, e.g. one a node is
+    /// still writing, instead of parsing it. The last entry is held back as well,
+    /// see [`Self::holding_back_last_entry`], and [`Self::bytes_read`] includes neither,
+    /// so that both can be read in full later on.
+    pub fn deferring_incomplete_lines(mut self) -> Self {
+        self.defer_incomplete_lines = true;
+        self.holding_back_last_entry()
     }
 
-    for (i, entry) in entries.iter_mut().enumerate() {
-        entry.sequence_id = i;
+    /// Does not yield the last entry, since more of its lines can be appended to
+    /// the input later, e.g. those of a crash report. It can be taken with
+    /// [`Self::take_held_back`] once the stream has ended. [`Self::bytes_read`]
+    /// is then the offset of its first line, where reading can resume.
+    pub fn holding_back_last_entry(mut self) -> Self {
+        self.hold_back_last_entry = true;
+        self
     }
 
-    Ok(ParseResult {
-        entries,
-        total_lines,
-    })
+    /// Takes the last entry once the stream has ended, if it was held back,
+    /// see [`Self::holding_back_last_entry`].
+    pub fn take_held_back(&mut self) -> Option<ParsedLogEntry> {
+        self.held_back.take()
+    }
+
+    /// The number of lines of the last entry if it was held back.
+    /// These lines are included in [`Self::total_lines`].
+    pub fn held_back_lines(&self) -> usize {
+        self.held_back_lines
+    }
+
+    /// Returns the format of the input, if it is known yet.
+    pub fn format(&self) -> Option<LogFormat> {
+        self.parser.format()
+    }
+
+    /// The number of lines read so far.
+    pub fn total_lines(&self) -> usize {
+        self.parser.total_lines()
+    }
+
+    /// The number of bytes read so far, including line endings.
+    /// For compressed files, this is the decompressed size.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    fn end(&mut self) -> Option<Result<ParsedLogEntry>> {
+        self.ended = true;
+        if !self.hold_back_last_entry {
+            return self.parser.flush().map(Ok);
+        }
+        let first_line = self.parser.pending_entry_first_line()?;
+        self.held_back_lines = self.parser.total_lines() + 1 - first_line;
+        self.held_back = self.parser.flush();
+        self.bytes_read = self.pending_entry_offset;
+        None
+    }
+}
+
+impl<R: BufRead> Iterator for LogEntryStream<R> {
+    type Item = Result<ParsedLogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ended {
+            return None;
+        }
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return self.end(),
+                Ok(_) if self.defer_incomplete_lines && !self.line.ends_with('\n') => {
+                    return self.end();
+                }
+                Ok(n) => {
+                    let offset = self.bytes_read;
+                    self.bytes_read += n as u64;
+                    let line = self
+                        .line
+                        .strip_suffix('\n')
+                        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+                        .unwrap_or(&self.line);
+                    let completed = self.parser.feed_line(line);
+                    if self.parser.pending_entry_first_line() == Some(self.parser.total_lines()) {
+                        self.pending_entry_offset = offset;
+                    }
+                    if let Some(entry) = completed {
+                        return Some(Ok(entry));
+                    }
+                }
+                Err(e) => {
+                    return Some(Err(crate::Error::ReadLine {
+                        line: self.parser.total_lines() + 1,
+                        source: e,
+                    }));
+                }
+            }
+        }
+    }
 }
 
 pub struct IncrementalParser {
     next_sequence_id: usize,
     current_entry: Option<ParsedLogEntry>,
+    /// The line the current entry starts on, counting from 1
+    current_entry_first_line: usize,
+    format: Option<LogFormat>,
+    total_lines: usize,
 }
 
 impl IncrementalParser {
+    /// Creates a parser that detects the [`LogFormat`] from the first non-blank line it is fed.
     pub fn new(start_sequence_id: usize) -> Self {
         Self {
             next_sequence_id: start_sequence_id,
             current_entry: None,
+            current_entry_first_line: 0,
+            format: None,
+            total_lines: 0,
         }
     }
 
+    pub fn with_format(start_sequence_id: usize, format: LogFormat) -> Self {
+        Self {
+            next_sequence_id: start_sequence_id,
+            current_entry: None,
+            current_entry_first_line: 0,
+            format: Some(format),
+            total_lines: 0,
+        }
+    }
+
+    /// Returns the format of the input, if it is known yet.
+    pub fn format(&self) -> Option<LogFormat> {
+        self.format
+    }
+
+    /// The number of lines fed so far.
+    pub fn total_lines(&self) -> usize {
+        self.total_lines
+    }
+
+    /// The line the entry that is not complete yet starts on, counting from 1.
+    pub fn pending_entry_first_line(&self) -> Option<usize> {
+        self.current_entry
+            .as_ref()
+            .map(|_| self.current_entry_first_line)
+    }
+
+    /// Feeds a line without its line ending. Returns the previous entry
+    /// once it is known to be complete, that is, when this line starts a new one.
+    ///
+    /// Entries of structured formats such as [`LogFormat::Json`] are never split
+    /// across lines, so they are never merged.
     pub fn feed_line(&mut self, line: &str) -> Option<ParsedLogEntry> {
+        self.total_lines += 1;
         let stripped_line = strip_ansi_codes(line);
-        match parse_log_entry(&stripped_line) {
-            Ok((_, new_entry)) => {
-                let is_continuation = self
-                    .current_entry
-                    .as_ref()
-                    .is_some_and(|prev| new_entry.is_continuation_of(prev));
+        if self.format.is_none() && !stripped_line.trim().is_empty() {
+            self.format = Some(LogFormat::detect(&stripped_line));
+        }
+
+        let parsed = match self.format {
+            Some(LogFormat::Json) => parse_json_log_entry(&stripped_line),
+            _ => parse_log_entry(&stripped_line).ok().map(|(_, entry)| entry),
+        };
+        match parsed {
+            Some(new_entry) => {
+                let is_continuation = self.format != Some(LogFormat::Json)
+                    && self
+                        .current_entry
+                        .as_ref()
+                        .is_some_and(|prev| new_entry.is_continuation_of(prev));
 
                 match (is_continuation, self.current_entry.as_mut()) {
                     (true, Some(prev_entry)) => {
@@ -193,14 +432,17 @@ impl IncrementalParser {
                             e
                         });
                         self.current_entry = Some(new_entry);
+                        self.current_entry_first_line = self.total_lines;
                         completed
                     }
                     (true, None) => None,
                 }
             }
-            Err(_) => {
+            None => {
                 if let Some(entry) = self.current_entry.as_mut() {
                     entry.append_continuation(stripped_line.trim_end());
+                } else {
+                    log::warn!("Orphaned continuation line {}: {}", self.total_lines, line);
                 }
                 None
             }
@@ -253,6 +495,8 @@ fn parse_standard_log_entry(input: &str) -> IResult<&str, ParsedLogEntry> {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: CustomAnnotations::default(),
         },
     ))
 }
@@ -280,6 +524,8 @@ fn parse_sasl_report_header(input: &str) -> IResult<&str, ParsedLogEntry> {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: CustomAnnotations::default(),
         },
     ))
 }
@@ -402,7 +648,7 @@ fn nom_verify_error() -> NomErr<NomError<&'static str>> {
 }
 
 #[inline]
-fn build_datetime(
+pub(crate) fn build_datetime(
     date: (i32, u32, u32),
     time: (u32, u32, u32, u32),
     tz_offset_seconds: i32,
@@ -428,7 +674,7 @@ fn build_datetime(
 }
 
 #[inline]
-fn parse_timestamp(input: &str) -> IResult<&str, DateTime<Utc>> {
+pub(crate) fn parse_timestamp(input: &str) -> IResult<&str, DateTime<Utc>> {
     let (input, date) = parse_date(input)?;
     let (input, _) = space1.parse(input)?;
     let (input, time) = parse_time(input)?;
@@ -0,0 +1,246 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::entry_metadata::entities::EntityKind;
+use crate::entry_metadata::labels::LogEntryLabels;
+use crate::entry_metadata::subsystems::Subsystem;
+use crate::severity::Severity;
+use chrono::DateTime;
+use duckdb::Row;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// A `node_log_entries` column that can be projected, deduplicated or grouped by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogColumn {
+    Id,
+    Node,
+    Timestamp,
+    Severity,
+    ErlangPid,
+    Subsystem,
+    Message,
+    Labels,
+    Domain,
+    Mfa,
+    SourceFile,
+    SourceLine,
+    Connection,
+    Vhost,
+    User,
+    Queue,
+    Stream,
+    Exchange,
+    Pattern,
+}
+
+impl LogColumn {
+    /// The name used in result headers and JSON responses.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogColumn::Id => "id",
+            LogColumn::Node => "node",
+            LogColumn::Timestamp => "timestamp",
+            LogColumn::Severity => "severity",
+            LogColumn::ErlangPid => "erlang_pid",
+            LogColumn::Subsystem => "subsystem",
+            LogColumn::Message => "message",
+            LogColumn::Labels => "labels",
+            LogColumn::Domain => "domain",
+            LogColumn::Mfa => "mfa",
+            LogColumn::SourceFile => "file",
+            LogColumn::SourceLine => "line",
+            LogColumn::Connection => "connection",
+            LogColumn::Vhost => "vhost",
+            LogColumn::User => "user",
+            LogColumn::Queue => "queue",
+            LogColumn::Stream => "stream",
+            LogColumn::Exchange => "exchange",
+            LogColumn::Pattern => "pattern",
+        }
+    }
+
+    /// The column with the given [`name`](Self::name), e.g. `file` or `user`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let column = match name {
+            "id" => LogColumn::Id,
+            "node" => LogColumn::Node,
+            "timestamp" => LogColumn::Timestamp,
+            "severity" => LogColumn::Severity,
+            "erlang_pid" => LogColumn::ErlangPid,
+            "subsystem" => LogColumn::Subsystem,
+            "message" => LogColumn::Message,
+            "labels" => LogColumn::Labels,
+            "domain" => LogColumn::Domain,
+            "mfa" => LogColumn::Mfa,
+            "file" => LogColumn::SourceFile,
+            "line" => LogColumn::SourceLine,
+            "connection" => LogColumn::Connection,
+            "vhost" => LogColumn::Vhost,
+            "user" => LogColumn::User,
+            "queue" => LogColumn::Queue,
+            "stream" => LogColumn::Stream,
+            "exchange" => LogColumn::Exchange,
+            "pattern" => LogColumn::Pattern,
+            _ => return None,
+        };
+        Some(column)
+    }
+
+    /// Whether query results can be ordered by this column. Columns that are
+    /// joined in from other tables cannot.
+    pub fn is_sortable(&self) -> bool {
+        !matches!(self, LogColumn::Labels | LogColumn::Pattern) && self.entity_kind().is_none()
+    }
+
+    /// The underlying column in the `node_log_entries` table. Labels are
+    /// joined in from `node_log_entry_labels` as a list of label ids,
+    /// queues, streams and exchanges from `node_log_entry_entities`,
+    /// pattern templates from `message_patterns`.
+    pub fn sql_column(&self) -> &'static str {
+        match self {
+            LogColumn::Id => "id",
+            LogColumn::Node => "node",
+            LogColumn::Timestamp => "timestamp",
+            LogColumn::Severity => "severity",
+            LogColumn::ErlangPid => "erlang_pid",
+            LogColumn::Subsystem => "subsystem_id",
+            LogColumn::Message => "message",
+            LogColumn::Labels => "labels",
+            LogColumn::Domain => "domain",
+            LogColumn::Mfa => "mfa",
+            LogColumn::SourceFile => "source_file",
+            LogColumn::SourceLine => "source_line",
+            LogColumn::Connection => "connection",
+            LogColumn::Vhost => "vhost",
+            LogColumn::User => "username",
+            LogColumn::Queue => "queue",
+            LogColumn::Stream => "stream",
+            LogColumn::Exchange => "exchange",
+            LogColumn::Pattern => "pattern",
+        }
+    }
+
+    /// The kind of entity this column is joined in from `node_log_entry_entities`, if any.
+    pub fn entity_kind(&self) -> Option<EntityKind> {
+        match self {
+            LogColumn::Queue => Some(EntityKind::Queue),
+            LogColumn::Stream => Some(EntityKind::Stream),
+            LogColumn::Exchange => Some(EntityKind::Exchange),
+            _ => None,
+        }
+    }
+
+    /// Reads the value of this column at `idx` and converts it to its display form:
+    /// subsystem ids become names, label id lists become lists of label names.
+    pub(crate) fn read_value(&self, row: &Row<'_>, idx: usize) -> duckdb::Result<JsonValue> {
+        let value = match self {
+            LogColumn::Id => JsonValue::from(row.get::<_, i64>(idx)?),
+            LogColumn::Node
+            | LogColumn::ErlangPid
+            | LogColumn::Message
+            | LogColumn::Domain
+            | LogColumn::Mfa
+            | LogColumn::SourceFile
+            | LogColumn::Connection
+            | LogColumn::Vhost
+            | LogColumn::User
+            | LogColumn::Queue
+            | LogColumn::Stream
+            | LogColumn::Exchange
+            | LogColumn::Pattern => JsonValue::from(row.get::<_, Option<String>>(idx)?),
+            LogColumn::SourceLine => JsonValue::from(row.get::<_, Option<i32>>(idx)?),
+            LogColumn::Timestamp => {
+                let micros: i64 = row.get(idx)?;
+                match DateTime::from_timestamp_micros(micros) {
+                    Some(ts) => JsonValue::from(ts.to_rfc3339()),
+                    None => JsonValue::Null,
+                }
+            }
+            LogColumn::Severity => {
+                let ordinal: Option<i8> = row.get(idx)?;
+                match ordinal.and_then(Severity::from_ordinal) {
+                    Some(severity) => JsonValue::from(severity.as_str()),
+                    None => JsonValue::Null,
+                }
+            }
+            LogColumn::Subsystem => {
+                let id: Option<i16> = row.get(idx)?;
+                match id.and_then(Subsystem::from_id) {
+                    Some(subsystem) => JsonValue::from(subsystem.to_string()),
+                    None => JsonValue::Null,
+                }
+            }
+            LogColumn::Labels => {
+                let ids: Option<String> = row.get(idx)?;
+                let labels = ids
+                    .map(|ids| LogEntryLabels::from_id_list(&ids))
+                    .unwrap_or_default();
+                JsonValue::Array(labels.names().map(JsonValue::from).collect())
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// A result-shaping stage that is executed by the database instead of
+/// returning full log entry rows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    /// Returns only the given columns.
+    Project(Vec<LogColumn>),
+    /// Returns unique combinations of the given columns.
+    Distinct(Vec<LogColumn>),
+    /// Counts matching entries, optionally grouped by a column.
+    CountBy(Option<LogColumn>),
+}
+
+impl Aggregation {
+    /// The columns this stage projects, deduplicates or groups by.
+    pub fn columns(&self) -> &[LogColumn] {
+        match self {
+            Aggregation::Project(columns) | Aggregation::Distinct(columns) => columns,
+            Aggregation::CountBy(Some(column)) => std::slice::from_ref(column),
+            Aggregation::CountBy(None) => &[],
+        }
+    }
+}
+
+/// Column names plus rows of an aggregated or projected query.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TabularResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<JsonValue>>,
+}
+
+impl TabularResult {
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+/// Formats a single cell value for plain text output.
+pub fn format_cell(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Array(items) => items.iter().map(format_cell).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Entries logged around a given one, see [`QueryContext::context_window`](crate::QueryContext::context_window).
+//!
+//! The neighbours of an entry are looked up with two range queries over
+//! `(timestamp, id)`, one in each direction, so that they use the timestamp
+//! indexes created by [`crate::post_insertion_operations`] instead of numbering
+//! every entry of the table. Only the first [`ContextWindow::MAX_HITS`] matching
+//! entries of a query are expanded.
+
+use crate::rel_db::node_log_entry::{Model, model_from_row, model_select_list};
+use duckdb::types::{TimeUnit, Value};
+use duckdb::{Connection, Error as DuckDbError, ToSql};
+use serde::{Deserialize, Serialize};
+
+/// Which entries count as the neighbours of an entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextScope {
+    /// Entries of all nodes
+    #[default]
+    All,
+    /// Entries of the same node
+    Node,
+    /// Entries of the same Erlang process on the same node
+    Process,
+}
+
+/// How many entries to include before and after each matching entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ContextWindow {
+    pub before: u64,
+    pub after: u64,
+    pub scope: ContextScope,
+}
+
+impl ContextWindow {
+    /// The largest number of entries a window includes on either side of an entry.
+    /// The CLI, the HTTP API and the QL compiler reject larger windows,
+    /// [`Self::new`] clamps them.
+    pub const MAX_ENTRIES: u64 = 1000;
+
+    /// The largest number of matching entries of a query that are expanded,
+    /// each with its own window. Queries with a context window return
+    /// at most this many matching entries.
+    pub const MAX_HITS: u64 = 100;
+
+    /// A window of `before` and `after` entries, each clamped to [`Self::MAX_ENTRIES`]
+    pub fn new(before: u64, after: u64) -> Self {
+        Self {
+            before: before.min(Self::MAX_ENTRIES),
+            after: after.min(Self::MAX_ENTRIES),
+            scope: ContextScope::All,
+        }
+    }
+
+    /// The same number of entries on either side
+    pub fn around(n: u64) -> Self {
+        Self::new(n, n)
+    }
+
+    #[must_use]
+    pub fn scoped(mut self, scope: ContextScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Up to [`before`](Self::before) entries logged before `anchor`,
+    /// `anchor` itself and up to [`after`](Self::after) entries logged after it,
+    /// in timestamp order. Entries logged at the same time are ordered by id.
+    pub(crate) fn entries_around(
+        &self,
+        conn: &Connection,
+        anchor: &Model,
+    ) -> Result<Vec<Model>, DuckDbError> {
+        let mut entries = self.neighbours(conn, anchor, false, self.before)?;
+        entries.reverse();
+        entries.push(anchor.clone());
+        entries.extend(self.neighbours(conn, anchor, true, self.after)?);
+        Ok(entries)
+    }
+
+    fn neighbours(
+        &self,
+        conn: &Connection,
+        anchor: &Model,
+        following: bool,
+        n: u64,
+    ) -> Result<Vec<Model>, DuckDbError> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+
+        let (comparison, direction) = if following {
+            (">", "ASC")
+        } else {
+            ("<", "DESC")
+        };
+        let timestamp =
+            Value::Timestamp(TimeUnit::Microsecond, anchor.timestamp.timestamp_micros());
+        let mut conditions = vec![format!(
+            "(timestamp {} ? OR (timestamp = ? AND id {} ?))",
+            comparison, comparison
+        )];
+        let mut params = vec![timestamp.clone(), timestamp, Value::BigInt(anchor.id)];
+        if self.scope != ContextScope::All {
+            conditions.push("node = ?".to_string());
+            params.push(Value::Text(anchor.node.clone()));
+        }
+        if self.scope == ContextScope::Process {
+            conditions.push("erlang_pid = ?".to_string());
+            params.push(Value::Text(anchor.erlang_pid.clone()));
+        }
+
+        let sql = format!(
+            "SELECT {} FROM node_log_entries
+             WHERE {}
+             ORDER BY timestamp {}, id {}
+             LIMIT {}",
+            model_select_list("node_log_entries"),
+            conditions.join(" AND "),
+            direction,
+            direction,
+            n
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let params_slice: Vec<&dyn ToSql> = params.iter().map(|p| p as &dyn ToSql).collect();
+        let rows = stmt.query_map(params_slice.as_slice(), model_from_row)?;
+        rows.collect()
+    }
+}
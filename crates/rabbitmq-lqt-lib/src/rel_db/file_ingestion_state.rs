@@ -0,0 +1,138 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Where the ingestion of every log file stopped, so that `logs merge` and `logs sync`
+//! only read what was appended to a file since it was last ingested.
+//!
+//! A file is identified by its inode and a hash of its first bytes (its head). If either
+//! changes, or the file shrinks, it was rotated or truncated and is read from the start.
+
+use crate::rel_db::DatabaseConnection;
+use duckdb::{Error as DuckDbError, OptionalExt, Row, params};
+use serde::{Deserialize, Serialize};
+use std::io::Error as IoError;
+
+pub(crate) const FILE_INGESTION_STATE_TABLE_DDL: &str = "
+        CREATE TABLE IF NOT EXISTS file_ingestion_state (
+            file_path VARCHAR PRIMARY KEY,
+            node VARCHAR NOT NULL,
+            inode UBIGINT,
+            file_size UBIGINT NOT NULL,
+            ingested_bytes UBIGINT NOT NULL,
+            tail_lines UBIGINT NOT NULL DEFAULT 0,
+            head_length UBIGINT NOT NULL,
+            head_hash VARCHAR NOT NULL
+        );
+";
+
+pub struct FileIngestionState;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Model {
+    /// The same path as in `file_metadata`
+    pub file_path: String,
+    /// The node the entries of the file are attributed to
+    pub node: String,
+    /// `None` on platforms without inodes
+    pub inode: Option<u64>,
+    /// The size of the file on disk when it was last ingested
+    pub file_size: u64,
+    /// Where the next ingestion resumes: the offset of the last entry, which more lines
+    /// can still be appended to, so it is read again. For compressed files, this is
+    /// the decompressed size, and they are never resumed.
+    pub ingested_bytes: u64,
+    /// How many lines of the last entry were read, that is, how many lines
+    /// from `ingested_bytes` on the next ingestion reads again
+    pub tail_lines: u64,
+    /// How many bytes of the file `head_hash` covers
+    pub head_length: u64,
+    pub head_hash: String,
+}
+
+impl FileIngestionState {
+    pub fn find(db: &DatabaseConnection, file_path: &str) -> Result<Option<Model>, DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+
+        conn.query_row(
+            "SELECT file_path, node, inode, file_size, ingested_bytes, tail_lines, head_length, head_hash
+             FROM file_ingestion_state WHERE file_path = ?",
+            params![file_path],
+            row_to_model,
+        )
+        .optional()
+    }
+
+    pub fn find_all(db: &DatabaseConnection) -> Result<Vec<Model>, DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT file_path, node, inode, file_size, ingested_bytes, tail_lines, head_length, head_hash
+             FROM file_ingestion_state ORDER BY file_path",
+        )?;
+        let rows = stmt.query_map([], row_to_model)?;
+        rows.collect()
+    }
+
+    pub fn upsert(db: &DatabaseConnection, state: &Model) -> Result<(), DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO file_ingestion_state (file_path, node, inode, file_size, ingested_bytes, tail_lines, head_length, head_hash)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                state.file_path,
+                state.node,
+                state.inode,
+                state.file_size,
+                state.ingested_bytes,
+                state.tail_lines,
+                state.head_length,
+                state.head_hash
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Forgets where the ingestion of a file stopped, e.g. before it is read again from the start
+    pub fn delete(db: &DatabaseConnection, file_path: &str) -> Result<(), DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+
+        conn.execute(
+            "DELETE FROM file_ingestion_state WHERE file_path = ?",
+            params![file_path],
+        )?;
+        Ok(())
+    }
+}
+
+fn row_to_model(row: &Row<'_>) -> Result<Model, DuckDbError> {
+    Ok(Model {
+        file_path: row.get(0)?,
+        node: row.get(1)?,
+        inode: row.get(2)?,
+        file_size: row.get(3)?,
+        ingested_bytes: row.get(4)?,
+        tail_lines: row.get(5)?,
+        head_length: row.get(6)?,
+        head_hash: row.get(7)?,
+    })
+}
@@ -15,7 +15,7 @@
 use crate::rel_db::DatabaseConnection;
 use chrono::{DateTime, Utc};
 use duckdb::types::{TimeUnit, Value};
-use duckdb::{Error as DuckDbError, params};
+use duckdb::{Error as DuckDbError, OptionalExt, Row, params};
 use serde::{Deserialize, Serialize};
 use std::io::Error as IoError;
 
@@ -45,6 +45,33 @@ fn vec_to_json(vec: &[String]) -> String {
     serde_json::to_string(vec).unwrap_or_else(|_| "[]".to_string())
 }
 
+fn row_to_model(row: &Row<'_>) -> Result<Model, DuckDbError> {
+    let oldest_entry_at: Option<i64> = row.get(4)?;
+    let most_recent_entry_at: Option<i64> = row.get(5)?;
+
+    let rabbitmq_versions_json: String = row.get(1)?;
+    let erlang_versions_json: String = row.get(2)?;
+    let nodes_json: String = row.get(8)?;
+    let subsystems_json: String = row.get(9)?;
+    let labels_json: String = row.get(10)?;
+    let enabled_plugins_json: String = row.get(11)?;
+
+    Ok(Model {
+        file_path: row.get(0)?,
+        rabbitmq_versions: json_to_vec(&rabbitmq_versions_json),
+        erlang_versions: json_to_vec(&erlang_versions_json),
+        tls_library: row.get(3)?,
+        oldest_entry_at: oldest_entry_at.and_then(DateTime::from_timestamp_micros),
+        most_recent_entry_at: most_recent_entry_at.and_then(DateTime::from_timestamp_micros),
+        total_lines: row.get(6)?,
+        total_entries: row.get(7)?,
+        nodes: json_to_vec(&nodes_json),
+        subsystems: json_to_vec(&subsystems_json),
+        labels: json_to_vec(&labels_json),
+        enabled_plugins: json_to_vec(&enabled_plugins_json),
+    })
+}
+
 impl FileMetadata {
     pub fn find_all(db: &DatabaseConnection) -> Result<Vec<Model>, DuckDbError> {
         let conn = db.get().map_err(|e| {
@@ -56,35 +83,39 @@ impl FileMetadata {
              FROM file_metadata",
         )?;
 
-        let rows = stmt.query_map([], |row| {
-            let oldest_entry_at: Option<i64> = row.get(4)?;
-            let most_recent_entry_at: Option<i64> = row.get(5)?;
-
-            let rabbitmq_versions_json: String = row.get(1)?;
-            let erlang_versions_json: String = row.get(2)?;
-            let nodes_json: String = row.get(8)?;
-            let subsystems_json: String = row.get(9)?;
-            let labels_json: String = row.get(10)?;
-            let enabled_plugins_json: String = row.get(11)?;
-
-            Ok(Model {
-                file_path: row.get(0)?,
-                rabbitmq_versions: json_to_vec(&rabbitmq_versions_json),
-                erlang_versions: json_to_vec(&erlang_versions_json),
-                tls_library: row.get(3)?,
-                oldest_entry_at: oldest_entry_at.and_then(DateTime::from_timestamp_micros),
-                most_recent_entry_at: most_recent_entry_at
-                    .and_then(DateTime::from_timestamp_micros),
-                total_lines: row.get(6)?,
-                total_entries: row.get(7)?,
-                nodes: json_to_vec(&nodes_json),
-                subsystems: json_to_vec(&subsystems_json),
-                labels: json_to_vec(&labels_json),
-                enabled_plugins: json_to_vec(&enabled_plugins_json),
-            })
+        let rows = stmt.query_map([], row_to_model)?;
+        rows.collect()
+    }
+
+    pub fn find(db: &DatabaseConnection, file_path: &str) -> Result<Option<Model>, DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
         })?;
 
-        rows.collect()
+        conn.query_row(
+            "SELECT file_path, rabbitmq_versions, erlang_versions, tls_library, oldest_entry_at, most_recent_entry_at, total_lines, total_entries, nodes, subsystems, labels, enabled_plugins
+             FROM file_metadata WHERE file_path = ?",
+            params![file_path],
+            row_to_model,
+        )
+        .optional()
+    }
+
+    /// Returns the most recent entry timestamp across all files, that is,
+    /// the end of the log set.
+    pub fn most_recent_entry_at(
+        db: &DatabaseConnection,
+    ) -> Result<Option<DateTime<Utc>>, DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+
+        let micros: Option<i64> = conn.query_row(
+            "SELECT MAX(most_recent_entry_at) FROM file_metadata",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(micros.and_then(DateTime::from_timestamp_micros))
     }
 
     pub fn insert_metadata(db: &DatabaseConnection, metadata: Model) -> Result<(), DuckDbError> {
@@ -0,0 +1,168 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage of message patterns. Each entry stores the id of its pattern
+//! in `node_log_entries.pattern_id`, templates are stored once per pattern
+//! in the `message_patterns` table, see [`MessagePattern`].
+
+use crate::entry_metadata::patterns::MessagePattern;
+use crate::rel_db::DatabaseConnection;
+use crate::rel_db::sql_condition::SqlCondition;
+use chrono::{DateTime, Utc};
+use duckdb::types::Value;
+use duckdb::{Connection, Error as DuckDbError, ToSql, params};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Error as IoError;
+
+/// Joins the template of each entry's pattern as a `pattern` column
+/// for queries that project, deduplicate or group by pattern.
+pub(crate) const PATTERNS_JOIN: &str = "LEFT JOIN (SELECT id AS template_id, template AS pattern FROM message_patterns) patterns ON patterns.template_id = node_log_entries.pattern_id";
+
+pub struct MessagePatterns;
+
+/// How often a pattern occurs across the log set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternFrequency {
+    pub pattern_id: i64,
+    pub template: String,
+    pub entry_count: i64,
+    pub node_count: i64,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl MessagePatterns {
+    /// A SQL condition on `node_log_entries` that matches entries whose pattern
+    /// template satisfies `template_predicate`, e.g. `regexp_matches(template, ?)`.
+    pub fn condition(template_predicate: SqlCondition) -> SqlCondition {
+        template_predicate.map_sql(|predicate| {
+            format!(
+                "pattern_id IN (SELECT id FROM message_patterns WHERE {})",
+                predicate
+            )
+        })
+    }
+
+    /// Stores templates that are not yet known.
+    pub(crate) fn insert_templates(
+        conn: &Connection,
+        templates: &HashMap<i64, String>,
+    ) -> Result<(), DuckDbError> {
+        if templates.is_empty() {
+            return Ok(());
+        }
+
+        let mut stmt = conn.prepare("INSERT OR IGNORE INTO message_patterns VALUES (?, ?)")?;
+        for (id, template) in templates {
+            stmt.execute(params![id, template])?;
+        }
+        Ok(())
+    }
+
+    /// Lists the most frequent patterns, optionally of a single node,
+    /// ordered by the number of entries that share them.
+    pub fn most_frequent(
+        db: &DatabaseConnection,
+        node: Option<&str>,
+        limit: u64,
+    ) -> Result<Vec<PatternFrequency>, DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+
+        let mut params: Vec<Value> = Vec::new();
+        let where_clause = match node {
+            Some(node) => {
+                params.push(Value::Text(node.to_string()));
+                "WHERE e.node = ?"
+            }
+            None => "",
+        };
+        let sql = format!(
+            "SELECT p.id, p.template,
+                    COUNT(*) AS entry_count,
+                    COUNT(DISTINCT e.node),
+                    MIN(e.timestamp),
+                    MAX(e.timestamp)
+             FROM node_log_entries e
+             JOIN message_patterns p ON p.id = e.pattern_id
+             {}
+             GROUP BY p.id, p.template
+             ORDER BY entry_count DESC, p.template ASC
+             LIMIT {}",
+            where_clause, limit
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let params_slice: Vec<&dyn ToSql> = params.iter().map(|p| p as &dyn ToSql).collect();
+        let rows = stmt.query_map(params_slice.as_slice(), |row| {
+            let first_seen: Option<i64> = row.get(4)?;
+            let last_seen: Option<i64> = row.get(5)?;
+            Ok(PatternFrequency {
+                pattern_id: row.get(0)?,
+                template: row.get(1)?,
+                entry_count: row.get(2)?,
+                node_count: row.get(3)?,
+                first_seen: first_seen.and_then(DateTime::from_timestamp_micros),
+                last_seen: last_seen.and_then(DateTime::from_timestamp_micros),
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    /// Computes patterns of entries stored by versions that predate `message_patterns`.
+    /// Returns the number of entries that were processed.
+    pub(crate) fn backfill(conn: &Connection) -> Result<usize, DuckDbError> {
+        let mut stmt =
+            conn.prepare("SELECT id, message FROM node_log_entries WHERE pattern_id IS NULL")?;
+        let rows = stmt
+            .query_map([], |row| {
+                let id: i64 = row.get(0)?;
+                let message: String = row.get(1)?;
+                Ok((id, message))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        conn.execute_batch(
+            "CREATE TABLE entry_patterns_backfill (entry_id BIGINT NOT NULL, pattern_id BIGINT NOT NULL);",
+        )?;
+        let mut templates = HashMap::new();
+        let mut appender = conn.appender("entry_patterns_backfill")?;
+        for (id, message) in &rows {
+            let pattern = MessagePattern::of(message);
+            appender.append_row(params![id, pattern.id])?;
+            templates.entry(pattern.id).or_insert(pattern.template);
+        }
+        appender.flush()?;
+        drop(appender);
+
+        Self::insert_templates(conn, &templates)?;
+        conn.execute_batch(
+            "
+            UPDATE node_log_entries SET pattern_id = entry_patterns_backfill.pattern_id
+                FROM entry_patterns_backfill WHERE node_log_entries.id = entry_patterns_backfill.entry_id;
+            DROP TABLE entry_patterns_backfill;
+            ",
+        )?;
+
+        Ok(rows.len())
+    }
+}
@@ -0,0 +1,248 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An optional inverted index of message terms, stored in the `message_terms` table
+//! with one row per entry and distinct term of its message.
+//!
+//! [`SearchQuery`] conditions use the index when the database has one, so that
+//! a search only reads the entries that contain all of its terms, and ranks them
+//! by TF-IDF. Without an index the same conditions scan every message and
+//! matching entries are returned in timestamp order. Phrases are matched by
+//! looking up their terms and then checking the candidates' messages.
+//!
+//! Once built, the index is kept up to date by [`MessageSearchIndex::index_new_entries`],
+//! which [`crate::post_insertion_operations`],
+//! [`crate::NodeLogEntry::renumber_in_timestamp_order`] and
+//! [`crate::NodeLogEntry::renumber_appended_in_timestamp_order`] call.
+
+use crate::rel_db::DatabaseConnection;
+use crate::rel_db::sql_condition::{SqlCondition, SqlParam};
+use crate::search::{SearchClause, SearchQuery};
+use duckdb::types::Value;
+use duckdb::{Connection, Error as DuckDbError, params};
+use std::io::Error as IoError;
+
+pub(crate) const MESSAGE_TERMS_TABLE_DDL: &str = "
+        CREATE TABLE IF NOT EXISTS message_terms (
+            entry_id BIGINT NOT NULL,
+            term VARCHAR NOT NULL,
+            term_frequency UINTEGER NOT NULL
+        );
+";
+
+/// Must split messages the way [`crate::search::tokenize`] splits queries
+const TERM_PATTERN: &str = "[a-z0-9_]+";
+
+pub struct MessageSearchIndex;
+
+impl MessageSearchIndex {
+    pub fn exists(db: &DatabaseConnection) -> Result<bool, DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+        Self::exists_on(&conn)
+    }
+
+    pub(crate) fn exists_on(conn: &Connection) -> Result<bool, DuckDbError> {
+        let has_terms_table: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'message_terms'",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(has_terms_table > 0)
+    }
+
+    /// Creates the index, or brings an existing one up to date.
+    ///
+    /// Returns the number of newly indexed entries.
+    pub fn build(db: &DatabaseConnection) -> Result<u64, DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+
+        conn.execute_batch(MESSAGE_TERMS_TABLE_DDL)?;
+        let indexed = Self::index_new_entries_on(&conn)?;
+        Self::create_secondary_indexes(&conn)?;
+        Ok(indexed)
+    }
+
+    /// Indexes entries with ids above the highest indexed one. Entries are appended with
+    /// increasing ids and renumbered only after this has been called, so these are
+    /// exactly the entries stored since the last call.
+    ///
+    /// Does nothing for databases without an index. Returns the number of newly indexed entries.
+    pub fn index_new_entries(db: &DatabaseConnection) -> Result<u64, DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+        Self::index_new_entries_on(&conn)
+    }
+
+    pub(crate) fn index_new_entries_on(conn: &Connection) -> Result<u64, DuckDbError> {
+        if !Self::exists_on(conn)? {
+            return Ok(0);
+        }
+
+        let last_indexed: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(entry_id), 0) FROM message_terms",
+            [],
+            |row| row.get(0),
+        )?;
+        let unindexed: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM node_log_entries WHERE id > ?",
+            [last_indexed],
+            |row| row.get(0),
+        )?;
+        if unindexed == 0 {
+            return Ok(0);
+        }
+
+        conn.execute(
+            &format!(
+                "INSERT INTO message_terms
+                 SELECT id, term, COUNT(*) FROM (
+                     SELECT id, unnest(regexp_extract_all(lower(message), '{}')) AS term
+                     FROM node_log_entries WHERE id > ?
+                 ) GROUP BY id, term
+                 ORDER BY id",
+                TERM_PATTERN
+            ),
+            [last_indexed],
+        )?;
+        Ok(unindexed as u64)
+    }
+
+    /// Indexes the message of an entry that replaced one with the same id,
+    /// which [`Self::index_new_entries`] does not consider new.
+    pub(crate) fn index_message_on(
+        conn: &Connection,
+        entry_id: i64,
+        message: &str,
+    ) -> Result<(), DuckDbError> {
+        conn.execute(
+            &format!(
+                "INSERT INTO message_terms
+                 SELECT ?, term, COUNT(*) FROM (
+                     SELECT unnest(regexp_extract_all(lower(?), '{}')) AS term
+                 ) GROUP BY term",
+                TERM_PATTERN
+            ),
+            params![entry_id, message],
+        )?;
+        Ok(())
+    }
+
+    pub(crate) fn create_secondary_indexes(conn: &Connection) -> Result<(), DuckDbError> {
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_message_terms_term ON message_terms(term);",
+        )
+    }
+
+    /// SQL conditions on `node_log_entries` that match entries containing every clause
+    /// of `search`. With `indexed`, terms are looked up in the index.
+    pub(crate) fn conditions(search: &SearchQuery, indexed: bool) -> (Vec<String>, Vec<Value>) {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+        for clause in search.clauses() {
+            if indexed {
+                for (idx, term) in clause.terms.iter().enumerate() {
+                    let predicate = if clause.prefix && idx == clause.terms.len() - 1 {
+                        "starts_with(term, ?)"
+                    } else {
+                        "term = ?"
+                    };
+                    conditions.push(format!(
+                        "id IN (SELECT entry_id FROM message_terms WHERE {})",
+                        predicate
+                    ));
+                    params.push(Value::Text(term.clone()));
+                }
+                if clause.terms.len() == 1 {
+                    continue;
+                }
+            }
+            conditions.push("regexp_matches(lower(message), ?)".to_string());
+            params.push(Value::Text(clause_pattern(clause)));
+        }
+        (conditions, params)
+    }
+
+    /// A SQL condition that matches entries containing every clause of `search`
+    /// by scanning their messages, for use in `OR` and `NOT` expressions
+    /// that cannot be answered from the index alone.
+    pub fn scan_condition(search: &SearchQuery) -> SqlCondition {
+        let placeholders = vec!["regexp_matches(lower(message), ?)"; search.clauses().len()];
+        let params = search
+            .clauses()
+            .iter()
+            .map(|clause| SqlParam::Text(clause_pattern(clause)))
+            .collect();
+        SqlCondition::new(format!("({})", placeholders.join(" AND ")), params)
+    }
+
+    /// A `LEFT JOIN` that adds a `search_score` column to `node_log_entries`:
+    /// the sum of the TF-IDF weights of the search terms in each message.
+    /// Requires an index.
+    pub(crate) fn scores_join(search: &SearchQuery) -> (String, Vec<Value>) {
+        let mut predicates = Vec::new();
+        let mut params = Vec::new();
+        for clause in search.clauses() {
+            for (idx, term) in clause.terms.iter().enumerate() {
+                if clause.prefix && idx == clause.terms.len() - 1 {
+                    predicates.push("starts_with(term, ?)");
+                } else {
+                    predicates.push("term = ?");
+                }
+                params.push(Value::Text(term.clone()));
+            }
+        }
+
+        let term_filter = predicates.join(" OR ");
+        let join = format!(
+            "LEFT JOIN (
+                 SELECT entry_id,
+                        SUM(term_frequency * ln(1 + (SELECT COUNT(*) FROM node_log_entries) / df)) AS search_score
+                 FROM (SELECT * FROM message_terms WHERE {0})
+                 JOIN (SELECT term, COUNT(*) AS df FROM message_terms WHERE {0} GROUP BY term) USING (term)
+                 GROUP BY entry_id
+             ) scores ON scores.entry_id = node_log_entries.id",
+            term_filter
+        );
+        let mut join_params = params.clone();
+        join_params.extend(params);
+        (join, join_params)
+    }
+}
+
+/// Matches the terms of `clause` next to each other, as whole terms
+/// except for the last one of a prefix clause
+fn clause_pattern(clause: &SearchClause) -> String {
+    let terms: Vec<_> = clause.terms.iter().map(|t| regex_escape(t)).collect();
+    format!(
+        "\\b{}{}",
+        terms.join("\\W+"),
+        if clause.prefix { "" } else { "\\b" }
+    )
+}
+
+fn regex_escape(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+    for c in term.chars() {
+        if !c.is_ascii_alphanumeric() && c != '_' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -11,15 +11,40 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+pub mod aggregation;
+pub mod context_window;
+pub mod file_ingestion_state;
 pub mod file_metadata;
+pub mod message_patterns;
+pub mod message_search_index;
+pub mod node_artifacts;
 pub mod node_log_entry;
+pub mod node_log_entry_custom_annotations;
+pub mod node_log_entry_entities;
+pub mod node_log_entry_labels;
+pub mod obfuscation;
 pub mod presets;
+pub mod sort_order;
+pub mod sql_condition;
 
+pub use aggregation::{Aggregation, LogColumn, TabularResult};
+pub use context_window::{ContextScope, ContextWindow};
+pub use file_ingestion_state::FileIngestionState;
 pub use file_metadata::FileMetadata;
-pub use node_log_entry::{NodeLogEntry, QueryContext};
+pub use message_patterns::{MessagePatterns, PatternFrequency};
+pub use message_search_index::MessageSearchIndex;
+pub use node_artifacts::{ArtifactKind, ConfigSetting, NodeArtifacts};
+pub use node_log_entry::{EntryAppender, NodeLogEntry, QueryContext};
+pub use node_log_entry_custom_annotations::NodeLogEntryCustomAnnotations;
+pub use node_log_entry_entities::{EntityMentions, NodeLogEntryEntities};
+pub use node_log_entry_labels::NodeLogEntryLabels;
+pub use obfuscation::obfuscate_database;
 pub use presets::QueryPreset;
+pub use sort_order::SortKey;
+pub use sql_condition::{SqlCondition, SqlParam};
 
-use duckdb::Error as DuckDbError;
+use crate::severity::Severity;
+use duckdb::{Connection, Error as DuckDbError};
 use r2d2::{Pool, PooledConnection};
 use r2d2_duckdb::DuckDbConnectionManager;
 use std::io::Error as IoError;
@@ -31,14 +56,21 @@ mod r2d2_duckdb {
     use duckdb::{Connection, Error as DuckDbError};
     use r2d2::ManageConnection;
     use std::path::PathBuf;
+    use std::sync::Mutex;
 
+    /// Hands out connections to a single database instance. Opening the file
+    /// once per pooled connection would create independent instances, each with
+    /// its own catalog and buffer pool, that do not see each other's tables and rows
+    /// and can read pages another instance has not written out yet.
     pub struct DuckDbConnectionManager {
-        path: PathBuf,
+        connection: Mutex<Connection>,
     }
 
     impl DuckDbConnectionManager {
-        pub fn file(path: PathBuf) -> Self {
-            Self { path }
+        pub fn file(path: PathBuf) -> Result<Self, DuckDbError> {
+            Ok(Self {
+                connection: Mutex::new(Connection::open(path)?),
+            })
         }
     }
 
@@ -47,7 +79,10 @@ mod r2d2_duckdb {
         type Error = DuckDbError;
 
         fn connect(&self) -> Result<Self::Connection, Self::Error> {
-            Connection::open(&self.path)
+            self.connection
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .try_clone()
         }
 
         fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
@@ -61,6 +96,80 @@ mod r2d2_duckdb {
     }
 }
 
+pub(crate) const NODE_LOG_ENTRIES_TABLE_DDL: &str = "
+        CREATE TABLE IF NOT EXISTS node_log_entries (
+            id BIGINT PRIMARY KEY,
+            node VARCHAR NOT NULL,
+            timestamp TIMESTAMPTZ NOT NULL,
+            severity TINYINT NOT NULL,
+            erlang_pid VARCHAR NOT NULL,
+            subsystem_id SMALLINT,
+            message VARCHAR NOT NULL,
+            resolution_or_discussion_url_id SMALLINT,
+            doc_url_id SMALLINT,
+            domain VARCHAR,
+            mfa VARCHAR,
+            source_file VARCHAR,
+            source_line INTEGER,
+            connection VARCHAR,
+            vhost VARCHAR,
+            username VARCHAR,
+            pattern_id BIGINT
+        );
+";
+
+/// Columns of `node_log_entries` in [`NODE_LOG_ENTRIES_TABLE_DDL`] order.
+pub(crate) const NODE_LOG_ENTRIES_COLUMNS: &[&str] = &[
+    "id",
+    "node",
+    "timestamp",
+    "severity",
+    "erlang_pid",
+    "subsystem_id",
+    "message",
+    "resolution_or_discussion_url_id",
+    "doc_url_id",
+    "domain",
+    "mfa",
+    "source_file",
+    "source_line",
+    "connection",
+    "vhost",
+    "username",
+    "pattern_id",
+];
+
+pub(crate) const NODE_LOG_ENTRY_LABELS_TABLE_DDL: &str = "
+        CREATE TABLE IF NOT EXISTS node_log_entry_labels (
+            entry_id BIGINT NOT NULL,
+            label_id SMALLINT NOT NULL
+        );
+";
+
+pub(crate) const NODE_LOG_ENTRY_ENTITIES_TABLE_DDL: &str = "
+        CREATE TABLE IF NOT EXISTS node_log_entry_entities (
+            entry_id BIGINT NOT NULL,
+            entity_kind VARCHAR NOT NULL,
+            vhost VARCHAR,
+            name VARCHAR NOT NULL
+        );
+";
+
+pub(crate) const NODE_LOG_ENTRY_CUSTOM_ANNOTATIONS_TABLE_DDL: &str = "
+        CREATE TABLE IF NOT EXISTS node_log_entry_custom_annotations (
+            entry_id BIGINT NOT NULL,
+            kind VARCHAR NOT NULL,
+            value VARCHAR NOT NULL
+        );
+";
+
+pub(crate) const MESSAGE_PATTERNS_TABLE_DDL: &str = "
+        CREATE TABLE IF NOT EXISTS message_patterns (
+            id BIGINT PRIMARY KEY,
+            template VARCHAR NOT NULL
+        );
+";
+
 pub type DbPool = Pool<DuckDbConnectionManager>;
 pub type DbConnection = PooledConnection<DuckDbConnectionManager>;
 
@@ -87,7 +196,7 @@ fn create_database_with_options(
     db_path: &Path,
     _fast_import: bool,
 ) -> Result<DatabaseConnection, DuckDbError> {
-    let manager = DuckDbConnectionManager::file(db_path.to_path_buf());
+    let manager = DuckDbConnectionManager::file(db_path.to_path_buf())?;
     let pool = Pool::builder()
         .max_size(4)
         .connection_timeout(Duration::from_secs(1))
@@ -100,21 +209,10 @@ fn create_database_with_options(
         DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
     })?;
 
+    conn.execute_batch(NODE_LOG_ENTRIES_TABLE_DDL)?;
+    conn.execute_batch(NODE_LOG_ENTRY_LABELS_TABLE_DDL)?;
     conn.execute_batch(
         "
-        CREATE TABLE IF NOT EXISTS node_log_entries (
-            id BIGINT PRIMARY KEY,
-            node VARCHAR NOT NULL,
-            timestamp TIMESTAMPTZ NOT NULL,
-            severity VARCHAR NOT NULL,
-            erlang_pid VARCHAR NOT NULL,
-            subsystem_id SMALLINT,
-            message VARCHAR NOT NULL,
-            labels BIGINT NOT NULL DEFAULT 0,
-            resolution_or_discussion_url_id SMALLINT,
-            doc_url_id SMALLINT
-        );
-
         CREATE TABLE IF NOT EXISTS file_metadata (
             file_path VARCHAR PRIMARY KEY,
             rabbitmq_versions VARCHAR NOT NULL DEFAULT '[]',
@@ -131,6 +229,12 @@ fn create_database_with_options(
         );
         ",
     )?;
+    migrate_schema(&conn)?;
+    migrate_entities(&conn)?;
+    migrate_patterns(&conn)?;
+    migrate_custom_annotations(&conn)?;
+    migrate_node_artifacts(&conn)?;
+    migrate_file_ingestion_state(&conn)?;
 
     Ok(DatabaseConnection {
         pool: Arc::new(pool),
@@ -162,14 +266,28 @@ pub fn post_insertion_operations(db: &DatabaseConnection) -> Result<(), DuckDbEr
         CREATE INDEX IF NOT EXISTS idx_timestamp_resolution_url_id ON node_log_entries(timestamp, resolution_or_discussion_url_id);
         CREATE INDEX IF NOT EXISTS idx_severity_doc_url_id ON node_log_entries(severity, doc_url_id);
         CREATE INDEX IF NOT EXISTS idx_node_timestamp_doc_url_id ON node_log_entries(node, timestamp, doc_url_id);
+        CREATE INDEX IF NOT EXISTS idx_node_log_entries_vhost ON node_log_entries(vhost);
+        CREATE INDEX IF NOT EXISTS idx_node_log_entries_mfa ON node_log_entries(mfa);
+        CREATE INDEX IF NOT EXISTS idx_node_log_entries_pattern_id ON node_log_entries(pattern_id);
+        CREATE INDEX IF NOT EXISTS idx_node_log_entry_labels_label_id ON node_log_entry_labels(label_id);
+        CREATE INDEX IF NOT EXISTS idx_node_log_entry_labels_entry_id ON node_log_entry_labels(entry_id);
+        CREATE INDEX IF NOT EXISTS idx_node_log_entry_entities_kind_name ON node_log_entry_entities(entity_kind, name);
+        CREATE INDEX IF NOT EXISTS idx_node_log_entry_entities_entry_id ON node_log_entry_entities(entry_id);
+        CREATE INDEX IF NOT EXISTS idx_node_log_entry_custom_annotations_kind_value ON node_log_entry_custom_annotations(kind, value);
+        CREATE INDEX IF NOT EXISTS idx_node_log_entry_custom_annotations_entry_id ON node_log_entry_custom_annotations(entry_id);
         ",
     )?;
+    if MessageSearchIndex::exists_on(&conn)? {
+        MessageSearchIndex::index_new_entries_on(&conn)?;
+        MessageSearchIndex::create_secondary_indexes(&conn)?;
+    }
 
     Ok(())
 }
 
+/// Opens an existing database, upgrading it to the current schema if necessary.
 pub fn open_database(db_path: &Path) -> Result<DatabaseConnection, DuckDbError> {
-    let manager = DuckDbConnectionManager::file(db_path.to_path_buf());
+    let manager = DuckDbConnectionManager::file(db_path.to_path_buf())?;
     let pool = Pool::builder()
         .max_size(4)
         .connection_timeout(Duration::from_secs(1))
@@ -178,7 +296,254 @@ pub fn open_database(db_path: &Path) -> Result<DatabaseConnection, DuckDbError>
             DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
         })?;
 
-    Ok(DatabaseConnection {
+    let db = DatabaseConnection {
         pool: Arc::new(pool),
-    })
+    };
+    migrate_database(&db)?;
+
+    Ok(db)
+}
+
+/// Upgrades databases created by earlier versions to the current schema.
+///
+/// Returns true if any changes were made.
+pub fn migrate_database(db: &DatabaseConnection) -> Result<bool, DuckDbError> {
+    let conn = db.get().map_err(|e| {
+        DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+    })?;
+
+    let has_entries_table: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'node_log_entries'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_entries_table == 0 {
+        return Ok(false);
+    }
+
+    conn.execute_batch(NODE_LOG_ENTRY_LABELS_TABLE_DDL)?;
+    let mut migrated = migrate_schema(&conn)?;
+    migrated |= migrate_entities(&conn)?;
+    migrated |= migrate_patterns(&conn)?;
+    migrated |= migrate_custom_annotations(&conn)?;
+    migrated |= migrate_node_artifacts(&conn)?;
+    migrated |= migrate_file_ingestion_state(&conn)?;
+    drop(conn);
+
+    if migrated {
+        post_insertion_operations(db)?;
+    }
+    Ok(migrated)
+}
+
+/// Rebuilds `node_log_entries` if its columns differ from [`NODE_LOG_ENTRIES_COLUMNS`],
+/// e.g. when it predates the structured metadata columns, still has the legacy
+/// label bitmask column or stores severities as names rather than ordinals.
+///
+/// Returns true if the table was rebuilt.
+fn migrate_schema(conn: &Connection) -> Result<bool, DuckDbError> {
+    let mut stmt = conn.prepare(
+        "SELECT column_name, data_type FROM information_schema.columns
+         WHERE table_name = 'node_log_entries'
+         ORDER BY ordinal_position",
+    )?;
+    let existing = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+    let existing_columns: Vec<&str> = existing.iter().map(|(name, _)| name.as_str()).collect();
+    let has_severity_names = existing
+        .iter()
+        .any(|(name, data_type)| name == "severity" && data_type == "VARCHAR");
+
+    if existing_columns.is_empty()
+        || (existing_columns == NODE_LOG_ENTRIES_COLUMNS && !has_severity_names)
+    {
+        return Ok(false);
+    }
+
+    let has_legacy_labels = existing_columns.contains(&"labels");
+    let retained = NODE_LOG_ENTRIES_COLUMNS
+        .iter()
+        .filter(|c| existing_columns.contains(c))
+        .copied()
+        .collect::<Vec<_>>();
+    let retained_columns = retained.join(", ");
+    let converted_columns = retained
+        .iter()
+        .map(|&column| {
+            if column == "severity" && has_severity_names {
+                severity_ordinal_expression()
+            } else {
+                column.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // Indexed tables cannot be altered in place, so the table is rebuilt.
+    // Secondary indexes are re-created by `post_insertion_operations`.
+    let migration = format!(
+        "
+        BEGIN TRANSACTION;
+        {}
+        CREATE TABLE node_log_entries_previous AS
+            SELECT {} FROM node_log_entries;
+
+        DROP TABLE node_log_entries;
+        {}
+        INSERT INTO node_log_entries ({})
+            SELECT {} FROM node_log_entries_previous;
+        DROP TABLE node_log_entries_previous;
+
+        COMMIT;
+        ",
+        if has_legacy_labels {
+            NodeLogEntryLabels::LEGACY_BITMASK_MIGRATION
+        } else {
+            ""
+        },
+        retained_columns,
+        NODE_LOG_ENTRIES_TABLE_DDL,
+        retained_columns,
+        converted_columns
+    );
+    if let Err(e) = conn.execute_batch(&migration) {
+        let _ = conn.execute_batch("ROLLBACK");
+        return Err(e);
+    }
+
+    if has_legacy_labels {
+        log::info!("Migrated entry labels to the node_log_entry_labels table");
+    }
+    log::info!("Migrated the node_log_entries table to the current schema");
+    Ok(true)
+}
+
+/// Converts severity names, as stored by earlier versions, to [`Severity::to_ordinal`] values.
+/// Names that are not known are converted to [`Severity::Info`], so that the migration
+/// does not fail on the `NOT NULL` constraint of the column.
+fn severity_ordinal_expression() -> String {
+    let branches: Vec<String> = Severity::all()
+        .iter()
+        .map(|severity| {
+            format!(
+                "WHEN '{}' THEN {}",
+                severity.as_str(),
+                severity.to_ordinal()
+            )
+        })
+        .collect();
+    format!(
+        "CASE severity {} ELSE {} END AS severity",
+        branches.join(" "),
+        Severity::Info.to_ordinal()
+    )
+}
+
+/// Creates `node_log_entry_entities` and, for databases that predate it,
+/// populates it from the existing entries.
+///
+/// Returns true if the table was created.
+fn migrate_entities(conn: &Connection) -> Result<bool, DuckDbError> {
+    let has_entities_table: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'node_log_entry_entities'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_entities_table > 0 {
+        return Ok(false);
+    }
+
+    conn.execute_batch(NODE_LOG_ENTRY_ENTITIES_TABLE_DDL)?;
+    let processed = NodeLogEntryEntities::backfill(conn)?;
+    if processed > 0 {
+        log::info!(
+            "Extracted entities of {} existing entries into the node_log_entry_entities table",
+            processed
+        );
+    }
+    Ok(true)
+}
+
+/// Creates `message_patterns` and, for databases that predate it,
+/// computes the patterns of the existing entries.
+///
+/// Returns true if the table was created.
+fn migrate_patterns(conn: &Connection) -> Result<bool, DuckDbError> {
+    let has_patterns_table: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'message_patterns'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_patterns_table > 0 {
+        return Ok(false);
+    }
+
+    conn.execute_batch(MESSAGE_PATTERNS_TABLE_DDL)?;
+    let processed = MessagePatterns::backfill(conn)?;
+    if processed > 0 {
+        log::info!(
+            "Computed message patterns of {} existing entries",
+            processed
+        );
+    }
+    Ok(true)
+}
+
+/// Creates `node_log_entry_custom_annotations` for databases that predate it.
+/// Entries stored by earlier versions were not annotated by user-defined rules,
+/// so there is nothing to backfill.
+///
+/// Returns true if the table was created.
+fn migrate_custom_annotations(conn: &Connection) -> Result<bool, DuckDbError> {
+    let has_custom_annotations_table: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'node_log_entry_custom_annotations'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_custom_annotations_table > 0 {
+        return Ok(false);
+    }
+
+    conn.execute_batch(NODE_LOG_ENTRY_CUSTOM_ANNOTATIONS_TABLE_DDL)?;
+    Ok(true)
+}
+
+/// Creates `node_artifacts` for databases that predate support bundle ingestion.
+///
+/// Returns true if the table was created.
+fn migrate_node_artifacts(conn: &Connection) -> Result<bool, DuckDbError> {
+    let has_node_artifacts_table: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'node_artifacts'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_node_artifacts_table > 0 {
+        return Ok(false);
+    }
+
+    conn.execute_batch(node_artifacts::NODE_ARTIFACTS_TABLE_DDL)?;
+    Ok(true)
+}
+
+/// Creates `file_ingestion_state` for databases that predate incremental ingestion.
+/// Files imported by earlier versions have no recorded state, so the next
+/// `logs merge` of such a file reads it from the start.
+///
+/// Returns true if the table was created.
+fn migrate_file_ingestion_state(conn: &Connection) -> Result<bool, DuckDbError> {
+    let has_file_ingestion_state_table: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'file_ingestion_state'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_file_ingestion_state_table > 0 {
+        return Ok(false);
+    }
+
+    conn.execute_batch(file_ingestion_state::FILE_INGESTION_STATE_TABLE_DDL)?;
+    Ok(true)
 }
@@ -0,0 +1,244 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Files other than logs that support bundles collect from every node,
+//! such as `rabbitmq.conf` and `enabled_plugins`. Their contents are stored as is
+//! and parsed when they are read.
+
+use crate::rel_db::DatabaseConnection;
+use duckdb::{Error as DuckDbError, params};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Error as IoError;
+use std::path::Path;
+
+pub(crate) const NODE_ARTIFACTS_TABLE_DDL: &str = "
+        CREATE TABLE IF NOT EXISTS node_artifacts (
+            node VARCHAR NOT NULL,
+            kind VARCHAR NOT NULL,
+            file_path VARCHAR NOT NULL,
+            content VARCHAR NOT NULL
+        );
+";
+
+/// The value that replaces secrets in obfuscated configuration files
+pub const REDACTED_VALUE: &str = "******";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    RabbitmqConf,
+    AdvancedConfig,
+    EnabledPlugins,
+}
+
+impl ArtifactKind {
+    /// Recognizes an artifact by its file name, e.g. `rabbitmq.conf`
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        match file_name {
+            "rabbitmq.conf" => Some(Self::RabbitmqConf),
+            "advanced.config" => Some(Self::AdvancedConfig),
+            "enabled_plugins" => Some(Self::EnabledPlugins),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .and_then(Self::from_file_name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RabbitmqConf => "rabbitmq_conf",
+            Self::AdvancedConfig => "advanced_config",
+            Self::EnabledPlugins => "enabled_plugins",
+        }
+    }
+
+    fn from_db_str(s: &str) -> Option<Self> {
+        match s {
+            "rabbitmq_conf" => Some(Self::RabbitmqConf),
+            "advanced_config" => Some(Self::AdvancedConfig),
+            "enabled_plugins" => Some(Self::EnabledPlugins),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ArtifactKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub struct NodeArtifacts;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Model {
+    pub node: String,
+    pub kind: ArtifactKind,
+    pub file_path: String,
+    pub content: String,
+}
+
+/// A `key = value` line of a `rabbitmq.conf` file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigSetting {
+    pub node: String,
+    pub key: String,
+    pub value: String,
+}
+
+impl NodeArtifacts {
+    pub fn insert(db: &DatabaseConnection, artifact: &Model) -> Result<(), DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+
+        conn.execute(
+            "INSERT INTO node_artifacts (node, kind, file_path, content) VALUES (?, ?, ?, ?)",
+            params![
+                artifact.node,
+                artifact.kind.as_str(),
+                artifact.file_path,
+                artifact.content
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn find_all(db: &DatabaseConnection) -> Result<Vec<Model>, DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT node, kind, file_path, content FROM node_artifacts ORDER BY node, kind, file_path",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+
+        let mut artifacts = Vec::new();
+        for row in rows {
+            let (node, kind, file_path, content) = row?;
+            // Kinds added by later versions are skipped
+            if let Some(kind) = ArtifactKind::from_db_str(&kind) {
+                artifacts.push(Model {
+                    node,
+                    kind,
+                    file_path,
+                    content,
+                });
+            }
+        }
+        Ok(artifacts)
+    }
+
+    /// Returns the settings of every stored `rabbitmq.conf` file, ordered by node
+    pub fn config_settings(db: &DatabaseConnection) -> Result<Vec<ConfigSetting>, DuckDbError> {
+        Ok(Self::find_all(db)?
+            .into_iter()
+            .filter(|a| a.kind == ArtifactKind::RabbitmqConf)
+            .flat_map(|a| {
+                parse_rabbitmq_conf(&a.content)
+                    .into_iter()
+                    .map(move |(key, value)| ConfigSetting {
+                        node: a.node.clone(),
+                        key,
+                        value,
+                    })
+            })
+            .collect())
+    }
+
+    /// Returns the plugins listed in every stored `enabled_plugins` file, by node
+    pub fn enabled_plugins(
+        db: &DatabaseConnection,
+    ) -> Result<Vec<(String, Vec<String>)>, DuckDbError> {
+        Ok(Self::find_all(db)?
+            .into_iter()
+            .filter(|a| a.kind == ArtifactKind::EnabledPlugins)
+            .map(|a| (a.node, parse_enabled_plugins(&a.content)))
+            .collect())
+    }
+}
+
+/// Parses the `key = value` lines of a `rabbitmq.conf` file, skipping comments and blank lines
+pub fn parse_rabbitmq_conf(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+/// Parses an `enabled_plugins` file, an Erlang term such as `[rabbitmq_management,rabbitmq_shovel].`
+pub fn parse_enabled_plugins(content: &str) -> Vec<String> {
+    let Some(start) = content.find('[') else {
+        return Vec::new();
+    };
+    let Some(end) = content[start..].find(']') else {
+        return Vec::new();
+    };
+    content[start + 1..start + end]
+        .split(',')
+        .map(|plugin| plugin.trim().trim_matches('\'').to_string())
+        .filter(|plugin| !plugin.is_empty())
+        .collect()
+}
+
+/// Whether a `rabbitmq.conf` key holds a credential, e.g. `default_pass`
+/// or `ssl_options.password`
+pub fn is_secret_setting(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    ["pass", "secret", "private_key"]
+        .iter()
+        .any(|fragment| key.contains(fragment))
+}
+
+/// Replaces the values of secret settings in a `rabbitmq.conf` file with [`REDACTED_VALUE`],
+/// preserving everything else, including comments.
+pub fn redact_secret_settings(content: &str) -> String {
+    let mut redacted = content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('#') {
+                return line.to_string();
+            }
+            match line.split_once('=') {
+                Some((key, _)) if is_secret_setting(key.trim()) => {
+                    format!("{}= {}", key, REDACTED_VALUE)
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if content.ends_with('\n') {
+        redacted.push('\n');
+    }
+    redacted
+}
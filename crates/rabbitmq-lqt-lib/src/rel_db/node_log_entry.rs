@@ -11,15 +11,40 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use crate::entry_metadata::labels::{LABEL_NAMES, LogEntryLabels};
-use crate::parser::ParsedLogEntry;
-use crate::rel_db::DatabaseConnection;
+use crate::constants::{doc_url_from_id, resolution_or_discussion_url_from_id};
+use crate::entry_metadata::entities::EntityKind;
+use crate::entry_metadata::labels::LogEntryLabels;
+use crate::entry_metadata::patterns::MessagePattern;
+use crate::entry_metadata::rules::{CustomAnnotations, custom_label_name};
+use crate::parser::{ParsedLogEntry, StructuredMetadata};
+use crate::rel_db::aggregation::{Aggregation, LogColumn, TabularResult, format_cell};
+use crate::rel_db::context_window::ContextWindow;
+use crate::rel_db::message_patterns::{MessagePatterns, PATTERNS_JOIN};
+use crate::rel_db::message_search_index::{MESSAGE_TERMS_TABLE_DDL, MessageSearchIndex};
+use crate::rel_db::node_log_entry_custom_annotations::{
+    NodeLogEntryCustomAnnotations, custom_annotations_from, custom_labels_subquery,
+    custom_urls_subqueries,
+};
+use crate::rel_db::node_log_entry_entities::{NodeLogEntryEntities, entity_join};
+use crate::rel_db::node_log_entry_labels::{LABELS_JOIN, NodeLogEntryLabels, label_ids_subquery};
 use crate::rel_db::presets::QueryPreset;
+use crate::rel_db::sort_order::{SortKey, order_by_items, order_by_list};
+use crate::rel_db::sql_condition::{SqlCondition, SqlParam};
+use crate::rel_db::{
+    DatabaseConnection, NODE_LOG_ENTRIES_TABLE_DDL, NODE_LOG_ENTRY_CUSTOM_ANNOTATIONS_TABLE_DDL,
+    NODE_LOG_ENTRY_ENTITIES_TABLE_DDL, NODE_LOG_ENTRY_LABELS_TABLE_DDL,
+};
+use crate::search::SearchQuery;
+use crate::severity::Severity;
 use chrono::{DateTime, Utc};
 use duckdb::types::{TimeUnit, Value};
-use duckdb::{Error as DuckDbError, ToSql, params};
+use duckdb::{Appender, Connection, Error as DuckDbError, ToSql, params};
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 use std::io::Error as IoError;
 
 fn pool_error_to_duckdb(e: impl Display) -> DuckDbError {
@@ -39,9 +64,17 @@ pub struct QueryContext {
     pub(crate) since_time: Option<DateTime<Utc>>,
     pub(crate) to_time: Option<DateTime<Utc>>,
     pub(crate) severity: Option<String>,
+    pub(crate) min_severity: Option<Severity>,
     pub(crate) erlang_pid: Option<String>,
     pub(crate) node: Option<String>,
     pub(crate) subsystem: Option<String>,
+    pub(crate) domain: Option<String>,
+    pub(crate) mfa: Option<String>,
+    pub(crate) source_file: Option<String>,
+    pub(crate) connection: Option<String>,
+    pub(crate) vhost: Option<String>,
+    pub(crate) user: Option<String>,
+    pub(crate) entities: Vec<(EntityKind, String)>,
     pub(crate) labels: Vec<String>,
     pub(crate) matching_all_labels: bool,
     pub(crate) limit: Option<u64>,
@@ -50,7 +83,11 @@ pub struct QueryContext {
     pub(crate) offset: Option<u64>,
     pub(crate) tail: Option<u64>,
     pub(crate) preset: Option<QueryPreset>,
-    pub(crate) raw_where_clauses: Vec<String>,
+    pub(crate) where_conditions: Vec<SqlCondition>,
+    pub(crate) search: Option<SearchQuery>,
+    pub(crate) aggregation: Option<Aggregation>,
+    pub(crate) order_by: Vec<SortKey>,
+    pub(crate) context_window: Option<ContextWindow>,
 }
 
 impl QueryContext {
@@ -72,6 +109,14 @@ impl QueryContext {
         self
     }
 
+    /// Only matches entries of `sev` or a higher severity, e.g. `warning`, `error` and `critical`
+    /// for [`Severity::Warning`].
+    #[must_use]
+    pub fn min_severity(mut self, sev: Severity) -> Self {
+        self.min_severity = Some(sev);
+        self
+    }
+
     #[must_use]
     pub fn erlang_pid(mut self, pid: impl Into<String>) -> Self {
         self.erlang_pid = Some(pid.into());
@@ -90,6 +135,49 @@ impl QueryContext {
         self
     }
 
+    #[must_use]
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    #[must_use]
+    pub fn mfa(mut self, mfa: impl Into<String>) -> Self {
+        self.mfa = Some(mfa.into());
+        self
+    }
+
+    #[must_use]
+    pub fn source_file(mut self, file: impl Into<String>) -> Self {
+        self.source_file = Some(file.into());
+        self
+    }
+
+    #[must_use]
+    pub fn connection(mut self, conn: impl Into<String>) -> Self {
+        self.connection = Some(conn.into());
+        self
+    }
+
+    #[must_use]
+    pub fn vhost(mut self, vhost: impl Into<String>) -> Self {
+        self.vhost = Some(vhost.into());
+        self
+    }
+
+    #[must_use]
+    pub fn user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Matches entries that mention the given entity, e.g. a queue named `orders`.
+    #[must_use]
+    pub fn entity(mut self, kind: EntityKind, name: impl Into<String>) -> Self {
+        self.entities.push((kind, name.into()));
+        self
+    }
+
     #[must_use]
     pub fn labels(mut self, labels: Vec<String>) -> Self {
         self.labels = labels;
@@ -144,33 +232,94 @@ impl QueryContext {
         self
     }
 
-    /// Adds a raw SQL WHERE clause fragment.
-    ///
-    /// ## Safety
-    ///
-    /// Only use with trusted, pre-validated input such as output from the QL compiler.
-    /// User input must be properly escaped before being included in the clause.
-    ///
-    /// The QL compiler (`rabbitmq_lqt_ql::compiler`) uses `escape_sql_string()` and
-    /// `escape_like_pattern()` to sanitize user-provided values before generating
-    /// SQL fragments that are passed to this method.
+    /// Restricts results to entries that satisfy `condition`, in addition to
+    /// the other filters. Used for QL expressions that have no dedicated filter.
     #[must_use]
-    pub fn raw_where(mut self, clause: impl Into<String>) -> Self {
-        self.raw_where_clauses.push(clause.into());
+    pub fn where_condition(mut self, condition: SqlCondition) -> Self {
+        self.where_conditions.push(condition);
         self
     }
 
-    /// Sets multiple raw SQL WHERE clause fragments.
-    ///
-    /// ## Safety
-    ///
-    /// **This method accepts raw SQL that is concatenated directly into the query.**
-    /// See [`raw_where`](Self::raw_where).
+    /// Replaces the conditions added with [`where_condition`](Self::where_condition).
     #[must_use]
-    pub fn raw_where_clauses(mut self, clauses: Vec<String>) -> Self {
-        self.raw_where_clauses = clauses;
+    pub fn where_conditions(mut self, conditions: Vec<SqlCondition>) -> Self {
+        self.where_conditions = conditions;
         self
     }
+
+    /// Restricts results to entries whose messages match `search`. Searches are combined,
+    /// so entries have to match all of them. See [`MessageSearchIndex`] for how
+    /// results are ordered when no [`sort_by`](Self::sort_by) keys are set.
+    #[must_use]
+    pub fn search(mut self, search: SearchQuery) -> Self {
+        if !search.is_empty() {
+            self.search = Some(match self.search.take() {
+                Some(existing) => existing.and(search),
+                None => search,
+            });
+        }
+        self
+    }
+
+    /// Orders results by `key`, after the keys added before it. Without keys,
+    /// results are ordered by timestamp.
+    #[must_use]
+    pub fn sort_by(mut self, key: SortKey) -> Self {
+        self.order_by.push(key);
+        self
+    }
+
+    /// Replaces the keys added with [`sort_by`](Self::sort_by).
+    #[must_use]
+    pub fn order_by(mut self, keys: Vec<SortKey>) -> Self {
+        self.order_by = keys;
+        self
+    }
+
+    pub fn get_order_by(&self) -> &[SortKey] {
+        &self.order_by
+    }
+
+    /// Expands every matching entry with the entries logged around it, see [`ContextWindow`].
+    /// The expanded results are in timestamp order, with each entry included once.
+    /// Only up to [`ContextWindow::MAX_HITS`] matching entries are expanded.
+    #[must_use]
+    pub fn context_window(mut self, window: ContextWindow) -> Self {
+        self.context_window = Some(window);
+        self
+    }
+
+    pub fn get_context_window(&self) -> Option<&ContextWindow> {
+        self.context_window.as_ref()
+    }
+
+    /// Sets a projection, distinct or count stage to be executed with
+    /// [`NodeLogEntry::aggregate`].
+    #[must_use]
+    pub fn aggregation(mut self, aggregation: Aggregation) -> Self {
+        self.aggregation = Some(aggregation);
+        self
+    }
+
+    pub fn get_aggregation(&self) -> Option<&Aggregation> {
+        self.aggregation.as_ref()
+    }
+
+    pub fn has_aggregation(&self) -> bool {
+        self.aggregation.is_some()
+    }
+
+    pub fn get_since_time(&self) -> Option<DateTime<Utc>> {
+        self.since_time
+    }
+
+    pub fn get_to_time(&self) -> Option<DateTime<Utc>> {
+        self.to_time
+    }
+
+    pub fn get_search(&self) -> Option<&SearchQuery> {
+        self.search.as_ref()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -182,9 +331,12 @@ pub struct Model {
     pub erlang_pid: String,
     pub subsystem_id: Option<i16>,
     pub message: String,
-    pub labels: i64,
+    pub labels: LogEntryLabels,
     pub resolution_or_discussion_url_id: Option<i16>,
     pub doc_url_id: Option<i16>,
+    pub metadata: StructuredMetadata,
+    #[serde(default, skip_serializing_if = "CustomAnnotations::is_empty")]
+    pub custom_annotations: CustomAnnotations,
 }
 
 impl Model {
@@ -195,23 +347,460 @@ impl Model {
 
     #[inline]
     pub fn format_labels(&self) -> String {
-        let labels = LogEntryLabels::from_bits_i64(self.labels);
-        let mut result = String::new();
-        for (i, label_name) in LABEL_NAMES.iter().enumerate() {
-            if labels.bits() & (1u64 << i) != 0 {
-                if !result.is_empty() {
-                    result.push('\n');
+        self.label_names().collect::<Vec<_>>().join("\n")
+    }
+
+    /// Names of the built-in labels followed by the custom ones, e.g. `custom:billing`
+    pub fn label_names(&self) -> impl Iterator<Item = String> + '_ {
+        self.labels
+            .names()
+            .map(String::from)
+            .chain(self.custom_annotations.label_names())
+    }
+
+    /// The documentation URL assigned by a user-defined rule or, failing that,
+    /// by the built-in annotators
+    pub fn doc_url(&self) -> Option<&str> {
+        self.custom_annotations
+            .doc_url
+            .as_deref()
+            .or_else(|| self.doc_url_id.and_then(doc_url_from_id))
+    }
+
+    /// The resolution or discussion URL assigned by a user-defined rule or, failing that,
+    /// by the built-in annotators
+    pub fn resolution_or_discussion_url(&self) -> Option<&str> {
+        self.custom_annotations
+            .resolution_or_discussion_url
+            .as_deref()
+            .or_else(|| {
+                self.resolution_or_discussion_url_id
+                    .and_then(resolution_or_discussion_url_from_id)
+            })
+    }
+
+    #[inline]
+    pub fn get_labels(&self) -> LogEntryLabels {
+        self.labels
+    }
+}
+
+/// With `indexed`, searches look up their terms in the message search index.
+fn build_where_clause(ctx: &QueryContext, indexed: bool) -> (String, Vec<Value>) {
+    let mut conditions = Vec::new();
+    let mut params: Vec<Value> = Vec::new();
+
+    if let Some(since) = ctx.since_time {
+        conditions.push("timestamp >= ?".to_string());
+        params.push(Value::Timestamp(
+            TimeUnit::Microsecond,
+            since.timestamp_micros(),
+        ));
+    }
+
+    if let Some(to) = ctx.to_time {
+        conditions.push("timestamp <= ?".to_string());
+        params.push(Value::Timestamp(
+            TimeUnit::Microsecond,
+            to.timestamp_micros(),
+        ));
+    }
+
+    if let Some(sev) = ctx.min_severity {
+        conditions.push("severity >= ?".to_string());
+        params.push(Value::TinyInt(sev.to_ordinal()));
+    }
+
+    if let Some(ref preset) = ctx.preset {
+        let or_parts = [
+            preset.severity().map(|sev| {
+                SqlCondition::new(
+                    "severity = ?",
+                    vec![SqlParam::Integer(i64::from(sev.to_ordinal()))],
+                )
+            }),
+            NodeLogEntryLabels::any_condition(preset.labels()),
+        ];
+        if let Some(condition) = or_parts.into_iter().flatten().reduce(SqlCondition::or) {
+            push_condition(&mut conditions, &mut params, &condition);
+        }
+    } else {
+        if let Some(ref sev) = ctx.severity {
+            match sev.parse::<Severity>() {
+                Ok(severity) => {
+                    conditions.push("severity = ?".to_string());
+                    params.push(Value::TinyInt(severity.to_ordinal()));
+                }
+                // No entry has an unknown severity
+                Err(_) => conditions.push("FALSE".to_string()),
+            }
+        }
+
+        if !ctx.labels.is_empty() {
+            let mut combined = LogEntryLabels::empty();
+            let mut custom_labels = Vec::new();
+            for label in &ctx.labels {
+                if let Some(flag) = LogEntryLabels::from_label(label) {
+                    combined |= flag;
+                } else if let Some(name) = custom_label_name(label) {
+                    custom_labels.push(name);
                 }
-                result.push_str(label_name);
+            }
+            let label_conditions = if ctx.matching_all_labels {
+                [
+                    NodeLogEntryLabels::all_condition(combined),
+                    NodeLogEntryCustomAnnotations::labels_condition(&custom_labels, true),
+                ]
+            } else {
+                [
+                    NodeLogEntryLabels::any_condition(combined),
+                    NodeLogEntryCustomAnnotations::labels_condition(&custom_labels, false),
+                ]
+            };
+            let combine = if ctx.matching_all_labels {
+                SqlCondition::and
+            } else {
+                SqlCondition::or
+            };
+            if let Some(condition) = label_conditions.into_iter().flatten().reduce(combine) {
+                push_condition(&mut conditions, &mut params, &condition);
             }
         }
-        result
     }
 
-    #[inline]
-    pub fn get_labels(&self) -> LogEntryLabels {
-        LogEntryLabels::from_bits_i64(self.labels)
+    if let Some(ref pid) = ctx.erlang_pid {
+        conditions.push("erlang_pid = ?".to_string());
+        params.push(Value::Text(pid.clone()));
+    }
+
+    if let Some(ref n) = ctx.node {
+        conditions.push("node = ?".to_string());
+        params.push(Value::Text(n.clone()));
+    }
+
+    let metadata_filters = [
+        ("domain", &ctx.domain),
+        ("mfa", &ctx.mfa),
+        ("source_file", &ctx.source_file),
+        ("connection", &ctx.connection),
+        ("vhost", &ctx.vhost),
+        ("username", &ctx.user),
+    ];
+    for (column, value) in metadata_filters {
+        if let Some(v) = value {
+            conditions.push(format!("{} = ?", column));
+            params.push(Value::Text(v.clone()));
+        }
+    }
+
+    for (kind, name) in &ctx.entities {
+        let (condition, param) = NodeLogEntryEntities::name_condition(*kind, name);
+        conditions.push(condition);
+        params.push(param);
+    }
+
+    if let Some(ref sub) = ctx.subsystem
+        && let Ok(subsystem) = sub.parse::<crate::entry_metadata::subsystems::Subsystem>()
+    {
+        conditions.push("subsystem_id = ?".to_string());
+        params.push(Value::SmallInt(subsystem.to_id()));
+    }
+
+    if ctx.has_resolution_or_discussion_url {
+        conditions.push(format!(
+            "(resolution_or_discussion_url_id IS NOT NULL OR {})",
+            NodeLogEntryCustomAnnotations::has_resolution_or_discussion_url_condition()
+        ));
+    }
+
+    if ctx.has_doc_url {
+        conditions.push(format!(
+            "(doc_url_id IS NOT NULL OR {})",
+            NodeLogEntryCustomAnnotations::has_doc_url_condition()
+        ));
+    }
+
+    if let Some(ref search) = ctx.search {
+        let (search_conditions, search_params) = MessageSearchIndex::conditions(search, indexed);
+        conditions.extend(search_conditions);
+        params.extend(search_params);
+    }
+
+    for condition in &ctx.where_conditions {
+        push_condition(&mut conditions, &mut params, condition);
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+
+    (where_clause, params)
+}
+
+fn push_condition(conditions: &mut Vec<String>, params: &mut Vec<Value>, condition: &SqlCondition) {
+    conditions.push(condition.sql().to_string());
+    params.extend(condition.params().iter().map(SqlParam::to_value));
+}
+
+fn read_labels(row: &duckdb::Row<'_>, idx: usize) -> duckdb::Result<LogEntryLabels> {
+    let ids: Option<String> = row.get(idx)?;
+    Ok(ids
+        .map(|ids| LogEntryLabels::from_id_list(&ids))
+        .unwrap_or_default())
+}
+
+/// The columns read by [`model_from_row`], with labels and custom annotations aggregated
+/// from the side tables for the `node_log_entries` row aliased as `entry_alias`.
+pub(crate) fn model_select_list(entry_alias: &str) -> String {
+    format!(
+        "id, node, timestamp, severity, erlang_pid, subsystem_id, message, {} AS labels, resolution_or_discussion_url_id, doc_url_id, domain, mfa, source_file, source_line, connection, vhost, username, {}, {}",
+        label_ids_subquery(entry_alias),
+        custom_labels_subquery(entry_alias),
+        custom_urls_subqueries(entry_alias)
+    )
+}
+
+pub(crate) fn model_from_row(row: &duckdb::Row<'_>) -> duckdb::Result<Model> {
+    let timestamp_micros: i64 = row.get(2)?;
+    let timestamp = DateTime::from_timestamp_micros(timestamp_micros)
+        .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap());
+    let source_line: Option<i32> = row.get(13)?;
+
+    Ok(Model {
+        id: row.get(0)?,
+        node: row.get(1)?,
+        timestamp,
+        severity: read_severity(row, 3)?.to_string(),
+        erlang_pid: row.get(4)?,
+        subsystem_id: row.get(5)?,
+        message: row.get(6)?,
+        labels: read_labels(row, 7)?,
+        resolution_or_discussion_url_id: row.get(8)?,
+        doc_url_id: row.get(9)?,
+        metadata: StructuredMetadata {
+            domain: row.get(10)?,
+            mfa: row.get(11)?,
+            file: row.get(12)?,
+            line: source_line.and_then(|l| u32::try_from(l).ok()),
+            connection: row.get(14)?,
+            vhost: row.get(15)?,
+            user: row.get(16)?,
+        },
+        custom_annotations: custom_annotations_from(row.get(17)?, row.get(18)?, row.get(19)?),
+    })
+}
+
+/// Reads a severity stored as its [`Severity::to_ordinal`] value.
+pub(crate) fn read_severity(row: &duckdb::Row<'_>, idx: usize) -> duckdb::Result<Severity> {
+    let ordinal: i8 = row.get(idx)?;
+    Severity::from_ordinal(ordinal).ok_or_else(|| {
+        DuckDbError::FromSqlConversionFailure(
+            idx,
+            duckdb::types::Type::TinyInt,
+            format!("unknown severity ordinal: {}", ordinal).into(),
+        )
+    })
+}
+
+fn join_sql_columns(columns: &[LogColumn]) -> String {
+    columns
+        .iter()
+        .map(|c| c.sql_column())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Appends entries and their side table rows (labels, entities, custom annotations
+/// and message patterns) through a single set of DuckDB appenders.
+pub struct EntryAppender<'conn> {
+    conn: &'conn Connection,
+    entries: Appender<'conn>,
+    labels: Appender<'conn>,
+    entities: Appender<'conn>,
+    custom_annotations: Appender<'conn>,
+    templates: HashMap<i64, String>,
+}
+
+impl<'conn> EntryAppender<'conn> {
+    fn new(conn: &'conn Connection) -> Result<Self, DuckDbError> {
+        Ok(Self {
+            conn,
+            entries: conn.appender("node_log_entries")?,
+            labels: conn.appender("node_log_entry_labels")?,
+            entities: conn.appender("node_log_entry_entities")?,
+            custom_annotations: conn.appender("node_log_entry_custom_annotations")?,
+            templates: HashMap::new(),
+        })
+    }
+
+    /// Appends an entry with the given id. Ids must be unique, see [`ParsedLogEntry::explicit_id`].
+    pub fn append(
+        &mut self,
+        entry: &ParsedLogEntry,
+        id: i64,
+        node: &str,
+    ) -> Result<(), DuckDbError> {
+        let timestamp_micros = entry.timestamp.timestamp_micros();
+        let pattern = MessagePattern::of(&entry.message);
+
+        self.entries.append_row(params![
+            id,
+            node,
+            Value::Timestamp(TimeUnit::Microsecond, timestamp_micros),
+            entry.severity.to_ordinal(),
+            entry.process_id,
+            entry.subsystem_id,
+            entry.message,
+            entry.resolution_or_discussion_url_id,
+            entry.doc_url_id,
+            entry.metadata.domain,
+            entry.metadata.mfa,
+            entry.metadata.file,
+            entry.metadata.line.and_then(|l| i32::try_from(l).ok()),
+            entry.metadata.connection,
+            entry.metadata.vhost,
+            entry.metadata.user,
+            pattern.id,
+        ])?;
+        self.templates.entry(pattern.id).or_insert(pattern.template);
+        NodeLogEntryLabels::append(&mut self.labels, id, entry.labels)?;
+        NodeLogEntryEntities::append(&mut self.entities, id, &entry.message, &entry.metadata)?;
+        NodeLogEntryCustomAnnotations::append(
+            &mut self.custom_annotations,
+            id,
+            &entry.custom_annotations,
+        )?;
+        Ok(())
+    }
+
+    /// Replaces the stored entry of `node` that `entry` is a longer version of, that is,
+    /// one with the same timestamp, severity and Erlang process whose message `entry`'s
+    /// message starts with. The stored entry keeps its id.
+    ///
+    /// The last entry of a log file is stored before it is known to be complete, and read
+    /// again by the next incremental ingestion, which uses this if more of its lines
+    /// were written since. Returns the id of the replaced entry, if any.
+    pub fn replace_shorter_version(
+        &mut self,
+        entry: &ParsedLogEntry,
+        node: &str,
+    ) -> Result<Option<i64>, DuckDbError> {
+        let id: Option<i64> = self.conn.query_row(
+            "SELECT MAX(id) FROM node_log_entries
+             WHERE node = ? AND timestamp = ? AND severity = ? AND erlang_pid = ?
+               AND starts_with(?, message)",
+            params![
+                node,
+                Value::Timestamp(TimeUnit::Microsecond, entry.timestamp.timestamp_micros()),
+                entry.severity.to_ordinal(),
+                entry.process_id,
+                entry.message,
+            ],
+            |row| row.get(0),
+        )?;
+        let Some(id) = id else {
+            return Ok(None);
+        };
+
+        for (table, id_column, _) in renumbered_tables(self.conn)? {
+            self.conn.execute(
+                &format!("DELETE FROM {} WHERE {} = ?", table, id_column),
+                [id],
+            )?;
+        }
+        self.append(entry, id, node)?;
+        if MessageSearchIndex::exists_on(self.conn)? {
+            MessageSearchIndex::index_message_on(self.conn, id, &entry.message)?;
+        }
+        Ok(Some(id))
+    }
+
+    /// Returns the fingerprints, see [`entry_fingerprint`], of the flushed entries
+    /// of `node` logged at or after `since`. Used to skip entries that are already stored
+    /// when files overlap, e.g. when a copy of a log file is ingested along with the original.
+    pub fn fingerprints_since(
+        &self,
+        node: &str,
+        since: DateTime<Utc>,
+    ) -> Result<HashSet<u64>, DuckDbError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT timestamp, severity, erlang_pid, message
+             FROM node_log_entries
+             WHERE node = ? AND timestamp >= ?",
+        )?;
+        let since = Value::Timestamp(TimeUnit::Microsecond, since.timestamp_micros());
+        let rows = stmt.query_map(params![node, since], |row| {
+            Ok(fingerprint(
+                row.get(0)?,
+                read_severity(row, 1)?,
+                &row.get::<_, String>(2)?,
+                &row.get::<_, String>(3)?,
+            ))
+        })?;
+        rows.collect()
+    }
+
+    /// Makes the appended entries visible to other connections.
+    pub fn flush(&mut self) -> Result<(), DuckDbError> {
+        self.entries.flush()?;
+        self.labels.flush()?;
+        self.entities.flush()?;
+        self.custom_annotations.flush()?;
+        MessagePatterns::insert_templates(self.conn, &self.templates)?;
+        self.templates.clear();
+        Ok(())
+    }
+}
+
+/// Identifies an entry by its timestamp, severity, Erlang process and message.
+/// Two entries of a node with the same fingerprint are copies of the same entry.
+pub fn entry_fingerprint(entry: &ParsedLogEntry) -> u64 {
+    fingerprint(
+        entry.timestamp.timestamp_micros(),
+        entry.severity,
+        &entry.process_id,
+        &entry.message,
+    )
+}
+
+fn fingerprint(timestamp_micros: i64, severity: Severity, erlang_pid: &str, message: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    timestamp_micros.hash(&mut hasher);
+    severity.hash(&mut hasher);
+    erlang_pid.hash(&mut hasher);
+    message.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The tables keyed by entry id, with their id column and DDL.
+/// The id column comes first in all of them.
+fn renumbered_tables(
+    conn: &Connection,
+) -> Result<Vec<(&'static str, &'static str, &'static str)>, DuckDbError> {
+    let mut tables = vec![
+        ("node_log_entries", "id", NODE_LOG_ENTRIES_TABLE_DDL),
+        (
+            "node_log_entry_labels",
+            "entry_id",
+            NODE_LOG_ENTRY_LABELS_TABLE_DDL,
+        ),
+        (
+            "node_log_entry_entities",
+            "entry_id",
+            NODE_LOG_ENTRY_ENTITIES_TABLE_DDL,
+        ),
+        (
+            "node_log_entry_custom_annotations",
+            "entry_id",
+            NODE_LOG_ENTRY_CUSTOM_ANNOTATIONS_TABLE_DDL,
+        ),
+    ];
+    if MessageSearchIndex::exists_on(conn)? {
+        tables.push(("message_terms", "entry_id", MESSAGE_TERMS_TABLE_DDL));
     }
+    Ok(tables)
 }
 
 impl NodeLogEntry {
@@ -233,157 +822,286 @@ impl NodeLogEntry {
         Ok(max_id.unwrap_or(0))
     }
 
-    pub fn query(db: &DatabaseConnection, ctx: &QueryContext) -> Result<Vec<Model>, DuckDbError> {
+    pub fn most_recent_timestamp(
+        db: &DatabaseConnection,
+    ) -> Result<Option<DateTime<Utc>>, DuckDbError> {
         let conn = db.get().map_err(pool_error_to_duckdb)?;
 
-        let mut conditions = Vec::new();
-        let mut params: Vec<Value> = Vec::new();
-
-        if let Some(since) = ctx.since_time {
-            conditions.push("timestamp >= ?".to_string());
-            params.push(Value::Timestamp(
-                TimeUnit::Microsecond,
-                since.timestamp_micros(),
-            ));
-        }
+        let micros: Option<i64> =
+            conn.query_row("SELECT MAX(timestamp) FROM node_log_entries", [], |row| {
+                row.get(0)
+            })?;
+        Ok(micros.and_then(DateTime::from_timestamp_micros))
+    }
 
-        if let Some(to) = ctx.to_time {
-            conditions.push("timestamp <= ?".to_string());
-            params.push(Value::Timestamp(
-                TimeUnit::Microsecond,
-                to.timestamp_micros(),
-            ));
-        }
+    pub fn query(db: &DatabaseConnection, ctx: &QueryContext) -> Result<Vec<Model>, DuckDbError> {
+        let conn = db.get().map_err(pool_error_to_duckdb)?;
 
-        if let Some(ref preset) = ctx.preset {
-            let mut or_parts = Vec::new();
+        let indexed = ctx.search.is_some() && MessageSearchIndex::exists_on(&conn)?;
+        let (where_clause, params) = build_where_clause(ctx, indexed);
 
-            if let Some(sev) = preset.severity() {
-                or_parts.push("severity = ?".to_string());
-                params.push(Value::Text(sev.to_string()));
-            }
+        let mut effective_limit = ctx.limit.unwrap_or(DEFAULT_MAX_QUERY_LIMIT);
+        let mut tail = ctx.tail;
+        if ctx.context_window.is_some() {
+            effective_limit = effective_limit.min(ContextWindow::MAX_HITS);
+            tail = tail.map(|n| n.min(ContextWindow::MAX_HITS));
+        }
 
-            let label_mask = preset.labels().bits();
-            if label_mask != 0 {
-                or_parts.push("(labels & ?) != 0".to_string());
-                params.push(Value::BigInt(label_mask as i64));
-            }
+        let offset_clause = match ctx.offset {
+            Some(n) => format!(" OFFSET {}", n),
+            None => String::new(),
+        };
 
-            if !or_parts.is_empty() {
-                conditions.push(format!("({})", or_parts.join(" OR ")));
-            }
+        let model_columns = model_select_list("sub");
+        let mut params = params;
+        let sql = if !ctx.order_by.is_empty() {
+            let order_by = order_by_list(&ctx.order_by, false);
+            let (inner_order_by, limit) = match tail {
+                Some(tail_n) => (order_by_list(&ctx.order_by, true), tail_n),
+                None => (order_by.clone(), effective_limit),
+            };
+            format!(
+                "SELECT {}
+                 FROM (SELECT * FROM node_log_entries
+                 {}
+                 ORDER BY {}
+                 LIMIT {}{}) sub
+                 ORDER BY {}",
+                model_columns, where_clause, inner_order_by, limit, offset_clause, order_by
+            )
+        } else if let (Some(search), true, None) = (&ctx.search, indexed, tail) {
+            let (scores_join, mut scores_params) = MessageSearchIndex::scores_join(search);
+            scores_params.append(&mut params);
+            params = scores_params;
+            format!(
+                "SELECT {}
+                 FROM (SELECT node_log_entries.*, search_score FROM node_log_entries
+                 {}
+                 {}
+                 ORDER BY search_score DESC, timestamp ASC
+                 LIMIT {}{}) sub
+                 ORDER BY search_score DESC, timestamp ASC",
+                model_columns, scores_join, where_clause, effective_limit, offset_clause
+            )
+        } else if let Some(tail_n) = tail {
+            format!(
+                "SELECT {}
+                 FROM (SELECT * FROM node_log_entries
+                 {}
+                 ORDER BY timestamp DESC
+                 LIMIT {}{}) sub
+                 ORDER BY timestamp ASC",
+                model_columns, where_clause, tail_n, offset_clause
+            )
         } else {
-            if let Some(ref sev) = ctx.severity {
-                conditions.push("severity = ?".to_string());
-                params.push(Value::Text(sev.clone()));
-            }
-
-            if !ctx.labels.is_empty() {
-                let mut combined_mask: u64 = 0;
-                for label in &ctx.labels {
-                    if let Some(bit) = LogEntryLabels::bit_for_label(label) {
-                        combined_mask |= bit;
-                    }
-                }
-                if combined_mask != 0 {
-                    if ctx.matching_all_labels {
-                        conditions.push("(labels & ?) = ?".to_string());
-                        params.push(Value::BigInt(combined_mask as i64));
-                        params.push(Value::BigInt(combined_mask as i64));
-                    } else {
-                        conditions.push("(labels & ?) != 0".to_string());
-                        params.push(Value::BigInt(combined_mask as i64));
-                    }
-                }
-            }
-        }
+            format!(
+                "SELECT {}
+                 FROM (SELECT * FROM node_log_entries
+                 {}
+                 ORDER BY timestamp ASC
+                 LIMIT {}{}) sub
+                 ORDER BY timestamp ASC",
+                model_columns, where_clause, effective_limit, offset_clause
+            )
+        };
 
-        if let Some(ref pid) = ctx.erlang_pid {
-            conditions.push("erlang_pid = ?".to_string());
-            params.push(Value::Text(pid.clone()));
-        }
+        let mut stmt = conn.prepare(&sql)?;
+        let params_slice: Vec<&dyn ToSql> = params.iter().map(|p| p as &dyn ToSql).collect();
 
-        if let Some(ref n) = ctx.node {
-            conditions.push("node = ?".to_string());
-            params.push(Value::Text(n.clone()));
-        }
+        let rows = stmt.query_map(params_slice.as_slice(), model_from_row)?;
+        let hits: Vec<Model> = rows.collect::<Result<_, _>>()?;
 
-        if let Some(ref sub) = ctx.subsystem
-            && let Ok(subsystem) = sub.parse::<crate::entry_metadata::subsystems::Subsystem>()
-        {
-            conditions.push("subsystem_id = ?".to_string());
-            params.push(Value::SmallInt(subsystem.to_id()));
+        match ctx.context_window {
+            Some(window) => Self::expand_context(&conn, &hits, &window),
+            None => Ok(hits),
         }
+    }
 
-        if ctx.has_resolution_or_discussion_url {
-            conditions.push("resolution_or_discussion_url_id IS NOT NULL".to_string());
-        }
+    /// Returns the entry with the given id along with the entries logged around it,
+    /// in timestamp order, or `None` if there is no such entry.
+    pub fn context(
+        db: &DatabaseConnection,
+        id: i64,
+        window: &ContextWindow,
+    ) -> Result<Option<Vec<Model>>, DuckDbError> {
+        let conn = db.get().map_err(pool_error_to_duckdb)?;
 
-        if ctx.has_doc_url {
-            conditions.push("doc_url_id IS NOT NULL".to_string());
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}
+             FROM node_log_entries
+             WHERE id = ?",
+            model_select_list("node_log_entries")
+        ))?;
+        let anchor = stmt
+            .query_map(params![id], model_from_row)?
+            .next()
+            .transpose()?;
+
+        match anchor {
+            Some(anchor) => Self::expand_context(&conn, &[anchor], window).map(Some),
+            None => Ok(None),
         }
+    }
 
-        for clause in &ctx.raw_where_clauses {
-            conditions.push(clause.clone());
+    fn expand_context(
+        conn: &Connection,
+        hits: &[Model],
+        window: &ContextWindow,
+    ) -> Result<Vec<Model>, DuckDbError> {
+        let mut entries: HashMap<i64, Model> = HashMap::new();
+        for hit in hits {
+            for entry in window.entries_around(conn, hit)? {
+                entries.entry(entry.id).or_insert(entry);
+            }
         }
 
-        let effective_limit = ctx.limit.unwrap_or(DEFAULT_MAX_QUERY_LIMIT);
+        let mut entries: Vec<Model> = entries.into_values().collect();
+        entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then(a.id.cmp(&b.id)));
+        Ok(entries)
+    }
 
-        let where_clause = if conditions.is_empty() {
-            String::new()
-        } else {
-            format!("WHERE {}", conditions.join(" AND "))
+    /// Executes the aggregation stage of the given context (projection, distinct or count)
+    /// and returns a table of results instead of full entries.
+    ///
+    /// The filtering conditions, limit and offset of the context apply as with [`Self::query`].
+    /// Contexts without an aggregation stage return an empty result.
+    pub fn aggregate(
+        db: &DatabaseConnection,
+        ctx: &QueryContext,
+    ) -> Result<TabularResult, DuckDbError> {
+        let Some(ref aggregation) = ctx.aggregation else {
+            return Ok(TabularResult::default());
         };
 
+        let conn = db.get().map_err(pool_error_to_duckdb)?;
+        let indexed = ctx.search.is_some() && MessageSearchIndex::exists_on(&conn)?;
+        let (where_clause, params) = build_where_clause(ctx, indexed);
+
+        let effective_limit = ctx.limit.unwrap_or(DEFAULT_MAX_QUERY_LIMIT);
         let offset_clause = match ctx.offset {
             Some(n) => format!(" OFFSET {}", n),
             None => String::new(),
         };
 
-        let sql = if let Some(tail_n) = ctx.tail {
-            format!(
-                "SELECT * FROM (SELECT id, node, timestamp, severity, erlang_pid, subsystem_id, message, labels, resolution_or_discussion_url_id, doc_url_id
-                 FROM node_log_entries
-                 {}
-                 ORDER BY timestamp DESC
-                 LIMIT {}{}) sub
-                 ORDER BY timestamp ASC",
-                where_clause, tail_n, offset_clause
-            )
-        } else {
-            format!(
-                "SELECT id, node, timestamp, severity, erlang_pid, subsystem_id, message, labels, resolution_or_discussion_url_id, doc_url_id
-                 FROM node_log_entries
-                 {}
-                 ORDER BY timestamp ASC
-                 LIMIT {}{}",
-                where_clause, effective_limit, offset_clause
-            )
+        let mut source = "node_log_entries".to_string();
+        for column in aggregation.columns() {
+            if *column == LogColumn::Labels {
+                source = format!("{} {}", source, LABELS_JOIN);
+            } else if *column == LogColumn::Pattern {
+                source = format!("{} {}", source, PATTERNS_JOIN);
+            } else if let Some(kind) = column.entity_kind() {
+                source = format!("{} {}", source, entity_join(kind));
+            }
+        }
+
+        let (columns, has_count, sql) = match aggregation {
+            Aggregation::Project(columns) => {
+                let select_list = join_sql_columns(columns);
+                let keys = if ctx.order_by.is_empty() {
+                    vec![SortKey::ascending(LogColumn::Timestamp)]
+                } else {
+                    ctx.order_by.clone()
+                };
+                let sql = if let Some(tail_n) = ctx.tail {
+                    // The keys are selected under aliases, as the projection
+                    // may not include the columns they refer to
+                    let items = order_by_items(&keys, false);
+                    let sort_columns: Vec<String> = items
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, (expression, _))| format!("{} AS sort_key_{}", expression, idx))
+                        .collect();
+                    let outer_order_by: Vec<String> = items
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, (_, direction))| format!("sort_key_{} {}", idx, direction))
+                        .collect();
+                    format!(
+                        "SELECT {} FROM (SELECT {}, {}
+                         FROM {}
+                         {}
+                         ORDER BY {}
+                         LIMIT {}{}) sub
+                         ORDER BY {}",
+                        select_list,
+                        select_list,
+                        sort_columns.join(", "),
+                        source,
+                        where_clause,
+                        order_by_list(&keys, true),
+                        tail_n,
+                        offset_clause,
+                        outer_order_by.join(", ")
+                    )
+                } else {
+                    format!(
+                        "SELECT {} FROM {} {} ORDER BY {} LIMIT {}{}",
+                        select_list,
+                        source,
+                        where_clause,
+                        order_by_list(&keys, false),
+                        effective_limit,
+                        offset_clause
+                    )
+                };
+                (columns.clone(), false, sql)
+            }
+            Aggregation::Distinct(columns) => {
+                let select_list = join_sql_columns(columns);
+                let sql = format!(
+                    "SELECT DISTINCT {} FROM {} {} ORDER BY {} LIMIT {}{}",
+                    select_list, source, where_clause, select_list, effective_limit, offset_clause
+                );
+                (columns.clone(), false, sql)
+            }
+            Aggregation::CountBy(Some(column)) => {
+                let sql_column = column.sql_column();
+                let sql = format!(
+                    "SELECT {}, COUNT(*) AS entry_count FROM {} {} GROUP BY {} ORDER BY entry_count DESC, {} ASC LIMIT {}{}",
+                    sql_column,
+                    source,
+                    where_clause,
+                    sql_column,
+                    sql_column,
+                    effective_limit,
+                    offset_clause
+                );
+                (vec![*column], true, sql)
+            }
+            Aggregation::CountBy(None) => {
+                let sql = format!(
+                    "SELECT COUNT(*) AS entry_count FROM node_log_entries {}",
+                    where_clause
+                );
+                (Vec::new(), true, sql)
+            }
         };
 
         let mut stmt = conn.prepare(&sql)?;
         let params_slice: Vec<&dyn ToSql> = params.iter().map(|p| p as &dyn ToSql).collect();
 
         let rows = stmt.query_map(params_slice.as_slice(), |row| {
-            let timestamp_micros: i64 = row.get(2)?;
-            let timestamp = DateTime::from_timestamp_micros(timestamp_micros)
-                .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap());
-
-            Ok(Model {
-                id: row.get(0)?,
-                node: row.get(1)?,
-                timestamp,
-                severity: row.get(3)?,
-                erlang_pid: row.get(4)?,
-                subsystem_id: row.get(5)?,
-                message: row.get(6)?,
-                labels: row.get(7)?,
-                resolution_or_discussion_url_id: row.get(8)?,
-                doc_url_id: row.get(9)?,
-            })
+            let mut values = Vec::with_capacity(columns.len() + 1);
+            for (idx, column) in columns.iter().enumerate() {
+                values.push(column.read_value(row, idx)?);
+            }
+            if has_count {
+                let count: i64 = row.get(columns.len())?;
+                values.push(count.into());
+            }
+            Ok(values)
         })?;
+        let rows = rows.collect::<Result<Vec<_>, _>>()?;
 
-        rows.collect()
+        let mut names: Vec<String> = columns.iter().map(|c| c.name().to_string()).collect();
+        if has_count {
+            names.push("count".to_string());
+        }
+
+        Ok(TabularResult {
+            columns: names,
+            rows,
+        })
     }
 
     pub fn insert_parsed_entries(
@@ -409,63 +1127,181 @@ impl NodeLogEntry {
         };
 
         for chunk in entries.chunks(DB_INSERT_BATCH_SIZE) {
-            let mut appender = conn.appender("node_log_entries")?;
-
+            let mut appender = EntryAppender::new(&conn)?;
             for entry in chunk {
                 let id = entry.explicit_id.unwrap_or_else(|| {
                     let id = running_id;
                     running_id += 1;
                     id
                 });
-                let timestamp_micros = entry.timestamp.timestamp_micros();
-
-                appender.append_row(params![
-                    id,
-                    node,
-                    Value::Timestamp(TimeUnit::Microsecond, timestamp_micros),
-                    entry.severity.to_string(),
-                    entry.process_id,
-                    entry.subsystem_id,
-                    entry.message,
-                    entry.labels.to_bits_i64(),
-                    entry.resolution_or_discussion_url_id,
-                    entry.doc_url_id,
-                ])?;
+                appender.append(entry, id, node)?;
             }
-
             appender.flush()?;
         }
 
         Ok(())
     }
 
+    /// Runs `f` with an [`EntryAppender`] that stays open until `f` returns,
+    /// so that bulk imports do not pay for setting up appenders for every batch.
+    /// Pending rows are flushed before returning.
+    pub fn with_appender<T, E>(
+        db: &DatabaseConnection,
+        f: impl FnOnce(&mut EntryAppender<'_>) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        E: From<DuckDbError>,
+    {
+        let conn = db.get().map_err(pool_error_to_duckdb)?;
+        let mut appender = EntryAppender::new(&conn)?;
+        let result = f(&mut appender)?;
+        appender.flush()?;
+        Ok(result)
+    }
+
+    /// Renumbers all entries so that ids follow timestamp order across all nodes and files,
+    /// with ties broken by node and then by the previous ids. Entries are ingested one file
+    /// at a time, so until renumbered, the ids of rotated or overlapping files of a node,
+    /// and of different nodes, interleave in the order the files were given.
+    ///
+    /// Secondary indexes are dropped along with the rebuilt tables and must be re-created
+    /// with [`crate::post_insertion_operations`]. Returns true if any ids were changed.
+    pub fn renumber_in_timestamp_order(db: &DatabaseConnection) -> Result<bool, DuckDbError> {
+        let conn = db.get().map_err(pool_error_to_duckdb)?;
+
+        // Afterwards, new entries would no longer have the highest ids
+        MessageSearchIndex::index_new_entries_on(&conn)?;
+
+        let out_of_order: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM (
+                SELECT id, row_number() OVER (ORDER BY timestamp, node, id) AS new_id
+                FROM node_log_entries
+             ) WHERE id <> new_id",
+            [],
+            |row| row.get(0),
+        )?;
+        if out_of_order == 0 {
+            return Ok(false);
+        }
+
+        // Indexed tables cannot be updated in place, so they are rebuilt
+        let mut renumbering = String::from(
+            "
+            BEGIN TRANSACTION;
+            CREATE TEMP TABLE entry_id_map AS
+                SELECT id AS old_id, row_number() OVER (ORDER BY timestamp, node, id) AS new_id
+                FROM node_log_entries;
+            ",
+        );
+        for (table, id_column, ddl) in renumbered_tables(&conn)? {
+            renumbering.push_str(&format!(
+                "
+                CREATE TABLE {0}_renumbered AS
+                    SELECT m.new_id AS {1}, t.* EXCLUDE ({1})
+                    FROM {0} t JOIN entry_id_map m ON t.{1} = m.old_id;
+                DROP TABLE {0};
+                {2}
+                INSERT INTO {0} SELECT * FROM {0}_renumbered ORDER BY {1};
+                DROP TABLE {0}_renumbered;
+                ",
+                table, id_column, ddl
+            ));
+        }
+        renumbering.push_str(
+            "
+            DROP TABLE entry_id_map;
+            COMMIT;
+            ",
+        );
+
+        if let Err(e) = conn.execute_batch(&renumbering) {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+        log::info!("Renumbered {} entries in timestamp order", out_of_order);
+        Ok(true)
+    }
+
+    /// Renumbers the entries with ids starting at `first_id` so that they follow timestamp
+    /// order among themselves, like [`NodeLogEntry::renumber_in_timestamp_order`] does
+    /// for all entries. Entries with lower ids keep theirs, so this is meant for
+    /// the entries appended by an incremental ingestion.
+    ///
+    /// Only the appended rows are rewritten and secondary indexes are kept.
+    /// Returns true if any ids were changed.
+    pub fn renumber_appended_in_timestamp_order(
+        db: &DatabaseConnection,
+        first_id: i64,
+    ) -> Result<bool, DuckDbError> {
+        let conn = db.get().map_err(pool_error_to_duckdb)?;
+
+        MessageSearchIndex::index_new_entries_on(&conn)?;
+
+        let out_of_order: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM (
+                SELECT id, ? - 1 + row_number() OVER (ORDER BY timestamp, node, id) AS new_id
+                FROM node_log_entries
+                WHERE id >= ?
+             ) WHERE id <> new_id",
+            [first_id, first_id],
+            |row| row.get(0),
+        )?;
+        if out_of_order == 0 {
+            return Ok(false);
+        }
+
+        let mut renumbering = format!(
+            "
+            BEGIN TRANSACTION;
+            CREATE TEMP TABLE entry_id_map AS
+                SELECT id AS old_id, {0} - 1 + row_number() OVER (ORDER BY timestamp, node, id) AS new_id
+                FROM node_log_entries
+                WHERE id >= {0};
+            ",
+            first_id
+        );
+        for (table, id_column, _) in renumbered_tables(&conn)? {
+            renumbering.push_str(&format!(
+                "
+                CREATE TEMP TABLE {0}_renumbered AS
+                    SELECT m.new_id AS {1}, t.* EXCLUDE ({1})
+                    FROM {0} t JOIN entry_id_map m ON t.{1} = m.old_id;
+                DELETE FROM {0} WHERE {1} >= {2};
+                INSERT INTO {0} SELECT * FROM {0}_renumbered ORDER BY {1};
+                DROP TABLE {0}_renumbered;
+                ",
+                table, id_column, first_id
+            ));
+        }
+        renumbering.push_str(
+            "
+            DROP TABLE entry_id_map;
+            COMMIT;
+            ",
+        );
+
+        if let Err(e) = conn.execute_batch(&renumbering) {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+        log::info!(
+            "Renumbered {} appended entries in timestamp order",
+            out_of_order
+        );
+        Ok(true)
+    }
+
     pub fn find_all(db: &DatabaseConnection) -> Result<Vec<Model>, DuckDbError> {
         let conn = db.get().map_err(pool_error_to_duckdb)?;
 
-        let mut stmt = conn.prepare(
-            "SELECT id, node, timestamp, severity, erlang_pid, subsystem_id, message, labels, resolution_or_discussion_url_id, doc_url_id
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}
              FROM node_log_entries
              ORDER BY timestamp ASC",
-        )?;
+            model_select_list("node_log_entries")
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
-            let timestamp_micros: i64 = row.get(2)?;
-            let timestamp = DateTime::from_timestamp_micros(timestamp_micros)
-                .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap());
-
-            Ok(Model {
-                id: row.get(0)?,
-                node: row.get(1)?,
-                timestamp,
-                severity: row.get(3)?,
-                erlang_pid: row.get(4)?,
-                subsystem_id: row.get(5)?,
-                message: row.get(6)?,
-                labels: row.get(7)?,
-                resolution_or_discussion_url_id: row.get(8)?,
-                doc_url_id: row.get(9)?,
-            })
-        })?;
+        let rows = stmt.query_map([], model_from_row)?;
 
         rows.collect()
     }
@@ -481,4 +1317,53 @@ impl NodeLogEntry {
 
         rows.collect()
     }
+
+    /// Counts the entries that match `ctx` per node and value of `column`,
+    /// in the display form of [`LogColumn::read_value`]. Unlike a `count by labels`
+    /// aggregation, entries with several labels are counted once per label.
+    pub fn get_node_counts_by(
+        db: &DatabaseConnection,
+        ctx: &QueryContext,
+        column: LogColumn,
+    ) -> Result<Vec<(String, Option<String>, i64)>, DuckDbError> {
+        let conn = db.get().map_err(pool_error_to_duckdb)?;
+        let indexed = ctx.search.is_some() && MessageSearchIndex::exists_on(&conn)?;
+        let (where_clause, params) = build_where_clause(ctx, indexed);
+
+        let (source, value_sql) = match column {
+            LogColumn::Labels => (
+                "node_log_entries JOIN node_log_entry_labels entry_labels ON entry_labels.entry_id = node_log_entries.id".to_string(),
+                "CAST(entry_labels.label_id AS VARCHAR)",
+            ),
+            LogColumn::Pattern => (
+                format!("node_log_entries {}", PATTERNS_JOIN),
+                column.sql_column(),
+            ),
+            _ => match column.entity_kind() {
+                Some(kind) => (
+                    format!("node_log_entries {}", entity_join(kind)),
+                    column.sql_column(),
+                ),
+                None => ("node_log_entries".to_string(), column.sql_column()),
+            },
+        };
+
+        let sql = format!(
+            "SELECT node, {} AS grouped_value, COUNT(*) AS entry_count FROM {} {} GROUP BY node, grouped_value ORDER BY node ASC, entry_count DESC",
+            value_sql, source, where_clause
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let params_slice: Vec<&dyn ToSql> = params.iter().map(|p| p as &dyn ToSql).collect();
+
+        let rows = stmt.query_map(params_slice.as_slice(), |row| {
+            let value = match column.read_value(row, 1)? {
+                JsonValue::Null => None,
+                other => Some(format_cell(&other)),
+            };
+            Ok((row.get(0)?, value, row.get(2)?))
+        })?;
+
+        rows.collect()
+    }
 }
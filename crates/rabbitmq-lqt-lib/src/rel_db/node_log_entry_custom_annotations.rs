@@ -0,0 +1,154 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage of the annotations assigned by user-defined rules
+//! in the `node_log_entry_custom_annotations` side table.
+//!
+//! Each row associates an entry id with an annotation kind (a custom label,
+//! a documentation URL or a resolution or discussion URL) and its value,
+//! see [`RuleSet`].
+//!
+//! [`RuleSet`]: crate::entry_metadata::rules::RuleSet
+
+use crate::entry_metadata::rules::CustomAnnotations;
+use crate::rel_db::DatabaseConnection;
+use crate::rel_db::sql_condition::{SqlCondition, SqlParam};
+use duckdb::{Appender, Error as DuckDbError, params};
+use std::io::Error as IoError;
+
+const LABEL_KIND: &str = "label";
+pub(crate) const DOC_URL_KIND: &str = "doc_url";
+pub(crate) const RESOLUTION_OR_DISCUSSION_URL_KIND: &str = "resolution_or_discussion_url";
+
+pub struct NodeLogEntryCustomAnnotations;
+
+/// A correlated subquery that returns the custom labels of the `node_log_entries` row
+/// aliased as `entry_alias` as a comma-separated list.
+pub(crate) fn custom_labels_subquery(entry_alias: &str) -> String {
+    format!(
+        "(SELECT string_agg(c.value, ',' ORDER BY c.value) FROM node_log_entry_custom_annotations c WHERE c.entry_id = {}.id AND c.kind = '{}')",
+        entry_alias, LABEL_KIND
+    )
+}
+
+/// Correlated subqueries that return the custom documentation URL and the custom
+/// resolution or discussion URL of the `node_log_entries` row aliased as `entry_alias`.
+pub(crate) fn custom_urls_subqueries(entry_alias: &str) -> String {
+    [DOC_URL_KIND, RESOLUTION_OR_DISCUSSION_URL_KIND]
+        .iter()
+        .map(|kind| {
+            format!(
+                "(SELECT max(c.value) FROM node_log_entry_custom_annotations c WHERE c.entry_id = {}.id AND c.kind = '{}')",
+                entry_alias, kind
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Instantiates custom annotations from the columns read with [`custom_labels_subquery`]
+/// and [`custom_urls_subqueries`].
+pub(crate) fn custom_annotations_from(
+    labels: Option<String>,
+    doc_url: Option<String>,
+    resolution_or_discussion_url: Option<String>,
+) -> CustomAnnotations {
+    CustomAnnotations {
+        labels: labels
+            .map(|list| list.split(',').map(String::from).collect())
+            .unwrap_or_default(),
+        doc_url,
+        resolution_or_discussion_url,
+    }
+}
+
+impl NodeLogEntryCustomAnnotations {
+    /// A SQL condition on `node_log_entries` that matches entries with at least one
+    /// (or, with `match_all`, all) of the given custom labels.
+    /// Returns `None` for an empty list.
+    pub fn labels_condition(labels: &[&str], match_all: bool) -> Option<SqlCondition> {
+        if labels.is_empty() {
+            return None;
+        }
+        let placeholders = vec!["?"; labels.len()].join(", ");
+        let mut params: Vec<SqlParam> = labels
+            .iter()
+            .map(|label| SqlParam::Text(label.to_string()))
+            .collect();
+        let having = if match_all {
+            params.push(SqlParam::Integer(labels.len() as i64));
+            " GROUP BY entry_id HAVING COUNT(DISTINCT value) = ?"
+        } else {
+            ""
+        };
+        Some(SqlCondition::new(
+            format!(
+                "id IN (SELECT entry_id FROM node_log_entry_custom_annotations WHERE kind = '{}' AND value IN ({}){})",
+                LABEL_KIND, placeholders, having
+            ),
+            params,
+        ))
+    }
+
+    /// A SQL condition on `node_log_entries` that matches entries with a custom documentation URL
+    pub fn has_doc_url_condition() -> String {
+        Self::has_kind_condition(DOC_URL_KIND)
+    }
+
+    /// A SQL condition on `node_log_entries` that matches entries with a custom
+    /// resolution or discussion URL
+    pub fn has_resolution_or_discussion_url_condition() -> String {
+        Self::has_kind_condition(RESOLUTION_OR_DISCUSSION_URL_KIND)
+    }
+
+    fn has_kind_condition(kind: &str) -> String {
+        format!(
+            "id IN (SELECT entry_id FROM node_log_entry_custom_annotations WHERE kind = '{}')",
+            kind
+        )
+    }
+
+    pub(crate) fn append(
+        appender: &mut Appender<'_>,
+        entry_id: i64,
+        annotations: &CustomAnnotations,
+    ) -> Result<(), DuckDbError> {
+        for label in &annotations.labels {
+            appender.append_row(params![entry_id, LABEL_KIND, label])?;
+        }
+        if let Some(ref url) = annotations.doc_url {
+            appender.append_row(params![entry_id, DOC_URL_KIND, url])?;
+        }
+        if let Some(ref url) = annotations.resolution_or_discussion_url {
+            appender.append_row(params![entry_id, RESOLUTION_OR_DISCUSSION_URL_KIND, url])?;
+        }
+        Ok(())
+    }
+
+    /// Lists the custom labels assigned to at least one entry, in alphabetical order.
+    pub fn label_names(db: &DatabaseConnection) -> Result<Vec<String>, DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT value FROM node_log_entry_custom_annotations
+             WHERE kind = ?
+             ORDER BY value ASC",
+        )?;
+        let rows = stmt.query_map([LABEL_KIND], |row| row.get(0))?;
+
+        rows.collect()
+    }
+}
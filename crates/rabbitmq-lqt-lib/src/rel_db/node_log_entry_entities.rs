@@ -0,0 +1,182 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage of the resources entries mention in the `node_log_entry_entities` side table.
+//!
+//! Each row associates an entry id with an entity kind, the virtual host
+//! the entity belongs to (if known) and its name, see [`extract_entities`].
+//!
+//! [`extract_entities`]: crate::entry_metadata::entities::extract_entities
+
+use crate::entry_metadata::entities::{EntityKind, extract_entities};
+use crate::parser::StructuredMetadata;
+use crate::rel_db::DatabaseConnection;
+use crate::rel_db::sql_condition::SqlCondition;
+use chrono::{DateTime, Utc};
+use duckdb::types::{Type, Value};
+use duckdb::{Appender, Connection, Error as DuckDbError, ToSql, params};
+use serde::{Deserialize, Serialize};
+use std::io::Error as IoError;
+use std::str::FromStr;
+
+pub struct NodeLogEntryEntities;
+
+/// How often an entity is mentioned across the log set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityMentions {
+    pub kind: EntityKind,
+    pub vhost: Option<String>,
+    pub name: String,
+    pub entry_count: i64,
+    pub node_count: i64,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+/// Joins a column named after the entity kind (e.g. `queue`) onto `node_log_entries`
+/// for queries that project, deduplicate or group by an entity.
+/// Entries that mention several entities of the kind produce a row per entity.
+pub(crate) fn entity_join(kind: EntityKind) -> String {
+    format!(
+        "LEFT JOIN (SELECT entry_id, name AS {kind} FROM node_log_entry_entities WHERE entity_kind = '{kind}') {kind}_entities ON {kind}_entities.entry_id = node_log_entries.id",
+        kind = kind.as_str()
+    )
+}
+
+impl NodeLogEntryEntities {
+    /// A SQL condition on `node_log_entries` that matches entries mentioning
+    /// an entity of the given kind whose `name` satisfies `name_predicate`,
+    /// e.g. `name = ?` or `regexp_matches(name, ?)`.
+    pub fn condition(kind: EntityKind, name_predicate: SqlCondition) -> SqlCondition {
+        name_predicate.map_sql(|predicate| Self::condition_sql(kind, predicate))
+    }
+
+    /// Like [`Self::condition`] with an exact name match, using a bound parameter for the name.
+    pub(crate) fn name_condition(kind: EntityKind, name: &str) -> (String, Value) {
+        (
+            Self::condition_sql(kind, "name = ?"),
+            Value::Text(name.to_string()),
+        )
+    }
+
+    fn condition_sql(kind: EntityKind, name_predicate: &str) -> String {
+        format!(
+            "id IN (SELECT entry_id FROM node_log_entry_entities WHERE entity_kind = '{}' AND {})",
+            kind.as_str(),
+            name_predicate
+        )
+    }
+
+    pub(crate) fn append(
+        appender: &mut Appender<'_>,
+        entry_id: i64,
+        message: &str,
+        metadata: &StructuredMetadata,
+    ) -> Result<(), DuckDbError> {
+        for entity in extract_entities(message, metadata) {
+            appender.append_row(params![
+                entry_id,
+                entity.kind.as_str(),
+                entity.vhost,
+                entity.name
+            ])?;
+        }
+        Ok(())
+    }
+
+    /// Lists the most mentioned entities, optionally of a single kind,
+    /// ordered by the number of entries that mention them.
+    pub fn most_mentioned(
+        db: &DatabaseConnection,
+        kind: Option<EntityKind>,
+        limit: u64,
+    ) -> Result<Vec<EntityMentions>, DuckDbError> {
+        let conn = db.get().map_err(|e| {
+            DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+        })?;
+
+        let mut params: Vec<Value> = Vec::new();
+        let where_clause = match kind {
+            Some(kind) => {
+                params.push(Value::Text(kind.as_str().to_string()));
+                "WHERE en.entity_kind = ?"
+            }
+            None => "",
+        };
+        let sql = format!(
+            "SELECT en.entity_kind, en.vhost, en.name,
+                    COUNT(DISTINCT en.entry_id) AS entry_count,
+                    COUNT(DISTINCT e.node),
+                    MIN(e.timestamp),
+                    MAX(e.timestamp)
+             FROM node_log_entry_entities en
+             JOIN node_log_entries e ON e.id = en.entry_id
+             {}
+             GROUP BY en.entity_kind, en.vhost, en.name
+             ORDER BY entry_count DESC, en.entity_kind ASC, en.name ASC
+             LIMIT {}",
+            where_clause, limit
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let params_slice: Vec<&dyn ToSql> = params.iter().map(|p| p as &dyn ToSql).collect();
+        let rows = stmt.query_map(params_slice.as_slice(), |row| {
+            let kind: String = row.get(0)?;
+            let first_seen: Option<i64> = row.get(5)?;
+            let last_seen: Option<i64> = row.get(6)?;
+            Ok(EntityMentions {
+                kind: EntityKind::from_str(&kind).map_err(|e| {
+                    DuckDbError::FromSqlConversionFailure(0, Type::Text, Box::new(e))
+                })?,
+                vhost: row.get(1)?,
+                name: row.get(2)?,
+                entry_count: row.get(3)?,
+                node_count: row.get(4)?,
+                first_seen: first_seen.and_then(DateTime::from_timestamp_micros),
+                last_seen: last_seen.and_then(DateTime::from_timestamp_micros),
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    /// Extracts entities of entries stored by versions that predate `node_log_entry_entities`.
+    /// Returns the number of entries that were processed.
+    pub(crate) fn backfill(conn: &Connection) -> Result<usize, DuckDbError> {
+        let mut stmt =
+            conn.prepare("SELECT id, message, vhost, username, connection FROM node_log_entries")?;
+        let rows = stmt
+            .query_map([], |row| {
+                let id: i64 = row.get(0)?;
+                let message: String = row.get(1)?;
+                let metadata = StructuredMetadata {
+                    vhost: row.get(2)?,
+                    user: row.get(3)?,
+                    connection: row.get(4)?,
+                    ..StructuredMetadata::default()
+                };
+                Ok((id, message, metadata))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        let mut appender = conn.appender("node_log_entry_entities")?;
+        for (id, message, metadata) in &rows {
+            Self::append(&mut appender, *id, message, metadata)?;
+        }
+        appender.flush()?;
+
+        Ok(rows.len())
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage of entry labels in the `node_log_entry_labels` side table.
+//!
+//! Each row associates an entry id with a label id (an index into [`LABEL_NAMES`]),
+//! so the number of labels is not limited by the width of an integer column.
+//!
+//! [`LABEL_NAMES`]: crate::entry_metadata::labels::LABEL_NAMES
+
+use crate::entry_metadata::labels::LogEntryLabels;
+use crate::rel_db::sql_condition::{SqlCondition, SqlParam};
+use duckdb::{Appender, Error as DuckDbError, params};
+
+pub struct NodeLogEntryLabels;
+
+/// A correlated subquery that returns the label ids of the `node_log_entries` row
+/// aliased as `entry_alias` as a comma-separated list, see [`LogEntryLabels::from_id_list`].
+pub(crate) fn label_ids_subquery(entry_alias: &str) -> String {
+    format!(
+        "(SELECT string_agg(CAST(l.label_id AS VARCHAR), ',' ORDER BY l.label_id) FROM node_log_entry_labels l WHERE l.entry_id = {}.id)",
+        entry_alias
+    )
+}
+
+/// Joins a comma-separated `labels` column onto `node_log_entries`
+/// for queries that group or deduplicate by labels.
+pub(crate) const LABELS_JOIN: &str = "LEFT JOIN (SELECT entry_id, string_agg(CAST(label_id AS VARCHAR), ',' ORDER BY label_id) AS labels FROM node_log_entry_labels GROUP BY entry_id) entry_labels ON entry_labels.entry_id = node_log_entries.id";
+
+impl NodeLogEntryLabels {
+    /// A SQL condition on `node_log_entries` that matches entries with at least one
+    /// of the given labels. Returns `None` for an empty label set.
+    pub fn any_condition(labels: LogEntryLabels) -> Option<SqlCondition> {
+        let (placeholders, params) = id_params(labels)?;
+        Some(SqlCondition::new(
+            format!(
+                "id IN (SELECT entry_id FROM node_log_entry_labels WHERE label_id IN ({}))",
+                placeholders
+            ),
+            params,
+        ))
+    }
+
+    /// A SQL condition on `node_log_entries` that matches entries with all
+    /// of the given labels. Returns `None` for an empty label set.
+    pub fn all_condition(labels: LogEntryLabels) -> Option<SqlCondition> {
+        let (placeholders, mut params) = id_params(labels)?;
+        params.push(SqlParam::Integer(labels.len() as i64));
+        Some(SqlCondition::new(
+            format!(
+                "id IN (SELECT entry_id FROM node_log_entry_labels WHERE label_id IN ({}) GROUP BY entry_id HAVING COUNT(DISTINCT label_id) = ?)",
+                placeholders
+            ),
+            params,
+        ))
+    }
+
+    pub(crate) fn append(
+        appender: &mut Appender<'_>,
+        entry_id: i64,
+        labels: LogEntryLabels,
+    ) -> Result<(), DuckDbError> {
+        for label_id in labels.ids() {
+            appender.append_row(params![entry_id, label_id as i16])?;
+        }
+        Ok(())
+    }
+
+    /// Copies labels of databases created before labels moved to `node_log_entry_labels`,
+    /// where they were stored as a `BIGINT` bitmask in `node_log_entries.labels`.
+    /// Executed by the schema migration before the bitmask column is dropped.
+    pub(crate) const LEGACY_BITMASK_MIGRATION: &str = "
+        INSERT INTO node_log_entry_labels (entry_id, label_id)
+            SELECT e.id, CAST(r.label_id AS SMALLINT)
+            FROM node_log_entries e, range(0, 64) AS r(label_id)
+            WHERE ((e.labels >> r.label_id) & 1) = 1;
+    ";
+}
+
+/// A placeholder list and the matching parameters for the ids of `labels`
+fn id_params(labels: LogEntryLabels) -> Option<(String, Vec<SqlParam>)> {
+    if labels.is_empty() {
+        return None;
+    }
+    let params: Vec<SqlParam> = labels
+        .ids()
+        .map(|id| SqlParam::Integer(i64::from(id)))
+        .collect();
+    Some((vec!["?"; params.len()].join(", "), params))
+}
@@ -0,0 +1,203 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Obfuscation of an entire database, e.g. one that has to be shared with a third party.
+//!
+//! Entries are copied from a source database into a fresh target database with
+//! their `node`, `erlang_pid`, `message`, `domain`, `mfa`, `source_file`, `connection`,
+//! `vhost` and `username` columns passed through a single [`LogObfuscator`], so a hostname,
+//! queue or user gets the same alias in every column and every file. Documentation and
+//! issue URLs, including those of custom annotations, are public and copied as is. Entities and message patterns
+//! are derived from the obfuscated values, file metadata paths and node lists
+//! are obfuscated with the same mapping. Node artifacts such as `rabbitmq.conf`
+//! have their secret settings redacted before the rest of their content is obfuscated.
+//! The message search index is never copied: when the source has one, it is rebuilt
+//! from the obfuscated messages.
+//!
+//! Writing a fresh database rather than updating the source one in place
+//! ensures that no original values remain in the free blocks of the file.
+
+use crate::entry_metadata::patterns::MessagePattern;
+use crate::parser::StructuredMetadata;
+use crate::rel_db::file_metadata::FileMetadata;
+use crate::rel_db::message_patterns::MessagePatterns;
+use crate::rel_db::message_search_index::MessageSearchIndex;
+use crate::rel_db::node_artifacts::{ArtifactKind, NodeArtifacts, redact_secret_settings};
+use crate::rel_db::node_log_entry::NodeLogEntry;
+use crate::rel_db::node_log_entry_custom_annotations::{
+    DOC_URL_KIND, RESOLUTION_OR_DISCUSSION_URL_KIND,
+};
+use crate::rel_db::node_log_entry_entities::NodeLogEntryEntities;
+use crate::rel_db::{DatabaseConnection, post_insertion_operations};
+use duckdb::types::{TimeUnit, Value};
+use duckdb::{Error as DuckDbError, params};
+use rabbitmq_lqt_obfuscation::LogObfuscator;
+use std::collections::HashMap;
+use std::io::Error as IoError;
+
+/// Copies the contents of `source` into `target`, which must be a freshly created database,
+/// obfuscating everything that can identify the cluster or its users.
+///
+/// Returns the number of copied entries.
+pub fn obfuscate_database(
+    source: &DatabaseConnection,
+    target: &DatabaseConnection,
+    obfuscator: &mut LogObfuscator,
+) -> Result<u64, DuckDbError> {
+    // Node names with fully qualified hostnames look like email addresses
+    for (node, _) in NodeLogEntry::get_node_counts(source)? {
+        obfuscator.add_node_name(&node);
+    }
+
+    let source_conn = source.get().map_err(|e| {
+        DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+    })?;
+    let target_conn = target.get().map_err(|e| {
+        DuckDbError::ToSqlConversionFailure(Box::new(IoError::other(e.to_string())))
+    })?;
+
+    let mut entries = target_conn.appender("node_log_entries")?;
+    let mut entities = target_conn.appender("node_log_entry_entities")?;
+    let mut templates = HashMap::new();
+    let mut entry_count = 0u64;
+
+    let mut stmt = source_conn.prepare(
+        "SELECT id, node, timestamp, severity, erlang_pid, subsystem_id, message,
+                resolution_or_discussion_url_id, doc_url_id, domain, mfa, source_file, source_line,
+                connection, vhost, username
+         FROM node_log_entries
+         ORDER BY id",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let node: String = row.get(1)?;
+        let timestamp_micros: i64 = row.get(2)?;
+        let erlang_pid: String = row.get(4)?;
+        let message: String = row.get(6)?;
+        let domain: Option<String> = row.get(9)?;
+        let mfa: Option<String> = row.get(10)?;
+        let source_file: Option<String> = row.get(11)?;
+        let connection: Option<String> = row.get(13)?;
+        let vhost: Option<String> = row.get(14)?;
+        let username: Option<String> = row.get(15)?;
+
+        let message = obfuscator.obfuscate_line(&message);
+        let metadata = StructuredMetadata {
+            connection: connection.map(|c| obfuscator.obfuscate_line(&c)),
+            vhost: vhost.map(|v| obfuscator.obfuscate_vhost(&v)),
+            user: username.map(|u| obfuscator.obfuscate_username(&u)),
+            ..StructuredMetadata::default()
+        };
+        let pattern = MessagePattern::of(&message);
+
+        entries.append_row(params![
+            id,
+            obfuscator.obfuscate_line(&node),
+            Value::Timestamp(TimeUnit::Microsecond, timestamp_micros),
+            row.get::<_, i8>(3)?,
+            obfuscator.obfuscate_line(&erlang_pid),
+            row.get::<_, Option<i16>>(5)?,
+            message,
+            row.get::<_, Option<i16>>(7)?,
+            row.get::<_, Option<i16>>(8)?,
+            domain.map(|d| obfuscator.obfuscate_line(&d)),
+            mfa.map(|m| obfuscator.obfuscate_line(&m)),
+            source_file.map(|f| obfuscator.obfuscate_file_path(&f)),
+            row.get::<_, Option<i32>>(12)?,
+            metadata.connection,
+            metadata.vhost,
+            metadata.user,
+            pattern.id,
+        ])?;
+        templates.entry(pattern.id).or_insert(pattern.template);
+        NodeLogEntryEntities::append(&mut entities, id, &message, &metadata)?;
+        entry_count += 1;
+    }
+    drop(rows);
+    drop(stmt);
+    entries.flush()?;
+    entities.flush()?;
+    drop(entries);
+    drop(entities);
+    MessagePatterns::insert_templates(&target_conn, &templates)?;
+
+    // Labels are names of built-in categories and are copied as is
+    let mut labels = target_conn.appender("node_log_entry_labels")?;
+    let mut stmt = source_conn.prepare("SELECT entry_id, label_id FROM node_log_entry_labels")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        labels.append_row(params![row.get::<_, i64>(0)?, row.get::<_, i16>(1)?])?;
+    }
+    drop(rows);
+    drop(stmt);
+    labels.flush()?;
+    drop(labels);
+
+    // Custom labels can be captured from messages by user-defined rules,
+    // URLs come from the rules themselves
+    let mut custom_annotations = target_conn.appender("node_log_entry_custom_annotations")?;
+    let mut stmt = source_conn
+        .prepare("SELECT entry_id, kind, value FROM node_log_entry_custom_annotations")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let kind: String = row.get(1)?;
+        let value: String = row.get(2)?;
+        let value = if kind == DOC_URL_KIND || kind == RESOLUTION_OR_DISCUSSION_URL_KIND {
+            value
+        } else {
+            obfuscator.obfuscate_line(&value)
+        };
+        custom_annotations.append_row(params![row.get::<_, i64>(0)?, kind, value])?;
+    }
+    drop(rows);
+    drop(stmt);
+    custom_annotations.flush()?;
+    drop(custom_annotations);
+
+    drop(source_conn);
+    drop(target_conn);
+
+    for mut metadata in FileMetadata::find_all(source)? {
+        metadata.file_path = obfuscator.obfuscate_file_path(&metadata.file_path);
+        metadata.nodes = metadata
+            .nodes
+            .iter()
+            .map(|node| obfuscator.obfuscate_line(node))
+            .collect();
+        FileMetadata::insert_metadata(target, metadata)?;
+    }
+
+    for mut artifact in NodeArtifacts::find_all(source)? {
+        if artifact.kind == ArtifactKind::RabbitmqConf {
+            artifact.content = redact_secret_settings(&artifact.content);
+        }
+        artifact.node = obfuscator.obfuscate_line(&artifact.node);
+        artifact.file_path = obfuscator.obfuscate_file_path(&artifact.file_path);
+        artifact.content = artifact
+            .content
+            .lines()
+            .map(|line| obfuscator.obfuscate_line(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        NodeArtifacts::insert(target, &artifact)?;
+    }
+
+    post_insertion_operations(target)?;
+    if MessageSearchIndex::exists(source)? {
+        MessageSearchIndex::build(target)?;
+    }
+
+    Ok(entry_count)
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 use crate::entry_metadata::labels::LogEntryLabels;
 use crate::rel_db::node_log_entry::QueryContext;
+use crate::severity::Severity;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
@@ -43,9 +44,9 @@ impl FromStr for QueryPreset {
 }
 
 impl QueryPreset {
-    pub fn severity(&self) -> Option<&'static str> {
+    pub fn severity(&self) -> Option<Severity> {
         match self {
-            QueryPreset::ErrorsOrCrashes => Some("error"),
+            QueryPreset::ErrorsOrCrashes => Some(Severity::Error),
         }
     }
 
@@ -0,0 +1,150 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ordering of `node_log_entries` query results.
+
+use crate::entry_metadata::subsystems::Subsystem;
+use crate::rel_db::aggregation::LogColumn;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A column to order query results by, see [`QueryContext::sort_by`](crate::QueryContext::sort_by).
+///
+/// Severities are ordered by rank (`debug` < `info` < … < `critical`), as that is how
+/// they are stored, and subsystems by name. Columns joined in from other tables (labels, patterns and entities)
+/// cannot be sorted by, see [`LogColumn::is_sortable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SortKey {
+    pub column: LogColumn,
+    pub descending: bool,
+}
+
+impl SortKey {
+    pub fn ascending(column: LogColumn) -> Self {
+        Self {
+            column,
+            descending: false,
+        }
+    }
+
+    pub fn descending(column: LogColumn) -> Self {
+        Self {
+            column,
+            descending: true,
+        }
+    }
+
+    #[must_use]
+    pub fn reversed(self) -> Self {
+        Self {
+            column: self.column,
+            descending: !self.descending,
+        }
+    }
+
+    /// Parses a comma-separated list of keys, e.g. `node,severity:desc`
+    pub fn parse_list(s: &str) -> Result<Vec<Self>, String> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(Self::from_str)
+            .collect()
+    }
+
+    /// The SQL expression this key orders by, `None` for columns that cannot be sorted by
+    pub(crate) fn sql_expression(&self) -> Option<String> {
+        if !self.column.is_sortable() {
+            return None;
+        }
+        Some(match self.column {
+            LogColumn::Subsystem => subsystem_rank_expression(),
+            column => column.sql_column().to_string(),
+        })
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    /// Parses `column`, `column:asc` or `column:desc`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, direction) = s.split_once(':').unwrap_or((s, "asc"));
+        let column = LogColumn::from_name(name.trim())
+            .ok_or_else(|| format!("Unknown sort column: '{}'", name.trim()))?;
+        if !column.is_sortable() {
+            return Err(format!("Cannot sort by '{}'", column.name()));
+        }
+        match direction.trim().to_lowercase().as_str() {
+            "asc" => Ok(Self::ascending(column)),
+            "desc" => Ok(Self::descending(column)),
+            other => Err(format!(
+                "Invalid sort direction: '{}', expected 'asc' or 'desc'",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = if self.descending { "desc" } else { "asc" };
+        write!(f, "{}:{}", self.column.name(), direction)
+    }
+}
+
+/// Builds an `ORDER BY` list for `keys`, with `reversed` flipping every direction.
+pub(crate) fn order_by_list(keys: &[SortKey], reversed: bool) -> String {
+    order_by_items(keys, reversed)
+        .iter()
+        .map(|(expression, direction)| format!("{} {}", expression, direction))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The expressions and directions (`ASC` or `DESC`) to order by for `keys`.
+///
+/// Ties are broken by entry id (unless it already is one of the keys), so that
+/// results, and the pages of results, are stable between queries.
+pub(crate) fn order_by_items(keys: &[SortKey], reversed: bool) -> Vec<(String, &'static str)> {
+    let direction = |descending: bool| {
+        if descending != reversed {
+            "DESC"
+        } else {
+            "ASC"
+        }
+    };
+    let mut items: Vec<(String, &'static str)> = keys
+        .iter()
+        .filter_map(|key| {
+            key.sql_expression()
+                .map(|expression| (expression, direction(key.descending)))
+        })
+        .collect();
+    if !keys.iter().any(|key| key.column == LogColumn::Id) {
+        items.push(("id".to_string(), direction(false)));
+    }
+    items
+}
+
+fn subsystem_rank_expression() -> String {
+    let mut subsystems: Vec<Subsystem> = (1..).map_while(Subsystem::from_id).collect();
+    subsystems.sort_by_key(|subsystem| subsystem.to_string());
+    let branches: Vec<String> = subsystems
+        .iter()
+        .enumerate()
+        .map(|(rank, subsystem)| format!("WHEN {} THEN {}", subsystem.to_id(), rank))
+        .collect();
+    format!("CASE subsystem_id {} END", branches.join(" "))
+}
@@ -0,0 +1,119 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parameterized SQL conditions on `node_log_entries`.
+//!
+//! A [`SqlCondition`] keeps the SQL text of a condition apart from the values it
+//! compares against. The values are bound to `?` placeholders when the query is executed,
+//! so they never become part of the SQL text and do not have to be escaped.
+
+use chrono::{DateTime, Utc};
+use duckdb::types::{TimeUnit, Value};
+
+/// A value bound to a `?` placeholder of a [`SqlCondition`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlParam {
+    Text(String),
+    Integer(i64),
+    Boolean(bool),
+    Timestamp(DateTime<Utc>),
+    Null,
+}
+
+impl SqlParam {
+    pub(crate) fn to_value(&self) -> Value {
+        match self {
+            SqlParam::Text(s) => Value::Text(s.clone()),
+            SqlParam::Integer(n) => Value::BigInt(*n),
+            SqlParam::Boolean(b) => Value::Boolean(*b),
+            SqlParam::Timestamp(ts) => {
+                Value::Timestamp(TimeUnit::Microsecond, ts.timestamp_micros())
+            }
+            SqlParam::Null => Value::Null,
+        }
+    }
+}
+
+/// A boolean SQL expression on `node_log_entries` with a `?` placeholder for each
+/// of its parameters, in order.
+///
+/// The SQL text must only be assembled from trusted parts: column names and
+/// conditions generated by this crate. Anything that comes from users belongs in the parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlCondition {
+    sql: String,
+    params: Vec<SqlParam>,
+}
+
+impl SqlCondition {
+    /// A condition with one `?` placeholder in `sql` per parameter
+    pub fn new(sql: impl Into<String>, params: Vec<SqlParam>) -> Self {
+        let sql = sql.into();
+        debug_assert_eq!(
+            sql.matches('?').count(),
+            params.len(),
+            "every parameter needs a placeholder: {}",
+            sql
+        );
+        Self { sql, params }
+    }
+
+    /// A condition without parameters
+    pub fn trusted(sql: impl Into<String>) -> Self {
+        Self::new(sql, Vec::new())
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    pub fn params(&self) -> &[SqlParam] {
+        &self.params
+    }
+
+    /// Wraps the SQL text, e.g. into a subquery, keeping the parameters.
+    /// `wrap` must not add placeholders.
+    #[must_use]
+    pub fn map_sql(self, wrap: impl FnOnce(&str) -> String) -> Self {
+        Self::new(wrap(&self.sql), self.params)
+    }
+
+    #[must_use]
+    pub fn and(self, other: SqlCondition) -> Self {
+        self.combine(" AND ", other)
+    }
+
+    #[must_use]
+    pub fn or(self, other: SqlCondition) -> Self {
+        self.combine(" OR ", other)
+    }
+
+    #[must_use]
+    pub fn negate(self) -> Self {
+        self.map_sql(|sql| format!("NOT ({})", sql))
+    }
+
+    #[must_use]
+    pub fn grouped(self) -> Self {
+        self.map_sql(|sql| format!("({})", sql))
+    }
+
+    fn combine(mut self, operator: &str, other: SqlCondition) -> Self {
+        self.params.extend(other.params);
+        Self::new(
+            format!("({}{}{})", self.sql, operator, other.sql),
+            self.params,
+        )
+    }
+}
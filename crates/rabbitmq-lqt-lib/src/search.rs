@@ -0,0 +1,124 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Full-text search queries over log messages.
+//!
+//! Messages and queries are split into terms: lowercased runs of ASCII letters,
+//! digits and underscores. A query matches the messages that contain all of its clauses:
+//!
+//! * `timeout` matches the term `timeout`
+//! * `conn*` matches any term that starts with `conn`
+//! * `"connection closed"` matches the two terms next to each other, in this order.
+//!   Words that consist of several terms, such as `tls-handshake`, are phrases as well
+//!
+//! See [`crate::rel_db::MessageSearchIndex`] for how queries are executed.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    clauses: Vec<SearchClause>,
+}
+
+/// Terms that must appear next to each other. With `prefix`, the last
+/// term only has to be the start of a term in the message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchClause {
+    pub terms: Vec<String>,
+    pub prefix: bool,
+}
+
+impl SearchQuery {
+    /// Parses a query. Parts without any terms, such as punctuation, are ignored.
+    pub fn parse(text: &str) -> Self {
+        let mut clauses = Vec::new();
+        let mut chars = text.chars().peekable();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+                continue;
+            }
+            let part: String = if c == '"' {
+                chars.next();
+                chars.by_ref().take_while(|&c| c != '"').collect()
+            } else {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                word
+            };
+
+            let terms = tokenize(&part);
+            if !terms.is_empty() {
+                clauses.push(SearchClause {
+                    terms,
+                    prefix: part.trim_end().ends_with('*'),
+                });
+            }
+        }
+        Self { clauses }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    pub fn clauses(&self) -> &[SearchClause] {
+        &self.clauses
+    }
+
+    /// A query that matches the messages both queries match
+    #[must_use]
+    pub fn and(mut self, other: SearchQuery) -> Self {
+        self.clauses.extend(other.clauses);
+        self
+    }
+}
+
+impl fmt::Display for SearchQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let clauses: Vec<_> = self.clauses.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", clauses.join(" "))
+    }
+}
+
+impl fmt::Display for SearchClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let star = if self.prefix { "*" } else { "" };
+        if self.terms.len() == 1 {
+            write!(f, "{}{}", self.terms[0], star)
+        } else {
+            write!(f, "\"{}{}\"", self.terms.join(" "), star)
+        }
+    }
+}
+
+/// Splits text into the terms the search index is made of
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !is_term_char(c))
+        .filter(|term| !term.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Matches the `[a-z0-9_]` class the index is built with in SQL
+pub(crate) fn is_term_char(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'
+}
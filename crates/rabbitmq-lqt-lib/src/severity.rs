@@ -42,6 +42,20 @@ impl Severity {
         }
     }
 
+    /// The rank of this severity, stored in the `severity` column of `node_log_entries`.
+    /// Higher ranks are more severe.
+    #[inline]
+    pub fn to_ordinal(self) -> i8 {
+        self as i8
+    }
+
+    #[inline]
+    pub fn from_ordinal(ordinal: i8) -> Option<Self> {
+        usize::try_from(ordinal)
+            .ok()
+            .and_then(|i| Self::all().get(i).copied())
+    }
+
     pub const fn all() -> [Self; 6] {
         [
             Self::Debug,
@@ -0,0 +1,375 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A cross-node, chronological view of a log set, see [`Timeline`].
+//!
+//! Entries of all nodes are merged in timestamp order. Bursts of near-identical
+//! messages logged by a node are collapsed into a single event, and cluster-level
+//! transitions such as node boots, shutdowns, Raft leader changes, network partitions,
+//! resource alarms and maintenance mode are highlighted.
+
+use crate::entry_metadata::labels::LogEntryLabels;
+use crate::entry_metadata::patterns::message_template;
+use crate::rel_db::node_log_entry::{Model, NodeLogEntry, QueryContext};
+use crate::rel_db::node_log_entry_labels::NodeLogEntryLabels;
+use crate::rel_db::sort_order::SortKey;
+use crate::rel_db::sql_condition::{SqlCondition, SqlParam};
+use crate::rel_db::{DatabaseConnection, LogColumn};
+use chrono::{DateTime, TimeDelta, Utc};
+use duckdb::Error as DuckDbError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use strum::{Display, EnumString};
+
+/// Entries logged by a node within this many seconds of each other
+/// are considered a burst if their messages share a template, see [`message_template`].
+pub const DEFAULT_BURST_WINDOW_SECS: u64 = 5;
+
+/// The number of entries a timeline query reads at a time.
+pub const TIMELINE_PAGE_SIZE: u64 = 10_000;
+
+/// A cluster-level state change that a timeline highlights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Transition {
+    NodeBoot,
+    NodeShutdown,
+    LeaderChange,
+    Partition,
+    Alarm,
+    MaintenanceMode,
+}
+
+impl Transition {
+    /// Classifies an entry using its labels and, where a label is too broad, its message.
+    pub fn classify(labels: LogEntryLabels, message: &str) -> Option<Self> {
+        let msg_lower = message.to_lowercase();
+
+        if labels.contains(LogEntryLabels::STARTUP_BANNER)
+            || msg_lower.starts_with("server startup complete")
+        {
+            return Some(Transition::NodeBoot);
+        }
+
+        if labels.contains(LogEntryLabels::SHUTDOWN)
+            && (msg_lower.starts_with("rabbitmq is asked to stop")
+                || msg_lower.starts_with("successfully stopped rabbitmq"))
+        {
+            return Some(Transition::NodeShutdown);
+        }
+
+        if msg_lower.contains("partition")
+            && labels.contains(LogEntryLabels::CLUSTERING | LogEntryLabels::NETWORKING)
+        {
+            return Some(Transition::Partition);
+        }
+
+        if labels.contains(LogEntryLabels::LIMITS) && msg_lower.contains("alarm") {
+            return Some(Transition::Alarm);
+        }
+
+        if labels.contains(LogEntryLabels::MAINTENANCE_MODE) {
+            return Some(Transition::MaintenanceMode);
+        }
+
+        // Quorum queue and stream leader changes are only labelled as Raft events
+        if labels.intersects(LogEntryLabels::ELECTIONS | LogEntryLabels::RAFT)
+            && (msg_lower.contains("candidate -> leader in term")
+                || msg_lower.contains("leadership transfer")
+                || (msg_lower.starts_with("rabbitmq metadata store:")
+                    && msg_lower.contains("leader")))
+        {
+            return Some(Transition::LeaderChange);
+        }
+
+        None
+    }
+
+    /// A SQL condition on `node_log_entries` that matches the entries
+    /// [`classify`](Self::classify) considers a transition of any kind.
+    pub fn any_condition() -> SqlCondition {
+        let message_starts_with = |prefix: &str| {
+            SqlCondition::new(
+                "message ILIKE ?",
+                vec![SqlParam::Text(format!("{}%", prefix))],
+            )
+        };
+        let message_contains = |infix: &str| {
+            SqlCondition::new(
+                "message ILIKE ?",
+                vec![SqlParam::Text(format!("%{}%", infix))],
+            )
+        };
+        let any_label =
+            |labels| NodeLogEntryLabels::any_condition(labels).expect("the label set is not empty");
+        let all_labels =
+            |labels| NodeLogEntryLabels::all_condition(labels).expect("the label set is not empty");
+
+        let node_boot = any_label(LogEntryLabels::STARTUP_BANNER)
+            .or(message_starts_with("server startup complete"));
+        let node_shutdown = all_labels(LogEntryLabels::SHUTDOWN).and(
+            message_starts_with("rabbitmq is asked to stop")
+                .or(message_starts_with("successfully stopped rabbitmq")),
+        );
+        let partition = message_contains("partition").and(all_labels(
+            LogEntryLabels::CLUSTERING | LogEntryLabels::NETWORKING,
+        ));
+        let alarm = all_labels(LogEntryLabels::LIMITS).and(message_contains("alarm"));
+        let maintenance_mode = any_label(LogEntryLabels::MAINTENANCE_MODE);
+        let leader_change =
+            any_label(LogEntryLabels::ELECTIONS | LogEntryLabels::RAFT).and(
+                message_contains("candidate -> leader in term")
+                    .or(message_contains("leadership transfer"))
+                    .or(message_starts_with("rabbitmq metadata store:")
+                        .and(message_contains("leader"))),
+            );
+
+        [
+            node_boot,
+            node_shutdown,
+            partition,
+            alarm,
+            maintenance_mode,
+            leader_change,
+        ]
+        .into_iter()
+        .reduce(SqlCondition::or)
+        .expect("there are transitions")
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Transition::NodeBoot => "node boot",
+            Transition::NodeShutdown => "node shutdown",
+            Transition::LeaderChange => "leader change",
+            Transition::Partition => "network partition",
+            Transition::Alarm => "resource alarm",
+            Transition::MaintenanceMode => "maintenance mode",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimelineOptions {
+    /// Near-identical messages a node logs within this window are collapsed.
+    /// A zero window disables collapsing.
+    pub burst_window: TimeDelta,
+    /// Only include events that are cluster-level transitions.
+    pub transitions_only: bool,
+}
+
+impl Default for TimelineOptions {
+    fn default() -> Self {
+        Self {
+            burst_window: TimeDelta::seconds(DEFAULT_BURST_WINDOW_SECS as i64),
+            transitions_only: false,
+        }
+    }
+}
+
+impl TimelineOptions {
+    #[must_use]
+    pub fn burst_window(mut self, window: TimeDelta) -> Self {
+        self.burst_window = window;
+        self
+    }
+
+    #[must_use]
+    pub fn transitions_only(mut self, only: bool) -> Self {
+        self.transitions_only = only;
+        self
+    }
+}
+
+/// An entry, or a burst of near-identical entries, logged by a node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    pub node: String,
+    /// The id of the first entry of the event
+    pub entry_id: i64,
+    pub timestamp: DateTime<Utc>,
+    /// The timestamp of the last entry of a burst, same as `timestamp` for single entries
+    pub last_timestamp: DateTime<Utc>,
+    pub severity: String,
+    /// The message of the first entry of the event
+    pub message: String,
+    /// The number of entries collapsed into this event
+    pub count: usize,
+    pub transition: Option<Transition>,
+}
+
+impl TimelineEvent {
+    #[inline]
+    pub fn is_burst(&self) -> bool {
+        self.count > 1
+    }
+
+    /// The first line of the message, for compact display.
+    pub fn summary(&self) -> &str {
+        self.message.lines().next().unwrap_or_default().trim()
+    }
+}
+
+/// A chronologically ordered view of the entries of all nodes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timeline {
+    /// Nodes that have at least one event, sorted by name
+    pub nodes: Vec<String>,
+    pub events: Vec<TimelineEvent>,
+}
+
+impl Timeline {
+    /// Builds a timeline of the entries that match `ctx`.
+    ///
+    /// Entries are read in pages of [`TIMELINE_PAGE_SIZE`], so all matching entries
+    /// are included unless `ctx` sets a limit. With `transitions_only`,
+    /// other entries are filtered out by the database.
+    pub fn query(
+        db: &DatabaseConnection,
+        ctx: &QueryContext,
+        options: &TimelineOptions,
+    ) -> Result<Self, DuckDbError> {
+        let mut ctx = ctx.clone().order_by(vec![
+            SortKey::ascending(LogColumn::Timestamp),
+            SortKey::ascending(LogColumn::Id),
+        ]);
+        if options.transitions_only {
+            ctx = ctx.where_condition(Transition::any_condition());
+        }
+
+        let mut builder = TimelineBuilder::new(options);
+        let mut remaining = ctx.limit;
+        let mut last: Option<(DateTime<Utc>, i64)> = None;
+        loop {
+            let page_size = remaining.map_or(TIMELINE_PAGE_SIZE, |n| n.min(TIMELINE_PAGE_SIZE));
+            if page_size == 0 {
+                break;
+            }
+            let mut page = ctx.clone().limit(page_size);
+            if let Some((timestamp, id)) = last {
+                page = page.where_condition(SqlCondition::new(
+                    "(timestamp > ? OR (timestamp = ? AND id > ?))",
+                    vec![
+                        SqlParam::Timestamp(timestamp),
+                        SqlParam::Timestamp(timestamp),
+                        SqlParam::Integer(id),
+                    ],
+                ));
+            }
+
+            let entries = NodeLogEntry::query(db, &page)?;
+            let read = entries.len() as u64;
+            last = entries.last().map(|e| (e.timestamp, e.id));
+            for entry in entries {
+                builder.push(entry);
+            }
+            remaining = remaining.map(|n| n - read);
+            if read < page_size {
+                break;
+            }
+        }
+
+        Ok(builder.finish())
+    }
+
+    pub fn build(mut entries: Vec<Model>, options: &TimelineOptions) -> Self {
+        entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then(a.id.cmp(&b.id)));
+
+        let mut builder = TimelineBuilder::new(options);
+        for entry in entries {
+            builder.push(entry);
+        }
+        builder.finish()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn transitions(&self) -> impl Iterator<Item = &TimelineEvent> {
+        self.events.iter().filter(|e| e.transition.is_some())
+    }
+}
+
+/// Collapses entries, fed in timestamp order, into events.
+struct TimelineBuilder<'a> {
+    options: &'a TimelineOptions,
+    events: Vec<TimelineEvent>,
+    /// The message template of each event
+    templates: Vec<String>,
+    /// The index of the most recent event of each node
+    last_event_of_node: HashMap<String, usize>,
+}
+
+impl<'a> TimelineBuilder<'a> {
+    fn new(options: &'a TimelineOptions) -> Self {
+        Self {
+            options,
+            events: Vec::new(),
+            templates: Vec::new(),
+            last_event_of_node: HashMap::new(),
+        }
+    }
+
+    fn push(&mut self, entry: Model) {
+        let transition = Transition::classify(entry.labels, &entry.message);
+        if self.options.transitions_only && transition.is_none() {
+            return;
+        }
+        let template = message_template(&entry.message);
+
+        if let Some(&idx) = self.last_event_of_node.get(&entry.node) {
+            let last = &mut self.events[idx];
+            if last.severity == entry.severity
+                && last.transition == transition
+                && self.templates[idx] == template
+                && entry.timestamp - last.last_timestamp <= self.options.burst_window
+            {
+                last.count += 1;
+                last.last_timestamp = entry.timestamp;
+                return;
+            }
+        }
+
+        self.last_event_of_node
+            .insert(entry.node.clone(), self.events.len());
+        self.templates.push(template);
+        self.events.push(TimelineEvent {
+            node: entry.node,
+            entry_id: entry.id,
+            timestamp: entry.timestamp,
+            last_timestamp: entry.timestamp,
+            severity: entry.severity,
+            message: entry.message,
+            count: 1,
+            transition,
+        });
+    }
+
+    fn finish(self) -> Timeline {
+        let nodes = self
+            .events
+            .iter()
+            .map(|e| e.node.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        Timeline {
+            nodes,
+            events: self.events,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::test_helpers::{create_test_entry, create_test_entry_with_subsystem};
+use rabbitmq_lqt_lib::entry_metadata::labels::LogEntryLabels;
+use rabbitmq_lqt_lib::{
+    Aggregation, DatabaseConnection, LogColumn, NodeLogEntry, QueryContext, Severity, Subsystem,
+    create_database,
+};
+use serde_json::json;
+use tempfile::TempDir;
+
+fn setup_db() -> (TempDir, DatabaseConnection) {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+
+    let mut crash = create_test_entry("process crashed", Severity::Error);
+    crash.labels = LogEntryLabels::ERL_PROCESS_CRASH;
+
+    let node1 = vec![
+        create_test_entry("first", Severity::Info),
+        create_test_entry("second", Severity::Info),
+        create_test_entry_with_subsystem("refused", Severity::Warning, Subsystem::Connections),
+        crash,
+    ];
+    let node2 = vec![
+        create_test_entry("third", Severity::Info),
+        create_test_entry_with_subsystem("refused", Severity::Warning, Subsystem::Connections),
+    ];
+
+    NodeLogEntry::insert_parsed_entries(&db, &node1, "rabbit@node1").unwrap();
+    NodeLogEntry::insert_parsed_entries(&db, &node2, "rabbit@node2").unwrap();
+
+    (temp_dir, db)
+}
+
+#[test]
+fn test_count_without_field() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default().aggregation(Aggregation::CountBy(None));
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+
+    assert_eq!(result.columns, vec!["count"]);
+    assert_eq!(result.rows, vec![vec![json!(6)]]);
+}
+
+#[test]
+fn test_count_by_severity_orders_by_count() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default().aggregation(Aggregation::CountBy(Some(LogColumn::Severity)));
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+
+    assert_eq!(result.columns, vec!["severity", "count"]);
+    assert_eq!(
+        result.rows,
+        vec![
+            vec![json!("info"), json!(3)],
+            vec![json!("warning"), json!(2)],
+            vec![json!("error"), json!(1)],
+        ]
+    );
+}
+
+#[test]
+fn test_count_by_respects_filters() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default()
+        .severity("warning")
+        .aggregation(Aggregation::CountBy(Some(LogColumn::Node)));
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+
+    assert_eq!(
+        result.rows,
+        vec![
+            vec![json!("rabbit@node1"), json!(1)],
+            vec![json!("rabbit@node2"), json!(1)],
+        ]
+    );
+}
+
+#[test]
+fn test_count_by_subsystem_uses_names() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default()
+        .severity("warning")
+        .aggregation(Aggregation::CountBy(Some(LogColumn::Subsystem)));
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+
+    assert_eq!(result.columns, vec!["subsystem", "count"]);
+    assert_eq!(result.rows, vec![vec![json!("connections"), json!(2)]]);
+}
+
+#[test]
+fn test_distinct_nodes() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default().aggregation(Aggregation::Distinct(vec![LogColumn::Node]));
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+
+    assert_eq!(result.columns, vec!["node"]);
+    assert_eq!(
+        result.rows,
+        vec![vec![json!("rabbit@node1")], vec![json!("rabbit@node2")]]
+    );
+}
+
+#[test]
+fn test_distinct_multiple_columns() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default().aggregation(Aggregation::Distinct(vec![
+        LogColumn::Node,
+        LogColumn::Severity,
+    ]));
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+
+    assert_eq!(result.columns, vec!["node", "severity"]);
+    assert_eq!(result.len(), 5);
+}
+
+#[test]
+fn test_project_selected_columns() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default()
+        .node("rabbit@node1")
+        .limit(2)
+        .aggregation(Aggregation::Project(vec![
+            LogColumn::Id,
+            LogColumn::Message,
+        ]));
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+
+    assert_eq!(result.columns, vec!["id", "message"]);
+    assert_eq!(result.len(), 2);
+    assert_eq!(result.rows[0].len(), 2);
+}
+
+#[test]
+fn test_project_labels_as_names() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default()
+        .severity("error")
+        .aggregation(Aggregation::Project(vec![LogColumn::Labels]));
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+
+    assert_eq!(result.rows, vec![vec![json!(["erl_process_crash"])]]);
+}
+
+#[test]
+fn test_aggregate_without_aggregation_is_empty() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default();
+    assert!(!ctx.has_aggregation());
+
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+    assert!(result.is_empty());
+    assert!(result.columns.is_empty());
+}
@@ -29,7 +29,7 @@ use rabbitmq_lqt_lib::entry_metadata::subsystem_annotators::{
     annotate_subsystems,
 };
 use rabbitmq_lqt_lib::entry_metadata::subsystems::Subsystem;
-use rabbitmq_lqt_lib::{ParsedLogEntry, Severity};
+use rabbitmq_lqt_lib::{ParsedLogEntry, Severity, StructuredMetadata};
 
 #[test]
 fn test_subsystem_display() {
@@ -91,6 +91,8 @@ fn test_metadata_store_annotator_no_match() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = MetadataStoreAnnotator;
@@ -111,6 +113,8 @@ fn test_metadata_store_annotator_annotates() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = MetadataStoreAnnotator;
@@ -133,6 +137,8 @@ fn test_feature_flags_annotator_matches() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = FeatureFlagsAnnotator;
@@ -153,6 +159,8 @@ fn test_feature_flags_annotator_no_match() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = FeatureFlagsAnnotator;
@@ -173,6 +181,8 @@ fn test_feature_flags_annotator_annotates() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = FeatureFlagsAnnotator;
@@ -196,6 +206,8 @@ fn test_annotate_entry_with_metadata_store() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     annotate_subsystems(&mut entry);
@@ -217,6 +229,8 @@ fn test_annotate_entry_with_feature_flags() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     annotate_subsystems(&mut entry);
@@ -238,6 +252,8 @@ fn test_annotate_entry_no_match() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     annotate_subsystems(&mut entry);
@@ -259,6 +275,8 @@ fn test_metadata_store_annotator_mnesia_khepri_data_copy() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = MetadataStoreAnnotator;
@@ -279,6 +297,8 @@ fn test_metadata_store_annotator_syncing_mnesia_khepri() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = MetadataStoreAnnotator;
@@ -299,6 +319,8 @@ fn test_boot_annotator_applying_mfa() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = BootAnnotator;
@@ -319,6 +341,8 @@ fn test_boot_annotator_finished_mfa() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = BootAnnotator;
@@ -339,6 +363,8 @@ fn test_boot_annotator_running_boot_step() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = BootAnnotator;
@@ -359,6 +385,8 @@ fn test_boot_annotator_no_match() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = BootAnnotator;
@@ -379,6 +407,8 @@ fn test_boot_annotator_annotates() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = BootAnnotator;
@@ -401,6 +431,8 @@ fn test_annotate_entry_with_boot() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     annotate_subsystems(&mut entry);
@@ -422,6 +454,8 @@ fn test_boot_annotator_boot_steps() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = BootAnnotator;
@@ -442,6 +476,8 @@ fn test_raft_annotator_wal_lowercase() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = RaftBasedAnnotator;
@@ -462,6 +498,8 @@ fn test_raft_annotator_wal_uppercase() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = RaftBasedAnnotator;
@@ -482,6 +520,8 @@ fn test_raft_annotator_ra_system() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = RaftBasedAnnotator;
@@ -502,6 +542,8 @@ fn test_raft_annotator_ra_colon() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = RaftBasedAnnotator;
@@ -522,6 +564,8 @@ fn test_raft_annotator_starting_ra_system() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = RaftBasedAnnotator;
@@ -542,6 +586,8 @@ fn test_raft_annotator_ra_log_underscore() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = RaftBasedAnnotator;
@@ -562,6 +608,8 @@ fn test_raft_annotator_no_match() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = RaftBasedAnnotator;
@@ -582,6 +630,8 @@ fn test_raft_annotator_annotates() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = RaftBasedAnnotator;
@@ -604,6 +654,8 @@ fn test_annotate_entry_with_raft() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     annotate_subsystems(&mut entry);
@@ -625,6 +677,8 @@ fn test_peer_discovery_annotator_matches() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = PeerDiscoveryAnnotator;
@@ -645,6 +699,8 @@ fn test_peer_discovery_annotator_no_match() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = PeerDiscoveryAnnotator;
@@ -665,6 +721,8 @@ fn test_peer_discovery_annotator_annotates() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = PeerDiscoveryAnnotator;
@@ -687,6 +745,8 @@ fn test_annotate_entry_with_peer_discovery() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     annotate_subsystems(&mut entry);
@@ -708,6 +768,8 @@ fn test_plugins_annotator_loading_plugins() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = PluginsAnnotator;
@@ -728,6 +790,8 @@ fn test_plugins_annotator_setting_plugins_up() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = PluginsAnnotator;
@@ -748,6 +812,8 @@ fn test_plugins_annotator_no_match() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = PluginsAnnotator;
@@ -770,6 +836,8 @@ fn test_plugins_annotator_annotates() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = PluginsAnnotator;
@@ -792,6 +860,8 @@ fn test_annotate_entry_with_plugins() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     annotate_subsystems(&mut entry);
@@ -816,6 +886,8 @@ fn test_access_control_annotator_matches() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = AccessControlAnnotator;
@@ -836,6 +908,8 @@ fn test_access_control_annotator_no_match() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = AccessControlAnnotator;
@@ -857,6 +931,8 @@ fn test_access_control_annotator_annotates() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let annotator = AccessControlAnnotator;
@@ -880,6 +956,8 @@ fn test_annotate_entry_with_access_control() {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     annotate_subsystems(&mut entry);
@@ -0,0 +1,188 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::test_helpers::create_test_entry;
+use chrono::{DateTime, TimeDelta, Utc};
+use rabbitmq_lqt_lib::rel_db::node_log_entry::Model;
+use rabbitmq_lqt_lib::{
+    ContextScope, ContextWindow, DatabaseConnection, NodeLogEntry, ParsedLogEntry, QueryContext,
+    Severity, create_database,
+};
+use tempfile::TempDir;
+
+fn entry_at(message: &str, severity: Severity, pid: &str, seconds: i64) -> ParsedLogEntry {
+    let start = DateTime::parse_from_rfc3339("2025-10-27T18:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let mut entry = create_test_entry(message, severity);
+    entry.timestamp = start + TimeDelta::seconds(seconds);
+    entry.process_id = pid.to_string();
+    entry
+}
+
+/// Entries of two nodes, interleaved in time but inserted one node at a time,
+/// so that ids do not follow timestamp order.
+fn setup_db() -> (TempDir, DatabaseConnection) {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database(&temp_dir.path().join("test.db")).unwrap();
+
+    let node1 = vec![
+        entry_at("a", Severity::Info, "<0.1.0>", 0),
+        entry_at("c", Severity::Info, "<0.2.0>", 2),
+        entry_at("e", Severity::Warning, "<0.1.0>", 4),
+        entry_at("g", Severity::Info, "<0.1.0>", 6),
+    ];
+    let node2 = vec![
+        entry_at("b", Severity::Info, "<0.1.0>", 1),
+        entry_at("d", Severity::Error, "<0.1.0>", 3),
+        entry_at("f", Severity::Info, "<0.1.0>", 5),
+    ];
+    NodeLogEntry::insert_parsed_entries(&db, &node1, "rabbit@node1").unwrap();
+    NodeLogEntry::insert_parsed_entries(&db, &node2, "rabbit@node2").unwrap();
+
+    (temp_dir, db)
+}
+
+fn id_of(db: &DatabaseConnection, message: &str) -> i64 {
+    NodeLogEntry::find_all(db)
+        .unwrap()
+        .into_iter()
+        .find(|entry| entry.message == message)
+        .unwrap()
+        .id
+}
+
+fn messages(entries: Vec<Model>) -> Vec<String> {
+    entries.into_iter().map(|entry| entry.message).collect()
+}
+
+fn context_of(db: &DatabaseConnection, message: &str, window: ContextWindow) -> Vec<String> {
+    messages(
+        NodeLogEntry::context(db, id_of(db, message), &window)
+            .unwrap()
+            .unwrap(),
+    )
+}
+
+#[test]
+fn test_context_spans_all_nodes_in_timestamp_order() {
+    let (_temp_dir, db) = setup_db();
+
+    assert_eq!(
+        context_of(&db, "d", ContextWindow::around(1)),
+        vec!["c", "d", "e"]
+    );
+    assert_eq!(
+        context_of(&db, "d", ContextWindow::around(2)),
+        vec!["b", "c", "d", "e", "f"]
+    );
+}
+
+#[test]
+fn test_context_before_and_after_are_independent() {
+    let (_temp_dir, db) = setup_db();
+
+    assert_eq!(context_of(&db, "a", ContextWindow::new(5, 0)), vec!["a"]);
+    assert_eq!(
+        context_of(&db, "a", ContextWindow::new(0, 2)),
+        vec!["a", "b", "c"]
+    );
+    assert_eq!(
+        context_of(&db, "g", ContextWindow::new(1, 5)),
+        vec!["f", "g"]
+    );
+}
+
+#[test]
+fn test_context_scoped_to_node_and_process() {
+    let (_temp_dir, db) = setup_db();
+
+    let same_node = ContextWindow::around(1).scoped(ContextScope::Node);
+    assert_eq!(context_of(&db, "d", same_node), vec!["b", "d", "f"]);
+
+    let same_node = ContextWindow::around(1).scoped(ContextScope::Node);
+    assert_eq!(context_of(&db, "e", same_node), vec!["c", "e", "g"]);
+
+    // Process ids are only unique within a node
+    let same_process = ContextWindow::around(1).scoped(ContextScope::Process);
+    assert_eq!(context_of(&db, "e", same_process), vec!["a", "e", "g"]);
+}
+
+#[test]
+fn test_context_of_unknown_entry() {
+    let (_temp_dir, db) = setup_db();
+
+    let context = NodeLogEntry::context(&db, 1000, &ContextWindow::around(1)).unwrap();
+    assert!(context.is_none());
+}
+
+#[test]
+fn test_query_with_context_window_merges_overlapping_windows() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default()
+        .severity("error")
+        .context_window(ContextWindow::around(1));
+    assert_eq!(
+        messages(NodeLogEntry::query(&db, &ctx).unwrap()),
+        vec!["c", "d", "e"]
+    );
+
+    let ctx = QueryContext::default()
+        .min_severity(Severity::Warning)
+        .context_window(ContextWindow::around(1));
+    assert_eq!(
+        messages(NodeLogEntry::query(&db, &ctx).unwrap()),
+        vec!["c", "d", "e", "f"]
+    );
+
+    let ctx = QueryContext::default()
+        .severity("critical")
+        .context_window(ContextWindow::around(1));
+    assert!(NodeLogEntry::query(&db, &ctx).unwrap().is_empty());
+}
+
+#[test]
+fn test_context_window_is_clamped() {
+    let window = ContextWindow::new(ContextWindow::MAX_ENTRIES + 1, u64::MAX);
+    assert_eq!(window.before, ContextWindow::MAX_ENTRIES);
+    assert_eq!(window.after, ContextWindow::MAX_ENTRIES);
+}
+
+#[test]
+fn test_query_with_context_window_expands_a_limited_number_of_hits() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database(&temp_dir.path().join("test.db")).unwrap();
+    let entries: Vec<ParsedLogEntry> = (0..(ContextWindow::MAX_HITS as i64 + 50) * 2)
+        .map(|i| {
+            let severity = if i % 2 == 0 {
+                Severity::Error
+            } else {
+                Severity::Info
+            };
+            entry_at(&i.to_string(), severity, "<0.1.0>", i)
+        })
+        .collect();
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "rabbit@node1").unwrap();
+
+    let ctx = QueryContext::default()
+        .severity("error")
+        .context_window(ContextWindow::new(0, 1));
+    let expanded = NodeLogEntry::query(&db, &ctx).unwrap();
+    assert_eq!(expanded.len() as u64, ContextWindow::MAX_HITS * 2);
+    assert_eq!(
+        expanded.last().unwrap().message,
+        (ContextWindow::MAX_HITS * 2 - 1).to_string()
+    );
+}
@@ -12,13 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use chrono::Utc;
-use rabbitmq_lqt_lib::entry_metadata::annotate_entry;
-use rabbitmq_lqt_lib::entry_metadata::labels::LogEntryLabels;
+use crate::test_helpers::{create_test_entry, create_test_entry_with_subsystem};
+use chrono::{TimeDelta, Utc};
+use rabbitmq_lqt_lib::entry_metadata::labels::{LABEL_NAMES, LogEntryLabels};
+use rabbitmq_lqt_lib::entry_metadata::{annotate_entry, annotate_entry_with_rules};
+use rabbitmq_lqt_lib::rel_db::FileMetadata;
+use rabbitmq_lqt_lib::rel_db::file_metadata::Model as FileMetadataModel;
+use rabbitmq_lqt_lib::rel_db::node_log_entry::entry_fingerprint;
 use rabbitmq_lqt_lib::{
-    NodeLogEntry, ParsedLogEntry, QueryContext, Severity, Subsystem, create_database,
-    open_database, parse_log_file,
+    Aggregation, CountDimension, DiffSide, EntityKind, Fact, LogColumn, LogDiff, MessagePattern,
+    MessagePatterns, MessageSearchIndex, NodeLogEntry, NodeLogEntryCustomAnnotations,
+    NodeLogEntryEntities, ParsedLogEntry, QueryContext, RuleSet, SearchQuery, Severity,
+    SqlCondition, SqlParam, StructuredMetadata, Subsystem, TimeAnchor, TimeReference,
+    create_database, migrate_database, obfuscate_database, open_database, parse_log_file,
+    post_insertion_operations,
 };
+use rabbitmq_lqt_obfuscation::LogObfuscator;
 use std::fs;
 use std::io::BufReader;
 use tempfile::TempDir;
@@ -33,6 +42,34 @@ fn test_create_database() {
     assert_eq!(count, 0);
 }
 
+#[test]
+fn test_pooled_connections_share_one_database_instance() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+
+    // Both are held at the same time, so the pool opens two connections
+    let writer = db.get().unwrap();
+    let reader = db.get().unwrap();
+    writer
+        .execute_batch("CREATE TABLE pool_check (n INTEGER); INSERT INTO pool_check VALUES (42);")
+        .unwrap();
+    let n: i32 = reader
+        .query_row("SELECT n FROM pool_check", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(n, 42);
+    drop(writer);
+    drop(reader);
+
+    let db = open_database(&db_path).unwrap();
+    let (first, second) = (db.get().unwrap(), db.get().unwrap());
+    first.execute_batch("DELETE FROM pool_check").unwrap();
+    let count: i64 = second
+        .query_row("SELECT COUNT(*) FROM pool_check", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
 #[test]
 fn test_insert_and_query() {
     let temp_dir = TempDir::new().unwrap();
@@ -52,6 +89,8 @@ fn test_insert_and_query() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -65,6 +104,8 @@ fn test_insert_and_query() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -97,6 +138,8 @@ fn test_query_with_severity_filter() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -110,6 +153,8 @@ fn test_query_with_severity_filter() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -121,6 +166,41 @@ fn test_query_with_severity_filter() {
     assert_eq!(errors[0].severity, "error");
 }
 
+#[test]
+fn test_query_with_min_severity_filter() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+
+    let entries: Vec<ParsedLogEntry> = Severity::all()
+        .into_iter()
+        .map(|severity| create_test_entry(&format!("{} message", severity), severity))
+        .collect();
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "test-node").unwrap();
+
+    let ctx = QueryContext::default().min_severity(Severity::Warning);
+    let mut severities: Vec<String> = NodeLogEntry::query(&db, &ctx)
+        .unwrap()
+        .into_iter()
+        .map(|m| m.severity)
+        .collect();
+    severities.sort();
+    assert_eq!(severities, vec!["critical", "error", "warning"]);
+
+    let ctx = QueryContext::default()
+        .min_severity(Severity::Notice)
+        .severity("error");
+    let results = NodeLogEntry::query(&db, &ctx).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].severity, "error");
+
+    let ctx = QueryContext::default().min_severity(Severity::Debug);
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), 6);
+
+    let ctx = QueryContext::default().severity("fatal");
+    assert!(NodeLogEntry::query(&db, &ctx).unwrap().is_empty());
+}
+
 #[test]
 fn test_query_with_limit() {
     let temp_dir = TempDir::new().unwrap();
@@ -140,6 +220,8 @@ fn test_query_with_limit() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         })
         .collect();
 
@@ -150,6 +232,56 @@ fn test_query_with_limit() {
     assert_eq!(limited.len(), 5);
 }
 
+#[test]
+fn test_query_with_where_conditions() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database(&temp_dir.path().join("test.db")).unwrap();
+    let entries = vec![
+        create_test_entry("user 'guest' logged in", Severity::Info),
+        create_test_entry(
+            "user 'guest'; DROP TABLE node_log_entries; --",
+            Severity::Warning,
+        ),
+        create_test_entry("100% of \\ disk used", Severity::Error),
+    ];
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "test-node").unwrap();
+
+    let ctx = QueryContext::default()
+        .severity("warning")
+        .where_condition(SqlCondition::new(
+            "message = ?",
+            vec![SqlParam::Text(entries[1].message.clone())],
+        ));
+    let results = NodeLogEntry::query(&db, &ctx).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].message, entries[1].message);
+
+    let literal_percent = SqlCondition::new(
+        "message ILIKE ? ESCAPE '\\'",
+        vec![SqlParam::Text("100\\% of \\\\%".to_string())],
+    );
+    let results = NodeLogEntry::query(
+        &db,
+        &QueryContext::default().where_condition(literal_percent),
+    )
+    .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].message, entries[2].message);
+
+    let either = SqlCondition::new("id = ?", vec![SqlParam::Integer(1)])
+        .or(SqlCondition::new("id = ?", vec![SqlParam::Integer(3)]))
+        .and(SqlCondition::trusted("severity = 1").negate().grouped());
+    assert_eq!(
+        either.sql(),
+        "((id = ? OR id = ?) AND (NOT (severity = 1)))"
+    );
+    let results =
+        NodeLogEntry::query(&db, &QueryContext::default().where_condition(either)).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, 3);
+    assert_eq!(NodeLogEntry::count_all(&db).unwrap(), 3);
+}
+
 #[test]
 fn test_open_existing_database() {
     let temp_dir = TempDir::new().unwrap();
@@ -169,6 +301,8 @@ fn test_open_existing_database() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         }];
         NodeLogEntry::insert_parsed_entries(&db, &entries, "test-node").unwrap();
     }
@@ -209,6 +343,8 @@ fn test_query_with_subsystem_filter() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -222,6 +358,8 @@ fn test_query_with_subsystem_filter() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -235,6 +373,8 @@ fn test_query_with_subsystem_filter() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -290,6 +430,8 @@ fn test_query_with_label_filter() {
             labels: labels_crash,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -303,6 +445,8 @@ fn test_query_with_label_filter() {
             labels: labels_undef,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -316,6 +460,8 @@ fn test_query_with_label_filter() {
             labels: labels_both,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -329,6 +475,8 @@ fn test_query_with_label_filter() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -375,6 +523,8 @@ fn test_query_with_combined_filters() {
             labels: labels_crash,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -388,6 +538,8 @@ fn test_query_with_combined_filters() {
             labels: labels_crash,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -401,6 +553,8 @@ fn test_query_with_combined_filters() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -442,6 +596,8 @@ fn test_query_with_has_doc_url_filter() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: Some(1),
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -455,6 +611,8 @@ fn test_query_with_has_doc_url_filter() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -468,6 +626,8 @@ fn test_query_with_has_doc_url_filter() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: Some(2),
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -502,6 +662,8 @@ fn test_query_with_has_resolution_or_discussion_url_filter() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: Some(123),
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -515,6 +677,8 @@ fn test_query_with_has_resolution_or_discussion_url_filter() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -549,6 +713,8 @@ fn test_query_with_both_url_filters() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: Some(100),
             doc_url_id: Some(3),
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -562,6 +728,8 @@ fn test_query_with_both_url_filters() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: Some(3),
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -575,6 +743,8 @@ fn test_query_with_both_url_filters() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: Some(100),
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -588,6 +758,8 @@ fn test_query_with_both_url_filters() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -621,6 +793,8 @@ fn test_query_with_unlabelled_filter() {
             labels: LogEntryLabels::UNLABELLED,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 1,
@@ -634,6 +808,8 @@ fn test_query_with_unlabelled_filter() {
             labels: LogEntryLabels::ACCESS_CONTROL,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 2,
@@ -647,6 +823,8 @@ fn test_query_with_unlabelled_filter() {
             labels: LogEntryLabels::UNLABELLED,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -678,6 +856,8 @@ fn test_query_excluding_unlabelled() {
             labels: LogEntryLabels::UNLABELLED,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 1,
@@ -691,6 +871,8 @@ fn test_query_excluding_unlabelled() {
             labels: LogEntryLabels::ELECTIONS | LogEntryLabels::RAFT,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -798,6 +980,8 @@ fn test_bulk_insert_with_chunking() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         })
         .collect();
 
@@ -838,6 +1022,8 @@ fn test_query_with_offset() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         })
         .collect();
 
@@ -867,6 +1053,8 @@ fn test_query_with_tail() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         })
         .collect();
 
@@ -876,3 +1064,969 @@ fn test_query_with_tail() {
     let results = NodeLogEntry::query(&db, &ctx).unwrap();
     assert_eq!(results.len(), 3);
 }
+
+#[test]
+fn test_time_reference_from_database_uses_most_recent_entry() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+
+    let empty = TimeReference::from_database(&db, TimeAnchor::LogEnd).unwrap();
+    assert_eq!(empty.log_end, None);
+
+    let latest = Utc::now() - chrono::Duration::days(30);
+    let entries: Vec<ParsedLogEntry> = (0..3)
+        .map(|i| ParsedLogEntry {
+            sequence_id: 0,
+            explicit_id: None,
+            timestamp: latest - chrono::Duration::minutes(i),
+            severity: Severity::Info,
+            process_id: format!("<0.{}.0>", i),
+            message: format!("Message {}", i),
+            message_lowercased: format!("message {}", i),
+            subsystem_id: None,
+            labels: LogEntryLabels::default(),
+            resolution_or_discussion_url_id: None,
+            doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
+        })
+        .collect();
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "test-node").unwrap();
+
+    let time_ref = TimeReference::from_database(&db, TimeAnchor::LogEnd).unwrap();
+    assert_eq!(
+        time_ref.log_end.map(|ts| ts.timestamp_micros()),
+        Some(latest.timestamp_micros())
+    );
+    assert_eq!(
+        time_ref.reference_time().timestamp_micros(),
+        latest.timestamp_micros()
+    );
+}
+
+#[test]
+fn test_query_returns_all_labels_of_an_entry() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+
+    let labels = LogEntryLabels::UNLABELLED
+        | LogEntryLabels::CONNECTIONS
+        | LogEntryLabels::STARTUP_BANNER
+        | LogEntryLabels::from_id(LABEL_NAMES.len() - 1);
+    let entries = vec![ParsedLogEntry {
+        sequence_id: 0,
+        explicit_id: None,
+        timestamp: Utc::now(),
+        severity: Severity::Info,
+        process_id: "<0.1.0>".to_string(),
+        message: "Test".to_string(),
+        message_lowercased: "test".to_string(),
+        subsystem_id: None,
+        labels,
+        resolution_or_discussion_url_id: None,
+        doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
+    }];
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "test-node").unwrap();
+
+    let results = NodeLogEntry::query(&db, &QueryContext::default()).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].labels, labels);
+
+    let ctx = QueryContext::default()
+        .add_label("connections")
+        .add_label("startup_banner")
+        .matching_all_labels(true);
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), 1);
+
+    let ctx = QueryContext::default()
+        .add_label("connections")
+        .add_label("raft")
+        .matching_all_labels(true);
+    assert!(NodeLogEntry::query(&db, &ctx).unwrap().is_empty());
+}
+
+#[test]
+fn test_open_database_migrates_legacy_label_bitmask() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("legacy.db");
+
+    let crash_and_undef = (LogEntryLabels::ERL_PROCESS_CRASH | LogEntryLabels::UNDEFINED_FN)
+        .ids()
+        .fold(0i64, |mask, id| mask | (1i64 << id));
+    {
+        let conn = duckdb::Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "
+            CREATE TABLE node_log_entries (
+                id BIGINT PRIMARY KEY,
+                node VARCHAR NOT NULL,
+                timestamp TIMESTAMPTZ NOT NULL,
+                severity VARCHAR NOT NULL,
+                erlang_pid VARCHAR NOT NULL,
+                subsystem_id SMALLINT,
+                message VARCHAR NOT NULL,
+                labels BIGINT NOT NULL DEFAULT 0,
+                resolution_or_discussion_url_id SMALLINT,
+                doc_url_id SMALLINT
+            );
+            CREATE INDEX idx_node_log_entries_node ON node_log_entries(node);
+            ",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO node_log_entries VALUES
+                (1, 'rabbit@node1', '2025-10-27 18:23:00+00', 'error', '<0.1.0>', NULL, 'crasher', ?, NULL, NULL),
+                (2, 'rabbit@node1', '2025-10-27 18:23:01+00', 'info', '<0.2.0>', NULL, 'regular', 0, NULL, NULL)",
+            duckdb::params![crash_and_undef],
+        )
+        .unwrap();
+    }
+
+    let db = open_database(&db_path).unwrap();
+    assert_eq!(NodeLogEntry::count_all(&db).unwrap(), 2);
+
+    let ctx = QueryContext::default().add_label("undefined_fn");
+    let results = NodeLogEntry::query(&db, &ctx).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, 1);
+    assert_eq!(
+        results[0].labels,
+        LogEntryLabels::ERL_PROCESS_CRASH | LogEntryLabels::UNDEFINED_FN
+    );
+
+    assert!(!migrate_database(&db).unwrap());
+}
+
+#[test]
+fn test_open_database_converts_severity_names_to_ordinals() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("severity_names.db");
+
+    {
+        let conn = duckdb::Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "
+            CREATE TABLE node_log_entries (
+                id BIGINT PRIMARY KEY,
+                node VARCHAR NOT NULL,
+                timestamp TIMESTAMPTZ NOT NULL,
+                severity VARCHAR NOT NULL,
+                erlang_pid VARCHAR NOT NULL,
+                subsystem_id SMALLINT,
+                message VARCHAR NOT NULL,
+                resolution_or_discussion_url_id SMALLINT,
+                doc_url_id SMALLINT,
+                domain VARCHAR,
+                mfa VARCHAR,
+                source_file VARCHAR,
+                source_line INTEGER,
+                connection VARCHAR,
+                vhost VARCHAR,
+                username VARCHAR,
+                pattern_id BIGINT
+            );
+            CREATE INDEX idx_node_log_entries_severity ON node_log_entries(severity);
+            INSERT INTO node_log_entries (id, node, timestamp, severity, erlang_pid, message) VALUES
+                (1, 'rabbit@node1', '2025-10-27 18:23:00+00', 'critical', '<0.1.0>', 'disk alarm set'),
+                (2, 'rabbit@node1', '2025-10-27 18:23:01+00', 'info', '<0.2.0>', 'accepting AMQP connection'),
+                (3, 'rabbit@node1', '2025-10-27 18:23:02+00', 'warning', '<0.3.0>', 'closing AMQP connection'),
+                (4, 'rabbit@node1', '2025-10-27 18:23:03+00', 'emergency', '<0.4.0>', 'unknown severity');
+            ",
+        )
+        .unwrap();
+    }
+
+    let db = open_database(&db_path).unwrap();
+    let all = NodeLogEntry::find_all(&db).unwrap();
+    let severities: Vec<&str> = all.iter().map(|m| m.severity.as_str()).collect();
+    // Unknown severity names become info
+    assert_eq!(severities, vec!["critical", "info", "warning", "info"]);
+
+    let ctx = QueryContext::default().min_severity(Severity::Warning);
+    let ids: Vec<i64> = NodeLogEntry::query(&db, &ctx)
+        .unwrap()
+        .iter()
+        .map(|m| m.id)
+        .collect();
+    assert_eq!(ids, vec![1, 3]);
+
+    assert!(!migrate_database(&db).unwrap());
+}
+
+#[test]
+fn test_structured_metadata_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+
+    let mut with_metadata =
+        create_test_entry("Ready to start client connection listeners", Severity::Info);
+    with_metadata.metadata = StructuredMetadata {
+        domain: Some("rabbitmq".to_string()),
+        mfa: Some("rabbit_networking:boot/0".to_string()),
+        file: Some("rabbit_networking.erl".to_string()),
+        line: Some(145),
+        connection: Some("10.0.0.2:57586 -> 10.0.0.1:5672".to_string()),
+        vhost: Some("vhost1".to_string()),
+        user: Some("user2".to_string()),
+    };
+    let without_metadata = create_test_entry("Server startup complete", Severity::Info);
+
+    NodeLogEntry::insert_parsed_entries(
+        &db,
+        &[with_metadata.clone(), without_metadata],
+        "rabbit@node1",
+    )
+    .unwrap();
+
+    let all = NodeLogEntry::find_all(&db).unwrap();
+    assert_eq!(all.len(), 2);
+    assert!(all.iter().any(|m| m.metadata == with_metadata.metadata));
+    assert!(all.iter().any(|m| m.metadata.is_empty()));
+
+    let ctx = QueryContext::default()
+        .vhost("vhost1")
+        .user("user2")
+        .mfa("rabbit_networking:boot/0");
+    let results = NodeLogEntry::query(&db, &ctx).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].metadata.line, Some(145));
+
+    let ctx = QueryContext::default().vhost("vhost2");
+    assert!(NodeLogEntry::query(&db, &ctx).unwrap().is_empty());
+}
+
+#[test]
+fn test_open_database_adds_structured_metadata_columns() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("pre_metadata.db");
+
+    {
+        let conn = duckdb::Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "
+            CREATE TABLE node_log_entries (
+                id BIGINT PRIMARY KEY,
+                node VARCHAR NOT NULL,
+                timestamp TIMESTAMPTZ NOT NULL,
+                severity VARCHAR NOT NULL,
+                erlang_pid VARCHAR NOT NULL,
+                subsystem_id SMALLINT,
+                message VARCHAR NOT NULL,
+                resolution_or_discussion_url_id SMALLINT,
+                doc_url_id SMALLINT
+            );
+            CREATE TABLE node_log_entry_labels (
+                entry_id BIGINT NOT NULL,
+                label_id SMALLINT NOT NULL
+            );
+            CREATE INDEX idx_node_log_entries_node ON node_log_entries(node);
+            INSERT INTO node_log_entries VALUES
+                (1, 'rabbit@node1', '2025-10-27 18:23:00+00', 'info', '<0.1.0>', NULL, 'accepting AMQP connection', NULL, NULL);
+            INSERT INTO node_log_entry_labels VALUES (1, 13);
+            ",
+        )
+        .unwrap();
+    }
+
+    let db = open_database(&db_path).unwrap();
+    let all = NodeLogEntry::find_all(&db).unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].message, "accepting AMQP connection");
+    assert_eq!(all[0].labels, LogEntryLabels::CONNECTIONS);
+    assert!(all[0].metadata.is_empty());
+
+    let mut entry = create_test_entry("Created vhost", Severity::Info);
+    entry.metadata.vhost = Some("/".to_string());
+    NodeLogEntry::insert_parsed_entries(&db, &[entry], "rabbit@node1").unwrap();
+
+    let ctx = QueryContext::default().vhost("/");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), 1);
+
+    assert!(!migrate_database(&db).unwrap());
+}
+
+#[test]
+fn test_query_by_entity() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+
+    let mut entries = vec![
+        create_test_entry(
+            "queue 'orders' in vhost 'vhost1': detected a new leader {'orders','rabbit@node1'} in term 2",
+            Severity::Info,
+        ),
+        create_test_entry(
+            "queue 'invoices' in vhost 'vhost1': leader saw pre_vote_rpc",
+            Severity::Info,
+        ),
+        create_test_entry(
+            "Requesting {'orders','rabbit@node2'} from 39357",
+            Severity::Debug,
+        ),
+    ];
+    for entry in &mut entries {
+        annotate_entry(entry);
+    }
+    NodeLogEntry::insert_parsed_entries(&db, &entries[..2], "rabbit@node1").unwrap();
+    NodeLogEntry::insert_parsed_entries(&db, &entries[2..], "rabbit@node2").unwrap();
+
+    let ctx = QueryContext::default().entity(EntityKind::Queue, "orders");
+    let results = NodeLogEntry::query(&db, &ctx).unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().any(|m| m.node == "rabbit@node2"));
+
+    let ctx = QueryContext::default()
+        .entity(EntityKind::Queue, "orders")
+        .node("rabbit@node1");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), 1);
+
+    let ctx = QueryContext::default().entity(EntityKind::Exchange, "orders");
+    assert!(NodeLogEntry::query(&db, &ctx).unwrap().is_empty());
+
+    let ctx = QueryContext::default().aggregation(Aggregation::CountBy(Some(LogColumn::Queue)));
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+    assert_eq!(result.columns, vec!["queue", "count"]);
+    assert_eq!(result.rows[0][0], "orders");
+    assert_eq!(result.rows[0][1], 2);
+}
+
+#[test]
+fn test_most_mentioned_entities() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+
+    let mut entries = vec![
+        create_test_entry(
+            "queue 'orders' in vhost 'vhost1': detected a new leader",
+            Severity::Info,
+        ),
+        create_test_entry(
+            "queue 'orders' in vhost 'vhost1': leader saw pre_vote_rpc",
+            Severity::Info,
+        ),
+        create_test_entry(
+            "queue 'invoices' in vhost 'vhost2': leader saw pre_vote_rpc",
+            Severity::Info,
+        ),
+    ];
+    for entry in &mut entries {
+        annotate_entry(entry);
+    }
+    NodeLogEntry::insert_parsed_entries(&db, &entries[..1], "rabbit@node1").unwrap();
+    NodeLogEntry::insert_parsed_entries(&db, &entries[1..], "rabbit@node2").unwrap();
+
+    let queues = NodeLogEntryEntities::most_mentioned(&db, Some(EntityKind::Queue), 10).unwrap();
+    assert_eq!(queues.len(), 2);
+    assert_eq!(queues[0].name, "orders");
+    assert_eq!(queues[0].vhost.as_deref(), Some("vhost1"));
+    assert_eq!(queues[0].entry_count, 2);
+    assert_eq!(queues[0].node_count, 2);
+    assert!(queues[0].first_seen.is_some());
+
+    let all = NodeLogEntryEntities::most_mentioned(&db, None, 10).unwrap();
+    assert!(
+        all.iter()
+            .any(|e| e.kind == EntityKind::Vhost && e.name == "vhost2")
+    );
+
+    let top = NodeLogEntryEntities::most_mentioned(&db, None, 1).unwrap();
+    assert_eq!(top.len(), 1);
+}
+
+#[test]
+fn test_open_database_extracts_entities_of_existing_entries() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("pre_entities.db");
+
+    {
+        let db = create_database(&db_path).unwrap();
+        let mut entry = create_test_entry(
+            "queue 'orders' in vhost 'vhost1': detected a new leader",
+            Severity::Info,
+        );
+        annotate_entry(&mut entry);
+        NodeLogEntry::insert_parsed_entries(&db, &[entry], "rabbit@node1").unwrap();
+        let conn = db.get().unwrap();
+        conn.execute_batch("DROP TABLE node_log_entry_entities")
+            .unwrap();
+    }
+
+    let db = open_database(&db_path).unwrap();
+    let ctx = QueryContext::default().entity(EntityKind::Queue, "orders");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), 1);
+
+    assert!(!migrate_database(&db).unwrap());
+}
+
+#[test]
+fn test_most_frequent_patterns() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+
+    let entries = [
+        create_test_entry(
+            "accepting AMQP connection 10.0.0.2:57586 -> 10.0.0.1:5672",
+            Severity::Info,
+        ),
+        create_test_entry(
+            "accepting AMQP connection 10.0.0.3:41222 -> 10.0.0.1:5672",
+            Severity::Info,
+        ),
+        create_test_entry(
+            "Server startup complete; 5 plugins started.",
+            Severity::Info,
+        ),
+    ];
+    NodeLogEntry::insert_parsed_entries(&db, &entries[..2], "rabbit@node1").unwrap();
+    NodeLogEntry::insert_parsed_entries(&db, &entries[1..], "rabbit@node2").unwrap();
+
+    let patterns = MessagePatterns::most_frequent(&db, None, 10).unwrap();
+    assert_eq!(patterns.len(), 2);
+    assert_eq!(
+        patterns[0].template,
+        "accepting AMQP connection <ip> -> <ip>"
+    );
+    assert_eq!(patterns[0].entry_count, 3);
+    assert_eq!(patterns[0].node_count, 2);
+    assert_eq!(
+        patterns[0].pattern_id,
+        MessagePattern::of("accepting AMQP connection 10.0.0.9:1 -> 10.0.0.1:5672").id
+    );
+    assert!(patterns[0].first_seen.is_some());
+
+    let node2 = MessagePatterns::most_frequent(&db, Some("rabbit@node2"), 10).unwrap();
+    assert_eq!(node2[0].entry_count, 1);
+
+    let ctx = QueryContext::default().aggregation(Aggregation::CountBy(Some(LogColumn::Pattern)));
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+    assert_eq!(result.columns, vec!["pattern", "count"]);
+    assert_eq!(result.rows[0][0], "accepting AMQP connection <ip> -> <ip>");
+    assert_eq!(result.rows[0][1], 3);
+}
+
+#[test]
+fn test_open_database_computes_patterns_of_existing_entries() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("pre_patterns.db");
+
+    {
+        let db = create_database(&db_path).unwrap();
+        let entry = create_test_entry("closing AMQP connection <0.1234.0>", Severity::Info);
+        NodeLogEntry::insert_parsed_entries(&db, &[entry], "rabbit@node1").unwrap();
+        let conn = db.get().unwrap();
+        conn.execute_batch(
+            "DROP TABLE message_patterns; UPDATE node_log_entries SET pattern_id = NULL",
+        )
+        .unwrap();
+    }
+
+    let db = open_database(&db_path).unwrap();
+    let patterns = MessagePatterns::most_frequent(&db, None, 10).unwrap();
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(patterns[0].template, "closing AMQP connection <pid>");
+
+    assert!(!migrate_database(&db).unwrap());
+}
+
+#[test]
+fn test_diff_of_two_databases() {
+    let temp_dir = TempDir::new().unwrap();
+    let baseline_db = create_database(&temp_dir.path().join("baseline.db")).unwrap();
+    let comparison_db = create_database(&temp_dir.path().join("comparison.db")).unwrap();
+
+    let mut closed = create_test_entry_with_subsystem(
+        "closing AMQP connection <0.1204.0> (10.0.0.2:57586 -> 10.0.0.1:5672)",
+        Severity::Info,
+        Subsystem::Connections,
+    );
+    annotate_entry(&mut closed);
+    NodeLogEntry::insert_parsed_entries(&baseline_db, &[closed.clone()], "rabbit@node1").unwrap();
+
+    let partition = create_test_entry("Partial partition detected", Severity::Error);
+    NodeLogEntry::insert_parsed_entries(&comparison_db, &[closed, partition], "rabbit@node1")
+        .unwrap();
+
+    for (db, version) in [(&baseline_db, "4.1.4"), (&comparison_db, "4.2.0")] {
+        FileMetadata::insert_metadata(
+            db,
+            FileMetadataModel {
+                file_path: "rabbit@node1.log".to_string(),
+                rabbitmq_versions: vec![version.to_string()],
+                erlang_versions: vec![],
+                tls_library: None,
+                oldest_entry_at: None,
+                most_recent_entry_at: None,
+                total_lines: 0,
+                total_entries: 0,
+                nodes: vec!["rabbit@node1".to_string()],
+                subsystems: vec![],
+                labels: vec![],
+                enabled_plugins: vec![],
+            },
+        )
+        .unwrap();
+    }
+
+    let diff = LogDiff::compute(
+        &DiffSide::new(&baseline_db, QueryContext::default()),
+        &DiffSide::new(&comparison_db, QueryContext::default()),
+    )
+    .unwrap();
+
+    assert_eq!(diff.baseline_entries, 1);
+    assert_eq!(diff.comparison_entries, 2);
+    assert_eq!(diff.new_patterns.len(), 1);
+    assert_eq!(diff.new_patterns[0].template, "Partial partition detected");
+    assert!(diff.vanished_patterns.is_empty());
+
+    let error = diff
+        .count_deltas
+        .iter()
+        .find(|d| d.dimension == CountDimension::Severity)
+        .unwrap();
+    assert_eq!(error.value, "error");
+    assert_eq!(error.delta(), 1);
+    assert!(
+        !diff
+            .count_deltas
+            .iter()
+            .any(|d| d.dimension == CountDimension::Subsystem)
+    );
+
+    assert_eq!(diff.fact_changes.len(), 1);
+    assert_eq!(diff.fact_changes[0].fact, Fact::RabbitmqVersion);
+    assert_eq!(diff.fact_changes[0].added, vec!["4.2.0".to_string()]);
+}
+
+#[test]
+fn test_diff_of_two_time_windows() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database(&temp_dir.path().join("test.db")).unwrap();
+
+    let now = Utc::now();
+    let mut before = create_test_entry(
+        "accepting AMQP connection 10.0.0.2:1 -> 10.0.0.1:5672",
+        Severity::Info,
+    );
+    before.timestamp = now - TimeDelta::hours(2);
+    let mut after = create_test_entry(
+        "Node rabbit@node2 is down, deleting its listeners",
+        Severity::Warning,
+    );
+    after.timestamp = now;
+    NodeLogEntry::insert_parsed_entries(&db, &[before, after], "rabbit@node1").unwrap();
+
+    let split = now - TimeDelta::hours(1);
+    let diff = LogDiff::compute(
+        &DiffSide::new(&db, QueryContext::default().to(split)),
+        &DiffSide::new(&db, QueryContext::default().since(split)),
+    )
+    .unwrap();
+
+    assert_eq!(diff.baseline_entries, 1);
+    assert_eq!(diff.comparison_entries, 1);
+    assert_eq!(
+        diff.new_patterns[0].template,
+        "Node <node> is down, deleting its listeners"
+    );
+    assert_eq!(
+        diff.vanished_patterns[0].template,
+        "accepting AMQP connection <ip> -> <ip>"
+    );
+    assert_eq!(diff.count_deltas.len(), 2);
+}
+
+const BILLING_RULES: &str = r#"
+[[rules]]
+name = "billing-errors"
+when = { starts_with = "[billing]", severity = "error" }
+then = { custom_labels = ["billing", "needs-triage"], doc_url = "https://wiki.example.com/billing" }
+
+[[rules]]
+name = "billing-timeouts"
+when = { regex = "invoice \\d+ timed out" }
+then = { custom_labels = ["billing"], resolution_url = "https://tracker.example.com/issues/42" }
+"#;
+
+#[test]
+fn test_custom_annotations_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+    let rules = RuleSet::from_toml_str(BILLING_RULES).unwrap();
+
+    let mut entries = vec![
+        create_test_entry("[billing] failed to charge account 12", Severity::Error),
+        create_test_entry("invoice 1207 timed out", Severity::Warning),
+        create_test_entry("[billing] charged account 12", Severity::Info),
+    ];
+    for entry in &mut entries {
+        annotate_entry_with_rules(entry, Some(&rules));
+    }
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "rabbit@node1").unwrap();
+
+    let results = NodeLogEntry::find_all(&db).unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(
+        results[0].custom_annotations.labels,
+        vec!["billing".to_string(), "needs-triage".to_string()]
+    );
+    assert_eq!(
+        results[0].doc_url(),
+        Some("https://wiki.example.com/billing")
+    );
+    assert!(
+        results[0]
+            .label_names()
+            .any(|name| name == "custom:needs-triage")
+    );
+    assert_eq!(
+        results[1].resolution_or_discussion_url(),
+        Some("https://tracker.example.com/issues/42")
+    );
+    assert!(results[2].custom_annotations.is_empty());
+
+    assert_eq!(
+        NodeLogEntryCustomAnnotations::label_names(&db).unwrap(),
+        vec!["billing".to_string(), "needs-triage".to_string()]
+    );
+}
+
+#[test]
+fn test_query_by_custom_label() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+    let rules = RuleSet::from_toml_str(BILLING_RULES).unwrap();
+
+    let mut entries = vec![
+        create_test_entry("[billing] failed to charge account 12", Severity::Error),
+        create_test_entry("invoice 1207 timed out", Severity::Warning),
+        create_test_entry("Server startup complete", Severity::Info),
+    ];
+    for entry in &mut entries {
+        annotate_entry_with_rules(entry, Some(&rules));
+    }
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "rabbit@node1").unwrap();
+
+    let ctx = QueryContext::default().add_label("custom:billing");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), 2);
+
+    let ctx = QueryContext::default()
+        .add_label("custom:billing")
+        .add_label("custom:needs-triage")
+        .matching_all_labels(true);
+    let results = NodeLogEntry::query(&db, &ctx).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].severity, "error");
+
+    let ctx = QueryContext::default()
+        .add_label("custom:needs-triage")
+        .add_label("custom:billing");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), 2);
+
+    let ctx = QueryContext::default().has_doc_url(true);
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), 1);
+
+    let ctx = QueryContext::default().has_resolution_or_discussion_url(true);
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), 1);
+}
+
+#[test]
+fn test_open_database_adds_custom_annotations_table() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("pre_custom_annotations.db");
+
+    {
+        let db = create_database(&db_path).unwrap();
+        let mut entry = create_test_entry("Server startup complete", Severity::Info);
+        annotate_entry(&mut entry);
+        NodeLogEntry::insert_parsed_entries(&db, &[entry], "rabbit@node1").unwrap();
+        let conn = db.get().unwrap();
+        conn.execute_batch("DROP TABLE node_log_entry_custom_annotations")
+            .unwrap();
+    }
+
+    let db = open_database(&db_path).unwrap();
+    let results = NodeLogEntry::find_all(&db).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].custom_annotations.is_empty());
+
+    assert!(!migrate_database(&db).unwrap());
+}
+
+#[test]
+fn test_with_appender_inserts_entries_and_side_tables() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+    let rules = RuleSet::from_toml_str(BILLING_RULES).unwrap();
+
+    let mut node1_entries = vec![
+        create_test_entry("[billing] failed to charge account 12", Severity::Error),
+        create_test_entry(
+            "accepting AMQP connection 127.0.0.1:5672 -> 127.0.0.1:56789",
+            Severity::Info,
+        ),
+    ];
+    let mut node2_entries = vec![create_test_entry(
+        "queue 'orders' in vhost 'vhost1': detected a new leader {'orders','rabbit@node2'} in term 2",
+        Severity::Info,
+    )];
+    for entry in node1_entries.iter_mut().chain(node2_entries.iter_mut()) {
+        annotate_entry_with_rules(entry, Some(&rules));
+    }
+
+    let appended = NodeLogEntry::with_appender(&db, |appender| {
+        let mut id = 10;
+        for (entries, node) in [
+            (&node1_entries, "rabbit@node1"),
+            (&node2_entries, "rabbit@node2"),
+        ] {
+            for entry in entries.iter() {
+                appender.append(entry, id, node)?;
+                id += 1;
+            }
+            appender.flush()?;
+        }
+        Ok::<_, duckdb::Error>(id - 10)
+    })
+    .unwrap();
+    assert_eq!(appended, 3);
+
+    assert_eq!(NodeLogEntry::count_all(&db).unwrap(), 3);
+    assert_eq!(NodeLogEntry::max_entry_id(&db).unwrap(), 12);
+
+    let ctx = QueryContext::default().node("rabbit@node2");
+    let results = NodeLogEntry::query(&db, &ctx).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, 12);
+
+    let ctx = QueryContext::default().add_label("custom:billing");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap()[0].id, 10);
+
+    let ctx = QueryContext::default().entity(EntityKind::Queue, "orders");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), 1);
+    assert!(
+        !MessagePatterns::most_frequent(&db, None, 10)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_renumber_in_timestamp_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+    let rules = RuleSet::from_toml_str(BILLING_RULES).unwrap();
+    let started_at = Utc::now();
+
+    let mut later = create_test_entry("queue 'orders' in vhost '/' declared", Severity::Info);
+    later.timestamp = started_at + TimeDelta::seconds(10);
+    let mut earlier = create_test_entry("[billing] failed to charge account 12", Severity::Error);
+    earlier.timestamp = started_at;
+    for entry in [&mut later, &mut earlier] {
+        annotate_entry_with_rules(entry, Some(&rules));
+    }
+    NodeLogEntry::insert_parsed_entries(&db, &[later], "rabbit@node1").unwrap();
+    NodeLogEntry::insert_parsed_entries(&db, &[earlier], "rabbit@node2").unwrap();
+
+    assert!(NodeLogEntry::renumber_in_timestamp_order(&db).unwrap());
+    assert!(!NodeLogEntry::renumber_in_timestamp_order(&db).unwrap());
+    post_insertion_operations(&db).unwrap();
+
+    let entries = NodeLogEntry::find_all(&db).unwrap();
+    assert_eq!(entries[0].id, 1);
+    assert_eq!(entries[0].node, "rabbit@node2");
+    assert_eq!(entries[1].id, 2);
+    assert_eq!(entries[1].node, "rabbit@node1");
+
+    // Labels, entities and custom annotations follow their entries
+    let ctx = QueryContext::default().add_label("custom:billing");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap()[0].id, 1);
+    let ctx = QueryContext::default().entity(EntityKind::Queue, "orders");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap()[0].id, 2);
+}
+
+#[test]
+fn test_renumber_appended_in_timestamp_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+    let rules = RuleSet::from_toml_str(BILLING_RULES).unwrap();
+    let started_at = Utc::now();
+
+    let mut stored = create_test_entry("queue 'stored' in vhost '/' declared", Severity::Info);
+    stored.timestamp = started_at + TimeDelta::seconds(5);
+    NodeLogEntry::insert_parsed_entries(&db, &[stored], "rabbit@node1").unwrap();
+    post_insertion_operations(&db).unwrap();
+
+    // Both are appended after the stored entry, the later one first
+    let mut later = create_test_entry("queue 'orders' in vhost '/' declared", Severity::Info);
+    later.timestamp = started_at + TimeDelta::seconds(10);
+    let mut earlier = create_test_entry("[billing] failed to charge account 12", Severity::Error);
+    earlier.timestamp = started_at;
+    for entry in [&mut later, &mut earlier] {
+        annotate_entry_with_rules(entry, Some(&rules));
+    }
+    NodeLogEntry::insert_parsed_entries(&db, &[later], "rabbit@node1").unwrap();
+    NodeLogEntry::insert_parsed_entries(&db, &[earlier], "rabbit@node2").unwrap();
+
+    assert!(NodeLogEntry::renumber_appended_in_timestamp_order(&db, 2).unwrap());
+    assert!(!NodeLogEntry::renumber_appended_in_timestamp_order(&db, 2).unwrap());
+
+    // The stored entry keeps its id even though the appended ones are not all newer
+    let entries = NodeLogEntry::find_all(&db).unwrap();
+    assert_eq!(entries[0].id, 2);
+    assert_eq!(entries[0].node, "rabbit@node2");
+    assert_eq!(entries[1].id, 1);
+    assert_eq!(entries[2].id, 3);
+
+    let ctx = QueryContext::default().add_label("custom:billing");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap()[0].id, 2);
+    let ctx = QueryContext::default().entity(EntityKind::Queue, "orders");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap()[0].id, 3);
+    let ctx = QueryContext::default().entity(EntityKind::Queue, "stored");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap()[0].id, 1);
+}
+
+#[test]
+fn test_replace_shorter_version_of_an_entry() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+    let started_at = Utc::now();
+
+    let mut truncated = create_test_entry("queue 'orders' in vhost '/' crashed:", Severity::Error);
+    truncated.timestamp = started_at;
+    let mut other = create_test_entry("queue 'orders' in vhost '/' crashed:", Severity::Error);
+    other.timestamp = started_at + TimeDelta::seconds(1);
+    NodeLogEntry::insert_parsed_entries(&db, &[truncated.clone(), other], "rabbit@node1").unwrap();
+    MessageSearchIndex::build(&db).unwrap();
+
+    let mut complete = truncated.clone();
+    complete.message.push_str("\n  reason: shutdown_timeout");
+    let mut unrelated = complete.clone();
+    unrelated.process_id = "<0.999.0>".to_string();
+    NodeLogEntry::with_appender(&db, |appender| {
+        assert_eq!(
+            appender
+                .replace_shorter_version(&unrelated, "rabbit@node1")
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            appender
+                .replace_shorter_version(&complete, "rabbit@node2")
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            appender
+                .replace_shorter_version(&complete, "rabbit@node1")
+                .unwrap(),
+            Some(1)
+        );
+        Ok::<_, duckdb::Error>(())
+    })
+    .unwrap();
+
+    let entries = NodeLogEntry::find_all(&db).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].id, 1);
+    assert_eq!(entries[0].message, complete.message);
+    let ctx = QueryContext::default().entity(EntityKind::Queue, "orders");
+    assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), 2);
+    let ctx = QueryContext::default().search(SearchQuery::parse("shutdown_timeout"));
+    let found = NodeLogEntry::query(&db, &ctx).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, 1);
+}
+
+#[test]
+fn test_fingerprints_of_stored_entries() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+    let started_at = Utc::now();
+
+    let mut first = create_test_entry("first", Severity::Info);
+    first.timestamp = started_at;
+    let mut second = create_test_entry("second", Severity::Info);
+    second.timestamp = started_at + TimeDelta::seconds(1);
+    NodeLogEntry::insert_parsed_entries(&db, &[first.clone(), second.clone()], "rabbit@node1")
+        .unwrap();
+
+    NodeLogEntry::with_appender(&db, |appender| {
+        let since_second = appender
+            .fingerprints_since("rabbit@node1", second.timestamp)
+            .unwrap();
+        assert_eq!(since_second.len(), 1);
+        assert!(since_second.contains(&entry_fingerprint(&second)));
+        assert!(!since_second.contains(&entry_fingerprint(&first)));
+
+        let mut copy = second.clone();
+        copy.sequence_id = 42;
+        assert!(since_second.contains(&entry_fingerprint(&copy)));
+        copy.severity = Severity::Warning;
+        assert!(!since_second.contains(&entry_fingerprint(&copy)));
+
+        assert!(
+            appender
+                .fingerprints_since("rabbit@node2", first.timestamp)
+                .unwrap()
+                .is_empty()
+        );
+        Ok::<_, duckdb::Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_obfuscate_database_keeps_urls_and_obfuscates_metadata() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = create_database(&temp_dir.path().join("source.db")).unwrap();
+    let target = create_database(&temp_dir.path().join("obfuscated.db")).unwrap();
+
+    let mut entry = create_test_entry("Accepted connection from 10.0.0.5", Severity::Info);
+    entry.metadata.domain = Some("rabbitmq.connection".to_string());
+    entry.metadata.mfa = Some("rabbit_reader:handle_input/3".to_string());
+    entry.metadata.file = Some("/home/alice/rabbitmq-server/src/rabbit_reader.erl".to_string());
+    entry.custom_annotations.doc_url =
+        Some("https://wiki.megacorp.com/rabbitmq/connections".to_string());
+    entry.custom_annotations.resolution_or_discussion_url =
+        Some("https://github.com/rabbitmq/rabbitmq-server/issues/14181".to_string());
+    NodeLogEntry::insert_parsed_entries(&source, &[entry], "rabbit@sunnyside").unwrap();
+
+    let mut obfuscator = LogObfuscator::new();
+    obfuscate_database(&source, &target, &mut obfuscator).unwrap();
+
+    let obfuscated = NodeLogEntry::find_all(&target).unwrap().pop().unwrap();
+    let file = obfuscated.metadata.file.unwrap();
+    assert!(!file.contains("alice"), "{}", file);
+    assert!(file.ends_with("/rabbit_reader.erl"), "{}", file);
+    assert_eq!(
+        obfuscated.metadata.mfa.as_deref(),
+        Some("rabbit_reader:handle_input/3")
+    );
+    assert_eq!(
+        obfuscated.custom_annotations.doc_url.as_deref(),
+        Some("https://wiki.megacorp.com/rabbitmq/connections")
+    );
+    assert_eq!(
+        obfuscated
+            .custom_annotations
+            .resolution_or_discussion_url
+            .as_deref(),
+        Some("https://github.com/rabbitmq/rabbitmq-server/issues/14181")
+    );
+}
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use rabbitmq_lqt_lib::datetime::parse_datetime_flexible;
+use chrono::{DateTime, Duration, Utc};
+use rabbitmq_lqt_lib::datetime::{
+    TimeAnchor, TimeReference, parse_datetime_flexible, parse_datetime_relative_to,
+};
 
 #[test]
 fn date_only_format() {
@@ -73,3 +76,47 @@ fn invalid_format_returns_error() {
 fn empty_string_returns_error() {
     assert!(parse_datetime_flexible("").is_err());
 }
+
+fn reference() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2025-10-27T18:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+#[test]
+fn relative_expression_uses_reference_time() {
+    let dt = parse_datetime_relative_to("2 hours ago", reference()).unwrap();
+    assert_eq!(dt, reference() - Duration::hours(2));
+}
+
+#[test]
+fn absolute_datetime_ignores_reference_time() {
+    let dt = parse_datetime_relative_to("2025-01-01 00:00:00", reference()).unwrap();
+    assert_eq!(dt.format("%Y-%m-%d").to_string(), "2025-01-01");
+}
+
+#[test]
+fn time_anchor_from_str() {
+    assert_eq!("now".parse::<TimeAnchor>().unwrap(), TimeAnchor::Now);
+    assert_eq!("log-end".parse::<TimeAnchor>().unwrap(), TimeAnchor::LogEnd);
+    assert_eq!("end".parse::<TimeAnchor>().unwrap(), TimeAnchor::LogEnd);
+    assert_eq!(
+        "2025-10-27T18:00:00Z".parse::<TimeAnchor>().unwrap(),
+        TimeAnchor::At(reference())
+    );
+    assert!("not-a-date-at-all".parse::<TimeAnchor>().is_err());
+}
+
+#[test]
+fn time_reference_resolves_log_end() {
+    let time_ref = TimeReference::new(TimeAnchor::LogEnd, Some(reference()));
+    assert_eq!(time_ref.reference_time(), reference());
+    assert_eq!(time_ref.resolve(TimeAnchor::At(reference())), reference());
+}
+
+#[test]
+fn time_reference_log_end_falls_back_to_now() {
+    let time_ref = TimeReference::new(TimeAnchor::LogEnd, None);
+    let before = Utc::now();
+    assert!(time_ref.reference_time() >= before);
+}
@@ -0,0 +1,158 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use rabbitmq_lqt_lib::rel_db::file_metadata::Model as FileMetadataModel;
+use rabbitmq_lqt_lib::{CountDimension, Fact, LogDiff, LogSetSummary};
+
+fn file_of(node: &str, rabbitmq_version: &str, plugins: &[&str]) -> FileMetadataModel {
+    FileMetadataModel {
+        file_path: format!("/var/log/rabbitmq/{}.log", node),
+        rabbitmq_versions: vec![rabbitmq_version.to_string()],
+        erlang_versions: vec!["27.3.4".to_string()],
+        tls_library: Some("OpenSSL 3.5.0".to_string()),
+        oldest_entry_at: None,
+        most_recent_entry_at: None,
+        total_lines: 0,
+        total_entries: 0,
+        nodes: vec![node.to_string()],
+        subsystems: vec![],
+        labels: vec![],
+        enabled_plugins: plugins.iter().map(|p| p.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_identical_summaries_have_no_differences() {
+    let mut summary = LogSetSummary::default();
+    summary.add_count("rabbit@node1", CountDimension::Severity, "info", 10);
+    summary.add_pattern("rabbit@node1", "accepting AMQP connection <ip> -> <ip>", 10);
+    summary
+        .files
+        .push(file_of("rabbit@node1", "4.2.0", &["rabbitmq_management"]));
+
+    let diff = LogDiff::build(&summary, &summary);
+    assert!(diff.is_empty());
+    assert_eq!(diff.baseline_entries, 10);
+    assert_eq!(diff.comparison_entries, 10);
+}
+
+#[test]
+fn test_new_and_vanished_patterns() {
+    let mut baseline = LogSetSummary::default();
+    baseline.add_pattern("rabbit@node1", "accepting AMQP connection <ip> -> <ip>", 5);
+    baseline.add_pattern(
+        "rabbit@node1",
+        "Server startup complete; <n> plugins started.",
+        1,
+    );
+
+    let mut comparison = LogSetSummary::default();
+    comparison.add_pattern("rabbit@node1", "accepting AMQP connection <ip> -> <ip>", 7);
+    comparison.add_pattern(
+        "rabbit@node1",
+        "Node <node> is down, deleting its listeners",
+        2,
+    );
+    comparison.add_pattern(
+        "rabbit@node2",
+        "Node <node> is down, deleting its listeners",
+        3,
+    );
+    comparison.add_pattern("rabbit@node2", "Partial partition detected", 1);
+
+    let diff = LogDiff::build(&baseline, &comparison);
+
+    assert_eq!(diff.new_patterns.len(), 2);
+    assert_eq!(
+        diff.new_patterns[0].template,
+        "Node <node> is down, deleting its listeners"
+    );
+    assert_eq!(diff.new_patterns[0].entry_count, 5);
+    assert_eq!(
+        diff.new_patterns[0].nodes,
+        vec!["rabbit@node1".to_string(), "rabbit@node2".to_string()]
+    );
+
+    assert_eq!(diff.vanished_patterns.len(), 1);
+    assert_eq!(
+        diff.vanished_patterns[0].template,
+        "Server startup complete; <n> plugins started."
+    );
+}
+
+#[test]
+fn test_count_deltas_per_node() {
+    let mut baseline = LogSetSummary::default();
+    baseline.add_count("rabbit@node1", CountDimension::Severity, "info", 100);
+    baseline.add_count("rabbit@node1", CountDimension::Severity, "error", 1);
+    baseline.add_count("rabbit@node1", CountDimension::Label, "connections", 40);
+
+    let mut comparison = LogSetSummary::default();
+    comparison.add_count("rabbit@node1", CountDimension::Severity, "info", 100);
+    comparison.add_count("rabbit@node1", CountDimension::Severity, "error", 25);
+    comparison.add_count("rabbit@node1", CountDimension::Subsystem, "raft", 12);
+
+    let diff = LogDiff::build(&baseline, &comparison);
+
+    assert_eq!(diff.baseline_entries, 101);
+    assert_eq!(diff.comparison_entries, 125);
+    assert_eq!(diff.count_deltas.len(), 3);
+
+    let error = &diff.count_deltas[0];
+    assert_eq!(error.dimension, CountDimension::Severity);
+    assert_eq!(error.value, "error");
+    assert_eq!(error.delta(), 24);
+
+    let raft = &diff.count_deltas[1];
+    assert_eq!(raft.dimension, CountDimension::Subsystem);
+    assert_eq!(raft.baseline, 0);
+    assert_eq!(raft.comparison, 12);
+
+    let connections = &diff.count_deltas[2];
+    assert_eq!(connections.dimension, CountDimension::Label);
+    assert_eq!(connections.delta(), -40);
+}
+
+#[test]
+fn test_fact_changes_of_shared_nodes() {
+    let mut baseline = LogSetSummary::default();
+    baseline.add_count("rabbit@node1", CountDimension::Severity, "info", 1);
+    baseline
+        .files
+        .push(file_of("rabbit@node1", "4.1.4", &["rabbitmq_management"]));
+
+    let mut comparison = LogSetSummary::default();
+    comparison.add_count("rabbit@node1", CountDimension::Severity, "info", 1);
+    comparison.add_count("rabbit@node2", CountDimension::Severity, "info", 1);
+    comparison.files.push(file_of(
+        "rabbit@node1",
+        "4.2.0",
+        &["rabbitmq_management", "rabbitmq_shovel"],
+    ));
+    comparison.files.push(file_of("rabbit@node2", "4.2.0", &[]));
+
+    let diff = LogDiff::build(&baseline, &comparison);
+
+    assert_eq!(diff.fact_changes.len(), 2);
+    assert!(diff.fact_changes.iter().all(|c| c.node == "rabbit@node1"));
+
+    let version = &diff.fact_changes[0];
+    assert_eq!(version.fact, Fact::RabbitmqVersion);
+    assert_eq!(version.added, vec!["4.2.0".to_string()]);
+    assert_eq!(version.removed, vec!["4.1.4".to_string()]);
+
+    let plugins = &diff.fact_changes[1];
+    assert_eq!(plugins.fact, Fact::EnabledPlugin);
+    assert_eq!(plugins.added, vec!["rabbitmq_shovel".to_string()]);
+    assert!(plugins.removed.is_empty());
+}
@@ -0,0 +1,147 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::test_helpers::create_test_entry;
+use rabbitmq_lqt_lib::entry_metadata::{
+    Entity, EntityKind, annotate_structured_metadata, extract_entities,
+};
+use rabbitmq_lqt_lib::{Severity, StructuredMetadata};
+use std::str::FromStr;
+
+fn entities_of(message: &str) -> Vec<Entity> {
+    let mut entry = create_test_entry(message, Severity::Info);
+    annotate_structured_metadata(&mut entry);
+    extract_entities(&entry.message, &entry.metadata)
+}
+
+#[test]
+fn test_extracts_queue_with_its_vhost() {
+    let entities = entities_of(
+        "queue 'queue1' in vhost 'vhost1': detected a new leader {'queue1','rabbit@host5'} in term 1",
+    );
+
+    assert_eq!(
+        entities,
+        vec![
+            Entity::new(EntityKind::Queue, Some("vhost1".to_string()), "queue1"),
+            Entity::new(EntityKind::Vhost, None, "vhost1"),
+        ]
+    );
+}
+
+#[test]
+fn test_extracts_stream_in_virtual_host() {
+    let entities =
+        entities_of("stream 'stream1' in virtual host 'vhost4' for stream coordinator restarted");
+
+    assert!(entities.contains(&Entity::new(
+        EntityKind::Stream,
+        Some("vhost4".to_string()),
+        "stream1"
+    )));
+    assert!(entities.contains(&Entity::new(EntityKind::Vhost, None, "vhost4")));
+}
+
+#[test]
+fn test_extracts_exchange_user_and_vhost() {
+    let entities = entities_of(
+        "Successfully set topic permissions on exchange 'amq.topic' for user 'user5' in virtual host 'vhost4'",
+    );
+
+    assert!(entities.contains(&Entity::new(
+        EntityKind::Exchange,
+        Some("vhost4".to_string()),
+        "amq.topic"
+    )));
+    assert!(entities.contains(&Entity::new(EntityKind::User, None, "user5")));
+    assert!(entities.contains(&Entity::new(EntityKind::Vhost, None, "vhost4")));
+}
+
+#[test]
+fn test_skips_the_default_exchange() {
+    let entities =
+        entities_of("Successfully set topic permissions on exchange '' for user 'user5'");
+
+    assert!(!entities.iter().any(|e| e.kind == EntityKind::Exchange));
+}
+
+#[test]
+fn test_extracts_queue_from_ra_server_id() {
+    let entities = entities_of("Requesting {'orders','rabbit@host2'} from 39357");
+
+    assert_eq!(
+        entities,
+        vec![Entity::new(EntityKind::Queue, None, "orders")]
+    );
+}
+
+#[test]
+fn test_extracts_connection_and_user() {
+    let entities = entities_of(
+        "connection 10.0.0.2:8959 -> 10.0.0.1:5672: user 'user2' authenticated and granted access to vhost 'vhost1'",
+    );
+
+    assert!(entities.contains(&Entity::new(
+        EntityKind::Connection,
+        None,
+        "10.0.0.2:8959 -> 10.0.0.1:5672"
+    )));
+    assert!(entities.contains(&Entity::new(EntityKind::User, None, "user2")));
+    assert!(entities.contains(&Entity::new(EntityKind::Vhost, None, "vhost1")));
+}
+
+#[test]
+fn test_uses_structured_metadata_vhost_for_queues() {
+    let metadata = StructuredMetadata {
+        vhost: Some("/".to_string()),
+        ..StructuredMetadata::default()
+    };
+    let entities = extract_entities("Queue 'orders' was declared", &metadata);
+
+    assert_eq!(
+        entities,
+        vec![
+            Entity::new(EntityKind::Queue, Some("/".to_string()), "orders"),
+            Entity::new(EntityKind::Vhost, None, "/"),
+        ]
+    );
+}
+
+#[test]
+fn test_each_entity_is_extracted_once() {
+    let entities = entities_of("queue 'q1' in vhost 'v1' and queue 'q1' in vhost 'v1'");
+
+    assert_eq!(
+        entities
+            .iter()
+            .filter(|e| e.kind == EntityKind::Queue)
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_messages_without_entities() {
+    assert!(entities_of("Server startup complete; 5 plugins started.").is_empty());
+}
+
+#[test]
+fn test_entity_kind_round_trip() {
+    for kind in EntityKind::ALL {
+        assert_eq!(EntityKind::from_str(kind.as_str()).unwrap(), kind);
+        assert_eq!(kind.to_string(), kind.as_str());
+    }
+    assert_eq!(EntityKind::from_str("Queue").unwrap(), EntityKind::Queue);
+    assert!(EntityKind::from_str("binding").is_err());
+}
@@ -0,0 +1,94 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rabbitmq_lqt_lib::file_set_metadata::extract_file_metadata;
+use rabbitmq_lqt_lib::rel_db::file_ingestion_state::Model;
+use rabbitmq_lqt_lib::rel_db::{FileIngestionState, FileMetadata};
+use rabbitmq_lqt_lib::{create_database, migrate_database, open_database};
+use tempfile::TempDir;
+
+fn ingestion_state(file_path: &str, ingested_bytes: u64) -> Model {
+    Model {
+        file_path: file_path.to_string(),
+        node: "rabbit@sunnyside".to_string(),
+        inode: Some(1234),
+        file_size: ingested_bytes,
+        ingested_bytes,
+        tail_lines: 1,
+        head_length: 64,
+        head_hash: "a1b2c3".to_string(),
+    }
+}
+
+#[test]
+fn test_file_ingestion_state_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database(&temp_dir.path().join("state.db")).unwrap();
+
+    assert_eq!(
+        FileIngestionState::find(&db, "/var/log/rabbit.log").unwrap(),
+        None
+    );
+
+    let state = ingestion_state("/var/log/rabbit.log", 1024);
+    FileIngestionState::upsert(&db, &state).unwrap();
+    assert_eq!(
+        FileIngestionState::find(&db, "/var/log/rabbit.log").unwrap(),
+        Some(state)
+    );
+
+    let grown = Model {
+        inode: None,
+        ..ingestion_state("/var/log/rabbit.log", 4096)
+    };
+    FileIngestionState::upsert(&db, &grown).unwrap();
+    FileIngestionState::upsert(&db, &ingestion_state("/var/log/other.log", 16)).unwrap();
+
+    let states = FileIngestionState::find_all(&db).unwrap();
+    assert_eq!(states.len(), 2);
+    assert_eq!(states[0].file_path, "/var/log/other.log");
+    assert_eq!(states[1], grown);
+}
+
+#[test]
+fn test_file_metadata_find() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database(&temp_dir.path().join("metadata.db")).unwrap();
+    let metadata = extract_file_metadata(&[], "/var/log/rabbit.log".to_string(), "rabbit@a", 3);
+    FileMetadata::insert_metadata(&db, metadata.clone()).unwrap();
+
+    assert_eq!(
+        FileMetadata::find(&db, "/var/log/rabbit.log").unwrap(),
+        Some(metadata)
+    );
+    assert_eq!(FileMetadata::find(&db, "/var/log/other.log").unwrap(), None);
+}
+
+#[test]
+fn test_open_database_creates_file_ingestion_state_table() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("pre_ingestion_state.db");
+
+    {
+        let db = create_database(&db_path).unwrap();
+        db.get()
+            .unwrap()
+            .execute_batch("DROP TABLE file_ingestion_state")
+            .unwrap();
+    }
+
+    let db = open_database(&db_path).unwrap();
+    assert!(FileIngestionState::find_all(&db).unwrap().is_empty());
+    assert!(!migrate_database(&db).unwrap());
+}
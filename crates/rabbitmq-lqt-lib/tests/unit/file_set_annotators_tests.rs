@@ -15,8 +15,8 @@
 use chrono::Utc;
 use rabbitmq_lqt_lib::Severity;
 use rabbitmq_lqt_lib::entry_metadata::labels::LogEntryLabels;
-use rabbitmq_lqt_lib::file_set_metadata::extract_file_metadata;
-use rabbitmq_lqt_lib::parser::ParsedLogEntry;
+use rabbitmq_lqt_lib::file_set_metadata::{FileMetadataContext, extract_file_metadata};
+use rabbitmq_lqt_lib::parser::{ParsedLogEntry, StructuredMetadata};
 use std::slice;
 
 fn create_test_entry(
@@ -36,6 +36,8 @@ fn create_test_entry(
         labels,
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     }
 }
 
@@ -415,3 +417,81 @@ fn test_multiline_entries_count_correctly() {
     assert_eq!(metadata.total_entries, 1);
     assert_eq!(metadata.total_lines, 5);
 }
+
+#[test]
+fn test_adding_entries_in_chunks_matches_extracting_at_once() {
+    let entries = vec![
+        create_test_entry(
+            "Starting RabbitMQ 4.2.0 on Erlang 27.3.4.3",
+            Some(1),
+            LogEntryLabels::empty(),
+        ),
+        create_test_entry(
+            "TLS/DTLS library: OpenSSL 3.0.13",
+            None,
+            LogEntryLabels::TLS,
+        ),
+        create_test_entry("Test message", Some(2), LogEntryLabels::QUEUES),
+        create_test_entry(
+            "Server startup complete; 1 plugins started.\n * rabbitmq_management",
+            None,
+            LogEntryLabels::empty(),
+        ),
+    ];
+
+    let mut ctx = FileMetadataContext::default();
+    ctx.nodes.insert("rabbit@node1".to_string());
+    ctx.total_lines = 5;
+    for chunk in entries.chunks(3) {
+        ctx.add_entries(chunk);
+    }
+    ctx.add_entries(&[]);
+
+    let expected = extract_file_metadata(&entries, "/tmp/test.log".to_string(), "rabbit@node1", 5);
+    let metadata = ctx.to_model("/tmp/test.log".to_string());
+    assert_eq!(metadata, expected);
+    assert_eq!(metadata.total_entries, 4);
+    assert_eq!(metadata.oldest_entry_at, Some(entries[0].timestamp));
+    assert_eq!(metadata.most_recent_entry_at, Some(entries[3].timestamp));
+}
+
+#[test]
+fn test_file_metadata_context_resumes_from_stored_metadata() {
+    let stored_entries = vec![
+        create_test_entry(
+            "Starting RabbitMQ 4.1.0 on Erlang 27.0",
+            None,
+            LogEntryLabels::empty(),
+        ),
+        create_test_entry("Test message", Some(2), LogEntryLabels::QUEUES),
+    ];
+    let stored = extract_file_metadata(
+        &stored_entries,
+        "/tmp/test.log".to_string(),
+        "rabbit@node1",
+        2,
+    );
+
+    let appended_entries = vec![create_test_entry(
+        "TLS/DTLS library: OpenSSL 3.0.13",
+        None,
+        LogEntryLabels::TLS,
+    )];
+    let mut ctx = FileMetadataContext::from_model(stored.clone());
+    ctx.total_lines += 1;
+    ctx.add_entries(&appended_entries);
+
+    let metadata = ctx.to_model("/tmp/test.log".to_string());
+    assert_eq!(metadata.total_entries, 3);
+    assert_eq!(metadata.total_lines, 3);
+    assert_eq!(metadata.rabbitmq_versions, vec!["4.1.0"]);
+    assert_eq!(metadata.tls_library, Some("OpenSSL".to_string()));
+    assert_eq!(metadata.nodes, vec!["rabbit@node1"]);
+    assert!(metadata.labels.contains(&"queues".to_string()));
+    assert!(metadata.labels.contains(&"tls".to_string()));
+    assert_eq!(metadata.oldest_entry_at, stored.oldest_entry_at);
+    assert_eq!(
+        metadata.most_recent_entry_at,
+        Some(appended_entries[0].timestamp)
+    );
+}
@@ -12,8 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use rabbitmq_lqt_lib::Severity;
-use rabbitmq_lqt_lib::parser::IncrementalParser;
+use rabbitmq_lqt_lib::parser::{IncrementalParser, LogEntryStream, LogFormat, parse_log_file};
+use rabbitmq_lqt_lib::{Error, Severity};
+use std::io::{BufReader, Cursor, Error as IoError, Read};
 
 #[test]
 fn single_line_entry_returned_on_flush() {
@@ -211,3 +212,128 @@ fn feed_after_flush_starts_fresh() {
     assert_eq!(entry2.sequence_id, 1);
     assert!(entry2.message.contains("accepting AMQP connection"));
 }
+
+#[test]
+fn counts_fed_lines() {
+    let mut parser = IncrementalParser::new(0);
+    parser.feed_line("2025-10-27 11:23:27.568937-07:00 [debug] <0.208.0> Starting Ra system called \"coordination\" with configuration:");
+    parser.feed_line("#{message_queue_data => off_heap,name => coordination,");
+    parser.feed_line("");
+
+    assert_eq!(parser.total_lines(), 3);
+}
+
+const MULTILINE_LOG: &str = "2025-10-27 11:23:27.566558-07:00 [notice] <0.208.0> Logging: configured log handlers are now ACTIVE\r\n\
+2025-10-27 11:23:27.568937-07:00 [debug] <0.208.0> Starting Ra system called \"coordination\" with configuration:\r\n\
+#{message_queue_data => off_heap,name => coordination,\r\n\
+  names => ra_coordination}\r\n\
+2025-10-27 11:23:28.566588-07:00 [info] <0.301.0> accepting AMQP connection <0.301.0>";
+
+#[test]
+fn stream_yields_the_same_entries_as_parse_log_file() {
+    let parsed = parse_log_file(BufReader::new(MULTILINE_LOG.as_bytes())).unwrap();
+    let mut stream = LogEntryStream::new(BufReader::new(MULTILINE_LOG.as_bytes()));
+    let streamed: Vec<_> = stream.by_ref().collect::<Result<_, _>>().unwrap();
+
+    assert_eq!(streamed, parsed.entries);
+    assert_eq!(streamed.len(), 3);
+    assert_eq!(streamed[1].message.matches('\n').count(), 2);
+    assert!(!streamed[1].message.contains('\r'));
+    assert_eq!(stream.total_lines(), parsed.total_lines);
+    assert_eq!(stream.total_lines(), 5);
+    assert_eq!(stream.format(), Some(LogFormat::Plaintext));
+}
+
+#[test]
+fn stream_counts_bytes_read() {
+    let mut stream = LogEntryStream::new(BufReader::new(MULTILINE_LOG.as_bytes()));
+
+    let first = stream.next().unwrap().unwrap();
+    assert_eq!(first.sequence_id, 0);
+    assert!(stream.bytes_read() < MULTILINE_LOG.len() as u64);
+
+    assert_eq!(stream.by_ref().count(), 2);
+    assert_eq!(stream.bytes_read(), MULTILINE_LOG.len() as u64);
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn stream_defers_an_incomplete_last_line_and_holds_back_the_last_entry() {
+    // MULTILINE_LOG does not end with a line ending, so its last entry might still be written
+    let mut stream =
+        LogEntryStream::new(BufReader::new(MULTILINE_LOG.as_bytes())).deferring_incomplete_lines();
+    let streamed: Vec<_> = stream.by_ref().collect::<Result<_, _>>().unwrap();
+
+    // More lines of the Ra system entry can follow, so it is held back along with its offset
+    assert_eq!(streamed.len(), 1);
+    let first_line = MULTILINE_LOG.split_inclusive('\n').next().unwrap();
+    assert_eq!(stream.bytes_read(), first_line.len() as u64);
+    assert_eq!(stream.total_lines(), 4);
+    assert_eq!(stream.held_back_lines(), 3);
+    let held_back = stream.take_held_back().unwrap();
+    assert_eq!(held_back.message.matches('\n').count(), 2);
+    assert!(stream.next().is_none());
+    assert!(stream.take_held_back().is_none());
+
+    let complete = format!("{}\n", MULTILINE_LOG);
+    let mut stream =
+        LogEntryStream::new(BufReader::new(complete.as_bytes())).deferring_incomplete_lines();
+    assert_eq!(stream.by_ref().count(), 2);
+    let last_line = MULTILINE_LOG.rsplit('\n').next().unwrap();
+    assert_eq!(
+        stream.bytes_read(),
+        (MULTILINE_LOG.len() - last_line.len()) as u64
+    );
+    assert_eq!(stream.held_back_lines(), 1);
+    assert_eq!(stream.take_held_back().unwrap().process_id, "<0.301.0>");
+}
+
+#[test]
+fn stream_holds_back_the_last_entry_including_an_incomplete_last_line() {
+    let mut stream =
+        LogEntryStream::new(BufReader::new(MULTILINE_LOG.as_bytes())).holding_back_last_entry();
+    assert_eq!(stream.by_ref().count(), 2);
+
+    let last_line = MULTILINE_LOG.rsplit('\n').next().unwrap();
+    assert_eq!(
+        stream.bytes_read(),
+        (MULTILINE_LOG.len() - last_line.len()) as u64
+    );
+    assert_eq!(stream.total_lines(), 5);
+    assert_eq!(stream.held_back_lines(), 1);
+    assert_eq!(
+        stream.take_held_back().unwrap().message,
+        "accepting AMQP connection <0.301.0>"
+    );
+}
+
+#[test]
+fn stream_with_format_skips_detection() {
+    let json = r#"{"time":"2025-10-27 18:21:12.330517+00:00","level":"info","msg":"accepting AMQP connection","pid":"<0.1204.0>"}"#;
+    let mut stream = LogEntryStream::with_format(Cursor::new(json), LogFormat::Json);
+
+    let entry = stream.next().unwrap().unwrap();
+    assert_eq!(entry.message, "accepting AMQP connection");
+    assert!(stream.next().is_none());
+}
+
+struct FailingReader;
+
+impl Read for FailingReader {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Err(IoError::other("disk on fire"))
+    }
+}
+
+#[test]
+fn stream_reports_the_line_that_failed_to_read() {
+    let mut stream = LogEntryStream::new(BufReader::new(
+        Cursor::new("2025-10-27 11:23:27.566558-07:00 [notice] <0.208.0> Logging: configured\n")
+            .chain(FailingReader),
+    ));
+
+    match stream.next() {
+        Some(Err(Error::ReadLine { line, .. })) => assert_eq!(line, 2),
+        other => panic!("expected a read error, got {:?}", other),
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono::{TimeZone, Utc};
+use rabbitmq_lqt_lib::parser::{
+    IncrementalParser, LogFormat, parse_json_log_entry, parse_log_file_with_format,
+};
+use rabbitmq_lqt_lib::{Severity, parse_log_file};
+use std::io::BufReader;
+
+const JSON_LINE: &str = r#"{"time":"2025-10-27 11:23:27.566558-07:00","level":"notice","msg":"Logging: configured log handlers are now ACTIVE","pid":"<0.208.0>","domain":"rabbitmq.prelaunch","mfa":["rabbit_prelaunch_logging","configure_logger",1],"file":"rabbit_prelaunch_logging.erl","line":578}"#;
+
+#[test]
+fn test_parse_json_entry() {
+    let entry = parse_json_log_entry(JSON_LINE).expect("should parse");
+
+    assert_eq!(
+        entry.timestamp,
+        Utc.with_ymd_and_hms(2025, 10, 27, 18, 23, 27).unwrap()
+            + chrono::Duration::microseconds(566558)
+    );
+    assert_eq!(entry.severity, Severity::Notice);
+    assert_eq!(entry.process_id, "<0.208.0>");
+    assert_eq!(
+        entry.message,
+        "Logging: configured log handlers are now ACTIVE"
+    );
+    assert_eq!(
+        entry.message_lowercased,
+        "logging: configured log handlers are now active"
+    );
+    assert_eq!(entry.metadata.domain.as_deref(), Some("rabbitmq.prelaunch"));
+    assert_eq!(
+        entry.metadata.mfa.as_deref(),
+        Some("rabbit_prelaunch_logging:configure_logger/1")
+    );
+    assert_eq!(
+        entry.metadata.file.as_deref(),
+        Some("rabbit_prelaunch_logging.erl")
+    );
+    assert_eq!(entry.metadata.line, Some(578));
+    assert!(entry.metadata.vhost.is_none());
+}
+
+#[test]
+fn test_parse_json_entry_with_connection_metadata() {
+    let line = r#"{"time":"2025-10-27 18:21:12.341008+00:00","level":"info","msg":"user 'user2' authenticated","pid":"<0.1204.0>","connection":"10.0.0.2:57586 -> 10.0.0.1:5672","vhost":"vhost1","user":"user2"}"#;
+    let entry = parse_json_log_entry(line).expect("should parse");
+
+    assert_eq!(
+        entry.metadata.connection.as_deref(),
+        Some("10.0.0.2:57586 -> 10.0.0.1:5672")
+    );
+    assert_eq!(entry.metadata.vhost.as_deref(), Some("vhost1"));
+    assert_eq!(entry.metadata.user.as_deref(), Some("user2"));
+    assert!(entry.metadata.domain.is_none());
+}
+
+#[test]
+fn test_parse_json_entry_with_multiline_message() {
+    let line = r#"{"time":"2025-10-27 18:22:40.002117+00:00","level":"warning","msg":"closing AMQP connection:\nclient unexpectedly closed TCP connection","pid":"<0.1204.0>"}"#;
+    let entry = parse_json_log_entry(line).expect("should parse");
+
+    assert!(entry.is_multiline());
+    assert_eq!(
+        entry.message,
+        "closing AMQP connection:\nclient unexpectedly closed TCP connection"
+    );
+}
+
+#[test]
+fn test_parse_json_entry_time_formats() {
+    let expected = Utc.with_ymd_and_hms(2025, 10, 27, 18, 23, 0).unwrap();
+
+    for time in [
+        r#""2025-10-27 18:23:00.000000+00:00""#,
+        r#""2025-10-27T18:23:00.000000+00:00""#,
+        r#""2025-10-27T18:23:00Z""#,
+        "1761589380000000",
+        "1761589380",
+    ] {
+        let line = format!(
+            r#"{{"time":{},"level":"info","msg":"Test","pid":"<0.1.0>"}}"#,
+            time
+        );
+        let entry =
+            parse_json_log_entry(&line).unwrap_or_else(|| panic!("should parse time {}", time));
+        assert_eq!(entry.timestamp, expected, "time: {}", time);
+    }
+}
+
+#[test]
+fn test_parse_json_entry_levels() {
+    for (level, severity) in [
+        ("debug", Severity::Debug),
+        ("info", Severity::Info),
+        ("notice", Severity::Notice),
+        ("warning", Severity::Warning),
+        ("error", Severity::Error),
+        ("critical", Severity::Critical),
+        ("alert", Severity::Critical),
+        ("emergency", Severity::Critical),
+    ] {
+        let line = format!(
+            r#"{{"time":"2025-10-27 18:23:00.000000+00:00","level":"{}","msg":"Test"}}"#,
+            level
+        );
+        let entry = parse_json_log_entry(&line).expect("should parse");
+        assert_eq!(entry.severity, severity, "level: {}", level);
+    }
+}
+
+#[test]
+fn test_parse_json_entry_without_pid() {
+    let line = r#"{"time":"2025-10-27 18:23:00.000000+00:00","level":"info","msg":"Test"}"#;
+    let entry = parse_json_log_entry(line).expect("should parse");
+    assert_eq!(entry.process_id, "<0.0.0>");
+    assert!(entry.metadata.is_empty());
+}
+
+#[test]
+fn test_parse_json_entry_rejects_invalid_input() {
+    assert!(parse_json_log_entry("").is_none());
+    assert!(parse_json_log_entry("not json").is_none());
+    assert!(parse_json_log_entry("{\"time\":").is_none());
+    assert!(parse_json_log_entry(r#"{"level":"info","msg":"No time"}"#).is_none());
+    assert!(
+        parse_json_log_entry(
+            r#"{"time":"2025-10-27 18:23:00.000000+00:00","level":"verbose","msg":"Test"}"#
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn test_log_format_detection() {
+    assert_eq!(LogFormat::detect(JSON_LINE), LogFormat::Json);
+    assert_eq!(
+        LogFormat::detect("2025-10-27 11:23:27.566558-07:00 [notice] <0.208.0> Message"),
+        LogFormat::Plaintext
+    );
+    assert_eq!(
+        LogFormat::detect("=INFO REPORT==== 4-Dec-2025::19:22:30.888840 ==="),
+        LogFormat::Plaintext
+    );
+}
+
+#[test]
+fn test_parse_log_file_detects_json_format() {
+    let input = format!(
+        "\n{}\n{}\n",
+        JSON_LINE,
+        r#"{"time":"2025-10-27 11:23:27.566558-07:00","level":"notice","msg":"Same timestamp, severity and pid","pid":"<0.208.0>"}"#
+    );
+    let result = parse_log_file(BufReader::new(input.as_bytes())).unwrap();
+
+    assert_eq!(result.format, LogFormat::Json);
+    assert_eq!(result.total_lines, 3);
+    // unlike plain text lines, JSON entries with identical timestamps are not merged
+    assert_eq!(result.entries.len(), 2);
+    assert_eq!(result.entries[0].sequence_id, 0);
+    assert_eq!(result.entries[1].sequence_id, 1);
+    assert_eq!(
+        result.entries[1].message,
+        "Same timestamp, severity and pid"
+    );
+}
+
+#[test]
+fn test_parse_log_file_detects_plaintext_format() {
+    let input = "2025-10-27 11:23:27.566558-07:00 [notice] <0.208.0> Message";
+    let result = parse_log_file(BufReader::new(input.as_bytes())).unwrap();
+
+    assert_eq!(result.format, LogFormat::Plaintext);
+    assert_eq!(result.entries.len(), 1);
+    assert!(result.entries[0].metadata.is_empty());
+}
+
+#[test]
+fn test_parse_log_file_with_explicit_format() {
+    let result =
+        parse_log_file_with_format(BufReader::new(JSON_LINE.as_bytes()), LogFormat::Plaintext)
+            .unwrap();
+    assert!(result.entries.is_empty());
+
+    let result =
+        parse_log_file_with_format(BufReader::new(JSON_LINE.as_bytes()), LogFormat::Json).unwrap();
+    assert_eq!(result.entries.len(), 1);
+}
+
+#[test]
+fn test_incremental_parser_detects_json_format() {
+    let mut parser = IncrementalParser::new(0);
+    assert!(parser.format().is_none());
+
+    assert!(parser.feed_line(JSON_LINE).is_none());
+    assert_eq!(parser.format(), Some(LogFormat::Json));
+
+    let first = parser
+        .feed_line(JSON_LINE)
+        .expect("first entry is complete");
+    assert_eq!(first.sequence_id, 0);
+    assert_eq!(first.metadata.line, Some(578));
+
+    let second = parser.flush().expect("second entry is buffered");
+    assert_eq!(second.sequence_id, 1);
+}
+
+#[test]
+fn test_log_format_from_str() {
+    assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+    assert_eq!(
+        "plaintext".parse::<LogFormat>().unwrap(),
+        LogFormat::Plaintext
+    );
+    assert!("xml".parse::<LogFormat>().is_err());
+}
@@ -730,7 +730,7 @@ fn test_real_rabbitmq_log_fixture_annotation() {
 }
 
 #[test]
-fn test_label_names_count_matches_bitflags() {
+fn test_label_names_count_matches_label_constants() {
     assert_eq!(
         LABEL_NAMES.len(),
         54,
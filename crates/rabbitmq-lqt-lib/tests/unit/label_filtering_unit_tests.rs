@@ -14,7 +14,9 @@
 
 use chrono::Utc;
 use rabbitmq_lqt_lib::entry_metadata::labels::LogEntryLabels;
-use rabbitmq_lqt_lib::{NodeLogEntry, ParsedLogEntry, QueryContext, Severity, create_database};
+use rabbitmq_lqt_lib::{
+    NodeLogEntry, ParsedLogEntry, QueryContext, Severity, StructuredMetadata, create_database,
+};
 use tempfile::TempDir;
 
 #[test]
@@ -46,6 +48,8 @@ fn test_query_with_multiple_labels_or_logic() {
             labels: labels_crash,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -59,6 +63,8 @@ fn test_query_with_multiple_labels_or_logic() {
             labels: labels_undef,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -72,6 +78,8 @@ fn test_query_with_multiple_labels_or_logic() {
             labels: labels_both,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -85,6 +93,8 @@ fn test_query_with_multiple_labels_or_logic() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -127,6 +137,8 @@ fn test_query_with_multiple_labels_and_logic() {
             labels: labels_crash,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -140,6 +152,8 @@ fn test_query_with_multiple_labels_and_logic() {
             labels: labels_undef,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -153,6 +167,8 @@ fn test_query_with_multiple_labels_and_logic() {
             labels: labels_both,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -166,6 +182,8 @@ fn test_query_with_multiple_labels_and_logic() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -212,6 +230,8 @@ fn test_query_with_three_labels_and_logic() {
             },
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -225,6 +245,8 @@ fn test_query_with_three_labels_and_logic() {
             labels: labels_two,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -238,6 +260,8 @@ fn test_query_with_three_labels_and_logic() {
             labels: labels_three,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -285,6 +309,8 @@ fn test_query_with_three_labels_or_logic() {
             },
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -298,6 +324,8 @@ fn test_query_with_three_labels_or_logic() {
             labels: labels_two,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -311,6 +339,8 @@ fn test_query_with_three_labels_or_logic() {
             labels: labels_three,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -324,6 +354,8 @@ fn test_query_with_three_labels_or_logic() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -360,6 +392,8 @@ fn test_single_label_unaffected_by_matching_all_labels() {
             labels: labels_crash,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -373,6 +407,8 @@ fn test_single_label_unaffected_by_matching_all_labels() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -418,6 +454,8 @@ fn test_and_logic_with_no_matching_entries() {
             labels: labels_crash,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -431,6 +469,8 @@ fn test_and_logic_with_no_matching_entries() {
             labels: labels_undef,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -467,6 +507,8 @@ fn test_and_logic_combined_with_other_filters() {
             labels: labels_both,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -480,6 +522,8 @@ fn test_and_logic_combined_with_other_filters() {
             labels: labels_both,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -493,6 +537,8 @@ fn test_and_logic_combined_with_other_filters() {
             labels: LogEntryLabels::default(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -537,6 +583,8 @@ fn test_new_labels_with_and_logic() {
             labels: labels_both,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -550,6 +598,8 @@ fn test_new_labels_with_and_logic() {
             labels: labels_queues,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 0,
@@ -563,6 +613,8 @@ fn test_new_labels_with_and_logic() {
             labels: labels_delete,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
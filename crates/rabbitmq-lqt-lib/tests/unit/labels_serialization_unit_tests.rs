@@ -118,7 +118,7 @@ fn test_serialize_deserialize_roundtrip_each_label_individually() {
         let deserialized: LogEntryLabels = serde_json::from_str(&serialized).unwrap();
         assert_eq!(
             label, deserialized,
-            "Round-trip failed for label with bits {:?}",
+            "Round-trip failed for label {:?}",
             label
         );
     }
@@ -0,0 +1,81 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rabbitmq_lqt_lib::entry_metadata::labels::{LABEL_NAMES, LogEntryLabels};
+
+#[test]
+fn test_label_ids_match_label_names() {
+    for (id, name) in LABEL_NAMES.iter().enumerate() {
+        assert_eq!(LogEntryLabels::id_for_label(name), Some(id as u16));
+        let label = LogEntryLabels::from_label(name).unwrap();
+        assert_eq!(label.ids().collect::<Vec<_>>(), vec![id as u16]);
+        assert_eq!(label.names().collect::<Vec<_>>(), vec![*name]);
+    }
+}
+
+#[test]
+fn test_label_ids_are_stable() {
+    assert_eq!(LogEntryLabels::id_for_label("unlabelled"), Some(0));
+    assert_eq!(LogEntryLabels::id_for_label("erl_process_crash"), Some(1));
+    assert_eq!(LogEntryLabels::id_for_label("tls"), Some(37));
+    assert_eq!(LogEntryLabels::TLS, LogEntryLabels::from_id(37));
+}
+
+#[test]
+fn test_unknown_label() {
+    assert_eq!(LogEntryLabels::id_for_label("no_such_label"), None);
+    assert!(LogEntryLabels::from_label("no_such_label").is_none());
+}
+
+#[test]
+fn test_all_contains_every_label() {
+    let all = LogEntryLabels::all();
+    assert_eq!(all.len(), LABEL_NAMES.len());
+    for name in LABEL_NAMES {
+        assert!(all.contains(LogEntryLabels::from_label(name).unwrap()));
+    }
+}
+
+#[test]
+fn test_contains_and_intersects() {
+    let labels = LogEntryLabels::RAFT | LogEntryLabels::ELECTIONS;
+
+    assert!(labels.contains(LogEntryLabels::RAFT));
+    assert!(labels.contains(LogEntryLabels::RAFT | LogEntryLabels::ELECTIONS));
+    assert!(!labels.contains(LogEntryLabels::RAFT | LogEntryLabels::QUEUES));
+
+    assert!(labels.intersects(LogEntryLabels::RAFT | LogEntryLabels::QUEUES));
+    assert!(!labels.intersects(LogEntryLabels::QUEUES));
+    assert!(!labels.intersects(LogEntryLabels::empty()));
+}
+
+#[test]
+fn test_id_list_roundtrip() {
+    let labels = LogEntryLabels::UNLABELLED | LogEntryLabels::TLS | LogEntryLabels::MNESIA;
+    let list = labels
+        .ids()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    assert_eq!(LogEntryLabels::from_id_list(&list), labels);
+    assert_eq!(LogEntryLabels::from_ids(labels.ids()), labels);
+}
+
+#[test]
+fn test_from_id_list_ignores_invalid_ids() {
+    let labels = LogEntryLabels::from_id_list("4, not-an-id,,65535");
+    assert_eq!(labels, LogEntryLabels::RAFT);
+    assert!(LogEntryLabels::from_id_list("").is_empty());
+}
@@ -3,6 +3,7 @@ mod test_helpers;
 mod access_control_annotator_unit_tests;
 mod access_control_label_unit_tests;
 mod access_control_subsystem_unit_tests;
+mod aggregation_unit_tests;
 mod amqp10_subsystem_unit_tests;
 mod annotation_pipeline_unit_tests;
 mod auto_delete_annotator_unit_tests;
@@ -20,12 +21,15 @@ mod connections_annotator_unit_tests;
 mod connections_subsystem_unit_tests;
 mod connections_tracking_label_unit_tests;
 mod consumers_label_unit_tests;
+mod context_window_unit_tests;
 mod database_integration_tests;
 mod datetime_unit_tests;
 mod definitions_label_unit_tests;
 mod delete_annotator_unit_tests;
 mod delete_label_unit_tests;
 mod deprecated_features_label_unit_tests;
+mod diff_unit_tests;
+mod entities_unit_tests;
 mod entry_filter_unit_tests;
 mod erlang_otp_subsystem_unit_tests;
 mod exchanges_subsystem_unit_tests;
@@ -34,17 +38,21 @@ mod exclusive_label_unit_tests;
 mod feature_flags_label_unit_tests;
 mod federation_label_unit_tests;
 mod federation_plugin_subsystem_unit_tests;
+mod file_ingestion_state_unit_tests;
 mod file_set_annotators_tests;
 mod http_label_unit_tests;
 mod incremental_parser_unit_tests;
+mod json_parser_unit_tests;
 mod khepri_label_unit_tests;
 mod label_annotators_integration_unit_tests;
 mod label_filtering_unit_tests;
 mod labels_serialization_unit_tests;
+mod labels_unit_tests;
 mod limits_label_unit_tests;
 mod maintenance_mode_label_unit_tests;
 mod maintenance_mode_subsystem_unit_tests;
 mod management_subsystem_unit_tests;
+mod metadata_annotators_unit_tests;
 mod metadata_store_labels_unit_tests;
 mod metadata_store_subsystem_unit_tests;
 mod metrics_label_unit_tests;
@@ -53,11 +61,13 @@ mod mnesia_label_unit_tests;
 mod mqtt_label_unit_tests;
 mod mqtt_plugin_subsystem_unit_tests;
 mod networking_label_unit_tests;
+mod node_artifacts_unit_tests;
 mod oauth2_label_unit_tests;
 mod oauth2_subsystem_unit_tests;
 mod parser_edge_cases_unit_tests;
 mod parser_integration_tests;
 mod parser_unit_tests;
+mod patterns_unit_tests;
 mod policies_label_unit_tests;
 mod preset_query_unit_tests;
 mod queue_federation_annotator_unit_tests;
@@ -70,8 +80,10 @@ mod raft_subsystem_unit_tests;
 mod raft_updates_label_unit_tests;
 mod regex_validation_tests;
 mod resolution_or_discussion_url_annotator_unit_tests;
+mod rules_unit_tests;
 mod runtime_parameters_label_unit_tests;
 mod runtime_parameters_subsystem_unit_tests;
+mod search_unit_tests;
 mod segment_writer_label_unit_tests;
 mod severity_unit_tests;
 mod shovel_plugin_subsystem_unit_tests;
@@ -79,10 +91,12 @@ mod shovels_annotator_unit_tests;
 mod shovels_label_unit_tests;
 mod shutdown_label_unit_tests;
 mod shutdown_subsystem_unit_tests;
+mod sort_order_unit_tests;
 mod sql_label_unit_tests;
 mod stomp_label_unit_tests;
 mod streams_label_unit_tests;
 mod subsystem_id_conversion_unit_tests;
+mod timeline_unit_tests;
 mod timeouts_label_unit_tests;
 mod tls_label_unit_tests;
 mod virtual_hosts_annotator_unit_tests;
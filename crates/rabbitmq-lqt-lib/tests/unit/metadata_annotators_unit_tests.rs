@@ -0,0 +1,108 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::test_helpers::create_test_entry;
+use rabbitmq_lqt_lib::Severity;
+use rabbitmq_lqt_lib::entry_metadata::{annotate_entry, annotate_structured_metadata};
+
+#[test]
+fn test_extracts_connection_user_and_vhost() {
+    let mut entry = create_test_entry(
+        "connection 10.0.0.2:8959 -> 10.0.0.1:5672: user 'user2' authenticated and granted access to vhost 'vhost1'",
+        Severity::Info,
+    );
+    annotate_structured_metadata(&mut entry);
+
+    assert_eq!(
+        entry.metadata.connection.as_deref(),
+        Some("10.0.0.2:8959 -> 10.0.0.1:5672")
+    );
+    assert_eq!(entry.metadata.user.as_deref(), Some("user2"));
+    assert_eq!(entry.metadata.vhost.as_deref(), Some("vhost1"));
+}
+
+#[test]
+fn test_extracts_metadata_from_closing_connection_message() {
+    let mut entry = create_test_entry(
+        "closing AMQP connection <0.3382.0> (10.0.0.2:8959 -> 10.0.0.1:5672, vhost: 'vhost1', user: 'user2')",
+        Severity::Info,
+    );
+    annotate_structured_metadata(&mut entry);
+
+    assert_eq!(
+        entry.metadata.connection.as_deref(),
+        Some("10.0.0.2:8959 -> 10.0.0.1:5672")
+    );
+    assert_eq!(entry.metadata.user.as_deref(), Some("user2"));
+    assert_eq!(entry.metadata.vhost.as_deref(), Some("vhost1"));
+}
+
+#[test]
+fn test_extracts_ipv6_connection() {
+    let mut entry = create_test_entry(
+        "accepting AMQP connection [::1]:50230 -> [::1]:5672",
+        Severity::Info,
+    );
+    annotate_structured_metadata(&mut entry);
+
+    assert_eq!(
+        entry.metadata.connection.as_deref(),
+        Some("[::1]:50230 -> [::1]:5672")
+    );
+}
+
+#[test]
+fn test_extracts_virtual_host() {
+    let mut entry = create_test_entry(
+        "Successfully set permissions for user 'user1' in virtual host 'vhost3' to '.*', '.*', '.*'",
+        Severity::Info,
+    );
+    annotate_structured_metadata(&mut entry);
+
+    assert_eq!(entry.metadata.user.as_deref(), Some("user1"));
+    assert_eq!(entry.metadata.vhost.as_deref(), Some("vhost3"));
+    assert_eq!(entry.metadata.connection, None);
+}
+
+#[test]
+fn test_does_not_match_username_or_unrelated_quotes() {
+    let mut entry = create_test_entry(
+        "Default username 'guest' will be created, queue 'orders' declared",
+        Severity::Info,
+    );
+    annotate_structured_metadata(&mut entry);
+
+    assert!(entry.metadata.is_empty());
+}
+
+#[test]
+fn test_keeps_existing_metadata() {
+    let mut entry = create_test_entry(
+        "user 'user1' authenticated and granted access to vhost 'vhost1'",
+        Severity::Info,
+    );
+    entry.metadata.vhost = Some("from-json".to_string());
+    annotate_structured_metadata(&mut entry);
+
+    assert_eq!(entry.metadata.vhost.as_deref(), Some("from-json"));
+    assert_eq!(entry.metadata.user.as_deref(), Some("user1"));
+}
+
+#[test]
+fn test_annotate_entry_extracts_metadata() {
+    let mut entry = create_test_entry("Adding vhost 'vhost2' (description: '')", Severity::Info);
+    annotate_entry(&mut entry);
+
+    assert_eq!(entry.metadata.vhost.as_deref(), Some("vhost2"));
+}
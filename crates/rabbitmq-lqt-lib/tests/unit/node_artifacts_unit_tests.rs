@@ -0,0 +1,186 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rabbitmq_lqt_lib::rel_db::node_artifacts::{
+    Model, REDACTED_VALUE, is_secret_setting, parse_enabled_plugins, parse_rabbitmq_conf,
+    redact_secret_settings,
+};
+use rabbitmq_lqt_lib::{
+    ArtifactKind, ConfigSetting, NodeArtifacts, create_database, migrate_database,
+    obfuscate_database, open_database,
+};
+use rabbitmq_lqt_obfuscation::LogObfuscator;
+use tempfile::TempDir;
+
+const RABBITMQ_CONF: &str = "\
+# Cluster formation
+cluster_formation.classic_config.nodes.1 = rabbit@sunnyside
+default_user = admin
+default_pass = s3kr3t
+
+ssl_options.password = t0ps3kr3t
+";
+
+fn rabbitmq_conf_artifact() -> Model {
+    Model {
+        node: "rabbit@sunnyside".to_string(),
+        kind: ArtifactKind::RabbitmqConf,
+        file_path: "bundle/rabbit@sunnyside/etc/rabbitmq.conf".to_string(),
+        content: RABBITMQ_CONF.to_string(),
+    }
+}
+
+#[test]
+fn test_artifact_kind_from_file_name() {
+    assert_eq!(
+        ArtifactKind::from_file_name("rabbitmq.conf"),
+        Some(ArtifactKind::RabbitmqConf)
+    );
+    assert_eq!(
+        ArtifactKind::from_file_name("advanced.config"),
+        Some(ArtifactKind::AdvancedConfig)
+    );
+    assert_eq!(
+        ArtifactKind::from_file_name("enabled_plugins"),
+        Some(ArtifactKind::EnabledPlugins)
+    );
+    assert_eq!(ArtifactKind::from_file_name("rabbit.log"), None);
+}
+
+#[test]
+fn test_parse_rabbitmq_conf_skips_comments_and_blank_lines() {
+    let settings = parse_rabbitmq_conf(RABBITMQ_CONF);
+    assert_eq!(
+        settings,
+        vec![
+            (
+                "cluster_formation.classic_config.nodes.1".to_string(),
+                "rabbit@sunnyside".to_string()
+            ),
+            ("default_user".to_string(), "admin".to_string()),
+            ("default_pass".to_string(), "s3kr3t".to_string()),
+            ("ssl_options.password".to_string(), "t0ps3kr3t".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_enabled_plugins() {
+    assert_eq!(
+        parse_enabled_plugins("[rabbitmq_management,rabbitmq_prometheus,\n rabbitmq_shovel]."),
+        vec![
+            "rabbitmq_management",
+            "rabbitmq_prometheus",
+            "rabbitmq_shovel"
+        ]
+    );
+    assert!(parse_enabled_plugins("[].").is_empty());
+    assert!(parse_enabled_plugins("").is_empty());
+}
+
+#[test]
+fn test_redact_secret_settings() {
+    assert!(is_secret_setting("default_pass"));
+    assert!(is_secret_setting("ssl_options.password"));
+    assert!(is_secret_setting("auth_oauth2.client_secret"));
+    assert!(!is_secret_setting("default_user"));
+
+    let redacted = redact_secret_settings(RABBITMQ_CONF);
+    assert!(!redacted.contains("s3kr3t"));
+    assert!(redacted.contains(&format!("default_pass = {}", REDACTED_VALUE)));
+    assert!(redacted.contains("default_user = admin"));
+    assert!(redacted.contains("# Cluster formation"));
+    assert!(redacted.ends_with('\n'));
+}
+
+#[test]
+fn test_node_artifacts_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database(&temp_dir.path().join("artifacts.db")).unwrap();
+
+    NodeArtifacts::insert(&db, &rabbitmq_conf_artifact()).unwrap();
+    NodeArtifacts::insert(
+        &db,
+        &Model {
+            node: "rabbit@sunnyside".to_string(),
+            kind: ArtifactKind::EnabledPlugins,
+            file_path: "bundle/rabbit@sunnyside/etc/enabled_plugins".to_string(),
+            content: "[rabbitmq_management].".to_string(),
+        },
+    )
+    .unwrap();
+
+    let artifacts = NodeArtifacts::find_all(&db).unwrap();
+    assert_eq!(artifacts.len(), 2);
+    assert_eq!(artifacts[1], rabbitmq_conf_artifact());
+
+    let settings = NodeArtifacts::config_settings(&db).unwrap();
+    assert_eq!(settings.len(), 4);
+    assert_eq!(
+        settings[1],
+        ConfigSetting {
+            node: "rabbit@sunnyside".to_string(),
+            key: "default_user".to_string(),
+            value: "admin".to_string(),
+        }
+    );
+
+    assert_eq!(
+        NodeArtifacts::enabled_plugins(&db).unwrap(),
+        vec![(
+            "rabbit@sunnyside".to_string(),
+            vec!["rabbitmq_management".to_string()]
+        )]
+    );
+}
+
+#[test]
+fn test_open_database_creates_node_artifacts_table() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("pre_artifacts.db");
+
+    {
+        let db = create_database(&db_path).unwrap();
+        db.get()
+            .unwrap()
+            .execute_batch("DROP TABLE node_artifacts")
+            .unwrap();
+    }
+
+    let db = open_database(&db_path).unwrap();
+    assert!(NodeArtifacts::find_all(&db).unwrap().is_empty());
+    assert!(!migrate_database(&db).unwrap());
+}
+
+#[test]
+fn test_obfuscate_database_redacts_and_obfuscates_node_artifacts() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = create_database(&temp_dir.path().join("source.db")).unwrap();
+    let target = create_database(&temp_dir.path().join("target.db")).unwrap();
+    NodeArtifacts::insert(&source, &rabbitmq_conf_artifact()).unwrap();
+
+    let mut obfuscator = LogObfuscator::new();
+    obfuscate_database(&source, &target, &mut obfuscator).unwrap();
+
+    let artifacts = NodeArtifacts::find_all(&target).unwrap();
+    assert_eq!(artifacts.len(), 1);
+    let artifact = &artifacts[0];
+    assert_eq!(artifact.kind, ArtifactKind::RabbitmqConf);
+    assert!(!artifact.node.contains("sunnyside"));
+    assert!(!artifact.file_path.contains("sunnyside"));
+    assert!(!artifact.content.contains("sunnyside"));
+    assert!(!artifact.content.contains("s3kr3t"));
+    assert!(!artifact.content.contains("t0ps3kr3t"));
+    assert!(artifact.content.contains("default_user = admin"));
+}
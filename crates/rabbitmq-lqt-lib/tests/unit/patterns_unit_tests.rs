@@ -0,0 +1,102 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use rabbitmq_lqt_lib::entry_metadata::patterns::{MAX_TEMPLATE_LENGTH, pattern_id};
+use rabbitmq_lqt_lib::{MessagePattern, message_template};
+
+#[test]
+fn test_template_of_message_without_variable_parts() {
+    assert_eq!(
+        message_template("Ready to start client connection listeners"),
+        "Ready to start client connection listeners"
+    );
+}
+
+#[test]
+fn test_template_replaces_pids_and_addresses() {
+    assert_eq!(
+        message_template("<0.1234.0> accepting AMQP connection 10.0.0.2:57586 -> 10.0.0.1:5672"),
+        "<pid> accepting AMQP connection <ip> -> <ip>"
+    );
+    assert_eq!(
+        message_template("started TCP listener on [::1]:5672"),
+        "started TCP listener on <ip>:<n>"
+    );
+}
+
+#[test]
+fn test_template_replaces_quoted_names_and_node_names() {
+    assert_eq!(
+        message_template(
+            "queue 'orders' in vhost 'vhost1': detected a new leader {'orders','rabbit@node1'} in term 2"
+        ),
+        "queue '<*>' in vhost '<*>': detected a new leader {'<*>','<*>'} in term <n>"
+    );
+    assert_eq!(
+        message_template("Node rabbit@sunnyside is down, deleting its listeners"),
+        "Node <node> is down, deleting its listeners"
+    );
+}
+
+#[test]
+fn test_template_replaces_refs_paths_and_numbers() {
+    assert_eq!(
+        message_template("Timed out waiting for #Ref<0.3001.4214.55> after 30000 ms"),
+        "Timed out waiting for <ref> after <n> ms"
+    );
+    assert_eq!(
+        message_template("Deleting pid file /var/lib/rabbitmq/mnesia/rabbit.pid"),
+        "Deleting pid file <path>"
+    );
+    assert_eq!(
+        message_template("Raft segment 00000012.segment synced"),
+        "Raft segment <n>.segment synced"
+    );
+}
+
+#[test]
+fn test_template_uses_first_line_only() {
+    assert_eq!(
+        message_template(
+            "** Generic server <0.500.0> terminating\n** Last message in was {timeout}"
+        ),
+        "** Generic server <pid> terminating"
+    );
+}
+
+#[test]
+fn test_template_is_truncated() {
+    let message = "x".repeat(MAX_TEMPLATE_LENGTH * 2);
+    assert_eq!(message_template(&message).len(), MAX_TEMPLATE_LENGTH);
+}
+
+#[test]
+fn test_messages_that_differ_in_variable_parts_share_a_pattern() {
+    let a = MessagePattern::of(
+        "closing AMQP connection <0.1.0> (10.0.0.2:1 -> 10.0.0.1:5672, vhost: '/', user: 'guest')",
+    );
+    let b = MessagePattern::of(
+        "closing AMQP connection <0.2.0> (10.0.0.3:2 -> 10.0.0.1:5672, vhost: 'vhost1', user: 'user1')",
+    );
+    let c = MessagePattern::of("accepting AMQP connection 10.0.0.3:2 -> 10.0.0.1:5672");
+
+    assert_eq!(a, b);
+    assert_ne!(a.id, c.id);
+}
+
+#[test]
+fn test_pattern_ids_are_stable() {
+    // FNV-1a of an empty input is its offset basis
+    assert_eq!(pattern_id(""), 0xcbf2_9ce4_8422_2325_u64 as i64);
+    assert_eq!(pattern_id("a"), 0xaf63_dc4c_8601_ec8c_u64 as i64);
+}
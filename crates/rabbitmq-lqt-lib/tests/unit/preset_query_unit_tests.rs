@@ -15,7 +15,8 @@
 use chrono::Utc;
 use rabbitmq_lqt_lib::entry_metadata::labels::LogEntryLabels;
 use rabbitmq_lqt_lib::{
-    NodeLogEntry, ParsedLogEntry, QueryContext, QueryPreset, Severity, create_database,
+    NodeLogEntry, ParsedLogEntry, QueryContext, QueryPreset, Severity, StructuredMetadata,
+    create_database,
 };
 use std::str::FromStr;
 use tempfile::TempDir;
@@ -42,7 +43,7 @@ fn test_preset_display() {
 #[test]
 fn test_preset_severity() {
     let preset = QueryPreset::ErrorsOrCrashes;
-    assert_eq!(preset.severity(), Some("error"));
+    assert_eq!(preset.severity(), Some(Severity::Error));
 }
 
 #[test]
@@ -80,6 +81,8 @@ fn test_query_with_preset_matches_error_severity() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 1,
@@ -93,6 +96,8 @@ fn test_query_with_preset_matches_error_severity() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -124,6 +129,8 @@ fn test_query_with_preset_matches_erl_process_crash_label() {
             labels: LogEntryLabels::ERL_PROCESS_CRASH,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 1,
@@ -137,6 +144,8 @@ fn test_query_with_preset_matches_erl_process_crash_label() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -173,6 +182,8 @@ fn test_query_with_preset_matches_exceptions_label() {
             labels: LogEntryLabels::EXCEPTIONS,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 1,
@@ -186,6 +197,8 @@ fn test_query_with_preset_matches_exceptions_label() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -217,6 +230,8 @@ fn test_query_with_preset_or_logic() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 1,
@@ -230,6 +245,8 @@ fn test_query_with_preset_or_logic() {
             labels: LogEntryLabels::ERL_PROCESS_CRASH,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 2,
@@ -243,6 +260,8 @@ fn test_query_with_preset_or_logic() {
             labels: LogEntryLabels::EXCEPTIONS,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 3,
@@ -256,6 +275,8 @@ fn test_query_with_preset_or_logic() {
             labels: LogEntryLabels::ERL_PROCESS_CRASH | LogEntryLabels::EXCEPTIONS,
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 4,
@@ -269,6 +290,8 @@ fn test_query_with_preset_or_logic() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -298,6 +321,8 @@ fn test_query_with_preset_and_node_filter() {
         labels: LogEntryLabels::empty(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     }];
     NodeLogEntry::insert_parsed_entries(&db, &entries, "rabbit@node1").unwrap();
 
@@ -313,6 +338,8 @@ fn test_query_with_preset_and_node_filter() {
         labels: LogEntryLabels::empty(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     }];
     NodeLogEntry::insert_parsed_entries(&db, &entries, "rabbit@node2").unwrap();
 
@@ -346,6 +373,8 @@ fn test_query_with_preset_and_time_filter() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 1,
@@ -359,6 +388,8 @@ fn test_query_with_preset_and_time_filter() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -394,6 +425,8 @@ fn test_query_with_preset_and_to_time_filter() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 1,
@@ -407,6 +440,8 @@ fn test_query_with_preset_and_to_time_filter() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -438,6 +473,8 @@ fn test_query_with_preset_no_matches() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
         ParsedLogEntry {
             sequence_id: 1,
@@ -451,6 +488,8 @@ fn test_query_with_preset_no_matches() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         },
     ];
 
@@ -481,6 +520,8 @@ fn test_query_with_preset_limit() {
             labels: LogEntryLabels::empty(),
             resolution_or_discussion_url_id: None,
             doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
         })
         .collect();
 
@@ -0,0 +1,289 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::test_helpers::{create_test_entry, create_test_entry_with_subsystem};
+use rabbitmq_lqt_lib::entry_metadata::rules::{custom_label_name, is_valid_custom_label};
+use rabbitmq_lqt_lib::entry_metadata::subsystems::Subsystem;
+use rabbitmq_lqt_lib::{Error, LogEntryLabels, RuleSet, Severity};
+
+const BILLING_RULES: &str = r#"
+[[rules]]
+name = "billing-plugin-errors"
+
+[rules.when]
+starts_with = "[billing]"
+severity = "error"
+
+[rules.then]
+labels = ["plugins"]
+custom_labels = ["billing", "needs-triage"]
+subsystem = "plugins"
+doc_url = "https://wiki.example.com/billing-plugin"
+
+[[rules]]
+name = "billing-timeouts"
+
+[rules.when]
+subsystem = "plugins"
+regex = "invoice \\d+ timed out"
+
+[rules.then]
+custom_labels = ["billing_timeouts"]
+resolution_url = "https://tracker.example.com/issues/42"
+"#;
+
+fn invalid_rules_reason(toml: &str) -> String {
+    match RuleSet::from_toml_str(toml) {
+        Err(Error::InvalidRules(reason)) => reason,
+        other => panic!("expected invalid rules, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_loads_rules_in_order() {
+    let rules = RuleSet::from_toml_str(BILLING_RULES).unwrap();
+
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules.rules()[0].name, "billing-plugin-errors");
+    assert_eq!(rules.rules()[1].name, "billing-timeouts");
+}
+
+#[test]
+fn test_matching_rule_assigns_annotations() {
+    let rules = RuleSet::from_toml_str(BILLING_RULES).unwrap();
+    let mut entry = create_test_entry("[billing] failed to charge account 12", Severity::Error);
+    entry.labels = LogEntryLabels::UNLABELLED;
+
+    rules.apply(&mut entry);
+
+    assert_eq!(entry.labels, LogEntryLabels::PLUGINS);
+    assert_eq!(entry.subsystem_id, Some(Subsystem::Plugins.to_id()));
+    assert_eq!(
+        entry.custom_annotations.labels,
+        vec!["billing".to_string(), "needs-triage".to_string()]
+    );
+    assert_eq!(
+        entry.custom_annotations.doc_url.as_deref(),
+        Some("https://wiki.example.com/billing-plugin")
+    );
+    assert!(
+        entry
+            .custom_annotations
+            .resolution_or_discussion_url
+            .is_none()
+    );
+}
+
+#[test]
+fn test_rule_requires_all_conditions() {
+    let rules = RuleSet::from_toml_str(BILLING_RULES).unwrap();
+    let mut entry = create_test_entry("[billing] charged account 12", Severity::Info);
+
+    rules.apply(&mut entry);
+
+    assert!(entry.labels.is_empty());
+    assert!(entry.subsystem_id.is_none());
+    assert!(entry.custom_annotations.is_empty());
+}
+
+#[test]
+fn test_later_rules_see_subsystem_assigned_by_earlier_ones() {
+    let rules = RuleSet::from_toml_str(BILLING_RULES).unwrap();
+    let mut entry = create_test_entry("[billing] invoice 1207 timed out", Severity::Error);
+
+    rules.apply(&mut entry);
+
+    assert_eq!(
+        entry.custom_annotations.labels,
+        vec![
+            "billing".to_string(),
+            "needs-triage".to_string(),
+            "billing_timeouts".to_string()
+        ]
+    );
+    assert_eq!(
+        entry
+            .custom_annotations
+            .resolution_or_discussion_url
+            .as_deref(),
+        Some("https://tracker.example.com/issues/42")
+    );
+}
+
+#[test]
+fn test_subsystem_condition() {
+    let rules = RuleSet::from_toml_str(BILLING_RULES).unwrap();
+    let mut in_plugins = create_test_entry_with_subsystem(
+        "invoice 7 timed out",
+        Severity::Warning,
+        Subsystem::Plugins,
+    );
+    let mut in_connections = create_test_entry_with_subsystem(
+        "invoice 7 timed out",
+        Severity::Warning,
+        Subsystem::Connections,
+    );
+
+    rules.apply(&mut in_plugins);
+    rules.apply(&mut in_connections);
+
+    assert_eq!(
+        in_plugins.custom_annotations.labels,
+        vec!["billing_timeouts"]
+    );
+    assert!(in_connections.custom_annotations.is_empty());
+}
+
+#[test]
+fn test_contains_is_case_insensitive() {
+    let rules = RuleSet::from_toml_str(
+        r#"
+[[rules]]
+name = "ledger"
+when = { contains = "Ledger Sync" }
+then = { custom_labels = ["ledger"] }
+"#,
+    )
+    .unwrap();
+    let mut entry = create_test_entry("ledger sync completed", Severity::Info);
+
+    rules.apply(&mut entry);
+
+    assert_eq!(entry.custom_annotations.labels, vec!["ledger"]);
+    assert_eq!(
+        entry.custom_annotations.label_names().collect::<Vec<_>>(),
+        vec!["custom:ledger"]
+    );
+}
+
+#[test]
+fn test_empty_rules_file() {
+    let rules = RuleSet::from_toml_str("").unwrap();
+
+    assert!(rules.is_empty());
+}
+
+#[test]
+fn test_unknown_built_in_label_is_rejected() {
+    let reason = invalid_rules_reason(
+        r#"
+[[rules]]
+name = "billing"
+when = { contains = "billing" }
+then = { labels = ["billing"] }
+"#,
+    );
+
+    assert!(reason.contains("unknown label 'billing'"));
+    assert!(reason.contains("custom_labels"));
+}
+
+#[test]
+fn test_invalid_custom_label_is_rejected() {
+    let reason = invalid_rules_reason(
+        r#"
+[[rules]]
+name = "billing"
+when = { contains = "billing" }
+then = { custom_labels = ["Billing Errors"] }
+"#,
+    );
+
+    assert!(reason.contains("invalid custom label 'Billing Errors'"));
+}
+
+#[test]
+fn test_invalid_regex_is_rejected() {
+    let reason = invalid_rules_reason(
+        r#"
+[[rules]]
+name = "billing"
+when = { regex = "invoice (\\d+" }
+then = { custom_labels = ["billing"] }
+"#,
+    );
+
+    assert!(reason.contains("rule 'billing' has an invalid regex"));
+}
+
+#[test]
+fn test_unknown_subsystem_is_rejected() {
+    let reason = invalid_rules_reason(
+        r#"
+[[rules]]
+name = "billing"
+when = { subsystem = "billing" }
+then = { custom_labels = ["billing"] }
+"#,
+    );
+
+    assert!(reason.contains("unknown subsystem 'billing'"));
+}
+
+#[test]
+fn test_rule_without_conditions_is_rejected() {
+    let reason = invalid_rules_reason(
+        r#"
+[[rules]]
+name = "everything"
+when = {}
+then = { custom_labels = ["everything"] }
+"#,
+    );
+
+    assert!(reason.contains("rule 'everything' has no conditions"));
+}
+
+#[test]
+fn test_rule_without_annotations_is_rejected() {
+    let reason = invalid_rules_reason(
+        r#"
+[[rules]]
+name = "nothing"
+when = { contains = "billing" }
+then = {}
+"#,
+    );
+
+    assert!(reason.contains("rule 'nothing' assigns no annotations"));
+}
+
+#[test]
+fn test_unknown_condition_is_rejected() {
+    let reason = invalid_rules_reason(
+        r#"
+[[rules]]
+name = "billing"
+when = { ends_with = "billing" }
+then = { custom_labels = ["billing"] }
+"#,
+    );
+
+    assert!(reason.contains("ends_with"));
+}
+
+#[test]
+fn test_custom_label_names() {
+    assert!(is_valid_custom_label("billing"));
+    assert!(is_valid_custom_label("needs-triage_2"));
+    assert!(!is_valid_custom_label(""));
+    assert!(!is_valid_custom_label("Billing"));
+    assert!(!is_valid_custom_label("billing,errors"));
+    assert!(!is_valid_custom_label("it's"));
+
+    assert_eq!(custom_label_name("custom:billing"), Some("billing"));
+    assert_eq!(custom_label_name("billing"), None);
+    assert_eq!(custom_label_name("custom:"), None);
+    assert_eq!(custom_label_name("custom:it's"), None);
+}
@@ -0,0 +1,308 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono::{TimeDelta, Utc};
+use rabbitmq_lqt_lib::search::tokenize;
+use rabbitmq_lqt_lib::{
+    Aggregation, DatabaseConnection, MessageSearchIndex, NodeLogEntry, QueryContext, SearchClause,
+    SearchQuery, Severity, create_database, obfuscate_database, post_insertion_operations,
+};
+use rabbitmq_lqt_obfuscation::LogObfuscator;
+use tempfile::TempDir;
+
+use crate::test_helpers::create_test_entry;
+
+const MESSAGES: &[&str] = &[
+    "Connection closed by peer",
+    "TLS handshake failed: connection reset, connection lost",
+    "Accepting AMQP connection from 10.0.0.1:5672",
+    "Queue 'orders' in vhost '/' declared",
+    "closed connection to node rabbit@sunnyside",
+];
+
+fn create_database_with_messages(temp_dir: &TempDir, indexed: bool) -> DatabaseConnection {
+    let db = create_database(&temp_dir.path().join("search.db")).unwrap();
+    let started_at = Utc::now();
+    let entries: Vec<_> = MESSAGES
+        .iter()
+        .enumerate()
+        .map(|(idx, message)| {
+            let mut entry = create_test_entry(message, Severity::Info);
+            entry.timestamp = started_at + TimeDelta::seconds(idx as i64);
+            entry
+        })
+        .collect();
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "rabbit@sunnyside").unwrap();
+    if indexed {
+        assert_eq!(
+            MessageSearchIndex::build(&db).unwrap(),
+            MESSAGES.len() as u64
+        );
+    }
+    db
+}
+
+fn search(db: &DatabaseConnection, text: &str) -> Vec<String> {
+    let ctx = QueryContext::default().search(SearchQuery::parse(text));
+    NodeLogEntry::query(db, &ctx)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.message)
+        .collect()
+}
+
+#[test]
+fn test_tokenize() {
+    assert_eq!(
+        tokenize("TLS handshake failed: {tls_alert,\"unknown CA\"} on 10.0.0.1"),
+        vec![
+            "tls",
+            "handshake",
+            "failed",
+            "tls_alert",
+            "unknown",
+            "ca",
+            "on",
+            "10",
+            "0",
+            "0",
+            "1"
+        ]
+    );
+    assert!(tokenize(" -- ").is_empty());
+}
+
+#[test]
+fn test_search_query_parse() {
+    let query = SearchQuery::parse("timeout  conn* \"connection closed\" tls-handshake --");
+    assert_eq!(
+        query.clauses(),
+        &[
+            SearchClause {
+                terms: vec!["timeout".to_string()],
+                prefix: false,
+            },
+            SearchClause {
+                terms: vec!["conn".to_string()],
+                prefix: true,
+            },
+            SearchClause {
+                terms: vec!["connection".to_string(), "closed".to_string()],
+                prefix: false,
+            },
+            SearchClause {
+                terms: vec!["tls".to_string(), "handshake".to_string()],
+                prefix: false,
+            },
+        ]
+    );
+    assert_eq!(
+        query.to_string(),
+        "timeout conn* \"connection closed\" \"tls handshake\""
+    );
+
+    let unterminated = SearchQuery::parse("\"handshake fail*");
+    assert_eq!(unterminated.to_string(), "\"handshake fail*\"");
+    assert!(unterminated.clauses()[0].prefix);
+
+    assert!(SearchQuery::parse("").is_empty());
+    assert!(SearchQuery::parse("\"\" *").is_empty());
+}
+
+#[test]
+fn test_search_matches_all_terms_with_and_without_index() {
+    for indexed in [false, true] {
+        let temp_dir = TempDir::new().unwrap();
+        let db = create_database_with_messages(&temp_dir, indexed);
+
+        assert_eq!(search(&db, "CLOSED").len(), 2, "indexed: {}", indexed);
+        assert_eq!(
+            search(&db, "connection peer"),
+            vec!["Connection closed by peer"],
+            "indexed: {}",
+            indexed
+        );
+        assert!(search(&db, "connect").is_empty(), "indexed: {}", indexed);
+        assert_eq!(search(&db, "connect*").len(), 4, "indexed: {}", indexed);
+        assert_eq!(
+            search(&db, "\"connection closed\""),
+            vec!["Connection closed by peer"],
+            "indexed: {}",
+            indexed
+        );
+        assert_eq!(
+            search(&db, "\"closed conn*\""),
+            vec!["closed connection to node rabbit@sunnyside"],
+            "indexed: {}",
+            indexed
+        );
+        assert_eq!(
+            search(&db, "rabbit@sunnyside"),
+            vec!["closed connection to node rabbit@sunnyside"],
+            "indexed: {}",
+            indexed
+        );
+    }
+}
+
+#[test]
+fn test_search_without_index_keeps_timestamp_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database_with_messages(&temp_dir, false);
+
+    assert!(!MessageSearchIndex::exists(&db).unwrap());
+    assert_eq!(
+        search(&db, "connection"),
+        vec![
+            MESSAGES[0].to_string(),
+            MESSAGES[1].to_string(),
+            MESSAGES[2].to_string(),
+            MESSAGES[4].to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_indexed_search_ranks_by_term_frequency() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database_with_messages(&temp_dir, true);
+
+    let results = search(&db, "connection");
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0], MESSAGES[1], "mentions the term twice");
+
+    let results = search(&db, "connection pe*");
+    assert_eq!(results, vec![MESSAGES[0]]);
+    let ctx = QueryContext::default()
+        .search(SearchQuery::parse("connection"))
+        .search(SearchQuery::parse("closed"));
+    let results = NodeLogEntry::query(&db, &ctx).unwrap();
+    assert_eq!(results.len(), 2);
+
+    // Tail queries return the most recent matches in timestamp order
+    let ctx = QueryContext::default()
+        .search(SearchQuery::parse("connection"))
+        .tail(2);
+    let results: Vec<_> = NodeLogEntry::query(&db, &ctx)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.message)
+        .collect();
+    assert_eq!(results, vec![MESSAGES[2], MESSAGES[4]]);
+}
+
+#[test]
+fn test_search_applies_to_aggregations() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database_with_messages(&temp_dir, true);
+
+    let ctx = QueryContext::default()
+        .search(SearchQuery::parse("closed"))
+        .aggregation(Aggregation::CountBy(None));
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+    assert_eq!(result.rows, vec![vec![serde_json::json!(2)]]);
+}
+
+#[test]
+fn test_search_index_is_kept_up_to_date() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database_with_messages(&temp_dir, true);
+
+    // Appended with a higher id but an earlier timestamp, so renumbering moves it first
+    let mut earlier = create_test_entry("Heartbeat timeout on connection", Severity::Warning);
+    earlier.timestamp = Utc::now() - TimeDelta::hours(1);
+    NodeLogEntry::insert_parsed_entries(&db, &[earlier], "rabbit@sunnyside").unwrap();
+    assert!(NodeLogEntry::renumber_in_timestamp_order(&db).unwrap());
+    post_insertion_operations(&db).unwrap();
+
+    let results = NodeLogEntry::query(
+        &db,
+        &QueryContext::default().search(SearchQuery::parse("heartbeat")),
+    )
+    .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, 1);
+    let results = NodeLogEntry::query(
+        &db,
+        &QueryContext::default().search(SearchQuery::parse("\"connection from\"")),
+    )
+    .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].message, MESSAGES[2]);
+
+    let mut later = create_test_entry("Heartbeat timeout again", Severity::Warning);
+    later.timestamp = Utc::now() + TimeDelta::hours(1);
+    NodeLogEntry::insert_parsed_entries(&db, &[later], "rabbit@sunnyside").unwrap();
+    assert_eq!(MessageSearchIndex::index_new_entries(&db).unwrap(), 1);
+    assert_eq!(MessageSearchIndex::index_new_entries(&db).unwrap(), 0);
+    assert_eq!(search(&db, "heartbeat").len(), 2);
+}
+
+#[test]
+fn test_search_index_follows_appended_entries_when_they_are_renumbered() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database_with_messages(&temp_dir, true);
+    let first_appended = MESSAGES.len() as i64 + 1;
+
+    let mut later = create_test_entry("Heartbeat timeout again", Severity::Warning);
+    later.timestamp = Utc::now() + TimeDelta::hours(2);
+    let mut earlier = create_test_entry("Heartbeat timeout on channel", Severity::Warning);
+    earlier.timestamp = Utc::now() + TimeDelta::hours(1);
+    NodeLogEntry::insert_parsed_entries(&db, &[later, earlier], "rabbit@sunnyside").unwrap();
+    assert!(NodeLogEntry::renumber_appended_in_timestamp_order(&db, first_appended).unwrap());
+
+    let results = NodeLogEntry::query(
+        &db,
+        &QueryContext::default().search(SearchQuery::parse("channel")),
+    )
+    .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, first_appended);
+    assert_eq!(results[0].message, "Heartbeat timeout on channel");
+    let results = NodeLogEntry::query(
+        &db,
+        &QueryContext::default().search(SearchQuery::parse("again")),
+    )
+    .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, first_appended + 1);
+    // Stored entries keep both their ids and their terms
+    assert_eq!(search(&db, "peer"), vec![MESSAGES[0]]);
+    assert_eq!(MessageSearchIndex::index_new_entries(&db).unwrap(), 0);
+}
+
+#[test]
+fn test_obfuscated_database_indexes_obfuscated_messages() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = create_database_with_messages(&temp_dir, true);
+    let target = create_database(&temp_dir.path().join("obfuscated.db")).unwrap();
+
+    let mut obfuscator = LogObfuscator::new();
+    obfuscate_database(&source, &target, &mut obfuscator).unwrap();
+
+    assert!(MessageSearchIndex::exists(&target).unwrap());
+    assert!(search(&target, "sunnyside").is_empty());
+    assert_eq!(search(&target, "peer"), vec![MESSAGES[0]]);
+    let obfuscated = NodeLogEntry::query(&target, &QueryContext::default()).unwrap();
+    let alias = tokenize(&obfuscated[4].message).pop().unwrap();
+    assert_ne!(alias, "sunnyside");
+    assert_eq!(search(&target, &alias), vec![obfuscated[4].message.clone()]);
+
+    // Databases without an index are obfuscated into ones without an index
+    let temp_dir = TempDir::new().unwrap();
+    let source = create_database_with_messages(&temp_dir, false);
+    let target = create_database(&temp_dir.path().join("obfuscated.db")).unwrap();
+    obfuscate_database(&source, &target, &mut obfuscator).unwrap();
+    assert!(!MessageSearchIndex::exists(&target).unwrap());
+}
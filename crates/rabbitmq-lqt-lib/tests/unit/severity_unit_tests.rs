@@ -44,3 +44,16 @@ fn test_severity_as_str() {
     assert_eq!(Severity::Error.as_str(), "error");
     assert_eq!(Severity::Critical.as_str(), "critical");
 }
+
+#[test]
+fn test_severity_ordinal_round_trip() {
+    for severity in Severity::all() {
+        assert_eq!(
+            Severity::from_ordinal(severity.to_ordinal()),
+            Some(severity)
+        );
+    }
+    assert!(Severity::Warning.to_ordinal() < Severity::Error.to_ordinal());
+    assert_eq!(Severity::from_ordinal(-1), None);
+    assert_eq!(Severity::from_ordinal(6), None);
+}
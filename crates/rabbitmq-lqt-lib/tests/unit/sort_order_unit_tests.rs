@@ -0,0 +1,162 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::test_helpers::create_test_entry;
+use chrono::{DateTime, TimeDelta, Utc};
+use rabbitmq_lqt_lib::{
+    Aggregation, DatabaseConnection, LogColumn, NodeLogEntry, ParsedLogEntry, QueryContext,
+    Severity, SortKey, Subsystem, create_database,
+};
+use serde_json::json;
+use tempfile::TempDir;
+
+fn entry_at(message: &str, severity: Severity, seconds: i64) -> ParsedLogEntry {
+    let start = DateTime::parse_from_rfc3339("2025-10-27T18:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let mut entry = create_test_entry(message, severity);
+    entry.timestamp = start + TimeDelta::seconds(seconds);
+    entry
+}
+
+fn setup_db() -> (TempDir, DatabaseConnection) {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database(&temp_dir.path().join("test.db")).unwrap();
+
+    let mut refused = entry_at("d", Severity::Warning, 3);
+    refused.subsystem_id = Some(Subsystem::Connections.to_id());
+    let node1 = vec![
+        entry_at("a", Severity::Info, 0),
+        entry_at("b", Severity::Critical, 1),
+        entry_at("c", Severity::Debug, 2),
+        refused,
+    ];
+    let mut booted = entry_at("e", Severity::Error, 4);
+    booted.subsystem_id = Some(Subsystem::Boot.to_id());
+    let node2 = vec![booted, entry_at("f", Severity::Notice, 5)];
+
+    NodeLogEntry::insert_parsed_entries(&db, &node1, "rabbit@node1").unwrap();
+    NodeLogEntry::insert_parsed_entries(&db, &node2, "rabbit@node2").unwrap();
+
+    (temp_dir, db)
+}
+
+fn messages(db: &DatabaseConnection, ctx: &QueryContext) -> Vec<String> {
+    NodeLogEntry::query(db, ctx)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.message)
+        .collect()
+}
+
+#[test]
+fn test_parse_sort_keys() {
+    assert_eq!(
+        SortKey::parse_list("node, severity:desc,id:asc").unwrap(),
+        vec![
+            SortKey::ascending(LogColumn::Node),
+            SortKey::descending(LogColumn::Severity),
+            SortKey::ascending(LogColumn::Id),
+        ]
+    );
+    assert_eq!(SortKey::parse_list("").unwrap(), vec![]);
+    assert_eq!(
+        SortKey::descending(LogColumn::SourceFile).to_string(),
+        "file:desc"
+    );
+
+    assert!(SortKey::parse_list("nonexistent").is_err());
+    assert!(SortKey::parse_list("node:sideways").is_err());
+    assert!(SortKey::parse_list("labels").is_err());
+    assert!(SortKey::parse_list("queue:desc").is_err());
+}
+
+#[test]
+fn test_query_orders_by_timestamp_by_default() {
+    let (_temp_dir, db) = setup_db();
+
+    assert_eq!(
+        messages(&db, &QueryContext::default()),
+        vec!["a", "b", "c", "d", "e", "f"]
+    );
+}
+
+#[test]
+fn test_query_sorted_by_severity_rank() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default().sort_by(SortKey::ascending(LogColumn::Severity));
+    assert_eq!(messages(&db, &ctx), vec!["c", "a", "f", "d", "e", "b"]);
+
+    let ctx = QueryContext::default().sort_by(SortKey::descending(LogColumn::Severity));
+    assert_eq!(messages(&db, &ctx), vec!["b", "e", "d", "f", "a", "c"]);
+}
+
+#[test]
+fn test_query_sorted_by_several_keys() {
+    let (_temp_dir, db) = setup_db();
+
+    let ctx = QueryContext::default()
+        .sort_by(SortKey::descending(LogColumn::Node))
+        .sort_by(SortKey::descending(LogColumn::Severity));
+    assert_eq!(messages(&db, &ctx), vec!["e", "f", "b", "d", "a", "c"]);
+
+    let ctx = QueryContext::default().order_by(vec![SortKey::descending(LogColumn::Id)]);
+    assert_eq!(messages(&db, &ctx), vec!["f", "e", "d", "c", "b", "a"]);
+}
+
+#[test]
+fn test_query_sorted_by_subsystem_name() {
+    let (_temp_dir, db) = setup_db();
+
+    // Entries without a subsystem come last, in id order
+    let ctx = QueryContext::default().sort_by(SortKey::ascending(LogColumn::Subsystem));
+    assert_eq!(messages(&db, &ctx), vec!["e", "d", "a", "b", "c", "f"]);
+}
+
+#[test]
+fn test_query_sorted_with_limit_offset_and_tail() {
+    let (_temp_dir, db) = setup_db();
+    let by_severity = QueryContext::default().sort_by(SortKey::descending(LogColumn::Severity));
+
+    let ctx = by_severity.clone().limit(2).offset(1);
+    assert_eq!(messages(&db, &ctx), vec!["e", "d"]);
+
+    let ctx = by_severity.tail(2);
+    assert_eq!(messages(&db, &ctx), vec!["a", "c"]);
+}
+
+#[test]
+fn test_projection_sorted_by_columns_it_does_not_include() {
+    let (_temp_dir, db) = setup_db();
+    let ctx = QueryContext::default()
+        .aggregation(Aggregation::Project(vec![LogColumn::Message]))
+        .sort_by(SortKey::ascending(LogColumn::Severity));
+
+    let result = NodeLogEntry::aggregate(&db, &ctx).unwrap();
+    assert_eq!(
+        result.rows,
+        vec![
+            vec![json!("c")],
+            vec![json!("a")],
+            vec![json!("f")],
+            vec![json!("d")],
+            vec![json!("e")],
+            vec![json!("b")],
+        ]
+    );
+
+    let result = NodeLogEntry::aggregate(&db, &ctx.tail(2)).unwrap();
+    assert_eq!(result.rows, vec![vec![json!("e")], vec![json!("b")]]);
+}
@@ -17,7 +17,7 @@
 use chrono::Utc;
 use rabbitmq_lqt_lib::entry_metadata::labels::LogEntryLabels;
 use rabbitmq_lqt_lib::entry_metadata::subsystems::Subsystem;
-use rabbitmq_lqt_lib::{ParsedLogEntry, Severity};
+use rabbitmq_lqt_lib::{ParsedLogEntry, Severity, StructuredMetadata};
 
 pub fn create_test_entry(message: &str, severity: Severity) -> ParsedLogEntry {
     ParsedLogEntry {
@@ -32,6 +32,8 @@ pub fn create_test_entry(message: &str, severity: Severity) -> ParsedLogEntry {
         labels: LogEntryLabels::default(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     }
 }
 
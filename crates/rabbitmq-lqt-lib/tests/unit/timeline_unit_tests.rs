@@ -0,0 +1,310 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::test_helpers::create_test_entry;
+use chrono::{DateTime, TimeDelta, Utc};
+use rabbitmq_lqt_lib::rel_db::node_log_entry::Model;
+use rabbitmq_lqt_lib::timeline::TIMELINE_PAGE_SIZE;
+use rabbitmq_lqt_lib::{
+    NodeLogEntry, ParsedLogEntry, QueryContext, Severity, Timeline, TimelineOptions, Transition,
+    annotate_labels, create_database,
+};
+use tempfile::TempDir;
+
+fn ts(secs: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(1_760_000_000 + secs, 0).unwrap()
+}
+
+fn model(id: i64, node: &str, secs: i64, severity: Severity, message: &str) -> Model {
+    let entry = create_test_entry(message, severity);
+    Model {
+        id,
+        node: node.to_string(),
+        timestamp: ts(secs),
+        severity: severity.to_string(),
+        erlang_pid: entry.process_id.clone(),
+        subsystem_id: None,
+        labels: annotate_labels(&entry),
+        message: entry.message,
+        resolution_or_discussion_url_id: None,
+        doc_url_id: None,
+        metadata: entry.metadata,
+        custom_annotations: Default::default(),
+    }
+}
+
+fn classify(message: &str) -> Option<Transition> {
+    let entry = create_test_entry(message, Severity::Info);
+    Transition::classify(annotate_labels(&entry), message)
+}
+
+#[test]
+fn test_classify_node_shutdown() {
+    assert_eq!(
+        classify("RabbitMQ is asked to stop..."),
+        Some(Transition::NodeShutdown)
+    );
+    assert_eq!(
+        classify("Successfully stopped RabbitMQ and its dependencies"),
+        Some(Transition::NodeShutdown)
+    );
+}
+
+#[test]
+fn test_classify_leader_change() {
+    assert_eq!(
+        classify("RabbitMQ metadata store: candidate -> leader in term: 1"),
+        Some(Transition::LeaderChange)
+    );
+}
+
+#[test]
+fn test_classify_quorum_queue_leader_change() {
+    assert_eq!(
+        classify(
+            "queue 'orders' in vhost '/': candidate -> leader in term: 2 machine version: 5, last applied 9"
+        ),
+        Some(Transition::LeaderChange)
+    );
+}
+
+#[test]
+fn test_classify_partition() {
+    assert_eq!(
+        classify("Network partition detected, node rabbit@host2 is unreachable"),
+        Some(Transition::Partition)
+    );
+}
+
+#[test]
+fn test_classify_alarm() {
+    assert_eq!(
+        classify("memory resource limit alarm set on node 'rabbit@host1'."),
+        Some(Transition::Alarm)
+    );
+}
+
+#[test]
+fn test_classify_maintenance_mode() {
+    assert_eq!(
+        classify("Node rabbit@host1 is marking the node as undergoing maintenance"),
+        Some(Transition::MaintenanceMode)
+    );
+}
+
+#[test]
+fn test_classify_ordinary_entry() {
+    assert_eq!(
+        classify("accepting AMQP connection 127.0.0.1:50000 -> 127.0.0.1:5672"),
+        None
+    );
+}
+
+#[test]
+fn test_timeline_merges_nodes_chronologically() {
+    let entries = vec![
+        model(1, "rabbit@b", 10, Severity::Info, "second"),
+        model(2, "rabbit@a", 5, Severity::Info, "first"),
+        model(3, "rabbit@a", 20, Severity::Info, "third"),
+    ];
+    let timeline = Timeline::build(entries, &TimelineOptions::default());
+
+    assert_eq!(timeline.nodes, vec!["rabbit@a", "rabbit@b"]);
+    let messages: Vec<_> = timeline.events.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(messages, vec!["first", "second", "third"]);
+}
+
+#[test]
+fn test_timeline_collapses_bursts() {
+    let entries = vec![
+        model(
+            1,
+            "rabbit@a",
+            0,
+            Severity::Warning,
+            "connection <0.1.0> closed",
+        ),
+        model(2, "rabbit@b", 1, Severity::Info, "unrelated"),
+        model(
+            3,
+            "rabbit@a",
+            2,
+            Severity::Warning,
+            "connection <0.2.0> closed",
+        ),
+        model(
+            4,
+            "rabbit@a",
+            4,
+            Severity::Warning,
+            "connection <0.3.0> closed",
+        ),
+        // outside of the burst window
+        model(
+            5,
+            "rabbit@a",
+            30,
+            Severity::Warning,
+            "connection <0.4.0> closed",
+        ),
+    ];
+    let timeline = Timeline::build(entries, &TimelineOptions::default());
+
+    assert_eq!(timeline.events.len(), 3);
+    let burst = &timeline.events[0];
+    assert_eq!(burst.entry_id, 1);
+    assert_eq!(burst.count, 3);
+    assert_eq!(burst.last_timestamp, ts(4));
+    assert!(burst.is_burst());
+    assert_eq!(timeline.events[2].count, 1);
+}
+
+#[test]
+fn test_timeline_does_not_collapse_across_severities() {
+    let entries = vec![
+        model(1, "rabbit@a", 0, Severity::Warning, "connection closed"),
+        model(2, "rabbit@a", 1, Severity::Error, "connection closed"),
+    ];
+    let timeline = Timeline::build(entries, &TimelineOptions::default());
+
+    assert_eq!(timeline.events.len(), 2);
+}
+
+#[test]
+fn test_timeline_with_zero_burst_window() {
+    let entries = vec![
+        model(1, "rabbit@a", 0, Severity::Info, "connection closed"),
+        model(2, "rabbit@a", 1, Severity::Info, "connection closed"),
+    ];
+    let options = TimelineOptions::default().burst_window(TimeDelta::zero());
+    let timeline = Timeline::build(entries, &options);
+
+    assert_eq!(timeline.events.len(), 2);
+}
+
+#[test]
+fn test_timeline_transitions_only() {
+    let entries = vec![
+        model(
+            1,
+            "rabbit@a",
+            0,
+            Severity::Info,
+            "RabbitMQ is asked to stop...",
+        ),
+        model(2, "rabbit@a", 1, Severity::Info, "stopping a listener"),
+        model(
+            3,
+            "rabbit@b",
+            2,
+            Severity::Info,
+            "RabbitMQ metadata store: candidate -> leader in term: 2",
+        ),
+    ];
+    let options = TimelineOptions::default().transitions_only(true);
+    let timeline = Timeline::build(entries, &options);
+
+    let transitions: Vec<_> = timeline.events.iter().map(|e| e.transition).collect();
+    assert_eq!(
+        transitions,
+        vec![
+            Some(Transition::NodeShutdown),
+            Some(Transition::LeaderChange)
+        ]
+    );
+    assert_eq!(timeline.transitions().count(), 2);
+}
+
+#[test]
+fn test_timeline_query_reads_all_pages() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database(&temp_dir.path().join("test.db")).unwrap();
+
+    let parsed = |secs: i64, message: &str| -> ParsedLogEntry {
+        let mut entry = create_test_entry(message, Severity::Info);
+        entry.timestamp = ts(secs);
+        entry.labels = annotate_labels(&entry);
+        entry
+    };
+    // Pairs of entries share a timestamp, including across pages
+    let total = TIMELINE_PAGE_SIZE as i64 + 51;
+    let mut entries: Vec<ParsedLogEntry> = (0..total - 1)
+        .map(|i| parsed(i / 2, "stopping a listener"))
+        .collect();
+    entries.push(parsed(total, "RabbitMQ is asked to stop..."));
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "rabbit@a").unwrap();
+
+    let options = TimelineOptions::default().burst_window(TimeDelta::zero());
+    let timeline = Timeline::query(&db, &QueryContext::default(), &options).unwrap();
+    let count: usize = timeline.events.iter().map(|e| e.count).sum();
+    assert_eq!(count, total as usize);
+    assert_eq!(timeline.events.last().unwrap().entry_id, total);
+
+    let ctx = QueryContext::default().limit(10_020);
+    let timeline = Timeline::query(&db, &ctx, &options).unwrap();
+    let count: usize = timeline.events.iter().map(|e| e.count).sum();
+    assert_eq!(count, 10_020);
+
+    let options = options.transitions_only(true);
+    let timeline = Timeline::query(&db, &QueryContext::default(), &options).unwrap();
+    assert_eq!(timeline.events.len(), 1);
+    assert_eq!(
+        timeline.events[0].transition,
+        Some(Transition::NodeShutdown)
+    );
+}
+
+#[test]
+fn test_transition_condition_matches_classified_entries() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database(&temp_dir.path().join("test.db")).unwrap();
+
+    let messages = [
+        "Server startup complete; 5 plugins started.",
+        "RabbitMQ is asked to stop...",
+        "Successfully stopped RabbitMQ and its dependencies",
+        "queue 'orders' in vhost '/': candidate -> leader in term: 3",
+        "RabbitMQ metadata store: candidate -> leader in term: 2",
+        "memory resource limit alarm set on node 'rabbit@a'.",
+        "stopping a listener",
+        "accepting AMQP connection 127.0.0.1:5672",
+        "pre_vote election called",
+    ];
+    let entries: Vec<ParsedLogEntry> = messages
+        .iter()
+        .enumerate()
+        .map(|(i, message)| {
+            let mut entry = create_test_entry(message, Severity::Info);
+            entry.timestamp = ts(i as i64);
+            entry.labels = annotate_labels(&entry);
+            entry
+        })
+        .collect();
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "rabbit@a").unwrap();
+
+    let matching: Vec<String> = NodeLogEntry::query(
+        &db,
+        &QueryContext::default().where_condition(Transition::any_condition()),
+    )
+    .unwrap()
+    .into_iter()
+    .map(|e| e.message)
+    .collect();
+    let classified: Vec<String> = messages
+        .iter()
+        .filter(|message| classify(message).is_some())
+        .map(|message| message.to_string())
+        .collect();
+    assert_eq!(classified.len(), 6);
+    assert_eq!(matching, classified);
+}
@@ -0,0 +1,42 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Error types for obfuscation mapping and policy files.
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid obfuscation mapping: {0}")]
+    InvalidMapping(#[from] serde_json::Error),
+
+    #[error("The obfuscation mapping is encrypted, a passphrase is required to read it")]
+    PassphraseRequired,
+
+    #[error("Failed to decrypt the obfuscation mapping: wrong passphrase or corrupted file")]
+    Decryption,
+
+    #[error("Failed to encrypt the obfuscation mapping")]
+    Encryption,
+
+    #[error("Unsupported obfuscation mapping encryption: {0}")]
+    UnsupportedEncryption(String),
+
+    #[error("Invalid obfuscation policy: {0}")]
+    InvalidPolicy(String),
+}
@@ -12,6 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod errors;
+pub mod mapping;
 mod obfuscator;
+pub mod patterns;
+pub mod policy;
 
+pub use errors::Error;
+pub use mapping::{Deobfuscator, ObfuscationMapping};
 pub use obfuscator::{LogObfuscator, ObfuscationStats};
+pub use policy::{Category, ObfuscationPolicy};
@@ -0,0 +1,278 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Obfuscation mappings: the aliases assigned by a [`crate::LogObfuscator`],
+//! keyed by the original values.
+//!
+//! A mapping can be saved to a JSON file, optionally encrypted with a passphrase,
+//! and imported on later runs so that aliases stay stable across bundles.
+//! A [`Deobfuscator`] uses it to translate aliases back into the original values.
+//! Redacted passwords and tokens are not a part of a mapping and cannot be restored.
+
+use crate::errors::Error;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use regex::Regex;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::num::NonZeroU32;
+use std::path::Path;
+
+const ENCRYPTION_ALGORITHM: &str = "aes-256-gcm";
+const KEY_DERIVATION_ALGORITHM: &str = "pbkdf2-hmac-sha256";
+/// The OWASP recommendation for PBKDF2-HMAC-SHA256
+const KEY_DERIVATION_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// Original values and their aliases, one map per kind of value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObfuscationMapping {
+    pub hostnames: BTreeMap<String, String>,
+    pub directories: BTreeMap<String, String>,
+    pub usernames: BTreeMap<String, String>,
+    pub vhosts: BTreeMap<String, String>,
+    pub queues: BTreeMap<String, String>,
+    pub exchanges: BTreeMap<String, String>,
+    pub streams: BTreeMap<String, String>,
+    pub policies: BTreeMap<String, String>,
+    pub upstreams: BTreeMap<String, String>,
+    pub shovels: BTreeMap<String, String>,
+    pub ipv4_addresses: BTreeMap<String, String>,
+    pub ipv6_addresses: BTreeMap<String, String>,
+    pub email_addresses: BTreeMap<String, String>,
+    pub connection_names: BTreeMap<String, String>,
+    pub windows_paths: BTreeMap<String, String>,
+    pub mac_addresses: BTreeMap<String, String>,
+    /// Values matched by the custom patterns of an obfuscation policy
+    pub custom_values: BTreeMap<String, String>,
+}
+
+/// The on-disk form of a mapping encrypted with a passphrase
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedMapping {
+    encryption: String,
+    key_derivation: String,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl ObfuscationMapping {
+    /// All maps of the mapping, in a fixed order
+    fn maps(&self) -> [&BTreeMap<String, String>; 17] {
+        [
+            &self.hostnames,
+            &self.directories,
+            &self.usernames,
+            &self.vhosts,
+            &self.queues,
+            &self.exchanges,
+            &self.streams,
+            &self.policies,
+            &self.upstreams,
+            &self.shovels,
+            &self.ipv4_addresses,
+            &self.ipv6_addresses,
+            &self.email_addresses,
+            &self.connection_names,
+            &self.windows_paths,
+            &self.mac_addresses,
+            &self.custom_values,
+        ]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.maps().iter().all(|map| map.is_empty())
+    }
+
+    /// The total number of aliases across all kinds of values
+    pub fn len(&self) -> usize {
+        self.maps().iter().map(|map| map.len()).sum()
+    }
+
+    /// Serializes the mapping to JSON. With a passphrase, the JSON document
+    /// is encrypted with AES-256-GCM using a key derived with PBKDF2.
+    pub fn to_json(&self, passphrase: Option<&str>) -> Result<String, Error> {
+        let plain = serde_json::to_string_pretty(self)?;
+        match passphrase {
+            None => Ok(plain),
+            Some(passphrase) => {
+                let encrypted = encrypt(plain.as_bytes(), passphrase)?;
+                Ok(serde_json::to_string_pretty(&encrypted)?)
+            }
+        }
+    }
+
+    /// Deserializes a mapping produced by [`ObfuscationMapping::to_json`].
+    /// The passphrase is only used if the mapping is encrypted.
+    pub fn from_json(json: &str, passphrase: Option<&str>) -> Result<Self, Error> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        if value.get("ciphertext").is_none() {
+            return Ok(serde_json::from_value(value)?);
+        }
+
+        let encrypted: EncryptedMapping = serde_json::from_value(value)?;
+        let passphrase = passphrase.ok_or(Error::PassphraseRequired)?;
+        let plain = decrypt(&encrypted, passphrase)?;
+        Ok(serde_json::from_slice(&plain)?)
+    }
+
+    pub fn write_to_file(&self, path: &Path, passphrase: Option<&str>) -> Result<(), Error> {
+        fs::write(path, self.to_json(passphrase)?)?;
+        Ok(())
+    }
+
+    pub fn read_from_file(path: &Path, passphrase: Option<&str>) -> Result<Self, Error> {
+        Self::from_json(&fs::read_to_string(path)?, passphrase)
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: NonZeroU32) -> Result<LessSafeKey, Error> {
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    let key = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| Error::Encryption)?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn encrypt(plain: &[u8], passphrase: &str) -> Result<EncryptedMapping, Error> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt).map_err(|_| Error::Encryption)?;
+    rng.fill(&mut nonce).map_err(|_| Error::Encryption)?;
+
+    let iterations =
+        NonZeroU32::new(KEY_DERIVATION_ITERATIONS).expect("key derivation iterations are not zero");
+    let key = derive_key(passphrase, &salt, iterations)?;
+    let mut in_out = plain.to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::empty(),
+        &mut in_out,
+    )
+    .map_err(|_| Error::Encryption)?;
+
+    Ok(EncryptedMapping {
+        encryption: ENCRYPTION_ALGORITHM.to_string(),
+        key_derivation: KEY_DERIVATION_ALGORITHM.to_string(),
+        iterations: KEY_DERIVATION_ITERATIONS,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(in_out),
+    })
+}
+
+fn decrypt(encrypted: &EncryptedMapping, passphrase: &str) -> Result<Vec<u8>, Error> {
+    if encrypted.encryption != ENCRYPTION_ALGORITHM {
+        return Err(Error::UnsupportedEncryption(encrypted.encryption.clone()));
+    }
+    if encrypted.key_derivation != KEY_DERIVATION_ALGORITHM {
+        return Err(Error::UnsupportedEncryption(
+            encrypted.key_derivation.clone(),
+        ));
+    }
+
+    let iterations = NonZeroU32::new(encrypted.iterations).ok_or(Error::Decryption)?;
+    let salt = BASE64
+        .decode(&encrypted.salt)
+        .map_err(|_| Error::Decryption)?;
+    let nonce: [u8; NONCE_LEN] = BASE64
+        .decode(&encrypted.nonce)
+        .map_err(|_| Error::Decryption)?
+        .try_into()
+        .map_err(|_| Error::Decryption)?;
+    let mut in_out = BASE64
+        .decode(&encrypted.ciphertext)
+        .map_err(|_| Error::Decryption)?;
+
+    let key = derive_key(passphrase, &salt, iterations)?;
+    let plain = key
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut in_out,
+        )
+        .map_err(|_| Error::Decryption)?;
+    Ok(plain.to_vec())
+}
+
+/// Translates aliases back into the original values
+#[derive(Debug)]
+pub struct Deobfuscator {
+    originals: HashMap<String, String>,
+    alias_re: Option<Regex>,
+}
+
+impl Deobfuscator {
+    pub fn new(mapping: &ObfuscationMapping) -> Self {
+        let originals: HashMap<String, String> = mapping
+            .maps()
+            .into_iter()
+            .flat_map(|map| map.iter())
+            .map(|(original, alias)| (alias.clone(), original.clone()))
+            .collect();
+
+        // Longer aliases go first so that e.g. host12 is not matched as host1
+        let mut aliases: Vec<&String> = originals.keys().collect();
+        aliases.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        let alias_re = (!aliases.is_empty()).then(|| {
+            let alternatives: Vec<String> = aliases.iter().map(|a| regex::escape(a)).collect();
+            Regex::new(&alternatives.join("|")).expect("escaped aliases form a valid regex")
+        });
+
+        Self {
+            originals,
+            alias_re,
+        }
+    }
+
+    /// Replaces every alias that is not a part of a longer word with its original value
+    pub fn deobfuscate_line(&self, line: &str) -> String {
+        let Some(alias_re) = &self.alias_re else {
+            return line.to_string();
+        };
+
+        let mut result = String::with_capacity(line.len());
+        let mut last = 0;
+        for m in alias_re.find_iter(line) {
+            let before = line[..m.start()].chars().next_back();
+            let after = line[m.end()..].chars().next();
+            if before.is_some_and(is_word_char) || after.is_some_and(is_word_char) {
+                continue;
+            }
+            result.push_str(&line[last..m.start()]);
+            result.push_str(&self.originals[m.as_str()]);
+            last = m.end();
+        }
+        result.push_str(&line[last..]);
+        result
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
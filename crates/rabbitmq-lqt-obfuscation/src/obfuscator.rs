@@ -12,9 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::mapping::ObfuscationMapping;
+use crate::patterns;
+use crate::policy::{Category, ObfuscationPolicy};
 use regex::Regex;
-use std::collections::HashMap;
 use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::Ipv4Addr;
+use std::ops::Range;
+
+/// Replaces passwords and tokens, which are never mapped to aliases
+const REDACTED: &str = "******";
+
+/// The domain of email address aliases, reserved for documentation by RFC 2606
+const EMAIL_ALIAS_DOMAIN: &str = "example.com";
+
+/// The prefix of MAC address aliases: a locally administered unicast address
+const MAC_ADDRESS_ALIAS_PREFIX: &str = "02:00:00:";
 
 /// Result of an obfuscation attempt, containing the value and whether it was freshly obfuscated.
 #[derive(Debug, Clone)]
@@ -95,6 +109,35 @@ fn is_obfuscated_directory(dir: &str) -> bool {
     has_numeric_suffix(dir, "/data/path")
 }
 
+/// Checks if an email address looks like it was already obfuscated (e.g., "email1@example.com")
+fn is_obfuscated_email(email: &str) -> bool {
+    email
+        .strip_suffix(EMAIL_ALIAS_DOMAIN)
+        .and_then(|rest| rest.strip_suffix('@'))
+        .is_some_and(|local| has_numeric_suffix(local, "email"))
+}
+
+/// Checks if a connection name looks like it was already obfuscated (e.g., "connection1")
+fn is_obfuscated_connection_name(name: &str) -> bool {
+    has_numeric_suffix(name, "connection")
+}
+
+/// Shovels and federation links name their connections after themselves,
+/// those names are obfuscated by the shovel and federation link patterns
+fn is_plugin_connection_name(name: &str) -> bool {
+    name.starts_with("Shovel ") || name.starts_with("Federation link")
+}
+
+/// Checks if a Windows path looks like it was already obfuscated (e.g., "C:\data\path1")
+fn is_obfuscated_windows_path(path: &str) -> bool {
+    has_numeric_suffix(path, "C:\\data\\path")
+}
+
+/// Checks if a MAC address looks like it was already obfuscated (e.g., "02:00:00:00:00:01")
+fn is_obfuscated_mac_address(mac: &str) -> bool {
+    mac.starts_with(MAC_ADDRESS_ALIAS_PREFIX)
+}
+
 /// Checks if an IPv4 address looks like it was already obfuscated (e.g., "10.0.0.1", "10.0.1.255")
 fn is_obfuscated_ipv4(ip: &str) -> bool {
     ip.starts_with("10.0.")
@@ -105,6 +148,49 @@ fn is_obfuscated_ipv6(ip: &str) -> bool {
     ip.starts_with("fd00::")
 }
 
+/// The number of an alias like "host3", as assigned by the counter of its kind
+fn alias_number(alias: &str, prefix: &str) -> Option<usize> {
+    alias.strip_prefix(prefix)?.parse().ok()
+}
+
+/// The number of an IPv4 alias: "10.0.0.1" is the first one, "10.0.1.1" the 256th
+fn ipv4_alias_number(alias: &str) -> Option<usize> {
+    let mut octets = alias.strip_prefix("10.0.")?.split('.');
+    let octet3: usize = octets.next()?.parse().ok()?;
+    let octet4: usize = octets.next()?.parse().ok()?;
+    Some(octet3 * 255 + octet4)
+}
+
+/// The number of a MAC address alias: "02:00:00:00:00:01" is the first one
+fn mac_address_alias_number(alias: &str) -> Option<usize> {
+    let hex = alias
+        .strip_prefix(MAC_ADDRESS_ALIAS_PREFIX)?
+        .replace(':', "");
+    usize::from_str_radix(&hex, 16).ok()
+}
+
+/// The ranges and values of the first capture group of every match
+fn first_group_matches(re: &Regex, input: &str) -> Vec<(Range<usize>, String)> {
+    re.captures_iter(input)
+        .filter_map(|cap| cap.get(1))
+        .map(|m| (m.range(), m.as_str().to_string()))
+        .collect()
+}
+
+/// Merges imported aliases into a map and moves its counter past the highest imported alias
+fn import_map(
+    map: &mut HashMap<String, String>,
+    counter: &mut usize,
+    imported: BTreeMap<String, String>,
+    alias_number: impl Fn(&str) -> Option<usize>,
+) {
+    for (original, alias) in imported {
+        *counter = (*counter).max(alias_number(&alias).unwrap_or(0));
+        map.insert(original, alias);
+    }
+    *counter = (*counter).max(map.len());
+}
+
 /// Formats a keyword-value replacement preserving the original quote style and separator.
 /// Handles patterns like: `keyword 'value'`, `keyword "value"`, `keyword: 'value'`, `keyword: "value"`
 fn format_quoted_replacement(full_str: &str, keyword: &str, value: &str) -> String {
@@ -133,6 +219,13 @@ pub struct ObfuscationStats {
     pub shovels_obfuscated: usize,
     pub ipv4_addresses_obfuscated: usize,
     pub ipv6_addresses_obfuscated: usize,
+    pub email_addresses_obfuscated: usize,
+    pub connection_names_obfuscated: usize,
+    pub windows_paths_obfuscated: usize,
+    pub mac_addresses_obfuscated: usize,
+    pub custom_values_obfuscated: usize,
+    pub uri_credentials_redacted: usize,
+    pub tokens_redacted: usize,
 }
 
 #[derive(Debug)]
@@ -149,6 +242,14 @@ pub struct LogObfuscator {
     shovel_map: HashMap<String, String>,
     ipv4_map: HashMap<String, String>,
     ipv6_map: HashMap<String, String>,
+    email_map: HashMap<String, String>,
+    connection_name_map: HashMap<String, String>,
+    windows_path_map: HashMap<String, String>,
+    mac_address_map: HashMap<String, String>,
+    custom_map: HashMap<String, String>,
+    /// Node names with fully qualified hostnames, like mq1@node1.example.com,
+    /// look like email addresses, so known ones are told apart
+    node_names: HashSet<String>,
 
     hostname_counter: usize,
     directory_counter: usize,
@@ -162,6 +263,12 @@ pub struct LogObfuscator {
     shovel_counter: usize,
     ipv4_counter: usize,
     ipv6_counter: usize,
+    email_counter: usize,
+    connection_name_counter: usize,
+    windows_path_counter: usize,
+    mac_address_counter: usize,
+    /// One counter per custom pattern of the policy
+    custom_counters: Vec<usize>,
 
     node_name_re: Regex,
     ipv4_re: Regex,
@@ -178,12 +285,25 @@ pub struct LogObfuscator {
     shovel_quoted_re: Regex,
     shovel_erlang_binary_re: Regex,
     erlang_queue_tuple_re: Regex,
-
+    email_re: Regex,
+    uri_credentials_re: Regex,
+    jwt_re: Regex,
+    bearer_token_re: Regex,
+    connection_name_re: Regex,
+    connection_description_name_re: Regex,
+    windows_path_re: Regex,
+    mac_address_re: Regex,
+
+    policy: ObfuscationPolicy,
     stats: ObfuscationStats,
 }
 
 impl LogObfuscator {
     pub fn new() -> Self {
+        Self::with_policy(ObfuscationPolicy::default())
+    }
+
+    pub fn with_policy(policy: ObfuscationPolicy) -> Self {
         Self {
             hostname_map: HashMap::new(),
             directory_map: HashMap::new(),
@@ -197,6 +317,12 @@ impl LogObfuscator {
             shovel_map: HashMap::new(),
             ipv4_map: HashMap::new(),
             ipv6_map: HashMap::new(),
+            email_map: HashMap::new(),
+            connection_name_map: HashMap::new(),
+            windows_path_map: HashMap::new(),
+            mac_address_map: HashMap::new(),
+            custom_map: HashMap::new(),
+            node_names: HashSet::new(),
 
             hostname_counter: 0,
             directory_counter: 0,
@@ -210,77 +336,53 @@ impl LogObfuscator {
             shovel_counter: 0,
             ipv4_counter: 0,
             ipv6_counter: 0,
-
-            // Matches Erlang node names like rabbit@hostname, hare@sunnyside
-            node_name_re: Regex::new(r"([a-zA-Z_][a-zA-Z0-9_]*)@([a-zA-Z][a-zA-Z0-9._-]*)")
-                .expect("node_name_re is a valid regex"),
-
-            // Matches IPv4 addresses
-            ipv4_re: Regex::new(r"\b(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3})\b")
-                .expect("ipv4_re is a valid regex"),
-
-            // Matches IPv6 addresses in bracketed form like [::1] or [fe80::1]
-            ipv6_re: Regex::new(r"\[([0-9a-fA-F:]+)\]").expect("ipv6_re is a valid regex"),
-
-            // Matches Unix-style paths starting with /Users, /home, /var, /tmp, /opt, /etc
-            unix_path_re: Regex::new(
-                r#"(?:"|')?(/(?:Users|home|var|tmp|opt|etc|data)/[a-zA-Z0-9._/-]+)(?:"|')?"#,
-            )
-            .expect("unix_path_re is a valid regex"),
-
-            // Matches user patterns like: user 'username', User 'username', user: 'username'
-            user_pattern_re: Regex::new(r#"(?i)user[:\s]+['"]([^'"]+)['"]"#)
-                .expect("user_pattern_re is a valid regex"),
-
-            // Matches vhost patterns like: vhost '/', vhost: 'name', vhost "name", virtual host '/'
-            vhost_pattern_re: Regex::new(r#"(?:vhost|virtual\s+host)[:\s]+['"]([^'"]+)['"]"#)
+            email_counter: 0,
+            connection_name_counter: 0,
+            windows_path_counter: 0,
+            mac_address_counter: 0,
+            custom_counters: vec![0; policy.custom_patterns().len()],
+
+            node_name_re: Regex::new(patterns::NODE_NAME).expect("node_name_re is a valid regex"),
+            ipv4_re: Regex::new(patterns::IPV4).expect("ipv4_re is a valid regex"),
+            ipv6_re: Regex::new(patterns::IPV6).expect("ipv6_re is a valid regex"),
+            unix_path_re: Regex::new(patterns::UNIX_PATH).expect("unix_path_re is a valid regex"),
+            user_pattern_re: Regex::new(patterns::USER).expect("user_pattern_re is a valid regex"),
+            vhost_pattern_re: Regex::new(patterns::VHOST)
                 .expect("vhost_pattern_re is a valid regex"),
-
-            // Matches queue patterns like: queue 'name', Queue 'name'
-            queue_pattern_re: Regex::new(r#"(?i)queue[:\s]+['"]([^'"]+)['"]"#)
+            queue_pattern_re: Regex::new(patterns::QUEUE)
                 .expect("queue_pattern_re is a valid regex"),
-
-            // Matches exchange patterns like: exchange 'name', Exchange 'name'
-            exchange_pattern_re: Regex::new(r#"(?i)exchange[:\s]+['"]([^'"]+)['"]"#)
+            exchange_pattern_re: Regex::new(patterns::EXCHANGE)
                 .expect("exchange_pattern_re is a valid regex"),
-
-            // Matches stream patterns like: stream 'name', Stream 'name', Stream: 'name'
-            stream_pattern_re: Regex::new(r#"(?i)stream[:\s]+['"]([^'"]+)['"]"#)
+            stream_pattern_re: Regex::new(patterns::STREAM)
                 .expect("stream_pattern_re is a valid regex"),
-
-            // Matches policy patterns like: policy 'name', Policy 'name'
-            policy_pattern_re: Regex::new(r#"(?i)policy[:\s]+['"]([^'"]+)['"]"#)
+            policy_pattern_re: Regex::new(patterns::POLICY)
                 .expect("policy_pattern_re is a valid regex"),
-
-            // Matches federation link patterns like:
-            // Federation link (upstream: name, policy: name)
-            federation_link_re: Regex::new(
-                r"Federation link \(upstream: ([^,]+), policy: ([^)]+)\)",
-            )
-            .expect("federation_link_re is a valid regex"),
-
-            // Matches shovel connection name patterns like:
-            // Shovel name: (without quotes, in connection descriptions)
-            shovel_connection_re: Regex::new(r"Shovel ([a-zA-Z0-9._-]+):")
+            federation_link_re: Regex::new(patterns::FEDERATION_LINK)
+                .expect("federation_link_re is a valid regex"),
+            shovel_connection_re: Regex::new(patterns::SHOVEL_CONNECTION)
                 .expect("shovel_connection_re is a valid regex"),
-
-            // Matches quoted shovel name patterns like:
-            // Shovel 'name' connected, Shovel 'name' in virtual host
-            shovel_quoted_re: Regex::new(r"Shovel '([^']+)'")
+            shovel_quoted_re: Regex::new(patterns::SHOVEL_QUOTED)
                 .expect("shovel_quoted_re is a valid regex"),
-
-            // Matches Erlang binary shovel name patterns like:
-            // Shovel <<"name">> received a 'basic.cancel'
-            shovel_erlang_binary_re: Regex::new(r#"Shovel <<"([^"]+)">>"#)
+            shovel_erlang_binary_re: Regex::new(patterns::SHOVEL_ERLANG_BINARY)
                 .expect("shovel_erlang_binary_re is a valid regex"),
-
-            // Matches Erlang tuples containing queue names and node names like:
-            // {'QueueName','rabbit@host'} in quorum queue Raft log messages
-            erlang_queue_tuple_re: Regex::new(
-                r#"\{'([^']+)','([a-zA-Z_][a-zA-Z0-9_]*)@([a-zA-Z][a-zA-Z0-9._-]*)'\}"#,
-            )
-            .expect("erlang_queue_tuple_re is a valid regex"),
-
+            erlang_queue_tuple_re: Regex::new(patterns::ERLANG_QUEUE_TUPLE)
+                .expect("erlang_queue_tuple_re is a valid regex"),
+            email_re: Regex::new(patterns::EMAIL).expect("email_re is a valid regex"),
+            uri_credentials_re: Regex::new(patterns::URI_CREDENTIALS)
+                .expect("uri_credentials_re is a valid regex"),
+            jwt_re: Regex::new(patterns::JWT).expect("jwt_re is a valid regex"),
+            bearer_token_re: Regex::new(patterns::BEARER_TOKEN)
+                .expect("bearer_token_re is a valid regex"),
+            connection_name_re: Regex::new(patterns::CONNECTION_NAME)
+                .expect("connection_name_re is a valid regex"),
+            connection_description_name_re: Regex::new(patterns::CONNECTION_DESCRIPTION_NAME)
+                .expect("connection_description_name_re is a valid regex"),
+            windows_path_re: Regex::new(patterns::WINDOWS_PATH)
+                .expect("windows_path_re is a valid regex"),
+            mac_address_re: Regex::new(patterns::MAC_ADDRESS)
+                .expect("mac_address_re is a valid regex"),
+
+            policy,
             stats: ObfuscationStats::default(),
         }
     }
@@ -289,8 +391,205 @@ impl LogObfuscator {
         &self.stats
     }
 
+    /// Makes `node` be obfuscated as a node name wherever it occurs, even if it looks
+    /// like an email address. Nodes are known from the names of their log files
+    /// or the `node` column of a database.
+    pub fn add_node_name(&mut self, node: &str) {
+        self.node_names.insert(node.to_string());
+    }
+
+    /// Returns the aliases assigned so far, e.g. to save them for later runs
+    /// or to translate aliases back with a [`crate::Deobfuscator`].
+    pub fn export_mapping(&self) -> ObfuscationMapping {
+        fn sorted(map: &HashMap<String, String>) -> BTreeMap<String, String> {
+            map.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+        }
+
+        ObfuscationMapping {
+            hostnames: sorted(&self.hostname_map),
+            directories: sorted(&self.directory_map),
+            usernames: sorted(&self.username_map),
+            vhosts: sorted(&self.vhost_map),
+            queues: sorted(&self.queue_map),
+            exchanges: sorted(&self.exchange_map),
+            streams: sorted(&self.stream_map),
+            policies: sorted(&self.policy_map),
+            upstreams: sorted(&self.upstream_map),
+            shovels: sorted(&self.shovel_map),
+            ipv4_addresses: sorted(&self.ipv4_map),
+            ipv6_addresses: sorted(&self.ipv6_map),
+            email_addresses: sorted(&self.email_map),
+            connection_names: sorted(&self.connection_name_map),
+            windows_paths: sorted(&self.windows_path_map),
+            mac_addresses: sorted(&self.mac_address_map),
+            custom_values: sorted(&self.custom_map),
+        }
+    }
+
+    /// Reuses the aliases of a previous run. Values seen for the first time
+    /// get aliases numbered after the highest imported one.
+    /// Imported aliases are not counted in [`ObfuscationStats`].
+    pub fn import_mapping(&mut self, mapping: ObfuscationMapping) {
+        import_map(
+            &mut self.hostname_map,
+            &mut self.hostname_counter,
+            mapping.hostnames,
+            |alias| alias_number(alias, "host"),
+        );
+        import_map(
+            &mut self.directory_map,
+            &mut self.directory_counter,
+            mapping.directories,
+            |alias| alias_number(alias, "/data/path"),
+        );
+        import_map(
+            &mut self.username_map,
+            &mut self.username_counter,
+            mapping.usernames,
+            |alias| alias_number(alias, "user"),
+        );
+        import_map(
+            &mut self.vhost_map,
+            &mut self.vhost_counter,
+            mapping.vhosts,
+            |alias| alias_number(alias, "vhost"),
+        );
+        import_map(
+            &mut self.queue_map,
+            &mut self.queue_counter,
+            mapping.queues,
+            |alias| alias_number(alias, "queue"),
+        );
+        import_map(
+            &mut self.exchange_map,
+            &mut self.exchange_counter,
+            mapping.exchanges,
+            |alias| alias_number(alias, "exchange"),
+        );
+        import_map(
+            &mut self.stream_map,
+            &mut self.stream_counter,
+            mapping.streams,
+            |alias| alias_number(alias, "stream"),
+        );
+        import_map(
+            &mut self.policy_map,
+            &mut self.policy_counter,
+            mapping.policies,
+            |alias| alias_number(alias, "policy"),
+        );
+        import_map(
+            &mut self.upstream_map,
+            &mut self.upstream_counter,
+            mapping.upstreams,
+            |alias| alias_number(alias, "upstream"),
+        );
+        import_map(
+            &mut self.shovel_map,
+            &mut self.shovel_counter,
+            mapping.shovels,
+            |alias| alias_number(alias, "shovel"),
+        );
+        import_map(
+            &mut self.ipv4_map,
+            &mut self.ipv4_counter,
+            mapping.ipv4_addresses,
+            ipv4_alias_number,
+        );
+        import_map(
+            &mut self.ipv6_map,
+            &mut self.ipv6_counter,
+            mapping.ipv6_addresses,
+            |alias| alias_number(alias, "fd00::"),
+        );
+        import_map(
+            &mut self.email_map,
+            &mut self.email_counter,
+            mapping.email_addresses,
+            |alias| {
+                let local = alias.split_once('@')?.0;
+                alias_number(local, "email")
+            },
+        );
+        import_map(
+            &mut self.connection_name_map,
+            &mut self.connection_name_counter,
+            mapping.connection_names,
+            |alias| alias_number(alias, "connection"),
+        );
+        import_map(
+            &mut self.windows_path_map,
+            &mut self.windows_path_counter,
+            mapping.windows_paths,
+            |alias| alias_number(alias, "C:\\data\\path"),
+        );
+        import_map(
+            &mut self.mac_address_map,
+            &mut self.mac_address_counter,
+            mapping.mac_addresses,
+            mac_address_alias_number,
+        );
+        // Custom values are shared by all custom patterns, each pattern
+        // continues after the highest imported alias with its prefix
+        for (original, alias) in mapping.custom_values {
+            for (pattern, counter) in self
+                .policy
+                .custom_patterns()
+                .iter()
+                .zip(self.custom_counters.iter_mut())
+            {
+                if let Some(n) = alias_number(&alias, &pattern.prefix) {
+                    *counter = (*counter).max(n);
+                }
+            }
+            self.custom_map.insert(original, alias);
+        }
+    }
+
+    /// Obfuscates a virtual host name that is not part of a log line, e.g. a database column,
+    /// using the same mapping as the virtual hosts mentioned in log lines.
+    pub fn obfuscate_vhost(&mut self, vhost: &str) -> String {
+        self.get_or_create_vhost(vhost).value
+    }
+
+    /// Obfuscates a username that is not part of a log line, e.g. a database column,
+    /// using the same mapping as the usernames mentioned in log lines.
+    pub fn obfuscate_username(&mut self, username: &str) -> String {
+        self.get_or_create_username(username).value
+    }
+
+    /// Obfuscates a log file path: its directory is replaced like other paths,
+    /// and the node name in its file name (e.g. `rabbit@hostname.log`) like node names in log lines.
+    pub fn obfuscate_file_path(&mut self, path: &str) -> String {
+        match path.rsplit_once('/') {
+            Some((dir, file_name)) if !dir.is_empty() => {
+                let dir = self.get_or_create_directory(dir).value;
+                format!("{}/{}", dir, self.obfuscate_file_name(file_name))
+            }
+            _ => self.obfuscate_file_name(path),
+        }
+    }
+
+    fn obfuscate_file_name(&mut self, file_name: &str) -> String {
+        // The node name pattern would otherwise take the extension,
+        // and any rotation or compression suffix, for a part of the hostname
+        match file_name.rfind(".log") {
+            Some(i) => {
+                self.add_node_name(&file_name[..i]);
+                format!(
+                    "{}{}",
+                    self.obfuscate_line(&file_name[..i]),
+                    &file_name[i..]
+                )
+            }
+            None => self.obfuscate_line(file_name),
+        }
+    }
+
     fn get_or_create_hostname(&mut self, hostname: &str) -> ObfuscatedString {
-        if is_obfuscated_hostname(hostname) {
+        if is_obfuscated_hostname(hostname)
+            || !self.policy.obfuscates(Category::Hostnames, hostname)
+        {
             return ObfuscatedString::unchanged(hostname.to_string());
         }
 
@@ -307,7 +606,7 @@ impl LogObfuscator {
     }
 
     fn get_or_create_directory(&mut self, dir: &str) -> ObfuscatedString {
-        if is_obfuscated_directory(dir) {
+        if is_obfuscated_directory(dir) || !self.policy.obfuscates(Category::Directories, dir) {
             return ObfuscatedString::unchanged(dir.to_string());
         }
 
@@ -324,7 +623,9 @@ impl LogObfuscator {
     }
 
     fn get_or_create_username(&mut self, username: &str) -> ObfuscatedString {
-        if is_obfuscated_username(username) {
+        if is_obfuscated_username(username)
+            || !self.policy.obfuscates(Category::Usernames, username)
+        {
             return ObfuscatedString::unchanged(username.to_string());
         }
 
@@ -341,7 +642,7 @@ impl LogObfuscator {
     }
 
     fn get_or_create_vhost(&mut self, vhost: &str) -> ObfuscatedString {
-        if is_obfuscated_vhost(vhost) {
+        if is_obfuscated_vhost(vhost) || !self.policy.obfuscates(Category::Vhosts, vhost) {
             return ObfuscatedString::unchanged(vhost.to_string());
         }
 
@@ -358,7 +659,7 @@ impl LogObfuscator {
     }
 
     fn get_or_create_queue(&mut self, queue: &str) -> ObfuscatedString {
-        if is_obfuscated_queue(queue) {
+        if is_obfuscated_queue(queue) || !self.policy.obfuscates(Category::Queues, queue) {
             return ObfuscatedString::unchanged(queue.to_string());
         }
 
@@ -375,7 +676,9 @@ impl LogObfuscator {
     }
 
     fn get_or_create_exchange(&mut self, exchange: &str) -> ObfuscatedString {
-        if is_obfuscated_exchange(exchange) {
+        if is_obfuscated_exchange(exchange)
+            || !self.policy.obfuscates(Category::Exchanges, exchange)
+        {
             return ObfuscatedString::unchanged(exchange.to_string());
         }
 
@@ -392,7 +695,7 @@ impl LogObfuscator {
     }
 
     fn get_or_create_stream(&mut self, stream: &str) -> ObfuscatedString {
-        if is_obfuscated_stream(stream) {
+        if is_obfuscated_stream(stream) || !self.policy.obfuscates(Category::Streams, stream) {
             return ObfuscatedString::unchanged(stream.to_string());
         }
 
@@ -409,7 +712,7 @@ impl LogObfuscator {
     }
 
     fn get_or_create_policy(&mut self, policy: &str) -> ObfuscatedString {
-        if is_obfuscated_policy(policy) {
+        if is_obfuscated_policy(policy) || !self.policy.obfuscates(Category::Policies, policy) {
             return ObfuscatedString::unchanged(policy.to_string());
         }
 
@@ -426,7 +729,9 @@ impl LogObfuscator {
     }
 
     fn get_or_create_upstream(&mut self, upstream: &str) -> ObfuscatedString {
-        if is_obfuscated_upstream(upstream) {
+        if is_obfuscated_upstream(upstream)
+            || !self.policy.obfuscates(Category::Upstreams, upstream)
+        {
             return ObfuscatedString::unchanged(upstream.to_string());
         }
 
@@ -443,7 +748,7 @@ impl LogObfuscator {
     }
 
     fn get_or_create_shovel(&mut self, shovel: &str) -> ObfuscatedString {
-        if is_obfuscated_shovel(shovel) {
+        if is_obfuscated_shovel(shovel) || !self.policy.obfuscates(Category::Shovels, shovel) {
             return ObfuscatedString::unchanged(shovel.to_string());
         }
 
@@ -464,7 +769,7 @@ impl LogObfuscator {
             return ObfuscatedString::unchanged("0.0.0.0".to_string());
         }
 
-        if is_obfuscated_ipv4(ip) {
+        if is_obfuscated_ipv4(ip) || !self.policy.obfuscates(Category::Ipv4Addresses, ip) {
             return ObfuscatedString::unchanged(ip.to_string());
         }
 
@@ -483,7 +788,7 @@ impl LogObfuscator {
     }
 
     fn get_or_create_ipv6(&mut self, ip: &str) -> ObfuscatedString {
-        if is_obfuscated_ipv6(ip) {
+        if is_obfuscated_ipv6(ip) || !self.policy.obfuscates(Category::Ipv6Addresses, ip) {
             return ObfuscatedString::unchanged(ip.to_string());
         }
 
@@ -499,9 +804,115 @@ impl LogObfuscator {
         }
     }
 
+    fn get_or_create_email(&mut self, email: &str) -> ObfuscatedString {
+        if is_obfuscated_email(email) || !self.policy.obfuscates(Category::EmailAddresses, email) {
+            return ObfuscatedString::unchanged(email.to_string());
+        }
+
+        match self.email_map.entry(email.to_string()) {
+            Entry::Occupied(e) => ObfuscatedString::changed(e.get().clone()),
+            Entry::Vacant(e) => {
+                self.email_counter += 1;
+                self.stats.email_addresses_obfuscated += 1;
+                let value = format!("email{}@{}", self.email_counter, EMAIL_ALIAS_DOMAIN);
+                e.insert(value.clone());
+                ObfuscatedString::changed(value)
+            }
+        }
+    }
+
+    fn get_or_create_connection_name(&mut self, name: &str) -> ObfuscatedString {
+        if is_obfuscated_connection_name(name)
+            || is_plugin_connection_name(name)
+            || !self.policy.obfuscates(Category::ConnectionNames, name)
+        {
+            return ObfuscatedString::unchanged(name.to_string());
+        }
+
+        match self.connection_name_map.entry(name.to_string()) {
+            Entry::Occupied(e) => ObfuscatedString::changed(e.get().clone()),
+            Entry::Vacant(e) => {
+                self.connection_name_counter += 1;
+                self.stats.connection_names_obfuscated += 1;
+                let value = format!("connection{}", self.connection_name_counter);
+                e.insert(value.clone());
+                ObfuscatedString::changed(value)
+            }
+        }
+    }
+
+    fn get_or_create_windows_path(&mut self, path: &str) -> ObfuscatedString {
+        if is_obfuscated_windows_path(path) || !self.policy.obfuscates(Category::WindowsPaths, path)
+        {
+            return ObfuscatedString::unchanged(path.to_string());
+        }
+
+        match self.windows_path_map.entry(path.to_string()) {
+            Entry::Occupied(e) => ObfuscatedString::changed(e.get().clone()),
+            Entry::Vacant(e) => {
+                self.windows_path_counter += 1;
+                self.stats.windows_paths_obfuscated += 1;
+                let value = format!("C:\\data\\path{}", self.windows_path_counter);
+                e.insert(value.clone());
+                ObfuscatedString::changed(value)
+            }
+        }
+    }
+
+    fn get_or_create_mac_address(&mut self, mac: &str) -> ObfuscatedString {
+        if is_obfuscated_mac_address(mac) || !self.policy.obfuscates(Category::MacAddresses, mac) {
+            return ObfuscatedString::unchanged(mac.to_string());
+        }
+
+        match self.mac_address_map.entry(mac.to_string()) {
+            Entry::Occupied(e) => ObfuscatedString::changed(e.get().clone()),
+            Entry::Vacant(e) => {
+                self.mac_address_counter += 1;
+                self.stats.mac_addresses_obfuscated += 1;
+                let n = self.mac_address_counter;
+                let value = format!(
+                    "{}{:02x}:{:02x}:{:02x}",
+                    MAC_ADDRESS_ALIAS_PREFIX,
+                    (n >> 16) & 0xff,
+                    (n >> 8) & 0xff,
+                    n & 0xff
+                );
+                e.insert(value.clone());
+                ObfuscatedString::changed(value)
+            }
+        }
+    }
+
+    fn get_or_create_custom(&mut self, pattern_index: usize, value: &str) -> ObfuscatedString {
+        let prefix = &self.policy.custom_patterns()[pattern_index].prefix;
+        if has_numeric_suffix(value, prefix) || self.policy.is_allowed(value) {
+            return ObfuscatedString::unchanged(value.to_string());
+        }
+
+        match self.custom_map.entry(value.to_string()) {
+            Entry::Occupied(e) => ObfuscatedString::changed(e.get().clone()),
+            Entry::Vacant(e) => {
+                self.custom_counters[pattern_index] += 1;
+                self.stats.custom_values_obfuscated += 1;
+                let value = format!("{}{}", prefix, self.custom_counters[pattern_index]);
+                e.insert(value.clone());
+                ObfuscatedString::changed(value)
+            }
+        }
+    }
+
     pub fn obfuscate_line(&mut self, line: &str) -> String {
         let mut result = line.to_string();
 
+        // Secrets go first, so that no other pattern aliases a part of them
+        if self.policy.is_enabled(Category::Tokens) {
+            result = self.redact_tokens(&result);
+        }
+        if self.policy.is_enabled(Category::UriCredentials) {
+            result = self.redact_uri_credentials(&result);
+        }
+        result = self.obfuscate_custom_patterns(&result);
+
         // Erlang tuples must be processed first to extract queue/hostname before
         // general node name obfuscation transforms them
         result = self.obfuscate_erlang_queue_tuples(&result);
@@ -512,6 +923,11 @@ impl LogObfuscator {
         result = self.obfuscate_shovel_connections(&result);
         result = self.obfuscate_shovel_quoted(&result);
         result = self.obfuscate_shovel_erlang_binary(&result);
+        result = self.obfuscate_connection_names(&result);
+        // Windows paths can contain node names, e.g. of the data directory
+        result = self.obfuscate_windows_paths(&result);
+        // Email addresses must be processed before node names, which they look like
+        result = self.obfuscate_emails(&result);
         result = self.obfuscate_node_names(&result);
         result = self.obfuscate_usernames(&result);
         result = self.obfuscate_vhosts(&result);
@@ -521,6 +937,7 @@ impl LogObfuscator {
         result = self.obfuscate_policies(&result);
         result = self.obfuscate_ipv4(&result);
         result = self.obfuscate_ipv6(&result);
+        result = self.obfuscate_mac_addresses(&result);
         result = self.obfuscate_directories(&result);
 
         result
@@ -537,6 +954,9 @@ impl LogObfuscator {
             let full_match = cap.get(0).unwrap();
             let node_prefix = cap.get(1).unwrap().as_str();
             let hostname = cap.get(2).unwrap().as_str();
+            if is_obfuscated_email(full_match.as_str()) {
+                continue;
+            }
 
             let obfuscated = self.get_or_create_hostname(hostname);
             if !obfuscated.was_obfuscated {
@@ -914,6 +1334,176 @@ impl LogObfuscator {
         }
         result
     }
+
+    fn obfuscate_custom_patterns(&mut self, input: &str) -> String {
+        let mut result = input.to_string();
+        for i in 0..self.policy.custom_patterns().len() {
+            let captures: Vec<_> = self.policy.custom_patterns()[i]
+                .regex
+                .captures_iter(&result)
+                .collect();
+            if captures.is_empty() {
+                continue;
+            }
+
+            let mut replaced = result.clone();
+            for cap in captures.iter().rev() {
+                let value = cap.get(1).unwrap_or_else(|| cap.get(0).unwrap());
+                if value.is_empty() {
+                    continue;
+                }
+                let obfuscated = self.get_or_create_custom(i, value.as_str());
+                if !obfuscated.was_obfuscated {
+                    continue;
+                }
+                replaced.replace_range(value.range(), &obfuscated.value);
+            }
+            result = replaced;
+        }
+        result
+    }
+
+    fn redact_tokens(&mut self, input: &str) -> String {
+        let mut result = input.to_string();
+
+        let captures: Vec<_> = self.bearer_token_re.captures_iter(&result).collect();
+        let mut redacted = result.clone();
+        for cap in captures.iter().rev() {
+            let token = cap.get(2).unwrap();
+            if token.as_str() == REDACTED {
+                continue;
+            }
+            self.stats.tokens_redacted += 1;
+            redacted.replace_range(token.range(), REDACTED);
+        }
+        result = redacted;
+
+        let matches: Vec<_> = self.jwt_re.find_iter(&result).collect();
+        let mut redacted = result.clone();
+        for m in matches.iter().rev() {
+            self.stats.tokens_redacted += 1;
+            redacted.replace_range(m.range(), REDACTED);
+        }
+        redacted
+    }
+
+    fn redact_uri_credentials(&mut self, input: &str) -> String {
+        let captures: Vec<_> = self.uri_credentials_re.captures_iter(input).collect();
+        if captures.is_empty() {
+            return input.to_string();
+        }
+
+        let mut result = input.to_string();
+        for cap in captures.iter().rev() {
+            let scheme = cap.get(1).unwrap().as_str();
+            let user = cap.get(2).unwrap().as_str();
+            let password = cap.get(3).unwrap().as_str();
+            let host = cap.get(4).unwrap().as_str();
+            if password == REDACTED {
+                continue;
+            }
+
+            let user = self.get_or_create_username(user).value;
+            // IP addresses are replaced later, like everywhere else in the line
+            let host = if host.parse::<Ipv4Addr>().is_ok() || host.starts_with('[') {
+                host.to_string()
+            } else {
+                self.get_or_create_hostname(host).value
+            };
+            self.stats.uri_credentials_redacted += 1;
+
+            let full_match = cap.get(0).unwrap();
+            let replacement = format!("{}://{}:{}@{}", scheme, user, REDACTED, host);
+            result.replace_range(full_match.range(), &replacement);
+        }
+        result
+    }
+
+    fn obfuscate_connection_names(&mut self, input: &str) -> String {
+        let names = first_group_matches(&self.connection_name_re, input);
+        let result = self.replace_connection_names(input, names);
+        let names = first_group_matches(&self.connection_description_name_re, &result);
+        self.replace_connection_names(&result, names)
+    }
+
+    fn replace_connection_names(
+        &mut self,
+        input: &str,
+        names: Vec<(Range<usize>, String)>,
+    ) -> String {
+        let mut result = input.to_string();
+        for (range, name) in names.into_iter().rev() {
+            let obfuscated = self.get_or_create_connection_name(&name);
+            if !obfuscated.was_obfuscated {
+                continue;
+            }
+            result.replace_range(range, &obfuscated.value);
+        }
+        result
+    }
+
+    fn obfuscate_windows_paths(&mut self, input: &str) -> String {
+        let captures: Vec<_> = self.windows_path_re.captures_iter(input).collect();
+        if captures.is_empty() {
+            return input.to_string();
+        }
+
+        let mut result = input.to_string();
+        for cap in captures.iter().rev() {
+            // Quotes, if any, are kept: only the path itself is replaced
+            let path = cap
+                .get(1)
+                .or_else(|| cap.get(2))
+                .or_else(|| cap.get(3))
+                .unwrap();
+            let obfuscated = self.get_or_create_windows_path(path.as_str());
+            if !obfuscated.was_obfuscated {
+                continue;
+            }
+            result.replace_range(path.range(), &obfuscated.value);
+        }
+        result
+    }
+
+    fn obfuscate_emails(&mut self, input: &str) -> String {
+        let captures: Vec<_> = self.email_re.captures_iter(input).collect();
+        if captures.is_empty() {
+            return input.to_string();
+        }
+
+        let mut result = input.to_string();
+        for cap in captures.iter().rev() {
+            let full_match = cap.get(0).unwrap();
+            if self.node_names.contains(full_match.as_str()) {
+                continue;
+            }
+
+            let obfuscated = self.get_or_create_email(full_match.as_str());
+            if !obfuscated.was_obfuscated {
+                continue;
+            }
+            result.replace_range(full_match.range(), &obfuscated.value);
+        }
+        result
+    }
+
+    fn obfuscate_mac_addresses(&mut self, input: &str) -> String {
+        let captures: Vec<_> = self.mac_address_re.captures_iter(input).collect();
+        if captures.is_empty() {
+            return input.to_string();
+        }
+
+        let mut result = input.to_string();
+        for cap in captures.iter().rev() {
+            let mac = cap.get(1).unwrap();
+            let obfuscated = self.get_or_create_mac_address(mac.as_str());
+            if !obfuscated.was_obfuscated {
+                continue;
+            }
+            result.replace_range(mac.range(), &obfuscated.value);
+        }
+        result
+    }
 }
 
 impl Default for LogObfuscator {
@@ -0,0 +1,173 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Obfuscation policies loaded from a TOML policy file.
+//!
+//! A policy can disable categories of values, list values that are never obfuscated,
+//! and add custom patterns with the prefix of their aliases:
+//!
+//! ```toml
+//! disabled_categories = ["exchanges", "mac_addresses"]
+//! allowed_values = ["/", "guest"]
+//!
+//! [[custom_patterns]]
+//! name = "customer-ids"
+//! regex = 'customer_id=(\d+)'
+//! prefix = "customer"
+//! ```
+//!
+//! A custom pattern replaces its first capture group, or the entire match if it has none.
+//! Custom patterns are applied in the order they are defined, before the built-in ones.
+
+use crate::errors::Error;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// A category of values the obfuscator detects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Hostnames,
+    Directories,
+    Usernames,
+    Vhosts,
+    Queues,
+    Exchanges,
+    Streams,
+    Policies,
+    Upstreams,
+    Shovels,
+    Ipv4Addresses,
+    Ipv6Addresses,
+    EmailAddresses,
+    UriCredentials,
+    Tokens,
+    ConnectionNames,
+    WindowsPaths,
+    MacAddresses,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFile {
+    #[serde(default)]
+    disabled_categories: Vec<Category>,
+    #[serde(default)]
+    allowed_values: Vec<String>,
+    #[serde(default)]
+    custom_patterns: Vec<CustomPatternDefinition>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CustomPatternDefinition {
+    name: String,
+    regex: String,
+    prefix: String,
+}
+
+/// A user-defined pattern whose matches are replaced with `{prefix}{n}` aliases
+#[derive(Debug, Clone)]
+pub struct CustomPattern {
+    pub name: String,
+    pub regex: Regex,
+    pub prefix: String,
+}
+
+impl CustomPattern {
+    fn compile(definition: CustomPatternDefinition) -> Result<Self, String> {
+        if definition.prefix.is_empty() {
+            return Err(format!(
+                "custom pattern '{}' has an empty prefix",
+                definition.name
+            ));
+        }
+        let regex = Regex::new(&definition.regex).map_err(|e| {
+            format!(
+                "custom pattern '{}' has an invalid regex: {}",
+                definition.name, e
+            )
+        })?;
+        Ok(Self {
+            name: definition.name,
+            regex,
+            prefix: definition.prefix,
+        })
+    }
+}
+
+/// Which values a [`crate::LogObfuscator`] replaces. The default policy
+/// enables every built-in category and has no custom patterns.
+#[derive(Debug, Clone, Default)]
+pub struct ObfuscationPolicy {
+    disabled_categories: HashSet<Category>,
+    allowed_values: HashSet<String>,
+    custom_patterns: Vec<CustomPattern>,
+}
+
+impl ObfuscationPolicy {
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let contents = fs::read_to_string(path)?;
+        Self::from_toml_str(&contents).map_err(|e| match e {
+            Error::InvalidPolicy(reason) => {
+                Error::InvalidPolicy(format!("{}: {}", path.display(), reason))
+            }
+            other => other,
+        })
+    }
+
+    pub fn from_toml_str(s: &str) -> Result<Self, Error> {
+        let file: PolicyFile = toml::from_str(s)
+            .map_err(|e| Error::InvalidPolicy(e.to_string().trim_end().to_string()))?;
+        let custom_patterns = file
+            .custom_patterns
+            .into_iter()
+            .map(CustomPattern::compile)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Error::InvalidPolicy)?;
+        Ok(Self {
+            disabled_categories: file.disabled_categories.into_iter().collect(),
+            allowed_values: file.allowed_values.into_iter().collect(),
+            custom_patterns,
+        })
+    }
+
+    pub fn disable(&mut self, category: Category) {
+        self.disabled_categories.insert(category);
+    }
+
+    pub fn allow(&mut self, value: &str) {
+        self.allowed_values.insert(value.to_string());
+    }
+
+    pub fn is_enabled(&self, category: Category) -> bool {
+        !self.disabled_categories.contains(&category)
+    }
+
+    pub fn is_allowed(&self, value: &str) -> bool {
+        self.allowed_values.contains(value)
+    }
+
+    /// Whether a value of the given category should be replaced
+    pub fn obfuscates(&self, category: Category, value: &str) -> bool {
+        self.is_enabled(category) && !self.is_allowed(value)
+    }
+
+    pub fn custom_patterns(&self) -> &[CustomPattern] {
+        &self.custom_patterns
+    }
+}
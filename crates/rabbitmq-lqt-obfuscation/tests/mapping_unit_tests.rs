@@ -0,0 +1,152 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use rabbitmq_lqt_obfuscation::{Deobfuscator, Error, LogObfuscator, ObfuscationMapping};
+use tempfile::NamedTempFile;
+
+fn obfuscator_with_aliases() -> LogObfuscator {
+    let mut obfuscator = LogObfuscator::new();
+    obfuscator.obfuscate_line("Node rabbit@sunnyside started");
+    obfuscator.obfuscate_line("queue 'orders' in vhost 'production': leader elected");
+    obfuscator.obfuscate_line("accepting AMQP connection 192.168.1.5:56789 -> 192.168.1.10:5672");
+    obfuscator
+}
+
+#[test]
+fn test_export_mapping() {
+    let mapping = obfuscator_with_aliases().export_mapping();
+
+    assert_eq!(mapping.hostnames["sunnyside"], "host1");
+    assert_eq!(mapping.queues["orders"], "queue1");
+    assert_eq!(mapping.vhosts["production"], "vhost1");
+    assert_eq!(mapping.ipv4_addresses["192.168.1.5"], "10.0.0.2");
+    assert_eq!(mapping.ipv4_addresses["192.168.1.10"], "10.0.0.1");
+    assert_eq!(mapping.len(), 5);
+}
+
+#[test]
+fn test_imported_mapping_keeps_aliases_stable() {
+    let mapping = obfuscator_with_aliases().export_mapping();
+
+    let mut obfuscator = LogObfuscator::new();
+    obfuscator.import_mapping(mapping);
+
+    let output =
+        obfuscator.obfuscate_line("queue 'invoices' in vhost 'production' on rabbit@rainyday");
+    assert!(output.contains("queue 'queue2'"));
+    assert!(output.contains("vhost 'vhost1'"));
+    assert!(output.contains("rabbit@host2"));
+
+    let output = obfuscator.obfuscate_line("Node rabbit@sunnyside stopped");
+    assert!(output.contains("rabbit@host1"));
+
+    let output = obfuscator.obfuscate_line("client 192.168.1.7 connected to 192.168.1.10");
+    assert!(output.contains("10.0.0.3"));
+    assert!(output.contains("10.0.0.1"));
+
+    // Only the values seen for the first time are counted
+    assert_eq!(obfuscator.stats().hostnames_obfuscated, 1);
+    assert_eq!(obfuscator.stats().queues_obfuscated, 1);
+    assert_eq!(obfuscator.stats().vhosts_obfuscated, 0);
+}
+
+#[test]
+fn test_mapping_json_round_trip() {
+    let mapping = obfuscator_with_aliases().export_mapping();
+
+    let json = mapping.to_json(None).unwrap();
+    assert!(json.contains("sunnyside"));
+
+    let restored = ObfuscationMapping::from_json(&json, None).unwrap();
+    assert_eq!(restored, mapping);
+}
+
+#[test]
+fn test_mapping_from_json_with_missing_kinds() {
+    let mapping =
+        ObfuscationMapping::from_json(r#"{"hostnames": {"sunnyside": "host1"}}"#, None).unwrap();
+
+    assert_eq!(mapping.hostnames["sunnyside"], "host1");
+    assert!(mapping.queues.is_empty());
+}
+
+#[test]
+fn test_encrypted_mapping_round_trip() {
+    let mapping = obfuscator_with_aliases().export_mapping();
+    let file = NamedTempFile::new().unwrap();
+
+    mapping
+        .write_to_file(file.path(), Some("correct horse battery staple"))
+        .unwrap();
+
+    let contents = std::fs::read_to_string(file.path()).unwrap();
+    assert!(!contents.contains("sunnyside"));
+    assert!(!contents.contains("orders"));
+
+    let restored =
+        ObfuscationMapping::read_from_file(file.path(), Some("correct horse battery staple"))
+            .unwrap();
+    assert_eq!(restored, mapping);
+}
+
+#[test]
+fn test_encrypted_mapping_with_wrong_passphrase() {
+    let json = obfuscator_with_aliases()
+        .export_mapping()
+        .to_json(Some("correct horse battery staple"))
+        .unwrap();
+
+    let result = ObfuscationMapping::from_json(&json, Some("incorrect horse"));
+    assert!(matches!(result, Err(Error::Decryption)));
+
+    let result = ObfuscationMapping::from_json(&json, None);
+    assert!(matches!(result, Err(Error::PassphraseRequired)));
+}
+
+#[test]
+fn test_deobfuscate_line() {
+    let mut obfuscator = obfuscator_with_aliases();
+    let obfuscated =
+        obfuscator.obfuscate_line("queue 'orders' in vhost 'production' on node rabbit@sunnyside");
+
+    let deobfuscator = Deobfuscator::new(&obfuscator.export_mapping());
+    assert_eq!(
+        deobfuscator.deobfuscate_line(&obfuscated),
+        "queue 'orders' in vhost 'production' on node rabbit@sunnyside"
+    );
+    assert_eq!(
+        deobfuscator.deobfuscate_line("please look at queue1 on host1, client 10.0.0.2"),
+        "please look at orders on sunnyside, client 192.168.1.5"
+    );
+}
+
+#[test]
+fn test_deobfuscate_line_only_replaces_whole_aliases() {
+    let deobfuscator = Deobfuscator::new(&obfuscator_with_aliases().export_mapping());
+
+    // host12, ghost1 and 10.0.0.15 are not aliases in this mapping
+    assert_eq!(
+        deobfuscator.deobfuscate_line("host12 ghost1 10.0.0.15 vhost1"),
+        "host12 ghost1 10.0.0.15 production"
+    );
+}
+
+#[test]
+fn test_deobfuscate_line_with_empty_mapping() {
+    let deobfuscator = Deobfuscator::new(&ObfuscationMapping::default());
+
+    assert_eq!(
+        deobfuscator.deobfuscate_line("queue1 on host1"),
+        "queue1 on host1"
+    );
+}
@@ -0,0 +1,192 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rabbitmq_lqt_obfuscation::{Category, Error, LogObfuscator, ObfuscationPolicy};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+#[test]
+fn test_default_policy_enables_all_categories() {
+    let policy = ObfuscationPolicy::default();
+
+    assert!(policy.is_enabled(Category::Hostnames));
+    assert!(policy.is_enabled(Category::MacAddresses));
+    assert!(policy.obfuscates(Category::Vhosts, "/"));
+    assert!(policy.custom_patterns().is_empty());
+}
+
+#[test]
+fn test_policy_from_toml_str() {
+    let policy = ObfuscationPolicy::from_toml_str(
+        r#"
+disabled_categories = ["exchanges", "mac_addresses"]
+allowed_values = ["/", "guest"]
+
+[[custom_patterns]]
+name = "customer-ids"
+regex = 'customer_id=(\d+)'
+prefix = "customer"
+"#,
+    )
+    .unwrap();
+
+    assert!(!policy.is_enabled(Category::Exchanges));
+    assert!(!policy.is_enabled(Category::MacAddresses));
+    assert!(policy.is_enabled(Category::Queues));
+    assert!(policy.is_allowed("guest"));
+    assert!(!policy.obfuscates(Category::Vhosts, "/"));
+    assert_eq!(policy.custom_patterns().len(), 1);
+    assert_eq!(policy.custom_patterns()[0].prefix, "customer");
+}
+
+#[test]
+fn test_policy_with_unknown_category() {
+    let result = ObfuscationPolicy::from_toml_str(r#"disabled_categories = ["rabbits"]"#);
+    assert!(matches!(result, Err(Error::InvalidPolicy(_))));
+}
+
+#[test]
+fn test_policy_with_unknown_field() {
+    let result = ObfuscationPolicy::from_toml_str(r#"allowed = ["guest"]"#);
+    assert!(matches!(result, Err(Error::InvalidPolicy(_))));
+}
+
+#[test]
+fn test_policy_with_invalid_custom_regex() {
+    let result = ObfuscationPolicy::from_toml_str(
+        r#"
+[[custom_patterns]]
+name = "broken"
+regex = "customer_id=("
+prefix = "customer"
+"#,
+    );
+    let Err(Error::InvalidPolicy(reason)) = result else {
+        panic!("expected an invalid policy error");
+    };
+    assert!(reason.contains("custom pattern 'broken' has an invalid regex"));
+}
+
+#[test]
+fn test_policy_from_file_includes_path_in_errors() {
+    let mut file = NamedTempFile::new().unwrap();
+    writeln!(file, r#"disabled_categories = "queues""#).unwrap();
+
+    let Err(Error::InvalidPolicy(reason)) = ObfuscationPolicy::from_file(file.path()) else {
+        panic!("expected an invalid policy error");
+    };
+    assert!(reason.starts_with(&file.path().display().to_string()));
+}
+
+#[test]
+fn test_disabled_categories_are_left_as_is() {
+    let mut policy = ObfuscationPolicy::default();
+    policy.disable(Category::Exchanges);
+    policy.disable(Category::Ipv4Addresses);
+    let mut obfuscator = LogObfuscator::with_policy(policy);
+
+    let output = obfuscator.obfuscate_line(
+        "exchange 'amq.topic' and queue 'orders' on 192.168.1.5 and rabbit@sunnyside",
+    );
+    assert!(output.contains("exchange 'amq.topic'"));
+    assert!(output.contains("queue 'queue1'"));
+    assert!(output.contains("192.168.1.5"));
+    assert!(output.contains("rabbit@host1"));
+    assert_eq!(obfuscator.stats().exchanges_obfuscated, 0);
+    assert_eq!(obfuscator.stats().ipv4_addresses_obfuscated, 0);
+}
+
+#[test]
+fn test_disabled_redaction_categories_are_left_as_is() {
+    let mut policy = ObfuscationPolicy::default();
+    policy.disable(Category::Tokens);
+    let mut obfuscator = LogObfuscator::with_policy(policy);
+
+    let output = obfuscator.obfuscate_line("Authorization: Bearer abc123");
+    assert_eq!(output, "Authorization: Bearer abc123");
+    assert_eq!(obfuscator.stats().tokens_redacted, 0);
+}
+
+#[test]
+fn test_allowed_values_are_left_as_is() {
+    let mut policy = ObfuscationPolicy::default();
+    policy.allow("/");
+    policy.allow("guest");
+    let mut obfuscator = LogObfuscator::with_policy(policy);
+
+    let output =
+        obfuscator.obfuscate_line("user 'guest' authenticated and granted access to vhost '/'");
+    assert_eq!(
+        output,
+        "user 'guest' authenticated and granted access to vhost '/'"
+    );
+
+    let output = obfuscator
+        .obfuscate_line("user 'alice' authenticated and granted access to vhost 'production'");
+    assert_eq!(
+        output,
+        "user 'user1' authenticated and granted access to vhost 'vhost1'"
+    );
+    assert_eq!(obfuscator.obfuscate_username("guest"), "guest");
+}
+
+#[test]
+fn test_custom_patterns() {
+    let policy = ObfuscationPolicy::from_toml_str(
+        r#"
+[[custom_patterns]]
+name = "customer-ids"
+regex = 'customer_id=(\d+)'
+prefix = "customer"
+
+[[custom_patterns]]
+name = "ticket-numbers"
+regex = 'TICKET-\d+'
+prefix = "ticket"
+"#,
+    )
+    .unwrap();
+    let mut obfuscator = LogObfuscator::with_policy(policy);
+
+    let output = obfuscator.obfuscate_line("publish failed for customer_id=4242 (TICKET-17)");
+    assert_eq!(output, "publish failed for customer_id=customer1 (ticket1)");
+
+    let output = obfuscator.obfuscate_line("retried customer_id=4242 and customer_id=77");
+    assert_eq!(
+        output,
+        "retried customer_id=customer1 and customer_id=customer2"
+    );
+    assert_eq!(obfuscator.stats().custom_values_obfuscated, 3);
+}
+
+#[test]
+fn test_custom_values_are_exported_and_imported() {
+    let toml = r#"
+[[custom_patterns]]
+name = "customer-ids"
+regex = 'customer_id=(\d+)'
+prefix = "customer"
+"#;
+    let mut obfuscator =
+        LogObfuscator::with_policy(ObfuscationPolicy::from_toml_str(toml).unwrap());
+    obfuscator.obfuscate_line("customer_id=4242");
+    let mapping = obfuscator.export_mapping();
+    assert_eq!(mapping.custom_values["4242"], "customer1");
+
+    let mut obfuscator =
+        LogObfuscator::with_policy(ObfuscationPolicy::from_toml_str(toml).unwrap());
+    obfuscator.import_mapping(mapping);
+    let output = obfuscator.obfuscate_line("customer_id=77 customer_id=4242");
+    assert_eq!(output, "customer_id=customer2 customer_id=customer1");
+}
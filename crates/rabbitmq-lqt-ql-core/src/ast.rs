@@ -20,6 +20,7 @@ use chrono::{DateTime, Utc};
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Query {
     pub time_range: Option<Duration>,
+    pub time_anchor: Option<RangeAnchor>,
     pub selector: Option<Selector>,
     pub filter: Option<FilterExpr>,
     pub pipeline: Vec<PipelineStage>,
@@ -47,6 +48,17 @@ pub enum Field {
     Labels,
     Timestamp,
     Id,
+    Domain,
+    Mfa,
+    File,
+    Line,
+    Connection,
+    Vhost,
+    User,
+    Queue,
+    Stream,
+    Exchange,
+    Pattern,
 }
 
 impl Field {
@@ -60,6 +72,17 @@ impl Field {
             Self::Labels => "labels",
             Self::Timestamp => "timestamp",
             Self::Id => "id",
+            Self::Domain => "domain",
+            Self::Mfa => "mfa",
+            Self::File => "file",
+            Self::Line => "line",
+            Self::Connection => "connection",
+            Self::Vhost => "vhost",
+            Self::User => "user",
+            Self::Queue => "queue",
+            Self::Stream => "stream",
+            Self::Exchange => "exchange",
+            Self::Pattern => "pattern",
         }
     }
 
@@ -73,6 +96,17 @@ impl Field {
             Self::Labels => "labels",
             Self::Timestamp => "timestamp",
             Self::Id => "id",
+            Self::Domain => "domain",
+            Self::Mfa => "mfa",
+            Self::File => "source_file",
+            Self::Line => "source_line",
+            Self::Connection => "connection",
+            Self::Vhost => "vhost",
+            Self::User => "username",
+            Self::Queue => "queue",
+            Self::Stream => "stream",
+            Self::Exchange => "exchange",
+            Self::Pattern => "pattern",
         }
     }
 }
@@ -140,6 +174,18 @@ impl Duration {
     }
 }
 
+/// What a relative time range such as `@1h` is measured back from,
+/// e.g. `@1h from end` or `@1h from "2025-10-27 18:00:00"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeAnchor {
+    /// The current wall clock time.
+    Now,
+    /// The most recent entry in the log set.
+    LogEnd,
+    /// An explicit timestamp, in any format accepted by time comparisons.
+    At(String),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DurationUnit {
     Seconds,
@@ -174,6 +220,7 @@ pub enum FilterExpr {
     HasResolutionUrl,
     Unlabelled,
     Preset(PresetName),
+    Search(String),
     Grouped(Box<FilterExpr>),
 }
 
@@ -184,10 +231,14 @@ pub enum PipelineStage {
     Offset(u64),
     Head(u64),
     Tail(u64),
-    Sort(SortSpec),
+    /// Sorts by the given keys, most significant first.
+    Sort(Vec<SortSpec>),
     Project(Vec<Field>),
     CountBy(Option<Field>),
     Distinct(Vec<Field>),
+    /// Expands every result with up to N entries logged before and after it,
+    /// optionally only those of the same node or process.
+    Context(u64, Option<Field>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -159,6 +159,72 @@ pub const FIELDS: &[FieldInfo] = &[
         description: "Log entry unique identifier",
         example_values: &["1", "42"],
     },
+    FieldInfo {
+        name: "domain",
+        aliases: &[],
+        description: "Erlang logger domain",
+        example_values: &["rabbitmq", "rabbitmq.prelaunch"],
+    },
+    FieldInfo {
+        name: "mfa",
+        aliases: &[],
+        description: "Module, function and arity that logged the entry",
+        example_values: &["rabbit_networking:boot/0"],
+    },
+    FieldInfo {
+        name: "file",
+        aliases: &["source_file"],
+        description: "Source file that logged the entry",
+        example_values: &["rabbit_networking.erl"],
+    },
+    FieldInfo {
+        name: "line",
+        aliases: &["source_line"],
+        description: "Source line that logged the entry",
+        example_values: &["145"],
+    },
+    FieldInfo {
+        name: "connection",
+        aliases: &["conn"],
+        description: "Client connection the entry is about",
+        example_values: &["10.0.0.2:57586 -> 10.0.0.1:5672"],
+    },
+    FieldInfo {
+        name: "vhost",
+        aliases: &["virtual_host"],
+        description: "Virtual host the entry is about",
+        example_values: &["/", "vhost1"],
+    },
+    FieldInfo {
+        name: "user",
+        aliases: &["username"],
+        description: "User the entry is about",
+        example_values: &["guest", "user1"],
+    },
+    FieldInfo {
+        name: "queue",
+        aliases: &[],
+        description: "A queue mentioned by the entry",
+        example_values: &["orders", "queue1"],
+    },
+    FieldInfo {
+        name: "stream",
+        aliases: &[],
+        description: "A stream mentioned by the entry",
+        example_values: &["events", "stream1"],
+    },
+    FieldInfo {
+        name: "exchange",
+        aliases: &[],
+        description: "An exchange mentioned by the entry",
+        example_values: &["amq.topic", "exchange1"],
+    },
+    FieldInfo {
+        name: "pattern",
+        aliases: &["template"],
+        description: "Message template with PIDs, names, addresses and numbers replaced by placeholders",
+        example_values: &["accepting AMQP connection <ip> -> <ip>"],
+    },
 ];
 
 pub const OPERATORS: &[OperatorInfo] = &[
@@ -264,8 +330,8 @@ pub const PIPELINE_STAGES: &[PipelineStageInfo] = &[
     PipelineStageInfo {
         name: "sort",
         aliases: &["order by"],
-        syntax: "sort <field> [asc|desc]",
-        description: "Sort results by a field",
+        syntax: "sort <field> [asc|desc][, <field> [asc|desc]...]",
+        description: "Sort results by one or more fields",
     },
     PipelineStageInfo {
         name: "count",
@@ -285,9 +351,15 @@ pub const PIPELINE_STAGES: &[PipelineStageInfo] = &[
         syntax: "project <field>[, <field>...]",
         description: "Select specific fields to return",
     },
+    PipelineStageInfo {
+        name: "context",
+        aliases: &[],
+        syntax: "context <n> [by node|pid]",
+        description: "Include N entries before and after each result",
+    },
 ];
 
-pub const SPECIAL_FILTERS: &[&str] = &["has_doc_url", "has_resolution_url", "unlabelled"];
+pub const SPECIAL_FILTERS: &[&str] = &["has_doc_url", "has_resolution_url", "unlabelled", "search"];
 
 pub const DURATION_UNITS: &[DurationUnitInfo] = &[
     DurationUnitInfo {
@@ -530,6 +602,17 @@ impl Field {
             Field::Labels,
             Field::Timestamp,
             Field::Id,
+            Field::Domain,
+            Field::Mfa,
+            Field::File,
+            Field::Line,
+            Field::Connection,
+            Field::Vhost,
+            Field::User,
+            Field::Queue,
+            Field::Stream,
+            Field::Exchange,
+            Field::Pattern,
         ]
     }
 
@@ -540,6 +623,12 @@ impl Field {
             Field::Message => &["msg"],
             Field::Labels => &["label"],
             Field::Timestamp => &["time", "ts"],
+            Field::File => &["source_file"],
+            Field::Line => &["source_line"],
+            Field::Connection => &["conn"],
+            Field::Vhost => &["virtual_host"],
+            Field::User => &["username"],
+            Field::Pattern => &["template"],
             _ => &[],
         }
     }
@@ -26,7 +26,7 @@ pub mod presets;
 
 pub use ast::{
     Duration, DurationUnit, Field, FilterExpr, LabelMatcher, MatchOp, PipelineStage, Query,
-    Selector, SortDirection, SortSpec, Value,
+    RangeAnchor, Selector, SortDirection, SortSpec, Value,
 };
 pub use errors::{Diagnostic, ParseError, Span};
 pub use parser::{parse, parse_filter_only};
@@ -16,7 +16,7 @@
 
 use crate::ast::{
     Duration, DurationUnit, Field, FilterExpr, LabelMatcher, MatchOp, PipelineStage, Query,
-    Selector, SortDirection, SortSpec, Value,
+    RangeAnchor, Selector, SortDirection, SortSpec, Value,
 };
 use crate::errors::ParseError;
 use crate::presets::PresetName;
@@ -24,7 +24,7 @@ use nom::{
     IResult, Parser,
     branch::alt,
     bytes::complete::{escaped_transform, tag, tag_no_case, take_until, take_while1},
-    character::complete::{char, digit1, multispace0, multispace1, none_of},
+    character::complete::{alpha1, char, digit1, multispace0, multispace1, none_of},
     combinator::{all_consuming, map, map_res, opt, value},
     error::{Error as NomError, ErrorKind},
     multi::{many0, separated_list0, separated_list1},
@@ -78,6 +78,15 @@ fn detect_preset_error(original: &str, remaining: &str) -> Option<ParseError> {
 fn parse_query(input: &str) -> IResult<&str, Query> {
     let (input, _) = multispace0.parse(input)?;
     let (input, time_range) = opt(parse_time_range).parse(input)?;
+    let (input, time_anchor) = if time_range.is_some() {
+        opt(preceded(
+            (multispace1, tag_no_case("from"), multispace1),
+            parse_range_anchor,
+        ))
+        .parse(input)?
+    } else {
+        (input, None)
+    };
     let (input, _) = multispace0.parse(input)?;
 
     let (input, has_wildcard) = opt(char('*')).parse(input)?;
@@ -104,6 +113,7 @@ fn parse_query(input: &str) -> IResult<&str, Query> {
         input,
         Query {
             time_range,
+            time_anchor,
             selector,
             filter,
             pipeline,
@@ -115,6 +125,15 @@ fn parse_time_range(input: &str) -> IResult<&str, Duration> {
     preceded(char('@'), parse_duration).parse(input)
 }
 
+fn parse_range_anchor(input: &str) -> IResult<&str, RangeAnchor> {
+    alt((
+        value(RangeAnchor::Now, tag_no_case("now")),
+        value(RangeAnchor::LogEnd, tag_no_case("end")),
+        map(parse_quoted_string, RangeAnchor::At),
+    ))
+    .parse(input)
+}
+
 fn parse_duration(input: &str) -> IResult<&str, Duration> {
     let (input, value) = map_res(digit1, |s: &str| s.parse::<i64>()).parse(input)?;
     let (input, unit) = parse_duration_unit(input)?;
@@ -148,7 +167,7 @@ fn parse_label_matcher(input: &str) -> IResult<&str, LabelMatcher> {
     let (input, _) = multispace0.parse(input)?;
     let (input, op) = parse_selector_op(input)?;
     let (input, _) = multispace0.parse(input)?;
-    let (input, value) = parse_value(input)?;
+    let (input, value) = parse_field_value(field, input)?;
 
     Ok((input, LabelMatcher { field, op, value }))
 }
@@ -166,6 +185,17 @@ fn parse_field(input: &str) -> IResult<&str, Field> {
         "labels" | "label" => Field::Labels,
         "timestamp" | "time" | "ts" => Field::Timestamp,
         "id" => Field::Id,
+        "domain" => Field::Domain,
+        "mfa" => Field::Mfa,
+        "file" | "source_file" => Field::File,
+        "line" | "source_line" => Field::Line,
+        "connection" | "conn" => Field::Connection,
+        "vhost" | "virtual_host" => Field::Vhost,
+        "user" | "username" => Field::User,
+        "queue" => Field::Queue,
+        "stream" => Field::Stream,
+        "exchange" => Field::Exchange,
+        "pattern" | "template" => Field::Pattern,
         _ => {
             return Err(nom::Err::Error(NomError::new(input, ErrorKind::Tag)));
         }
@@ -213,6 +243,19 @@ fn parse_value(input: &str) -> IResult<&str, Value> {
     .parse(input)
 }
 
+/// Severity levels can also be written without quotes, e.g. `severity >= warning`.
+fn parse_field_value(field: Field, input: &str) -> IResult<&str, Value> {
+    if field == Field::Severity {
+        alt((parse_value, parse_bare_severity)).parse(input)
+    } else {
+        parse_value(input)
+    }
+}
+
+fn parse_bare_severity(input: &str) -> IResult<&str, Value> {
+    map(alpha1, |level: &str| Value::String(level.to_string())).parse(input)
+}
+
 fn parse_relative_time_value(input: &str) -> IResult<&str, Value> {
     let (input, _) = char('@').parse(input)?;
     let (input, duration) = parse_duration(input)?;
@@ -375,6 +418,7 @@ fn parse_primary_expr(input: &str) -> IResult<&str, FilterExpr> {
         parse_grouped_expr,
         parse_preset_expr,
         parse_special_filter,
+        parse_search_expr,
         parse_hashtag_label,
         parse_label_filter,
         parse_subsystem_filter,
@@ -431,6 +475,14 @@ fn parse_special_filter(input: &str) -> IResult<&str, FilterExpr> {
     .parse(input)
 }
 
+fn parse_search_expr(input: &str) -> IResult<&str, FilterExpr> {
+    let (input, _) = tag_no_case("search").parse(input)?;
+    let (input, _) = multispace1.parse(input)?;
+    let (input, text) = parse_quoted_string(input)?;
+
+    Ok((input, FilterExpr::Search(text)))
+}
+
 fn parse_label_filter(input: &str) -> IResult<&str, FilterExpr> {
     let (input, _) = tag_no_case("labels").parse(input)?;
     let (input, _) = multispace0.parse(input)?;
@@ -492,7 +544,7 @@ fn parse_comparison_expr(input: &str) -> IResult<&str, FilterExpr> {
     let (input, _) = multispace0.parse(input)?;
     let (input, op) = parse_match_op(input)?;
     let (input, _) = multispace0.parse(input)?;
-    let (input, value) = parse_value(input)?;
+    let (input, value) = parse_field_value(field, input)?;
 
     Ok((
         input,
@@ -511,6 +563,7 @@ fn parse_pipeline_stage(input: &str) -> IResult<&str, PipelineStage> {
         parse_count_stage,
         parse_distinct_stage,
         parse_project_stage,
+        parse_context_stage,
     ))
     .parse(input)
 }
@@ -557,16 +610,22 @@ fn parse_sort_stage(input: &str) -> IResult<&str, PipelineStage> {
     ))
     .parse(input)?;
     let (input, _) = multispace1.parse(input)?;
+    let (input, specs) =
+        separated_list1((multispace0, char(','), multispace0), parse_sort_spec).parse(input)?;
+    Ok((input, PipelineStage::Sort(specs)))
+}
+
+fn parse_sort_spec(input: &str) -> IResult<&str, SortSpec> {
     let (input, field) = parse_field(input)?;
     let (input, _) = multispace0.parse(input)?;
     let (input, direction) = opt(parse_sort_direction).parse(input)?;
 
     Ok((
         input,
-        PipelineStage::Sort(SortSpec {
+        SortSpec {
             field,
             direction: direction.unwrap_or_default(),
-        }),
+        },
     ))
 }
 
@@ -604,6 +663,18 @@ fn parse_project_stage(input: &str) -> IResult<&str, PipelineStage> {
     Ok((input, PipelineStage::Project(fields)))
 }
 
+fn parse_context_stage(input: &str) -> IResult<&str, PipelineStage> {
+    let (input, _) = tag_no_case("context").parse(input)?;
+    let (input, _) = multispace1.parse(input)?;
+    let (input, n) = map_res(digit1, |s: &str| s.parse::<u64>()).parse(input)?;
+    let (input, by_field) = opt(preceded(
+        (multispace1, tag_no_case("by"), multispace1),
+        parse_field,
+    ))
+    .parse(input)?;
+    Ok((input, PipelineStage::Context(n, by_field)))
+}
+
 pub fn parse_filter_only(input: &str) -> Result<FilterExpr, ParseError> {
     let input = input.trim();
     if input.is_empty() {
@@ -37,7 +37,7 @@
 
 use crate::ast::{
     Duration, DurationUnit, Field, FilterExpr, LabelMatcher, MatchOp, PipelineStage, Query,
-    Selector, SortDirection, SortSpec, Value,
+    RangeAnchor, Selector, SortDirection, SortSpec, Value,
 };
 use crate::compiler::{CompiledQuery, compile};
 use crate::errors::CompileError;
@@ -48,6 +48,7 @@ use chrono::{DateTime, Utc};
 #[derive(Debug, Clone, Default)]
 pub struct QueryBuilder {
     time_range: Option<Duration>,
+    time_anchor: Option<RangeAnchor>,
     selector_matchers: Vec<LabelMatcher>,
     filters: Vec<FilterExpr>,
     pipeline: Vec<PipelineStage>,
@@ -110,6 +111,17 @@ impl QueryBuilder {
         self
     }
 
+    /// Measures the time range back from the given anchor instead of the default one.
+    pub fn anchored_at(mut self, anchor: RangeAnchor) -> Self {
+        self.time_anchor = Some(anchor);
+        self
+    }
+
+    /// Measures the time range back from the most recent entry in the log set.
+    pub fn from_log_end(self) -> Self {
+        self.anchored_at(RangeAnchor::LogEnd)
+    }
+
     /// Filters by severity level (exact match).
     pub fn severity(mut self, level: impl Into<String>) -> Self {
         self.selector_matchers.push(LabelMatcher {
@@ -120,6 +132,16 @@ impl QueryBuilder {
         self
     }
 
+    /// Filters by severity level and above, e.g. `"warning"` also matches errors.
+    pub fn min_severity(mut self, level: impl Into<String>) -> Self {
+        self.selector_matchers.push(LabelMatcher {
+            field: Field::Severity,
+            op: MatchOp::GtEq,
+            value: Value::String(level.into()),
+        });
+        self
+    }
+
     /// Filters by subsystem (exact match).
     pub fn subsystem(mut self, name: impl Into<String>) -> Self {
         self.selector_matchers.push(LabelMatcher {
@@ -150,6 +172,56 @@ impl QueryBuilder {
         self
     }
 
+    /// Filters by virtual host (exact match).
+    pub fn vhost(mut self, name: impl Into<String>) -> Self {
+        self.selector_matchers.push(LabelMatcher {
+            field: Field::Vhost,
+            op: MatchOp::Eq,
+            value: Value::String(name.into()),
+        });
+        self
+    }
+
+    /// Filters by user (exact match).
+    pub fn user(mut self, name: impl Into<String>) -> Self {
+        self.selector_matchers.push(LabelMatcher {
+            field: Field::User,
+            op: MatchOp::Eq,
+            value: Value::String(name.into()),
+        });
+        self
+    }
+
+    /// Filters entries that mention the given queue.
+    pub fn queue(mut self, name: impl Into<String>) -> Self {
+        self.selector_matchers.push(LabelMatcher {
+            field: Field::Queue,
+            op: MatchOp::Eq,
+            value: Value::String(name.into()),
+        });
+        self
+    }
+
+    /// Filters entries that mention the given exchange.
+    pub fn exchange(mut self, name: impl Into<String>) -> Self {
+        self.selector_matchers.push(LabelMatcher {
+            field: Field::Exchange,
+            op: MatchOp::Eq,
+            value: Value::String(name.into()),
+        });
+        self
+    }
+
+    /// Filters by the module:function/arity that logged the entry (exact match).
+    pub fn mfa(mut self, mfa: impl Into<String>) -> Self {
+        self.selector_matchers.push(LabelMatcher {
+            field: Field::Mfa,
+            op: MatchOp::Eq,
+            value: Value::String(mfa.into()),
+        });
+        self
+    }
+
     /// Filters messages containing the given text (case-insensitive).
     pub fn message_contains(mut self, text: impl Into<String>) -> Self {
         self.filters
@@ -183,6 +255,13 @@ impl QueryBuilder {
         self
     }
 
+    /// Filters entries whose messages contain all words of a full-text search,
+    /// e.g. `timeout "connection closed" handsh*`.
+    pub fn search(mut self, text: impl Into<String>) -> Self {
+        self.filters.push(FilterExpr::Search(text.into()));
+        self
+    }
+
     /// Filters entries having any of the specified labels.
     pub fn labels_any(mut self, labels: Vec<String>) -> Self {
         self.filters.push(FilterExpr::LabelAny(labels));
@@ -337,9 +416,13 @@ impl QueryBuilder {
     }
 
     /// Sorts results by the given field and direction.
-    pub fn sort(mut self, field: Field, direction: SortDirection) -> Self {
-        self.pipeline
-            .push(PipelineStage::Sort(SortSpec { field, direction }));
+    pub fn sort(self, field: Field, direction: SortDirection) -> Self {
+        self.sort_by(vec![SortSpec { field, direction }])
+    }
+
+    /// Sorts results by several fields, most significant first.
+    pub fn sort_by(mut self, specs: Vec<SortSpec>) -> Self {
+        self.pipeline.push(PipelineStage::Sort(specs));
         self
     }
 
@@ -376,6 +459,13 @@ impl QueryBuilder {
         self
     }
 
+    /// Includes up to `n` entries logged before and after each result, optionally
+    /// only those with the same node (`Field::Node`) or process (`Field::ErlangPid`).
+    pub fn context(mut self, n: u64, by: Option<Field>) -> Self {
+        self.pipeline.push(PipelineStage::Context(n, by));
+        self
+    }
+
     /// Builds the Query AST from the builder state.
     pub fn build(self) -> Query {
         let selector = if self.selector_matchers.is_empty() {
@@ -390,6 +480,7 @@ impl QueryBuilder {
 
         Query {
             time_range: self.time_range,
+            time_anchor: self.time_anchor,
             selector,
             filter,
             pipeline: self.pipeline,
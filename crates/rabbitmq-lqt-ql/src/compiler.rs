@@ -15,14 +15,21 @@
 //! Compiler that translates the RLQT Query Language AST to QueryContext.
 
 use crate::ast::{
-    Field, FilterExpr, LabelMatcher, MatchOp, PipelineStage, Query, Selector, SortDirection, Value,
+    Field, FilterExpr, LabelMatcher, MatchOp, PipelineStage, Query, RangeAnchor, Selector,
+    SortDirection, SortSpec, Value,
 };
 use crate::errors::CompileError;
-use chrono::Utc;
-use chrono_english::{Dialect, parse_date_string};
-use rabbitmq_lqt_lib::QueryContext;
+use chrono::{DateTime, Utc};
+use rabbitmq_lqt_lib::datetime::parse_datetime_relative_to;
 use rabbitmq_lqt_lib::entry_metadata::labels::LogEntryLabels;
+use rabbitmq_lqt_lib::entry_metadata::rules::custom_label_name;
 use rabbitmq_lqt_lib::entry_metadata::subsystems::Subsystem;
+use rabbitmq_lqt_lib::{
+    Aggregation, ContextScope, ContextWindow, EntityKind, LogColumn, MessagePatterns,
+    MessageSearchIndex, NodeLogEntryCustomAnnotations, NodeLogEntryEntities, NodeLogEntryLabels,
+    QueryContext, SearchQuery, Severity, SortKey, SqlCondition, SqlParam, TimeAnchor,
+    TimeReference,
+};
 use regex::Regex;
 use std::mem;
 use std::str::FromStr;
@@ -30,42 +37,184 @@ use std::str::FromStr;
 #[derive(Debug, Clone, Default)]
 pub struct CompiledQuery {
     pub context: QueryContext,
-    pub sql_where_fragments: Vec<String>,
-    pub sql_order_by: Option<String>,
+    pub where_conditions: Vec<SqlCondition>,
+    pub order_by: Vec<SortKey>,
     pub sql_offset: Option<u64>,
     pub sql_limit_from_end: Option<u64>,
     pub projection: Option<Vec<Field>>,
     pub distinct_fields: Option<Vec<Field>>,
     pub count_by_field: Option<Field>,
     pub has_aggregation: bool,
+    pub context_window: Option<ContextWindow>,
+}
+
+impl CompiledQuery {
+    /// Returns the result-shaping stage to execute in the database, if any.
+    ///
+    /// `count` takes precedence over `distinct`, which takes precedence over `project`.
+    pub fn aggregation(&self) -> Option<Aggregation> {
+        if self.has_aggregation {
+            return Some(Aggregation::CountBy(self.count_by_field.map(log_column)));
+        }
+        if let Some(ref fields) = self.distinct_fields {
+            return Some(Aggregation::Distinct(
+                fields.iter().copied().map(log_column).collect(),
+            ));
+        }
+        self.projection
+            .as_ref()
+            .map(|fields| Aggregation::Project(fields.iter().copied().map(log_column).collect()))
+    }
+}
+
+/// Maps a QL field to the database column it refers to.
+pub fn log_column(field: Field) -> LogColumn {
+    match field {
+        Field::Severity => LogColumn::Severity,
+        Field::Subsystem => LogColumn::Subsystem,
+        Field::Node => LogColumn::Node,
+        Field::ErlangPid => LogColumn::ErlangPid,
+        Field::Message => LogColumn::Message,
+        Field::Labels => LogColumn::Labels,
+        Field::Timestamp => LogColumn::Timestamp,
+        Field::Id => LogColumn::Id,
+        Field::Domain => LogColumn::Domain,
+        Field::Mfa => LogColumn::Mfa,
+        Field::File => LogColumn::SourceFile,
+        Field::Line => LogColumn::SourceLine,
+        Field::Connection => LogColumn::Connection,
+        Field::Vhost => LogColumn::Vhost,
+        Field::User => LogColumn::User,
+        Field::Queue => LogColumn::Queue,
+        Field::Stream => LogColumn::Stream,
+        Field::Exchange => LogColumn::Exchange,
+        Field::Pattern => LogColumn::Pattern,
+    }
 }
 
+/// Compiles a query, measuring relative time ranges back from the current time.
 pub fn compile(query: &Query) -> Result<CompiledQuery, CompileError> {
+    compile_with_reference(query, &TimeReference::now())
+}
+
+/// Compiles a query, measuring relative time ranges (`@1h`, `timestamp > "2 hours ago"`)
+/// back from the anchor selected by the query (`@1h from end`) or, when the query
+/// does not specify one, the default anchor of `reference`.
+pub fn compile_with_reference(
+    query: &Query,
+    reference: &TimeReference,
+) -> Result<CompiledQuery, CompileError> {
+    let anchor = match query.time_anchor {
+        Some(ref anchor) => resolve_range_anchor(anchor, reference)?,
+        None => reference.default_anchor,
+    };
+    let reference_time = reference.resolve(anchor);
+    let query = anchor_relative_times(query, reference_time)?;
+
     let mut compiled = CompiledQuery::default();
 
     if let Some(ref time_range) = query.time_range {
-        let since = Utc::now() - time_range.to_chrono_duration();
+        let since = reference_time - time_range.to_chrono_duration();
         compiled.context = compiled.context.since(since);
+        if let TimeAnchor::At(_) = anchor {
+            compiled.context = mem::take(&mut compiled.context).to(reference_time);
+        }
     }
 
     if let Some(ref selector) = query.selector {
-        compile_selector(selector, &mut compiled)?;
+        compile_selector(selector, &mut compiled, reference_time)?;
     }
 
     if let Some(ref filter) = query.filter {
-        compile_filter(filter, &mut compiled)?;
+        compile_filter(filter, &mut compiled, reference_time)?;
     }
 
     for stage in &query.pipeline {
-        compile_pipeline_stage(stage, &mut compiled)?;
+        compile_pipeline_stage(stage, &mut compiled, reference_time)?;
+    }
+
+    if compiled.context_window.is_some() && compiled.aggregation().is_some() {
+        return Err(CompileError::UnsupportedOperation {
+            operation: "context with count, distinct or project".to_string(),
+        });
     }
 
     Ok(compiled)
 }
 
-fn compile_selector(selector: &Selector, compiled: &mut CompiledQuery) -> Result<(), CompileError> {
+fn resolve_range_anchor(
+    anchor: &RangeAnchor,
+    reference: &TimeReference,
+) -> Result<TimeAnchor, CompileError> {
+    match anchor {
+        RangeAnchor::Now => Ok(TimeAnchor::Now),
+        RangeAnchor::LogEnd => Ok(TimeAnchor::LogEnd),
+        RangeAnchor::At(s) => {
+            parse_timestamp_string(s, reference.reference_time()).map(TimeAnchor::At)
+        }
+    }
+}
+
+/// Replaces relative time values with absolute timestamps measured back from `reference_time`.
+fn anchor_relative_times(
+    query: &Query,
+    reference_time: DateTime<Utc>,
+) -> Result<Query, CompileError> {
+    let mut query = query.clone();
+    if let Some(ref mut selector) = query.selector {
+        for matcher in &mut selector.matchers {
+            anchor_matcher(matcher, reference_time)?;
+        }
+    }
+    if let Some(ref mut filter) = query.filter {
+        anchor_filter(filter, reference_time)?;
+    }
+    for stage in &mut query.pipeline {
+        if let PipelineStage::Where(filter) = stage {
+            anchor_filter(filter, reference_time)?;
+        }
+    }
+    Ok(query)
+}
+
+fn anchor_filter(
+    filter: &mut FilterExpr,
+    reference_time: DateTime<Utc>,
+) -> Result<(), CompileError> {
+    match filter {
+        FilterExpr::Comparison(matcher) => anchor_matcher(matcher, reference_time),
+        FilterExpr::And(left, right) | FilterExpr::Or(left, right) => {
+            anchor_filter(left, reference_time)?;
+            anchor_filter(right, reference_time)
+        }
+        FilterExpr::Not(inner) | FilterExpr::Grouped(inner) => anchor_filter(inner, reference_time),
+        _ => Ok(()),
+    }
+}
+
+fn anchor_matcher(
+    matcher: &mut LabelMatcher,
+    reference_time: DateTime<Utc>,
+) -> Result<(), CompileError> {
+    match matcher.value {
+        Value::RelativeTime(ref duration) => {
+            matcher.value = Value::Timestamp(reference_time - duration.to_chrono_duration());
+        }
+        Value::String(ref s) if matcher.field == Field::Timestamp => {
+            matcher.value = Value::Timestamp(parse_timestamp_string(s, reference_time)?);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn compile_selector(
+    selector: &Selector,
+    compiled: &mut CompiledQuery,
+    reference_time: DateTime<Utc>,
+) -> Result<(), CompileError> {
     for matcher in &selector.matchers {
-        compile_matcher(matcher, compiled)?;
+        compile_matcher(matcher, compiled, reference_time)?;
     }
     Ok(())
 }
@@ -73,17 +222,18 @@ fn compile_selector(selector: &Selector, compiled: &mut CompiledQuery) -> Result
 fn compile_matcher(
     matcher: &LabelMatcher,
     compiled: &mut CompiledQuery,
+    reference_time: DateTime<Utc>,
 ) -> Result<(), CompileError> {
     match matcher.field {
         Field::Severity => {
             if matcher.op == MatchOp::Eq {
                 if let Value::String(ref s) = matcher.value {
-                    validate_severity(s)?;
-                    compiled.context = mem::take(&mut compiled.context).severity(s.clone());
+                    let severity = parse_severity(s)?;
+                    compiled.context = mem::take(&mut compiled.context).severity(severity.as_str());
                 }
             } else {
-                let fragment = compile_comparison_to_sql(matcher)?;
-                compiled.sql_where_fragments.push(fragment);
+                let condition = compile_comparison_to_sql(matcher, reference_time)?;
+                compiled.where_conditions.push(condition);
             }
         }
         Field::Subsystem => {
@@ -92,8 +242,8 @@ fn compile_matcher(
                     compiled.context = mem::take(&mut compiled.context).subsystem(s.clone());
                 }
             } else {
-                let fragment = compile_comparison_to_sql(matcher)?;
-                compiled.sql_where_fragments.push(fragment);
+                let condition = compile_comparison_to_sql(matcher, reference_time)?;
+                compiled.where_conditions.push(condition);
             }
         }
         Field::Node => {
@@ -102,8 +252,8 @@ fn compile_matcher(
                     compiled.context = mem::take(&mut compiled.context).node(s.clone());
                 }
             } else {
-                let fragment = compile_comparison_to_sql(matcher)?;
-                compiled.sql_where_fragments.push(fragment);
+                let condition = compile_comparison_to_sql(matcher, reference_time)?;
+                compiled.where_conditions.push(condition);
             }
         }
         Field::ErlangPid => {
@@ -112,8 +262,8 @@ fn compile_matcher(
                     compiled.context = mem::take(&mut compiled.context).erlang_pid(s.clone());
                 }
             } else {
-                let fragment = compile_comparison_to_sql(matcher)?;
-                compiled.sql_where_fragments.push(fragment);
+                let condition = compile_comparison_to_sql(matcher, reference_time)?;
+                compiled.where_conditions.push(condition);
             }
         }
         Field::Labels => {
@@ -128,110 +278,140 @@ fn compile_matcher(
             }
         }
         Field::Timestamp => {
-            compile_timestamp_matcher(matcher, compiled)?;
+            compile_timestamp_matcher(matcher, compiled, reference_time)?;
         }
         Field::Message => {
-            let fragment = compile_comparison_to_sql(matcher)?;
-            compiled.sql_where_fragments.push(fragment);
+            let condition = compile_comparison_to_sql(matcher, reference_time)?;
+            compiled.where_conditions.push(condition);
+        }
+        Field::Id | Field::Line | Field::Pattern => {
+            let condition = compile_comparison_to_sql(matcher, reference_time)?;
+            compiled.where_conditions.push(condition);
+        }
+        Field::Domain
+        | Field::Mfa
+        | Field::File
+        | Field::Connection
+        | Field::Vhost
+        | Field::User => {
+            if matcher.op == MatchOp::Eq
+                && let Value::String(ref s) = matcher.value
+            {
+                let context = mem::take(&mut compiled.context);
+                compiled.context = with_metadata_filter(context, matcher.field, s.clone());
+            } else {
+                let condition = compile_comparison_to_sql(matcher, reference_time)?;
+                compiled.where_conditions.push(condition);
+            }
         }
-        Field::Id => {
-            let fragment = compile_comparison_to_sql(matcher)?;
-            compiled.sql_where_fragments.push(fragment);
+        Field::Queue | Field::Stream | Field::Exchange => {
+            if matcher.op == MatchOp::Eq
+                && let Value::String(ref s) = matcher.value
+                && let Some(kind) = entity_kind(matcher.field)
+            {
+                compiled.context = mem::take(&mut compiled.context).entity(kind, s.clone());
+            } else {
+                let condition = compile_comparison_to_sql(matcher, reference_time)?;
+                compiled.where_conditions.push(condition);
+            }
         }
     }
     Ok(())
 }
 
+/// The kind of entity a field matches in `node_log_entry_entities`, if any.
+fn entity_kind(field: Field) -> Option<EntityKind> {
+    match field {
+        Field::Queue => Some(EntityKind::Queue),
+        Field::Stream => Some(EntityKind::Stream),
+        Field::Exchange => Some(EntityKind::Exchange),
+        _ => None,
+    }
+}
+
+/// Applies an equality filter on one of the structured metadata fields.
+fn with_metadata_filter(context: QueryContext, field: Field, value: String) -> QueryContext {
+    match field {
+        Field::Domain => context.domain(value),
+        Field::Mfa => context.mfa(value),
+        Field::File => context.source_file(value),
+        Field::Connection => context.connection(value),
+        Field::Vhost => context.vhost(value),
+        Field::User => context.user(value),
+        _ => context,
+    }
+}
+
+/// Relative times and timestamp strings have been replaced with timestamps
+/// by [`anchor_relative_times`] at this point.
 fn compile_timestamp_matcher(
     matcher: &LabelMatcher,
     compiled: &mut CompiledQuery,
+    reference_time: DateTime<Utc>,
 ) -> Result<(), CompileError> {
-    match &matcher.value {
-        Value::RelativeTime(duration) => {
-            let ts = Utc::now() - duration.to_chrono_duration();
-            match matcher.op {
-                MatchOp::GtEq | MatchOp::Gt => {
-                    compiled.context = mem::take(&mut compiled.context).since(ts);
-                }
-                MatchOp::LtEq | MatchOp::Lt => {
-                    compiled.context = mem::take(&mut compiled.context).to(ts);
-                }
-                _ => {
-                    let fragment = compile_comparison_to_sql(matcher)?;
-                    compiled.sql_where_fragments.push(fragment);
-                }
-            }
+    let Value::Timestamp(ts) = matcher.value else {
+        return Err(CompileError::InvalidTimestamp {
+            reason: "expected timestamp value".to_string(),
+        });
+    };
+    match matcher.op {
+        MatchOp::GtEq | MatchOp::Gt => {
+            compiled.context = mem::take(&mut compiled.context).since(ts);
         }
-        Value::Timestamp(ts) => match matcher.op {
-            MatchOp::GtEq | MatchOp::Gt => {
-                compiled.context = mem::take(&mut compiled.context).since(*ts);
-            }
-            MatchOp::LtEq | MatchOp::Lt => {
-                compiled.context = mem::take(&mut compiled.context).to(*ts);
-            }
-            _ => {
-                let fragment = compile_comparison_to_sql(matcher)?;
-                compiled.sql_where_fragments.push(fragment);
-            }
-        },
-        Value::String(s) => {
-            let ts = parse_timestamp_string(s)?;
-            let mut modified_matcher = matcher.clone();
-            modified_matcher.value = Value::Timestamp(ts);
-            compile_timestamp_matcher(&modified_matcher, compiled)?;
+        MatchOp::LtEq | MatchOp::Lt => {
+            compiled.context = mem::take(&mut compiled.context).to(ts);
         }
         _ => {
-            return Err(CompileError::InvalidTimestamp {
-                reason: "expected timestamp value".to_string(),
-            });
+            let condition = compile_comparison_to_sql(matcher, reference_time)?;
+            compiled.where_conditions.push(condition);
         }
     }
     Ok(())
 }
 
-fn parse_timestamp_string(s: &str) -> Result<chrono::DateTime<Utc>, CompileError> {
-    parse_date_string(s, Utc::now(), Dialect::Us).map_err(|e| CompileError::InvalidTimestamp {
-        reason: e.to_string(),
-    })
+fn parse_timestamp_string(
+    s: &str,
+    reference_time: DateTime<Utc>,
+) -> Result<DateTime<Utc>, CompileError> {
+    parse_datetime_relative_to(s, reference_time)
+        .map_err(|reason| CompileError::InvalidTimestamp { reason })
 }
 
-fn compile_filter(filter: &FilterExpr, compiled: &mut CompiledQuery) -> Result<(), CompileError> {
+fn compile_filter(
+    filter: &FilterExpr,
+    compiled: &mut CompiledQuery,
+    reference_time: DateTime<Utc>,
+) -> Result<(), CompileError> {
     match filter {
         FilterExpr::Comparison(matcher) => {
-            compile_matcher(matcher, compiled)?;
+            compile_matcher(matcher, compiled, reference_time)?;
         }
         FilterExpr::And(left, right) => {
-            compile_filter(left, compiled)?;
-            compile_filter(right, compiled)?;
+            compile_filter(left, compiled, reference_time)?;
+            compile_filter(right, compiled, reference_time)?;
         }
         FilterExpr::Or(left, right) => {
-            let left_sql = compile_filter_to_sql(left)?;
-            let right_sql = compile_filter_to_sql(right)?;
-            compiled
-                .sql_where_fragments
-                .push(format!("({} OR {})", left_sql, right_sql));
+            let left_sql = compile_filter_to_sql(left, reference_time)?;
+            let right_sql = compile_filter_to_sql(right, reference_time)?;
+            compiled.where_conditions.push(left_sql.or(right_sql));
         }
         FilterExpr::Not(inner) => {
-            let inner_sql = compile_filter_to_sql(inner)?;
-            compiled
-                .sql_where_fragments
-                .push(format!("NOT ({})", inner_sql));
+            let inner_sql = compile_filter_to_sql(inner, reference_time)?;
+            compiled.where_conditions.push(inner_sql.negate());
         }
         FilterExpr::LabelAny(labels) => {
-            let mask = compute_label_mask(labels)?;
             compiled
-                .sql_where_fragments
-                .push(format!("(labels & {}) != 0", mask));
+                .where_conditions
+                .push(format_label_any_sql(labels)?);
         }
         FilterExpr::LabelAll(labels) => {
-            let mask = compute_label_mask(labels)?;
             compiled
-                .sql_where_fragments
-                .push(format!("(labels & {}) = {}", mask, mask));
+                .where_conditions
+                .push(format_label_all_sql(labels)?);
         }
         FilterExpr::SubsystemAny(subsystems) => {
             compiled
-                .sql_where_fragments
+                .where_conditions
                 .push(format_subsystem_any_sql(subsystems)?);
         }
         FilterExpr::HasDocUrl => {
@@ -246,140 +426,244 @@ fn compile_filter(filter: &FilterExpr, compiled: &mut CompiledQuery) -> Result<(
         }
         FilterExpr::Preset(preset) => {
             let expanded = preset.to_filter_expr();
-            compile_filter(&expanded, compiled)?;
+            compile_filter(&expanded, compiled, reference_time)?;
+        }
+        FilterExpr::Search(text) => {
+            let search = parse_search(text)?;
+            compiled.context = mem::take(&mut compiled.context).search(search);
         }
         FilterExpr::Grouped(inner) => {
-            compile_filter(inner, compiled)?;
+            compile_filter(inner, compiled, reference_time)?;
         }
     }
     Ok(())
 }
 
-fn compile_filter_to_sql(filter: &FilterExpr) -> Result<String, CompileError> {
+fn compile_filter_to_sql(
+    filter: &FilterExpr,
+    reference_time: DateTime<Utc>,
+) -> Result<SqlCondition, CompileError> {
     match filter {
-        FilterExpr::Comparison(matcher) => compile_comparison_to_sql(matcher),
+        FilterExpr::Comparison(matcher) => compile_comparison_to_sql(matcher, reference_time),
         FilterExpr::And(left, right) => {
-            let left_sql = compile_filter_to_sql(left)?;
-            let right_sql = compile_filter_to_sql(right)?;
-            Ok(format!("({} AND {})", left_sql, right_sql))
+            let left_sql = compile_filter_to_sql(left, reference_time)?;
+            let right_sql = compile_filter_to_sql(right, reference_time)?;
+            Ok(left_sql.and(right_sql))
         }
         FilterExpr::Or(left, right) => {
-            let left_sql = compile_filter_to_sql(left)?;
-            let right_sql = compile_filter_to_sql(right)?;
-            Ok(format!("({} OR {})", left_sql, right_sql))
+            let left_sql = compile_filter_to_sql(left, reference_time)?;
+            let right_sql = compile_filter_to_sql(right, reference_time)?;
+            Ok(left_sql.or(right_sql))
         }
         FilterExpr::Not(inner) => {
-            let inner_sql = compile_filter_to_sql(inner)?;
-            Ok(format!("NOT ({})", inner_sql))
-        }
-        FilterExpr::LabelAny(labels) => {
-            let mask = compute_label_mask(labels)?;
-            Ok(format!("(labels & {}) != 0", mask))
-        }
-        FilterExpr::LabelAll(labels) => {
-            let mask = compute_label_mask(labels)?;
-            Ok(format!("(labels & {}) = {}", mask, mask))
+            let inner_sql = compile_filter_to_sql(inner, reference_time)?;
+            Ok(inner_sql.negate())
         }
+        FilterExpr::LabelAny(labels) => format_label_any_sql(labels),
+        FilterExpr::LabelAll(labels) => format_label_all_sql(labels),
         FilterExpr::SubsystemAny(subsystems) => format_subsystem_any_sql(subsystems),
-        FilterExpr::HasDocUrl => Ok("doc_url_id IS NOT NULL".to_string()),
-        FilterExpr::HasResolutionUrl => {
-            Ok("resolution_or_discussion_url_id IS NOT NULL".to_string())
-        }
-        FilterExpr::Unlabelled => {
-            let unlabelled_bit = LogEntryLabels::UNLABELLED.bits();
-            Ok(format!("(labels & {}) != 0", unlabelled_bit))
-        }
+        FilterExpr::HasDocUrl => Ok(SqlCondition::trusted(format!(
+            "(doc_url_id IS NOT NULL OR {})",
+            NodeLogEntryCustomAnnotations::has_doc_url_condition()
+        ))),
+        FilterExpr::HasResolutionUrl => Ok(SqlCondition::trusted(format!(
+            "(resolution_or_discussion_url_id IS NOT NULL OR {})",
+            NodeLogEntryCustomAnnotations::has_resolution_or_discussion_url_condition()
+        ))),
+        FilterExpr::Unlabelled => Ok(label_set_any_sql(LogEntryLabels::UNLABELLED)),
         FilterExpr::Preset(preset) => {
             let expanded = preset.to_filter_expr();
-            compile_filter_to_sql(&expanded)
+            compile_filter_to_sql(&expanded, reference_time)
         }
+        FilterExpr::Search(text) => Ok(MessageSearchIndex::scan_condition(&parse_search(text)?)),
         FilterExpr::Grouped(inner) => {
-            let inner_sql = compile_filter_to_sql(inner)?;
-            Ok(format!("({})", inner_sql))
+            let inner_sql = compile_filter_to_sql(inner, reference_time)?;
+            Ok(inner_sql.grouped())
+        }
+    }
+}
+
+fn parse_search(text: &str) -> Result<SearchQuery, CompileError> {
+    let search = SearchQuery::parse(text);
+    if search.is_empty() {
+        return Err(CompileError::EmptySearch {
+            text: text.to_string(),
+        });
+    }
+    Ok(search)
+}
+
+fn compile_comparison_to_sql(
+    matcher: &LabelMatcher,
+    reference_time: DateTime<Utc>,
+) -> Result<SqlCondition, CompileError> {
+    match matcher.field {
+        Field::Pattern => return compile_pattern_comparison_to_sql(matcher, reference_time),
+        Field::Severity => return compile_severity_comparison_to_sql(matcher, reference_time),
+        _ => {}
+    }
+    match entity_kind(matcher.field) {
+        Some(kind) => compile_entity_comparison_to_sql(kind, matcher, reference_time),
+        None => {
+            compile_column_comparison_to_sql(matcher.field.sql_column(), matcher, reference_time)
         }
     }
 }
 
-fn compile_comparison_to_sql(matcher: &LabelMatcher) -> Result<String, CompileError> {
-    let column = matcher.field.sql_column();
-    let value_sql = compile_value_to_sql(&matcher.value)?;
+/// Splits a matcher with a negated operator into its positive form and a negation flag.
+fn positive_matcher(matcher: &LabelMatcher) -> (LabelMatcher, bool) {
+    let (op, negated) = match matcher.op {
+        MatchOp::NotEq => (MatchOp::Eq, true),
+        MatchOp::NotRegex => (MatchOp::Regex, true),
+        op => (op, false),
+    };
+    let positive = LabelMatcher {
+        field: matcher.field,
+        op,
+        value: matcher.value.clone(),
+    };
+    (positive, negated)
+}
 
-    match matcher.op {
-        MatchOp::Eq => Ok(format!("{} = {}", column, value_sql)),
-        MatchOp::NotEq => Ok(format!("{} != {}", column, value_sql)),
-        MatchOp::Lt => Ok(format!("{} < {}", column, value_sql)),
-        MatchOp::LtEq => Ok(format!("{} <= {}", column, value_sql)),
-        MatchOp::Gt => Ok(format!("{} > {}", column, value_sql)),
-        MatchOp::GtEq => Ok(format!("{} >= {}", column, value_sql)),
-        MatchOp::Contains => {
-            if let Value::String(ref s) = matcher.value {
-                Ok(format!(
-                    "{} ILIKE '%{}%' ESCAPE '\\'",
-                    column,
-                    escape_like_pattern(s)
-                ))
-            } else {
-                Ok(format!("{} ILIKE '%' || {} || '%'", column, value_sql))
-            }
+fn negate_if(condition: SqlCondition, negated: bool) -> SqlCondition {
+    if negated {
+        condition.negate()
+    } else {
+        condition
+    }
+}
+
+/// Entities are matched by name in `node_log_entry_entities`. Negated operators
+/// match entries that do not mention a matching entity of the kind.
+fn compile_entity_comparison_to_sql(
+    kind: EntityKind,
+    matcher: &LabelMatcher,
+    reference_time: DateTime<Utc>,
+) -> Result<SqlCondition, CompileError> {
+    let (positive, negated) = positive_matcher(matcher);
+    let name_predicate = compile_column_comparison_to_sql("name", &positive, reference_time)?;
+    let condition = NodeLogEntryEntities::condition(kind, name_predicate);
+    Ok(negate_if(condition, negated))
+}
+
+/// Patterns are matched by template in `message_patterns`.
+fn compile_pattern_comparison_to_sql(
+    matcher: &LabelMatcher,
+    reference_time: DateTime<Utc>,
+) -> Result<SqlCondition, CompileError> {
+    let (positive, negated) = positive_matcher(matcher);
+    let template_predicate =
+        compile_column_comparison_to_sql("template", &positive, reference_time)?;
+    let condition = MessagePatterns::condition(template_predicate);
+    Ok(negate_if(condition, negated))
+}
+
+/// Severities are stored as ordinals, so comparisons follow rank: `severity >= "warning"`
+/// also matches errors and critical entries. Regular expressions and substrings
+/// are matched against the severity names.
+fn compile_severity_comparison_to_sql(
+    matcher: &LabelMatcher,
+    reference_time: DateTime<Utc>,
+) -> Result<SqlCondition, CompileError> {
+    let column = Field::Severity.sql_column();
+    let (Value::String(ref s) | Value::Regex(ref s)) = matcher.value else {
+        return compile_column_comparison_to_sql(column, matcher, reference_time);
+    };
+    let (positive, negated) = positive_matcher(matcher);
+    let matching: Vec<Severity> = match positive.op {
+        MatchOp::Regex => {
+            let regex = compile_regex(s)?;
+            Severity::all()
+                .into_iter()
+                .filter(|severity| regex.is_match(severity.as_str()))
+                .collect()
+        }
+        MatchOp::Contains | MatchOp::IContains => {
+            let needle = s.to_lowercase();
+            Severity::all()
+                .into_iter()
+                .filter(|severity| severity.as_str().contains(&needle))
+                .collect()
         }
-        MatchOp::IContains => {
-            if let Value::String(ref s) = matcher.value {
-                Ok(format!(
-                    "{} ILIKE '%{}%' ESCAPE '\\'",
-                    column,
-                    escape_like_pattern(s)
-                ))
-            } else {
-                Ok(format!("{} ILIKE '%' || {} || '%'", column, value_sql))
-            }
+        MatchOp::HasLabel => {
+            return compile_column_comparison_to_sql(column, matcher, reference_time);
         }
-        MatchOp::Regex => {
-            if let Value::Regex(ref pattern) = matcher.value {
-                validate_regex(pattern)?;
-                Ok(format!(
-                    "regexp_matches({}, '{}')",
-                    column,
-                    escape_sql_string(pattern)
-                ))
-            } else if let Value::String(ref pattern) = matcher.value {
-                validate_regex(pattern)?;
-                Ok(format!(
-                    "regexp_matches({}, '{}')",
-                    column,
-                    escape_sql_string(pattern)
-                ))
-            } else {
-                Err(CompileError::UnsupportedOperation {
-                    operation: "regex requires string pattern".to_string(),
-                })
-            }
+        _ => {
+            let ranked = LabelMatcher {
+                field: matcher.field,
+                op: matcher.op,
+                value: Value::Integer(i64::from(parse_severity(s)?.to_ordinal())),
+            };
+            return compile_column_comparison_to_sql(column, &ranked, reference_time);
         }
-        MatchOp::NotRegex => {
-            if let Value::Regex(ref pattern) = matcher.value {
-                validate_regex(pattern)?;
-                Ok(format!(
-                    "NOT regexp_matches({}, '{}')",
-                    column,
-                    escape_sql_string(pattern)
-                ))
-            } else if let Value::String(ref pattern) = matcher.value {
-                validate_regex(pattern)?;
-                Ok(format!(
-                    "NOT regexp_matches({}, '{}')",
-                    column,
-                    escape_sql_string(pattern)
-                ))
-            } else {
-                Err(CompileError::UnsupportedOperation {
+    };
+    Ok(negate_if(severities_in_sql(&matching), negated))
+}
+
+fn severities_in_sql(severities: &[Severity]) -> SqlCondition {
+    if severities.is_empty() {
+        return SqlCondition::trusted("FALSE".to_string());
+    }
+    let placeholders = vec!["?"; severities.len()].join(", ");
+    let params = severities
+        .iter()
+        .map(|severity| SqlParam::Integer(i64::from(severity.to_ordinal())))
+        .collect();
+    SqlCondition::new(format!("severity IN ({})", placeholders), params)
+}
+
+/// Compares `column` to the matcher's value, which is always bound as a parameter.
+fn compile_column_comparison_to_sql(
+    column: &str,
+    matcher: &LabelMatcher,
+    reference_time: DateTime<Utc>,
+) -> Result<SqlCondition, CompileError> {
+    let comparison = |operator: &str| -> Result<SqlCondition, CompileError> {
+        let param = compile_value_to_param(&matcher.value, reference_time)?;
+        Ok(SqlCondition::new(
+            format!("{} {} ?", column, operator),
+            vec![param],
+        ))
+    };
+
+    match matcher.op {
+        MatchOp::Eq => comparison("="),
+        MatchOp::NotEq => comparison("!="),
+        MatchOp::Lt => comparison("<"),
+        MatchOp::LtEq => comparison("<="),
+        MatchOp::Gt => comparison(">"),
+        MatchOp::GtEq => comparison(">="),
+        MatchOp::Contains | MatchOp::IContains => {
+            let pattern = match compile_value_to_param(&matcher.value, reference_time)? {
+                SqlParam::Text(s) => SqlParam::Text(format!("%{}%", escape_like_pattern(&s))),
+                SqlParam::Integer(n) => SqlParam::Text(format!("%{}%", n)),
+                SqlParam::Boolean(b) => SqlParam::Text(format!("%{}%", b)),
+                SqlParam::Timestamp(ts) => {
+                    SqlParam::Text(format!("%{}%", ts.format("%Y-%m-%d %H:%M:%S%.6f")))
+                }
+                SqlParam::Null => SqlParam::Null,
+            };
+            Ok(SqlCondition::new(
+                format!("{} ILIKE ? ESCAPE '\\'", column),
+                vec![pattern],
+            ))
+        }
+        MatchOp::Regex | MatchOp::NotRegex => {
+            let (Value::Regex(pattern) | Value::String(pattern)) = &matcher.value else {
+                return Err(CompileError::UnsupportedOperation {
                     operation: "regex requires string pattern".to_string(),
-                })
-            }
+                });
+            };
+            compile_regex(pattern)?;
+            let condition = SqlCondition::new(
+                format!("regexp_matches({}, ?)", column),
+                vec![SqlParam::Text(pattern.clone())],
+            );
+            Ok(negate_if(condition, matcher.op == MatchOp::NotRegex))
         }
         MatchOp::HasLabel => {
             if let Value::String(ref label) = matcher.value {
-                let bit = LogEntryLabels::bit_for_label(label)
-                    .ok_or_else(|| CompileError::unknown_label(label))?;
-                Ok(format!("(labels & {}) != 0", bit))
+                format_label_any_sql(std::slice::from_ref(label))
             } else {
                 Err(CompileError::UnsupportedOperation {
                     operation: "~= requires string label".to_string(),
@@ -389,31 +673,33 @@ fn compile_comparison_to_sql(matcher: &LabelMatcher) -> Result<String, CompileEr
     }
 }
 
-fn compile_value_to_sql(value: &Value) -> Result<String, CompileError> {
+fn compile_value_to_param(
+    value: &Value,
+    reference_time: DateTime<Utc>,
+) -> Result<SqlParam, CompileError> {
     match value {
-        Value::String(s) => Ok(format!("'{}'", escape_sql_string(s))),
-        Value::Regex(s) => Ok(format!("'{}'", escape_sql_string(s))),
-        Value::Integer(n) => Ok(n.to_string()),
-        Value::Timestamp(ts) => Ok(format!("'{}'", ts.format("%Y-%m-%d %H:%M:%S%.6f"))),
-        Value::RelativeTime(duration) => {
-            let ts = Utc::now() - duration.to_chrono_duration();
-            Ok(format!("'{}'", ts.format("%Y-%m-%d %H:%M:%S%.6f")))
-        }
+        Value::String(s) | Value::Regex(s) => Ok(SqlParam::Text(s.clone())),
+        Value::Integer(n) => Ok(SqlParam::Integer(*n)),
+        Value::Timestamp(ts) => Ok(SqlParam::Timestamp(*ts)),
+        Value::RelativeTime(duration) => Ok(SqlParam::Timestamp(
+            reference_time - duration.to_chrono_duration(),
+        )),
         Value::LabelList(_) => Err(CompileError::UnsupportedOperation {
             operation: "label list in SQL comparison".to_string(),
         }),
-        Value::Boolean(b) => Ok(if *b { "true" } else { "false" }.to_string()),
-        Value::Null => Ok("NULL".to_string()),
+        Value::Boolean(b) => Ok(SqlParam::Boolean(*b)),
+        Value::Null => Ok(SqlParam::Null),
     }
 }
 
 fn compile_pipeline_stage(
     stage: &PipelineStage,
     compiled: &mut CompiledQuery,
+    reference_time: DateTime<Utc>,
 ) -> Result<(), CompileError> {
     match stage {
         PipelineStage::Where(filter) => {
-            compile_filter(filter, compiled)?;
+            compile_filter(filter, compiled, reference_time)?;
         }
         PipelineStage::Limit(n) => {
             compiled.context = mem::take(&mut compiled.context).limit(*n);
@@ -427,12 +713,11 @@ fn compile_pipeline_stage(
         PipelineStage::Tail(n) => {
             compiled.sql_limit_from_end = Some(*n);
         }
-        PipelineStage::Sort(spec) => {
-            let direction = match spec.direction {
-                SortDirection::Asc => "ASC",
-                SortDirection::Desc => "DESC",
-            };
-            compiled.sql_order_by = Some(format!("{} {}", spec.field.sql_column(), direction));
+        PipelineStage::Sort(specs) => {
+            compiled.order_by = specs
+                .iter()
+                .map(compile_sort_spec)
+                .collect::<Result<_, _>>()?;
         }
         PipelineStage::Project(fields) => {
             compiled.projection = Some(fields.clone());
@@ -444,18 +729,87 @@ fn compile_pipeline_stage(
         PipelineStage::Distinct(fields) => {
             compiled.distinct_fields = Some(fields.clone());
         }
+        PipelineStage::Context(n, field) => {
+            if *n > ContextWindow::MAX_ENTRIES {
+                return Err(CompileError::ContextTooLarge {
+                    entries: *n,
+                    max: ContextWindow::MAX_ENTRIES,
+                });
+            }
+            let scope = match field {
+                None => ContextScope::All,
+                Some(Field::Node) => ContextScope::Node,
+                Some(Field::ErlangPid) => ContextScope::Process,
+                Some(other) => {
+                    return Err(CompileError::UnsupportedOperation {
+                        operation: format!("context by {}", log_column(*other).name()),
+                    });
+                }
+            };
+            compiled.context_window = Some(ContextWindow::around(*n).scoped(scope));
+        }
     }
     Ok(())
 }
 
-fn compute_label_mask(labels: &[String]) -> Result<u64, CompileError> {
-    let mut mask: u64 = 0;
+fn compile_sort_spec(spec: &SortSpec) -> Result<SortKey, CompileError> {
+    let column = log_column(spec.field);
+    if !column.is_sortable() {
+        return Err(CompileError::UnsupportedOperation {
+            operation: format!("sorting by {}", column.name()),
+        });
+    }
+    Ok(match spec.direction {
+        SortDirection::Asc => SortKey::ascending(column),
+        SortDirection::Desc => SortKey::descending(column),
+    })
+}
+
+/// Splits a label list into a set of built-in labels and a list of custom labels,
+/// which are referred to with a prefix, e.g. `custom:billing`.
+fn compute_label_set(labels: &[String]) -> Result<(LogEntryLabels, Vec<&str>), CompileError> {
+    let mut set = LogEntryLabels::empty();
+    let mut custom_labels = Vec::new();
     for label in labels {
-        let bit = LogEntryLabels::bit_for_label(label)
-            .ok_or_else(|| CompileError::unknown_label(label))?;
-        mask |= bit;
+        if let Some(flag) = LogEntryLabels::from_label(label) {
+            set |= flag;
+        } else if let Some(name) = custom_label_name(label) {
+            custom_labels.push(name);
+        } else {
+            return Err(CompileError::unknown_label(label));
+        }
     }
-    Ok(mask)
+    Ok((set, custom_labels))
+}
+
+fn label_set_any_sql(labels: LogEntryLabels) -> SqlCondition {
+    NodeLogEntryLabels::any_condition(labels)
+        .unwrap_or_else(|| SqlCondition::trusted("FALSE".to_string()))
+}
+
+fn format_label_any_sql(labels: &[String]) -> Result<SqlCondition, CompileError> {
+    let (set, custom_labels) = compute_label_set(labels)?;
+    let Some(custom_condition) =
+        NodeLogEntryCustomAnnotations::labels_condition(&custom_labels, false)
+    else {
+        return Ok(label_set_any_sql(set));
+    };
+    Ok(match NodeLogEntryLabels::any_condition(set) {
+        Some(condition) => condition.or(custom_condition),
+        None => custom_condition,
+    })
+}
+
+fn format_label_all_sql(labels: &[String]) -> Result<SqlCondition, CompileError> {
+    let (set, custom_labels) = compute_label_set(labels)?;
+    Ok([
+        NodeLogEntryLabels::all_condition(set),
+        NodeLogEntryCustomAnnotations::labels_condition(&custom_labels, true),
+    ]
+    .into_iter()
+    .flatten()
+    .reduce(SqlCondition::and)
+    .unwrap_or_else(|| SqlCondition::trusted("TRUE".to_string())))
 }
 
 fn compute_subsystem_ids(subsystems: &[String]) -> Result<Vec<i16>, CompileError> {
@@ -469,49 +823,34 @@ fn compute_subsystem_ids(subsystems: &[String]) -> Result<Vec<i16>, CompileError
         .collect()
 }
 
-fn format_subsystem_any_sql(subsystems: &[String]) -> Result<String, CompileError> {
+fn format_subsystem_any_sql(subsystems: &[String]) -> Result<SqlCondition, CompileError> {
     let ids = compute_subsystem_ids(subsystems)?;
-    let id_list = ids
-        .iter()
-        .map(|id| id.to_string())
-        .collect::<Vec<_>>()
-        .join(", ");
-    Ok(format!("subsystem_id IN ({})", id_list))
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let params = ids
+        .into_iter()
+        .map(|id| SqlParam::Integer(i64::from(id)))
+        .collect();
+    Ok(SqlCondition::new(
+        format!("subsystem_id IN ({})", placeholders),
+        params,
+    ))
 }
 
-fn validate_severity(s: &str) -> Result<(), CompileError> {
-    match s.to_lowercase().as_str() {
-        "debug" | "info" | "notice" | "warning" | "error" | "critical" => Ok(()),
-        _ => Err(CompileError::invalid_severity(s)),
-    }
+fn parse_severity(s: &str) -> Result<Severity, CompileError> {
+    Severity::from_str(&s.to_lowercase()).map_err(|_| CompileError::invalid_severity(s))
 }
 
-fn validate_regex(pattern: &str) -> Result<(), CompileError> {
+fn compile_regex(pattern: &str) -> Result<Regex, CompileError> {
     Regex::new(pattern).map_err(|e| CompileError::RegexCompilation {
         pattern: pattern.to_string(),
         reason: e.to_string(),
-    })?;
-    Ok(())
+    })
 }
 
-/// Escapes a string for use in SQL single-quoted string literals.
-///
-/// # Safety
-///
-/// This function provides protection against SQL injection by:
-/// - Doubling single quotes (standard SQL escaping)
-/// - Escaping backslashes (for databases that interpret them as escape chars)
-///
-/// All user input that reaches this function has already been validated by the
-/// QL parser, which restricts the character set and structure of queries.
-fn escape_sql_string(s: &str) -> String {
-    s.replace('\\', "\\\\").replace('\'', "''")
-}
-
-/// Escapes a string for use in SQL LIKE patterns.
-///
-/// In addition to standard string escaping, this escapes LIKE wildcards
-/// (`%` and `_`) to prevent unintended pattern matching.
+/// Escapes the `LIKE` wildcards (`%` and `_`) and the escape character itself,
+/// so that a value bound to an `ILIKE ? ESCAPE '\'` pattern is matched literally.
 fn escape_like_pattern(s: &str) -> String {
-    escape_sql_string(s).replace('%', "\\%").replace('_', "\\_")
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
@@ -26,6 +26,9 @@ pub enum Error {
 
     #[error("Compile error: {0}")]
     Compile(#[from] CompileError),
+
+    #[error(transparent)]
+    Library(#[from] rabbitmq_lqt_lib::Error),
 }
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -57,6 +60,12 @@ pub enum CompileError {
 
     #[error("Invalid timestamp: {reason}")]
     InvalidTimestamp { reason: String },
+
+    #[error("Search \"{text}\" has no words to look for")]
+    EmptySearch { text: String },
+
+    #[error("A context of {entries} entries exceeds the maximum of {max}")]
+    ContextTooLarge { entries: u64, max: u64 },
 }
 
 impl CompileError {
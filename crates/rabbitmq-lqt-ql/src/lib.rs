@@ -46,8 +46,9 @@
 pub mod builder;
 pub mod compiler;
 pub mod errors;
+pub mod report;
 
-use rabbitmq_lqt_lib::QueryContext;
+use rabbitmq_lqt_lib::{QueryContext, TimeReference};
 use std::result::Result as StdResult;
 
 // Re-export everything from rabbitmq-lqt-ql-core
@@ -58,38 +59,60 @@ pub use rabbitmq_lqt_ql_core::presets;
 pub use rabbitmq_lqt_ql_core::{Diagnostic, ParseError, Span};
 pub use rabbitmq_lqt_ql_core::{
     Duration, DurationUnit, Field, FilterExpr, LabelMatcher, MatchOp, PipelineStage, Query,
-    Selector, SortDirection, SortSpec, Value,
+    RangeAnchor, Selector, SortDirection, SortSpec, Value,
 };
 pub use rabbitmq_lqt_ql_core::{parse, parse_filter_only};
 
 pub use builder::{FilterBuilder, QueryBuilder};
-pub use compiler::{CompiledQuery, compile};
+pub use compiler::{CompiledQuery, compile, compile_with_reference};
 pub use errors::CompileError;
+pub use report::{Check, Finding, FindingSeverity, Report, ReportOptions};
 
 pub type Result<T, E = errors::Error> = StdResult<T, E>;
 
 /// Parses a query string and compiles it to a QueryContext.
 pub fn parse_and_compile(input: &str) -> Result<CompiledQuery> {
+    parse_and_compile_with_reference(input, &TimeReference::now())
+}
+
+/// Parses a query string and compiles it, anchoring relative time ranges to `reference`.
+pub fn parse_and_compile_with_reference(
+    input: &str,
+    reference: &TimeReference,
+) -> Result<CompiledQuery> {
     let query = parse(input)?;
-    let compiled = compile(&query)?;
+    let compiled = compile_with_reference(&query, reference)?;
     Ok(compiled)
 }
 
 /// Parses a query string and returns a QueryContext for use with rabbitmq-lqt-lib.
 pub fn to_query_context(input: &str) -> Result<QueryContext> {
-    let compiled = parse_and_compile(input)?;
-    let mut ctx = if compiled.sql_where_fragments.is_empty() {
-        compiled.context
-    } else {
-        compiled
-            .context
-            .raw_where_clauses(compiled.sql_where_fragments)
-    };
+    to_query_context_with_reference(input, &TimeReference::now())
+}
+
+/// Like [`to_query_context`] but anchors relative time ranges to `reference`,
+/// for example, the end of the log set.
+pub fn to_query_context_with_reference(
+    input: &str,
+    reference: &TimeReference,
+) -> Result<QueryContext> {
+    let compiled = parse_and_compile_with_reference(input, reference)?;
+    let aggregation = compiled.aggregation();
+    let mut ctx = compiled
+        .context
+        .where_conditions(compiled.where_conditions)
+        .order_by(compiled.order_by);
     if let Some(offset) = compiled.sql_offset {
         ctx = ctx.offset(offset);
     }
     if let Some(tail_n) = compiled.sql_limit_from_end {
         ctx = ctx.tail(tail_n);
     }
+    if let Some(aggregation) = aggregation {
+        ctx = ctx.aggregation(aggregation);
+    }
+    if let Some(window) = compiled.context_window {
+        ctx = ctx.context_window(window);
+    }
     Ok(ctx)
 }
@@ -0,0 +1,479 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Automated findings: a catalog of rule-based checks over a log set, see [`Report`].
+//!
+//! Most checks are QL queries, several of them built on [`PresetName`]s. The database
+//! counts the entries they match, and the earliest of them become the evidence of a finding.
+//! Version skew across nodes is detected using `file_metadata` instead.
+
+use crate::errors::Error;
+use crate::presets::PresetName;
+use crate::to_query_context;
+use chrono::{DateTime, Utc};
+use rabbitmq_lqt_lib::Error as LibError;
+use rabbitmq_lqt_lib::constants::{
+    ALARMS_DOC_URL_ID, CONSUMER_ACK_TIMEOUT_DOC_URL_ID, RESOLUTION_OR_DISCUSSION_URL_IDS,
+};
+use rabbitmq_lqt_lib::rel_db::FileMetadata;
+use rabbitmq_lqt_lib::rel_db::file_metadata::Model as FileMetadataModel;
+use rabbitmq_lqt_lib::rel_db::node_log_entry::Model;
+use rabbitmq_lqt_lib::{
+    Aggregation, DatabaseConnection, LogColumn, MessagePatterns, NodeLogEntry, QueryContext,
+    SortKey, SqlCondition, SqlParam, doc_url_from_id, resolution_or_discussion_url_from_id,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// The maximum number of entry ids listed as the evidence of a finding.
+pub const MAX_EVIDENCE_ENTRIES: usize = 10;
+/// A node crashing with the same message this many times is considered a crash loop.
+pub const CRASH_LOOP_THRESHOLD: usize = 3;
+/// A node observing this many Raft leader changes is considered to have repeated elections.
+pub const REPEATED_ELECTIONS_THRESHOLD: usize = 3;
+
+/// How urgent a finding is. Findings are ranked by severity first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl FindingSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+impl fmt::Display for FindingSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A rule-based check of the report catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    KnownIssues,
+    CrashLoops,
+    Alarms,
+    RepeatedElections,
+    TlsHandshakeFailures,
+    ConsumerAckTimeouts,
+    DeprecatedFeatures,
+    VersionSkew,
+}
+
+impl Check {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::KnownIssues => "known_issues",
+            Self::CrashLoops => "crash_loops",
+            Self::Alarms => "alarms",
+            Self::RepeatedElections => "repeated_elections",
+            Self::TlsHandshakeFailures => "tls_handshake_failures",
+            Self::ConsumerAckTimeouts => "consumer_ack_timeouts",
+            Self::DeprecatedFeatures => "deprecated_features",
+            Self::VersionSkew => "version_skew",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::KnownIssues => "Entries that match a known issue, discussion or pull request",
+            Self::CrashLoops => "Erlang processes that repeatedly crash with the same error",
+            Self::Alarms => "Resource alarms (memory, free disk space, file descriptors)",
+            Self::RepeatedElections => "Repeated Raft leader elections",
+            Self::TlsHandshakeFailures => "Failed TLS handshakes",
+            Self::ConsumerAckTimeouts => {
+                "Channels closed because a consumer did not acknowledge a delivery in time"
+            }
+            Self::DeprecatedFeatures => "Deprecated features in use",
+            Self::VersionSkew => "Nodes running different RabbitMQ or Erlang versions",
+        }
+    }
+
+    pub fn severity(&self) -> FindingSeverity {
+        match self {
+            Self::CrashLoops | Self::Alarms => FindingSeverity::Critical,
+            Self::KnownIssues
+            | Self::RepeatedElections
+            | Self::TlsHandshakeFailures
+            | Self::ConsumerAckTimeouts
+            | Self::VersionSkew => FindingSeverity::Warning,
+            Self::DeprecatedFeatures => FindingSeverity::Info,
+        }
+    }
+
+    /// The QL query that selects the entries this check inspects.
+    /// Version skew is detected using file metadata and has no query.
+    pub fn query_string(&self) -> Option<String> {
+        let query = match self {
+            Self::KnownIssues => "has_resolution_url".to_string(),
+            Self::CrashLoops => PresetName::Crashes.query_string().to_string(),
+            Self::Alarms => r#"labels any ["limits"] and message contains "alarm""#.to_string(),
+            // The leader changes of `Transition::classify`
+            Self::RepeatedElections => concat!(
+                r#"labels any ["elections", "raft"] and (message contains "candidate -> leader in term""#,
+                r#" or message contains "leadership transfer""#,
+                r#" or message =~ "(?is)^rabbitmq metadata store:.*leader")"#
+            )
+            .to_string(),
+            Self::TlsHandshakeFailures => format!(
+                r#"({}) and message contains "handshake""#,
+                PresetName::TlsIssues.query_string()
+            ),
+            Self::ConsumerAckTimeouts => {
+                r#"message contains "waiting for a consumer acknowledgement of a delivery""#
+                    .to_string()
+            }
+            Self::DeprecatedFeatures => r#"labels any ["deprecated_features"]"#.to_string(),
+            Self::VersionSkew => return None,
+        };
+        Some(query)
+    }
+
+    /// The documentation guide that explains this kind of finding, if any.
+    pub fn doc_url(&self) -> Option<&'static str> {
+        match self {
+            Self::Alarms => doc_url_from_id(ALARMS_DOC_URL_ID),
+            Self::ConsumerAckTimeouts => doc_url_from_id(CONSUMER_ACK_TIMEOUT_DOC_URL_ID),
+            _ => None,
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::KnownIssues,
+            Self::CrashLoops,
+            Self::Alarms,
+            Self::RepeatedElections,
+            Self::TlsHandshakeFailures,
+            Self::ConsumerAckTimeouts,
+            Self::DeprecatedFeatures,
+            Self::VersionSkew,
+        ]
+    }
+
+    /// Finds the problems this check detects among the entries that match `ctx`.
+    ///
+    /// The database counts the matching entries, only up to [`MAX_EVIDENCE_ENTRIES`]
+    /// of them are fetched for each finding.
+    pub fn find(
+        &self,
+        db: &DatabaseConnection,
+        ctx: &QueryContext,
+    ) -> Result<Vec<Finding>, LibError> {
+        let mut findings = Vec::new();
+        match self {
+            Self::KnownIssues => {
+                for url_id in RESOLUTION_OR_DISCUSSION_URL_IDS {
+                    let matching = ctx.clone().where_condition(SqlCondition::new(
+                        "resolution_or_discussion_url_id = ?",
+                        vec![SqlParam::Integer(i64::from(url_id))],
+                    ));
+                    let counts = count_by(db, &matching, LogColumn::Node)?;
+                    if !counts.is_empty() {
+                        findings.push(Finding::find(db, *self, &matching, counts)?);
+                    }
+                }
+            }
+            Self::CrashLoops => {
+                for (node, _) in count_by(db, ctx, LogColumn::Node)? {
+                    let on_node = ctx.clone().node(node.as_str());
+                    for (template, count) in count_by(db, &on_node, LogColumn::Pattern)? {
+                        if count < CRASH_LOOP_THRESHOLD {
+                            continue;
+                        }
+                        let matching = on_node.clone().where_condition(MessagePatterns::condition(
+                            SqlCondition::new("template = ?", vec![SqlParam::Text(template)]),
+                        ));
+                        let counts = vec![(node.clone(), count)];
+                        findings.push(Finding::find(db, *self, &matching, counts)?);
+                    }
+                }
+            }
+            Self::RepeatedElections => {
+                for (node, count) in count_by(db, ctx, LogColumn::Node)? {
+                    if count >= REPEATED_ELECTIONS_THRESHOLD {
+                        let matching = ctx.clone().node(node.as_str());
+                        let counts = vec![(node, count)];
+                        findings.push(Finding::find(db, *self, &matching, counts)?);
+                    }
+                }
+            }
+            Self::VersionSkew => {}
+            _ => {
+                let counts = count_by(db, ctx, LogColumn::Node)?;
+                if !counts.is_empty() {
+                    findings.push(Finding::find(db, *self, ctx, counts)?);
+                }
+            }
+        }
+        Ok(findings)
+    }
+}
+
+/// Counts the entries that match `ctx` per value of `column`, most frequent first.
+fn count_by(
+    db: &DatabaseConnection,
+    ctx: &QueryContext,
+    column: LogColumn,
+) -> Result<Vec<(String, usize)>, LibError> {
+    let counts = NodeLogEntry::aggregate(
+        db,
+        &ctx.clone().aggregation(Aggregation::CountBy(Some(column))),
+    )?;
+    Ok(counts
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let value = row.first()?.as_str()?;
+            let count = row.get(1)?.as_u64()?;
+            Some((value.to_string(), count as usize))
+        })
+        .collect())
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A problem detected by a check, with the entries that back it up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub check: Check,
+    pub severity: FindingSeverity,
+    pub summary: String,
+    pub entry_count: usize,
+    pub nodes: Vec<String>,
+    /// Ids of up to [`MAX_EVIDENCE_ENTRIES`] matching entries, oldest first
+    pub evidence: Vec<i64>,
+    /// Documentation guides, issues and discussions relevant to the finding
+    pub urls: Vec<String>,
+}
+
+impl Finding {
+    /// A finding about the entries that match `ctx`, given their number on each node.
+    fn find(
+        db: &DatabaseConnection,
+        check: Check,
+        ctx: &QueryContext,
+        counts: Vec<(String, usize)>,
+    ) -> Result<Self, LibError> {
+        let earliest = ctx
+            .clone()
+            .order_by(vec![
+                SortKey::ascending(LogColumn::Timestamp),
+                SortKey::ascending(LogColumn::Id),
+            ])
+            .limit(MAX_EVIDENCE_ENTRIES as u64);
+        let evidence = NodeLogEntry::query(db, &earliest)?;
+
+        let entry_count = counts.iter().map(|(_, count)| count).sum();
+        let nodes: BTreeSet<String> = counts.into_iter().map(|(node, _)| node).collect();
+        Ok(Self::of(check, entry_count, nodes, &evidence))
+    }
+
+    fn of(check: Check, entry_count: usize, nodes: BTreeSet<String>, evidence: &[Model]) -> Self {
+        let mut urls: Vec<String> = Vec::new();
+        let entry_urls = evidence.iter().flat_map(|e| {
+            [
+                e.resolution_or_discussion_url_id
+                    .and_then(resolution_or_discussion_url_from_id),
+                e.doc_url_id.and_then(doc_url_from_id),
+            ]
+        });
+        for url in check.doc_url().into_iter().chain(entry_urls.flatten()) {
+            if !urls.iter().any(|u| u == url) {
+                urls.push(url.to_string());
+            }
+        }
+
+        let first = evidence
+            .first()
+            .map(|e| e.message.lines().next().unwrap_or_default());
+        let summary = match (check, first) {
+            (Check::CrashLoops | Check::KnownIssues, Some(message)) => format!(
+                "{}: {} entries, e.g. \"{}\"",
+                check.description(),
+                entry_count,
+                message
+            ),
+            _ => format!(
+                "{}: {} entries on {} node(s)",
+                check.description(),
+                entry_count,
+                nodes.len()
+            ),
+        };
+
+        Self {
+            check,
+            severity: check.severity(),
+            summary,
+            entry_count,
+            nodes: nodes.into_iter().collect(),
+            evidence: evidence.iter().map(|e| e.id).collect(),
+            urls,
+        }
+    }
+
+    /// Compares the RabbitMQ and Erlang versions that nodes report in their log files.
+    pub fn version_skew(files: &[FileMetadataModel]) -> Vec<Finding> {
+        let mut rabbitmq: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        let mut erlang: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for file in files {
+            for node in &file.nodes {
+                rabbitmq
+                    .entry(node.as_str())
+                    .or_default()
+                    .extend(file.rabbitmq_versions.iter().map(String::as_str));
+                erlang
+                    .entry(node.as_str())
+                    .or_default()
+                    .extend(file.erlang_versions.iter().map(String::as_str));
+            }
+        }
+
+        [("RabbitMQ", rabbitmq), ("Erlang", erlang)]
+            .into_iter()
+            .filter_map(|(name, versions)| {
+                let versions: BTreeMap<&str, BTreeSet<&str>> = versions
+                    .into_iter()
+                    .filter(|(_, v)| !v.is_empty())
+                    .collect();
+                let distinct: BTreeSet<&BTreeSet<&str>> = versions.values().collect();
+                if distinct.len() < 2 {
+                    return None;
+                }
+                let per_node: Vec<String> = versions
+                    .iter()
+                    .map(|(node, v)| {
+                        format!(
+                            "{}: {}",
+                            node,
+                            v.iter().copied().collect::<Vec<_>>().join(", ")
+                        )
+                    })
+                    .collect();
+                Some(Finding {
+                    check: Check::VersionSkew,
+                    severity: Check::VersionSkew.severity(),
+                    summary: format!(
+                        "Nodes run different {} versions: {}",
+                        name,
+                        per_node.join("; ")
+                    ),
+                    entry_count: 0,
+                    nodes: versions.keys().map(|n| n.to_string()).collect(),
+                    evidence: Vec::new(),
+                    urls: Vec::new(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Narrows down the entries a report inspects.
+#[derive(Debug, Clone, Default)]
+pub struct ReportOptions {
+    pub since_time: Option<DateTime<Utc>>,
+    pub to_time: Option<DateTime<Utc>>,
+    pub node: Option<String>,
+}
+
+impl ReportOptions {
+    pub fn since(mut self, time: DateTime<Utc>) -> Self {
+        self.since_time = Some(time);
+        self
+    }
+
+    pub fn to(mut self, time: DateTime<Utc>) -> Self {
+        self.to_time = Some(time);
+        self
+    }
+
+    pub fn node(mut self, node: impl Into<String>) -> Self {
+        self.node = Some(node.into());
+        self
+    }
+}
+
+/// Findings of all checks, most severe and most frequent first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report {
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    /// Runs all checks of the catalog.
+    pub fn run(db: &DatabaseConnection, options: &ReportOptions) -> Result<Self, Error> {
+        let mut findings = Vec::new();
+
+        for check in Check::all() {
+            let Some(query) = check.query_string() else {
+                continue;
+            };
+            let mut ctx = to_query_context(&query)?;
+            if let Some(since) = options.since_time {
+                ctx = ctx.since(since);
+            }
+            if let Some(to) = options.to_time {
+                ctx = ctx.to(to);
+            }
+            if let Some(ref node) = options.node {
+                ctx = ctx.node(node);
+            }
+            findings.extend(check.find(db, &ctx)?);
+        }
+
+        let files: Vec<FileMetadataModel> = FileMetadata::find_all(db)
+            .map_err(LibError::from)?
+            .into_iter()
+            .filter(|file| {
+                options
+                    .node
+                    .as_ref()
+                    .is_none_or(|node| file.nodes.contains(node))
+            })
+            .collect();
+        findings.extend(Finding::version_skew(&files));
+
+        Ok(Self::ranked(findings))
+    }
+
+    pub fn ranked(mut findings: Vec<Finding>) -> Self {
+        findings.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then(b.entry_count.cmp(&a.entry_count))
+                .then(a.check.as_str().cmp(b.check.as_str()))
+        });
+        Self { findings }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
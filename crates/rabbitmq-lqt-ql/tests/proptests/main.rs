@@ -0,0 +1 @@
+mod sql_injection_proptests;
@@ -0,0 +1,164 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fuzzes QL string values against injection and escaping bugs: whatever a value
+//! contains, the generated SQL must not change and queries must match the value literally.
+
+use chrono::Utc;
+use proptest::prelude::*;
+use rabbitmq_lqt_lib::{
+    DatabaseConnection, LogEntryLabels, NodeLogEntry, ParsedLogEntry, Severity, StructuredMetadata,
+    create_database,
+};
+use rabbitmq_lqt_ql::errors::Error;
+use rabbitmq_lqt_ql::{CompileError, parse_and_compile, to_query_context};
+use tempfile::TempDir;
+
+/// Quotes, backslashes, `LIKE` wildcards and SQL punctuation
+const TRICKY_TEXT: &str = "[a-zA-Z0-9 '\"\\\\%_;*()=-]{0,12}";
+
+fn ql_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn create_database_with_messages(temp_dir: &TempDir, messages: &[String]) -> DatabaseConnection {
+    let db = create_database(&temp_dir.path().join("proptest.db")).unwrap();
+    let entries: Vec<_> = messages
+        .iter()
+        .map(|message| ParsedLogEntry {
+            sequence_id: 0,
+            explicit_id: None,
+            timestamp: Utc::now(),
+            severity: Severity::Info,
+            process_id: "<0.208.0>".to_string(),
+            message: message.clone(),
+            message_lowercased: message.to_lowercase(),
+            subsystem_id: None,
+            labels: LogEntryLabels::default(),
+            resolution_or_discussion_url_id: None,
+            doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
+        })
+        .collect();
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "rabbit@proptest").unwrap();
+    db
+}
+
+fn matching_messages(db: &DatabaseConnection, query: &str) -> Vec<String> {
+    let ctx = to_query_context(query).unwrap();
+    let mut messages: Vec<_> = NodeLogEntry::query(db, &ctx)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.message)
+        .collect();
+    messages.sort();
+    messages
+}
+
+fn expected_messages(messages: &[String], matches: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut expected: Vec<_> = messages.iter().filter(|m| matches(m)).cloned().collect();
+    expected.sort();
+    expected
+}
+
+/// Messages and a value that is often a part of one of them
+fn messages_and_value() -> impl Strategy<Value = (Vec<String>, String)> {
+    prop::collection::vec(TRICKY_TEXT, 1..6).prop_flat_map(|messages| {
+        let first = messages[0].clone();
+        let substring = (0..=first.len(), 0..=first.len()).prop_map(move |(a, b)| {
+            let (start, end) = if a <= b { (a, b) } else { (b, a) };
+            first[start..end].to_string()
+        });
+        let value = prop_oneof![substring, TRICKY_TEXT.prop_map(String::from)];
+        (Just(messages), value)
+    })
+}
+
+proptest! {
+    #[test]
+    fn values_never_become_part_of_the_sql(value in TRICKY_TEXT, field in prop::sample::select(vec![
+        "message", "node", "vhost", "user", "mfa", "queue", "exchange", "pattern",
+    ])) {
+        for op in ["==", "!=", "contains"] {
+            let query = format!("{} {} {}", field, op, ql_string(&value));
+            let reference = format!("{} {} \"x\"", field, op);
+            let compiled = parse_and_compile(&query).unwrap();
+            let reference = parse_and_compile(&reference).unwrap();
+            let sql: Vec<_> = compiled.where_conditions.iter().map(|c| c.sql()).collect();
+            let reference_sql: Vec<_> = reference.where_conditions.iter().map(|c| c.sql()).collect();
+            prop_assert_eq!(sql, reference_sql, "query: {}", query);
+        }
+    }
+
+    #[test]
+    fn search_terms_never_become_part_of_the_sql(value in TRICKY_TEXT) {
+        let query = format!("search {} or node == \"x\"", ql_string(&value));
+        match parse_and_compile(&query) {
+            Ok(compiled) => {
+                for condition in &compiled.where_conditions {
+                    prop_assert!(!condition.sql().contains('\''), "{}", condition.sql());
+                }
+            }
+            Err(Error::Compile(CompileError::EmptySearch { .. })) => {}
+            Err(e) => prop_assert!(false, "{}: {}", query, e),
+        }
+    }
+}
+
+proptest! {
+    // Every case creates a database
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn queries_match_values_literally((messages, value) in messages_and_value()) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = create_database_with_messages(&temp_dir, &messages);
+        let needle = value.to_lowercase();
+
+        let query = format!("message contains {}", ql_string(&value));
+        prop_assert_eq!(
+            matching_messages(&db, &query),
+            expected_messages(&messages, |m| m.to_lowercase().contains(&needle))
+        );
+        let query = format!("not message contains {}", ql_string(&value));
+        prop_assert_eq!(
+            matching_messages(&db, &query),
+            expected_messages(&messages, |m| !m.to_lowercase().contains(&needle))
+        );
+
+        let query = format!("message == {} or node == {}", ql_string(&value), ql_string(&value));
+        prop_assert_eq!(
+            matching_messages(&db, &query),
+            expected_messages(&messages, |m| m == value)
+        );
+
+        let query = format!("message =~ {}", ql_string(&regex::escape(&value)));
+        prop_assert_eq!(
+            matching_messages(&db, &query),
+            expected_messages(&messages, |m| m.contains(&value))
+        );
+
+        let query = format!("search {} or not search {}", ql_string(&value), ql_string(&value));
+        match to_query_context(&query) {
+            Ok(ctx) => {
+                prop_assert_eq!(NodeLogEntry::query(&db, &ctx).unwrap().len(), messages.len());
+            }
+            Err(Error::Compile(CompileError::EmptySearch { .. })) => {}
+            Err(e) => prop_assert!(false, "{}: {}", query, e),
+        }
+
+        prop_assert_eq!(NodeLogEntry::count_all(&db).unwrap(), messages.len() as u64);
+    }
+}
@@ -75,6 +75,16 @@ fn test_fields_include_all_ast_fields() {
     assert!(field_names.contains(&"labels"));
     assert!(field_names.contains(&"timestamp"));
     assert!(field_names.contains(&"id"));
+    assert!(field_names.contains(&"domain"));
+    assert!(field_names.contains(&"mfa"));
+    assert!(field_names.contains(&"file"));
+    assert!(field_names.contains(&"line"));
+    assert!(field_names.contains(&"connection"));
+    assert!(field_names.contains(&"vhost"));
+    assert!(field_names.contains(&"user"));
+    assert!(field_names.contains(&"queue"));
+    assert!(field_names.contains(&"stream"));
+    assert!(field_names.contains(&"exchange"));
 }
 
 #[test]
@@ -122,6 +132,7 @@ fn test_pipeline_stages_include_all_stages() {
     assert!(names.contains(&"count"));
     assert!(names.contains(&"distinct"));
     assert!(names.contains(&"project"));
+    assert!(names.contains(&"context"));
 }
 
 #[test]
@@ -139,6 +150,7 @@ fn test_special_filters_are_listed() {
     assert!(SPECIAL_FILTERS.contains(&"has_doc_url"));
     assert!(SPECIAL_FILTERS.contains(&"has_resolution_url"));
     assert!(SPECIAL_FILTERS.contains(&"unlabelled"));
+    assert!(SPECIAL_FILTERS.contains(&"search"));
 }
 
 #[test]
@@ -326,7 +338,7 @@ fn test_completions_for_duration_context() {
 #[test]
 fn test_field_all_matches_ast() {
     let all_fields = Field::all();
-    assert_eq!(all_fields.len(), 8);
+    assert_eq!(all_fields.len(), 19);
     assert!(all_fields.contains(&Field::Severity));
     assert!(all_fields.contains(&Field::Subsystem));
     assert!(all_fields.contains(&Field::Node));
@@ -335,6 +347,10 @@ fn test_field_all_matches_ast() {
     assert!(all_fields.contains(&Field::Labels));
     assert!(all_fields.contains(&Field::Timestamp));
     assert!(all_fields.contains(&Field::Id));
+    assert!(all_fields.contains(&Field::Vhost));
+    assert!(all_fields.contains(&Field::User));
+    assert!(all_fields.contains(&Field::Queue));
+    assert!(all_fields.contains(&Field::Exchange));
 }
 
 #[test]
@@ -347,6 +363,9 @@ fn test_field_aliases() {
     assert!(Field::Subsystem.aliases().is_empty());
     assert!(Field::Node.aliases().is_empty());
     assert!(Field::Id.aliases().is_empty());
+    assert_eq!(Field::Vhost.aliases(), &["virtual_host"]);
+    assert_eq!(Field::User.aliases(), &["username"]);
+    assert!(Field::Mfa.aliases().is_empty());
 }
 
 #[test]
@@ -15,7 +15,7 @@
 //! Unit tests for the builder API.
 
 use rabbitmq_lqt_ql::ast::{
-    DurationUnit, Field, FilterExpr, MatchOp, PipelineStage, SortDirection, Value,
+    DurationUnit, Field, FilterExpr, MatchOp, PipelineStage, SortDirection, SortSpec, Value,
 };
 use rabbitmq_lqt_ql::builder::{FilterBuilder, QueryBuilder, and, comparison, not, or};
 use rabbitmq_lqt_ql::parse;
@@ -70,6 +70,18 @@ fn test_time_range_weeks() {
     assert_eq!(duration.unit, DurationUnit::Weeks);
 }
 
+#[test]
+fn test_min_severity_selector() {
+    let query = QueryBuilder::new().min_severity("warning").build();
+    let selector = query.selector.expect("should have selector");
+    assert_eq!(selector.matchers[0].field, Field::Severity);
+    assert_eq!(selector.matchers[0].op, MatchOp::GtEq);
+    assert_eq!(
+        selector.matchers[0].value,
+        Value::String("warning".to_string())
+    );
+}
+
 #[test]
 fn test_severity_selector() {
     let query = QueryBuilder::new().severity("error").build();
@@ -111,6 +123,36 @@ fn test_erlang_pid_selector() {
     assert_eq!(selector.matchers[0].field, Field::ErlangPid);
 }
 
+#[test]
+fn test_structured_metadata_selectors() {
+    let query = QueryBuilder::new()
+        .vhost("vhost1")
+        .user("user2")
+        .mfa("rabbit_reader:start_connection/3")
+        .build();
+    let selector = query.selector.expect("should have selector");
+    assert_eq!(selector.matchers.len(), 3);
+    assert_eq!(selector.matchers[0].field, Field::Vhost);
+    assert_eq!(selector.matchers[1].field, Field::User);
+    assert_eq!(selector.matchers[2].field, Field::Mfa);
+}
+
+#[test]
+fn test_entity_selectors() {
+    let query = QueryBuilder::new()
+        .queue("orders")
+        .exchange("amq.topic")
+        .build();
+    let selector = query.selector.expect("should have selector");
+    assert_eq!(selector.matchers.len(), 2);
+    assert_eq!(selector.matchers[0].field, Field::Queue);
+    assert_eq!(
+        selector.matchers[0].value,
+        Value::String("orders".to_string())
+    );
+    assert_eq!(selector.matchers[1].field, Field::Exchange);
+}
+
 #[test]
 fn test_multiple_selectors() {
     let query = QueryBuilder::new()
@@ -228,6 +270,13 @@ fn test_unlabelled_filter() {
     assert!(matches!(filter, FilterExpr::Unlabelled));
 }
 
+#[test]
+fn test_search_filter() {
+    let query = QueryBuilder::new().search("handshake timeout").build();
+    let filter = query.filter.expect("should have filter");
+    assert_eq!(filter, FilterExpr::Search("handshake timeout".to_string()));
+}
+
 #[test]
 fn test_preset_errors() {
     let query = QueryBuilder::new().errors().build();
@@ -331,7 +380,9 @@ fn test_sort_pipeline() {
         .build();
     assert_eq!(query.pipeline.len(), 1);
     match &query.pipeline[0] {
-        PipelineStage::Sort(spec) => {
+        PipelineStage::Sort(specs) => {
+            assert_eq!(specs.len(), 1);
+            let spec = &specs[0];
             assert_eq!(spec.field, Field::Timestamp);
             assert_eq!(spec.direction, SortDirection::Desc);
         }
@@ -343,7 +394,9 @@ fn test_sort_pipeline() {
 fn test_sort_by_timestamp_desc() {
     let query = QueryBuilder::new().sort_by_timestamp_desc().build();
     match &query.pipeline[0] {
-        PipelineStage::Sort(spec) => {
+        PipelineStage::Sort(specs) => {
+            assert_eq!(specs.len(), 1);
+            let spec = &specs[0];
             assert_eq!(spec.field, Field::Timestamp);
             assert_eq!(spec.direction, SortDirection::Desc);
         }
@@ -355,7 +408,9 @@ fn test_sort_by_timestamp_desc() {
 fn test_sort_by_timestamp_asc() {
     let query = QueryBuilder::new().sort_by_timestamp_asc().build();
     match &query.pipeline[0] {
-        PipelineStage::Sort(spec) => {
+        PipelineStage::Sort(specs) => {
+            assert_eq!(specs.len(), 1);
+            let spec = &specs[0];
             assert_eq!(spec.field, Field::Timestamp);
             assert_eq!(spec.direction, SortDirection::Asc);
         }
@@ -363,6 +418,31 @@ fn test_sort_by_timestamp_asc() {
     }
 }
 
+#[test]
+fn test_sort_by_several_fields() {
+    let query = QueryBuilder::new()
+        .sort_by(vec![
+            SortSpec {
+                field: Field::Node,
+                direction: SortDirection::Asc,
+            },
+            SortSpec {
+                field: Field::Severity,
+                direction: SortDirection::Desc,
+            },
+        ])
+        .build();
+    assert_eq!(query.pipeline.len(), 1);
+    match &query.pipeline[0] {
+        PipelineStage::Sort(specs) => {
+            assert_eq!(specs.len(), 2);
+            assert_eq!(specs[1].field, Field::Severity);
+            assert_eq!(specs[1].direction, SortDirection::Desc);
+        }
+        _ => panic!("expected sort stage"),
+    }
+}
+
 #[test]
 fn test_project_pipeline() {
     let query = QueryBuilder::new()
@@ -396,6 +476,18 @@ fn test_count_pipeline() {
     assert!(matches!(query.pipeline[0], PipelineStage::CountBy(None)));
 }
 
+#[test]
+fn test_context_pipeline() {
+    let query = QueryBuilder::new()
+        .severity("error")
+        .context(10, Some(Field::Node))
+        .build();
+    assert_eq!(
+        query.pipeline,
+        vec![PipelineStage::Context(10, Some(Field::Node))]
+    );
+}
+
 #[test]
 fn test_distinct_pipeline() {
     let query = QueryBuilder::new()
@@ -453,7 +545,7 @@ fn test_compile_message_contains() {
         .compile()
         .expect("should compile");
 
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
@@ -463,7 +555,7 @@ fn test_compile_labels_any() {
         .compile()
         .expect("should compile");
 
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
@@ -473,7 +565,7 @@ fn test_compile_with_sort() {
         .compile()
         .expect("should compile");
 
-    assert!(compiled.sql_order_by.is_some());
+    assert!(!compiled.order_by.is_empty());
 }
 
 #[test]
@@ -736,7 +828,7 @@ fn test_realistic_error_investigation_query() {
         .compile()
         .expect("should compile");
 
-    assert!(compiled.sql_order_by.is_some());
+    assert!(!compiled.order_by.is_empty());
 }
 
 #[test]
@@ -749,7 +841,7 @@ fn test_realistic_connection_analysis_query() {
         .compile()
         .expect("should compile");
 
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
@@ -771,7 +863,7 @@ fn test_realistic_access_audit_query() {
         .compile()
         .expect("should compile");
 
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
@@ -12,7 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use rabbitmq_lqt_ql::{compile, parse};
+use chrono::{DateTime, Duration, Utc};
+use rabbitmq_lqt_lib::{
+    Aggregation, ContextScope, ContextWindow, LogColumn, SortKey, SqlCondition, SqlParam,
+    TimeAnchor, TimeReference,
+};
+use rabbitmq_lqt_ql::{CompileError, compile, compile_with_reference, parse, to_query_context};
 
 #[test]
 fn test_compile_severity_selector() {
@@ -56,6 +61,68 @@ fn test_compile_invalid_severity() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_compile_severity_comparisons_follow_rank() {
+    for (input, sql, ordinal) in [
+        ("severity >= warning", "severity >= ?", 3),
+        (r#"severity > "notice""#, "severity > ?", 2),
+        ("severity < ERROR", "severity < ?", 4),
+        ("{severity<=info}", "severity <= ?", 1),
+        ("severity != debug", "severity != ?", 0),
+    ] {
+        let compiled = compile(&parse(input).unwrap()).unwrap();
+        assert_eq!(
+            compiled.where_conditions,
+            vec![SqlCondition::new(sql, vec![SqlParam::Integer(ordinal)])],
+            "for query {}",
+            input
+        );
+    }
+
+    let compiled = compile(&parse("severity == ERROR").unwrap()).unwrap();
+    assert!(compiled.where_conditions.is_empty());
+}
+
+#[test]
+fn test_compile_severity_patterns_match_names() {
+    let compiled = compile(&parse("severity =~ /^(error|critical)$/").unwrap()).unwrap();
+    assert_eq!(
+        compiled.where_conditions,
+        vec![SqlCondition::new(
+            "severity IN (?, ?)",
+            vec![SqlParam::Integer(4), SqlParam::Integer(5)]
+        )]
+    );
+
+    let compiled = compile(&parse(r#"severity contains "ERR""#).unwrap()).unwrap();
+    assert_eq!(
+        compiled.where_conditions,
+        vec![SqlCondition::new(
+            "severity IN (?)",
+            vec![SqlParam::Integer(4)]
+        )]
+    );
+
+    let compiled = compile(&parse(r#"severity !~ "i""#).unwrap()).unwrap();
+    assert_eq!(
+        compiled.where_conditions[0].sql(),
+        "NOT (severity IN (?, ?, ?, ?))"
+    );
+
+    let compiled = compile(&parse(r#"severity contains "fatal""#).unwrap()).unwrap();
+    assert_eq!(compiled.where_conditions[0].sql(), "FALSE");
+}
+
+#[test]
+fn test_compile_severity_threshold_with_unknown_level() {
+    let err = compile(&parse("severity >= fatal").unwrap()).unwrap_err();
+    assert!(
+        matches!(err, CompileError::InvalidSeverity { .. }),
+        "{:?}",
+        err
+    );
+}
+
 #[test]
 fn test_compile_subsystem_selector() {
     let query = parse(r#"{subsystem="connections"}"#).unwrap();
@@ -77,6 +144,149 @@ fn test_compile_erlang_pid_selector() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_compile_vhost_selector_uses_query_context() {
+    let query = parse(r#"{vhost="vhost1"}"#).unwrap();
+    let compiled = compile(&query).unwrap();
+    assert!(compiled.where_conditions.is_empty());
+}
+
+#[test]
+fn test_compile_mfa_regex_sql() {
+    let query = parse(r#"mfa =~ "^rabbit_networking:""#).unwrap();
+    let compiled = compile(&query).unwrap();
+    let sql = compiled.where_conditions[0].sql();
+    assert!(sql.contains("regexp_matches(mfa"));
+}
+
+#[test]
+fn test_compile_user_not_eq_sql() {
+    let query = parse(r#"user != "guest""#).unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new("username != ?", vec![SqlParam::Text("guest".to_string())])
+    );
+}
+
+#[test]
+fn test_compile_source_line_comparison_sql() {
+    let query = parse("file == \"rabbit_networking.erl\" and line >= 140").unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.where_conditions,
+        vec![SqlCondition::new(
+            "source_line >= ?",
+            vec![SqlParam::Integer(140)]
+        )]
+    );
+}
+
+#[test]
+fn test_compile_queue_selector_uses_query_context() {
+    let query = parse(r#"{queue="orders", vhost="/"}"#).unwrap();
+    let compiled = compile(&query).unwrap();
+    assert!(compiled.where_conditions.is_empty());
+}
+
+#[test]
+fn test_compile_queue_regex_sql() {
+    let query = parse(r#"queue =~ "^orders""#).unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new(
+            "id IN (SELECT entry_id FROM node_log_entry_entities WHERE entity_kind = 'queue' AND regexp_matches(name, ?))",
+            vec![SqlParam::Text("^orders".to_string())]
+        )
+    );
+}
+
+#[test]
+fn test_compile_exchange_not_eq_sql() {
+    let query = parse(r#"exchange != "amq.topic""#).unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new(
+            "NOT (id IN (SELECT entry_id FROM node_log_entry_entities WHERE entity_kind = 'exchange' AND name = ?))",
+            vec![SqlParam::Text("amq.topic".to_string())]
+        )
+    );
+}
+
+#[test]
+fn test_compile_queue_or_stream_sql() {
+    let query = parse(r#"queue == "orders" or stream == "orders""#).unwrap();
+    let compiled = compile(&query).unwrap();
+    let condition = &compiled.where_conditions[0];
+    assert!(
+        condition
+            .sql()
+            .contains("entity_kind = 'queue' AND name = ?")
+    );
+    assert!(
+        condition
+            .sql()
+            .contains("entity_kind = 'stream' AND name = ?")
+    );
+    assert_eq!(
+        condition.params(),
+        &[
+            SqlParam::Text("orders".to_string()),
+            SqlParam::Text("orders".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_compile_count_by_queue() {
+    let query = parse("* | count by queue").unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.aggregation(),
+        Some(Aggregation::CountBy(Some(LogColumn::Queue)))
+    );
+}
+
+#[test]
+fn test_compile_pattern_eq_sql() {
+    let query = parse(r#"pattern == "accepting AMQP connection <ip> -> <ip>""#).unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new(
+            "pattern_id IN (SELECT id FROM message_patterns WHERE template = ?)",
+            vec![SqlParam::Text(
+                "accepting AMQP connection <ip> -> <ip>".to_string()
+            )]
+        )
+    );
+}
+
+#[test]
+fn test_compile_pattern_not_regex_sql() {
+    let query = parse(r#"template !~ "^closing""#).unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new(
+            "NOT (pattern_id IN (SELECT id FROM message_patterns WHERE regexp_matches(template, ?)))",
+            vec![SqlParam::Text("^closing".to_string())]
+        )
+    );
+}
+
+#[test]
+fn test_compile_count_by_pattern() {
+    let query = parse("* | count by pattern").unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.aggregation(),
+        Some(Aggregation::CountBy(Some(LogColumn::Pattern)))
+    );
+}
+
 #[test]
 fn test_compile_multiple_selectors() {
     let query = parse(r#"{severity="error", subsystem="connections"}"#).unwrap();
@@ -130,27 +340,33 @@ fn test_compile_head() {
 fn test_compile_message_contains_sql() {
     let query = parse(r#"message contains "accepting AMQP connection""#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("ILIKE"));
-    assert!(sql.contains("accepting AMQP connection"));
+    assert!(!compiled.where_conditions.is_empty());
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new(
+            "message ILIKE ? ESCAPE '\\'",
+            vec![SqlParam::Text("%accepting AMQP connection%".to_string())]
+        )
+    );
 }
 
 #[test]
 fn test_compile_message_contains_http_access_denied() {
     let query = parse(r#"message contains "HTTP access denied""#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("HTTP access denied"));
+    assert!(!compiled.where_conditions.is_empty());
+    assert_eq!(
+        compiled.where_conditions[0].params(),
+        &[SqlParam::Text("%HTTP access denied%".to_string())]
+    );
 }
 
 #[test]
 fn test_compile_message_regex_sql() {
     let query = parse(r#"message =~ "connection.*vhost""#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
+    assert!(!compiled.where_conditions.is_empty());
+    let sql = compiled.where_conditions[0].sql();
     assert!(sql.contains("regexp_matches"));
 }
 
@@ -158,9 +374,14 @@ fn test_compile_message_regex_sql() {
 fn test_compile_message_not_regex_sql() {
     let query = parse(r#"message !~ "startup""#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("NOT regexp_matches"));
+    assert!(!compiled.where_conditions.is_empty());
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new(
+            "NOT (regexp_matches(message, ?))",
+            vec![SqlParam::Text("startup".to_string())]
+        )
+    );
 }
 
 #[test]
@@ -174,17 +395,64 @@ fn test_compile_invalid_regex() {
 fn test_compile_label_any_sql() {
     let query = parse(r#"labels any ["connections", "disconnects"]"#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("labels &"));
-    assert!(sql.contains("!= 0"));
+    assert!(!compiled.where_conditions.is_empty());
+    let sql = compiled.where_conditions[0].sql();
+    assert!(sql.contains("node_log_entry_labels"));
+    assert!(!sql.contains("HAVING"));
 }
 
 #[test]
 fn test_compile_label_all_sql() {
     let query = parse(r#"labels all ["connections", "networking"]"#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
+    let sql = compiled.where_conditions[0].sql();
+    assert!(sql.contains("node_log_entry_labels"));
+    assert!(sql.contains("HAVING COUNT(DISTINCT label_id) = ?"));
+    assert_eq!(
+        compiled.where_conditions[0].params().last(),
+        Some(&SqlParam::Integer(2))
+    );
+}
+
+#[test]
+fn test_compile_custom_label_any_sql() {
+    let query = parse(r#"labels any ["connections", "custom:billing"]"#).unwrap();
+    let compiled = compile(&query).unwrap();
+    let sql = compiled.where_conditions[0].sql();
+    assert!(sql.contains("node_log_entry_labels"));
+    assert!(sql.contains("node_log_entry_custom_annotations"));
+    assert!(sql.contains("value IN (?)"));
+    assert!(sql.contains(" OR "));
+    assert!(
+        compiled.where_conditions[0]
+            .params()
+            .contains(&SqlParam::Text("billing".to_string()))
+    );
+}
+
+#[test]
+fn test_compile_custom_label_all_sql() {
+    let query = parse(r#"labels all ["custom:billing", "custom:needs-triage"]"#).unwrap();
+    let compiled = compile(&query).unwrap();
+    let sql = compiled.where_conditions[0].sql();
+    assert!(!sql.contains("node_log_entry_labels"));
+    assert!(sql.contains("HAVING COUNT(DISTINCT value) = ?"));
+    assert_eq!(
+        compiled.where_conditions[0].params(),
+        &[
+            SqlParam::Text("billing".to_string()),
+            SqlParam::Text("needs-triage".to_string()),
+            SqlParam::Integer(2)
+        ]
+    );
+}
+
+#[test]
+fn test_compile_invalid_custom_label() {
+    let query = parse(r#"labels any ["custom:it's"]"#).unwrap();
+    let result = compile(&query);
+    assert!(result.is_err());
 }
 
 #[test]
@@ -198,11 +466,15 @@ fn test_compile_unknown_label() {
 fn test_compile_subsystem_any_sql() {
     let query = parse(r#"subsystem any ["raft", "metadata_store"]"#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("subsystem_id IN"));
-    assert!(sql.contains("4")); // Raft = 4
-    assert!(sql.contains("1")); // MetadataStore = 1
+    assert!(!compiled.where_conditions.is_empty());
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new(
+            "subsystem_id IN (?, ?)",
+            // Raft = 4, MetadataStore = 1
+            vec![SqlParam::Integer(4), SqlParam::Integer(1)]
+        )
+    );
 }
 
 #[test]
@@ -233,12 +505,49 @@ fn test_compile_unlabelled() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_compile_search() {
+    let ctx = to_query_context(r#"search "\"connection closed\" peer*""#).unwrap();
+    assert_eq!(
+        ctx.get_search().unwrap().to_string(),
+        "\"connection closed\" peer*"
+    );
+
+    let ctx = to_query_context(r#"search "timeout" | where search "heartbeat""#).unwrap();
+    assert_eq!(ctx.get_search().unwrap().to_string(), "timeout heartbeat");
+}
+
+#[test]
+fn test_compile_search_in_or_scans_messages() {
+    let query = parse(r#"search "it's closed" or severity == "error""#).unwrap();
+    let compiled = compile(&query).unwrap();
+    assert!(compiled.context.get_search().is_none());
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new(
+            "((regexp_matches(lower(message), ?) AND regexp_matches(lower(message), ?)) OR severity = ?)",
+            vec![
+                SqlParam::Text(r"\bit\W+s\b".to_string()),
+                SqlParam::Text(r"\bclosed\b".to_string()),
+                SqlParam::Integer(4),
+            ]
+        )
+    );
+}
+
+#[test]
+fn test_compile_empty_search() {
+    let query = parse(r#"search "--""#).unwrap();
+    let err = compile(&query).unwrap_err();
+    assert_eq!(err.to_string(), r#"Search "--" has no words to look for"#);
+}
+
 #[test]
 fn test_compile_or_filter_sql() {
     let query = parse(r#"severity == "error" or severity == "warning""#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
+    assert!(!compiled.where_conditions.is_empty());
+    let sql = compiled.where_conditions[0].sql();
     assert!(sql.contains("OR"));
 }
 
@@ -246,8 +555,8 @@ fn test_compile_or_filter_sql() {
 fn test_compile_not_filter_sql() {
     let query = parse(r#"not severity == "debug""#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
+    assert!(!compiled.where_conditions.is_empty());
+    let sql = compiled.where_conditions[0].sql();
     assert!(sql.contains("NOT"));
 }
 
@@ -255,20 +564,20 @@ fn test_compile_not_filter_sql() {
 fn test_compile_sort_order_by() {
     let query = parse("* | sort timestamp desc").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(compiled.sql_order_by.is_some());
-    let order = compiled.sql_order_by.as_ref().unwrap();
-    assert!(order.contains("timestamp"));
-    assert!(order.contains("DESC"));
+    assert_eq!(
+        compiled.order_by,
+        vec![SortKey::descending(LogColumn::Timestamp)]
+    );
 }
 
 #[test]
 fn test_compile_sort_severity_asc() {
     let query = parse("* | sort severity asc").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(compiled.sql_order_by.is_some());
-    let order = compiled.sql_order_by.as_ref().unwrap();
-    assert!(order.contains("severity"));
-    assert!(order.contains("ASC"));
+    assert_eq!(
+        compiled.order_by,
+        vec![SortKey::ascending(LogColumn::Severity)]
+    );
 }
 
 #[test]
@@ -289,50 +598,50 @@ fn test_compile_preset_errors() {
 fn test_compile_preset_crashes() {
     let query = parse(":crashes").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
 fn test_compile_preset_errors_or_crashes() {
     let query = parse(":errors_or_crashes").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
 fn test_compile_preset_disconnects() {
     let query = parse(":disconnects").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
 fn test_compile_preset_tls_issues() {
     let query = parse(":tls_issues").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
 fn test_compile_preset_access_denied() {
     let query = parse(":access_denied").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
 fn test_compile_preset_timeouts() {
     let query = parse(":timeouts").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
 fn test_compile_comparison_neq_sql() {
     let query = parse(r#"severity != "debug""#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
+    assert!(!compiled.where_conditions.is_empty());
+    let sql = compiled.where_conditions[0].sql();
     assert!(sql.contains("!="));
 }
 
@@ -354,9 +663,13 @@ fn test_compile_timestamp_to() {
 fn test_compile_sql_string_escaping() {
     let query = parse(r#"message contains "user's data""#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("user''s data"));
+    assert!(!compiled.where_conditions.is_empty());
+    let condition = &compiled.where_conditions[0];
+    assert!(!condition.sql().contains("user"));
+    assert_eq!(
+        condition.params(),
+        &[SqlParam::Text("%user's data%".to_string())]
+    );
 }
 
 #[test]
@@ -365,21 +678,21 @@ fn test_compile_complete_query() {
         parse(r#"@24h {severity="warning"} message contains "HTTP access denied" | limit 100"#)
             .unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
 fn test_compile_wildcard_query() {
     let query = parse("*").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(compiled.sql_where_fragments.is_empty());
+    assert!(compiled.where_conditions.is_empty());
 }
 
 #[test]
 fn test_compile_multiple_pipeline_stages() {
     let query = parse(r#"* | where severity == "error" | sort timestamp desc | limit 50"#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(compiled.sql_order_by.is_some());
+    assert!(!compiled.order_by.is_empty());
 }
 
 #[test]
@@ -400,8 +713,8 @@ fn test_compile_and_filter() {
 fn test_compile_message_eq_sql() {
     let query = parse(r#"message == "Logging: configured log handlers are now ACTIVE""#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
+    assert!(!compiled.where_conditions.is_empty());
+    let sql = compiled.where_conditions[0].sql();
     assert!(sql.contains("message = "));
 }
 
@@ -409,18 +722,22 @@ fn test_compile_message_eq_sql() {
 fn test_compile_id_gt_sql() {
     let query = parse("id > 100").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("id > 100"));
+    assert!(!compiled.where_conditions.is_empty());
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new("id > ?", vec![SqlParam::Integer(100)])
+    );
 }
 
 #[test]
 fn test_compile_id_lt_sql() {
     let query = parse("id < 50").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("id < 50"));
+    assert!(!compiled.where_conditions.is_empty());
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new("id < ?", vec![SqlParam::Integer(50)])
+    );
 }
 
 #[test]
@@ -430,25 +747,29 @@ fn test_compile_grouped_or_and_sql() {
     )
     .unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
 fn test_compile_regex_escape_sql() {
     let query = parse(r#"message =~ "rabbit@host[0-9]+""#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("regexp_matches"));
-    assert!(sql.contains("rabbit@host[0-9]+"));
+    assert!(!compiled.where_conditions.is_empty());
+    assert_eq!(
+        compiled.where_conditions[0],
+        SqlCondition::new(
+            "regexp_matches(message, ?)",
+            vec![SqlParam::Text("rabbit@host[0-9]+".to_string())]
+        )
+    );
 }
 
 #[test]
 fn test_compile_message_icontains_sql() {
     let query = parse(r#"message icontains "AMQP""#).unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
+    assert!(!compiled.where_conditions.is_empty());
+    let sql = compiled.where_conditions[0].sql();
     assert!(sql.contains("ILIKE"));
 }
 
@@ -456,20 +777,50 @@ fn test_compile_message_icontains_sql() {
 fn test_compile_sort_message_desc() {
     let query = parse("* | sort message desc").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(compiled.sql_order_by.is_some());
-    let order = compiled.sql_order_by.as_ref().unwrap();
-    assert!(order.contains("message"));
-    assert!(order.contains("DESC"));
+    assert_eq!(
+        compiled.order_by,
+        vec![SortKey::descending(LogColumn::Message)]
+    );
 }
 
 #[test]
 fn test_compile_sort_node_asc() {
     let query = parse("* | sort node asc").unwrap();
     let compiled = compile(&query).unwrap();
-    assert!(compiled.sql_order_by.is_some());
-    let order = compiled.sql_order_by.as_ref().unwrap();
-    assert!(order.contains("node"));
-    assert!(order.contains("ASC"));
+    assert_eq!(compiled.order_by, vec![SortKey::ascending(LogColumn::Node)]);
+}
+
+#[test]
+fn test_compile_sort_by_several_fields() {
+    let query = parse("* | sort node, severity desc, subsystem, id").unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.order_by,
+        vec![
+            SortKey::ascending(LogColumn::Node),
+            SortKey::descending(LogColumn::Severity),
+            SortKey::ascending(LogColumn::Subsystem),
+            SortKey::ascending(LogColumn::Id),
+        ]
+    );
+}
+
+#[test]
+fn test_compile_later_sort_replaces_earlier_one() {
+    let query = parse("* | sort node | sort timestamp desc").unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.order_by,
+        vec![SortKey::descending(LogColumn::Timestamp)]
+    );
+}
+
+#[test]
+fn test_compile_sort_by_joined_column_fails() {
+    for field in ["labels", "queue", "pattern"] {
+        let query = parse(&format!("* | sort {}", field)).unwrap();
+        assert!(compile(&query).is_err(), "sorting by {}", field);
+    }
 }
 
 #[test]
@@ -552,17 +903,78 @@ fn test_compile_count_without_by() {
     assert!(compiled.count_by_field.is_none());
 }
 
+#[test]
+fn test_compile_context_stage() {
+    let compiled = compile(&parse("severity = error | context 5").unwrap()).unwrap();
+    assert_eq!(compiled.context_window, Some(ContextWindow::around(5)));
+
+    let compiled = compile(&parse("* | context 2 by node").unwrap()).unwrap();
+    assert_eq!(
+        compiled.context_window,
+        Some(ContextWindow::around(2).scoped(ContextScope::Node))
+    );
+
+    let ctx = to_query_context("* | head 1 | context 3 by pid").unwrap();
+    assert_eq!(
+        ctx.get_context_window(),
+        Some(&ContextWindow::around(3).scoped(ContextScope::Process))
+    );
+}
+
+#[test]
+fn test_compile_context_stage_rejects_large_windows() {
+    let compiled = compile(&parse("* | context 1000").unwrap()).unwrap();
+    assert_eq!(
+        compiled.context_window,
+        Some(ContextWindow::around(ContextWindow::MAX_ENTRIES))
+    );
+
+    let result = compile(&parse("* | context 1001").unwrap());
+    assert_eq!(
+        result.unwrap_err(),
+        CompileError::ContextTooLarge {
+            entries: 1001,
+            max: ContextWindow::MAX_ENTRIES
+        }
+    );
+}
+
+#[test]
+fn test_compile_context_stage_unsupported_uses() {
+    let result = compile(&parse("* | context 2 by message").unwrap());
+    assert!(matches!(
+        result,
+        Err(CompileError::UnsupportedOperation { .. })
+    ));
+
+    for query in [
+        "* | context 2 | count",
+        "* | distinct node | context 2",
+        "* | context 2 | project message",
+    ] {
+        let result = compile(&parse(query).unwrap());
+        assert!(
+            matches!(result, Err(CompileError::UnsupportedOperation { .. })),
+            "{}",
+            query
+        );
+    }
+}
+
 #[test]
 fn test_compile_hashtag_label() {
     let query = parse("#connections").unwrap();
     let compiled = compile(&query).unwrap();
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("labels &"));
-    assert!(sql.contains("!= 0"));
+    let sql = compiled.where_conditions[0].sql();
+    assert!(sql.contains("node_log_entry_labels"));
 
     let query2 = parse("##connections").unwrap();
     let compiled2 = compile(&query2).unwrap();
-    assert!(compiled2.sql_where_fragments[0].contains("labels &"));
+    assert!(
+        compiled2.where_conditions[0]
+            .sql()
+            .contains("node_log_entry_labels")
+    );
 
     let query3 = parse("#peer_discovery:classic").unwrap();
     assert!(compile(&query3).is_ok());
@@ -578,7 +990,7 @@ fn test_compile_hashtag_label_unknown() {
 fn test_compile_hashtag_label_boolean_operators() {
     let or_query = parse("#connections or #disconnects").unwrap();
     let or_compiled = compile(&or_query).unwrap();
-    assert!(or_compiled.sql_where_fragments[0].contains("OR"));
+    assert!(or_compiled.where_conditions[0].sql().contains("OR"));
 
     let and_query = parse("#connections and #tls").unwrap();
     assert!(compile(&and_query).is_ok());
@@ -588,6 +1000,181 @@ fn test_compile_hashtag_label_boolean_operators() {
 fn test_compile_hashtag_label_negated() {
     let query = parse("-#connections").unwrap();
     let compiled = compile(&query).unwrap();
-    let sql = &compiled.sql_where_fragments[0];
+    let sql = compiled.where_conditions[0].sql();
     assert!(sql.contains("NOT"));
 }
+
+#[test]
+fn test_aggregation_count_by_field() {
+    let query = parse("* | count by node").unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.aggregation(),
+        Some(Aggregation::CountBy(Some(LogColumn::Node)))
+    );
+}
+
+#[test]
+fn test_aggregation_count_without_field() {
+    let query = parse("* | count").unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(compiled.aggregation(), Some(Aggregation::CountBy(None)));
+}
+
+#[test]
+fn test_aggregation_distinct_maps_subsystem_column() {
+    let query = parse("* | distinct node, subsystem").unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.aggregation(),
+        Some(Aggregation::Distinct(vec![
+            LogColumn::Node,
+            LogColumn::Subsystem
+        ]))
+    );
+}
+
+#[test]
+fn test_aggregation_count_by_structured_metadata_field() {
+    let query = parse("* | count by vhost").unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.aggregation(),
+        Some(Aggregation::CountBy(Some(LogColumn::Vhost)))
+    );
+}
+
+#[test]
+fn test_aggregation_project() {
+    let query = parse("* | project timestamp, message").unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(
+        compiled.aggregation(),
+        Some(Aggregation::Project(vec![
+            LogColumn::Timestamp,
+            LogColumn::Message
+        ]))
+    );
+}
+
+#[test]
+fn test_aggregation_absent_without_stages() {
+    let query = parse(r#"severity == "error" | limit 10"#).unwrap();
+    let compiled = compile(&query).unwrap();
+    assert_eq!(compiled.aggregation(), None);
+}
+
+#[test]
+fn test_to_query_context_carries_aggregation() {
+    let ctx = to_query_context("* | count by severity").unwrap();
+    assert_eq!(
+        ctx.get_aggregation(),
+        Some(&Aggregation::CountBy(Some(LogColumn::Severity)))
+    );
+}
+
+#[test]
+fn test_to_query_context_carries_sort() {
+    let ctx = to_query_context("* | sort severity desc, node").unwrap();
+    assert_eq!(
+        ctx.get_order_by(),
+        &[
+            SortKey::descending(LogColumn::Severity),
+            SortKey::ascending(LogColumn::Node)
+        ]
+    );
+}
+
+fn log_end() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2025-10-27T18:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+#[test]
+fn test_time_range_anchored_to_log_end() {
+    let query = parse("@1h").unwrap();
+    let reference = TimeReference::new(TimeAnchor::LogEnd, Some(log_end()));
+    let compiled = compile_with_reference(&query, &reference).unwrap();
+
+    assert_eq!(
+        compiled.context.get_since_time(),
+        Some(log_end() - Duration::hours(1))
+    );
+    assert_eq!(compiled.context.get_to_time(), None);
+}
+
+#[test]
+fn test_time_range_from_now_overrides_default_anchor() {
+    let query = parse("@1h from now").unwrap();
+    let reference = TimeReference::new(TimeAnchor::LogEnd, Some(log_end()));
+    let compiled = compile_with_reference(&query, &reference).unwrap();
+
+    let since = compiled.context.get_since_time().unwrap();
+    assert!(since > Utc::now() - Duration::hours(2));
+}
+
+#[test]
+fn test_time_range_from_explicit_timestamp() {
+    let query = parse(r#"@2h from "2025-10-20T12:00:00Z""#).unwrap();
+    let reference = TimeReference::new(TimeAnchor::LogEnd, Some(log_end()));
+    let compiled = compile_with_reference(&query, &reference).unwrap();
+
+    let anchor = DateTime::parse_from_rfc3339("2025-10-20T12:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    assert_eq!(
+        compiled.context.get_since_time(),
+        Some(anchor - Duration::hours(2))
+    );
+    assert_eq!(compiled.context.get_to_time(), Some(anchor));
+}
+
+#[test]
+fn test_relative_timestamp_filters_anchored_to_explicit_timestamp() {
+    let anchor = DateTime::parse_from_rfc3339("2025-10-20T12:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let reference = TimeReference::new(TimeAnchor::At(anchor), Some(log_end()));
+
+    let query = parse(r#"timestamp >= @1h and timestamp <= "30 minutes ago""#).unwrap();
+    let compiled = compile_with_reference(&query, &reference).unwrap();
+    assert_eq!(
+        compiled.context.get_since_time(),
+        Some(anchor - Duration::hours(1))
+    );
+    assert_eq!(
+        compiled.context.get_to_time(),
+        Some(anchor - Duration::minutes(30))
+    );
+
+    let query = parse("* | where timestamp > @2h | where timestamp < @1h").unwrap();
+    let compiled = compile_with_reference(&query, &reference).unwrap();
+    assert_eq!(
+        compiled.context.get_since_time(),
+        Some(anchor - Duration::hours(2))
+    );
+    assert_eq!(
+        compiled.context.get_to_time(),
+        Some(anchor - Duration::hours(1))
+    );
+}
+
+#[test]
+fn test_time_range_from_invalid_timestamp() {
+    let query = parse(r#"@2h from "not a timestamp""#).unwrap();
+    let reference = TimeReference::new(TimeAnchor::LogEnd, Some(log_end()));
+    assert!(compile_with_reference(&query, &reference).is_err());
+}
+
+#[test]
+fn test_relative_timestamp_filter_anchored_to_log_end() {
+    let query = parse("timestamp >= @1h").unwrap();
+    let reference = TimeReference::new(TimeAnchor::LogEnd, Some(log_end()));
+    let compiled = compile_with_reference(&query, &reference).unwrap();
+
+    assert_eq!(
+        compiled.context.get_since_time(),
+        Some(log_end() - Duration::hours(1))
+    );
+}
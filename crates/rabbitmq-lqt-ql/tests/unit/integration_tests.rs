@@ -20,6 +20,7 @@
 //! - crates/rabbitmq-lqt-cli/tests/fixtures/rabbit@fixture3.log
 //! - crates/rabbitmq-lqt-cli/tests/fixtures/rabbit@fixture4.log
 
+use rabbitmq_lqt_lib::{LogColumn, SortKey, SqlParam};
 use rabbitmq_lqt_ql::{compile, parse, parse_and_compile};
 
 #[test]
@@ -58,7 +59,7 @@ fn test_full_query_http_access_denied() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
@@ -67,7 +68,7 @@ fn test_full_query_amqp_connection() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    assert!(compiled.sql_order_by.is_some());
+    assert!(!compiled.order_by.is_empty());
 }
 
 #[test]
@@ -205,7 +206,7 @@ fn test_complex_query_with_or() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
@@ -221,7 +222,7 @@ fn test_complex_query_with_not() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
@@ -238,8 +239,8 @@ fn test_query_with_regex_node_pattern() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    let sql = &compiled.sql_where_fragments[0];
+    assert!(!compiled.where_conditions.is_empty());
+    let sql = compiled.where_conditions[0].sql();
     assert!(sql.contains("regexp_matches"));
 }
 
@@ -277,7 +278,7 @@ fn test_labels_any_query() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
+    assert!(!compiled.where_conditions.is_empty());
 }
 
 #[test]
@@ -339,10 +340,10 @@ fn test_pipeline_sort_timestamp_desc() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    assert!(compiled.sql_order_by.is_some());
-    let order = compiled.sql_order_by.unwrap();
-    assert!(order.contains("timestamp"));
-    assert!(order.contains("DESC"));
+    assert_eq!(
+        compiled.order_by,
+        vec![SortKey::descending(LogColumn::Timestamp)]
+    );
 }
 
 #[test]
@@ -351,7 +352,7 @@ fn test_pipeline_sort_severity_asc() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    assert!(compiled.sql_order_by.is_some());
+    assert!(!compiled.order_by.is_empty());
 }
 
 #[test]
@@ -390,7 +391,7 @@ fn test_multiple_pipeline_stages() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    assert!(compiled.sql_order_by.is_some());
+    assert!(!compiled.order_by.is_empty());
 }
 
 #[test]
@@ -434,7 +435,7 @@ fn test_wildcard_only() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    assert!(compiled.sql_where_fragments.is_empty());
+    assert!(compiled.where_conditions.is_empty());
 }
 
 #[test]
@@ -488,8 +489,12 @@ fn test_sql_injection_prevention() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("''; DROP TABLE logs; --"));
+    let condition = &compiled.where_conditions[0];
+    assert!(!condition.sql().contains("DROP"));
+    assert_eq!(
+        condition.params(),
+        &[SqlParam::Text("%'; DROP TABLE logs; --%".to_string())]
+    );
 }
 
 #[test]
@@ -498,8 +503,10 @@ fn test_query_with_apostrophe() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    let sql = &compiled.sql_where_fragments[0];
-    assert!(sql.contains("user''s connection"));
+    assert_eq!(
+        compiled.where_conditions[0].params(),
+        &[SqlParam::Text("%user's connection%".to_string())]
+    );
 }
 
 #[test]
@@ -522,6 +529,6 @@ fn test_complete_query_structure() {
     let result = parse_and_compile(query);
     assert!(result.is_ok());
     let compiled = result.unwrap();
-    assert!(!compiled.sql_where_fragments.is_empty());
-    assert!(compiled.sql_order_by.is_some());
+    assert!(!compiled.where_conditions.is_empty());
+    assert!(!compiled.order_by.is_empty());
 }
@@ -5,3 +5,4 @@ mod error_messages_unit_tests;
 mod integration_tests;
 mod parser_unit_tests;
 mod preset_unit_tests;
+mod report_unit_tests;
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use rabbitmq_lqt_ql::ast::{
-    DurationUnit, Field, FilterExpr, MatchOp, PipelineStage, SortDirection, Value,
+    DurationUnit, Field, FilterExpr, MatchOp, PipelineStage, RangeAnchor, SortDirection, Value,
 };
 use rabbitmq_lqt_ql::parse;
 use rabbitmq_lqt_ql::presets::PresetName;
@@ -95,6 +95,45 @@ fn test_parse_multiple_matchers() {
     assert_eq!(selector.matchers[1].field, Field::Subsystem);
 }
 
+#[test]
+fn test_parse_unquoted_severity() {
+    for (input, op) in [
+        ("severity >= warning", MatchOp::GtEq),
+        ("level < error", MatchOp::Lt),
+        (
+            "severity == notice and node == \"rabbit@host1\"",
+            MatchOp::Eq,
+        ),
+    ] {
+        let query = parse(input).unwrap();
+        let matcher = match query.filter.as_ref().unwrap() {
+            FilterExpr::Comparison(matcher) => matcher,
+            FilterExpr::And(left, _) => match left.as_ref() {
+                FilterExpr::Comparison(matcher) => matcher,
+                other => panic!("Expected Comparison filter, got {:?}", other),
+            },
+            other => panic!("Expected Comparison filter, got {:?}", other),
+        };
+        assert_eq!(matcher.field, Field::Severity, "for query {}", input);
+        assert_eq!(matcher.op, op, "for query {}", input);
+        assert!(
+            matches!(matcher.value, Value::String(_)),
+            "for query {}",
+            input
+        );
+    }
+
+    let query = parse("{severity>=error}").unwrap();
+    let selector = query.selector.as_ref().unwrap();
+    assert_eq!(selector.matchers[0].op, MatchOp::GtEq);
+    assert_eq!(
+        selector.matchers[0].value,
+        Value::String("error".to_string())
+    );
+
+    assert!(parse("node == rabbit").is_err());
+}
+
 #[test]
 fn test_parse_node_selector() {
     let query = parse(r#"{node="rabbit@host1"}"#).unwrap();
@@ -117,6 +156,84 @@ fn test_parse_erlang_pid_selector() {
     );
 }
 
+#[test]
+fn test_parse_structured_metadata_selectors() {
+    let query = parse(r#"{vhost="vhost1", user="user2", mfa="rabbit_networking:boot/0"}"#).unwrap();
+    let selector = query.selector.as_ref().unwrap();
+    assert_eq!(selector.matchers[0].field, Field::Vhost);
+    assert_eq!(
+        selector.matchers[0].value,
+        Value::String("vhost1".to_string())
+    );
+    assert_eq!(selector.matchers[1].field, Field::User);
+    assert_eq!(selector.matchers[2].field, Field::Mfa);
+}
+
+#[test]
+fn test_parse_entity_fields() {
+    for (input, expected) in [
+        (r#"queue == "orders""#, Field::Queue),
+        (r#"stream == "events""#, Field::Stream),
+        (r#"exchange =~ "^amq""#, Field::Exchange),
+    ] {
+        let query = parse(input).unwrap();
+        if let FilterExpr::Comparison(matcher) = query.filter.as_ref().unwrap() {
+            assert_eq!(matcher.field, expected, "for query {}", input);
+        } else {
+            panic!("Expected Comparison filter for query {}", input);
+        }
+    }
+
+    let query = parse(r#"{queue="orders", vhost="/"}"#).unwrap();
+    let selector = query.selector.as_ref().unwrap();
+    assert_eq!(selector.matchers[0].field, Field::Queue);
+    assert_eq!(selector.matchers[1].field, Field::Vhost);
+}
+
+#[test]
+fn test_parse_pattern_field() {
+    for input in [
+        r#"pattern == "Closing all channels from connection <*>""#,
+        r#"template =~ "^accepting""#,
+    ] {
+        let query = parse(input).unwrap();
+        if let FilterExpr::Comparison(matcher) = query.filter.as_ref().unwrap() {
+            assert_eq!(matcher.field, Field::Pattern, "for query {}", input);
+        } else {
+            panic!("Expected Comparison filter for query {}", input);
+        }
+    }
+}
+
+#[test]
+fn test_parse_structured_metadata_field_aliases() {
+    for (input, expected) in [
+        (r#"virtual_host == "/""#, Field::Vhost),
+        (r#"username == "guest""#, Field::User),
+        (r#"source_file == "rabbit.erl""#, Field::File),
+        (r#"conn contains "5672""#, Field::Connection),
+        (r#"domain == "rabbitmq""#, Field::Domain),
+    ] {
+        let query = parse(input).unwrap();
+        if let FilterExpr::Comparison(matcher) = query.filter.as_ref().unwrap() {
+            assert_eq!(matcher.field, expected, "for query {}", input);
+        } else {
+            panic!("Expected Comparison filter for query {}", input);
+        }
+    }
+}
+
+#[test]
+fn test_parse_source_line_comparison() {
+    let query = parse("line > 100").unwrap();
+    if let FilterExpr::Comparison(matcher) = query.filter.as_ref().unwrap() {
+        assert_eq!(matcher.field, Field::Line);
+        assert_eq!(matcher.value, Value::Integer(100));
+    } else {
+        panic!("Expected Comparison filter");
+    }
+}
+
 #[test]
 fn test_parse_message_contains() {
     let query = parse(r#"message contains "accepting AMQP connection""#).unwrap();
@@ -208,6 +325,35 @@ fn test_parse_time_range_days() {
     assert_eq!(time_range.unit, DurationUnit::Days);
 }
 
+#[test]
+fn test_parse_time_range_without_anchor() {
+    let query = parse("@1h").unwrap();
+    assert!(query.time_anchor.is_none());
+}
+
+#[test]
+fn test_parse_time_range_from_end() {
+    let query = parse("@1h from end").unwrap();
+    assert_eq!(query.time_range.as_ref().unwrap().value, 1);
+    assert_eq!(query.time_anchor, Some(RangeAnchor::LogEnd));
+}
+
+#[test]
+fn test_parse_time_range_from_now() {
+    let query = parse(r#"@30m from now {severity="error"}"#).unwrap();
+    assert_eq!(query.time_anchor, Some(RangeAnchor::Now));
+    assert!(query.selector.is_some());
+}
+
+#[test]
+fn test_parse_time_range_from_timestamp() {
+    let query = parse(r#"@2h from "2025-10-27 18:00:00""#).unwrap();
+    assert_eq!(
+        query.time_anchor,
+        Some(RangeAnchor::At("2025-10-27 18:00:00".to_string()))
+    );
+}
+
 #[test]
 fn test_parse_time_range_weeks() {
     let query = parse("@2w").unwrap();
@@ -392,6 +538,30 @@ fn test_parse_unlabelled() {
     assert!(matches!(filter, FilterExpr::Unlabelled));
 }
 
+#[test]
+fn test_parse_search() {
+    let query = parse(r#"search "connection closed" and severity == "error""#).unwrap();
+    let filter = query.filter.as_ref().unwrap();
+    match filter {
+        FilterExpr::And(left, _) => {
+            assert_eq!(**left, FilterExpr::Search("connection closed".to_string()));
+        }
+        _ => panic!("Expected And filter"),
+    }
+
+    let query = parse(r#"{node="rabbit@a"} | where SEARCH 'handsh*'"#).unwrap();
+    assert_eq!(
+        query.pipeline[0],
+        PipelineStage::Where(FilterExpr::Search("handsh*".to_string()))
+    );
+}
+
+#[test]
+fn test_parse_search_requires_quoted_text() {
+    assert!(parse("search timeout").is_err());
+    assert!(parse(r#"search"timeout""#).is_err());
+}
+
 #[test]
 fn test_parse_and_filter() {
     let query = parse(r#"severity == "error" and subsystem == "connections""#).unwrap();
@@ -446,7 +616,9 @@ fn test_parse_pipeline_tail() {
 #[test]
 fn test_parse_pipeline_sort_timestamp_desc() {
     let query = parse("* | sort timestamp desc").unwrap();
-    if let PipelineStage::Sort(spec) = &query.pipeline[0] {
+    if let PipelineStage::Sort(specs) = &query.pipeline[0] {
+        assert_eq!(specs.len(), 1);
+        let spec = &specs[0];
         assert_eq!(spec.field, Field::Timestamp);
         assert_eq!(spec.direction, SortDirection::Desc);
     } else {
@@ -457,7 +629,9 @@ fn test_parse_pipeline_sort_timestamp_desc() {
 #[test]
 fn test_parse_pipeline_sort_severity_asc() {
     let query = parse("* | sort severity asc").unwrap();
-    if let PipelineStage::Sort(spec) = &query.pipeline[0] {
+    if let PipelineStage::Sort(specs) = &query.pipeline[0] {
+        assert_eq!(specs.len(), 1);
+        let spec = &specs[0];
         assert_eq!(spec.field, Field::Severity);
         assert_eq!(spec.direction, SortDirection::Asc);
     } else {
@@ -582,6 +756,20 @@ fn test_parse_count_by_subsystem() {
     ));
 }
 
+#[test]
+fn test_parse_context() {
+    let query = parse(":errors | context 20").unwrap();
+    assert_eq!(query.pipeline, vec![PipelineStage::Context(20, None)]);
+
+    let query = parse("* | head 5 | CONTEXT 3 by pid").unwrap();
+    assert_eq!(
+        query.pipeline[1],
+        PipelineStage::Context(3, Some(Field::ErlangPid))
+    );
+
+    assert!(parse("* | context").is_err());
+}
+
 #[test]
 fn test_parse_distinct() {
     let query = parse("* | distinct severity").unwrap();
@@ -906,7 +1094,9 @@ fn test_parse_last_alias_for_tail() {
 #[test]
 fn test_parse_order_by_alias_for_sort() {
     let query = parse("* | order by timestamp desc").unwrap();
-    if let PipelineStage::Sort(spec) = &query.pipeline[0] {
+    if let PipelineStage::Sort(specs) = &query.pipeline[0] {
+        assert_eq!(specs.len(), 1);
+        let spec = &specs[0];
         assert_eq!(spec.field, Field::Timestamp);
         assert_eq!(spec.direction, SortDirection::Desc);
     } else {
@@ -917,7 +1107,9 @@ fn test_parse_order_by_alias_for_sort() {
 #[test]
 fn test_parse_order_by_case_insensitive() {
     let query = parse("* | ORDER BY severity ASC").unwrap();
-    if let PipelineStage::Sort(spec) = &query.pipeline[0] {
+    if let PipelineStage::Sort(specs) = &query.pipeline[0] {
+        assert_eq!(specs.len(), 1);
+        let spec = &specs[0];
         assert_eq!(spec.field, Field::Severity);
         assert_eq!(spec.direction, SortDirection::Asc);
     } else {
@@ -925,6 +1117,25 @@ fn test_parse_order_by_case_insensitive() {
     }
 }
 
+#[test]
+fn test_parse_sort_by_several_fields() {
+    let query = parse("* | sort node, severity desc,timestamp | limit 10").unwrap();
+    assert_eq!(query.pipeline.len(), 2);
+    if let PipelineStage::Sort(specs) = &query.pipeline[0] {
+        let keys: Vec<_> = specs.iter().map(|s| (s.field, s.direction)).collect();
+        assert_eq!(
+            keys,
+            vec![
+                (Field::Node, SortDirection::Asc),
+                (Field::Severity, SortDirection::Desc),
+                (Field::Timestamp, SortDirection::Asc),
+            ]
+        );
+    } else {
+        panic!("Expected Sort stage");
+    }
+}
+
 #[test]
 fn test_parse_hashtag_label() {
     let query = parse("#connections").unwrap();
@@ -72,6 +72,7 @@ fn test_to_filter_expr_returns_valid_ast() {
             | FilterExpr::LabelAll(_)
             | FilterExpr::SubsystemAny(_)
             | FilterExpr::Preset(_)
+            | FilterExpr::Search(_)
             | FilterExpr::HasDocUrl
             | FilterExpr::HasResolutionUrl
             | FilterExpr::Unlabelled => {}
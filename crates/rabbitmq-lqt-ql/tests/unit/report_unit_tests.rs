@@ -0,0 +1,265 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use chrono::{Duration, TimeZone, Utc};
+use rabbitmq_lqt_lib::constants::ISSUE_14181;
+use rabbitmq_lqt_lib::rel_db::file_metadata::Model as FileMetadataModel;
+use rabbitmq_lqt_lib::{
+    DatabaseConnection, LogEntryLabels, NodeLogEntry, ParsedLogEntry, Severity, StructuredMetadata,
+    create_database,
+};
+use rabbitmq_lqt_ql::{Check, Finding, FindingSeverity, Report, to_query_context};
+use tempfile::TempDir;
+
+fn entry(id: i64, message: &str, labels: LogEntryLabels) -> ParsedLogEntry {
+    ParsedLogEntry {
+        sequence_id: 0,
+        explicit_id: Some(id),
+        timestamp: Utc.with_ymd_and_hms(2025, 10, 27, 18, 0, 0).unwrap() + Duration::seconds(id),
+        severity: Severity::Error,
+        process_id: "<0.208.0>".to_string(),
+        message: message.to_string(),
+        message_lowercased: message.to_lowercase(),
+        subsystem_id: None,
+        labels,
+        resolution_or_discussion_url_id: None,
+        doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
+    }
+}
+
+fn database_with(
+    entries_of_nodes: &[(&str, Vec<ParsedLogEntry>)],
+) -> (TempDir, DatabaseConnection) {
+    let temp_dir = TempDir::new().unwrap();
+    let db = create_database(&temp_dir.path().join("report.db")).unwrap();
+    for (node, entries) in entries_of_nodes {
+        NodeLogEntry::insert_parsed_entries(&db, entries, node).unwrap();
+    }
+    (temp_dir, db)
+}
+
+fn find(check: Check, db: &DatabaseConnection) -> Vec<Finding> {
+    let ctx = to_query_context(&check.query_string().unwrap()).unwrap();
+    check.find(db, &ctx).unwrap()
+}
+
+fn file_of(node: &str, rabbitmq_version: &str, erlang_version: &str) -> FileMetadataModel {
+    FileMetadataModel {
+        file_path: format!("{}.log", node),
+        rabbitmq_versions: vec![rabbitmq_version.to_string()],
+        erlang_versions: vec![erlang_version.to_string()],
+        tls_library: None,
+        oldest_entry_at: None,
+        most_recent_entry_at: None,
+        total_lines: 0,
+        total_entries: 0,
+        nodes: vec![node.to_string()],
+        subsystems: vec![],
+        labels: vec![],
+        enabled_plugins: vec![],
+    }
+}
+
+#[test]
+fn test_all_check_queries_compile() {
+    for check in Check::all() {
+        if let Some(query) = check.query_string() {
+            assert!(
+                to_query_context(&query).is_ok(),
+                "query of check {} must compile",
+                check
+            );
+        }
+    }
+}
+
+#[test]
+fn test_crash_loops_require_repeated_crashes() {
+    let crash = |id, pid: &str| {
+        entry(
+            id,
+            &format!("** Generic server {} terminating", pid),
+            LogEntryLabels::ERL_PROCESS_CRASH,
+        )
+    };
+
+    let (_dir, db) = database_with(&[(
+        "rabbit@node1",
+        vec![crash(1, "<0.1.0>"), crash(2, "<0.2.0>")],
+    )]);
+    assert!(find(Check::CrashLoops, &db).is_empty());
+
+    let (_dir, db) = database_with(&[
+        (
+            "rabbit@node1",
+            vec![
+                crash(1, "<0.1.0>"),
+                crash(2, "<0.2.0>"),
+                crash(3, "<0.3.0>"),
+            ],
+        ),
+        ("rabbit@node2", vec![crash(4, "<0.4.0>")]),
+    ]);
+    let findings = find(Check::CrashLoops, &db);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].severity, FindingSeverity::Critical);
+    assert_eq!(findings[0].entry_count, 3);
+    assert_eq!(findings[0].evidence, vec![1, 2, 3]);
+    assert_eq!(findings[0].nodes, vec!["rabbit@node1".to_string()]);
+}
+
+#[test]
+fn test_repeated_elections_count_leader_changes_per_node() {
+    let election = |id| {
+        entry(
+            id,
+            "queue 'orders' in vhost '/': candidate -> leader in term: 3",
+            LogEntryLabels::ELECTIONS,
+        )
+    };
+
+    let (_dir, db) = database_with(&[
+        (
+            "rabbit@node1",
+            vec![
+                election(1),
+                election(2),
+                entry(
+                    3,
+                    "RabbitMQ metadata store: 'rabbit@node1' is the new leader",
+                    LogEntryLabels::RAFT,
+                ),
+            ],
+        ),
+        (
+            "rabbit@node2",
+            vec![
+                election(4),
+                entry(5, "pre_vote election called", LogEntryLabels::ELECTIONS),
+                entry(6, "pre_vote election called", LogEntryLabels::ELECTIONS),
+            ],
+        ),
+    ]);
+    let findings = find(Check::RepeatedElections, &db);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].nodes, vec!["rabbit@node1".to_string()]);
+    assert_eq!(findings[0].entry_count, 3);
+}
+
+#[test]
+fn test_known_issues_link_to_the_issue() {
+    let mut known = entry(7, "Khepri projection failed", LogEntryLabels::KHEPRI);
+    known.resolution_or_discussion_url_id = Some(ISSUE_14181);
+    let (_dir, db) = database_with(&[("rabbit@node1", vec![known])]);
+
+    let findings = find(Check::KnownIssues, &db);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(
+        findings[0].urls,
+        vec!["https://github.com/rabbitmq/rabbitmq-server/issues/14181".to_string()]
+    );
+    assert_eq!(findings[0].evidence, vec![7]);
+}
+
+#[test]
+fn test_alarms_link_to_the_alarms_guide() {
+    let (_dir, db) = database_with(&[]);
+    assert!(find(Check::Alarms, &db).is_empty());
+
+    let alarm = entry(
+        1,
+        "memory resource limit alarm set on node 'rabbit@node1'.",
+        LogEntryLabels::LIMITS,
+    );
+    let (_dir, db) = database_with(&[("rabbit@node1", vec![alarm])]);
+    let findings = find(Check::Alarms, &db);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(
+        findings[0].urls,
+        vec!["https://www.rabbitmq.com/docs/alarms"]
+    );
+}
+
+#[test]
+fn test_findings_count_entries_beyond_the_query_limit() {
+    let deprecated: Vec<ParsedLogEntry> = (1..=10_050)
+        .map(|id| {
+            entry(
+                id,
+                "Deprecated features: `transient_nonexcl_queues`",
+                LogEntryLabels::DEPRECATED_FEATURES,
+            )
+        })
+        .collect();
+    let (_dir, db) = database_with(&[("rabbit@node1", deprecated)]);
+
+    let findings = find(Check::DeprecatedFeatures, &db);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].entry_count, 10_050);
+    assert_eq!(findings[0].evidence, (1..=10).collect::<Vec<i64>>());
+}
+
+#[test]
+fn test_version_skew() {
+    let files = vec![
+        file_of("rabbit@node1", "4.1.4", "27.3.4"),
+        file_of("rabbit@node2", "4.2.0", "27.3.4"),
+    ];
+    let findings = Finding::version_skew(&files);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].check, Check::VersionSkew);
+    assert!(findings[0].summary.contains("rabbit@node1: 4.1.4"));
+    assert!(findings[0].summary.contains("rabbit@node2: 4.2.0"));
+
+    let files = vec![
+        file_of("rabbit@node1", "4.2.0", "27.3.4"),
+        file_of("rabbit@node2", "4.2.0", "27.3.4"),
+    ];
+    assert!(Finding::version_skew(&files).is_empty());
+}
+
+#[test]
+fn test_findings_are_ranked_by_severity_then_entry_count() {
+    let mut entries: Vec<ParsedLogEntry> = (1..=20)
+        .map(|id| {
+            entry(
+                id,
+                "Deprecated features: `transient_nonexcl_queues`",
+                LogEntryLabels::DEPRECATED_FEATURES,
+            )
+        })
+        .collect();
+    entries.push(entry(
+        21,
+        "disk resource limit alarm set on node 'rabbit@node1'.",
+        LogEntryLabels::LIMITS,
+    ));
+    let (_dir, db) = database_with(&[("rabbit@node1", entries)]);
+
+    let mut findings = find(Check::DeprecatedFeatures, &db);
+    findings.extend(find(Check::Alarms, &db));
+    findings.extend(Finding::version_skew(&[
+        file_of("rabbit@node1", "4.1.4", "27.3.4"),
+        file_of("rabbit@node2", "4.2.0", "27.3.4"),
+    ]));
+
+    let report = Report::ranked(findings);
+    let checks: Vec<Check> = report.findings.iter().map(|f| f.check).collect();
+    assert_eq!(
+        checks,
+        vec![Check::Alarms, Check::VersionSkew, Check::DeprecatedFeatures]
+    );
+    assert_eq!(report.findings[2].evidence.len(), 10);
+}
@@ -2,10 +2,12 @@ import { useState, useEffect, useCallback } from 'react'
 import { useQuery } from '@tanstack/react-query'
 import { FilterPanel } from './components/FilterPanel'
 import { LogTable } from './components/LogTable'
+import { EntryContextPanel } from './components/EntryContextPanel'
 import { FileMetadataTab } from './components/FileMetadataTab'
 import { MetadataHeader } from './components/MetadataHeader'
 import { PresetFilterPanel } from './components/PresetFilterPanel'
 import { QLPanel } from './components/QLPanel'
+import { ResultTable } from './components/ResultTable'
 import {
   queryLogs,
   queryLogsByPreset,
@@ -13,6 +15,7 @@ import {
   getMetadata,
   getStats,
   getFileMetadata,
+  LogEntry,
   QueryParams,
   PresetQueryParams,
 } from './api/client'
@@ -20,6 +23,9 @@ import { formatDate } from './utils/dateFormat'
 
 type Tab = 'filters' | 'ql' | 'metadata' | 'preset_errors_or_crashes'
 
+// How often to refresh what is shown while new log entries keep arriving
+const LIVE_REFRESH_INTERVAL_MS = 2000
+
 function tabFromURL(): Tab {
   const params = new URLSearchParams(window.location.search)
   const tab = params.get('tab')
@@ -51,9 +57,17 @@ function filtersFromURL(): QueryParams {
   if (params.has('since_time')) filters.since_time = params.get('since_time')!
   if (params.has('to_time')) filters.to_time = params.get('to_time')!
   if (params.has('severity')) filters.severity = params.get('severity')!
+  if (params.has('min_severity')) filters.min_severity = params.get('min_severity')!
   if (params.has('erlang_pid')) filters.erlang_pid = params.get('erlang_pid')!
   if (params.has('node')) filters.node = params.get('node')!
   if (params.has('subsystem')) filters.subsystem = params.get('subsystem')!
+  if (params.has('domain')) filters.domain = params.get('domain')!
+  if (params.has('mfa')) filters.mfa = params.get('mfa')!
+  if (params.has('file')) filters.file = params.get('file')!
+  if (params.has('connection')) filters.connection = params.get('connection')!
+  if (params.has('vhost')) filters.vhost = params.get('vhost')!
+  if (params.has('user')) filters.user = params.get('user')!
+  if (params.has('sort')) filters.sort = params.get('sort')!
   if (params.has('labels')) filters.labels = params.get('labels')!
   if (params.has('matching_all_labels')) filters.matching_all_labels = params.get('matching_all_labels') === 'true'
   if (params.has('has_resolution_or_discussion_url')) filters.has_resolution_or_discussion_url = params.get('has_resolution_or_discussion_url') === 'true'
@@ -85,9 +99,17 @@ function stateToURL(filters: QueryParams, presetFilters: PresetQueryParams, qlSt
     if (filters.since_time) params.set('since_time', filters.since_time)
     if (filters.to_time) params.set('to_time', filters.to_time)
     if (filters.severity) params.set('severity', filters.severity)
+    if (filters.min_severity) params.set('min_severity', filters.min_severity)
     if (filters.erlang_pid) params.set('erlang_pid', filters.erlang_pid)
     if (filters.node) params.set('node', filters.node)
     if (filters.subsystem) params.set('subsystem', filters.subsystem)
+    if (filters.domain) params.set('domain', filters.domain)
+    if (filters.mfa) params.set('mfa', filters.mfa)
+    if (filters.file) params.set('file', filters.file)
+    if (filters.connection) params.set('connection', filters.connection)
+    if (filters.vhost) params.set('vhost', filters.vhost)
+    if (filters.user) params.set('user', filters.user)
+    if (filters.sort) params.set('sort', filters.sort)
     if (filters.labels) params.set('labels', filters.labels)
     if (filters.matching_all_labels) params.set('matching_all_labels', 'true')
     if (filters.has_resolution_or_discussion_url) params.set('has_resolution_or_discussion_url', 'true')
@@ -112,6 +134,7 @@ function App() {
   const [activeTab, setActiveTab] = useState<Tab>(() => tabFromURL())
   const [qlQueryTrigger, setQLQueryTrigger] = useState(0)
   const [filterPanelCollapsed, setFilterPanelCollapsed] = useState(false)
+  const [contextEntry, setContextEntry] = useState<LogEntry | null>(null)
 
   useEffect(() => {
     const handlePopState = () => {
@@ -138,19 +161,23 @@ function App() {
     }))
   }
 
-  const { data: metadata } = useQuery({
-    queryKey: ['metadata'],
-    queryFn: getMetadata,
-  })
-
   const { data: stats } = useQuery({
     queryKey: ['stats'],
     queryFn: getStats,
+    refetchInterval: (query) => (query.state.data?.live ? LIVE_REFRESH_INTERVAL_MS : false),
+  })
+  const refetchInterval = stats?.live ? LIVE_REFRESH_INTERVAL_MS : false
+
+  const { data: metadata } = useQuery({
+    queryKey: ['metadata'],
+    queryFn: getMetadata,
+    refetchInterval,
   })
 
   const { data: fileMetadata } = useQuery({
     queryKey: ['fileMetadata'],
     queryFn: getFileMetadata,
+    refetchInterval,
   })
 
   const {
@@ -161,6 +188,7 @@ function App() {
     queryKey: ['logs', filters],
     queryFn: () => queryLogs(filters),
     enabled: activeTab === 'filters',
+    refetchInterval,
   })
 
   const {
@@ -171,6 +199,7 @@ function App() {
     queryKey: ['preset_errors_or_crashes', presetFilters],
     queryFn: () => queryLogsByPreset('errors_or_crashes', presetFilters),
     enabled: activeTab === 'preset_errors_or_crashes',
+    refetchInterval,
   })
 
   const {
@@ -181,6 +210,7 @@ function App() {
     queryKey: ['ql', qlState.query, qlState.limit, qlQueryTrigger],
     queryFn: () => queryLogsByQL({ query: qlState.query, limit: qlState.limit }),
     enabled: activeTab === 'ql' && qlState.query.trim().length > 0 && qlQueryTrigger > 0,
+    refetchInterval,
   })
 
   const handleRunQLQuery = useCallback(() => {
@@ -268,7 +298,10 @@ function App() {
                 </div>
               </div>
               <div className="text-right">
-                <div className="text-sm text-gray-500">Total Entries</div>
+                <div className="text-sm text-gray-500">
+                  {stats.live && <span className="text-green-600 font-medium mr-2">● Live</span>}
+                  Total Entries
+                </div>
                 <div className="text-2xl font-bold text-gray-900 mt-1">
                   {stats.total_entries.toLocaleString()}
                 </div>
@@ -334,7 +367,13 @@ function App() {
                 </div>
               </div>
 
-              <LogTable data={logsData?.entries || []} onPidFilterClick={handlePidFilterClick} />
+              <LogTable
+                data={logsData?.entries || []}
+                onPidFilterClick={handlePidFilterClick}
+                sort={filters.sort}
+                onSortChange={(sort) => setFilters({ ...filters, sort })}
+                onShowContext={setContextEntry}
+              />
             </div>
           </div>
         )}
@@ -379,6 +418,11 @@ function App() {
                     </span>
                   ) : qlError ? (
                     <span className="text-red-600">Error: {(qlError as Error).message}</span>
+                  ) : qlLogsData?.columns ? (
+                    <span>
+                      Showing <span className="font-semibold">{qlLogsData.total}</span>{' '}
+                      {qlLogsData.total === 1 ? 'row' : 'rows'}
+                    </span>
                   ) : qlLogsData ? (
                     <span>
                       Showing <span className="font-semibold">{qlLogsData.total}</span> matching{' '}
@@ -391,7 +435,15 @@ function App() {
               </div>
             </div>
 
-            <LogTable data={qlLogsData?.entries || []} onPidFilterClick={handlePidFilterClick} />
+            {qlLogsData?.columns ? (
+              <ResultTable columns={qlLogsData.columns} rows={qlLogsData.rows || []} />
+            ) : (
+              <LogTable
+                data={qlLogsData?.entries || []}
+                onPidFilterClick={handlePidFilterClick}
+                onShowContext={setContextEntry}
+              />
+            )}
           </div>
         )}
 
@@ -447,13 +499,25 @@ function App() {
                 </div>
               </div>
 
-              <LogTable data={presetLogsData?.entries || []} onPidFilterClick={handlePidFilterClick} />
+              <LogTable
+                data={presetLogsData?.entries || []}
+                onPidFilterClick={handlePidFilterClick}
+                onShowContext={setContextEntry}
+              />
             </div>
           </div>
         )}
 
         {activeTab === 'metadata' && <FileMetadataTab fileMetadata={fileMetadata} />}
       </main>
+
+      {contextEntry && (
+        <EntryContextPanel
+          entry={contextEntry}
+          onClose={() => setContextEntry(null)}
+          onShowContext={setContextEntry}
+        />
+      )}
     </div>
   )
 }
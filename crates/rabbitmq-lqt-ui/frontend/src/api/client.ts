@@ -9,6 +9,13 @@ export interface LogEntry {
   labels: Record<string, boolean>
   doc_url: string | null
   resolution_or_discussion_url: string | null
+  domain: string | null
+  mfa: string | null
+  file: string | null
+  line: number | null
+  connection: string | null
+  vhost: string | null
+  user: string | null
 }
 
 export interface LogQueryResponse {
@@ -29,6 +36,8 @@ export interface StatsResponse {
     node: string
     count: number
   }>
+  // Set when the server keeps ingesting new log entries (web serve --watch-log-dir-path)
+  live: boolean
 }
 
 export interface FileMetadataResponse {
@@ -50,9 +59,18 @@ export interface QueryParams {
   since_time?: string
   to_time?: string
   severity?: string
+  min_severity?: string
   erlang_pid?: string
   node?: string
   subsystem?: string
+  domain?: string
+  mfa?: string
+  file?: string
+  connection?: string
+  vhost?: string
+  user?: string
+  search?: string
+  sort?: string
   labels?: string
   matching_all_labels?: boolean
   limit?: number
@@ -136,12 +154,44 @@ export async function queryLogsByPreset(
   return response.json()
 }
 
+export interface ContextParams {
+  before?: number
+  after?: number
+  same_node?: boolean
+  same_pid?: boolean
+}
+
+export async function getLogContext(id: number, params: ContextParams = {}): Promise<LogQueryResponse> {
+  const queryString = new URLSearchParams(
+    Object.entries(params)
+      .filter(([_, v]) => v !== undefined && v !== null && v !== '')
+      .map(([k, v]) => [k, String(v)])
+  ).toString()
+
+  const url = `${API_BASE}/logs/${id}/context${queryString ? `?${queryString}` : ''}`
+  const response = await fetch(url)
+
+  if (!response.ok) {
+    const errorBody = await response.json().catch(() => ({}))
+    throw new Error(errorBody.error || `Failed to fetch log context: ${response.statusText}`)
+  }
+
+  return response.json()
+}
+
 export interface QLQueryParams {
   query: string
   limit?: number
 }
 
-export async function queryLogsByQL(params: QLQueryParams): Promise<LogQueryResponse> {
+export type TabularCell = string | number | string[] | null
+
+export interface QLQueryResponse extends LogQueryResponse {
+  columns?: string[]
+  rows?: TabularCell[][]
+}
+
+export async function queryLogsByQL(params: QLQueryParams): Promise<QLQueryResponse> {
   const queryString = new URLSearchParams(
     Object.entries(params)
       .filter(([_, v]) => v !== undefined && v !== null && v !== '')
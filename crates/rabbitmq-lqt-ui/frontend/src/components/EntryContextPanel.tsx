@@ -0,0 +1,78 @@
+import { useState } from 'react'
+import { useQuery } from '@tanstack/react-query'
+import { getLogContext, LogEntry } from '../api/client'
+import { LogTable } from './LogTable'
+
+type Scope = 'all' | 'node' | 'pid'
+
+interface EntryContextPanelProps {
+  entry: LogEntry
+  onClose: () => void
+  onShowContext: (entry: LogEntry) => void
+}
+
+const windowSizes = [10, 20, 50, 100]
+
+export function EntryContextPanel({ entry, onClose, onShowContext }: EntryContextPanelProps) {
+  const [size, setSize] = useState(20)
+  const [scope, setScope] = useState<Scope>('all')
+
+  const { data, isLoading, error } = useQuery({
+    queryKey: ['context', entry.id, size, scope],
+    queryFn: () =>
+      getLogContext(entry.id, {
+        before: size,
+        after: size,
+        same_node: scope === 'node' || undefined,
+        same_pid: scope === 'pid' || undefined,
+      }),
+  })
+
+  return (
+    <div className="fixed inset-0 z-50 bg-black/40 flex items-start justify-center p-6 overflow-y-auto">
+      <div className="bg-white rounded-lg shadow-xl w-full max-w-[95vw]">
+        <div className="p-4 border-b border-gray-200 flex flex-wrap items-center justify-between gap-3 bg-gray-50 rounded-t-lg">
+          <div className="text-sm text-gray-700">
+            Entries around <span className="font-semibold font-mono">#{entry.id}</span> on{' '}
+            <span className="font-mono">{entry.node}</span>
+            {isLoading && <span className="ml-2 text-gray-500">Loading...</span>}
+            {error && <span className="ml-2 text-red-600">Error: {(error as Error).message}</span>}
+          </div>
+          <div className="flex items-center gap-2">
+            <select
+              value={size}
+              onChange={(e) => setSize(Number(e.target.value))}
+              className="px-3 py-1.5 text-sm border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              {windowSizes.map((n) => (
+                <option key={n} value={n}>
+                  {n} before and after
+                </option>
+              ))}
+            </select>
+            <select
+              value={scope}
+              onChange={(e) => setScope(e.target.value as Scope)}
+              className="px-3 py-1.5 text-sm border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              <option value="all">All nodes</option>
+              <option value="node">Same node</option>
+              <option value="pid">Same process</option>
+            </select>
+            <button
+              onClick={onClose}
+              className="text-sm px-3 py-1.5 bg-white border border-gray-300 hover:bg-gray-50 rounded font-medium"
+            >
+              Close
+            </button>
+          </div>
+        </div>
+        <LogTable
+          data={data?.entries || []}
+          highlightedId={entry.id}
+          onShowContext={onShowContext}
+        />
+      </div>
+    </div>
+  )
+}
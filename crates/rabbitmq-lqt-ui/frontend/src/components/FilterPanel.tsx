@@ -84,6 +84,21 @@ export function FilterPanel({ metadata, filters, onFilterChange, collapsed, onTo
       </div>
 
       <div className="space-y-6">
+        <div className="border-b border-gray-200 pb-4">
+          <h3 className="text-sm font-semibold text-gray-900 mb-3">Search</h3>
+          <input
+            type="search"
+            value={filters.search || ''}
+            onChange={(e) => updateFilter('search', e.target.value || undefined)}
+            placeholder={'timeout "connection closed" handsh*'}
+            aria-label="Search messages"
+            className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+          />
+          <p className="mt-1 text-xs text-gray-500">
+            Messages with all words. Quote phrases, end a word with * to match a prefix
+          </p>
+        </div>
+
         <div className="border-b border-gray-200 pb-4">
           <h3 className="text-sm font-semibold text-gray-900 mb-3">Well Known Issues</h3>
           <div>
@@ -194,6 +209,84 @@ export function FilterPanel({ metadata, filters, onFilterChange, collapsed, onTo
           </div>
         </div>
 
+        <div className="border-b border-gray-200 pb-4">
+          <h3 className="text-sm font-semibold text-gray-900 mb-3">Structured Metadata</h3>
+          <div className="space-y-4">
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">
+                Virtual Host
+              </label>
+              <input
+                type="text"
+                value={filters.vhost || ''}
+                onChange={(e) => updateFilter('vhost', e.target.value || undefined)}
+                placeholder="/"
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              />
+            </div>
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">
+                User
+              </label>
+              <input
+                type="text"
+                value={filters.user || ''}
+                onChange={(e) => updateFilter('user', e.target.value || undefined)}
+                placeholder="guest"
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              />
+            </div>
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">
+                Connection
+              </label>
+              <input
+                type="text"
+                value={filters.connection || ''}
+                onChange={(e) => updateFilter('connection', e.target.value || undefined)}
+                placeholder="10.0.0.2:57586 -> 10.0.0.1:5672"
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              />
+            </div>
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">
+                Module:Function/Arity
+              </label>
+              <input
+                type="text"
+                value={filters.mfa || ''}
+                onChange={(e) => updateFilter('mfa', e.target.value || undefined)}
+                placeholder="rabbit_networking:boot/0"
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              />
+            </div>
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">
+                Source File
+              </label>
+              <input
+                type="text"
+                value={filters.file || ''}
+                onChange={(e) => updateFilter('file', e.target.value || undefined)}
+                placeholder="rabbit_networking.erl"
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              />
+            </div>
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">
+                Logger Domain
+              </label>
+              <input
+                type="text"
+                value={filters.domain || ''}
+                onChange={(e) => updateFilter('domain', e.target.value || undefined)}
+                placeholder="rabbitmq.prelaunch"
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              />
+            </div>
+          </div>
+        </div>
+
         <div className="border-b border-gray-200 pb-4">
           <h3 className="text-sm font-semibold text-gray-900 mb-3">Zoom In Further</h3>
           <div>
@@ -246,6 +339,24 @@ export function FilterPanel({ metadata, filters, onFilterChange, collapsed, onTo
               ))}
             </select>
           </div>
+
+          <div className="mt-4">
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              Minimum severity
+            </label>
+            <select
+              value={filters.min_severity || ''}
+              onChange={(e) => updateFilter('min_severity', e.target.value || undefined)}
+              className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              <option value="">Any</option>
+              {metadata?.severities.map((sev) => (
+                <option key={sev} value={sev}>
+                  {sev} and above
+                </option>
+              ))}
+            </select>
+          </div>
         </div>
 
         <div>
@@ -12,6 +12,67 @@ import { useState } from 'react'
 interface LogTableProps {
   data: LogEntry[]
   onPidFilterClick?: (pid: string) => void
+  // Comma-separated sort keys, e.g. `node,severity:desc`
+  sort?: string
+  // When provided, the headers of sortable columns can be clicked to change the sort order
+  onSortChange?: (sort: string | undefined) => void
+  // When provided, every row gets an action that shows the entries logged around it
+  onShowContext?: (entry: LogEntry) => void
+  // The row to highlight, e.g. the entry whose context is shown
+  highlightedId?: number
+}
+
+interface SortKey {
+  column: string
+  descending: boolean
+}
+
+// Columns that the server can order results by
+const sortableColumns = new Set([
+  'id',
+  'timestamp',
+  'severity',
+  'node',
+  'erlang_pid',
+  'subsystem',
+  'vhost',
+  'user',
+  'mfa',
+  'message',
+])
+
+function parseSort(sort?: string): SortKey[] {
+  if (!sort) return []
+  return sort
+    .split(',')
+    .map((key) => key.trim())
+    .filter((key) => key.length > 0)
+    .map((key) => {
+      const [column, direction] = key.split(':')
+      return { column, descending: direction === 'desc' }
+    })
+}
+
+function formatSort(keys: SortKey[]): string | undefined {
+  if (keys.length === 0) return undefined
+  return keys.map((key) => `${key.column}:${key.descending ? 'desc' : 'asc'}`).join(',')
+}
+
+// A click cycles a column through ascending, descending and unsorted.
+// Without `additive` (Shift), the column replaces the other sort keys.
+function nextSort(keys: SortKey[], column: string, additive: boolean): SortKey[] {
+  const existing = keys.find((key) => key.column === column)
+  const updated: SortKey | null = !existing
+    ? { column, descending: false }
+    : !existing.descending
+      ? { column, descending: true }
+      : null
+
+  if (!additive) return updated ? [updated] : []
+  if (!existing) return [...keys, updated!]
+  return updated
+    ? keys.map((key) => (key.column === column ? updated : key))
+    : keys.filter((key) => key.column !== column)
 }
 
 const severityColors: Record<string, string> = {
@@ -23,13 +84,27 @@ const severityColors: Record<string, string> = {
   critical: 'text-red-800 font-bold',
 }
 
-const createColumns = (onPidFilterClick?: (pid: string) => void): ColumnDef<LogEntry>[] => [
+const createColumns = (
+  onPidFilterClick?: (pid: string) => void,
+  onShowContext?: (entry: LogEntry) => void
+): ColumnDef<LogEntry>[] => [
   {
     id: 'id',
     accessorKey: 'id',
     header: 'ID',
     cell: (info) => (
-      <div className="text-sm text-gray-600 font-mono">{info.getValue() as number}</div>
+      <div className="flex flex-col items-start gap-1">
+        <span className="text-sm text-gray-600 font-mono">{info.getValue() as number}</span>
+        {onShowContext && (
+          <button
+            onClick={() => onShowContext(info.row.original)}
+            title="Show surrounding entries"
+            className="text-xs text-blue-600 hover:text-blue-800 hover:underline whitespace-nowrap"
+          >
+            Context
+          </button>
+        )}
+      </div>
     ),
     size: 45,
   },
@@ -110,6 +185,48 @@ const createColumns = (onPidFilterClick?: (pid: string) => void): ColumnDef<LogE
     },
     size: 110,
   },
+  {
+    id: 'vhost',
+    accessorKey: 'vhost',
+    header: 'Virtual Host',
+    cell: (info) => {
+      const value = info.getValue() as string | null
+      return (
+        <div className="text-sm text-gray-700 font-mono truncate">
+          {value || '-'}
+        </div>
+      )
+    },
+    size: 100,
+  },
+  {
+    id: 'user',
+    accessorKey: 'user',
+    header: 'User',
+    cell: (info) => {
+      const value = info.getValue() as string | null
+      return (
+        <div className="text-sm text-gray-700 font-mono truncate">
+          {value || '-'}
+        </div>
+      )
+    },
+    size: 90,
+  },
+  {
+    id: 'mfa',
+    accessorKey: 'mfa',
+    header: 'MFA',
+    cell: (info) => {
+      const value = info.getValue() as string | null
+      return (
+        <div className="text-sm text-gray-700 font-mono truncate">
+          {value || '-'}
+        </div>
+      )
+    },
+    size: 160,
+  },
   {
     id: 'message',
     accessorKey: 'message',
@@ -196,13 +313,24 @@ const createColumns = (onPidFilterClick?: (pid: string) => void): ColumnDef<LogE
 
 const defaultColumnVisibility: VisibilityState = {
   doc_url: false,
+  vhost: false,
+  user: false,
+  mfa: false,
 }
 
-export function LogTable({ data, onPidFilterClick }: LogTableProps) {
+export function LogTable({
+  data,
+  onPidFilterClick,
+  sort,
+  onSortChange,
+  onShowContext,
+  highlightedId,
+}: LogTableProps) {
   const [columnVisibility, setColumnVisibility] = useState<VisibilityState>(defaultColumnVisibility)
   const [showColumnControls, setShowColumnControls] = useState(false)
 
-  const columns = createColumns(onPidFilterClick)
+  const columns = createColumns(onPidFilterClick, onShowContext)
+  const sortKeys = parseSort(sort)
 
   const table = useReactTable({
     data,
@@ -288,23 +416,51 @@ export function LogTable({ data, onPidFilterClick }: LogTableProps) {
           <thead className="bg-gray-50">
             {table.getHeaderGroups().map((headerGroup) => (
               <tr key={headerGroup.id}>
-                {headerGroup.headers.map((header) => (
-                  <th
-                    key={header.id}
-                    className="px-4 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider"
-                    style={{ width: header.getSize() }}
-                  >
-                    {header.isPlaceholder
-                      ? null
-                      : flexRender(header.column.columnDef.header, header.getContext())}
-                  </th>
-                ))}
+                {headerGroup.headers.map((header) => {
+                  const label = header.isPlaceholder
+                    ? null
+                    : flexRender(header.column.columnDef.header, header.getContext())
+                  const sortable = onSortChange && sortableColumns.has(header.column.id)
+                  const sortIndex = sortKeys.findIndex((key) => key.column === header.column.id)
+                  const sortKey = sortIndex >= 0 ? sortKeys[sortIndex] : null
+
+                  return (
+                    <th
+                      key={header.id}
+                      className="px-4 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider"
+                      style={{ width: header.getSize() }}
+                    >
+                      {sortable ? (
+                        <button
+                          onClick={(e) =>
+                            onSortChange?.(formatSort(nextSort(sortKeys, header.column.id, e.shiftKey)))
+                          }
+                          title="Click to sort, Shift+click to add a sort key"
+                          className="flex items-center gap-1 uppercase tracking-wider hover:text-gray-900"
+                        >
+                          {label}
+                          {sortKey && (
+                            <span className="text-blue-600">
+                              {sortKey.descending ? '▼' : '▲'}
+                              {sortKeys.length > 1 && <sup>{sortIndex + 1}</sup>}
+                            </span>
+                          )}
+                        </button>
+                      ) : (
+                        label
+                      )}
+                    </th>
+                  )
+                })}
               </tr>
             ))}
           </thead>
           <tbody className="bg-white divide-y divide-gray-200">
             {table.getRowModel().rows.map((row) => (
-              <tr key={row.id} className="hover:bg-gray-50">
+              <tr
+                key={row.id}
+                className={row.original.id === highlightedId ? 'bg-yellow-50' : 'hover:bg-gray-50'}
+              >
                 {row.getVisibleCells().map((cell) => (
                   <td key={cell.id} className="px-4 py-3">
                     {flexRender(cell.column.columnDef.cell, cell.getContext())}
@@ -18,8 +18,9 @@ const EXAMPLE_QUERIES = [
   { query: '#tls', description: 'TLS-related entries' },
   { query: '#connections and -#timeouts', description: 'Connections without timeouts' },
   { query: 'message contains "timeout" | limit 50', description: 'Messages with "timeout"' },
-  { query: '@1h severity == "warning" or severity == "error"', description: 'Warnings/errors last hour' },
+  { query: '@1h severity >= warning', description: 'Warnings and more severe entries, last hour' },
   { query: ':errors_or_crashes @7d', description: 'Errors or crashes in the last week' },
+  { query: ':crashes | head 1 | context 20 by node', description: 'The first crash with what its node logged around it' },
 ]
 
 export function QLPanel({
@@ -234,7 +235,10 @@ export function QLPanel({
                 <span className="font-medium text-blue-700">2. Time ranges</span> start with <code className="bg-blue-100 px-1 rounded">@</code> —
                 like <code className="bg-blue-100 px-1 rounded">@1h</code> (last hour),{' '}
                 <code className="bg-blue-100 px-1 rounded">@24h</code> (last day), or{' '}
-                <code className="bg-blue-100 px-1 rounded">@7d</code> (last week)
+                <code className="bg-blue-100 px-1 rounded">@7d</code> (last week),
+                measured back from the most recent log entry. Use{' '}
+                <code className="bg-blue-100 px-1 rounded">@1h from now</code> for the wall clock or{' '}
+                <code className="bg-blue-100 px-1 rounded">@1h from "2025-10-27 18:00:00"</code> for a specific time
               </p>
               <p>
                 <span className="font-medium text-blue-700">3. Labels</span> start with <code className="bg-blue-100 px-1 rounded">#</code> —
@@ -244,13 +248,15 @@ export function QLPanel({
               </p>
               <p>
                 <span className="font-medium text-blue-700">4. Filters</span> match specific fields —
-                like <code className="bg-blue-100 px-1 rounded">severity == "error"</code> or{' '}
+                like <code className="bg-blue-100 px-1 rounded">severity == "error"</code>,{' '}
+                <code className="bg-blue-100 px-1 rounded">severity &gt;= warning</code> (warnings, errors and critical entries) or{' '}
                 <code className="bg-blue-100 px-1 rounded">message contains "timeout"</code>
               </p>
               <p>
                 <span className="font-medium text-blue-700">5. Pipeline</span> stages use <code className="bg-blue-100 px-1 rounded">|</code> —
-                like <code className="bg-blue-100 px-1 rounded">| limit 100</code> or{' '}
-                <code className="bg-blue-100 px-1 rounded">| sort timestamp desc</code>
+                like <code className="bg-blue-100 px-1 rounded">| limit 100</code>,{' '}
+                <code className="bg-blue-100 px-1 rounded">| sort timestamp desc</code> or{' '}
+                <code className="bg-blue-100 px-1 rounded">| context 20 by pid</code> (20 entries of the same process before and after each result)
               </p>
             </div>
           </div>
@@ -352,7 +358,7 @@ export function QLPanel({
             <div>
               <span className="font-medium text-gray-700">Duration:</span>{' '}
               {autocompleteData.duration_units.map(d => d.suffix).join(', ')}
-              {' '}(e.g., @24h, @7d)
+              {' '}(e.g., @24h, @7d, @1h from now)
             </div>
             <div>
               <span className="font-medium text-gray-700">Special:</span>{' '}
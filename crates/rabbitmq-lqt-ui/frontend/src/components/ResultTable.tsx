@@ -0,0 +1,59 @@
+import { TabularCell } from '../api/client'
+
+interface ResultTableProps {
+  columns: string[]
+  rows: TabularCell[][]
+}
+
+function formatCell(value: TabularCell): string {
+  if (value === null || value === undefined) return ''
+  if (Array.isArray(value)) return value.join(', ')
+  return String(value)
+}
+
+export function ResultTable({ columns, rows }: ResultTableProps) {
+  return (
+    <div className="bg-white shadow-sm border border-gray-200 rounded-lg overflow-hidden">
+      <div className="overflow-x-auto">
+        <table className="min-w-full divide-y divide-gray-200">
+          <thead className="bg-gray-50">
+            <tr>
+              {columns.map((column) => (
+                <th
+                  key={column}
+                  className="px-4 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider"
+                >
+                  {column}
+                </th>
+              ))}
+            </tr>
+          </thead>
+          <tbody className="bg-white divide-y divide-gray-200">
+            {rows.map((row, rowIndex) => (
+              <tr key={rowIndex} className="hover:bg-gray-50">
+                {row.map((value, cellIndex) => (
+                  <td
+                    key={cellIndex}
+                    className={
+                      columns[cellIndex] === 'count'
+                        ? 'px-4 py-3 text-sm text-gray-900 font-mono text-right'
+                        : 'px-4 py-3 text-sm text-gray-700 font-mono'
+                    }
+                  >
+                    {formatCell(value)}
+                  </td>
+                ))}
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      </div>
+
+      {rows.length === 0 && (
+        <div className="text-center py-12 text-gray-500">
+          No matching rows found.
+        </div>
+      )}
+    </div>
+  )
+}
@@ -19,31 +19,44 @@ use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use chrono::{DateTime, Utc};
-use rabbitmq_lqt_lib::constants::{doc_url_from_id, resolution_or_discussion_url_from_id};
-use rabbitmq_lqt_lib::entry_metadata::labels::LABEL_NAMES;
 use rabbitmq_lqt_lib::entry_metadata::subsystems::Subsystem;
 use rabbitmq_lqt_lib::rel_db::node_log_entry::Model;
 use rabbitmq_lqt_lib::rel_db::presets::QueryPreset;
-use rabbitmq_lqt_lib::{NodeLogEntry, QueryContext};
-use rabbitmq_lqt_ql::to_query_context;
+use rabbitmq_lqt_lib::{
+    ContextScope, ContextWindow, NodeLogEntry, QueryContext, SearchQuery, Severity, SortKey,
+    TimeAnchor, TimeReference,
+};
+use rabbitmq_lqt_ql::to_query_context_with_reference;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::Error as IoError;
 
+const DEFAULT_CONTEXT_ENTRIES: u64 = 20;
+
 #[derive(Debug, Deserialize)]
 pub struct LogQueryParams {
     since_time: Option<String>,
     to_time: Option<String>,
     severity: Option<String>,
+    min_severity: Option<String>,
     erlang_pid: Option<String>,
     node: Option<String>,
     subsystem: Option<String>,
+    domain: Option<String>,
+    mfa: Option<String>,
+    file: Option<String>,
+    connection: Option<String>,
+    vhost: Option<String>,
+    user: Option<String>,
+    search: Option<String>,
+    sort: Option<String>,
     labels: Option<String>,
     matching_all_labels: Option<bool>,
     limit: Option<u64>,
     has_resolution_or_discussion_url: Option<bool>,
     has_doc_url: Option<bool>,
     unlabelled: Option<bool>,
+    time_anchor: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -52,6 +65,18 @@ pub struct LogQueryResponse {
     total: usize,
 }
 
+/// Response of the QL endpoint. Queries with a `project`, `distinct` or `count`
+/// stage return `columns` and `rows` instead of `entries`.
+#[derive(Debug, Serialize)]
+pub struct QLQueryResponse {
+    entries: Vec<LogEntry>,
+    total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    columns: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rows: Option<Vec<Vec<serde_json::Value>>>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct LogEntry {
     id: i64,
@@ -64,28 +89,28 @@ pub struct LogEntry {
     labels: HashMap<String, bool>,
     doc_url: Option<String>,
     resolution_or_discussion_url: Option<String>,
+    domain: Option<String>,
+    mfa: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    connection: Option<String>,
+    vhost: Option<String>,
+    user: Option<String>,
 }
 
 impl From<Model> for LogEntry {
     fn from(model: Model) -> Self {
-        let label_bits = model.labels as u64;
-        let mut labels = HashMap::new();
-        for (i, label_name) in LABEL_NAMES.iter().enumerate() {
-            if label_bits & (1u64 << i) != 0 {
-                labels.insert(label_name.to_string(), true);
-            }
-        }
+        let labels: HashMap<String, bool> = model.label_names().map(|name| (name, true)).collect();
 
         let subsystem = model
             .subsystem_id
             .and_then(Subsystem::from_id)
             .map(|s| s.to_string());
 
-        let doc_url = model.doc_url_id.and_then(doc_url_from_id);
+        let doc_url = model.doc_url().map(String::from);
+        let resolution_or_discussion_url = model.resolution_or_discussion_url().map(String::from);
 
-        let resolution_or_discussion_url = model
-            .resolution_or_discussion_url_id
-            .and_then(resolution_or_discussion_url_from_id);
+        let metadata = model.metadata;
 
         Self {
             id: model.id,
@@ -96,8 +121,15 @@ impl From<Model> for LogEntry {
             message: model.message,
             subsystem,
             labels,
-            doc_url: doc_url.map(String::from),
-            resolution_or_discussion_url: resolution_or_discussion_url.map(String::from),
+            doc_url,
+            resolution_or_discussion_url,
+            domain: metadata.domain,
+            mfa: metadata.mfa,
+            file: metadata.file,
+            line: metadata.line,
+            connection: metadata.connection,
+            vhost: metadata.vhost,
+            user: metadata.user,
         }
     }
 }
@@ -112,6 +144,7 @@ impl IntoResponse for ServerError {
             ServerError::DateTimeParse(ref e) => (StatusCode::BAD_REQUEST, e.clone()),
             ServerError::InvalidPreset(ref e) => (StatusCode::BAD_REQUEST, e.clone()),
             ServerError::InvalidQuery(ref e) => (StatusCode::BAD_REQUEST, e.clone()),
+            ServerError::EntryNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
         };
 
         (status, Json(serde_json::json!({ "error": message }))).into_response()
@@ -122,12 +155,13 @@ pub async fn query_logs(
     State(state): State<AppState>,
     Query(params): Query<LogQueryParams>,
 ) -> Result<Json<LogQueryResponse>, ServerError> {
+    let reference = time_reference(&state, params.time_anchor.as_deref()).await?;
     let mut ctx = QueryContext::default();
 
     if let Some(since) = params
         .since_time
         .as_ref()
-        .map(|s| parse_datetime_flexible(s))
+        .map(|s| parse_datetime_relative_to(s, &reference))
         .transpose()?
     {
         ctx = ctx.since(since);
@@ -136,7 +170,7 @@ pub async fn query_logs(
     if let Some(to) = params
         .to_time
         .as_ref()
-        .map(|s| parse_datetime_flexible(s))
+        .map(|s| parse_datetime_relative_to(s, &reference))
         .transpose()?
     {
         ctx = ctx.to(to);
@@ -146,6 +180,10 @@ pub async fn query_logs(
         ctx = ctx.severity(sev);
     }
 
+    if let Some(sev) = params.min_severity.as_ref() {
+        ctx = ctx.min_severity(sev.parse::<Severity>().map_err(ServerError::InvalidQuery)?);
+    }
+
     if let Some(pid) = params.erlang_pid.as_ref() {
         ctx = ctx.erlang_pid(pid);
     }
@@ -158,6 +196,38 @@ pub async fn query_logs(
         ctx = ctx.subsystem(sub);
     }
 
+    if let Some(domain) = params.domain.as_ref() {
+        ctx = ctx.domain(domain);
+    }
+
+    if let Some(mfa) = params.mfa.as_ref() {
+        ctx = ctx.mfa(mfa);
+    }
+
+    if let Some(file) = params.file.as_ref() {
+        ctx = ctx.source_file(file);
+    }
+
+    if let Some(conn) = params.connection.as_ref() {
+        ctx = ctx.connection(conn);
+    }
+
+    if let Some(vhost) = params.vhost.as_ref() {
+        ctx = ctx.vhost(vhost);
+    }
+
+    if let Some(user) = params.user.as_ref() {
+        ctx = ctx.user(user);
+    }
+
+    if let Some(text) = params.search.as_ref() {
+        ctx = ctx.search(SearchQuery::parse(text));
+    }
+
+    if let Some(sort) = params.sort.as_ref() {
+        ctx = ctx.order_by(SortKey::parse_list(sort).map_err(ServerError::InvalidQuery)?);
+    }
+
     if let Some(labels_str) = params.labels.as_ref() {
         let labels: Vec<String> = labels_str
             .split(',')
@@ -206,8 +276,32 @@ pub async fn query_logs(
     Ok(Json(LogQueryResponse { entries, total }))
 }
 
-fn parse_datetime_flexible(s: &str) -> Result<DateTime<Utc>, ServerError> {
-    rabbitmq_lqt_lib::datetime::parse_datetime_flexible(s).map_err(ServerError::DateTimeParse)
+pub(crate) fn parse_datetime_relative_to(
+    s: &str,
+    reference: &TimeReference,
+) -> Result<DateTime<Utc>, ServerError> {
+    rabbitmq_lqt_lib::datetime::parse_datetime_relative_to(s, reference.reference_time())
+        .map_err(ServerError::DateTimeParse)
+}
+
+/// Resolves the time anchor for a request: the `time_anchor` parameter if provided,
+/// the server default otherwise.
+pub(crate) async fn time_reference(
+    state: &AppState,
+    requested_anchor: Option<&str>,
+) -> Result<TimeReference, ServerError> {
+    let anchor = match requested_anchor {
+        Some(s) if !s.is_empty() => s
+            .parse::<TimeAnchor>()
+            .map_err(ServerError::DateTimeParse)?,
+        _ => state.time_anchor,
+    };
+
+    let db = state.db.clone();
+    let reference = tokio::task::spawn_blocking(move || TimeReference::from_database(&db, anchor))
+        .await
+        .map_err(|e| ServerError::Io(IoError::other(format!("Task join error: {}", e))))??;
+    Ok(reference)
 }
 
 #[derive(Debug, Deserialize)]
@@ -216,6 +310,7 @@ pub struct PresetQueryParams {
     to_time: Option<String>,
     node: Option<String>,
     limit: Option<u64>,
+    time_anchor: Option<String>,
 }
 
 pub async fn query_logs_by_preset(
@@ -224,13 +319,14 @@ pub async fn query_logs_by_preset(
     Query(params): Query<PresetQueryParams>,
 ) -> Result<Json<LogQueryResponse>, ServerError> {
     let preset: QueryPreset = preset_name.parse().map_err(ServerError::InvalidPreset)?;
+    let reference = time_reference(&state, params.time_anchor.as_deref()).await?;
 
     let mut ctx = QueryContext::from(preset);
 
     if let Some(since) = params
         .since_time
         .as_ref()
-        .map(|s| parse_datetime_flexible(s))
+        .map(|s| parse_datetime_relative_to(s, &reference))
         .transpose()?
     {
         ctx = ctx.since(since);
@@ -239,7 +335,7 @@ pub async fn query_logs_by_preset(
     if let Some(to) = params
         .to_time
         .as_ref()
-        .map(|s| parse_datetime_flexible(s))
+        .map(|s| parse_datetime_relative_to(s, &reference))
         .transpose()?
     {
         ctx = ctx.to(to);
@@ -266,18 +362,64 @@ pub async fn query_logs_by_preset(
     Ok(Json(LogQueryResponse { entries, total }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ContextQueryParams {
+    before: Option<u64>,
+    after: Option<u64>,
+    same_node: Option<bool>,
+    same_pid: Option<bool>,
+}
+
+/// Returns the entry with the given ID along with the entries logged around it.
+/// `before` and `after` must not exceed [`ContextWindow::MAX_ENTRIES`].
+pub async fn query_log_context(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Query(params): Query<ContextQueryParams>,
+) -> Result<Json<LogQueryResponse>, ServerError> {
+    let scope = if params.same_pid.unwrap_or(false) {
+        ContextScope::Process
+    } else if params.same_node.unwrap_or(false) {
+        ContextScope::Node
+    } else {
+        ContextScope::All
+    };
+    let before = params.before.unwrap_or(DEFAULT_CONTEXT_ENTRIES);
+    let after = params.after.unwrap_or(DEFAULT_CONTEXT_ENTRIES);
+    if before.max(after) > ContextWindow::MAX_ENTRIES {
+        return Err(ServerError::InvalidQuery(format!(
+            "before and after must not exceed {}",
+            ContextWindow::MAX_ENTRIES
+        )));
+    }
+    let window = ContextWindow::new(before, after).scoped(scope);
+
+    let db = state.db.clone();
+    let models = tokio::task::spawn_blocking(move || NodeLogEntry::context(&db, id, &window))
+        .await
+        .map_err(|e| ServerError::Io(IoError::other(format!("Task join error: {}", e))))??
+        .ok_or(ServerError::EntryNotFound(id))?;
+
+    let total = models.len();
+    let entries: Vec<LogEntry> = models.into_iter().map(LogEntry::from).collect();
+
+    Ok(Json(LogQueryResponse { entries, total }))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct QLQueryParams {
     query: String,
     limit: Option<u64>,
+    time_anchor: Option<String>,
 }
 
 pub async fn query_logs_by_ql(
     State(state): State<AppState>,
     Query(params): Query<QLQueryParams>,
-) -> Result<Json<LogQueryResponse>, ServerError> {
-    let mut ctx =
-        to_query_context(&params.query).map_err(|e| ServerError::InvalidQuery(e.to_string()))?;
+) -> Result<Json<QLQueryResponse>, ServerError> {
+    let reference = time_reference(&state, params.time_anchor.as_deref()).await?;
+    let mut ctx = to_query_context_with_reference(&params.query, &reference)
+        .map_err(|e| ServerError::InvalidQuery(e.to_string()))?;
 
     if let Some(l) = params.limit
         && l > 0
@@ -286,6 +428,20 @@ pub async fn query_logs_by_ql(
     }
 
     let db = state.db.clone();
+
+    if ctx.has_aggregation() {
+        let result = tokio::task::spawn_blocking(move || NodeLogEntry::aggregate(&db, &ctx))
+            .await
+            .map_err(|e| ServerError::Io(IoError::other(format!("Task join error: {}", e))))??;
+
+        return Ok(Json(QLQueryResponse {
+            entries: Vec::new(),
+            total: result.len(),
+            columns: Some(result.columns),
+            rows: Some(result.rows),
+        }));
+    }
+
     let models = tokio::task::spawn_blocking(move || NodeLogEntry::query(&db, &ctx))
         .await
         .map_err(|e| ServerError::Io(IoError::other(format!("Task join error: {}", e))))??;
@@ -293,5 +449,10 @@ pub async fn query_logs_by_ql(
     let total = models.len();
     let entries: Vec<LogEntry> = models.into_iter().map(LogEntry::from).collect();
 
-    Ok(Json(LogQueryResponse { entries, total }))
+    Ok(Json(QLQueryResponse {
+        entries,
+        total,
+        columns: None,
+        rows: None,
+    }))
 }
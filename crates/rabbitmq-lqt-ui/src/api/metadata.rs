@@ -18,7 +18,8 @@ use axum::Json;
 use axum::extract::State;
 use rabbitmq_lqt_lib::Severity;
 use rabbitmq_lqt_lib::entry_metadata::labels::LABEL_NAMES;
-use rabbitmq_lqt_lib::rel_db::{self, FileMetadata, NodeLogEntry};
+use rabbitmq_lqt_lib::entry_metadata::rules::CUSTOM_LABEL_PREFIX;
+use rabbitmq_lqt_lib::rel_db::{self, FileMetadata, NodeLogEntry, NodeLogEntryCustomAnnotations};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::io::Error as IoError;
@@ -35,6 +36,8 @@ pub struct MetadataResponse {
 pub struct StatsResponse {
     total_entries: u64,
     nodes: Vec<NodeStats>,
+    /// Whether new log entries keep arriving, see `web serve --watch-log-dir-path`
+    live: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -58,9 +61,13 @@ pub async fn get_metadata(
         .collect();
 
     let db = state.db.clone();
-    let file_metadata_list = tokio::task::spawn_blocking(move || FileMetadata::find_all(&db))
-        .await
-        .map_err(|e| ServerError::Io(IoError::other(format!("Task join error: {}", e))))??;
+    let (file_metadata_list, custom_labels) = tokio::task::spawn_blocking(move || {
+        let file_metadata_list = FileMetadata::find_all(&db)?;
+        let custom_labels = NodeLogEntryCustomAnnotations::label_names(&db)?;
+        Ok::<_, duckdb::Error>((file_metadata_list, custom_labels))
+    })
+    .await
+    .map_err(|e| ServerError::Io(IoError::other(format!("Task join error: {}", e))))??;
 
     let mut nodes_set = HashSet::new();
     let mut subsystems_set = HashSet::new();
@@ -74,6 +81,11 @@ pub async fn get_metadata(
     let subsystems = hashset_to_sorted_vec(subsystems_set);
     let mut labels: Vec<String> = LABEL_NAMES.iter().map(|s| s.to_string()).collect();
     labels.sort_unstable();
+    labels.extend(
+        custom_labels
+            .iter()
+            .map(|label| format!("{}{}", CUSTOM_LABEL_PREFIX, label)),
+    );
 
     Ok(Json(MetadataResponse {
         severities,
@@ -104,6 +116,7 @@ pub async fn get_stats(State(state): State<AppState>) -> Result<Json<StatsRespon
     Ok(Json(StatsResponse {
         total_entries: total,
         nodes,
+        live: state.live,
     }))
 }
 
@@ -14,3 +14,4 @@
 
 pub mod logs;
 pub mod metadata;
+pub mod timeline;
@@ -0,0 +1,82 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::api::logs::{parse_datetime_relative_to, time_reference};
+use crate::errors::ServerError;
+use crate::server::AppState;
+use axum::Json;
+use axum::extract::{Query, State};
+use chrono::TimeDelta;
+use rabbitmq_lqt_lib::{QueryContext, Timeline, TimelineOptions};
+use serde::Deserialize;
+use std::io::Error as IoError;
+
+#[derive(Debug, Deserialize)]
+pub struct TimelineQueryParams {
+    since_time: Option<String>,
+    to_time: Option<String>,
+    node: Option<String>,
+    limit: Option<u64>,
+    burst_window_secs: Option<u64>,
+    transitions_only: Option<bool>,
+    time_anchor: Option<String>,
+}
+
+pub async fn get_timeline(
+    State(state): State<AppState>,
+    Query(params): Query<TimelineQueryParams>,
+) -> Result<Json<Timeline>, ServerError> {
+    let reference = time_reference(&state, params.time_anchor.as_deref()).await?;
+    let mut ctx = QueryContext::default();
+
+    if let Some(since) = params
+        .since_time
+        .as_ref()
+        .map(|s| parse_datetime_relative_to(s, &reference))
+        .transpose()?
+    {
+        ctx = ctx.since(since);
+    }
+
+    if let Some(to) = params
+        .to_time
+        .as_ref()
+        .map(|s| parse_datetime_relative_to(s, &reference))
+        .transpose()?
+    {
+        ctx = ctx.to(to);
+    }
+
+    if let Some(n) = params.node.as_ref() {
+        ctx = ctx.node(n);
+    }
+
+    if let Some(l) = params.limit
+        && l > 0
+    {
+        ctx = ctx.limit(l);
+    }
+
+    let mut options =
+        TimelineOptions::default().transitions_only(params.transitions_only.unwrap_or(false));
+    if let Some(secs) = params.burst_window_secs {
+        options = options.burst_window(TimeDelta::seconds(secs as i64));
+    }
+
+    let db = state.db.clone();
+    let timeline = tokio::task::spawn_blocking(move || Timeline::query(&db, &ctx, &options))
+        .await
+        .map_err(|e| ServerError::Io(IoError::other(format!("Task join error: {}", e))))??;
+
+    Ok(Json(timeline))
+}
@@ -39,6 +39,20 @@ pub fn clap_parser() -> Command {
                 .default_value("15692")
                 .value_name("PORT")
                 .help("Port to listen on"),
+        )
+        .arg(
+            Arg::new("time_anchor")
+                .long("time-anchor")
+                .value_name("ANCHOR")
+                .value_parser(["log-end", "now"])
+                .default_value("log-end")
+                .help("What relative times such as '2 hours ago' or '@1h' are measured from"),
+        )
+        .arg(
+            Arg::new("reference_time")
+                .long("reference-time")
+                .value_name("DATETIME")
+                .help("Measure relative times from this point in time. Overrides --time-anchor"),
         );
 
     let web_group = Command::new("web")
@@ -36,4 +36,7 @@ pub enum ServerError {
 
     #[error("Invalid query: {0}")]
     InvalidQuery(String),
+
+    #[error("No log entry with ID {0}")]
+    EntryNotFound(i64),
 }
@@ -18,4 +18,4 @@ pub mod errors;
 pub mod server;
 
 pub use errors::ServerError;
-pub use server::run_server;
+pub use server::{run_server, serve_database};
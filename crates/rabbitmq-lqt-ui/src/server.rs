@@ -12,13 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::api::{logs, metadata};
+use crate::api::{logs, metadata, timeline};
 use crate::errors::ServerError;
 use axum::Router;
 use axum::routing::get;
 use clap::ArgMatches;
 use rabbitmq_lqt_lib::rel_db::FileMetadata;
-use rabbitmq_lqt_lib::{DatabaseConnection, NodeLogEntry, open_database};
+use rabbitmq_lqt_lib::{DatabaseConnection, NodeLogEntry, TimeAnchor, open_database};
 use rust_embed::Embed;
 use std::io::{Error as IoError, ErrorKind};
 use std::path::{Path, PathBuf};
@@ -33,6 +33,11 @@ struct Assets;
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<DatabaseConnection>,
+    /// The default anchor for relative times such as "2 hours ago" or `@1h`.
+    pub time_anchor: TimeAnchor,
+    /// Whether log entries are still being ingested into the database,
+    /// so that the frontend keeps refreshing what it shows
+    pub live: bool,
 }
 
 pub async fn handle_serve_command(args: &ArgMatches) -> Result<(), ServerError> {
@@ -51,10 +56,29 @@ pub async fn handle_serve_command(args: &ArgMatches) -> Result<(), ServerError>
         .parse()
         .expect("port must be a valid number");
 
-    run_server(&db_path, host, port).await
+    let time_anchor = time_anchor_from_args(args)?;
+
+    run_server(&db_path, host, port, time_anchor).await
+}
+
+/// Returns the anchor selected with `--reference-time` or `--time-anchor`,
+/// defaulting to the end of the log set.
+pub fn time_anchor_from_args(args: &ArgMatches) -> Result<TimeAnchor, ServerError> {
+    let value = args
+        .get_one::<String>("reference_time")
+        .or_else(|| args.get_one::<String>("time_anchor"));
+    match value {
+        Some(s) => s.parse().map_err(ServerError::DateTimeParse),
+        None => Ok(TimeAnchor::LogEnd),
+    }
 }
 
-pub async fn run_server(db_path: &Path, host: &str, port: u16) -> Result<(), ServerError> {
+pub async fn run_server(
+    db_path: &Path,
+    host: &str,
+    port: u16,
+    time_anchor: TimeAnchor,
+) -> Result<(), ServerError> {
     if !db_path.exists() {
         return Err(ServerError::Io(IoError::new(
             ErrorKind::NotFound,
@@ -70,7 +94,20 @@ pub async fn run_server(db_path: &Path, host: &str, port: u16) -> Result<(), Ser
     }
 
     let db = open_database(db_path)?;
+    serve_database(db, db_path, host, port, time_anchor, false).await
+}
 
+/// Serves an already open database. With `live`, another task of this process
+/// keeps ingesting log entries into it: DuckDB allows only one process to write
+/// to a database file, so a separate ingestion process could not run alongside.
+pub async fn serve_database(
+    db: DatabaseConnection,
+    db_path: &Path,
+    host: &str,
+    port: u16,
+    time_anchor: TimeAnchor,
+    live: bool,
+) -> Result<(), ServerError> {
     log::info!("rabbitmq-lqt v{}", env!("CARGO_PKG_VERSION"));
 
     let entry_count = NodeLogEntry::count_all(&db).unwrap_or(0);
@@ -99,7 +136,13 @@ pub async fn run_server(db_path: &Path, host: &str, port: u16) -> Result<(), Ser
         }
     }
 
-    let state = AppState { db: Arc::new(db) };
+    log::info!("Relative times are measured from: {}", time_anchor);
+
+    let state = AppState {
+        db: Arc::new(db),
+        time_anchor,
+        live,
+    };
     let app = create_router(state);
 
     let addr = format!("{}:{}", host, port);
@@ -123,9 +166,11 @@ fn api_routes(state: AppState) -> Router {
         .route("/logs", get(logs::query_logs))
         .route("/logs/ql", get(logs::query_logs_by_ql))
         .route("/logs/preset/{preset}", get(logs::query_logs_by_preset))
+        .route("/logs/{id}/context", get(logs::query_log_context))
         .route("/metadata", get(metadata::get_metadata))
         .route("/stats", get(metadata::get_stats))
         .route("/file-metadata", get(metadata::get_file_metadata))
+        .route("/timeline", get(timeline::get_timeline))
         .with_state(state)
 }
 
@@ -139,6 +184,28 @@ fn create_router(state: AppState) -> Router {
 
 #[allow(dead_code)]
 pub fn create_router_for_testing(db: Arc<DatabaseConnection>) -> Router {
-    let state = AppState { db };
+    create_router_for_testing_with_time_anchor(db, TimeAnchor::LogEnd)
+}
+
+#[allow(dead_code)]
+pub fn create_router_for_testing_with_time_anchor(
+    db: Arc<DatabaseConnection>,
+    time_anchor: TimeAnchor,
+) -> Router {
+    let state = AppState {
+        db,
+        time_anchor,
+        live: false,
+    };
+    Router::new().nest("/api", api_routes(state))
+}
+
+#[allow(dead_code)]
+pub fn create_live_router_for_testing(db: Arc<DatabaseConnection>) -> Router {
+    let state = AppState {
+        db,
+        time_anchor: TimeAnchor::LogEnd,
+        live: true,
+    };
     Router::new().nest("/api", api_routes(state))
 }
@@ -0,0 +1,274 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unit tests for the log query API endpoint.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::Utc;
+use rabbitmq_lqt_lib::MessageSearchIndex;
+use rabbitmq_lqt_lib::Severity;
+use rabbitmq_lqt_lib::create_database;
+use rabbitmq_lqt_lib::entry_metadata::labels::LogEntryLabels;
+use rabbitmq_lqt_lib::parser::{ParsedLogEntry, StructuredMetadata};
+use rabbitmq_lqt_lib::rel_db::NodeLogEntry;
+use rabbitmq_lqt_ui::server::create_router_for_testing;
+use serde_json::Value;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+fn setup_test_db() -> (TempDir, rabbitmq_lqt_lib::DatabaseConnection) {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+    (temp_dir, db)
+}
+
+fn entry(id: i64, message: &str, metadata: StructuredMetadata) -> ParsedLogEntry {
+    ParsedLogEntry {
+        sequence_id: id as usize,
+        explicit_id: Some(id),
+        timestamp: Utc::now(),
+        severity: Severity::Info,
+        process_id: format!("<0.{}.0>", id),
+        message: message.to_string(),
+        message_lowercased: message.to_lowercase(),
+        subsystem_id: None,
+        labels: LogEntryLabels::empty(),
+        resolution_or_discussion_url_id: None,
+        doc_url_id: None,
+        metadata,
+        custom_annotations: Default::default(),
+    }
+}
+
+fn insert_test_entries(db: &rabbitmq_lqt_lib::DatabaseConnection) {
+    let entries = [
+        entry(
+            1,
+            "user 'user2' authenticated and granted access to vhost 'vhost1'",
+            StructuredMetadata {
+                mfa: Some("rabbit_reader:handle_method0/3".to_string()),
+                connection: Some("10.0.0.2:57586 -> 10.0.0.1:5672".to_string()),
+                vhost: Some("vhost1".to_string()),
+                user: Some("user2".to_string()),
+                ..StructuredMetadata::default()
+            },
+        ),
+        entry(
+            2,
+            "user 'user3' authenticated and granted access to vhost 'vhost7'",
+            StructuredMetadata {
+                vhost: Some("vhost7".to_string()),
+                user: Some("user3".to_string()),
+                ..StructuredMetadata::default()
+            },
+        ),
+        entry(3, "Server startup complete", StructuredMetadata::default()),
+    ];
+    NodeLogEntry::insert_parsed_entries(db, &entries, "rabbit@node1").unwrap();
+}
+
+async fn get_json(db: rabbitmq_lqt_lib::DatabaseConnection, uri: &str) -> Value {
+    let app = create_router_for_testing(Arc::new(db));
+    let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_logs_query_vhost_filter() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+
+    let json = get_json(db, "/api/logs?vhost=vhost1").await;
+
+    assert_eq!(json["total"], 1);
+    let entry = &json["entries"][0];
+    assert_eq!(entry["vhost"], "vhost1");
+    assert_eq!(entry["user"], "user2");
+    assert_eq!(entry["mfa"], "rabbit_reader:handle_method0/3");
+    assert_eq!(entry["connection"], "10.0.0.2:57586 -> 10.0.0.1:5672");
+    assert!(entry["domain"].is_null());
+}
+
+#[tokio::test]
+async fn test_logs_query_user_and_mfa_filters() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+
+    let json = get_json(db, "/api/logs?user=user3").await;
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["entries"][0]["vhost"], "vhost7");
+
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+
+    let json = get_json(
+        db,
+        "/api/logs?mfa=rabbit_reader%3Ahandle_method0%2F3&user=user3",
+    )
+    .await;
+    assert_eq!(json["total"], 0);
+}
+
+#[tokio::test]
+async fn test_logs_query_search() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+    MessageSearchIndex::build(&db).unwrap();
+
+    let json = get_json(
+        db.clone(),
+        "/api/logs?search=%22granted%20access%22%20vhost7",
+    )
+    .await;
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["entries"][0]["id"], 2);
+
+    let json = get_json(db.clone(), "/api/logs?search=startup%20comp*").await;
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["entries"][0]["id"], 3);
+
+    // A search box with nothing to look for does not filter
+    let json = get_json(db, "/api/logs?search=%20--%20").await;
+    assert_eq!(json["total"], 3);
+}
+
+#[tokio::test]
+async fn test_logs_query_sort() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+
+    let json = get_json(db.clone(), "/api/logs?sort=id%3Adesc").await;
+    let ids: Vec<&Value> = json["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| &e["id"])
+        .collect();
+    assert_eq!(ids, [3, 2, 1]);
+
+    let json = get_json(db.clone(), "/api/logs?sort=user%3Adesc&limit=1").await;
+    assert_eq!(json["entries"][0]["user"], "user3");
+
+    let app = create_router_for_testing(Arc::new(db));
+    let request = Request::builder()
+        .uri("/api/logs?sort=labels")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_logs_query_min_severity() {
+    let (_temp_dir, db) = setup_test_db();
+    let entries: Vec<ParsedLogEntry> = [Severity::Debug, Severity::Warning, Severity::Critical]
+        .into_iter()
+        .enumerate()
+        .map(|(i, severity)| {
+            let mut entry = entry(
+                i as i64 + 1,
+                "Free disk space is low",
+                StructuredMetadata::default(),
+            );
+            entry.severity = severity;
+            entry
+        })
+        .collect();
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "rabbit@node1").unwrap();
+
+    let json = get_json(db.clone(), "/api/logs?min_severity=warning&sort=id").await;
+    let severities: Vec<&Value> = json["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| &e["severity"])
+        .collect();
+    assert_eq!(severities, ["warning", "critical"]);
+
+    let json = get_json(db.clone(), "/api/logs/ql?query=severity%20%3E%3D%20error").await;
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["entries"][0]["id"], 3);
+
+    let app = create_router_for_testing(Arc::new(db));
+    let request = Request::builder()
+        .uri("/api/logs?min_severity=fatal")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_logs_context() {
+    let (_temp_dir, db) = setup_test_db();
+    let entries: Vec<ParsedLogEntry> = (1..=5)
+        .map(|id| entry(id, "Closing connection", StructuredMetadata::default()))
+        .collect();
+    NodeLogEntry::insert_parsed_entries(&db, &entries, "rabbit@node1").unwrap();
+
+    let ids = |json: &Value| -> Vec<i64> {
+        json["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["id"].as_i64().unwrap())
+            .collect()
+    };
+
+    let json = get_json(db.clone(), "/api/logs/3/context?before=1&after=1").await;
+    assert_eq!(ids(&json), [2, 3, 4]);
+    assert_eq!(json["total"], 3);
+
+    let json = get_json(db.clone(), "/api/logs/3/context").await;
+    assert_eq!(ids(&json), [1, 2, 3, 4, 5]);
+
+    // Every entry was logged by a different process
+    let json = get_json(db.clone(), "/api/logs/3/context?same_pid=true").await;
+    assert_eq!(ids(&json), [3]);
+
+    let app = create_router_for_testing(Arc::new(db));
+    let request = Request::builder()
+        .uri("/api/logs/3/context?before=1001")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = Request::builder()
+        .uri("/api/logs/42/context")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_ql_query_vhost_selector() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+
+    let json = get_json(db, "/api/logs/ql?query=%7Bvhost%3D%22vhost7%22%7D").await;
+
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["entries"][0]["user"], "user3");
+}
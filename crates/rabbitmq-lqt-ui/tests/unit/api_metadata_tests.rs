@@ -19,11 +19,11 @@ use rabbitmq_lqt_lib::DatabaseConnection;
 use rabbitmq_lqt_lib::Severity;
 use rabbitmq_lqt_lib::create_database;
 use rabbitmq_lqt_lib::entry_metadata::labels::LogEntryLabels;
-use rabbitmq_lqt_lib::parser::ParsedLogEntry;
+use rabbitmq_lqt_lib::parser::{ParsedLogEntry, StructuredMetadata};
 use rabbitmq_lqt_lib::rel_db::FileMetadata;
 use rabbitmq_lqt_lib::rel_db::NodeLogEntry;
 use rabbitmq_lqt_lib::rel_db::file_metadata;
-use rabbitmq_lqt_ui::server::create_router_for_testing;
+use rabbitmq_lqt_ui::server::{create_live_router_for_testing, create_router_for_testing};
 use serde_json::Value;
 use std::sync::Arc;
 use tempfile::TempDir;
@@ -429,6 +429,8 @@ async fn test_get_stats_returns_total_and_per_node_counts() {
         labels: LogEntryLabels::empty(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let entry2 = ParsedLogEntry {
@@ -443,6 +445,8 @@ async fn test_get_stats_returns_total_and_per_node_counts() {
         labels: LogEntryLabels::empty(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let entry3 = ParsedLogEntry {
@@ -457,6 +461,8 @@ async fn test_get_stats_returns_total_and_per_node_counts() {
         labels: LogEntryLabels::empty(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     NodeLogEntry::insert_parsed_entries(&db, &[entry1, entry2], "rabbit@node1").unwrap();
@@ -512,6 +518,30 @@ async fn test_get_stats_empty_database() {
     assert_eq!(nodes.len(), 0);
 }
 
+#[tokio::test]
+async fn test_get_stats_reports_whether_entries_are_live() {
+    let (_temp_dir, db) = setup_test_db();
+    let db = Arc::new(db);
+
+    for (app, live) in [
+        (create_router_for_testing(db.clone()), false),
+        (create_live_router_for_testing(db.clone()), true),
+    ] {
+        let request = Request::builder()
+            .uri("/api/stats")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(json["live"], live);
+    }
+}
+
 #[tokio::test]
 async fn test_get_metadata_returns_sorted_collections() {
     let (_temp_dir, db) = setup_test_db();
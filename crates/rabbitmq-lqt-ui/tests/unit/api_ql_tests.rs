@@ -16,13 +16,16 @@
 
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use rabbitmq_lqt_lib::Severity;
+use rabbitmq_lqt_lib::TimeAnchor;
 use rabbitmq_lqt_lib::create_database;
 use rabbitmq_lqt_lib::entry_metadata::labels::LogEntryLabels;
-use rabbitmq_lqt_lib::parser::ParsedLogEntry;
+use rabbitmq_lqt_lib::parser::{ParsedLogEntry, StructuredMetadata};
 use rabbitmq_lqt_lib::rel_db::NodeLogEntry;
-use rabbitmq_lqt_ui::server::create_router_for_testing;
+use rabbitmq_lqt_ui::server::{
+    create_router_for_testing, create_router_for_testing_with_time_anchor,
+};
 use serde_json::Value;
 use std::sync::Arc;
 use tempfile::TempDir;
@@ -48,6 +51,8 @@ fn insert_test_entries(db: &rabbitmq_lqt_lib::DatabaseConnection) {
         labels: LogEntryLabels::empty(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let entry2 = ParsedLogEntry {
@@ -62,6 +67,8 @@ fn insert_test_entries(db: &rabbitmq_lqt_lib::DatabaseConnection) {
         labels: LogEntryLabels::empty(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     let entry3 = ParsedLogEntry {
@@ -76,6 +83,8 @@ fn insert_test_entries(db: &rabbitmq_lqt_lib::DatabaseConnection) {
         labels: LogEntryLabels::empty(),
         resolution_or_discussion_url_id: None,
         doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
     };
 
     NodeLogEntry::insert_parsed_entries(db, &[entry1, entry2], "rabbit@node1").unwrap();
@@ -341,3 +350,172 @@ async fn test_ql_query_backslash_escaping() {
 
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn test_ql_query_count_by_returns_columns_and_rows() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+
+    let app = create_router_for_testing(Arc::new(db));
+
+    let request = Request::builder()
+        .uri("/api/logs/ql?query=*%20%7C%20count%20by%20node")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(json["total"], 2);
+    assert_eq!(json["entries"].as_array().unwrap().len(), 0);
+    assert_eq!(json["columns"], serde_json::json!(["node", "count"]));
+    assert_eq!(
+        json["rows"],
+        serde_json::json!([["rabbit@node1", 2], ["rabbit@node2", 1]])
+    );
+}
+
+#[tokio::test]
+async fn test_ql_query_distinct_returns_columns_and_rows() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+
+    let app = create_router_for_testing(Arc::new(db));
+
+    let request = Request::builder()
+        .uri("/api/logs/ql?query=*%20%7C%20distinct%20severity")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(json["total"], 3);
+    assert_eq!(json["columns"], serde_json::json!(["severity"]));
+}
+
+#[tokio::test]
+async fn test_ql_query_without_aggregation_omits_columns() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+
+    let app = create_router_for_testing(Arc::new(db));
+
+    let request = Request::builder()
+        .uri("/api/logs/ql?query=*")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert!(json.get("columns").is_none());
+    assert!(json.get("rows").is_none());
+}
+
+fn insert_old_entries(db: &rabbitmq_lqt_lib::DatabaseConnection) {
+    let log_end = Utc::now() - Duration::days(30);
+    let entries: Vec<ParsedLogEntry> = (0..3)
+        .map(|i| ParsedLogEntry {
+            sequence_id: i,
+            explicit_id: Some(i as i64 + 1),
+            timestamp: log_end - Duration::minutes(i as i64 * 45),
+            severity: Severity::Info,
+            process_id: format!("<0.{}.0>", i),
+            message: format!("Message {}", i),
+            message_lowercased: format!("message {}", i),
+            subsystem_id: None,
+            labels: LogEntryLabels::empty(),
+            resolution_or_discussion_url_id: None,
+            doc_url_id: None,
+            metadata: StructuredMetadata::default(),
+            custom_annotations: Default::default(),
+        })
+        .collect();
+    NodeLogEntry::insert_parsed_entries(db, &entries, "rabbit@node1").unwrap();
+}
+
+#[tokio::test]
+async fn test_ql_time_range_is_anchored_to_log_end_by_default() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_old_entries(&db);
+
+    let app = create_router_for_testing(Arc::new(db));
+
+    let request = Request::builder()
+        .uri("/api/logs/ql?query=%401h")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(json["total"], 2);
+}
+
+#[tokio::test]
+async fn test_ql_time_range_anchored_to_now() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_old_entries(&db);
+
+    let app = create_router_for_testing_with_time_anchor(Arc::new(db), TimeAnchor::Now);
+
+    let request = Request::builder()
+        .uri("/api/logs/ql?query=%401h")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(json["total"], 0);
+}
+
+#[tokio::test]
+async fn test_ql_time_anchor_parameter_overrides_server_default() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_old_entries(&db);
+
+    let app = create_router_for_testing(Arc::new(db));
+
+    let request = Request::builder()
+        .uri("/api/logs/ql?query=%401h&time_anchor=now")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(json["total"], 0);
+}
@@ -0,0 +1,131 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unit tests for the timeline API endpoint.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::{DateTime, Utc};
+use rabbitmq_lqt_lib::Severity;
+use rabbitmq_lqt_lib::create_database;
+use rabbitmq_lqt_lib::entry_metadata::labels::LogEntryLabels;
+use rabbitmq_lqt_lib::parser::{ParsedLogEntry, StructuredMetadata};
+use rabbitmq_lqt_lib::rel_db::NodeLogEntry;
+use rabbitmq_lqt_ui::server::create_router_for_testing;
+use serde_json::Value;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+fn setup_test_db() -> (TempDir, rabbitmq_lqt_lib::DatabaseConnection) {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = create_database(&db_path).unwrap();
+    (temp_dir, db)
+}
+
+fn entry(id: i64, secs: i64, message: &str, labels: LogEntryLabels) -> ParsedLogEntry {
+    ParsedLogEntry {
+        sequence_id: id as usize,
+        explicit_id: Some(id),
+        timestamp: DateTime::<Utc>::from_timestamp(1_760_000_000 + secs, 0).unwrap(),
+        severity: Severity::Info,
+        process_id: format!("<0.{}.0>", id),
+        message: message.to_string(),
+        message_lowercased: message.to_lowercase(),
+        subsystem_id: None,
+        labels,
+        resolution_or_discussion_url_id: None,
+        doc_url_id: None,
+        metadata: StructuredMetadata::default(),
+        custom_annotations: Default::default(),
+    }
+}
+
+fn insert_test_entries(db: &rabbitmq_lqt_lib::DatabaseConnection) {
+    let node1_entries = [
+        entry(
+            1,
+            0,
+            "RabbitMQ metadata store: candidate -> leader in term: 2",
+            LogEntryLabels::RAFT | LogEntryLabels::ELECTIONS,
+        ),
+        entry(2, 10, "closing connection <0.1.0>", LogEntryLabels::empty()),
+        entry(3, 11, "closing connection <0.2.0>", LogEntryLabels::empty()),
+    ];
+    NodeLogEntry::insert_parsed_entries(db, &node1_entries, "rabbit@node1").unwrap();
+
+    let node2_entries = [entry(
+        4,
+        5,
+        "RabbitMQ is asked to stop...",
+        LogEntryLabels::SHUTDOWN,
+    )];
+    NodeLogEntry::insert_parsed_entries(db, &node2_entries, "rabbit@node2").unwrap();
+}
+
+async fn get_json(db: rabbitmq_lqt_lib::DatabaseConnection, uri: &str) -> Value {
+    let app = create_router_for_testing(Arc::new(db));
+    let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_timeline_merges_nodes_and_collapses_bursts() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+
+    let json = get_json(db, "/api/timeline").await;
+
+    assert_eq!(
+        json["nodes"],
+        serde_json::json!(["rabbit@node1", "rabbit@node2"])
+    );
+    let events = json["events"].as_array().unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0]["transition"], "leader_change");
+    assert_eq!(events[1]["node"], "rabbit@node2");
+    assert_eq!(events[1]["transition"], "node_shutdown");
+    assert_eq!(events[2]["count"], 2);
+    assert!(events[2]["transition"].is_null());
+}
+
+#[tokio::test]
+async fn test_timeline_transitions_only() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+
+    let json = get_json(db, "/api/timeline?transitions_only=true").await;
+
+    let events = json["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|e| !e["transition"].is_null()));
+}
+
+#[tokio::test]
+async fn test_timeline_without_burst_collapsing() {
+    let (_temp_dir, db) = setup_test_db();
+    insert_test_entries(&db);
+
+    let json = get_json(db, "/api/timeline?burst_window_secs=0&node=rabbit@node1").await;
+
+    assert_eq!(json["nodes"], serde_json::json!(["rabbit@node1"]));
+    assert_eq!(json["events"].as_array().unwrap().len(), 3);
+}
@@ -1,4 +1,6 @@
+use rabbitmq_lqt_lib::TimeAnchor;
 use rabbitmq_lqt_ui::cli::clap_parser;
+use rabbitmq_lqt_ui::server::time_anchor_from_args;
 
 #[test]
 fn test_web_serve_requires_database_path() {
@@ -82,3 +84,59 @@ fn test_web_serve_custom_host_and_port() {
     assert_eq!(serve_matches.get_one::<String>("host").unwrap(), "0.0.0.0");
     assert_eq!(serve_matches.get_one::<String>("port").unwrap(), "8080");
 }
+
+#[test]
+fn test_web_serve_default_time_anchor() {
+    let matches = clap_parser()
+        .try_get_matches_from(vec![
+            "rabbitmq-lqt-ui",
+            "web",
+            "serve",
+            "--input-db-file-path",
+            "/path/to/db.sqlite",
+        ])
+        .unwrap();
+    let web_matches = matches.subcommand_matches("web").unwrap();
+    let serve_matches = web_matches.subcommand_matches("serve").unwrap();
+    assert_eq!(
+        time_anchor_from_args(serve_matches).unwrap(),
+        TimeAnchor::LogEnd
+    );
+}
+
+#[test]
+fn test_web_serve_reference_time_overrides_time_anchor() {
+    let matches = clap_parser()
+        .try_get_matches_from(vec![
+            "rabbitmq-lqt-ui",
+            "web",
+            "serve",
+            "--input-db-file-path",
+            "/path/to/db.sqlite",
+            "--time-anchor",
+            "now",
+            "--reference-time",
+            "2025-10-27 18:00:00",
+        ])
+        .unwrap();
+    let web_matches = matches.subcommand_matches("web").unwrap();
+    let serve_matches = web_matches.subcommand_matches("serve").unwrap();
+    assert!(matches!(
+        time_anchor_from_args(serve_matches).unwrap(),
+        TimeAnchor::At(_)
+    ));
+}
+
+#[test]
+fn test_web_serve_rejects_unknown_time_anchor() {
+    let result = clap_parser().try_get_matches_from(vec![
+        "rabbitmq-lqt-ui",
+        "web",
+        "serve",
+        "--input-db-file-path",
+        "/path/to/db.sqlite",
+        "--time-anchor",
+        "tomorrow",
+    ]);
+    assert!(result.is_err());
+}
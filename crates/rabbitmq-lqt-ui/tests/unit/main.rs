@@ -1,4 +1,6 @@
+mod api_logs_tests;
 mod api_metadata_tests;
 mod api_ql_tests;
+mod api_timeline_tests;
 mod cli_tests;
 mod datetime_parsing_tests;